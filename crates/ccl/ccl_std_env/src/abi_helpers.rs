@@ -53,7 +53,7 @@ impl<'a, Host: MeshHostAbi, MemMgr: CclWasmMemoryManager>
 {
     /// Example: CCL function to get the job ID as a CCL-native string type (conceptual).
     pub fn ccl_job_get_id(&mut self) -> Result<String, HostAbiError> {
-        // Estimate initial buffer size, could be a fixed reasonable default
+        // Initial guess; if it is too small the host tells us the exact size to retry with
         const INITIAL_BUF_LEN: u32 = 128;
         let mut buffer_len = INITIAL_BUF_LEN;
         let mut buffer_ptr;
@@ -64,15 +64,13 @@ impl<'a, Host: MeshHostAbi, MemMgr: CclWasmMemoryManager>
 
             let result = self.host_abi.host_job_get_id(buffer_ptr, buffer_len);
 
-            if result == HostAbiError::BufferTooSmall as i32 {
+            if result < 0 { // Buffer too small; the host reports the size it needs
                 self.memory_manager.ccl_free_buffer(buffer_ptr).map_err(|_| HostAbiError::UnknownError)?;
-                buffer_len *= 2; // Grow buffer and retry
-                if buffer_len > 1024 * 1024 { // Safety break for huge IDs
+                let required = result.unsigned_abs();
+                if required <= buffer_len || required > 1024 * 1024 { // No progress, or a huge ID
                     return Err(HostAbiError::ResourceLimitExceeded);
                 }
-            } else if result < 0 { // Some other HostAbiError
-                self.memory_manager.ccl_free_buffer(buffer_ptr).map_err(|_| HostAbiError::UnknownError)?;
-                return Err(unsafe { std::mem::transmute(result) });
+                buffer_len = required; // Retry with exactly the required size
             } else { // Success, result is number of bytes written
                 let num_bytes = result as u32;
                 let id_bytes = unsafe { self.memory_manager.get_wasm_memory_slice(buffer_ptr, num_bytes) };
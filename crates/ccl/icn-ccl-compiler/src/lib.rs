@@ -1,5 +1,9 @@
-use anyhow::{anyhow, Result};
-use std::path::Path;
+use anyhow::{anyhow, Context, Result};
+use cid::Cid;
+use icn_types::CompilationManifest;
+use multihash::{Code, MultihashDigest};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
 use tempfile::TempDir;
 use thiserror::Error;
 use serde_json; // For compile_to_dsl_string
@@ -7,8 +11,22 @@ use icn_ccl_dsl; // For lower_ccl_to_dsl_ast and compile_to_wasm
 use icn_ccl_wasm_codegen; // For compile_to_wasm
 use log::error; // Only error was not flagged as unused
 
+pub mod lint;
 pub mod lower;
 
+pub use lint::{LintWarning, Severity};
+
+/// Version recorded in the [`CompilationManifest`] of everything this build compiles
+pub const COMPILER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Multicodec for raw bytes, used for both CCL source and WASM CIDs
+const RAW_CODEC: u64 = 0x55;
+
+/// CIDv1 (raw codec, SHA2-256) of `bytes`, as stored for CCL sources and WASM modules
+pub fn content_cid(bytes: &[u8]) -> String {
+    Cid::new_v1(RAW_CODEC, Code::Sha2_256.digest(bytes)).to_string()
+}
+
 /// Error types specific to the CCL compiler
 #[derive(Error, Debug)]
 pub enum CompilerError {
@@ -38,6 +56,8 @@ pub enum CompilerError {
 pub struct CclCompiler {
     /// Storage for temporary files
     _temp_dir: TempDir, // Renamed to indicate it might become unused by CclCompiler itself
+    /// Whether to run the code generator's optimization passes
+    optimize: bool,
 }
 
 impl CclCompiler {
@@ -46,47 +66,109 @@ impl CclCompiler {
         let temp_dir = TempDir::new()?;
         Ok(Self {
             _temp_dir: temp_dir,
+            optimize: false,
         })
     }
 
+    /// Run the code generator's optimization passes (static condition folding,
+    /// collapsing repeated properties) on everything this compiler emits.
+    pub fn with_optimization(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
     /// Lowers CCL source to an intermediate DSL AST representation.
+    /// Source without a file has nowhere to resolve imports from, so they are rejected.
     fn lower_ccl_to_dsl_ast(&self, ccl_source: &str) -> Result<Vec<icn_ccl_dsl::DslModule>> {
-        lower::lower_str(ccl_source).map_err(|e| {
-            anyhow!(CompilerError::LoweringError(format!(
-                "Lowering failed: {}",
-                e
-            )))
-        })
+        let (imports, modules) = lower_with_import_paths(ccl_source)?;
+        if let Some(import) = imports.first() {
+            return Err(anyhow!(CompilerError::LoweringError(format!(
+                "Cannot resolve import \"{}\" without a source file; compile the file instead",
+                import
+            ))));
+        }
+        Ok(modules)
+    }
+
+    /// Lowers a CCL file and everything it imports, returning the modules and
+    /// the sources that were read.
+    fn lower_file_to_dsl_ast(
+        &self,
+        ccl_path: &Path,
+    ) -> Result<(Vec<icn_ccl_dsl::DslModule>, ImportResolver)> {
+        let mut resolver = ImportResolver::new(ccl_path)?;
+        let modules = resolver.lower_file(ccl_path)?;
+        Ok((modules, resolver))
     }
 
     /// Compile CCL source to an intermediate DSL representation (JSON string).
     pub fn compile_to_dsl_string(&self, ccl_source: &str) -> Result<String> {
         let dsl_modules = self.lower_ccl_to_dsl_ast(ccl_source)?;
-        serde_json::to_string_pretty(&dsl_modules).map_err(|e| {
-            anyhow!(CompilerError::DslGenerationError(format!(
-                "Failed to serialize DSL modules: {}",
-                e
-            )))
-        })
+        dsl_to_string(&dsl_modules)
+    }
+
+    /// Lower CCL source and report likely mistakes; the source still compiles either way.
+    pub fn lint(&self, ccl_source: &str) -> Result<Vec<LintWarning>> {
+        let dsl_modules = self.lower_ccl_to_dsl_ast(ccl_source)?;
+        Ok(lint::lint(&dsl_modules))
+    }
+
+    /// Like [`lint`](Self::lint), for a file and the files it imports.
+    pub fn lint_file(&self, ccl_path: &Path) -> Result<Vec<LintWarning>> {
+        let (dsl_modules, _) = self.lower_file_to_dsl_ast(ccl_path)?;
+        Ok(lint::lint(&dsl_modules))
     }
 
     /// Compile CCL source to WASM bytecode.
     pub fn compile_to_wasm(&self, ccl_source: &str) -> Result<Vec<u8>> {
         let dsl_modules = self.lower_ccl_to_dsl_ast(ccl_source)?;
         // Use the wasm-codegen crate for DSL AST to WASM compilation
-        Ok(icn_ccl_wasm_codegen::compile_to_wasm(dsl_modules))
+        Ok(icn_ccl_wasm_codegen::compile_to_wasm(
+            dsl_modules,
+            self.optimize,
+        ))
+    }
+
+    /// Compile CCL source to WASM and record what produced it.
+    pub fn compile_to_manifest(&self, ccl_source: &str) -> Result<(Vec<u8>, CompilationManifest)> {
+        let wasm_bytes = self.compile_to_wasm(ccl_source)?;
+        let manifest = CompilationManifest {
+            compiler_version: COMPILER_VERSION.to_string(),
+            ccl_cid: content_cid(ccl_source.as_bytes()),
+            wasm_cid: content_cid(&wasm_bytes),
+        };
+        Ok((wasm_bytes, manifest))
+    }
+
+    /// Compile a CCL file to WASM and record what produced it. The manifest's
+    /// `ccl_cid` covers every file the compilation read, see [`sources_cid`].
+    pub fn compile_file_to_manifest(
+        &self,
+        ccl_path: &Path,
+    ) -> Result<(Vec<u8>, CompilationManifest)> {
+        let (dsl_modules, resolver) = self.lower_file_to_dsl_ast(ccl_path)?;
+        let wasm_bytes = icn_ccl_wasm_codegen::compile_to_wasm(dsl_modules, self.optimize);
+        let manifest = CompilationManifest {
+            compiler_version: COMPILER_VERSION.to_string(),
+            ccl_cid: sources_cid(&resolver.sources),
+            wasm_cid: content_cid(&wasm_bytes),
+        };
+        Ok((wasm_bytes, manifest))
     }
 
     /// Compile CCL directly from a file to WASM bytecode.
     pub fn compile_file(&self, ccl_path: &Path) -> Result<Vec<u8>> {
-        let ccl_source = std::fs::read_to_string(ccl_path)?;
-        self.compile_to_wasm(&ccl_source)
+        let (dsl_modules, _) = self.lower_file_to_dsl_ast(ccl_path)?;
+        Ok(icn_ccl_wasm_codegen::compile_to_wasm(
+            dsl_modules,
+            self.optimize,
+        ))
     }
 
     /// Generate DSL (JSON string) for a file and save it.
     pub fn compile_file_to_dsl_string(&self, ccl_path: &Path, dsl_path: &Path) -> Result<()> {
-        let ccl_source = std::fs::read_to_string(ccl_path)?;
-        let dsl_string = self.compile_to_dsl_string(&ccl_source)?;
+        let (dsl_modules, _) = self.lower_file_to_dsl_ast(ccl_path)?;
+        let dsl_string = dsl_to_string(&dsl_modules)?;
         std::fs::write(dsl_path, dsl_string)?;
         Ok(())
     }
@@ -99,10 +181,139 @@ impl CclCompiler {
     }
 }
 
+/// CID of the CCL sources a compilation read, as `(path, source)` pairs in the
+/// order they were lowered.
+///
+/// A single file hashes to [`content_cid`] of its source, so manifests of
+/// files without imports are unchanged. Otherwise the CID covers the JSON
+/// encoding of the pairs, binding both the contents and where each file sits
+/// in the project.
+pub fn sources_cid(sources: &[(String, String)]) -> String {
+    match sources {
+        [(_, source)] => content_cid(source.as_bytes()),
+        _ => content_cid(&serde_json::to_vec(sources).expect("string pairs always serialize")),
+    }
+}
+
+fn lower_with_import_paths(ccl_source: &str) -> Result<(Vec<String>, Vec<icn_ccl_dsl::DslModule>)> {
+    lower::lower_str_with_imports(ccl_source).map_err(|e| {
+        anyhow!(CompilerError::LoweringError(format!(
+            "Lowering failed: {}",
+            e
+        )))
+    })
+}
+
+/// Import resolution for one compilation.
+///
+/// Imports are resolved relative to the importing file and must stay inside
+/// the project root, the directory of the file being compiled: absolute paths
+/// and `..` components are rejected, as is anything that resolves outside the
+/// root through a symlink. A file imported along several paths is lowered once.
+struct ImportResolver {
+    /// Canonical project root
+    root: PathBuf,
+    /// Files whose imports are still being resolved; meeting one again is a cycle
+    importing: HashSet<PathBuf>,
+    /// Files already lowered
+    lowered: HashSet<PathBuf>,
+    /// Every file read, relative to `root`, in the order lowered
+    sources: Vec<(String, String)>,
+}
+
+impl ImportResolver {
+    fn new(ccl_path: &Path) -> Result<Self> {
+        let canonical = canonicalize(ccl_path)?;
+        let root = canonical
+            .parent()
+            .unwrap_or_else(|| Path::new("/"))
+            .to_path_buf();
+        Ok(Self {
+            root,
+            importing: HashSet::new(),
+            lowered: HashSet::new(),
+            sources: Vec::new(),
+        })
+    }
+
+    /// Lower `ccl_path` with the modules of each file it imports prepended, in
+    /// import order. A file that was already lowered contributes nothing.
+    fn lower_file(&mut self, ccl_path: &Path) -> Result<Vec<icn_ccl_dsl::DslModule>> {
+        let canonical = canonicalize(ccl_path)?;
+        let relative = canonical
+            .strip_prefix(&self.root)
+            .map_err(|_| {
+                anyhow!(CompilerError::LoweringError(format!(
+                    "Import {} resolves outside the project root {}",
+                    ccl_path.display(),
+                    self.root.display()
+                )))
+            })?
+            .to_string_lossy()
+            .into_owned();
+        if self.lowered.contains(&canonical) {
+            return Ok(Vec::new());
+        }
+        if !self.importing.insert(canonical.clone()) {
+            return Err(anyhow!(CompilerError::LoweringError(format!(
+                "Import cycle: {} imports itself",
+                canonical.display()
+            ))));
+        }
+
+        let ccl_source = std::fs::read_to_string(&canonical)?;
+        let (imports, modules) = lower_with_import_paths(&ccl_source)?;
+        let base_dir = canonical.parent().unwrap_or(&self.root).to_path_buf();
+        let mut all_modules = Vec::new();
+        for import in imports {
+            let import_path = confined_import_path(&import)?;
+            all_modules.extend(self.lower_file(&base_dir.join(import_path))?);
+        }
+        all_modules.extend(modules);
+
+        self.importing.remove(&canonical);
+        self.lowered.insert(canonical);
+        self.sources.push((relative, ccl_source));
+        Ok(all_modules)
+    }
+}
+
+fn canonicalize(ccl_path: &Path) -> Result<PathBuf> {
+    ccl_path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve CCL file {}", ccl_path.display()))
+}
+
+/// `import` as a relative path that cannot step above the importing file's directory
+fn confined_import_path(import: &str) -> Result<&Path> {
+    let path = Path::new(import);
+    if path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(path)
+    } else {
+        Err(anyhow!(CompilerError::LoweringError(format!(
+            "Import \"{}\" must be a relative path without `..`",
+            import
+        ))))
+    }
+}
+
+fn dsl_to_string(dsl_modules: &[icn_ccl_dsl::DslModule]) -> Result<String> {
+    serde_json::to_string_pretty(dsl_modules).map_err(|e| {
+        anyhow!(CompilerError::DslGenerationError(format!(
+            "Failed to serialize DSL modules: {}",
+            e
+        )))
+    })
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::lower::lower_str;
+    use crate::lower::{lower_reader, lower_str, LowerError};
+    use icn_ccl_parser::CclError;
     use insta::assert_json_snapshot;
 
     const ELECTION_CCL_STR: &str = include_str!("../../icn-ccl-parser/templates/election.ccl");
@@ -190,4 +401,55 @@ bylaws_def "Cooperative Bylaws from CONST" version "1.0.0-const" {
         let dsl_modules = lower_str(BYLAWS_CCL_STR).unwrap();
         assert_json_snapshot!(dsl_modules);
     }
+
+    #[test]
+    fn quorum_threshold_fraction_lowers() {
+        let dsl_modules = lower_str("proposal \"quorum\" {\n  quorum_threshold 0.60;\n}\n").unwrap();
+        assert_json_snapshot!(dsl_modules);
+    }
+
+    #[test]
+    fn quorum_threshold_percentage_lowers() {
+        let dsl_modules = lower_str("proposal \"quorum\" {\n  quorum_threshold 60%;\n}\n").unwrap();
+        assert_json_snapshot!(dsl_modules);
+    }
+
+    #[test]
+    fn quorum_threshold_outside_unit_range_is_rejected() {
+        for src in [
+            "proposal \"quorum\" {\n  quorum_threshold 1.5;\n}\n",
+            "proposal \"quorum\" {\n  quorum_threshold 150%;\n}\n",
+            "proposal \"quorum\" {\n  quorum_threshold -0.1;\n}\n",
+        ] {
+            match lower_str(src) {
+                Err(LowerError::Stream(CclError::ValidationError(_))) => {}
+                other => panic!("expected ValidationError for {:?}, got {:?}", src, other),
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_lowering_matches_whole_file() {
+        let combined = format!("{}\n{}", ELECTION_CCL_STR, BUDGET_CCL_STR);
+        let whole = lower_str(&combined).unwrap();
+        let streamed = lower_reader(std::io::Cursor::new(combined))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&streamed).unwrap(),
+            serde_json::to_value(&whole).unwrap()
+        );
+    }
+
+    #[test]
+    fn streaming_lowering_reports_offending_statement() {
+        let src = "proposal \"ok\" {\n  title \"fine\";\n}\n\nproposal \"bad\" {\n  = \n}\n";
+        let mut stream = lower_reader(std::io::Cursor::new(src));
+        assert!(stream.next().unwrap().is_ok());
+        match stream.next().unwrap() {
+            Err(LowerError::AtLine { line, .. }) => assert_eq!(line, 5),
+            other => panic!("expected AtLine error, got {:?}", other),
+        }
+        assert!(stream.next().is_none());
+    }
 }
@@ -0,0 +1,127 @@
+//! Checks over lowered CCL that flag likely mistakes without blocking compilation.
+
+use icn_ccl_dsl::{ActionStep, DslModule, IfExpr, MeteredAction, Rule, RuleValue, Span};
+use icn_ccl_wasm_codegen::{fold_condition, is_function_call};
+use std::collections::HashSet;
+use std::fmt;
+
+/// How much attention a [`LintWarning`] deserves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Harmless, but probably not what the author meant.
+    Info,
+    /// Part of the contract is silently overridden or can never run.
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found by [`lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintWarning {
+    pub severity: Severity,
+    pub message: String,
+    /// Source position of the offending rule or action, when known.
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(
+                f,
+                "{}: line {}:{}: {}",
+                self.severity, span.line, span.column, self.message
+            ),
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
+
+/// Flag duplicate keys within a section, `else` branches that can never run,
+/// and metered actions with an amount of 0.
+pub fn lint(modules: &[DslModule]) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    for module in modules {
+        match module {
+            DslModule::Proposal(p) => lint_rules(&p.rules, &mut warnings),
+            DslModule::Section(s) => lint_rules(&s.rules, &mut warnings),
+            DslModule::Role(r) => lint_rules(&r.attributes, &mut warnings),
+            DslModule::MeteredAction(m) => lint_metered_action(m, &mut warnings),
+            DslModule::ActionHandler(h) => {
+                for step in &h.steps {
+                    if let ActionStep::Metered(m) = step {
+                        lint_metered_action(m, &mut warnings);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
+/// Lint one block of rules; nested blocks are their own scope for duplicates.
+fn lint_rules(rules: &[Rule], warnings: &mut Vec<LintWarning>) {
+    let mut seen = HashSet::new();
+    for rule in rules {
+        // Repeated calls are deliberate, so calls don't count as duplicate keys
+        let is_call = matches!(&rule.value, RuleValue::Map(kv) if is_function_call(kv));
+        if !is_call && !seen.insert(rule.key.as_str()) {
+            warnings.push(LintWarning {
+                severity: Severity::Warning,
+                message: format!(
+                    "duplicate key \"{}\"; only the last value takes effect",
+                    rule.key
+                ),
+                span: rule.span,
+            });
+        }
+
+        match &rule.value {
+            RuleValue::If(expr) => lint_if_expr(expr, rule.span, warnings),
+            RuleValue::ForEach { body, .. } => lint_rules(body, warnings),
+            RuleValue::Range(range) => lint_rules(&range.rules, warnings),
+            RuleValue::Map(kv) if !is_call => lint_rules(kv, warnings),
+            _ => {}
+        }
+    }
+}
+
+fn lint_if_expr(expr: &IfExpr, span: Option<Span>, warnings: &mut Vec<LintWarning>) {
+    if let (Some(true), Some(_)) = (fold_condition(&expr.condition_raw), &expr.else_rules) {
+        warnings.push(LintWarning {
+            severity: Severity::Warning,
+            message: format!(
+                "else branch is unreachable: `{}` is always true",
+                expr.condition_raw.trim()
+            ),
+            span,
+        });
+    }
+
+    lint_rules(&expr.then_rules, warnings);
+    if let Some(else_rules) = &expr.else_rules {
+        lint_rules(else_rules, warnings);
+    }
+}
+
+fn lint_metered_action(action: &MeteredAction, warnings: &mut Vec<LintWarning>) {
+    if action.amount == 0 {
+        warnings.push(LintWarning {
+            severity: Severity::Info,
+            message: format!(
+                "metered action for \"{}\" has amount 0 and does nothing",
+                action.resource_type
+            ),
+            span: action.span,
+        });
+    }
+}
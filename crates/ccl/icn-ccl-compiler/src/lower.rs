@@ -1,13 +1,15 @@
 use icn_ccl_dsl::{
     ActionHandler, ActionStep, Anchor, DslModule, GenericSection, IfExpr, MeteredAction, Proposal,
-    RangeRule, ResourceType, Role as DslAstRole, Rule as DslRule, RuleValue as DslValue,
+    RangeRule, ResourceType, Role as DslAstRole, Rule as DslRule, RuleValue as DslValue, Span,
 };
-use icn_ccl_parser::{CclParser, Rule};
+use icn_ccl_parser::{stream_statements, CclError, CclParser, Rule, StatementStream};
 use pest::iterators::{Pair, Pairs};
 use pest::Parser;
 use thiserror::Error;
 use uuid::Uuid;
 use serde_json;
+use std::collections::VecDeque;
+use std::io::BufRead;
 
 // Constant UUID for deterministic test snapshots
 #[cfg(test)]
@@ -37,10 +39,44 @@ pub enum LowerError {
     Parse(#[from] Box<pest::error::Error<Rule>>),
     #[error("unhandled rule: {0}")]
     Unhandled(UnhandledRuleInfo),
+    #[error("stream error: {0}")]
+    Stream(#[from] icn_ccl_parser::CclError),
+    #[error("in statement starting at line {line}: {source}")]
+    AtLine {
+        line: usize,
+        #[source]
+        source: Box<LowerError>,
+    },
 }
 
 /// Primary entry‐point used by CLI & tests.
 pub fn lower_str(src: &str) -> Result<Vec<DslModule>, LowerError> {
+    lower_source(src, 0)
+}
+
+/// Like [`lower_str`], but also returns the paths named by top-level `import`
+/// statements, in source order. Resolving them is up to the caller, which knows
+/// where `src` came from; [`lower_str`] and [`lower_reader`] ignore imports.
+pub fn lower_str_with_imports(src: &str) -> Result<(Vec<String>, Vec<DslModule>), LowerError> {
+    let pairs = parse_root(src)?.into_inner();
+    let imports = pairs
+        .clone()
+        .filter(|pair| pair.as_rule() == Rule::statement)
+        .filter_map(|pair| pair.into_inner().next())
+        .filter(|inner| inner.as_rule() == Rule::import_statement)
+        .filter_map(|import| import.into_inner().next())
+        .map(|path| path.as_str().trim_matches('"').to_owned())
+        .collect();
+    let modules = Lowerer { line_offset: 0 }.lower(pairs)?;
+    Ok((imports, modules))
+}
+
+/// Lower `src`, recording source lines as if it started `line_offset` lines into a file.
+fn lower_source(src: &str, line_offset: usize) -> Result<Vec<DslModule>, LowerError> {
+    Lowerer { line_offset }.lower(parse_root(src)?.into_inner())
+}
+
+fn parse_root(src: &str) -> Result<Pair<'_, Rule>, LowerError> {
     let mut pairs = CclParser::parse(Rule::ccl, src).map_err(Box::new)?;
     let ccl_root_pair = pairs.next().ok_or_else(|| {
         // This case should ideally not happen if parsing Rule::ccl was successful
@@ -53,13 +89,78 @@ pub fn lower_str(src: &str) -> Result<Vec<DslModule>, LowerError> {
             pest::Span::new(src, 0, 0).unwrap(), // Dummy span
         ))
     })?;
-    Lowerer.lower(ccl_root_pair.into_inner())
+    Ok(ccl_root_pair)
+}
+
+/// Lower CCL read from `reader` one top-level statement at a time.
+///
+/// Modules are yielded as soon as their statement has been read and lowered, so large
+/// documents can be processed incrementally. Errors are wrapped in [`LowerError::AtLine`]
+/// with the line the offending statement starts on, and end the stream.
+pub fn lower_reader<R: BufRead>(reader: R) -> ModuleStream<R> {
+    ModuleStream {
+        statements: stream_statements(reader),
+        pending: VecDeque::new(),
+        failed: false,
+    }
+}
+
+/// Iterator returned by [`lower_reader`].
+pub struct ModuleStream<R> {
+    statements: StatementStream<R>,
+    pending: VecDeque<DslModule>,
+    failed: bool,
+}
+
+impl<R: BufRead> Iterator for ModuleStream<R> {
+    type Item = Result<DslModule, LowerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(module) = self.pending.pop_front() {
+                return Some(Ok(module));
+            }
+            if self.failed {
+                return None;
+            }
+
+            let statement = match self.statements.next()? {
+                Ok(statement) => statement,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e.into()));
+                }
+            };
+            match lower_source(&statement.source, statement.line.saturating_sub(1)) {
+                Ok(modules) => self.pending.extend(modules),
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(LowerError::AtLine {
+                        line: statement.line,
+                        source: Box::new(e),
+                    }));
+                }
+            }
+        }
+    }
 }
 
 #[derive(Default)]
-struct Lowerer;
+struct Lowerer {
+    /// Added to the line of every recorded [`Span`]
+    line_offset: usize,
+}
 
 impl Lowerer {
+    /// Where `pair` starts in the original source
+    fn span_of(&self, pair: &Pair<'_, Rule>) -> Option<Span> {
+        let (line, column) = pair.as_span().start_pos().line_col();
+        Some(Span {
+            line: line + self.line_offset,
+            column,
+        })
+    }
+
     fn lower(&self, pairs: Pairs<'_, Rule>) -> Result<Vec<DslModule>, LowerError> {
         let mut modules = Vec::new();
         for pair in pairs {
@@ -142,6 +243,9 @@ impl Lowerer {
             Rule::actions_def => {
                 modules.extend(self.lower_actions(pair)?);
             }
+            Rule::import_statement => {
+                // Resolved by the caller of `lower_str_with_imports`
+            }
             Rule::organization_def
             | Rule::governance_def
             | Rule::membership_def
@@ -196,6 +300,7 @@ impl Lowerer {
     ) -> Result<DslAstRole, LowerError> {
         // role_def = { "role" ~ string_literal ~ block }
         let pair_span = role_def_pair.as_span(); // Span of the whole role_def for error reporting
+        let span = self.span_of(&role_def_pair);
         let mut inner_role_pairs = role_def_pair.into_inner();
 
         // First inner is string_literal (role name)
@@ -248,6 +353,7 @@ impl Lowerer {
                 Some(description)
             },
             attributes,
+            span,
         })
     }
 
@@ -261,6 +367,7 @@ impl Lowerer {
             for statement_pair in block_pair.into_inner() {
                 if statement_pair.as_rule() == Rule::statement {
                     if let Some(inner_def_pair) = statement_pair.into_inner().next() {
+                        let span = self.span_of(&inner_def_pair);
                         match inner_def_pair.as_rule() {
                             Rule::any_statement => {
                                 let mut field_parts = inner_def_pair.clone().into_inner(); // Clone for logging if needed
@@ -289,6 +396,7 @@ impl Lowerer {
                                                         dsl_rules.push(DslRule {
                                                             key: key_str.to_string(),
                                                             value: dsl_val,
+                                                            span,
                                                         });
                                                     }
                                                 }
@@ -300,6 +408,7 @@ impl Lowerer {
                                                 dsl_rules.push(DslRule {
                                                     key: key_str.to_string(),
                                                     value: DslValue::Map(nested_rules),
+                                                    span,
                                                 });
                                             }
                                             Rule::general_identifier => {
@@ -308,6 +417,7 @@ impl Lowerer {
                                                     value: DslValue::String(
                                                         value_outer_pair.as_str().to_string(),
                                                     ),
+                                                    span,
                                                 });
                                             }
                                             _ => {
@@ -323,10 +433,20 @@ impl Lowerer {
                                         dsl_rules.push(DslRule {
                                             key: key_str.to_string(),
                                             value: DslValue::Boolean(true), // Placeholder for valueless keys
+                                            span,
                                         });
                                     }
                                 }
                             }
+                            Rule::quorum_threshold => {
+                                dsl_rules.push(DslRule {
+                                    key: "quorum_threshold".to_string(),
+                                    value: DslValue::Number(
+                                        self.lower_quorum_threshold(inner_def_pair)?,
+                                    ),
+                                    span,
+                                });
+                            }
                             Rule::range_statement => {
                                 let range_rule_data = self.lower_range_statement(inner_def_pair)?;
                                 let key = format!(
@@ -336,6 +456,7 @@ impl Lowerer {
                                 dsl_rules.push(DslRule {
                                     key,
                                     value: DslValue::Range(Box::new(range_rule_data)),
+                                    span,
                                 });
                             }
                             Rule::if_statement => {
@@ -346,6 +467,16 @@ impl Lowerer {
                                 dsl_rules.push(DslRule {
                                     key,
                                     value: DslValue::If(Box::new(if_expr_data)),
+                                    span,
+                                });
+                            }
+                            Rule::for_each_statement => {
+                                let for_each = self.lower_for_each_statement(inner_def_pair)?;
+                                let key = format!("for_each_{}", dsl_rules.len());
+                                dsl_rules.push(DslRule {
+                                    key,
+                                    value: for_each,
+                                    span,
                                 });
                             }
                             Rule::function_call_statement => {
@@ -367,6 +498,7 @@ impl Lowerer {
                                         dsl_rules.push(DslRule {
                                             key: fn_name,
                                             value: dsl_val,
+                                            span,
                                         });
                                     }
                                     // Else: malformed function_call_statement, inner was not function_call
@@ -383,6 +515,43 @@ impl Lowerer {
         Ok((description_body, dsl_rules))
     }
 
+    /// Normalize `quorum_threshold 0.60;` and `quorum_threshold 60%;` to the
+    /// same fraction, rejecting anything outside `[0, 1]`.
+    fn lower_quorum_threshold(&self, pair: Pair<'_, Rule>) -> Result<f64, LowerError> {
+        let span = pair.as_span();
+        let value_pair = pair.into_inner().next().ok_or_else(|| {
+            LowerError::Parse(Box::new(pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::CustomError {
+                    message: "quorum_threshold missing value".to_string(),
+                },
+                span,
+            )))
+        })?;
+
+        let raw = value_pair.as_str();
+        let (digits, scale) = match value_pair.as_rule() {
+            Rule::percentage => (raw.trim_end_matches('%'), 100.0),
+            _ => (raw, 1.0),
+        };
+        let threshold = digits.parse::<f64>().map_err(|e| {
+            LowerError::Parse(Box::new(pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::CustomError {
+                    message: format!("Invalid number: {}", e),
+                },
+                value_pair.as_span(),
+            )))
+        })? / scale;
+
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(CclError::ValidationError(format!(
+                "quorum_threshold {} is outside the range 0..=1 (0%..=100%)",
+                raw
+            ))
+            .into());
+        }
+        Ok(threshold)
+    }
+
     fn lower_value_rule(&self, value_pair: Pair<'_, Rule>) -> Result<DslValue, LowerError> {
         // value_pair is the actual primitive rule like string_literal, number, boolean, etc.
         // not Rule::value itself.
@@ -459,10 +628,12 @@ impl Lowerer {
                             // k_pair is string_literal or identifier
                             // v_rule_container_pair is Rule::value, its inner is the actual value type
                             let key_str = k_pair.as_str().trim_matches('"').to_string();
+                            let span = self.span_of(&k_pair);
                             if let Some(actual_v_pair) = v_rule_container_pair.into_inner().next() {
                                 dsl_rules.push(DslRule {
                                     key: key_str,
                                     value: self.lower_value_rule(actual_v_pair)?,
+                                    span,
                                 });
                             }
                         }
@@ -474,6 +645,7 @@ impl Lowerer {
                 // function_call = { identifier ~ "(" ~ function_call_args ~ ")" }
                 // function_call_args = { (object_pair ~ ("," ~ object_pair)*)? }
                 let original_fn_call_span = value_pair.as_span();
+                let call_span = self.span_of(&value_pair);
                 let mut inner_fc_pairs = value_pair.into_inner();
                 let fn_name_pair = inner_fc_pairs.next();
                 let fn_args_container_pair = inner_fc_pairs.next(); // This is Rule::function_call_args
@@ -496,6 +668,7 @@ impl Lowerer {
                                     (arg_key_pair, arg_value_wrapper_pair)
                                 {
                                     let arg_key_str = k_pair.as_str().trim_matches('"').to_string();
+                                    let arg_span = self.span_of(&k_pair);
                                     // v_wrapper_pair is Rule::value, its inner is the actual value type
                                     if let Some(actual_arg_val_pair) =
                                         v_wrapper_pair.into_inner().next()
@@ -505,6 +678,7 @@ impl Lowerer {
                                         named_args_rules.push(DslRule {
                                             key: arg_key_str,
                                             value: dsl_arg_val,
+                                            span: arg_span,
                                         });
                                     }
                                     // Else: Rule::value was empty, or malformed object_pair, ignore for now or error
@@ -518,10 +692,12 @@ impl Lowerer {
                         DslRule {
                             key: "function_name".to_string(),
                             value: DslValue::String(fn_name),
+                            span: call_span,
                         },
                         DslRule {
                             key: "args".to_string(),
                             value: DslValue::Map(named_args_rules),
+                            span: call_span,
                         },
                     ];
                     Ok(DslValue::Map(fn_call_map_rules))
@@ -711,8 +887,42 @@ impl Lowerer {
         })
     }
 
+    fn lower_for_each_statement(&self, pair: Pair<'_, Rule>) -> Result<DslValue, LowerError> {
+        // pair is Rule::for_each_statement = { "for_each" ~ identifier ~ "in" ~ general_identifier ~ block }
+        let original_span = pair.as_span();
+        let mut inner_pairs = pair.into_inner();
+        let missing = |what: &str| {
+            LowerError::Parse(Box::new(pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::CustomError {
+                    message: format!("for_each statement missing {}", what),
+                },
+                original_span,
+            )))
+        };
+
+        let var = inner_pairs
+            .next()
+            .ok_or_else(|| missing("loop variable"))?
+            .as_str()
+            .to_string();
+        let collection_raw = inner_pairs
+            .next()
+            .ok_or_else(|| missing("collection"))?
+            .as_str()
+            .to_string();
+        let body_pair = inner_pairs.next().ok_or_else(|| missing("body"))?;
+        let (_body_desc, body) = self.lower_block_common_fields(body_pair)?;
+
+        Ok(DslValue::ForEach {
+            var,
+            collection_raw,
+            body,
+        })
+    }
+
     fn lower_proposal(&self, pair: Pair<'_, Rule>) -> Result<Proposal, LowerError> {
         let pair_span = pair.as_span();
+        let span = self.span_of(&pair);
         let mut proposal_specific_pairs = pair.into_inner();
 
         let title = proposal_specific_pairs
@@ -743,11 +953,12 @@ impl Lowerer {
 
         let (description_body, dsl_rules) = self.lower_block_common_fields(block_pair)?;
 
-        Ok(self.build_stub_proposal(title, version, description_body, dsl_rules))
+        Ok(self.build_stub_proposal(title, version, description_body, dsl_rules, span))
     }
 
     fn lower_election(&self, pair: Pair<'_, Rule>) -> Result<Proposal, LowerError> {
         let pair_span = pair.as_span();
+        let span = self.span_of(&pair);
         let mut election_specific_pairs = pair.into_inner(); // These are specific to election_def
 
         let title = election_specific_pairs
@@ -780,6 +991,7 @@ impl Lowerer {
             "0.0.0-unknown".to_string(),
             description_body,
             dsl_rules,
+            span,
         ))
     }
 
@@ -789,6 +1001,7 @@ impl Lowerer {
         version: String,
         body: String,
         rules: Vec<DslRule>,
+        span: Option<Span>,
     ) -> Proposal {
         let id = {
             #[cfg(test)]
@@ -809,12 +1022,14 @@ impl Lowerer {
             author: "unknown".into(),
             created_at: 0,
             rules, // Use passed in rules
+            span,
         }
     }
 
     fn lower_bylaws_def(&self, pair: Pair<'_, Rule>) -> Result<Proposal, LowerError> {
         // bylaws_def = { "bylaws_def" ~ string_literal ~ "version" ~ string_literal ~ block }
         let pair_span = pair.as_span();
+        let span = self.span_of(&pair);
         let mut bylaws_specific_pairs = pair.into_inner();
 
         let title = bylaws_specific_pairs
@@ -856,7 +1071,7 @@ impl Lowerer {
 
         let (description_body, dsl_rules) = self.lower_block_common_fields(block_pair)?;
 
-        Ok(self.build_stub_proposal(title, version, description_body, dsl_rules))
+        Ok(self.build_stub_proposal(title, version, description_body, dsl_rules, span))
     }
 
     fn lower_actions(&self, pair: Pair<'_, Rule>) -> Result<Vec<DslModule>, LowerError> {
@@ -895,6 +1110,7 @@ impl Lowerer {
                     let _on_pair_span_for_log = on_pair.as_span();
                     if on_pair_rule == Rule::action_def {
                         let on_action_def_span = on_pair.as_span();
+                        let span = self.span_of(&on_pair);
                         let mut inner_action_def_pairs = on_pair.into_inner();
                         let event_name_pair = inner_action_def_pairs.next().ok_or_else(|| {
                             LowerError::Parse(Box::new(pest::error::Error::new_from_span(
@@ -965,7 +1181,11 @@ impl Lowerer {
                                 }
                             }
                         }
-                        handlers.push(DslModule::ActionHandler(ActionHandler { event, steps }));
+                        handlers.push(DslModule::ActionHandler(ActionHandler {
+                            event,
+                            steps,
+                            span,
+                        }));
                     }
                 }
             }
@@ -976,6 +1196,7 @@ impl Lowerer {
     fn lower_mint_token(&self, pair: Pair<'_, Rule>) -> Result<MeteredAction, LowerError> {
         // pair is Rule::mint_token = { "mint_token" ~ block }
         let original_pair_span = pair.as_span(); // Get span before moving pair
+        let span = self.span_of(&pair);
         let block_pair = pair.into_inner().next().ok_or_else(|| {
             LowerError::Parse(Box::new(pest::error::Error::new_from_span(
                 pest::error::ErrorVariant::CustomError {
@@ -1090,12 +1311,14 @@ impl Lowerer {
             amount,
             recipient,
             data,
+            span,
         })
     }
 
     fn lower_anchor_data(&self, pair: Pair<'_, Rule>) -> Result<Anchor, LowerError> {
         // pair is Rule::anchor_data = { "anchor_data" ~ block }
         let original_pair_span = pair.as_span(); // Get span before moving pair
+        let span = self.span_of(&pair);
         let block_pair = pair.into_inner().next().ok_or_else(|| {
             LowerError::Parse(Box::new(pest::error::Error::new_from_span(
                 pest::error::ErrorVariant::CustomError {
@@ -1191,6 +1414,7 @@ impl Lowerer {
         Ok(Anchor {
             data_reference,
             path,
+            span,
         })
     }
 
@@ -1368,6 +1592,7 @@ impl Lowerer {
         // We want "organization" or "process", etc.
         let kind = kind_str_debug.to_lowercase().replace("_def", "");
         let original_pair_span = pair.as_span();
+        let span = self.span_of(&pair);
 
         let mut title: Option<String> = None;
         let mut block_pair_option: Option<Pair<'_, Rule>> = None;
@@ -1401,7 +1626,12 @@ impl Lowerer {
 
         if let Some(block_pair) = block_pair_option {
             let (_description, rules) = self.lower_block_common_fields(block_pair)?;
-            Ok(GenericSection { kind, title, rules })
+            Ok(GenericSection {
+                kind,
+                title,
+                rules,
+                span,
+            })
         } else {
             Err(LowerError::Parse(Box::new(
                 pest::error::Error::new_from_span(
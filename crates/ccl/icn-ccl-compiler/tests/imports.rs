@@ -0,0 +1,214 @@
+use icn_ccl_compiler::{content_cid, CclCompiler, CompilerError};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn proposal(name: &str) -> String {
+    format!("proposal \"{}\" {{\n  quorum 0.5;\n}}\n", name)
+}
+
+fn write(dir: &Path, file: &str, source: &str) {
+    let path = dir.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, source).unwrap();
+}
+
+fn lowering_error(err: anyhow::Error) -> String {
+    match err.downcast::<CompilerError>() {
+        Ok(CompilerError::LoweringError(msg)) => msg,
+        other => panic!("expected LoweringError, got {:?}", other),
+    }
+}
+
+#[test]
+fn imported_modules_are_prepended() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join("shared")).unwrap();
+    std::fs::write(
+        dir.path().join("shared/quorum.ccl"),
+        "proposal \"shared_quorum\" {\n  quorum 0.5;\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("bylaws.ccl"),
+        "import \"shared/quorum.ccl\";\n\nproposal \"bylaws\" {\n  threshold 2;\n}\n",
+    )
+    .unwrap();
+
+    let dsl_path = dir.path().join("bylaws.dsl.json");
+    CclCompiler::new()
+        .unwrap()
+        .compile_file_to_dsl_string(&dir.path().join("bylaws.ccl"), &dsl_path)
+        .unwrap();
+    let dsl = std::fs::read_to_string(dsl_path).unwrap();
+
+    let imported = dsl.find("\"shared_quorum\"").expect("imported proposal");
+    let own = dsl.find("\"bylaws\"").expect("importing proposal");
+    assert!(imported < own);
+}
+
+#[test]
+fn import_cycle_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("a.ccl"),
+        "import \"b.ccl\";\nproposal \"a\" {\n  quorum 0.5;\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("b.ccl"),
+        "import \"a.ccl\";\nproposal \"b\" {\n  quorum 0.5;\n}\n",
+    )
+    .unwrap();
+
+    let err = CclCompiler::new()
+        .unwrap()
+        .compile_file(&dir.path().join("a.ccl"))
+        .unwrap_err();
+    match err.downcast_ref::<CompilerError>() {
+        Some(CompilerError::LoweringError(msg)) => assert!(msg.contains("cycle")),
+        other => panic!("expected LoweringError, got {:?}", other),
+    }
+}
+
+#[test]
+fn imports_resolve_relative_to_the_importing_file() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "policies/common/quorum.ccl",
+        &proposal("quorum"),
+    );
+    write(
+        dir.path(),
+        "policies/bylaws.ccl",
+        &format!("import \"common/quorum.ccl\";\n{}", proposal("bylaws")),
+    );
+    write(
+        dir.path(),
+        "main.ccl",
+        &format!("import \"policies/bylaws.ccl\";\n{}", proposal("main")),
+    );
+
+    let dsl_path = dir.path().join("main.dsl.json");
+    CclCompiler::new()
+        .unwrap()
+        .compile_file_to_dsl_string(&dir.path().join("main.ccl"), &dsl_path)
+        .unwrap();
+    let dsl = std::fs::read_to_string(dsl_path).unwrap();
+    assert!(dsl.contains("\"quorum\""));
+    assert!(dsl.contains("\"bylaws\""));
+}
+
+#[test]
+fn diamond_imports_are_lowered_once() {
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "shared.ccl", &proposal("shared"));
+    write(
+        dir.path(),
+        "left.ccl",
+        &format!("import \"shared.ccl\";\n{}", proposal("left")),
+    );
+    write(
+        dir.path(),
+        "right.ccl",
+        &format!("import \"shared.ccl\";\n{}", proposal("right")),
+    );
+    write(
+        dir.path(),
+        "top.ccl",
+        &format!(
+            "import \"left.ccl\";\nimport \"right.ccl\";\n{}",
+            proposal("top")
+        ),
+    );
+
+    let dsl_path = dir.path().join("top.dsl.json");
+    CclCompiler::new()
+        .unwrap()
+        .compile_file_to_dsl_string(&dir.path().join("top.ccl"), &dsl_path)
+        .unwrap();
+    let dsl = std::fs::read_to_string(dsl_path).unwrap();
+    assert_eq!(dsl.matches("\"shared\"").count(), 1);
+}
+
+#[test]
+fn imports_outside_the_project_root_are_rejected() {
+    let outer = TempDir::new().unwrap();
+    write(outer.path(), "secret.ccl", &proposal("secret"));
+    write(outer.path(), "project/sub/inner.ccl", &proposal("inner"));
+    let absolute = outer.path().join("secret.ccl");
+    let compiler = CclCompiler::new().unwrap();
+
+    for import in [
+        "../secret.ccl",
+        "sub/../../secret.ccl",
+        absolute.to_str().unwrap(),
+    ] {
+        write(
+            outer.path(),
+            "project/main.ccl",
+            &format!("import \"{}\";\n{}", import, proposal("main")),
+        );
+        let err = compiler
+            .compile_file(&outer.path().join("project/main.ccl"))
+            .unwrap_err();
+        assert!(
+            lowering_error(err).contains(import),
+            "{} was accepted",
+            import
+        );
+    }
+
+    // A nested file may not climb back out either
+    write(
+        outer.path(),
+        "project/sub/inner.ccl",
+        &format!("import \"../../secret.ccl\";\n{}", proposal("inner")),
+    );
+    write(
+        outer.path(),
+        "project/main.ccl",
+        &format!("import \"sub/inner.ccl\";\n{}", proposal("main")),
+    );
+    assert!(compiler
+        .compile_file(&outer.path().join("project/main.ccl"))
+        .is_err());
+}
+
+#[test]
+fn manifest_covers_every_imported_source() {
+    let dir = TempDir::new().unwrap();
+    let compiler = CclCompiler::new().unwrap();
+    let main = dir.path().join("main.ccl");
+
+    // Without imports the CID is that of the file itself
+    write(dir.path(), "main.ccl", &proposal("main"));
+    let (_, manifest) = compiler.compile_file_to_manifest(&main).unwrap();
+    assert_eq!(manifest.ccl_cid, content_cid(proposal("main").as_bytes()));
+
+    write(dir.path(), "shared.ccl", &proposal("shared"));
+    write(
+        dir.path(),
+        "main.ccl",
+        &format!("import \"shared.ccl\";\n{}", proposal("main")),
+    );
+    let (_, before) = compiler.compile_file_to_manifest(&main).unwrap();
+
+    // Editing only the imported file changes the recorded CID
+    write(
+        dir.path(),
+        "shared.ccl",
+        "proposal \"shared\" {\n  quorum 0.75;\n}\n",
+    );
+    let (_, after) = compiler.compile_file_to_manifest(&main).unwrap();
+    assert_ne!(before.ccl_cid, after.ccl_cid);
+}
+
+#[test]
+fn source_without_a_file_cannot_import() {
+    let err = CclCompiler::new()
+        .unwrap()
+        .compile_to_wasm(&format!("import \"shared.ccl\";\n{}", proposal("main")))
+        .unwrap_err();
+    assert!(lowering_error(err).contains("shared.ccl"));
+}
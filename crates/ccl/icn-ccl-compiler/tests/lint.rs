@@ -0,0 +1,77 @@
+use icn_ccl_compiler::lint::lint;
+use icn_ccl_compiler::lower::lower_str;
+use icn_ccl_compiler::{LintWarning, Severity};
+
+fn lint_ccl(src: &str) -> Vec<LintWarning> {
+    lint(&lower_str(src).expect("lower to DSL"))
+}
+
+#[test]
+fn duplicate_key_in_section_is_flagged() {
+    let warnings =
+        lint_ccl("proposal \"dup\" {\n  quorum 0.5;\n  threshold 2;\n  quorum 0.6;\n}\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, Severity::Warning);
+    assert!(warnings[0].message.contains("\"quorum\""));
+    assert_eq!(warnings[0].span.map(|s| s.line), Some(4));
+}
+
+#[test]
+fn same_key_in_separate_blocks_is_not_a_duplicate() {
+    let src = r#"
+proposal "scoped" {
+  quorum 0.5;
+  voting {
+    quorum 0.6;
+  };
+  log_event(name: "a");
+  log_event(name: "b");
+}
+"#;
+    assert!(lint_ccl(src).is_empty());
+}
+
+#[test]
+fn else_after_always_true_condition_is_flagged() {
+    let src = r#"
+proposal "static" {
+  if 1 == 1 {
+    quorum 0.5;
+  } else {
+    quorum 0.9;
+  }
+  if proposal.type == "bylaw_change" {
+    quorum 0.6;
+  } else {
+    quorum 0.7;
+  }
+}
+"#;
+    let warnings = lint_ccl(src);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, Severity::Warning);
+    assert!(warnings[0].message.contains("unreachable"));
+    assert_eq!(warnings[0].span.map(|s| s.line), Some(3));
+}
+
+#[test]
+fn zero_amount_metered_action_is_flagged() {
+    let src = r#"
+actions {
+  on "proposal.accepted" {
+    mint_token {
+      type "receipt";
+      amount 0;
+    }
+    mint_token {
+      type "reward";
+      amount 5;
+    }
+  }
+}
+"#;
+    let warnings = lint_ccl(src);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, Severity::Info);
+    assert!(warnings[0].message.contains("\"receipt\""));
+}
@@ -0,0 +1,29 @@
+use icn_ccl_compiler::lower::lower_str;
+use icn_ccl_compiler::CclCompiler;
+
+const FOLDABLE_CCL: &str = r#"
+proposal "folding" {
+  if 1 == 1 {
+    quorum 0.5;
+  } else {
+    quorum 0.9;
+  }
+}
+"#;
+
+#[test]
+fn optimization_is_opt_in() -> anyhow::Result<()> {
+    let plain = CclCompiler::new()?.compile_to_wasm(FOLDABLE_CCL)?;
+    let optimized = CclCompiler::new()?
+        .with_optimization(true)
+        .compile_to_wasm(FOLDABLE_CCL)?;
+
+    let modules = lower_str(FOLDABLE_CCL).expect("lower to DSL");
+    assert_eq!(
+        optimized,
+        icn_ccl_wasm_codegen::compile_to_wasm(modules.clone(), true)
+    );
+    assert_eq!(plain, icn_ccl_wasm_codegen::compile_to_wasm(modules, false));
+    assert_ne!(plain, optimized);
+    Ok(())
+}
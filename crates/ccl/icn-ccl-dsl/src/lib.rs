@@ -9,6 +9,15 @@ use uuid::Uuid;
 // Re-export ResourceType so other crates can use it via icn_ccl_dsl::ResourceType
 pub use icn_economics::ResourceType;
 
+/// Position in the CCL source a DSL element was lowered from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number.
+    pub column: usize,
+}
+
 /// Represents a generic section of the CCL that hasn't been fully modeled yet.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenericSection {
@@ -18,6 +27,9 @@ pub struct GenericSection {
     pub title: Option<String>,
     /// Everything inside the section's block, parsed as DSL rules.
     pub rules: Vec<Rule>,
+    /// Where this was lowered from; not serialized.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
 /// Every top-level cooperative artefact the DSL can emit.
@@ -56,6 +68,9 @@ pub struct Proposal {
     pub created_at: i64,
     /// Associated rules for the proposal.
     pub rules: Vec<Rule>,
+    /// Where this was lowered from; not serialized.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
 /// Simple vote artefact.
@@ -94,6 +109,9 @@ pub struct Anchor {
     pub data_reference: String,
     /// Optional path for where the data is anchored, e.g., a namespace or directory.
     pub path: Option<String>,
+    /// Where this was lowered from; not serialized.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
 /// Execution metering (resource consumption).
@@ -107,6 +125,9 @@ pub struct MeteredAction {
     pub recipient: Option<String>,
     /// Optional structured data associated with the action.
     pub data: Option<Vec<Rule>>,
+    /// Where this was lowered from; not serialized.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
 /// Represents a role definition.
@@ -118,6 +139,9 @@ pub struct Role {
     pub description: Option<String>,
     /// Attributes associated with the role (e.g., term_length, seats).
     pub attributes: Vec<Rule>,
+    /// Where this was lowered from; not serialized.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
 /// Generic on-chain rule block.
@@ -127,6 +151,9 @@ pub struct Rule {
     pub key: String,
     /// Value of the rule.
     pub value: RuleValue,
+    /// Where this was lowered from; not serialized.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
 /// Represents the value of a rule, which can be of various types.
@@ -147,6 +174,15 @@ pub enum RuleValue {
     Range(Box<RangeRule>),
     /// An if-expression, for conditional rules.
     If(Box<IfExpr>),
+    /// Rules applied once per item of a collection.
+    ForEach {
+        /// Name bound to the current item (e.g., "member").
+        var: String,
+        /// The raw collection expression (e.g., "members").
+        collection_raw: String,
+        /// Rules applied for each item.
+        body: Vec<Rule>,
+    },
 }
 
 /// Represents a rule defining a numeric range and associated sub-rules.
@@ -178,6 +214,9 @@ pub struct ActionHandler {
     pub event: String,
     /// A list of steps to execute when the event occurs.
     pub steps: Vec<ActionStep>,
+    /// Where this was lowered from; not serialized.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
 /// Represents a single step within an ActionHandler.
@@ -227,6 +266,7 @@ mod tests {
             author: "did:key:z6M...".into(),
             created_at: 0,
             rules: vec![],
+            span: None,
         };
         let json = serde_json::to_string_pretty(&p).unwrap();
         let back: Proposal = serde_json::from_str(&json).unwrap();
@@ -0,0 +1,393 @@
+//! Populating a [`CclDocument`] from the pest parse tree.
+//!
+//! The first named top-level definition (`organization`, `proposal`, `election`,
+//! `budget` or `bylaws_def`) is the document header: its name is the title and its
+//! block supplies the description, author, creation date, version and generic rules.
+//! The optional sections come from dedicated top-level definitions:
+//!
+//! - budget: a `budget "name" { ... }` definition, with categories from `allocations`
+//! - execution: the `mint_token`, `anchor_data` and `perform_metered_action`
+//!   statements found under `actions`
+//! - accountability: a `reporting { ... }` definition
+
+use crate::{
+    CclAccountability, CclAction, CclAuthorization, CclBudget, CclDisbursement, CclDocument,
+    CclExecution, CclParser, CclReports, CclTransparency, ParserError, Rule,
+};
+use icn_ccl_dsl::{Rule as DslRule, RuleValue};
+use pest::iterators::Pair;
+use pest::Parser;
+use std::collections::HashMap;
+
+/// Header keys that populate document fields rather than `rules`
+const HEADER_KEYS: &[&str] = &["description", "author", "created", "version"];
+
+pub(crate) fn parse_document(input: &str) -> Result<CclDocument, ParserError> {
+    let ccl = CclParser::parse(Rule::ccl, input)
+        .map_err(|e| ParserError::ParseError(e.to_string()))?
+        .next()
+        .ok_or_else(|| ParserError::ParseError("empty parse tree".to_string()))?;
+
+    let mut header: Option<Header<'_>> = None;
+    let mut budget = None;
+    let mut allocations = None;
+    let mut actions = Vec::new();
+    let mut reporting = None;
+
+    for statement in ccl.into_inner() {
+        let Some(def) = statement.into_inner().next() else {
+            continue; // EOI
+        };
+        match def.as_rule() {
+            Rule::organization_def
+            | Rule::proposal_def
+            | Rule::election_def
+            | Rule::budget_def
+            | Rule::bylaws_def => {
+                let is_budget = def.as_rule() == Rule::budget_def;
+                let parsed = Header::from_def(def)?;
+                if is_budget && budget.is_none() {
+                    budget = Some(block_rules(parsed.block.clone()));
+                }
+                header.get_or_insert(parsed);
+            }
+            Rule::allocations_def => allocations = def.into_inner().next(),
+            Rule::actions_def => actions.extend(def.into_inner().next()),
+            Rule::reporting_def => reporting = def.into_inner().next().map(block_rules),
+            _ => {}
+        }
+    }
+
+    let header = header.ok_or_else(|| ParserError::MissingField("title".to_string()))?;
+    let mut rules = block_rules(header.block);
+    let text = |key: &str| string_field(&rules, key).unwrap_or_default();
+    let description = text("description");
+    let author = text("author");
+    let created = text("created");
+    let version = header.version.unwrap_or_else(|| text("version"));
+    rules.retain(|rule| !HEADER_KEYS.contains(&rule.key.as_str()));
+
+    Ok(CclDocument {
+        title: header.title,
+        description,
+        author,
+        created,
+        version,
+        budget: budget.map(|rules| lower_budget(&rules, allocations)),
+        execution: if actions.is_empty() {
+            None
+        } else {
+            Some(CclExecution {
+                actions: actions.into_iter().flat_map(collect_actions).collect(),
+            })
+        },
+        accountability: reporting.map(|rules| lower_accountability(&rules)),
+        rules,
+    })
+}
+
+/// A named top-level definition
+struct Header<'i> {
+    title: String,
+    /// Only `bylaws_def` declares its version outside the block
+    version: Option<String>,
+    block: Pair<'i, Rule>,
+}
+
+impl<'i> Header<'i> {
+    fn from_def(def: Pair<'i, Rule>) -> Result<Self, ParserError> {
+        let mut parts: Vec<_> = def.into_inner().collect();
+        let block = parts
+            .pop()
+            .filter(|p| p.as_rule() == Rule::block)
+            .ok_or_else(|| ParserError::MissingField("block".to_string()))?;
+        let mut strings = parts.into_iter().map(|p| unquote(p.as_str()));
+        let title = strings
+            .next()
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| ParserError::MissingField("title".to_string()))?;
+        Ok(Self {
+            title,
+            version: strings.next(),
+            block,
+        })
+    }
+}
+
+/// The key/value statements of a block, with nested blocks as maps.
+/// Conditionals, ranges and named definitions are skipped.
+fn block_rules(block: Pair<'_, Rule>) -> Vec<DslRule> {
+    let mut rules = Vec::new();
+    for statement in block.into_inner() {
+        let Some(inner) = statement.into_inner().next() else {
+            continue;
+        };
+        if inner.as_rule() != Rule::any_statement {
+            continue;
+        }
+        let mut parts = inner.into_inner();
+        let Some(key) = parts.next() else {
+            continue;
+        };
+        let value = match parts.next() {
+            Some(v) if v.as_rule() == Rule::block => RuleValue::Map(block_rules(v)),
+            Some(v) if v.as_rule() == Rule::value => match v.into_inner().next() {
+                Some(primitive) => lower_value(primitive),
+                None => continue,
+            },
+            Some(v) => RuleValue::String(v.as_str().to_string()),
+            None => RuleValue::Boolean(true),
+        };
+        rules.push(DslRule {
+            key: unquote(key.as_str()),
+            value,
+            span: None,
+        });
+    }
+    rules
+}
+
+fn lower_value(pair: Pair<'_, Rule>) -> RuleValue {
+    match pair.as_rule() {
+        Rule::string_literal => RuleValue::String(unquote(pair.as_str())),
+        Rule::number => pair
+            .as_str()
+            .parse()
+            .map(RuleValue::Number)
+            .unwrap_or_else(|_| RuleValue::String(pair.as_str().to_string())),
+        Rule::boolean => RuleValue::Boolean(pair.as_str() == "true"),
+        Rule::array => RuleValue::List(
+            pair.into_inner()
+                .filter_map(|v| v.into_inner().next())
+                .map(lower_value)
+                .collect(),
+        ),
+        Rule::object => RuleValue::Map(
+            pair.into_inner()
+                .filter_map(|object_pair| {
+                    let mut kv = object_pair.into_inner();
+                    let key = unquote(kv.next()?.as_str());
+                    let value = lower_value(kv.next()?.into_inner().next()?);
+                    Some(DslRule {
+                        key,
+                        value,
+                        span: None,
+                    })
+                })
+                .collect(),
+        ),
+        // durations, identifiers, function calls and range values keep their source text
+        _ => RuleValue::String(pair.as_str().to_string()),
+    }
+}
+
+fn lower_budget(rules: &[DslRule], allocations: Option<Pair<'_, Rule>>) -> CclBudget {
+    let total = whole_field(rules, "total").unwrap_or(0);
+
+    let mut categories: HashMap<String, u64> = map_field(rules, "categories")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|rule| Some((rule.key.clone(), whole(&rule.value)?)))
+        .collect();
+    if let Some(allocations) = allocations {
+        for (name, category) in named_blocks(allocations, "category") {
+            if let Some(share) = number_field(&category, "allocation") {
+                categories.insert(name, allocation_amount(total, share));
+            }
+        }
+    }
+
+    let disbursement = map_field(rules, "disbursement").unwrap_or_default();
+    let authorization = map_field(rules, "authorization").unwrap_or_default();
+
+    CclBudget {
+        total,
+        currency: string_field(rules, "currency").unwrap_or_default(),
+        categories,
+        disbursement: CclDisbursement {
+            schedule: string_field(disbursement, "schedule").unwrap_or_default(),
+            start_date: string_field(disbursement, "start_date").unwrap_or_default(),
+            end_date: string_field(disbursement, "end_date").unwrap_or_default(),
+        },
+        authorization: CclAuthorization {
+            threshold: whole_field(authorization, "threshold").unwrap_or(0),
+            roles: string_list_field(authorization, "roles"),
+            require_review: bool_field(authorization, "require_review").unwrap_or(false),
+        },
+    }
+}
+
+/// Allocations of at most 1 are shares of `total`; larger ones are amounts.
+fn allocation_amount(total: u64, allocation: f64) -> u64 {
+    if allocation <= 1.0 {
+        (total as f64 * allocation.max(0.0)).round() as u64
+    } else {
+        allocation as u64
+    }
+}
+
+fn lower_accountability(rules: &[DslRule]) -> CclAccountability {
+    // Without an explicit metric list, every declared report is a metric
+    let mut metrics = string_list_field(rules, "metrics");
+    if metrics.is_empty() {
+        metrics = map_field(rules, "reports")
+            .unwrap_or_default()
+            .iter()
+            .map(|report| report.key.clone())
+            .collect();
+    }
+    let transparency = map_field(rules, "transparency").unwrap_or_default();
+
+    CclAccountability {
+        reports: CclReports {
+            frequency: string_field(rules, "frequency").unwrap_or_default(),
+            metrics,
+        },
+        transparency: CclTransparency {
+            disclosure_level: string_field(transparency, "disclosure_level").unwrap_or_default(),
+            public_dashboard: bool_field(transparency, "public_dashboard").unwrap_or(false),
+        },
+    }
+}
+
+/// Every action under an `actions` block, in source order
+fn collect_actions(actions: Pair<'_, Rule>) -> Vec<CclAction> {
+    let mut found = Vec::new();
+    for pair in actions.into_inner().flatten() {
+        match pair.as_rule() {
+            Rule::mint_token | Rule::anchor_data => {
+                let kind = if pair.as_rule() == Rule::mint_token {
+                    "mint_token"
+                } else {
+                    "anchor_data"
+                };
+                if let Some(block) = pair.into_inner().next() {
+                    found.extend(action_from_block(kind, &block_rules(block)));
+                }
+            }
+            // perform_metered_action("type", ctx.field, amount);
+            Rule::perform_metered_action => {
+                let parts: Vec<_> = pair.into_inner().collect();
+                if let (Some(action_type), Some(amount)) = (parts.first(), parts.last()) {
+                    found.push(CclAction::PerformAction {
+                        action_type: unquote(action_type.as_str()),
+                        amount: amount
+                            .as_str()
+                            .parse::<f64>()
+                            .map_or(0, |n| n.max(0.0) as u64),
+                    });
+                }
+            }
+            // `mint_token { ... };` and friends parse as generic statements
+            Rule::any_statement => {
+                let mut parts = pair.into_inner();
+                if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+                    if value.as_rule() == Rule::block {
+                        found.extend(action_from_block(key.as_str(), &block_rules(value)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+fn action_from_block(kind: &str, rules: &[DslRule]) -> Option<CclAction> {
+    match kind {
+        "mint_token" => Some(CclAction::MintTokens {
+            token_type: string_field(rules, "type").unwrap_or_default(),
+            amount: whole_field(rules, "amount").unwrap_or(0),
+            recipient: string_field(rules, "recipient").unwrap_or_default(),
+        }),
+        "anchor_data" => Some(CclAction::AnchorData(
+            string_field(rules, "path").unwrap_or_default(),
+        )),
+        "perform_metered_action" => Some(CclAction::PerformAction {
+            action_type: string_field(rules, "action").unwrap_or_default(),
+            amount: whole_field(rules, "amount").unwrap_or(0),
+        }),
+        _ => None,
+    }
+}
+
+/// `kind "name" { ... }` definitions directly inside `block`
+fn named_blocks(block: Pair<'_, Rule>, kind: &str) -> Vec<(String, Vec<DslRule>)> {
+    block
+        .into_inner()
+        .filter_map(|statement| statement.into_inner().next())
+        .filter(|def| def.as_rule() == Rule::named_construct_statement)
+        .filter_map(|def| {
+            let mut parts = def.into_inner();
+            if parts.next()?.as_str() != kind {
+                return None;
+            }
+            let name = unquote(parts.next()?.as_str());
+            Some((name, block_rules(parts.next()?)))
+        })
+        .collect()
+}
+
+fn unquote(raw: &str) -> String {
+    raw.trim_matches('"').to_string()
+}
+
+fn field<'r>(rules: &'r [DslRule], key: &str) -> Option<&'r RuleValue> {
+    rules
+        .iter()
+        .find(|rule| rule.key == key)
+        .map(|rule| &rule.value)
+}
+
+fn string_field(rules: &[DslRule], key: &str) -> Option<String> {
+    match field(rules, key)? {
+        RuleValue::String(s) => Some(s.clone()),
+        RuleValue::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn number_field(rules: &[DslRule], key: &str) -> Option<f64> {
+    match field(rules, key)? {
+        RuleValue::Number(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn whole(value: &RuleValue) -> Option<u64> {
+    match value {
+        RuleValue::Number(n) if n.is_finite() && *n >= 0.0 => Some(*n as u64),
+        _ => None,
+    }
+}
+
+fn whole_field(rules: &[DslRule], key: &str) -> Option<u64> {
+    whole(field(rules, key)?)
+}
+
+fn bool_field(rules: &[DslRule], key: &str) -> Option<bool> {
+    match field(rules, key)? {
+        RuleValue::Boolean(b) => Some(*b),
+        _ => None,
+    }
+}
+
+fn map_field<'r>(rules: &'r [DslRule], key: &str) -> Option<&'r [DslRule]> {
+    match field(rules, key)? {
+        RuleValue::Map(nested) => Some(nested),
+        _ => None,
+    }
+}
+
+fn string_list_field(rules: &[DslRule], key: &str) -> Vec<String> {
+    match field(rules, key) {
+        Some(RuleValue::List(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                RuleValue::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{CclDocument, CclError, CclParserResult};
+use icn_ccl_dsl::{Rule, RuleValue};
+use serde::{Deserialize, Serialize};
+
+const QUORUM_KEYS: &[&str] = &["quorum_percentage"];
+const PASS_THRESHOLD_KEYS: &[&str] = &["pass_threshold", "pass_threshold_percentage"];
+const MIN_DURATION_KEYS: &[&str] = &["min_duration"];
+const MAX_DURATION_KEYS: &[&str] = &["max_duration"];
+
+/// Governance parameters extracted from a CCL document's rules.
+///
+/// Fields are `None` when the document does not declare them, so callers can
+/// fall back to their own defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GovernanceParams {
+    /// Fraction of eligible voters that must participate (0.0..=1.0)
+    pub quorum_percentage: Option<f64>,
+
+    /// Fraction of cast votes required for a proposal to pass (0.0 exclusive, 1.0 inclusive)
+    pub pass_threshold: Option<f64>,
+
+    /// Minimum voting period, in seconds
+    pub min_duration_secs: Option<u64>,
+
+    /// Maximum voting period, in seconds
+    pub max_duration_secs: Option<u64>,
+}
+
+impl CclDocument {
+    /// Extract and type-check the known governance parameters from the document rules.
+    ///
+    /// Parameters are looked up at the top level and inside nested map blocks such as
+    /// `proposal_processing { ... }`. Conditional (`if`) and `range` blocks are not
+    /// considered, since their values only apply in specific circumstances.
+    pub fn governance_params(&self) -> CclParserResult<GovernanceParams> {
+        let mut params = GovernanceParams::default();
+        collect_params(&self.rules, &mut params)?;
+
+        if let (Some(min), Some(max)) = (params.min_duration_secs, params.max_duration_secs) {
+            if min > max {
+                return Err(invalid(
+                    "min_duration",
+                    format!("{}s exceeds max_duration of {}s", min, max),
+                ));
+            }
+        }
+
+        Ok(params)
+    }
+}
+
+fn collect_params(rules: &[Rule], params: &mut GovernanceParams) -> CclParserResult<()> {
+    for rule in rules {
+        let key = rule.key.as_str();
+        if QUORUM_KEYS.contains(&key) {
+            let value = expect_fraction(key, &rule.value)?;
+            set_once(key, &mut params.quorum_percentage, value)?;
+        } else if PASS_THRESHOLD_KEYS.contains(&key) {
+            let value = expect_fraction(key, &rule.value)?;
+            if value == 0.0 {
+                return Err(invalid(key, "must be greater than 0".to_string()));
+            }
+            set_once(key, &mut params.pass_threshold, value)?;
+        } else if MIN_DURATION_KEYS.contains(&key) {
+            let value = expect_duration(key, &rule.value)?;
+            if value == 0 {
+                return Err(invalid(key, "must be greater than 0".to_string()));
+            }
+            set_once(key, &mut params.min_duration_secs, value)?;
+        } else if MAX_DURATION_KEYS.contains(&key) {
+            let value = expect_duration(key, &rule.value)?;
+            set_once(key, &mut params.max_duration_secs, value)?;
+        } else if let RuleValue::Map(nested) = &rule.value {
+            collect_params(nested, params)?;
+        }
+    }
+    Ok(())
+}
+
+fn set_once<T>(name: &str, slot: &mut Option<T>, value: T) -> CclParserResult<()> {
+    if slot.is_some() {
+        return Err(invalid(name, "declared more than once".to_string()));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn expect_fraction(name: &str, value: &RuleValue) -> CclParserResult<f64> {
+    match value {
+        RuleValue::Number(n) if n.is_finite() && (0.0..=1.0).contains(n) => Ok(*n),
+        RuleValue::Number(n) => Err(invalid(
+            name,
+            format!("{} is outside the range 0.0..=1.0", n),
+        )),
+        other => Err(invalid(name, format!("expected a number, found {:?}", other))),
+    }
+}
+
+fn expect_duration(name: &str, value: &RuleValue) -> CclParserResult<u64> {
+    match value {
+        RuleValue::String(s) => parse_duration_secs(s)
+            .ok_or_else(|| invalid(name, format!("'{}' is not a valid duration", s))),
+        RuleValue::Number(n) if n.is_finite() && *n >= 0.0 && n.fract() == 0.0 => Ok(*n as u64),
+        RuleValue::Number(n) => Err(invalid(
+            name,
+            format!("{} is not a whole number of seconds", n),
+        )),
+        other => Err(invalid(
+            name,
+            format!("expected a duration, found {:?}", other),
+        )),
+    }
+}
+
+/// Parse durations of the form `"30s"`, `"15m"`, `"12h"`, `"7d"` or `"2w"` into seconds.
+fn parse_duration_secs(raw: &str) -> Option<u64> {
+    let raw = raw.trim();
+    let unit_start = raw.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = raw.split_at(unit_start);
+    let amount: u64 = amount.parse().ok()?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    amount.checked_mul(multiplier)
+}
+
+fn invalid(name: &str, reason: String) -> CclError {
+    CclError::InvalidParameter {
+        name: name.to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(key: &str, value: RuleValue) -> Rule {
+        Rule {
+            key: key.to_string(),
+            value,
+            span: None,
+        }
+    }
+
+    fn doc_with_rules(rules: Vec<Rule>) -> CclDocument {
+        CclDocument {
+            title: "Bylaws".to_string(),
+            description: String::new(),
+            author: String::new(),
+            created: String::new(),
+            version: "1.0.0".to_string(),
+            budget: None,
+            execution: None,
+            accountability: None,
+            rules,
+        }
+    }
+
+    #[test]
+    fn extracts_nested_proposal_processing_params() {
+        let doc = doc_with_rules(vec![
+            rule("name", RuleValue::String("Bylaws".to_string())),
+            rule(
+                "proposal_processing",
+                RuleValue::Map(vec![
+                    rule("min_duration", RuleValue::String("7d".to_string())),
+                    rule("max_duration", RuleValue::String("21d".to_string())),
+                    rule("pass_threshold_percentage", RuleValue::Number(0.66)),
+                    rule("quorum_percentage", RuleValue::Number(0.10)),
+                    rule("can_be_emergency", RuleValue::Boolean(true)),
+                ]),
+            ),
+        ]);
+
+        let params = doc.governance_params().unwrap();
+        assert_eq!(
+            params,
+            GovernanceParams {
+                quorum_percentage: Some(0.10),
+                pass_threshold: Some(0.66),
+                min_duration_secs: Some(7 * 24 * 60 * 60),
+                max_duration_secs: Some(21 * 24 * 60 * 60),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_params_are_none() {
+        let params = doc_with_rules(vec![]).governance_params().unwrap();
+        assert_eq!(params, GovernanceParams::default());
+    }
+
+    #[test]
+    fn rejects_out_of_range_quorum() {
+        let doc = doc_with_rules(vec![rule("quorum_percentage", RuleValue::Number(1.5))]);
+        match doc.governance_params() {
+            Err(CclError::InvalidParameter { name, .. }) => assert_eq!(name, "quorum_percentage"),
+            other => panic!("expected InvalidParameter, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_type_and_inverted_durations() {
+        let doc = doc_with_rules(vec![rule(
+            "pass_threshold",
+            RuleValue::String("most".to_string()),
+        )]);
+        assert!(matches!(
+            doc.governance_params(),
+            Err(CclError::InvalidParameter { .. })
+        ));
+
+        let doc = doc_with_rules(vec![
+            rule("min_duration", RuleValue::String("14d".to_string())),
+            rule("max_duration", RuleValue::String("7d".to_string())),
+        ]);
+        match doc.governance_params() {
+            Err(CclError::InvalidParameter { name, .. }) => assert_eq!(name, "min_duration"),
+            other => panic!("expected InvalidParameter, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_duration_units() {
+        assert_eq!(parse_duration_secs("45s"), Some(45));
+        assert_eq!(parse_duration_secs("15m"), Some(900));
+        assert_eq!(parse_duration_secs("12h"), Some(43_200));
+        assert_eq!(parse_duration_secs("2w"), Some(1_209_600));
+        assert_eq!(parse_duration_secs("7"), None);
+        assert_eq!(parse_duration_secs("d"), None);
+        assert_eq!(parse_duration_secs("3y"), None);
+    }
+}
@@ -4,6 +4,12 @@ use pest_derive::Parser;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+mod document;
+mod governance;
+mod stream;
+pub use governance::GovernanceParams;
+pub use stream::{stream_statements, SourceStatement, StatementStream};
+
 /// Custom error types for CCL parsing.
 #[derive(Error, Debug)]
 pub enum CclError {
@@ -15,6 +21,8 @@ pub enum CclError {
     IoError(#[from] std::io::Error),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("Invalid governance parameter '{name}': {reason}")]
+    InvalidParameter { name: String, reason: String },
 }
 
 // Aliased to avoid conflict with anyhow::Result if that were to be used elsewhere.
@@ -59,6 +67,10 @@ pub struct CclDocument {
 
     /// Accountability requirements (if any)
     pub accountability: Option<CclAccountability>,
+
+    /// Generic key/value rules declared in the document body
+    #[serde(default)]
+    pub rules: Vec<icn_ccl_dsl::Rule>,
 }
 
 /// Budget allocation in a CCL document
@@ -160,69 +172,13 @@ pub struct CclTransparency {
     pub public_dashboard: bool,
 }
 
-/// Parse a CCL document
-pub fn parse_ccl(_input: &str) -> CclParserResult<CclDocument> {
-    // This is a stub implementation that returns a fixed document
-    // In a real implementation, we would use nom or pest to parse the CCL
-
-    // For now, return a fixed document based on the example
-    Ok(CclDocument {
-        title: "Q3 Budget Allocation".to_string(),
-        description: "Allocate funds for Q3 2023 cooperative activities".to_string(),
-        author: "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK".to_string(),
-        created: "2023-10-15T14:30:00Z".to_string(),
-        version: "1.0.0".to_string(),
-        budget: Some(CclBudget {
-            total: 10000,
-            currency: "USDC".to_string(),
-            categories: {
-                let mut map = std::collections::HashMap::new();
-                map.insert("development".to_string(), 6000);
-                map.insert("marketing".to_string(), 2000);
-                map.insert("operations".to_string(), 1500);
-                map.insert("community".to_string(), 500);
-                map
-            },
-            disbursement: CclDisbursement {
-                schedule: "monthly".to_string(),
-                start_date: "2023-10-01".to_string(),
-                end_date: "2023-12-31".to_string(),
-            },
-            authorization: CclAuthorization {
-                threshold: 2,
-                roles: vec!["treasurer".to_string(), "director".to_string()],
-                require_review: true,
-            },
-        }),
-        execution: Some(CclExecution {
-            actions: vec![
-                CclAction::AnchorData("budget_q3_2023".to_string()),
-                CclAction::PerformAction {
-                    action_type: "budget_allocation".to_string(),
-                    amount: 10000,
-                },
-                CclAction::MintTokens {
-                    token_type: "participation_token".to_string(),
-                    amount: 100,
-                    recipient: "community_pool".to_string(),
-                },
-            ],
-        }),
-        accountability: Some(CclAccountability {
-            reports: CclReports {
-                frequency: "monthly".to_string(),
-                metrics: vec![
-                    "spend_by_category".to_string(),
-                    "completion_status".to_string(),
-                    "remaining_funds".to_string(),
-                ],
-            },
-            transparency: CclTransparency {
-                disclosure_level: "public".to_string(),
-                public_dashboard: true,
-            },
-        }),
-    })
+/// Parse a CCL document, populating its fields from the parse tree.
+///
+/// Sections the source does not declare are `None`. A document without a named
+/// top-level definition to take its title from is rejected with
+/// [`ParserError::MissingField`].
+pub fn parse_ccl(input: &str) -> Result<CclDocument, ParserError> {
+    document::parse_document(input)
 }
 
 // Define the CCL parser using Pest
@@ -293,21 +249,7 @@ pub struct CclElectionRole {
 impl CclDocument {
     /// Parse a CCL string into a document
     pub fn parse(input: &str) -> CclParserResult<Self> {
-        let _parsed =
-            CclParser::parse(Rule::ccl, input).map_err(|e| CclError::ParseError(e.to_string()))?;
-
-        // TODO: Actual parsing logic to populate CclDocument fields from `_parsed` (pest Pairs)
-        // For now, return a minimal CclDocument to satisfy compilation
-        Ok(CclDocument {
-            title: "Parsed CCL Document (Stub)".to_string(),
-            description: "Description (Stub)".to_string(),
-            author: "Author (Stub)".to_string(),
-            created: "Created (Stub)".to_string(),
-            version: "Version (Stub)".to_string(),
-            budget: None,
-            execution: None,
-            accountability: None,
-        })
+        parse_ccl(input).map_err(|e| CclError::ParseError(e.to_string()))
     }
 
     /// Convert the CCL document to a DSL representation
@@ -0,0 +1,263 @@
+use crate::{CclError, CclParserResult};
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+/// The source text of a single top-level CCL statement, as read from a stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceStatement {
+    /// Raw CCL source of the statement, suitable for `CclParser::parse(Rule::ccl, ..)`
+    pub source: String,
+
+    /// 1-based line on which the statement starts in the original input
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    /// Between statements; nothing buffered yet
+    Idle,
+    /// Inside a statement
+    InStatement,
+    /// A top-level block just closed; the statement may still continue with `;` or `else`
+    AfterBlock,
+}
+
+/// Iterator that splits a CCL reader into top-level statements without loading the
+/// whole input into memory.
+///
+/// Splitting is purely lexical: braces, brackets and parentheses are balanced while
+/// string literals and `//` comments are skipped. Each yielded statement still has to be
+/// parsed, which lets callers report errors at the offending statement rather than after
+/// reading the entire file. Memory use is bounded by the largest top-level statement.
+pub struct StatementStream<R> {
+    reader: R,
+    line_no: usize,
+    state: ScanState,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    current: String,
+    current_line: usize,
+    ready: VecDeque<SourceStatement>,
+    finished: bool,
+}
+
+/// Create a [`StatementStream`] over a buffered reader.
+pub fn stream_statements<R: BufRead>(reader: R) -> StatementStream<R> {
+    StatementStream {
+        reader,
+        line_no: 0,
+        state: ScanState::Idle,
+        depth: 0,
+        in_string: false,
+        escaped: false,
+        current: String::new(),
+        current_line: 0,
+        ready: VecDeque::new(),
+        finished: false,
+    }
+}
+
+impl<R: BufRead> StatementStream<R> {
+    fn emit(&mut self) {
+        let source = std::mem::take(&mut self.current);
+        self.ready.push_back(SourceStatement {
+            source: source.trim_end().to_string(),
+            line: self.current_line,
+        });
+        self.state = ScanState::Idle;
+    }
+
+    fn begin(&mut self) {
+        self.state = ScanState::InStatement;
+        self.current_line = self.line_no;
+    }
+
+    fn scan_line(&mut self, line: &str) -> CclParserResult<()> {
+        for (idx, c) in line.char_indices() {
+            // String literals may span lines, so their state lives on the stream.
+            if self.in_string {
+                self.current.push(c);
+                if self.escaped {
+                    self.escaped = false;
+                } else if c == '\\' {
+                    self.escaped = true;
+                } else if c == '"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            if c == '/' && line[idx..].starts_with("//") {
+                // Comment runs to end of line; keep it only if we are mid-statement.
+                if self.state != ScanState::Idle {
+                    self.current.push_str(line[idx..].trim_end_matches(['\r', '\n']));
+                    self.current.push('\n');
+                }
+                return Ok(());
+            }
+
+            if c.is_whitespace() {
+                if self.state != ScanState::Idle {
+                    self.current.push(c);
+                }
+                continue;
+            }
+
+            if self.state == ScanState::AfterBlock {
+                let continues_with_else = line[idx..].starts_with("else")
+                    && !line[idx + 4..]
+                        .chars()
+                        .next()
+                        .is_some_and(|n| n.is_ascii_alphanumeric() || n == '_');
+                if c == ';' {
+                    self.current.push(c);
+                    self.emit();
+                    continue;
+                } else if continues_with_else {
+                    self.state = ScanState::InStatement;
+                } else {
+                    self.emit();
+                }
+            }
+
+            if self.state == ScanState::Idle {
+                self.begin();
+            }
+
+            self.current.push(c);
+            match c {
+                '"' => self.in_string = true,
+                '{' | '(' | '[' => self.depth += 1,
+                '}' | ')' | ']' => {
+                    self.depth = self.depth.checked_sub(1).ok_or_else(|| {
+                        CclError::ParseError(format!(
+                            "unbalanced '{}' at line {}",
+                            c, self.line_no
+                        ))
+                    })?;
+                    if c == '}' && self.depth == 0 {
+                        self.state = ScanState::AfterBlock;
+                    }
+                }
+                ';' if self.depth == 0 => self.emit(),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> CclParserResult<()> {
+        self.finished = true;
+        if self.in_string {
+            return Err(CclError::ParseError(format!(
+                "unterminated string literal in statement starting at line {}",
+                self.current_line
+            )));
+        }
+        if self.depth > 0 {
+            return Err(CclError::ParseError(format!(
+                "unterminated block in statement starting at line {}",
+                self.current_line
+            )));
+        }
+        if self.state != ScanState::Idle {
+            self.emit();
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for StatementStream<R> {
+    type Item = CclParserResult<SourceStatement>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(stmt) = self.ready.pop_front() {
+                return Some(Ok(stmt));
+            }
+            if self.finished {
+                return None;
+            }
+
+            let mut line = String::new();
+            let read = match self.reader.read_line(&mut line) {
+                Ok(read) => read,
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(CclError::IoError(e)));
+                }
+            };
+
+            let result = if read == 0 {
+                self.finish()
+            } else {
+                self.line_no += 1;
+                self.scan_line(&line)
+            };
+            if let Err(e) = result {
+                self.finished = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn collect(src: &str) -> Vec<SourceStatement> {
+        stream_statements(Cursor::new(src))
+            .collect::<CclParserResult<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn splits_top_level_statements() {
+        let src = r#"
+// leading comment
+proposal "A" {
+  title "first";
+}
+log_event(name: "x"); election "B" { seats 3; }
+if proposal.type == "x" {
+  a 1;
+} else {
+  b 2;
+}
+member_age range 18 120 { status "eligible"; };
+"#;
+        let stmts = collect(src);
+        assert_eq!(stmts.len(), 5);
+        assert_eq!(stmts[0].line, 3);
+        assert!(stmts[0].source.starts_with("proposal \"A\""));
+        assert_eq!(stmts[1].source, "log_event(name: \"x\");");
+        assert_eq!(stmts[2].line, 6);
+        assert!(stmts[2].source.starts_with("election"));
+        assert!(stmts[3].source.contains("else"));
+        assert!(stmts[4].source.ends_with("};"));
+    }
+
+    #[test]
+    fn ignores_braces_in_strings_and_comments() {
+        let stmts = collect("a \"{ not a block\"; // } also not\nb { c \"}\"; }\n");
+        assert_eq!(stmts.len(), 2);
+        assert_eq!(stmts[0].source, "a \"{ not a block\";");
+        assert_eq!(stmts[1].line, 2);
+    }
+
+    #[test]
+    fn reports_unterminated_block_at_its_start() {
+        let results: Vec<_> =
+            stream_statements(Cursor::new("ok 1;\n\nbroken {\n  x 1;\n")).collect();
+        assert!(matches!(results[0], Ok(ref s) if s.source == "ok 1;"));
+        match &results[1] {
+            Err(CclError::ParseError(msg)) => assert!(msg.contains("line 3"), "{}", msg),
+            other => panic!("expected parse error, got {:?}", other),
+        }
+        assert_eq!(results.len(), 2);
+    }
+}
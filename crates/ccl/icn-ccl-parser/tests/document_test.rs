@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use icn_ccl_parser::{parse_ccl, CclAction, ParserError};
+    use std::fs;
+    use std::path::Path;
+
+    fn template(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("templates")
+            .join(name);
+        fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Failed to read template file: {}", path.display()))
+    }
+
+    #[test]
+    fn budget_template_populates_document() {
+        let doc = parse_ccl(&template("budget.ccl")).expect("budget template should parse");
+
+        assert_eq!(doc.title, "cooperative_budget");
+        assert_eq!(
+            doc.description,
+            "Annual cooperative budget allocation and spending rules"
+        );
+        assert_eq!(doc.version, "1.0.0");
+        assert_eq!(doc.author, "");
+        assert!(doc.rules.iter().any(|r| r.key == "period"));
+        assert!(!doc.rules.iter().any(|r| r.key == "description"));
+
+        let budget = doc.budget.expect("budget section");
+        assert_eq!(budget.currency, "USD");
+        let mut categories: Vec<_> = budget.categories.keys().cloned().collect();
+        categories.sort();
+        assert_eq!(
+            categories,
+            ["community", "operations", "projects", "reserve"]
+        );
+
+        let accountability = doc.accountability.expect("accountability section");
+        assert_eq!(accountability.reports.frequency, "monthly");
+        assert_eq!(
+            accountability.reports.metrics,
+            ["spending_summary", "transaction_log"]
+        );
+
+        let actions = doc.execution.expect("execution section").actions;
+        assert!(matches!(
+            &actions[0],
+            CclAction::MintTokens { token_type, recipient, .. }
+                if token_type == "expense_receipt" && recipient == "ctx.submitter_id"
+        ));
+        assert!(matches!(&actions[1], CclAction::AnchorData(path) if path == "finance/expenses"));
+        assert!(matches!(
+            &actions[2],
+            CclAction::PerformAction { action_type, .. } if action_type == "update_budget_allocation"
+        ));
+        assert_eq!(actions.len(), 4);
+    }
+
+    #[test]
+    fn election_template_has_no_budget_or_accountability() {
+        let doc = parse_ccl(&template("election.ccl")).expect("election template should parse");
+
+        assert_eq!(doc.title, "role_election");
+        assert_eq!(
+            doc.description,
+            "Rules for electing members to organizational roles"
+        );
+        assert_eq!(doc.version, "1.0.0");
+        assert!(doc.budget.is_none());
+        assert!(doc.accountability.is_none());
+
+        let actions = doc.execution.expect("execution section").actions;
+        assert!(matches!(
+            &actions[0],
+            CclAction::MintTokens { token_type, .. } if token_type == "nomination_receipt"
+        ));
+        assert!(actions.iter().any(|a| matches!(
+            a,
+            CclAction::PerformAction { action_type, .. } if action_type == "record_vote"
+        )));
+    }
+
+    #[test]
+    fn budget_allocations_are_shares_of_the_total() {
+        let src = r#"
+budget "q3" {
+  total 10000;
+  currency "USDC";
+  disbursement { schedule "monthly"; start_date "2023-10-01"; end_date "2023-12-31"; };
+  authorization { threshold 2; roles ["treasurer", "director"]; require_review true; };
+}
+
+allocations {
+  category "development" { allocation 0.6; }
+  category "community" { allocation 500; }
+}
+"#;
+        let budget = parse_ccl(src).unwrap().budget.unwrap();
+
+        assert_eq!(budget.total, 10000);
+        assert_eq!(budget.categories["development"], 6000);
+        assert_eq!(budget.categories["community"], 500);
+        assert_eq!(budget.disbursement.end_date, "2023-12-31");
+        assert_eq!(budget.authorization.threshold, 2);
+        assert_eq!(budget.authorization.roles, ["treasurer", "director"]);
+        assert!(budget.authorization.require_review);
+    }
+
+    #[test]
+    fn document_without_title_is_rejected() {
+        let src = r#"
+reporting {
+  frequency "monthly";
+}
+"#;
+        match parse_ccl(src) {
+            Err(ParserError::MissingField(field)) => assert_eq!(field, "title"),
+            other => panic!("expected MissingField, got {:?}", other),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::opcodes::{Opcode, Program};
+use host_abi::{abi_version_section_data, ICN_ABI_VERSION_SECTION, ICN_HOST_ABI_VERSION};
 use icn_economics::ResourceType;
 use icn_types::mesh::{MeshJobParams, QoSProfile, WorkflowType};
 use serde_cbor;
@@ -6,7 +7,7 @@ use serde_json;
 use std::collections::HashMap;
 
 use wasm_encoder::{
-    CodeSection, ConstExpr, DataSection, EntityType, ExportKind,
+    BlockType, CodeSection, ConstExpr, CustomSection, DataSection, EntityType, ExportKind,
     ExportSection, Function, FunctionSection, ImportSection, Instruction, MemorySection,
     MemoryType, Module, TypeSection, ValType,
 };
@@ -14,6 +15,10 @@ use wasm_encoder::{
 pub const JOB_ID_BUFFER_SIZE: u32 = 128;
 pub const JOB_ID_BUFFER_OFFSET: u32 = 0; // Start data segments at offset 0
 
+/// Upper bound on the iterations of a single emitted loop, whatever length
+/// the host reports for its collection.
+pub const MAX_LOOP_ITERATIONS: u32 = 1024;
+
 // Helper to emit data segments correctly using ConstExpr
 fn emit_data_segment(data_section: &mut DataSection, offset: u32, data: &[u8]) {
     data_section.active(
@@ -47,6 +52,42 @@ fn encode_push_string(
     f.instruction(&Instruction::I32Const(string_len));
 }
 
+// Each open loop uses two locals after local 0: its item index and its item count
+fn loop_index_local(depth: u32) -> u32 {
+    1 + 2 * depth
+}
+
+fn loop_count_local(depth: u32) -> u32 {
+    2 + 2 * depth
+}
+
+/// Deepest nesting of `BeginLoop` / `EndLoop` in `prog`
+fn max_loop_depth(prog: &Program) -> u32 {
+    let (mut depth, mut max) = (0u32, 0u32);
+    for op in &prog.ops {
+        match op {
+            Opcode::BeginLoop { .. } => {
+                depth += 1;
+                max = max.max(depth);
+            }
+            Opcode::EndLoop => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max
+}
+
+/// Advance the loop at `depth` and branch back to its head, then close it
+fn emit_loop_end(f: &mut Function, depth: u32) {
+    f.instruction(&Instruction::LocalGet(loop_index_local(depth)));
+    f.instruction(&Instruction::I32Const(1));
+    f.instruction(&Instruction::I32Add);
+    f.instruction(&Instruction::LocalSet(loop_index_local(depth)));
+    f.instruction(&Instruction::Br(0));
+    f.instruction(&Instruction::End); // loop
+    f.instruction(&Instruction::End); // block
+}
+
 pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
     let mut module = Module::new();
     let mut code = CodeSection::new();
@@ -64,9 +105,8 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
     // This type will be used for the "_start" function.
     // Find an existing ()->i32 type if host_submit_mesh_job (type 16) matches, or add new.
     // Type 16 is (i32, i32, i32, i32) -> i32. So, we need a new type for () -> i32.
-    // Let's assume existing types are 0-16. New type for _start will be 17.
-    let main_func_signature_type_idx = 17;
-    type_section.function(vec![], vec![ValType::I32]);
+    // Host function types are 0-18 (defined below). The type for _start comes right after them.
+    let main_func_signature_type_idx = 19;
 
     // Define memory (memory 0)
     // Initial size of 1 page (64KiB) should be enough for now.
@@ -90,7 +130,7 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
     // It will be after all imported functions.
     // Assuming host_fns has N items, imported functions are 0..N-1.
     // The first *defined* function in this module will be index N.
-    let host_fns_count = 17; // As per current host_fns array (indices 0-16)
+    let host_fns_count = 19; // As per current host_fns array (indices 0-18)
     let main_function_idx = host_fns_count as u32;
 
     // Declare the main function in the FunctionSection
@@ -99,12 +139,16 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
     // Create the body for the main "_start" function
     // Locals:
     // 0: last_submit_job_result (i32)
-    let locals = vec![(1, ValType::I32)]; // CORRECTED: Declare locals if needed, pass to Function::new
+    // 1..: index and count of each nested loop, see loop_index_local / loop_count_local
+    let locals = vec![(1 + 2 * max_loop_depth(prog), ValType::I32)]; // CORRECTED: Declare locals if needed, pass to Function::new
     let mut main_f = Function::new(locals); // CORRECTED: Use Function::new
                                             // Initialize local(0) to 0 (default successful/neutral return if no SubmitJob happens or if it's not last)
     main_f.instruction(&Instruction::I32Const(0));
     main_f.instruction(&Instruction::LocalSet(0));
 
+    // Number of loops opened and not yet closed
+    let mut loop_depth: u32 = 0;
+
     // Process all opcodes, emitting them into the single main_f function body
     for op in prog.ops.iter() {
         // Note: The original type_index mapping per opcode is no longer used to declare separate functions.
@@ -169,6 +213,42 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
                 // encode_push_string(&mut f, &format!("{:?}", op)); // old log behavior
                 main_f.instruction(&Instruction::Call(8)); // host fn 8: log_endif (or just log)
             }
+            Opcode::BeginLoop { var, collection } => {
+                let depth = loop_depth;
+                loop_depth += 1;
+
+                // count = min(loop_begin(var, collection), MAX_LOOP_ITERATIONS)
+                encode_push_string(&mut main_f, var, &mut data_section, &mut next_data_offset);
+                encode_push_string(&mut main_f, collection, &mut data_section, &mut next_data_offset);
+                main_f.instruction(&Instruction::Call(17)); // host fn 17: loop_begin -> item count
+                main_f.instruction(&Instruction::LocalTee(loop_count_local(depth)));
+                main_f.instruction(&Instruction::I32Const(MAX_LOOP_ITERATIONS as i32));
+                main_f.instruction(&Instruction::I32GtU);
+                main_f.instruction(&Instruction::If(BlockType::Empty));
+                main_f.instruction(&Instruction::I32Const(MAX_LOOP_ITERATIONS as i32));
+                main_f.instruction(&Instruction::LocalSet(loop_count_local(depth)));
+                main_f.instruction(&Instruction::End);
+
+                main_f.instruction(&Instruction::I32Const(0));
+                main_f.instruction(&Instruction::LocalSet(loop_index_local(depth)));
+
+                // block { loop { if index >= count break; loop_next(index); body... } }
+                main_f.instruction(&Instruction::Block(BlockType::Empty));
+                main_f.instruction(&Instruction::Loop(BlockType::Empty));
+                main_f.instruction(&Instruction::LocalGet(loop_index_local(depth)));
+                main_f.instruction(&Instruction::LocalGet(loop_count_local(depth)));
+                main_f.instruction(&Instruction::I32GeU);
+                main_f.instruction(&Instruction::BrIf(1));
+                main_f.instruction(&Instruction::LocalGet(loop_index_local(depth)));
+                main_f.instruction(&Instruction::Call(18)); // host fn 18: loop_next(index) binds var
+            }
+            Opcode::EndLoop => {
+                // An unmatched EndLoop has no loop to close
+                if let Some(depth) = loop_depth.checked_sub(1) {
+                    loop_depth = depth;
+                    emit_loop_end(&mut main_f, depth);
+                }
+            }
             Opcode::SetProperty {
                 key, value_json, ..
             } => {
@@ -273,6 +353,8 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
                     is_interactive: false,
                     expected_output_schema_cid: None,
                     execution_policy: None,
+                    deterministic: false,
+                    output_recipients: Vec::new(),
                 };
 
                 // 2. Serialize MeshJobParams to CBOR
@@ -305,6 +387,12 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
         }
     }
 
+    // Close any loop left open so the body stays well-formed
+    while let Some(depth) = loop_depth.checked_sub(1) {
+        loop_depth = depth;
+        emit_loop_end(&mut main_f, depth);
+    }
+
     // Finalize main function body
     main_f.instruction(&Instruction::LocalGet(0));
     main_f.instruction(&Instruction::End);
@@ -338,6 +426,14 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
         vec![ValType::I32, ValType::I32, ValType::I32, ValType::I32],
         vec![ValType::I32],
     );
+    // Type 17: loop_begin(var_ptr: i32, var_len: i32, collection_ptr: i32, collection_len: i32) -> item_count: i32
+    type_section.function(
+        vec![ValType::I32, ValType::I32, ValType::I32, ValType::I32],
+        vec![ValType::I32],
+    );
+    type_section.function(vec![ValType::I32], vec![]); // 18: loop_next(index)
+    // Type 19: _start() -> i32
+    type_section.function(vec![], vec![ValType::I32]);
 
     // Imports: Define all imported host functions
     let host_fns = [
@@ -358,6 +454,8 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
         ("use_resource", 14u32),
         ("transfer_token", 15u32),
         ("host_submit_mesh_job", 16u32),
+        ("loop_begin", 17u32),
+        ("loop_next", 18u32),
     ];
     for (name, type_idx) in host_fns.iter() {
         import_section.import("icn_host", name, EntityType::Function(*type_idx));
@@ -376,5 +474,12 @@ pub fn program_to_wasm(prog: &Program) -> Vec<u8> {
     module.section(&code); // Actual function bodies
     module.section(&export_section); // Add export section
 
+    // Record the host ABI this module was generated against so the runtime can reject ABI mismatches
+    let abi_version = abi_version_section_data(ICN_HOST_ABI_VERSION);
+    module.section(&CustomSection {
+        name: ICN_ABI_VERSION_SECTION,
+        data: &abi_version,
+    });
+
     module.finish()
 }
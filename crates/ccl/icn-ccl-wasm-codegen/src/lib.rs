@@ -1,15 +1,23 @@
 //'!' Pass #2: lower `icn-ccl-dsl` structures into an executable opcode stream.
 
 use crate::opcodes::{Opcode, Program};
-use icn_ccl_dsl::{ActionStep, DslModule, IfExpr, Rule, RuleValue};
+use icn_ccl_dsl::{ActionStep, DslModule, IfExpr, Rule, RuleValue, Span};
 // This line was removed due to clippy::single_component_path_imports
 // use serde_json;
 
 pub mod emit;
 pub mod opcodes;
+mod optimize;
+
+pub use optimize::fold_condition;
 
 pub struct WasmGenerator {
     ops: Vec<Opcode>,
+    /// Source position of each entry in `ops`.
+    spans: Vec<Option<Span>>,
+    /// Span attributed to ops pushed right now; the innermost element that has one.
+    span: Option<Span>,
+    optimize: bool,
 }
 
 impl Default for WasmGenerator {
@@ -20,27 +28,59 @@ impl Default for WasmGenerator {
 
 impl WasmGenerator {
     pub fn new() -> Self {
-        Self { ops: Vec::new() }
+        Self {
+            ops: Vec::new(),
+            spans: Vec::new(),
+            span: None,
+            optimize: false,
+        }
+    }
+
+    /// Fold `if` conditions that compare two literals into their taken branch,
+    /// and keep only the last of adjacent `SetProperty` ops for the same key.
+    pub fn optimize(mut self) -> Self {
+        self.optimize = true;
+        self
     }
 
     pub fn generate(mut self, modules: Vec<DslModule>) -> Program {
         for module in modules {
             self.walk_module(&module);
         }
-        Program::new(self.ops)
+        if self.optimize {
+            let paired = self.ops.into_iter().zip(self.spans).collect();
+            (self.ops, self.spans) = optimize::collapse_set_properties(paired)
+                .into_iter()
+                .unzip();
+        }
+        Program::with_spans(self.ops, self.spans)
+    }
+
+    fn push(&mut self, op: Opcode) {
+        self.ops.push(op);
+        self.spans.push(self.span);
     }
 
     fn walk_module(&mut self, m: &DslModule) {
+        self.span = match m {
+            DslModule::Proposal(p) => p.span,
+            DslModule::ActionHandler(h) => h.span,
+            DslModule::Section(s) => s.span,
+            DslModule::Role(r) => r.span,
+            DslModule::Anchor(a) => a.span,
+            DslModule::MeteredAction(m) => m.span,
+            _ => None,
+        };
         match m {
             DslModule::Proposal(p) => {
-                self.ops.push(Opcode::CreateProposal {
+                self.push(Opcode::CreateProposal {
                     title: p.title.clone(),
                     version: Some(p.version.clone()),
                 });
                 self.walk_rules(&p.rules);
             }
             DslModule::ActionHandler(h) => {
-                self.ops.push(Opcode::OnEvent {
+                self.push(Opcode::OnEvent {
                     event: h.event.clone(),
                 });
                 for step in &h.steps {
@@ -48,43 +88,48 @@ impl WasmGenerator {
                 }
             }
             DslModule::Section(s) => {
-                self.ops.push(Opcode::BeginSection {
+                self.push(Opcode::BeginSection {
                     kind: s.kind.clone(),
                     title: s.title.clone(),
                 });
                 self.walk_rules(&s.rules);
-                self.ops.push(Opcode::EndSection);
+                self.push(Opcode::EndSection);
             }
             DslModule::Role(r) => {
-                self.ops.push(Opcode::BeginSection {
+                self.push(Opcode::BeginSection {
                     kind: "role".to_string(), // Fixed kind for roles
                     title: Some(r.name.clone()),
                 });
                 if let Some(desc) = &r.description {
                     let json_desc = serde_json::to_string(desc)
                         .unwrap_or_else(|_| "\"<serialization error>\"".to_string());
-                    self.ops.push(Opcode::SetProperty {
+                    self.push(Opcode::SetProperty {
                         key: "description".to_string(),
                         value_json: json_desc,
                     });
                 }
                 self.walk_rules(&r.attributes); // Process attributes as a list of rules
-                self.ops.push(Opcode::EndSection);
+                self.push(Opcode::EndSection);
             }
-            other => self
-                .ops
-                .push(Opcode::Todo(format!("Unhandled DslModule: {:?}", other))),
+            other => self.push(Opcode::Todo(format!("Unhandled DslModule: {:?}", other))),
         }
     }
 
     fn walk_step(&mut self, step: &ActionStep) {
+        let enclosing = self.span;
+        let own = match step {
+            ActionStep::Metered(m) => m.span,
+            ActionStep::Anchor(a) => a.span,
+            _ => None,
+        };
+        self.span = own.or(enclosing);
         match step {
             ActionStep::Metered(m) => {
                 let data_json = m
                     .data
                     .as_ref()
                     .map(|d| serde_json::to_string(d).unwrap_or_else(|_| "[]".to_string()));
-                self.ops.push(Opcode::MintToken {
+                self.push(Opcode::MintToken {
                     res_type: m.resource_type.clone(),
                     amount: m.amount,
                     recipient: m.recipient.clone(),
@@ -92,7 +137,7 @@ impl WasmGenerator {
                 });
             }
             ActionStep::Anchor(a) => {
-                self.ops.push(Opcode::AnchorData {
+                self.push(Opcode::AnchorData {
                     path: a.path.clone(),
                     data_ref: a.data_reference.clone(),
                 });
@@ -103,14 +148,13 @@ impl WasmGenerator {
                 amount,
             } => {
                 // Record resource usage and perform an action
-                self.ops.push(Opcode::UseResource {
+                self.push(Opcode::UseResource {
                     resource_type: resource.to_string(),
                     amount: *amount,
                 });
 
                 // Generate code for the action identifier
-                self.ops
-                    .push(Opcode::Todo(format!("Perform action: {}", ident)));
+                self.push(Opcode::Todo(format!("Perform action: {}", ident)));
             }
             ActionStep::TransferToken {
                 token_type,
@@ -119,7 +163,7 @@ impl WasmGenerator {
                 recipient,
             } => {
                 // Transfer tokens between accounts
-                self.ops.push(Opcode::TransferToken {
+                self.push(Opcode::TransferToken {
                     token_type: token_type.clone(),
                     amount: *amount,
                     sender: Some(sender.clone()),
@@ -127,21 +171,37 @@ impl WasmGenerator {
                 });
             }
         }
+        self.span = enclosing;
     }
 
     /// Walk a vector of `Rule`s and push op-codes
     fn walk_rules(&mut self, rules: &[Rule]) {
         for r in rules {
+            let enclosing = self.span;
+            self.span = r.span.or(enclosing);
             match &r.value {
                 RuleValue::If(expr) => self.walk_if_expr(expr),
 
+                RuleValue::ForEach {
+                    var,
+                    collection_raw,
+                    body,
+                } => {
+                    self.push(Opcode::BeginLoop {
+                        var: var.clone(),
+                        collection: collection_raw.clone(),
+                    });
+                    self.walk_rules(body);
+                    self.push(Opcode::EndLoop);
+                }
+
                 RuleValue::Range(range) => {
-                    self.ops.push(Opcode::BeginSection {
+                    self.push(Opcode::BeginSection {
                         kind: format!("range_{}_{}", range.start, range.end),
                         title: Some(r.key.clone()),
                     });
                     self.walk_rules(&range.rules);
-                    self.ops.push(Opcode::EndSection);
+                    self.push(Opcode::EndSection);
                 }
 
                 RuleValue::Map(kv) => {
@@ -165,27 +225,44 @@ impl WasmGenerator {
                 | RuleValue::List(_) => {
                     let json_value = serde_json::to_string(&r.value)
                         .unwrap_or_else(|_| "\"<serialization error>\"".to_string());
-                    self.ops.push(Opcode::SetProperty {
+                    self.push(Opcode::SetProperty {
                         key: r.key.clone(),
                         value_json: json_value,
                     });
                 }
             }
+            self.span = enclosing;
         }
     }
 
-    /// emit If / Else / EndIf
+    /// emit If / Else / EndIf, or only the taken branch of a static condition when optimizing
     fn walk_if_expr(&mut self, ifx: &IfExpr) {
-        self.ops.push(Opcode::If {
+        if self.optimize {
+            match optimize::fold_condition(&ifx.condition_raw) {
+                Some(true) => {
+                    self.walk_rules(&ifx.then_rules);
+                    return;
+                }
+                Some(false) => {
+                    if let Some(else_rules) = &ifx.else_rules {
+                        self.walk_rules(else_rules);
+                    }
+                    return;
+                }
+                None => {}
+            }
+        }
+
+        self.push(Opcode::If {
             condition: ifx.condition_raw.clone(),
         });
         self.walk_rules(&ifx.then_rules);
 
         if let Some(else_rules) = &ifx.else_rules {
-            self.ops.push(Opcode::Else);
+            self.push(Opcode::Else);
             self.walk_rules(else_rules);
         }
-        self.ops.push(Opcode::EndIf);
+        self.push(Opcode::EndIf);
     }
 
     // --------------------------------------------------------
@@ -199,7 +276,7 @@ impl WasmGenerator {
         let args_payload_json = serde_json::to_string(args_rule)
             .unwrap_or_else(|_| "{}".to_string()); // Default to an empty JSON object string on error
 
-        self.ops.push(Opcode::CallHost {
+        self.push(Opcode::CallHost {
             fn_name: fn_name.to_string(),
             args_payload: args_payload_json,
         });
@@ -210,13 +287,19 @@ impl WasmGenerator {
 //  Utility – recognise the map-structure produced by the lowerer for calls
 // -------------------------------------------------------------------------
 
-fn is_function_call(kv: &[Rule]) -> bool {
+/// Whether `kv` is the `function_name` / `args` map the lowerer emits for a call.
+pub fn is_function_call(kv: &[Rule]) -> bool {
     kv.first()
         .map(|first| first.key == "function_name")
         .unwrap_or(false)
 }
 
-pub fn compile_to_wasm(modules: Vec<DslModule>) -> Vec<u8> {
-    let prog = WasmGenerator::new().generate(modules);
+/// Generate and emit `modules`, running the [`WasmGenerator::optimize`] passes if `optimize` is set.
+pub fn compile_to_wasm(modules: Vec<DslModule>, optimize: bool) -> Vec<u8> {
+    let mut generator = WasmGenerator::new();
+    if optimize {
+        generator = generator.optimize();
+    }
+    let prog = generator.generate(modules);
     emit::program_to_wasm(&prog)
 }
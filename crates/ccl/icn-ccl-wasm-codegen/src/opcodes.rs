@@ -1,6 +1,7 @@
 //'!' Extremely-rough first cut at a host-call opcode list.
 //'!' Everything will get revisited once we know the real WASM ABI.
 
+use icn_ccl_dsl::Span;
 use serde::{Deserialize, Serialize};
 
 // Opcode represents a single operation in a compiled ICN program.
@@ -59,6 +60,14 @@ pub enum Opcode {
     },
     Else,
     EndIf,
+    /// Run the ops up to the matching `EndLoop` once per item of `collection`,
+    /// bound to `var`. Emitted loops are capped at
+    /// [`crate::emit::MAX_LOOP_ITERATIONS`] iterations.
+    BeginLoop {
+        var: String,
+        collection: String,
+    },
+    EndLoop,
 
     // misc
     RangeCheck {
@@ -84,10 +93,29 @@ pub enum Opcode {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Program {
     pub ops: Vec<Opcode>,
+    /// Source position of each op, parallel to `ops`; not serialized.
+    #[serde(skip)]
+    spans: Vec<Option<Span>>,
 }
 
 impl Program {
     pub fn new(ops: Vec<Opcode>) -> Self {
-        Program { ops }
+        let spans = vec![None; ops.len()];
+        Program { ops, spans }
+    }
+
+    /// Build a program whose ops remember the CCL source position they came from.
+    pub fn with_spans(ops: Vec<Opcode>, spans: Vec<Option<Span>>) -> Self {
+        debug_assert_eq!(ops.len(), spans.len());
+        Program { ops, spans }
+    }
+
+    /// `(op index, span)` for every op whose source position is known.
+    pub fn source_map(&self) -> Vec<(usize, Span)> {
+        self.spans
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, span)| span.map(|span| (idx, span)))
+            .collect()
     }
 }
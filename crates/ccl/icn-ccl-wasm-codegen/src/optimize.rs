@@ -0,0 +1,83 @@
+//'!' Static simplifications applied by `WasmGenerator::optimize`.
+
+use crate::opcodes::Opcode;
+use icn_ccl_dsl::Span;
+
+/// Two-character operators first, so `>=` is not read as `>`.
+const OPERATORS: &[&str] = &["==", "!=", ">=", "<=", ">", "<"];
+
+#[derive(Debug, PartialEq)]
+enum Literal {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+}
+
+fn parse_literal(raw: &str) -> Option<Literal> {
+    let raw = raw.trim();
+    if let Some(inner) = raw
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        return (!inner.contains('"')).then(|| Literal::String(inner.to_string()));
+    }
+    match raw {
+        "true" => Some(Literal::Boolean(true)),
+        "false" => Some(Literal::Boolean(false)),
+        _ => raw
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(Literal::Number),
+    }
+}
+
+/// Evaluate a raw `if` condition whose operands are both literals, e.g. `1 == 1`
+/// or `"a" != "b"`. Anything that depends on runtime values yields `None`.
+pub fn fold_condition(condition_raw: &str) -> Option<bool> {
+    let (lhs, op, rhs) = condition_raw.char_indices().find_map(|(i, _)| {
+        let rest = &condition_raw[i..];
+        OPERATORS
+            .iter()
+            .find(|op| rest.starts_with(**op))
+            .map(|op| (&condition_raw[..i], *op, &rest[op.len()..]))
+    })?;
+
+    match (parse_literal(lhs)?, parse_literal(rhs)?) {
+        (Literal::Number(a), Literal::Number(b)) => Some(match op {
+            "==" => a == b,
+            "!=" => a != b,
+            ">=" => a >= b,
+            "<=" => a <= b,
+            ">" => a > b,
+            _ => a < b,
+        }),
+        (a, b) if std::mem::discriminant(&a) == std::mem::discriminant(&b) => match op {
+            "==" => Some(a == b),
+            "!=" => Some(a != b),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Drop a `SetProperty` when the op right after it sets the same key again.
+/// Each op travels with its source span so the two stay aligned.
+pub(crate) fn collapse_set_properties(
+    ops: Vec<(Opcode, Option<Span>)>,
+) -> Vec<(Opcode, Option<Span>)> {
+    let mut collapsed: Vec<(Opcode, Option<Span>)> = Vec::with_capacity(ops.len());
+    for (op, span) in ops {
+        if let (
+            Some((Opcode::SetProperty { key: previous, .. }, _)),
+            Opcode::SetProperty { key, .. },
+        ) = (collapsed.last(), &op)
+        {
+            if previous == key {
+                collapsed.pop();
+            }
+        }
+        collapsed.push((op, span));
+    }
+    collapsed
+}
@@ -8,7 +8,7 @@ use wasmparser::{Parser, Payload, TypeRef, WasmFeatures};
 fn emit_budget_wasm_validates() {
     let src = include_str!("../../icn-ccl-parser/templates/budget.ccl");
     let modules = lower_str(src).expect("lower to DSL");
-    let bytes = compile_to_wasm(modules);
+    let bytes = compile_to_wasm(modules, false);
 
     // quick sanity: wasmparser validates
     Validator::new()
@@ -72,3 +72,49 @@ fn wasm_contains_range_check() {
         "range_check import not found in module"
     );
 }
+
+#[test]
+fn wasm_embeds_host_abi_version() {
+    let src = include_str!("../../icn-ccl-parser/templates/budget.ccl");
+    let modules = lower_str(src).expect("lower to DSL");
+    let wasm_bin = compile_to_wasm(modules, false);
+
+    assert_eq!(
+        host_abi::read_abi_version(&wasm_bin).expect("readable module"),
+        Some(host_abi::ICN_HOST_ABI_VERSION)
+    );
+    Validator::new()
+        .validate_all(&wasm_bin)
+        .expect("custom section keeps module valid");
+}
+
+#[test]
+fn for_each_emits_bounded_loop() {
+    let src = r#"
+        proposal "payout" {
+            for_each member in members {
+                for_each delegate in member.delegates {
+                    notify delegate;
+                }
+            }
+        }
+    "#;
+    let wasm_bin = compile_to_wasm(lower_str(src).expect("lowering failed"), false);
+
+    Validator::new()
+        .validate_all(&wasm_bin)
+        .expect("nested loops must validate");
+
+    let mut loop_imports = Vec::new();
+    for payload in Parser::new(0).parse_all(&wasm_bin) {
+        if let Ok(Payload::ImportSection(reader)) = payload {
+            for imp in reader {
+                let imp = imp.expect("Failed to read import entry");
+                if imp.name.starts_with("loop_") {
+                    loop_imports.push(imp.name.to_string());
+                }
+            }
+        }
+    }
+    assert_eq!(loop_imports, ["loop_begin", "loop_next"]);
+}
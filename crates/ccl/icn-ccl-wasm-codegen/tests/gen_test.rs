@@ -1,5 +1,5 @@
 use icn_ccl_compiler::lower::lower_str;
-use icn_ccl_wasm_codegen::opcodes::Program;
+use icn_ccl_wasm_codegen::opcodes::{Opcode, Program};
 use icn_ccl_wasm_codegen::WasmGenerator;
 use insta::assert_json_snapshot;
 
@@ -27,3 +27,100 @@ macro_rules! snapshot_file {
 snapshot_file!(election_ops, "../../icn-ccl-parser/templates/election.ccl");
 snapshot_file!(budget_ops, "../../icn-ccl-parser/templates/budget.ccl");
 snapshot_file!(bylaws_ops, "../../icn-ccl-parser/templates/bylaws.ccl");
+
+#[test]
+fn for_each_ops() {
+    let src = r#"
+proposal "payout" {
+  for_each member in members {
+    recipient member;
+    amount 10;
+  }
+}
+"#;
+    let program_ops = modules_from_ccl_string(src);
+    assert_json_snapshot!("for_each_ops", program_ops);
+}
+
+const FOLDABLE_CCL: &str = r#"
+proposal "folding" {
+  if 1 == 1 {
+    quorum 0.5;
+  } else {
+    quorum 0.9;
+  }
+  threshold 1;
+  threshold 2;
+}
+"#;
+
+fn optimized_ops(ccl_string: &str) -> Vec<Opcode> {
+    let modules = lower_str(ccl_string).expect("lower to DSL");
+    WasmGenerator::new().optimize().generate(modules).ops
+}
+
+#[test]
+fn static_condition_is_folded_when_optimizing() {
+    let ops = optimized_ops(FOLDABLE_CCL);
+    assert!(!ops.iter().any(|op| matches!(op, Opcode::If { .. })));
+    assert!(ops.contains(&Opcode::SetProperty {
+        key: "quorum".to_string(),
+        value_json: "0.5".to_string(),
+    }));
+    assert!(!ops.iter().any(|op| matches!(
+        op,
+        Opcode::SetProperty { value_json, .. } if value_json == "0.9"
+    )));
+
+    // Without optimizing, the branch is emitted as-is
+    let unoptimized = modules_from_ccl_string(FOLDABLE_CCL).ops;
+    assert!(unoptimized.iter().any(|op| matches!(op, Opcode::If { .. })));
+}
+
+#[test]
+fn repeated_property_keeps_last_value_when_optimizing() {
+    let thresholds: Vec<_> = optimized_ops(FOLDABLE_CCL)
+        .into_iter()
+        .filter(|op| matches!(op, Opcode::SetProperty { key, .. } if key == "threshold"))
+        .collect();
+    assert_eq!(
+        thresholds,
+        [Opcode::SetProperty {
+            key: "threshold".to_string(),
+            value_json: "2.0".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn anchor_data_maps_back_to_its_source_line() {
+    let src = r#"proposal "traced" {
+  title "traced";
+}
+
+actions {
+  on "proposal.accepted" {
+    mint_token {
+      type "receipt";
+      amount 1;
+    }
+    anchor_data {
+      path "governance/traced";
+      data "bafyexample";
+    }
+  }
+}
+"#;
+    let program = modules_from_ccl_string(src);
+    let anchor_idx = program
+        .ops
+        .iter()
+        .position(|op| matches!(op, Opcode::AnchorData { .. }))
+        .expect("AnchorData op");
+    let (_, span) = program
+        .source_map()
+        .into_iter()
+        .find(|(idx, _)| *idx == anchor_idx)
+        .expect("AnchorData has a span");
+    assert_eq!(span.line, 11);
+}
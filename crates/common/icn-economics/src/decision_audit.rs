@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use icn_identity::Did;
+use serde::{Deserialize, Serialize};
+use sled::Db;
+use tokio::sync::Mutex;
+
+use crate::ScopedResourceToken;
+
+const DECISION_TREE_NAME: &str = "authorization_decisions";
+
+/// Result of a single authorization check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionOutcome {
+    Allowed,
+    Denied {
+        /// Human-readable reason, taken from the authorization error
+        reason: String,
+    },
+}
+
+/// Audit record of one `check_authorization` call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizationDecision {
+    pub did: Did,
+    pub resource_type: String,
+    pub scope: String,
+    pub amount: u64,
+    pub outcome: DecisionOutcome,
+    /// Unix timestamp (seconds) from the enforcer's clock
+    pub timestamp: u64,
+}
+
+impl AuthorizationDecision {
+    pub(crate) fn new(
+        did: &Did,
+        token: &ScopedResourceToken,
+        outcome: DecisionOutcome,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            did: did.clone(),
+            resource_type: token.resource_type.clone(),
+            scope: token.scope.clone(),
+            amount: token.amount,
+            outcome,
+            timestamp,
+        }
+    }
+
+    pub fn is_denied(&self) -> bool {
+        matches!(self.outcome, DecisionOutcome::Denied { .. })
+    }
+}
+
+/// Destination for authorization decisions.
+///
+/// Implementations may store decisions locally or forward them elsewhere
+/// (e.g. to agoranet). A failing sink never changes the authorization result.
+#[async_trait]
+pub trait DecisionSink: Send + Sync {
+    async fn record(&self, decision: AuthorizationDecision) -> Result<()>;
+}
+
+/// Keeps decisions in memory, in the order they were made.
+#[derive(Debug, Default)]
+pub struct InMemoryDecisionSink {
+    decisions: Mutex<Vec<AuthorizationDecision>>,
+}
+
+impl InMemoryDecisionSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All recorded decisions, oldest first
+    pub async fn decisions(&self) -> Vec<AuthorizationDecision> {
+        self.decisions.lock().await.clone()
+    }
+
+    /// Denials recorded for `did`, oldest first
+    pub async fn denials_for(&self, did: &Did) -> Vec<AuthorizationDecision> {
+        self.decisions
+            .lock()
+            .await
+            .iter()
+            .filter(|d| &d.did == did && d.is_denied())
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DecisionSink for InMemoryDecisionSink {
+    async fn record(&self, decision: AuthorizationDecision) -> Result<()> {
+        self.decisions.lock().await.push(decision);
+        Ok(())
+    }
+}
+
+/// Persists decisions in a Sled tree keyed by a monotonic sequence number.
+#[derive(Clone)]
+pub struct SledDecisionSink {
+    db: Db,
+}
+
+impl SledDecisionSink {
+    /// Opens or creates a Sled database at the given path for the decision log.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let db = sled::open(path).context("Failed to open Sled database for decision log")?;
+        db.open_tree(DECISION_TREE_NAME)
+            .context("Failed to open authorization_decisions tree in Sled database")?;
+        Ok(Self { db })
+    }
+
+    fn tree(&self) -> Result<sled::Tree> {
+        self.db
+            .open_tree(DECISION_TREE_NAME)
+            .context("Failed to access authorization_decisions tree in Sled database")
+    }
+
+    /// All recorded decisions, oldest first
+    pub fn decisions(&self) -> Result<Vec<AuthorizationDecision>> {
+        self.tree()?
+            .iter()
+            .values()
+            .map(|value| {
+                let bytes = value.context("Failed to read decision from Sled")?;
+                bincode::deserialize(&bytes).context("Failed to deserialize decision")
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DecisionSink for SledDecisionSink {
+    async fn record(&self, decision: AuthorizationDecision) -> Result<()> {
+        let sequence = self
+            .db
+            .generate_id()
+            .context("Failed to allocate decision sequence number")?;
+        let bytes = bincode::serialize(&decision).context("Failed to serialize decision")?;
+        // Big-endian keys keep iteration in recording order
+        self.tree()?
+            .insert(sequence.to_be_bytes(), bytes)
+            .context("Failed to store decision in Sled")?;
+        Ok(())
+    }
+}
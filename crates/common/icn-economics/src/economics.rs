@@ -1,11 +1,13 @@
 use crate::policy::ResourceAuthorizationPolicy;
+use crate::supply::{InMemorySupplyStore, SupplyStore};
 use icn_identity::Did;
 use icn_types::org::{CommunityId, CooperativeId};
 use icn_types::resource::ResourceType;
 use log::debug;
 use std::collections::HashMap;
+use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, MutexGuard, RwLock};
 
 #[derive(Debug, Error)]
 pub enum EconomicsError {
@@ -13,6 +15,16 @@ pub enum EconomicsError {
     Unauthorized,
     #[error("insufficient funds for transfer")]
     InsufficientFunds,
+    #[error("{0} cannot be minted")]
+    NotMintable(ResourceType),
+    #[error("minting {requested} would take the supply of {minted} past the cap of {cap}")]
+    SupplyCapExceeded {
+        cap: u64,
+        minted: u64,
+        requested: u64,
+    },
+    #[error("minted supply unavailable: {0}")]
+    SupplyStorage(String),
 }
 
 /// Represents a key for the resource ledger, combining DID, organization scope, and resource type
@@ -39,13 +51,156 @@ pub struct TransferArgs<'a> {
     pub amt: u64,
 }
 
+/// Bytes a federation quorum signs to authorize minting during the governance
+/// execution `execution_id`.
+pub fn mint_authorization_payload(execution_id: &str) -> Vec<u8> {
+    format!("icn-mint-authorization:{}", execution_id).into_bytes()
+}
+
 pub struct Economics {
     policy: ResourceAuthorizationPolicy,
+    /// Federation-wide ceiling on total minted tokens; `None` means unbounded
+    supply_cap: std::sync::RwLock<Option<u64>>,
+    /// Total minted so far, per resource type
+    supply_store: Arc<dyn SupplyStore>,
+    /// Held from a mint's cap check until it is committed or abandoned
+    mint_lock: Mutex<()>,
+}
+
+/// A mint that passed the supply cap check and has not been recorded yet.
+///
+/// Further mints wait until it is committed or dropped; dropping it records nothing.
+pub struct PendingMint<'a> {
+    economics: &'a Economics,
+    _guard: MutexGuard<'a, ()>,
+    rt: ResourceType,
+    amt: u64,
+    new_supply: u64,
+}
+
+impl PendingMint<'_> {
+    /// Record the mint: bump the minted supply, then credit `recipient` in `ledger`
+    pub async fn commit(
+        self,
+        recipient: &Did,
+        coop_id: Option<&CooperativeId>,
+        community_id: Option<&CommunityId>,
+        ledger: &RwLock<HashMap<LedgerKey, u64>>,
+    ) -> Result<(), EconomicsError> {
+        self.economics
+            .supply_store
+            .set_minted(self.rt, self.new_supply)
+            .await
+            .map_err(|e| EconomicsError::SupplyStorage(e.to_string()))?;
+
+        debug!(
+            "Minting {} tokens for {} (coop: {:?}, community: {:?})",
+            self.amt, recipient, coop_id, community_id
+        );
+        let mut l = ledger.write().await;
+        let key = LedgerKey {
+            did: recipient.to_string(),
+            coop_id: coop_id.map(|c| c.to_string()),
+            community_id: community_id.map(|c| c.to_string()),
+            resource_type: self.rt,
+        };
+
+        // Get the current usage and subtract the amount (minting reduces usage)
+        // In our token model, lower usage means more tokens
+        let current = l.entry(key.clone()).or_insert(0);
+
+        // Check for overflow - ensure usage doesn't go negative
+        if *current < self.amt {
+            *current = 0;
+        } else {
+            *current -= self.amt;
+        }
+
+        let token_max: u64 = 100; // Maximum token allowance
+        let available_tokens = token_max.saturating_sub(*current);
+        debug!(
+            "New token balance for {}: {} tokens (usage: {})",
+            recipient, available_tokens, *current
+        );
+        Ok(())
+    }
 }
 
 impl Economics {
     pub fn new(policy: ResourceAuthorizationPolicy) -> Self {
-        Self { policy }
+        Self {
+            policy,
+            supply_cap: std::sync::RwLock::new(None),
+            supply_store: Arc::new(InMemorySupplyStore::new()),
+            mint_lock: Mutex::new(()),
+        }
+    }
+
+    /// Keep the minted supply in `store`, e.g. a [`crate::SledSupplyStore`] so the
+    /// supply cap holds across restarts
+    pub fn with_supply_store(mut self, store: Arc<dyn SupplyStore>) -> Self {
+        self.supply_store = store;
+        self
+    }
+
+    /// Set the total token supply ceiling, typically from the federation's trust bundle
+    pub fn set_supply_cap(&self, cap: Option<u64>) {
+        *self.supply_cap.write().unwrap_or_else(|e| e.into_inner()) = cap;
+    }
+
+    /// The current total token supply ceiling
+    pub fn supply_cap(&self) -> Option<u64> {
+        *self.supply_cap.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Total of `rt` minted so far
+    pub async fn minted_supply(&self, rt: ResourceType) -> Result<u64, EconomicsError> {
+        self.supply_store
+            .minted(rt)
+            .await
+            .map_err(|e| EconomicsError::SupplyStorage(e.to_string()))
+    }
+
+    /// Check that `amt` of `rt` may be minted without passing the supply cap.
+    ///
+    /// Nothing is recorded until the returned [`PendingMint`] is committed, so a
+    /// caller can do its own part of the mint in between and drop it on failure.
+    pub async fn begin_mint(
+        &self,
+        rt: ResourceType,
+        amt: u64,
+    ) -> Result<PendingMint<'_>, EconomicsError> {
+        // Only token type can be minted
+        if rt != ResourceType::Token {
+            debug!("Attempted to mint non-token resource type: {:?}", rt);
+            return Err(EconomicsError::NotMintable(rt));
+        }
+
+        let guard = self.mint_lock.lock().await;
+        let minted = self.minted_supply(rt).await?;
+        let cap = self.supply_cap();
+        let new_supply = match (minted.checked_add(amt), cap) {
+            (Some(total), Some(cap)) if total <= cap => total,
+            (Some(total), None) => total,
+            _ => {
+                debug!(
+                    "Mint of {} {} refused: supply cap {:?} reached",
+                    amt, rt, cap
+                );
+                return Err(EconomicsError::SupplyCapExceeded {
+                    cap: cap.unwrap_or(u64::MAX),
+                    minted,
+                    requested: amt,
+                });
+            }
+        };
+        Ok(PendingMint {
+            economics: self,
+            _guard: guard,
+            rt,
+            amt,
+            new_supply,
+        })
     }
 
     pub fn authorize(
@@ -98,6 +253,11 @@ impl Economics {
 
     /// Mint tokens for a DID, which reduces their token usage (increases token allowance)
     /// Only works for Token resource type
+    /// Returns:
+    /// - 0 on success
+    /// - -3 on invalid resource type
+    /// - -4 if the mint would take total supply past the supply cap
+    /// - -5 if the minted supply could not be read or stored
     pub async fn mint(
         &self,
         recipient: &Did,
@@ -107,42 +267,23 @@ impl Economics {
         amt: u64,
         ledger: &RwLock<HashMap<LedgerKey, u64>>,
     ) -> i32 {
-        // Only token type can be minted
-        if rt != ResourceType::Token {
-            debug!("Attempted to mint non-token resource type: {:?}", rt);
-            return -3;
-        }
-
-        debug!(
-            "Minting {} tokens for {} (coop: {:?}, community: {:?})",
-            amt, recipient, coop_id, community_id
-        );
-        let mut l = ledger.write().await;
-        let key = LedgerKey {
-            did: recipient.to_string(),
-            coop_id: coop_id.map(|c| c.to_string()),
-            community_id: community_id.map(|c| c.to_string()),
-            resource_type: rt,
+        let minted = match self.begin_mint(rt, amt).await {
+            Ok(pending) => {
+                pending
+                    .commit(recipient, coop_id, community_id, ledger)
+                    .await
+            }
+            Err(e) => Err(e),
         };
-
-        // Get the current usage and subtract the amount (minting reduces usage)
-        // In our token model, lower usage means more tokens
-        let current = l.entry(key.clone()).or_insert(0);
-
-        // Check for overflow - ensure usage doesn't go negative
-        if *current < amt {
-            *current = 0;
-        } else {
-            *current -= amt;
+        match minted {
+            Ok(()) => 0,
+            Err(EconomicsError::NotMintable(_)) => -3,
+            Err(EconomicsError::SupplyCapExceeded { .. }) => -4,
+            Err(e) => {
+                debug!("Mint of {} tokens for {} failed: {}", amt, recipient, e);
+                -5
+            }
         }
-
-        let token_max: u64 = 100; // Maximum token allowance
-        let available_tokens = token_max.saturating_sub(*current);
-        debug!(
-            "New token balance for {}: {} tokens (usage: {})",
-            recipient, available_tokens, *current
-        );
-        0
     }
 
     /// Transfer tokens from one DID to another
@@ -1,13 +1,19 @@
 #![forbid(unsafe_code)]
 
+pub mod decision_audit;
 pub mod economics;
 pub mod mana;
 pub mod mana_metrics;
 pub mod policy;
 pub mod sled_mana_ledger;
+pub mod supply;
 pub mod types;
 
-pub use economics::Economics;
+pub use decision_audit::{
+    AuthorizationDecision, DecisionOutcome, DecisionSink, InMemoryDecisionSink, SledDecisionSink,
+};
+pub use economics::{mint_authorization_payload, Economics, PendingMint};
+pub use supply::{InMemorySupplyStore, SledSupplyStore, SupplyStore};
 pub use icn_types::resource::ResourceType;
 pub use policy::ResourceAuthorizationPolicy;
 // Using a different name for the import to avoid conflict
@@ -16,13 +22,14 @@ pub use economics::LedgerKey;
 
 // Use the canonical Did type from icn_identity
 use icn_identity::Did;
+use icn_types::clock::{Clock, SystemClock};
 // use icn_types::EconomicsError; // This direct import is fine, or can be removed if ResourceAuthorizationError is used exclusively
 
 // Mana-related types will be re-exported from the new mana.rs as needed.
 // For now, removing old re-exports if they conflict or are replaced by new design.
 // pub use mana::{ManaPool, ManaManager, ManaError}; // Comment out for now, to be re-evaluated
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use async_trait::async_trait;
 // use icn_identity_core::did::Did;
 // type Did = String; // DIDs are strings in the format did:key:...
@@ -52,6 +59,45 @@ pub struct ScopedResourceToken {
     pub issuer: Option<String>,
 }
 
+/// Several resource amounts granted together under one scope and expiry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResourceTokenBundle {
+    /// (resource_type, amount) pairs covered by the bundle
+    pub entries: Vec<(String, u64)>,
+
+    /// Scope shared by every entry
+    pub scope: String,
+
+    /// Optional expiration timestamp
+    pub expires_at: Option<u64>,
+
+    /// Optional issuer of the bundle
+    pub issuer: Option<String>,
+}
+
+impl ResourceTokenBundle {
+    /// Split the bundle into one token per resource type.
+    ///
+    /// Entries naming the same resource type are summed so that a quota is
+    /// checked against the bundle's total demand for that resource.
+    pub fn tokens(&self) -> Vec<ScopedResourceToken> {
+        let mut tokens: Vec<ScopedResourceToken> = Vec::new();
+        for (resource_type, amount) in &self.entries {
+            match tokens.iter_mut().find(|t| &t.resource_type == resource_type) {
+                Some(token) => token.amount = token.amount.saturating_add(*amount),
+                None => tokens.push(ScopedResourceToken {
+                    resource_type: resource_type.clone(),
+                    amount: *amount,
+                    scope: self.scope.clone(),
+                    expires_at: self.expires_at,
+                    issuer: self.issuer.clone(),
+                }),
+            }
+        }
+        tokens
+    }
+}
+
 /// Policy for authorizing resource usage
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ResourceAuthorization {
@@ -70,6 +116,18 @@ pub enum ResourceAuthorization {
         period_secs: u64,
     },
 
+    /// Enforce a quota that starts over every period (e.g. 100 jobs per day).
+    ///
+    /// Windows are aligned to multiples of `period_secs` since the Unix epoch,
+    /// so a daily quota resets at midnight UTC rather than a day after first use.
+    PeriodicQuota {
+        /// Maximum amount per window
+        amount: u64,
+
+        /// Window length in seconds
+        period_secs: u64,
+    },
+
     /// Allow only specific DIDs to access the resource
     PermitList(Vec<Did>),
 }
@@ -80,6 +138,10 @@ pub trait ResourceRepository: Send + Sync {
     /// Record resource usage
     async fn record_usage(&self, did: &Did, token: &ScopedResourceToken) -> Result<()>;
 
+    /// Record several tokens as one unit: either all of them are recorded or,
+    /// on error, none is
+    async fn record_usage_batch(&self, did: &Did, tokens: &[ScopedResourceToken]) -> Result<()>;
+
     /// Get total resource usage for a DID and resource type within a scope
     async fn get_usage(&self, did: &Did, resource_type: &str, scope: &str) -> Result<u64>;
 
@@ -98,6 +160,18 @@ pub trait ResourceRepository: Send + Sync {
 pub trait PolicyEnforcer: Send + Sync {
     /// Check if a resource usage is authorized
     async fn check_authorization(&self, did: &Did, token: &ScopedResourceToken) -> Result<bool, ResourceAuthorizationError>;
+
+    /// How much more of `resource_type` the DID may use in `scope` right now,
+    /// without recording anything.
+    ///
+    /// Returns `None` when the policy sets no limit and `Some(0)` once the
+    /// limit is exhausted.
+    async fn remaining_allowance(
+        &self,
+        did: &Did,
+        resource_type: &str,
+        scope: &str,
+    ) -> Result<Option<u64>, ResourceAuthorizationError>;
 }
 
 /// Resource policy enforcer implementation
@@ -107,17 +181,41 @@ pub struct ResourcePolicyEnforcer {
 
     /// Policies by resource type and scope
     policies: HashMap<(String, String), ResourceAuthorization>,
+
+    /// Time source for expiry and rate-limit windows
+    clock: Arc<dyn Clock>,
+
+    /// Optional audit log of every authorization decision; `None` disables auditing
+    decision_sink: Option<Arc<dyn DecisionSink>>,
+
+    /// Held while a bundle is checked and recorded, so concurrent bundles
+    /// cannot both pass a check that only one of them fits
+    bundle_lock: Mutex<()>,
 }
 
 impl ResourcePolicyEnforcer {
     /// Create a new policy enforcer with the specified repository
     pub fn new(repository: Box<dyn ResourceRepository>) -> Self {
+        Self::with_clock(repository, Arc::new(SystemClock))
+    }
+
+    /// Create a new policy enforcer that reads time from `clock`
+    pub fn with_clock(repository: Box<dyn ResourceRepository>, clock: Arc<dyn Clock>) -> Self {
         Self {
             repository,
             policies: HashMap::new(),
+            clock,
+            decision_sink: None,
+            bundle_lock: Mutex::new(()),
         }
     }
 
+    /// Record every authorization decision (allow or deny, with reason) to `sink`
+    pub fn with_decision_sink(mut self, sink: Arc<dyn DecisionSink>) -> Self {
+        self.decision_sink = Some(sink);
+        self
+    }
+
     /// Set a policy for a resource type within a scope
     pub fn set_policy(&mut self, resource_type: &str, scope: &str, policy: ResourceAuthorization) {
         self.policies
@@ -129,11 +227,136 @@ impl ResourcePolicyEnforcer {
         self.policies
             .get(&(resource_type.to_string(), scope.to_string()))
     }
+
+    /// Check every entry of a bundle, failing with the first denial
+    pub async fn check_authorization_bundle(
+        &self,
+        did: &Did,
+        bundle: &ResourceTokenBundle,
+    ) -> Result<bool, ResourceAuthorizationError> {
+        for token in bundle.tokens() {
+            self.check_authorization(did, &token).await?;
+        }
+        Ok(true)
+    }
+
+    /// Authorize and record a bundle as a unit.
+    ///
+    /// Every entry is checked before anything is written, and the entries are
+    /// then recorded in a single batch, so a denial or a failed write for one
+    /// resource leaves the usage of all the others untouched.
+    pub async fn record_usage_bundle(
+        &self,
+        did: &Did,
+        bundle: &ResourceTokenBundle,
+    ) -> Result<(), ResourceAuthorizationError> {
+        let _bundle = self.bundle_lock.lock().await;
+        let tokens = bundle.tokens();
+        for token in &tokens {
+            self.check_authorization(did, token).await?;
+        }
+        self.repository
+            .record_usage_batch(did, &tokens)
+            .await
+            .map_err(|e| ResourceAuthorizationError::SystemTimeError(format!("Failed to record usage: {}", e)))
+    }
 }
 
 #[async_trait]
 impl PolicyEnforcer for ResourcePolicyEnforcer {
     async fn check_authorization(&self, did: &Did, token: &ScopedResourceToken) -> Result<bool, ResourceAuthorizationError> {
+        let result = self.evaluate(did, token).await;
+        if let Some(sink) = &self.decision_sink {
+            let outcome = match &result {
+                Ok(_) => DecisionOutcome::Allowed,
+                Err(e) => DecisionOutcome::Denied { reason: e.to_string() },
+            };
+            let decision = AuthorizationDecision::new(did, token, outcome, self.clock.epoch());
+            if let Err(e) = sink.record(decision).await {
+                tracing::warn!(did = %did, error = %e, "Failed to record authorization decision");
+            }
+        }
+        result
+    }
+
+    async fn remaining_allowance(
+        &self,
+        did: &Did,
+        resource_type: &str,
+        scope: &str,
+    ) -> Result<Option<u64>, ResourceAuthorizationError> {
+        let policy = self.get_policy(resource_type, scope).ok_or_else(|| {
+            ResourceAuthorizationError::NoPolicyFound {
+                resource_type: resource_type.to_string(),
+                scope: scope.to_string(),
+            }
+        })?;
+
+        let (limit, usage) = match policy {
+            ResourceAuthorization::AllowAll => return Ok(None),
+            ResourceAuthorization::PermitList(permits) => {
+                return Ok(if permits.contains(did) { None } else { Some(0) });
+            }
+            ResourceAuthorization::Quota(quota) => {
+                let usage = self
+                    .repository
+                    .get_usage(did, resource_type, scope)
+                    .await
+                    .map_err(|e| ResourceAuthorizationError::SystemTimeError(format!("Failed to get usage: {}", e)))?;
+                (*quota, usage)
+            }
+            ResourceAuthorization::RateLimit {
+                amount,
+                period_secs,
+            } => {
+                let since = self.clock.epoch().saturating_sub(*period_secs);
+                (
+                    *amount,
+                    self.usage_since(did, resource_type, scope, since).await?,
+                )
+            }
+            ResourceAuthorization::PeriodicQuota {
+                amount,
+                period_secs,
+            } => {
+                let (window_start, _) = self.periodic_window(*period_secs);
+                (
+                    *amount,
+                    self.usage_since(did, resource_type, scope, window_start)
+                        .await?,
+                )
+            }
+        };
+        Ok(Some(limit.saturating_sub(usage)))
+    }
+}
+
+impl ResourcePolicyEnforcer {
+    /// Total usage recorded at or after `since`
+    async fn usage_since(
+        &self,
+        did: &Did,
+        resource_type: &str,
+        scope: &str,
+        since: u64,
+    ) -> Result<u64, ResourceAuthorizationError> {
+        let usage_history = self
+            .repository
+            .get_usage_history(did, resource_type, scope, since)
+            .await
+            .map_err(|e| ResourceAuthorizationError::SystemTimeError(format!("Failed to get usage history: {}", e)))?;
+        Ok(usage_history.iter().map(|(_, amount)| amount).sum())
+    }
+
+    /// Start and end of the `PeriodicQuota` window containing the current time
+    fn periodic_window(&self, period_secs: u64) -> (u64, u64) {
+        let now = self.clock.epoch();
+        let window_start = now - now % period_secs.max(1);
+        (window_start, window_start.saturating_add(period_secs))
+    }
+
+    /// Apply the configured policy to `token` without auditing the outcome
+    async fn evaluate(&self, did: &Did, token: &ScopedResourceToken) -> Result<bool, ResourceAuthorizationError> {
         // Get the policy for this resource and scope
         let policy = self
             .get_policy(&token.resource_type, &token.scope)
@@ -146,10 +369,7 @@ impl PolicyEnforcer for ResourcePolicyEnforcer {
 
         // Check if the token is expired
         if let Some(expires_at) = token.expires_at {
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map_err(|e| ResourceAuthorizationError::SystemTimeError(e.to_string()))?
-                .as_secs();
+            let now = self.clock.epoch();
 
             if now > expires_at {
                 return Err(ResourceAuthorizationError::TokenExpired {
@@ -194,20 +414,13 @@ impl PolicyEnforcer for ResourcePolicyEnforcer {
                 period_secs,
             } => {
                 // Check usage within the time period
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .map_err(|e| ResourceAuthorizationError::SystemTimeError(e.to_string()))?
-                    .as_secs();
+                let now = self.clock.epoch();
 
                 let since = now.saturating_sub(*period_secs);
 
-                let usage_history = self
-                    .repository
-                    .get_usage_history(did, &token.resource_type, &token.scope, since)
-                    .await
-                    .map_err(|e| ResourceAuthorizationError::SystemTimeError(format!("Failed to get usage history: {}", e)))?; // Similar to get_usage
-
-                let total_usage: u64 = usage_history.iter().map(|(_, amount)| amount).sum();
+                let total_usage = self
+                    .usage_since(did, &token.resource_type, &token.scope, since)
+                    .await?;
 
                 if total_usage + token.amount <= *amount {
                     Ok(true)
@@ -223,6 +436,32 @@ impl PolicyEnforcer for ResourcePolicyEnforcer {
                 }
             }
 
+            ResourceAuthorization::PeriodicQuota {
+                amount,
+                period_secs,
+            } => {
+                // Only usage since the start of the current window counts
+                let (window_start, resets_at) = self.periodic_window(*period_secs);
+
+                let usage_in_window = self
+                    .usage_since(did, &token.resource_type, &token.scope, window_start)
+                    .await?;
+
+                if usage_in_window + token.amount <= *amount {
+                    Ok(true)
+                } else {
+                    Err(ResourceAuthorizationError::PeriodicQuotaExceeded {
+                        quota: *amount,
+                        period_seconds: *period_secs,
+                        current_usage_in_window: usage_in_window,
+                        requested_amount: token.amount,
+                        resets_at,
+                        resource_type: token.resource_type.clone(),
+                        scope: token.scope.clone(),
+                    })
+                }
+            }
+
             ResourceAuthorization::PermitList(permits) => {
                 // Check if DID is in the permit list
                 if permits.contains(did) {
@@ -240,20 +479,41 @@ impl PolicyEnforcer for ResourcePolicyEnforcer {
 }
 
 /// In-memory implementation of the ResourceRepository trait for testing
-#[derive(Debug, Default)]
 pub struct InMemoryResourceRepository {
     /// Usage records (did, resource_type, scope) -> [(timestamp, amount)]
     usage: Mutex<HashMap<UsageKey, UsageData>>,
+    /// Time source used to stamp usage records
+    clock: Arc<dyn Clock>,
 }
 
 impl InMemoryResourceRepository {
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Create a repository that stamps usage with time read from `clock`
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
             usage: Mutex::new(HashMap::new()),
+            clock,
         }
     }
 }
 
+impl Default for InMemoryResourceRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for InMemoryResourceRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryResourceRepository")
+            .field("usage", &self.usage)
+            .finish_non_exhaustive()
+    }
+}
+
 #[async_trait]
 impl ResourceRepository for InMemoryResourceRepository {
     async fn record_usage(&self, did: &Did, token: &ScopedResourceToken) -> Result<()> {
@@ -262,10 +522,7 @@ impl ResourceRepository for InMemoryResourceRepository {
             token.resource_type.clone(),
             token.scope.clone(),
         );
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|e| anyhow!("Error getting current time: {}", e))?
-            .as_secs();
+        let now = self.clock.epoch();
 
         let mut usage_guard = self.usage.lock().await;
         usage_guard
@@ -275,6 +532,23 @@ impl ResourceRepository for InMemoryResourceRepository {
         Ok(())
     }
 
+    async fn record_usage_batch(&self, did: &Did, tokens: &[ScopedResourceToken]) -> Result<()> {
+        let now = self.clock.epoch();
+        let mut usage_guard = self.usage.lock().await;
+        for token in tokens {
+            let key = (
+                did.to_string(),
+                token.resource_type.clone(),
+                token.scope.clone(),
+            );
+            usage_guard
+                .entry(key)
+                .or_default()
+                .push((now, token.amount));
+        }
+        Ok(())
+    }
+
     async fn get_usage(&self, did: &Did, resource_type: &str, scope: &str) -> Result<u64> {
         let key = (
             did.to_string(),
@@ -316,7 +590,15 @@ pub use sled_mana_ledger::SledManaLedger;
 use crate::mana::{ManaLedger, ManaState};
 use std::sync::Arc;
 
+/// Scope whose mana is always the DID's global balance
+pub const GLOBAL_MANA_SCOPE: &str = "global";
+
 /// Adapts a ManaLedger to the ResourceRepository trait for "mana" tokens.
+///
+/// A token's scope draws on the DID's budget in that scope once one has been
+/// stored with `ManaLedger::update_scoped_state`. Scopes without a budget, an
+/// empty scope and `GLOBAL_MANA_SCOPE` all draw on the global balance, as
+/// every scope did before budgets existed.
 pub struct ManaRepositoryAdapter<L: ManaLedger> {
     ledger: Arc<L>,
 }
@@ -327,6 +609,22 @@ impl<L: ManaLedger> ManaRepositoryAdapter<L> {
     pub fn new(ledger: Arc<L>) -> Self {
         Self { ledger }
     }
+
+    /// The ledger this adapter records usage against
+    pub fn ledger(&self) -> &Arc<L> {
+        &self.ledger
+    }
+
+    /// `did`'s own budget in `scope`, or `None` when `scope` draws on its
+    /// global balance
+    async fn scoped_budget(&self, did: &Did, scope: &str) -> Result<Option<ManaState>> {
+        if scope.is_empty() || scope == GLOBAL_MANA_SCOPE {
+            return Ok(None);
+        }
+        self.ledger.get_scoped_state(did, scope).await.map_err(|e| {
+            anyhow::anyhow!("Failed to get mana state for DID {} in scope {}: {}", did, scope, e)
+        })
+    }
 }
 
 #[async_trait::async_trait] // Ensure async_trait is available
@@ -339,54 +637,67 @@ impl<L: ManaLedger + Send + Sync + 'static> ResourceRepository for ManaRepositor
             ));
         }
 
-        // Ensure scope matches if relevant, for now, we assume "mana" is global for the DID
-        // or the scope in ScopedResourceToken isn't strictly enforced by ManaState itself.
-
-        let maybe_state = self.ledger.get_mana_state(did).await.map_err(|e| {
-            anyhow::anyhow!("Failed to get mana state for DID {}: {}", did, e)
-        })?;
+        // Spend from the scope's own budget when it has one. Either debit is a
+        // single ledger step, so concurrent spends cannot overwrite each other.
+        let outcome = if self.scoped_budget(did, &token.scope).await?.is_some() {
+            self.ledger
+                .debit_scoped(did, &token.scope, token.amount)
+                .await
+        } else {
+            self.ledger
+                .batch_deduct(&[(did.clone(), token.amount)])
+                .await
+                .map(|mut results| results.pop().map_or(Ok(()), |(_, outcome)| outcome))
+        };
+        outcome
+            .map_err(|e| anyhow::anyhow!("Failed to update mana state for DID {}: {}", did, e))?
+            .map_err(|e| {
+                anyhow::Error::new(e).context(format!("Mana debit failed for DID {}", did))
+            })
+    }
 
-        let mut state = maybe_state.unwrap_or_else(|| {
-            // Default state if DID has no mana record yet.
-            // Max mana and regen rate would ideally come from a default policy or config.
-            // For now, if no record, they can't spend unless these are non-zero.
-            ManaState {
-                current_mana: 0,
-                max_mana: 0, // Or a default capacity if applicable
-                regen_rate_per_epoch: 0.0,
-                last_updated_epoch: 0, // Or current epoch if relevant
+    /// Debits the batch's total in one step. The tokens must all be mana
+    /// drawn from the same scope, as a single debit cannot span several budgets.
+    async fn record_usage_batch(&self, did: &Did, tokens: &[ScopedResourceToken]) -> Result<()> {
+        let Some(first) = tokens.first() else {
+            return Ok(());
+        };
+        let mut total = ScopedResourceToken {
+            amount: 0,
+            ..first.clone()
+        };
+        for token in tokens {
+            if token.resource_type != "mana" {
+                return Err(anyhow::anyhow!(
+                    "ManaRepositoryAdapter: unsupported resource type '{}', expected 'mana'",
+                    token.resource_type
+                ));
             }
-        });
-
-        if state.current_mana < token.amount {
-            return Err(anyhow::anyhow!(
-                "Insufficient mana for DID {}: has {}, needs {}",
-                did,
-                state.current_mana,
-                token.amount
-            ));
+            if token.scope != total.scope {
+                return Err(anyhow::anyhow!(
+                    "ManaRepositoryAdapter: batch spans scopes '{}' and '{}'",
+                    total.scope,
+                    token.scope
+                ));
+            }
+            total.amount = total.amount.saturating_add(token.amount);
         }
-
-        state.current_mana -= token.amount;
-        self.ledger
-            .update_mana_state(did, state)
-            .await
-            .map_err(|e| {
-                anyhow::anyhow!("Failed to update mana state for DID {}: {}", did, e)
-            })?;
-        Ok(())
+        self.record_usage(did, &total).await
     }
 
-    async fn get_usage(&self, did: &Did, resource_type: &str, _scope: &str) -> Result<u64> {
+    async fn get_usage(&self, did: &Did, resource_type: &str, scope: &str) -> Result<u64> {
         if resource_type != "mana" {
             return Err(anyhow::anyhow!(
                 "ManaRepositoryAdapter: unsupported resource type '{}', expected 'mana'",
                 resource_type
             ));
         }
-        let state = self.ledger.get_mana_state(did).await.map_err(|e| {
-            anyhow::anyhow!("Failed to get mana state for DID {}: {}", did, e)
-        })?;
+        let state = match self.scoped_budget(did, scope).await? {
+            Some(state) => Some(state),
+            None => self.ledger.get_mana_state(did).await.map_err(|e| {
+                anyhow::anyhow!("Failed to get mana state for DID {}: {}", did, e)
+            })?,
+        };
         Ok(state.map(|s| s.current_mana).unwrap_or(0))
     }
 
@@ -416,6 +727,7 @@ impl<L: ManaLedger + Send + Sync + 'static> ResourceRepository for ManaRepositor
 mod tests {
     use super::*;
     use crate::mana::InMemoryManaLedger;
+    use icn_types::clock::MockClock;
     use std::str::FromStr; // For Did::from_str
     use icn_identity::KeyPair; // For the test_did() helper function
 
@@ -495,8 +807,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_rate_limit_policy() {
-        let repo = Box::new(InMemoryResourceRepository::default());
-        let mut enforcer = ResourcePolicyEnforcer::new(repo);
+        // Usage is stamped by the same clock the enforcer reads
+        let clock = MockClock::at_epoch(1_000);
+        let repo = Box::new(InMemoryResourceRepository::with_clock(Arc::new(
+            clock.clone(),
+        )));
+        let mut enforcer = ResourcePolicyEnforcer::with_clock(repo, Arc::new(clock.clone()));
         enforcer.set_policy(
             "api_calls",
             "user_group_a",
@@ -535,8 +851,121 @@ mod tests {
             _ => panic!("Expected RateLimitExceeded error"),
         }
 
-        // If we could advance time by 60s here, the limit would reset.
-        // For simplicity, this test only checks immediate rate limiting.
+        // Still limited at the very end of the period
+        clock.advance_secs(60);
+        assert!(matches!(
+            enforcer.check_authorization(&did, &create_token()).await,
+            Err(ResourceAuthorizationError::RateLimitExceeded { .. })
+        ));
+
+        // One second later the earlier usage has left the window
+        clock.advance_secs(1);
+        let token5 = create_token();
+        assert!(enforcer.check_authorization(&did, &token5).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_periodic_quota_resets_each_window() {
+        // Two hours into a day, with usage stamped by the same clock
+        let clock = MockClock::at_epoch(86_400 * 100 + 7_200);
+        let repo = Box::new(InMemoryResourceRepository::with_clock(Arc::new(
+            clock.clone(),
+        )));
+        let mut enforcer = ResourcePolicyEnforcer::with_clock(repo, Arc::new(clock.clone()));
+        enforcer.set_policy(
+            "jobs",
+            "coop_a",
+            ResourceAuthorization::PeriodicQuota {
+                amount: 2,
+                period_secs: 86_400,
+            },
+        );
+
+        let did = test_did();
+        let token = ScopedResourceToken {
+            resource_type: "jobs".to_string(),
+            amount: 1,
+            scope: "coop_a".to_string(),
+            expires_at: None,
+            issuer: None,
+        };
+
+        for _ in 0..2 {
+            assert!(enforcer.check_authorization(&did, &token).await.unwrap());
+            enforcer
+                .repository
+                .record_usage(&did, &token)
+                .await
+                .unwrap();
+        }
+
+        // Late in the same day the quota is still used up
+        clock.advance_secs(20 * 3_600);
+        match enforcer.check_authorization(&did, &token).await {
+            Err(ResourceAuthorizationError::PeriodicQuotaExceeded {
+                current_usage_in_window,
+                resets_at,
+                ..
+            }) => {
+                assert_eq!(current_usage_in_window, 2);
+                assert_eq!(resets_at, 86_400 * 101);
+            }
+            other => panic!("Expected PeriodicQuotaExceeded error, got {:?}", other),
+        }
+
+        // Once the next day starts, yesterday's usage no longer counts
+        clock.advance_secs(2 * 3_600);
+        assert!(enforcer.check_authorization(&did, &token).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_remaining_allowance_after_partial_usage() {
+        let clock = MockClock::at_epoch(1_000);
+        let repo = Box::new(InMemoryResourceRepository::with_clock(Arc::new(
+            clock.clone(),
+        )));
+        let mut enforcer = ResourcePolicyEnforcer::with_clock(repo, Arc::new(clock.clone()));
+        enforcer.set_policy("storage", "coop", ResourceAuthorization::Quota(100));
+        enforcer.set_policy(
+            "api_calls",
+            "coop",
+            ResourceAuthorization::RateLimit {
+                amount: 10,
+                period_secs: 60,
+            },
+        );
+        enforcer.set_policy("compute", "coop", ResourceAuthorization::AllowAll);
+
+        let did = test_did();
+        let use_resource = |resource_type: &str, amount| ScopedResourceToken {
+            resource_type: resource_type.to_string(),
+            amount,
+            scope: "coop".to_string(),
+            expires_at: None,
+            issuer: None,
+        };
+        let repo = &enforcer.repository;
+        repo.record_usage(&did, &use_resource("storage", 70)).await.unwrap();
+        repo.record_usage(&did, &use_resource("api_calls", 4)).await.unwrap();
+        clock.advance_secs(30);
+        repo.record_usage(&did, &use_resource("api_calls", 3)).await.unwrap();
+
+        let remaining =
+            |resource_type: &'static str| enforcer.remaining_allowance(&did, resource_type, "coop");
+        assert_eq!(remaining("storage").await.unwrap(), Some(30));
+        assert_eq!(remaining("api_calls").await.unwrap(), Some(3));
+        assert_eq!(remaining("compute").await.unwrap(), None);
+
+        // The first calls leave the rate-limit window; the quota never resets
+        clock.advance_secs(31);
+        assert_eq!(remaining("api_calls").await.unwrap(), Some(7));
+        repo.record_usage(&did, &use_resource("storage", 30)).await.unwrap();
+        assert_eq!(remaining("storage").await.unwrap(), Some(0));
+
+        assert!(matches!(
+            enforcer.remaining_allowance(&did, "storage", "other").await,
+            Err(ResourceAuthorizationError::NoPolicyFound { .. })
+        ));
     }
 
     #[tokio::test]
@@ -571,25 +1000,139 @@ mod tests {
         }
     }
 
+    fn feature_token() -> ScopedResourceToken {
+        ScopedResourceToken {
+            resource_type: "special_feature".to_string(),
+            amount: 1,
+            scope: "beta_users".to_string(),
+            expires_at: None,
+            issuer: None,
+        }
+    }
+
     #[tokio::test]
-    async fn test_expired_token() {
+    async fn test_decision_sink_records_allow_and_deny() {
+        let repo = Box::new(InMemoryResourceRepository::default());
+        let sink = Arc::new(InMemoryDecisionSink::new());
+        let allowed = test_did();
+        let denied = test_did();
+        let mut enforcer =
+            ResourcePolicyEnforcer::with_clock(repo, Arc::new(MockClock::at_epoch(1_000)))
+                .with_decision_sink(sink.clone());
+        enforcer.set_policy(
+            "special_feature",
+            "beta_users",
+            ResourceAuthorization::PermitList(vec![allowed.clone()]),
+        );
+
+        let token = feature_token();
+        assert!(enforcer.check_authorization(&allowed, &token).await.unwrap());
+        assert!(enforcer.check_authorization(&denied, &token).await.is_err());
+
+        let decisions = sink.decisions().await;
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].did, allowed);
+        assert_eq!(decisions[0].outcome, DecisionOutcome::Allowed);
+        assert_eq!(decisions[0].timestamp, 1_000);
+        assert_eq!(decisions[0].resource_type, "special_feature");
+
+        let denials = sink.denials_for(&denied).await;
+        assert_eq!(denials.len(), 1);
+        match &denials[0].outcome {
+            DecisionOutcome::Denied { reason } => assert!(reason.contains("Access denied")),
+            other => panic!("Expected denial, got {:?}", other),
+        }
+        assert!(sink.denials_for(&allowed).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sled_decision_sink_persists_decisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let did = test_did();
+        {
+            let sink = Arc::new(SledDecisionSink::open(dir.path()).unwrap());
+            let mut enforcer = ResourcePolicyEnforcer::new(Box::new(
+                InMemoryResourceRepository::default(),
+            ))
+            .with_decision_sink(sink);
+            enforcer.set_policy("special_feature", "beta_users", ResourceAuthorization::Quota(0));
+            assert!(enforcer.check_authorization(&did, &feature_token()).await.is_err());
+        }
+
+        let decisions = SledDecisionSink::open(dir.path()).unwrap().decisions().unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].did, did);
+        assert!(decisions[0].is_denied());
+    }
+
+    fn bundle(entries: &[(&str, u64)]) -> ResourceTokenBundle {
+        ResourceTokenBundle {
+            entries: entries.iter().map(|(r, a)| (r.to_string(), *a)).collect(),
+            scope: "project_x".to_string(),
+            expires_at: None,
+            issuer: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bundle_records_all_entries() {
         let repo = Box::new(InMemoryResourceRepository::default());
         let mut enforcer = ResourcePolicyEnforcer::new(repo);
-        enforcer.set_policy("compute", "global", ResourceAuthorization::AllowAll);
+        enforcer.set_policy("cpu", "project_x", ResourceAuthorization::Quota(100));
+        enforcer.set_policy("storage", "project_x", ResourceAuthorization::AllowAll);
+
+        let did = test_did();
+        let bundle = bundle(&[("cpu", 40), ("storage", 500), ("cpu", 20)]);
+        assert!(enforcer.check_authorization_bundle(&did, &bundle).await.unwrap());
+        enforcer.record_usage_bundle(&did, &bundle).await.unwrap();
+
+        let repo = &enforcer.repository;
+        assert_eq!(repo.get_usage(&did, "cpu", "project_x").await.unwrap(), 60);
+        assert_eq!(repo.get_usage(&did, "storage", "project_x").await.unwrap(), 500);
+    }
 
-        let past_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-            - 3600; // 1 hour ago
+    #[tokio::test]
+    async fn test_bundle_denial_records_nothing() {
+        let repo = Box::new(InMemoryResourceRepository::default());
+        let mut enforcer = ResourcePolicyEnforcer::new(repo);
+        enforcer.set_policy("cpu", "project_x", ResourceAuthorization::AllowAll);
+        enforcer.set_policy("storage", "project_x", ResourceAuthorization::Quota(100));
+
+        let did = test_did();
+        // Each storage entry fits the quota alone, but not together.
+        let bundle = bundle(&[("cpu", 10), ("storage", 60), ("storage", 60)]);
+        let result = enforcer.record_usage_bundle(&did, &bundle).await;
+        match result {
+            Err(ResourceAuthorizationError::QuotaExceeded { requested_amount, .. }) => {
+                assert_eq!(requested_amount, 120)
+            }
+            other => panic!("Expected QuotaExceeded error, got {:?}", other),
+        }
+
+        let repo = &enforcer.repository;
+        assert_eq!(repo.get_usage(&did, "cpu", "project_x").await.unwrap(), 0);
+        assert_eq!(repo.get_usage(&did, "storage", "project_x").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_expired_token() {
+        let repo = Box::new(InMemoryResourceRepository::default());
+        let clock = MockClock::at_epoch(10_000);
+        let mut enforcer = ResourcePolicyEnforcer::with_clock(repo, Arc::new(clock.clone()));
+        enforcer.set_policy("compute", "global", ResourceAuthorization::AllowAll);
 
         let token = ScopedResourceToken {
             resource_type: "compute".to_string(),
             amount: 100,
             scope: "global".to_string(),
-            expires_at: Some(past_timestamp),
+            expires_at: Some(13_600),
             issuer: None,
         };
+        // Valid up to and including its expiry second
+        clock.advance_secs(3_600);
+        assert!(enforcer.check_authorization(&test_did(), &token).await.unwrap());
+
+        clock.advance_secs(1);
         let result = enforcer.check_authorization(&test_did(), &token).await;
         assert!(result.is_err());
         match result.err().unwrap() {
@@ -598,6 +1141,82 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_mana_repository_adapter_keeps_scoped_budgets_apart() {
+        let ledger = Arc::new(InMemoryManaLedger::new());
+        let adapter = ManaRepositoryAdapter::new(ledger.clone());
+        let did = test_did();
+        let state = |current_mana| ManaState {
+            current_mana,
+            max_mana: 100,
+            regen_rate_per_epoch: 0.0,
+            last_updated_epoch: 0,
+        };
+        ledger.update_mana_state(&did, state(100)).await.unwrap();
+        ledger.update_scoped_state(&did, "coop-a", state(50)).await.unwrap();
+        ledger.update_scoped_state(&did, "coop-b", state(50)).await.unwrap();
+
+        let spend = |scope: &str, amount| ScopedResourceToken {
+            resource_type: "mana".to_string(),
+            amount,
+            scope: scope.to_string(),
+            expires_at: None,
+            issuer: None,
+        };
+        adapter.record_usage(&did, &spend("coop-a", 30)).await.unwrap();
+
+        assert_eq!(adapter.get_usage(&did, "mana", "coop-a").await.unwrap(), 20);
+        assert_eq!(adapter.get_usage(&did, "mana", "coop-b").await.unwrap(), 50);
+        assert_eq!(adapter.get_usage(&did, "mana", GLOBAL_MANA_SCOPE).await.unwrap(), 100);
+
+        // A scope's budget caps spending there even if the global balance could cover it
+        let err = adapter.record_usage(&did, &spend("coop-a", 25)).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("Insufficient mana"), "{:#}", err);
+
+        // Scopes without a budget of their own still spend the global balance
+        adapter.record_usage(&did, &spend("", 10)).await.unwrap();
+        adapter.record_usage(&did, &spend("coop-c", 15)).await.unwrap();
+        assert_eq!(adapter.get_usage(&did, "mana", GLOBAL_MANA_SCOPE).await.unwrap(), 75);
+        assert_eq!(adapter.get_usage(&did, "mana", "coop-b").await.unwrap(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_mana_repository_adapter_batch_is_all_or_nothing() {
+        let ledger = Arc::new(InMemoryManaLedger::new());
+        let adapter = ManaRepositoryAdapter::new(ledger.clone());
+        let did = test_did();
+        let state = ManaState {
+            current_mana: 100,
+            max_mana: 100,
+            regen_rate_per_epoch: 0.0,
+            last_updated_epoch: 0,
+        };
+        ledger.update_mana_state(&did, state).await.unwrap();
+
+        let spend = |resource_type: &str, amount| ScopedResourceToken {
+            resource_type: resource_type.to_string(),
+            amount,
+            scope: GLOBAL_MANA_SCOPE.to_string(),
+            expires_at: None,
+            issuer: None,
+        };
+        assert!(adapter
+            .record_usage_batch(&did, &[spend("mana", 30), spend("cpu", 5)])
+            .await
+            .is_err());
+        assert!(adapter
+            .record_usage_batch(&did, &[spend("mana", 60), spend("mana", 60)])
+            .await
+            .is_err());
+        assert_eq!(adapter.get_usage(&did, "mana", GLOBAL_MANA_SCOPE).await.unwrap(), 100);
+
+        adapter
+            .record_usage_batch(&did, &[spend("mana", 30), spend("mana", 20)])
+            .await
+            .unwrap();
+        assert_eq!(adapter.get_usage(&did, "mana", GLOBAL_MANA_SCOPE).await.unwrap(), 50);
+    }
+
     #[tokio::test]
     async fn test_mana_repository_adapter_records_usage() {
         let ledger = Arc::new(InMemoryManaLedger::new());
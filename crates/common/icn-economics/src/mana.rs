@@ -3,7 +3,9 @@ use anyhow::Result;
 use async_trait::async_trait;
 use icn_identity::Did;
 use icn_identity::ScopeKey;
-pub use icn_types::mana::ManaState;
+use icn_types::clock::{Clock, SystemClock};
+pub use icn_types::mana::{ManaError, ManaState};
+use icn_types::resource::ResourceType;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -18,12 +20,6 @@ pub trait ManaMetricsHook: std::fmt::Debug {
     // Optional: Add fn remove_balance(&self, scope: &ScopeKey); if pools can be deleted.
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum ManaError {
-    #[error("Insufficient mana: requested {requested}, available {available}")]
-    InsufficientMana { requested: u64, available: u64 },
-}
-
 #[derive(Debug, Clone)]
 pub struct ManaPool {
     /// Current available mana units
@@ -231,18 +227,156 @@ impl Default for ManaManager {
 }
 
 // --- ManaLedger Trait ---
+/// Mana balances per DID.
+///
+/// Every DID has a default pool, read and written by `get_mana_state` and
+/// `update_mana_state`. A DID may additionally hold separate pools for
+/// specific resource types (e.g. compute vs storage mana) and budgets for
+/// specific scopes (e.g. one per cooperative), which are independent of the
+/// default pool and of each other.
 #[async_trait]
 pub trait ManaLedger: Send + Sync {
     async fn get_mana_state(&self, did: &Did) -> Result<Option<ManaState>>;
     async fn update_mana_state(&self, did: &Did, new_state: ManaState) -> Result<()>;
     async fn all_dids(&self) -> Result<Vec<Did>>;
+
+    /// State of `did`'s pool for `resource`
+    async fn get_pool_state(&self, did: &Did, resource: ResourceType) -> Result<Option<ManaState>>;
+
+    /// Store `did`'s pool for `resource`, creating it if needed
+    async fn update_pool_state(
+        &self,
+        did: &Did,
+        resource: ResourceType,
+        new_state: ManaState,
+    ) -> Result<()>;
+
+    /// Every resource-specific pool held in the ledger
+    async fn all_pools(&self) -> Result<Vec<(Did, ResourceType)>>;
+
+    /// `did`'s budget in `scope`, if one has been stored
+    async fn get_scoped_state(&self, did: &Did, scope: &str) -> Result<Option<ManaState>>;
+
+    /// Store `did`'s budget in `scope`, creating it if needed
+    async fn update_scoped_state(&self, did: &Did, scope: &str, new_state: ManaState) -> Result<()>;
+
+    /// Every per-scope budget held in the ledger
+    async fn all_scoped(&self) -> Result<Vec<(Did, String)>>;
+
+    /// Debit `amount` from `did`'s budget in `scope` in a single step, so no
+    /// other write to that budget can interleave with it.
+    ///
+    /// A budget that cannot cover the debit, or does not exist, is reported
+    /// with `ManaError::InsufficientMana` and left untouched. The outer error
+    /// is reserved for storage failures.
+    async fn debit_scoped(
+        &self,
+        did: &Did,
+        scope: &str,
+        amount: u64,
+    ) -> Result<Result<(), ManaError>>;
+
+    /// Debit each `(did, amount)` in order, reporting the outcome per DID.
+    ///
+    /// A DID that cannot cover its debit, or has no mana record, is reported
+    /// with `ManaError::InsufficientMana` and left untouched while the other
+    /// debits still apply. The outer error is reserved for storage failures.
+    ///
+    /// The default implementation reads and writes each DID in turn; ledgers
+    /// override it to apply a whole batch at once.
+    async fn batch_deduct(
+        &self,
+        deductions: &[(Did, u64)],
+    ) -> Result<Vec<(Did, Result<(), ManaError>)>> {
+        let mut results = Vec::with_capacity(deductions.len());
+        for (did, amount) in deductions {
+            let outcome = debit_record(self.get_mana_state(did).await?, *amount);
+            if let Ok(state) = &outcome {
+                self.update_mana_state(did, state.clone()).await?;
+            }
+            results.push((did.clone(), outcome.map(|_| ())));
+        }
+        Ok(results)
+    }
+}
+
+/// Debit `amount` from a possibly missing mana record, which has nothing to spend
+pub(crate) fn debit_record(state: Option<ManaState>, amount: u64) -> Result<ManaState, ManaError> {
+    let mut state = state.ok_or(ManaError::InsufficientMana {
+        requested: amount,
+        available: 0,
+    })?;
+    state.debit(amount)?;
+    Ok(state)
 }
 
 // --- RegenerationPolicy Enum ---
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RegenerationPolicy {
     FixedRatePerTick(u64), // Fixed mana regenerated each tick
-                           // Future policies could include: PercentageOfMax, ReputationScaled, etc.
+    /// Regenerate `max_mana * fraction_per_tick` each tick
+    ProportionalToMax {
+        fraction_per_tick: f64,
+    },
+    /// Regenerate `rate_per_second` for every second elapsed since the state's
+    /// `last_updated_epoch`
+    ElapsedTime {
+        rate_per_second: f64,
+    },
+    // Future policies could include: ReputationScaled, etc.
+}
+
+impl RegenerationPolicy {
+    /// Credit what `state` accrues at `epoch` and stamp it with `epoch`.
+    /// Returns whether its balance changed.
+    ///
+    /// `ElapsedTime` states are stamped on every tick, even at `max_mana`, so
+    /// time spent full never counts towards the next refill. The per-tick
+    /// policies ignore the epoch and only stamp states they credit.
+    pub fn apply(&self, state: &mut ManaState, epoch: u64) -> bool {
+        let original_mana = state.current_mana;
+        // Saturates at max_mana, so a full balance stays untouched
+        state.credit(self.accrued(state, epoch));
+        let regenerated = state.current_mana != original_mana;
+        if regenerated || matches!(self, RegenerationPolicy::ElapsedTime { .. }) {
+            state.last_updated_epoch = epoch;
+        }
+        regenerated
+    }
+
+    /// Balance `state` reports at `epoch`, without storing anything.
+    ///
+    /// Only `ElapsedTime` accrues between ticks; the per-tick policies credit
+    /// nothing until the next tick runs.
+    pub fn projected_balance(&self, state: &ManaState, epoch: u64) -> u64 {
+        match self {
+            RegenerationPolicy::ElapsedTime { .. } => {
+                let mut projected = state.clone();
+                projected.credit(self.accrued(state, epoch));
+                projected.current_mana
+            }
+            _ => state.current_mana,
+        }
+    }
+
+    /// Whole mana `state` accrues under this policy in a tick at `epoch`,
+    /// before the `max_mana` cap is applied.
+    ///
+    /// Negative or NaN rates accrue nothing. `ElapsedTime` drops the fraction
+    /// of a mana left over, so its rate should credit whole mana per tick.
+    pub fn accrued(&self, state: &ManaState, epoch: u64) -> u64 {
+        // Float-to-int casts saturate, and map negative or NaN values to 0
+        match *self {
+            RegenerationPolicy::FixedRatePerTick(amount) => amount,
+            RegenerationPolicy::ProportionalToMax { fraction_per_tick } => {
+                (state.max_mana as f64 * fraction_per_tick) as u64
+            }
+            RegenerationPolicy::ElapsedTime { rate_per_second } => {
+                let elapsed = epoch.saturating_sub(state.last_updated_epoch);
+                (rate_per_second * elapsed as f64) as u64
+            }
+        }
+    }
 }
 
 // --- ManaRegenerator Struct ---
@@ -251,22 +385,77 @@ pub enum RegenerationPolicy {
 pub struct RegenerationTickDetails {
     pub processed_dids_count: usize,
     pub regenerated_dids_count: usize,
+    /// Resource-specific pools visited, across all DIDs
+    pub processed_pools_count: usize,
+    pub regenerated_pools_count: usize,
+    /// Per-scope budgets visited, across all DIDs
+    pub processed_scoped_count: usize,
+    pub regenerated_scoped_count: usize,
     pub errors: Vec<(Did, String)>,
 }
 
 pub struct ManaRegenerator<L: ManaLedger> {
     pub ledger: Arc<L>,
-    pub policy: RegenerationPolicy,
-    // Could also include a time source or epoch tracker if regeneration is time-dependent
+    /// Swappable at runtime; each tick reads the policy once at its start
+    policy: std::sync::RwLock<RegenerationPolicy>,
+    /// Policies for resource-specific pools; pools without one use `policy`
+    pool_policies: std::sync::RwLock<HashMap<ResourceType, RegenerationPolicy>>,
+    /// Time source used to stamp `last_updated_epoch` on regenerated states
+    pub clock: Arc<dyn Clock>,
 }
 
 impl<L: ManaLedger + Send + Sync> ManaRegenerator<L> {
     // Ensure L is Send + Sync for Arc<L>
     pub fn new(ledger: Arc<L>, policy: RegenerationPolicy) -> Self {
-        Self { ledger, policy }
+        Self::with_clock(ledger, policy, Arc::new(SystemClock))
+    }
+
+    /// Create a regenerator that reads time from `clock`
+    pub fn with_clock(ledger: Arc<L>, policy: RegenerationPolicy, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            ledger,
+            policy: std::sync::RwLock::new(policy),
+            pool_policies: std::sync::RwLock::new(HashMap::new()),
+            clock,
+        }
+    }
+
+    /// The policy applied by the next tick
+    pub fn policy(&self) -> RegenerationPolicy {
+        self.policy.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the policy; ticks already in progress finish with the old one
+    pub fn set_policy(&self, policy: RegenerationPolicy) {
+        *self.policy.write().unwrap_or_else(|e| e.into_inner()) = policy;
+    }
+
+    /// The policy applied to `resource` pools by the next tick
+    pub fn pool_policy(&self, resource: ResourceType) -> RegenerationPolicy {
+        self.pool_policies
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&resource)
+            .cloned()
+            .unwrap_or_else(|| self.policy())
+    }
+
+    /// Regenerate `resource` pools under `policy` instead of the default one
+    pub fn set_pool_policy(&self, resource: ResourceType, policy: RegenerationPolicy) {
+        self.pool_policies
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(resource, policy);
     }
 
     pub async fn tick(&self) -> Result<RegenerationTickDetails> {
+        let policy = self.policy();
+        let pool_policies = self
+            .pool_policies
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let now = self.clock.epoch();
         let mut regenerated_dids_count = 0;
         let mut errors = Vec::new();
 
@@ -281,28 +470,28 @@ impl<L: ManaLedger + Send + Sync> ManaRegenerator<L> {
                 for did in dids {
                     match self.ledger.get_mana_state(&did).await {
                         Ok(Some(mut state)) => {
+                            let original = state.clone();
                             let original_mana = state.current_mana;
 
-                            let RegenerationPolicy::FixedRatePerTick(regen_amount) = self.policy;
-
-                            state.current_mana =
-                                (state.current_mana + regen_amount).min(state.max_mana);
-
-                            if state.current_mana != original_mana {
+                            let regenerated = policy.apply(&mut state, now);
+                            if regenerated {
                                 regenerated_dids_count += 1;
+                            } else {
+                                trace!(did = %did, mana = original_mana, "Mana already at max or regen amount is zero.");
+                            }
+                            // A state stamped with a new epoch is stored even if its balance is unchanged
+                            if state != original {
                                 if let Err(e) =
                                     self.ledger.update_mana_state(&did, state.clone()).await
                                 {
                                     // Pass cloned state
                                     errors.push((did.clone(), format!("update_failed: {}", e)));
-                                } else {
+                                } else if regenerated {
                                     // Successfully updated, log if needed (original log was here)
                                     // Log was: tracing::debug!(did = %did, old_mana = original_mana, new_mana = state.current_mana, regen_amount = regen_amount, "Mana regenerated");
                                     // Avoiding ledger read just for log: new_mana = state.current_mana
-                                    debug!(did = %did, old_mana = original_mana, new_mana = state.current_mana, regen_amount = regen_amount, "Mana regenerated");
+                                    debug!(did = %did, old_mana = original_mana, new_mana = state.current_mana, "Mana regenerated");
                                 }
-                            } else {
-                                trace!(did = %did, mana = original_mana, "Mana already at max or regen amount is zero.");
                             }
                         }
                         Ok(None) => {
@@ -319,7 +508,7 @@ impl<L: ManaLedger + Send + Sync> ManaRegenerator<L> {
             Err(e) => {
                 // This error means we couldn't even get the list of DIDs to process.
                 // It's a more fundamental issue with the tick operation itself.
-                let policy_label = policy_to_label(&self.policy);
+                let policy_label = policy_to_label(&policy);
                 MANA_REGENERATION_ERRORS_TOTAL
                     .with_label_values(&[policy_label, "all_dids_read_failed"])
                     .inc();
@@ -330,14 +519,94 @@ impl<L: ManaLedger + Send + Sync> ManaRegenerator<L> {
             }
         }
 
+        // Resource-specific pools, each under its type's policy
+        let pools = match self.ledger.all_pools().await {
+            Ok(pools) => pools,
+            Err(e) => {
+                MANA_REGENERATION_ERRORS_TOTAL
+                    .with_label_values(&[policy_to_label(&policy), "all_pools_read_failed"])
+                    .inc();
+                return Err(anyhow::anyhow!(
+                    "Failed to retrieve mana pools from ledger for tick: {}",
+                    e
+                ));
+            }
+        };
+        let mut regenerated_pools_count = 0;
+        for (did, resource) in &pools {
+            let pool_policy = pool_policies.get(resource).unwrap_or(&policy);
+            match self.ledger.get_pool_state(did, *resource).await {
+                Ok(Some(mut state)) => {
+                    let original = state.clone();
+                    if pool_policy.apply(&mut state, now) {
+                        regenerated_pools_count += 1;
+                    }
+                    if state != original {
+                        if let Err(e) = self.ledger.update_pool_state(did, *resource, state).await {
+                            errors.push((did.clone(), format!("update_failed: {} pool: {}", resource, e)));
+                        }
+                    }
+                }
+                Ok(None) => {
+                    warn!(did = %did, %resource, "Mana pool listed by all_pools not found during tick, skipping.");
+                }
+                Err(e) => {
+                    errors.push((did.clone(), format!("read_failed: {} pool: {}", resource, e)));
+                }
+            }
+        }
+
+        // Per-scope budgets, under the default policy
+        let scoped = match self.ledger.all_scoped().await {
+            Ok(scoped) => scoped,
+            Err(e) => {
+                MANA_REGENERATION_ERRORS_TOTAL
+                    .with_label_values(&[policy_to_label(&policy), "all_scoped_read_failed"])
+                    .inc();
+                return Err(anyhow::anyhow!(
+                    "Failed to retrieve scoped mana budgets from ledger for tick: {}",
+                    e
+                ));
+            }
+        };
+        let mut regenerated_scoped_count = 0;
+        for (did, scope) in &scoped {
+            match self.ledger.get_scoped_state(did, scope).await {
+                Ok(Some(mut state)) => {
+                    let original = state.clone();
+                    if policy.apply(&mut state, now) {
+                        regenerated_scoped_count += 1;
+                    }
+                    if state != original {
+                        if let Err(e) = self.ledger.update_scoped_state(did, scope, state).await {
+                            errors.push((
+                                did.clone(),
+                                format!("update_failed: scope {}: {}", scope, e),
+                            ));
+                        }
+                    }
+                }
+                Ok(None) => {
+                    warn!(did = %did, %scope, "Scoped mana budget listed by all_scoped not found during tick, skipping.");
+                }
+                Err(e) => {
+                    errors.push((did.clone(), format!("read_failed: scope {}: {}", scope, e)));
+                }
+            }
+        }
+
         let details = RegenerationTickDetails {
             processed_dids_count: processed_dids_count_val,
             regenerated_dids_count,
+            processed_pools_count: pools.len(),
+            regenerated_pools_count,
+            processed_scoped_count: scoped.len(),
+            regenerated_scoped_count,
             errors,
         };
 
         // Increment metrics based on collected details
-        let policy_label = policy_to_label(&self.policy);
+        let policy_label = policy_to_label(&policy);
 
         MANA_REGENERATION_TICKS_TOTAL
             .with_label_values(&[policy_label])
@@ -376,13 +645,15 @@ impl<L: ManaLedger + Send + Sync> ManaRegenerator<L> {
 #[derive(Default)]
 pub struct InMemoryManaLedger {
     inner: RwLock<HashMap<Did, ManaState>>,
+    /// Resource-specific pools, kept apart from the default pools in `inner`
+    pools: RwLock<HashMap<(Did, ResourceType), ManaState>>,
+    /// Per-scope budgets
+    scoped: RwLock<HashMap<(Did, String), ManaState>>,
 }
 
 impl InMemoryManaLedger {
     pub fn new() -> Self {
-        Self {
-            inner: RwLock::new(HashMap::new()),
-        }
+        Self::default()
     }
 
     // Helper for tests to set initial states easily
@@ -405,4 +676,74 @@ impl ManaLedger for InMemoryManaLedger {
     async fn all_dids(&self) -> Result<Vec<Did>> {
         Ok(self.inner.read().await.keys().cloned().collect())
     }
+
+    async fn get_pool_state(&self, did: &Did, resource: ResourceType) -> Result<Option<ManaState>> {
+        Ok(self.pools.read().await.get(&(did.clone(), resource)).cloned())
+    }
+
+    async fn update_pool_state(
+        &self,
+        did: &Did,
+        resource: ResourceType,
+        new_state: ManaState,
+    ) -> Result<()> {
+        self.pools.write().await.insert((did.clone(), resource), new_state);
+        Ok(())
+    }
+
+    async fn all_pools(&self) -> Result<Vec<(Did, ResourceType)>> {
+        Ok(self.pools.read().await.keys().cloned().collect())
+    }
+
+    async fn get_scoped_state(&self, did: &Did, scope: &str) -> Result<Option<ManaState>> {
+        Ok(self
+            .scoped
+            .read()
+            .await
+            .get(&(did.clone(), scope.to_string()))
+            .cloned())
+    }
+
+    async fn update_scoped_state(&self, did: &Did, scope: &str, new_state: ManaState) -> Result<()> {
+        self.scoped
+            .write()
+            .await
+            .insert((did.clone(), scope.to_string()), new_state);
+        Ok(())
+    }
+
+    async fn all_scoped(&self) -> Result<Vec<(Did, String)>> {
+        Ok(self.scoped.read().await.keys().cloned().collect())
+    }
+
+    async fn debit_scoped(
+        &self,
+        did: &Did,
+        scope: &str,
+        amount: u64,
+    ) -> Result<Result<(), ManaError>> {
+        let key = (did.clone(), scope.to_string());
+        let mut scoped = self.scoped.write().await;
+        let outcome = debit_record(scoped.get(&key).cloned(), amount).map(|state| {
+            scoped.insert(key, state);
+        });
+        Ok(outcome)
+    }
+
+    async fn batch_deduct(
+        &self,
+        deductions: &[(Did, u64)],
+    ) -> Result<Vec<(Did, Result<(), ManaError>)>> {
+        // One write lock for the batch, so no debit interleaves with another writer
+        let mut inner = self.inner.write().await;
+        Ok(deductions
+            .iter()
+            .map(|(did, amount)| {
+                let outcome = debit_record(inner.get(did).cloned(), *amount).map(|state| {
+                    inner.insert(did.clone(), state);
+                });
+                (did.clone(), outcome)
+            })
+            .collect())
+    }
 }
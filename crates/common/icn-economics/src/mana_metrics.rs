@@ -52,5 +52,7 @@ lazy_static! {
 pub fn policy_to_label(policy: &RegenerationPolicy) -> &'static str {
     match policy {
         RegenerationPolicy::FixedRatePerTick(_) => "fixed_rate_per_tick",
+        RegenerationPolicy::ProportionalToMax { .. } => "proportional_to_max",
+        RegenerationPolicy::ElapsedTime { .. } => "elapsed_time",
     }
 }
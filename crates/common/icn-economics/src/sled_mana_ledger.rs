@@ -2,13 +2,79 @@ use crate::mana_metrics::*; // Added for metrics
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use icn_identity::Did;
+use sled::transaction::{
+    ConflictableTransactionError, ConflictableTransactionResult, TransactionError, Transactional,
+    TransactionalTree,
+};
 use sled::Db;
 use std::str::FromStr; // Added for Did::from_str
-use tracing::{error}; // debug was unused // Added for logging
+use tracing::{error, info}; // debug was unused // Added for logging
 
-use crate::mana::{ManaLedger, ManaState};
+use crate::mana::{debit_record, ManaError, ManaLedger, ManaState};
+use icn_types::resource::ResourceType;
+use serde::{Deserialize, Serialize};
 
 const MANA_STATE_TREE_NAME: &str = "mana_states";
+const MANA_AUDIT_TREE_NAME: &str = "mana_audit_log";
+const MANA_POOL_TREE_NAME: &str = "mana_pool_states";
+const MANA_SCOPED_TREE_NAME: &str = "mana_scoped_states";
+const MANA_SCOPED_AUDIT_TREE_NAME: &str = "mana_scoped_audit_log";
+const MANA_META_TREE_NAME: &str = "mana_meta";
+const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// Storage layout version written by this build.
+///
+/// Version 1 held one balance per DID in `mana_states`. Version 2 keeps that
+/// tree as each DID's default pool and adds `mana_pool_states` for
+/// resource-specific pools, version 3 adds `mana_scoped_states` for
+/// per-scope budgets, and version 4 adds `mana_scoped_audit_log` to audit
+/// them. Stores without a recorded version are version 1.
+///
+/// Migrating also gives every balance and budget stored before it was audited
+/// an opening audit entry, so reconciliation derives that balance rather than
+/// zero.
+pub const MANA_SCHEMA_VERSION: u32 = 4;
+
+/// A single balance change recorded in the mana audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManaAuditEntry {
+    /// Monotonic sequence number assigned by the database
+    pub sequence: u64,
+    /// Signed change applied to `current_mana`
+    pub delta: i128,
+    /// Balance stored after the change was applied
+    pub resulting_balance: u64,
+}
+
+/// A balance whose stored value does not match the sum of its audit-log deltas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceDiscrepancy {
+    pub did: Did,
+    /// Scope of the budget, or `None` for the DID's default balance
+    pub scope: Option<String>,
+    /// Balance currently stored in the mana state tree
+    pub stored_balance: u64,
+    /// Balance recomputed from the audit log
+    pub log_balance: u64,
+}
+
+/// Result of reconciling stored balances against the audit log.
+#[derive(Debug, Clone, Default)]
+pub struct ReconciliationReport {
+    /// Number of balances checked, default and scoped
+    pub checked: usize,
+    /// DIDs whose stored balance differs from the log-derived balance
+    pub discrepancies: Vec<BalanceDiscrepancy>,
+    /// Number of discrepancies repaired (only non-zero when repair was requested)
+    pub repaired: usize,
+}
+
+impl ReconciliationReport {
+    /// Returns true if no discrepancies were found
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
 
 /// A ManaLedger implementation using Sled persistent storage.
 #[derive(Clone)] // Clone is possible because sled::Db is Arc internally
@@ -24,7 +90,119 @@ impl SledManaLedger {
         // or to handle any potential errors specific to tree opening early.
         db.open_tree(MANA_STATE_TREE_NAME)
             .context("Failed to open mana_states tree in Sled database")?;
-        Ok(Self { db })
+        db.open_tree(MANA_AUDIT_TREE_NAME)
+            .context("Failed to open mana_audit_log tree in Sled database")?;
+        let ledger = Self { db };
+        ledger.migrate()?;
+        Ok(ledger)
+    }
+
+    /// Storage layout version of the open store
+    pub fn schema_version(&self) -> Result<u32> {
+        let meta = self
+            .db
+            .open_tree(MANA_META_TREE_NAME)
+            .context("Failed to open mana_meta tree in Sled database")?;
+        match meta.get(SCHEMA_VERSION_KEY)? {
+            None => Ok(1),
+            Some(ivec) => {
+                let bytes: [u8; 4] = ivec[..]
+                    .try_into()
+                    .map_err(|_| anyhow!("Corrupt mana ledger schema version: {:?}", ivec))?;
+                Ok(u32::from_be_bytes(bytes))
+            }
+        }
+    }
+
+    // Bring a store written by an older build up to MANA_SCHEMA_VERSION
+    fn migrate(&self) -> Result<()> {
+        let version = self.schema_version()?;
+        if version > MANA_SCHEMA_VERSION {
+            return Err(anyhow!(
+                "Mana ledger schema version {} is newer than the supported version {}",
+                version,
+                MANA_SCHEMA_VERSION
+            ));
+        }
+        if version == MANA_SCHEMA_VERSION {
+            return Ok(());
+        }
+        // Version 1 balances already sit in `mana_states`, which later versions
+        // read as the default pool, so only the newer trees have to be added
+        self.get_pool_tree()?;
+        self.get_scoped_tree()?;
+        self.get_scoped_audit_tree()?;
+        self.seed_opening_audit_entries()?;
+        self.seed_opening_scoped_audit_entries()?;
+        let meta = self
+            .db
+            .open_tree(MANA_META_TREE_NAME)
+            .context("Failed to open mana_meta tree in Sled database")?;
+        meta.insert(SCHEMA_VERSION_KEY, MANA_SCHEMA_VERSION.to_be_bytes().to_vec())?;
+        meta.flush()
+            .context("Failed to flush mana ledger schema version")?;
+        info!(from = version, to = MANA_SCHEMA_VERSION, "Migrated mana ledger schema");
+        Ok(())
+    }
+
+    // Record each stored balance that has no audit history as a single credit
+    // of the full balance
+    fn seed_opening_audit_entries(&self) -> Result<()> {
+        for item in self.get_tree()?.iter() {
+            let (key, value) = item.context("Failed to iterate mana_states during migration")?;
+            let did_str = std::str::from_utf8(&key)
+                .map_err(|e| anyhow!("Mana state key is not UTF-8: {}", e))?;
+            let did = Did::from_str(did_str)
+                .map_err(|e| anyhow!("Invalid DID in mana state key: {}", e))?;
+            let state: ManaState = bincode::deserialize(&value)
+                .map_err(|e| anyhow!("Failed to deserialize ManaState for DID {}: {}", did, e))?;
+            if state.current_mana == 0
+                || self
+                    .get_audit_tree()?
+                    .scan_prefix(Self::audit_prefix(&did))
+                    .next()
+                    .is_some()
+            {
+                continue;
+            }
+            self.append_audit_entry(&did, state.current_mana as i128, state.current_mana)?;
+            info!(%did, balance = state.current_mana, "Seeded opening mana audit entry");
+        }
+        Ok(())
+    }
+
+    // Like seed_opening_audit_entries, for per-scope budgets
+    fn seed_opening_scoped_audit_entries(&self) -> Result<()> {
+        let audit_tree = self.get_scoped_audit_tree()?;
+        for item in self.get_scoped_tree()?.iter() {
+            let (key, value) =
+                item.context("Failed to iterate mana_scoped_states during migration")?;
+            let (did, scope) = Self::parse_scoped_key(&key)?;
+            let state: ManaState = bincode::deserialize(&value).map_err(|e| {
+                anyhow!(
+                    "Failed to deserialize scope {} ManaState for DID {}: {}",
+                    scope,
+                    did,
+                    e
+                )
+            })?;
+            let prefix = Self::scoped_audit_prefix(&did, &scope);
+            if state.current_mana == 0 || audit_tree.scan_prefix(&prefix).next().is_some() {
+                continue;
+            }
+            let entry = ManaAuditEntry {
+                sequence: self
+                    .db
+                    .generate_id()
+                    .context("Failed to generate mana audit sequence number")?,
+                delta: state.current_mana as i128,
+                resulting_balance: state.current_mana,
+            };
+            let (key, value) = Self::audit_record(prefix, &entry)?;
+            audit_tree.insert(key, value)?;
+            info!(%did, %scope, balance = state.current_mana, "Seeded opening scoped mana audit entry");
+        }
+        Ok(())
     }
 
     // Helper to get the specific tree for mana states
@@ -33,6 +211,300 @@ impl SledManaLedger {
             .open_tree(MANA_STATE_TREE_NAME)
             .context("Failed to access mana_states tree in Sled database")
     }
+
+    // Helper to get the tree holding resource-specific pools
+    fn get_pool_tree(&self) -> Result<sled::Tree> {
+        self.db
+            .open_tree(MANA_POOL_TREE_NAME)
+            .context("Failed to access mana_pool_states tree in Sled database")
+    }
+
+    // Helper to get the tree holding per-scope budgets
+    fn get_scoped_tree(&self) -> Result<sled::Tree> {
+        self.db
+            .open_tree(MANA_SCOPED_TREE_NAME)
+            .context("Failed to access mana_scoped_states tree in Sled database")
+    }
+
+    // Scoped budget keys are `<did>\0<scope>`
+    fn scoped_key(did: &Did, scope: &str) -> Vec<u8> {
+        let mut key = did.to_string().into_bytes();
+        key.push(0);
+        key.extend_from_slice(scope.as_bytes());
+        key
+    }
+
+    fn parse_scoped_key(key: &[u8]) -> Result<(Did, String)> {
+        let split = key
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow!("Malformed scoped mana key"))?;
+        let did_str = std::str::from_utf8(&key[..split])
+            .map_err(|e| anyhow!("Scoped mana key is not UTF-8: {}", e))?;
+        let did =
+            Did::from_str(did_str).map_err(|e| anyhow!("Invalid DID in scoped mana key: {}", e))?;
+        let scope = std::str::from_utf8(&key[split + 1..])
+            .map_err(|e| anyhow!("Scope in scoped mana key is not UTF-8: {}", e))?;
+        Ok((did, scope.to_string()))
+    }
+
+    // Pool keys are `<did>\0<resource type as big-endian u32>`
+    fn pool_key(did: &Did, resource: ResourceType) -> Vec<u8> {
+        let mut key = did.to_string().into_bytes();
+        key.push(0);
+        key.extend_from_slice(&(resource as u32).to_be_bytes());
+        key
+    }
+
+    fn parse_pool_key(key: &[u8]) -> Result<(Did, ResourceType)> {
+        let split = key
+            .len()
+            .checked_sub(5)
+            .filter(|&at| key[at] == 0)
+            .ok_or_else(|| anyhow!("Malformed mana pool key"))?;
+        let did_str = std::str::from_utf8(&key[..split])
+            .map_err(|e| anyhow!("Mana pool key is not UTF-8: {}", e))?;
+        let did = Did::from_str(did_str)
+            .map_err(|e| anyhow!("Invalid DID in mana pool key: {}", e))?;
+        let code = u32::from_be_bytes(key[split + 1..].try_into()?);
+        let resource = ResourceType::from(code);
+        if resource as u32 != code {
+            return Err(anyhow!("Unknown resource type {} in mana pool key", code));
+        }
+        Ok((did, resource))
+    }
+
+    // Helper to get the tree holding the append-only audit log
+    fn get_audit_tree(&self) -> Result<sled::Tree> {
+        self.db
+            .open_tree(MANA_AUDIT_TREE_NAME)
+            .context("Failed to access mana_audit_log tree in Sled database")
+    }
+
+    // Helper to get the tree auditing per-scope budgets
+    fn get_scoped_audit_tree(&self) -> Result<sled::Tree> {
+        self.db
+            .open_tree(MANA_SCOPED_AUDIT_TREE_NAME)
+            .context("Failed to access mana_scoped_audit_log tree in Sled database")
+    }
+
+    // Audit keys are `<did>\0<sequence as big-endian u64>` so a prefix scan
+    // over `<did>\0` yields a DID's entries in insertion order.
+    fn audit_prefix(did: &Did) -> Vec<u8> {
+        let mut prefix = did.to_string().into_bytes();
+        prefix.push(0);
+        prefix
+    }
+
+    // Scoped audit keys are `<did>\0<scope>\0<sequence as big-endian u64>`
+    fn scoped_audit_prefix(did: &Did, scope: &str) -> Vec<u8> {
+        let mut prefix = Self::scoped_key(did, scope);
+        prefix.push(0);
+        prefix
+    }
+
+    // Key and serialized value under which `entry` is stored after `prefix`
+    fn audit_record(prefix: Vec<u8>, entry: &ManaAuditEntry) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut key = prefix;
+        key.extend_from_slice(&entry.sequence.to_be_bytes());
+        let value = bincode::serialize(entry)
+            .map_err(|e| anyhow!("Serialization error for mana audit entry: {}", e))?;
+        Ok((key, value))
+    }
+
+    // Read the state under `key` within a transaction
+    fn read_state_tx(
+        states: &TransactionalTree,
+        key: &[u8],
+    ) -> ConflictableTransactionResult<Option<ManaState>, anyhow::Error> {
+        states
+            .get(key)?
+            .map(|ivec| bincode::deserialize::<ManaState>(&ivec))
+            .transpose()
+            .map_err(|e| {
+                ConflictableTransactionError::Abort(anyhow!(
+                    "Failed to deserialize ManaState: {}",
+                    e
+                ))
+            })
+    }
+
+    // Store `state` under `key` and audit its change from `previous_balance`
+    // under `audit_prefix`, within a transaction over both trees
+    fn write_audited_tx(
+        states: &TransactionalTree,
+        audit: &TransactionalTree,
+        key: Vec<u8>,
+        audit_prefix: Vec<u8>,
+        previous_balance: u64,
+        state: &ManaState,
+    ) -> ConflictableTransactionResult<(), anyhow::Error> {
+        let serialized = bincode::serialize(state).map_err(|e| {
+            ConflictableTransactionError::Abort(anyhow!("Serialization error for ManaState: {}", e))
+        })?;
+        states.insert(key, serialized)?;
+        let delta = state.current_mana as i128 - previous_balance as i128;
+        if delta != 0 {
+            let entry = ManaAuditEntry {
+                sequence: audit.generate_id()?,
+                delta,
+                resulting_balance: state.current_mana,
+            };
+            let (key, value) = Self::audit_record(audit_prefix, &entry)
+                .map_err(ConflictableTransactionError::Abort)?;
+            audit.insert(key, value)?;
+        }
+        Ok(())
+    }
+
+    // Map a failed transaction on `did`'s scoped budget to an error
+    fn scoped_tx_error(
+        did: &Did,
+        scope: &str,
+        e: TransactionError<anyhow::Error>,
+    ) -> anyhow::Error {
+        match e {
+            TransactionError::Abort(e) => e.context(format!("Scope {} of DID {}", scope, did)),
+            TransactionError::Storage(e) => {
+                anyhow!(
+                    "Sled transaction I/O error for scope {} of DID {}: {}",
+                    scope,
+                    did,
+                    e
+                )
+            }
+        }
+    }
+
+    fn append_audit_entry(&self, did: &Did, delta: i128, resulting_balance: u64) -> Result<()> {
+        let audit_tree = self.get_audit_tree()?;
+        let sequence = self
+            .db
+            .generate_id()
+            .context("Failed to generate mana audit sequence number")?;
+        let entry = ManaAuditEntry {
+            sequence,
+            delta,
+            resulting_balance,
+        };
+        let (key, value) = Self::audit_record(Self::audit_prefix(did), &entry)?;
+        audit_tree
+            .insert(key, value)
+            .map_err(|e| anyhow!("Sled audit log insert I/O error for DID {}: {}", did, e))?;
+        Ok(())
+    }
+
+    /// Returns the audit log entries for a DID in the order they were recorded.
+    pub fn audit_log(&self, did: &Did) -> Result<Vec<ManaAuditEntry>> {
+        Self::read_audit_log(&self.get_audit_tree()?, Self::audit_prefix(did), did)
+    }
+
+    /// Returns the audit log entries for `did`'s budget in `scope` in the order
+    /// they were recorded.
+    pub fn scoped_audit_log(&self, did: &Did, scope: &str) -> Result<Vec<ManaAuditEntry>> {
+        Self::read_audit_log(
+            &self.get_scoped_audit_tree()?,
+            Self::scoped_audit_prefix(did, scope),
+            did,
+        )
+    }
+
+    fn read_audit_log(
+        audit_tree: &sled::Tree,
+        prefix: Vec<u8>,
+        did: &Did,
+    ) -> Result<Vec<ManaAuditEntry>> {
+        audit_tree
+            .scan_prefix(prefix)
+            .map(|item| {
+                let (_key, value) =
+                    item.map_err(|e| anyhow!("Sled audit log iteration I/O error for DID {}: {}", did, e))?;
+                bincode::deserialize::<ManaAuditEntry>(&value).map_err(|e| {
+                    anyhow!("Failed to deserialize mana audit entry for DID {}: {}", did, e)
+                })
+            })
+            .collect()
+    }
+
+    /// Recomputes each DID's balance, and each of its scoped budgets, from its
+    /// audit log and compares it with the stored value.
+    ///
+    /// When `repair` is true, discrepant stored balances are overwritten with the
+    /// log-derived value. Repairs do not append to the audit log, since they restore
+    /// the state the log already describes. Balances with no audit history are
+    /// reported but never repaired, as there is no log to restore from.
+    pub async fn reconcile(&self, repair: bool) -> Result<ReconciliationReport> {
+        let mut report = ReconciliationReport::default();
+
+        for did in self.all_dids().await? {
+            report.checked += 1;
+            if let Some(state) = self.get_mana_state(&did).await? {
+                self.reconcile_balance(&mut report, did, None, state, repair)?;
+            }
+        }
+        for (did, scope) in self.all_scoped().await? {
+            report.checked += 1;
+            if let Some(state) = self.get_scoped_state(&did, &scope).await? {
+                self.reconcile_balance(&mut report, did, Some(scope), state, repair)?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    // Check one default balance (`scope` of `None`) or scoped budget against
+    // its audit log, recording and optionally repairing a discrepancy
+    fn reconcile_balance(
+        &self,
+        report: &mut ReconciliationReport,
+        did: Did,
+        scope: Option<String>,
+        mut state: ManaState,
+        repair: bool,
+    ) -> Result<()> {
+        let (tree, key, log) = match &scope {
+            Some(scope) => (
+                self.get_scoped_tree()?,
+                Self::scoped_key(&did, scope),
+                self.scoped_audit_log(&did, scope)?,
+            ),
+            None => (
+                self.get_tree()?,
+                did.to_string().into_bytes(),
+                self.audit_log(&did)?,
+            ),
+        };
+        let log_sum: i128 = log.iter().map(|e| e.delta).sum();
+        let log_balance = u64::try_from(log_sum.max(0)).unwrap_or(u64::MAX);
+        if log_balance == state.current_mana {
+            return Ok(());
+        }
+
+        error!(
+            %did,
+            scope = scope.as_deref().unwrap_or(""),
+            stored = state.current_mana,
+            derived = log_balance,
+            "Mana balance does not match audit log"
+        );
+        report.discrepancies.push(BalanceDiscrepancy {
+            did: did.clone(),
+            scope,
+            stored_balance: state.current_mana,
+            log_balance,
+        });
+
+        if repair && !log.is_empty() {
+            // Repair restores the audited balance verbatim rather than applying a delta
+            state.current_mana = log_balance;
+            let serialized = bincode::serialize(&state)
+                .map_err(|e| anyhow!("Serialization error for ManaState for DID {}: {}", did, e))?;
+            tree.insert(key, serialized)
+                .map_err(|e| anyhow!("Sled tree insert I/O error for DID {}: {}", did, e))?;
+            report.repaired += 1;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -115,10 +587,26 @@ impl ManaLedger for SledManaLedger {
         match bincode::serialize(&new_state) {
             Ok(serialized_state) => {
                 match tree.insert(&did_key_bytes, serialized_state) {
-                    Ok(_) => {
+                    Ok(previous) => {
                         MANA_LEDGER_OPERATIONS_TOTAL
                             .with_label_values(&["sled", "set", "success"])
                             .inc();
+                        // Record the balance change in the audit log so that stored
+                        // balances can later be reconciled against it.
+                        let previous_balance = previous
+                            .and_then(|ivec| bincode::deserialize::<ManaState>(&ivec).ok())
+                            .map(|state| state.current_mana)
+                            .unwrap_or(0);
+                        let delta = new_state.current_mana as i128 - previous_balance as i128;
+                        if delta != 0 {
+                            if let Err(e) = self.append_audit_entry(did, delta, new_state.current_mana) {
+                                MANA_LEDGER_ERRORS_TOTAL
+                                    .with_label_values(&["sled", "audit_append", "io"])
+                                    .inc();
+                                error!(%did, error = %e, "Failed to append mana audit log entry");
+                                return Err(e);
+                            }
+                        }
                         // Optional: Explicit flush for critical updates
                         // if let Err(e) = tree.flush_async().await {
                         //     MANA_LEDGER_ERRORS_TOTAL
@@ -216,6 +704,227 @@ impl ManaLedger for SledManaLedger {
 
         Ok(dids)
     }
+
+    async fn get_pool_state(&self, did: &Did, resource: ResourceType) -> Result<Option<ManaState>> {
+        let tree = self.get_pool_tree()?;
+        let result = tree
+            .get(Self::pool_key(did, resource))
+            .map_err(|e| anyhow!("Sled tree I/O error for {} pool of DID {}: {}", resource, did, e))
+            .and_then(|value| {
+                value
+                    .map(|ivec| bincode::deserialize::<ManaState>(&ivec))
+                    .transpose()
+                    .map_err(|e| {
+                        anyhow!("Failed to deserialize {} pool ManaState for DID {}: {}", resource, did, e)
+                    })
+            });
+        let outcome = if result.is_ok() { "success" } else { "error" };
+        MANA_LEDGER_OPERATIONS_TOTAL
+            .with_label_values(&["sled", "get_pool", outcome])
+            .inc();
+        result
+    }
+
+    async fn update_pool_state(
+        &self,
+        did: &Did,
+        resource: ResourceType,
+        new_state: ManaState,
+    ) -> Result<()> {
+        let tree = self.get_pool_tree()?;
+        let result = bincode::serialize(&new_state)
+            .map_err(|e| anyhow!("Serialization error for {} pool ManaState for DID {}: {}", resource, did, e))
+            .and_then(|value| {
+                tree.insert(Self::pool_key(did, resource), value).map_err(|e| {
+                    anyhow!("Sled tree insert I/O error for {} pool of DID {}: {}", resource, did, e)
+                })
+            });
+        let outcome = if result.is_ok() { "success" } else { "error" };
+        MANA_LEDGER_OPERATIONS_TOTAL
+            .with_label_values(&["sled", "set_pool", outcome])
+            .inc();
+        result.map(|_| ())
+    }
+
+    async fn all_pools(&self) -> Result<Vec<(Did, ResourceType)>> {
+        let tree = self.get_pool_tree()?;
+        let mut pools = Vec::new();
+        for item in tree.iter() {
+            let (key, _value) =
+                item.map_err(|e| anyhow!("Sled tree iteration I/O error in all_pools: {}", e))?;
+            match Self::parse_pool_key(&key) {
+                Ok(pool) => pools.push(pool),
+                Err(e) => {
+                    // Like all_dids, skip entries that cannot be parsed
+                    MANA_LEDGER_ERRORS_TOTAL
+                        .with_label_values(&["sled", "list_pools_parse_key", "deserialization"])
+                        .inc();
+                    error!(error = %e, "Error parsing Sled key in all_pools");
+                }
+            }
+        }
+        Ok(pools)
+    }
+
+    async fn get_scoped_state(&self, did: &Did, scope: &str) -> Result<Option<ManaState>> {
+        let tree = self.get_scoped_tree()?;
+        let result = tree
+            .get(Self::scoped_key(did, scope))
+            .map_err(|e| anyhow!("Sled tree I/O error for scope {} of DID {}: {}", scope, did, e))
+            .and_then(|value| {
+                value
+                    .map(|ivec| bincode::deserialize::<ManaState>(&ivec))
+                    .transpose()
+                    .map_err(|e| {
+                        anyhow!("Failed to deserialize scope {} ManaState for DID {}: {}", scope, did, e)
+                    })
+            });
+        let outcome = if result.is_ok() { "success" } else { "error" };
+        MANA_LEDGER_OPERATIONS_TOTAL
+            .with_label_values(&["sled", "get_scoped", outcome])
+            .inc();
+        result
+    }
+
+    async fn update_scoped_state(&self, did: &Did, scope: &str, new_state: ManaState) -> Result<()> {
+        let tree = self.get_scoped_tree()?;
+        let audit_tree = self.get_scoped_audit_tree()?;
+        // The budget and its audit entry are written together, as in batch_deduct
+        let result = (&tree, &audit_tree)
+            .transaction(|(states, audit)| {
+                let key = Self::scoped_key(did, scope);
+                let previous_balance =
+                    Self::read_state_tx(states, &key)?.map_or(0, |state| state.current_mana);
+                Self::write_audited_tx(
+                    states,
+                    audit,
+                    key,
+                    Self::scoped_audit_prefix(did, scope),
+                    previous_balance,
+                    &new_state,
+                )
+            })
+            .map_err(|e| Self::scoped_tx_error(did, scope, e));
+        let outcome = if result.is_ok() { "success" } else { "error" };
+        MANA_LEDGER_OPERATIONS_TOTAL
+            .with_label_values(&["sled", "set_scoped", outcome])
+            .inc();
+        result
+    }
+
+    async fn all_scoped(&self) -> Result<Vec<(Did, String)>> {
+        let tree = self.get_scoped_tree()?;
+        let mut scoped = Vec::new();
+        for item in tree.iter() {
+            let (key, _value) =
+                item.map_err(|e| anyhow!("Sled tree iteration I/O error in all_scoped: {}", e))?;
+            match Self::parse_scoped_key(&key) {
+                Ok(budget) => scoped.push(budget),
+                Err(e) => {
+                    // Like all_dids, skip entries that cannot be parsed
+                    MANA_LEDGER_ERRORS_TOTAL
+                        .with_label_values(&["sled", "list_scoped_parse_key", "deserialization"])
+                        .inc();
+                    error!(error = %e, "Error parsing Sled key in all_scoped");
+                }
+            }
+        }
+        Ok(scoped)
+    }
+
+    async fn debit_scoped(
+        &self,
+        did: &Did,
+        scope: &str,
+        amount: u64,
+    ) -> Result<Result<(), ManaError>> {
+        let tree = self.get_scoped_tree()?;
+        let audit_tree = self.get_scoped_audit_tree()?;
+        let result = (&tree, &audit_tree)
+            .transaction(|(states, audit)| {
+                let key = Self::scoped_key(did, scope);
+                let previous = Self::read_state_tx(states, &key)?;
+                let previous_balance = previous.as_ref().map_or(0, |state| state.current_mana);
+                let state = match debit_record(previous, amount) {
+                    Ok(state) => state,
+                    Err(e) => return Ok(Err(e)),
+                };
+                Self::write_audited_tx(
+                    states,
+                    audit,
+                    key,
+                    Self::scoped_audit_prefix(did, scope),
+                    previous_balance,
+                    &state,
+                )?;
+                Ok(Ok(()))
+            })
+            .map_err(|e| Self::scoped_tx_error(did, scope, e));
+        let outcome = if result.is_ok() { "success" } else { "error" };
+        MANA_LEDGER_OPERATIONS_TOTAL
+            .with_label_values(&["sled", "debit_scoped", outcome])
+            .inc();
+        result
+    }
+
+    async fn batch_deduct(
+        &self,
+        deductions: &[(Did, u64)],
+    ) -> Result<Vec<(Did, Result<(), ManaError>)>> {
+        let tree = self.get_tree()?;
+        let audit_tree = self.get_audit_tree()?;
+
+        // Balances and their audit entries are written in one transaction, so a
+        // crash part-way through the batch leaves neither tree half-updated
+        let outcome = (&tree, &audit_tree).transaction(|(states, audit)| {
+            let mut results = Vec::with_capacity(deductions.len());
+            for (did, amount) in deductions {
+                let key = did.to_string().into_bytes();
+                let previous = Self::read_state_tx(states, &key)?;
+                let previous_balance = previous.as_ref().map_or(0, |state| state.current_mana);
+
+                let state = match debit_record(previous, *amount) {
+                    Ok(state) => state,
+                    Err(e) => {
+                        results.push((did.clone(), Err(e)));
+                        continue;
+                    }
+                };
+                Self::write_audited_tx(
+                    states,
+                    audit,
+                    key,
+                    Self::audit_prefix(did),
+                    previous_balance,
+                    &state,
+                )?;
+                results.push((did.clone(), Ok(())));
+            }
+            Ok(results)
+        });
+
+        match outcome {
+            Ok(results) => {
+                MANA_LEDGER_OPERATIONS_TOTAL
+                    .with_label_values(&["sled", "batch_deduct", "success"])
+                    .inc();
+                Ok(results)
+            }
+            Err(e) => {
+                MANA_LEDGER_OPERATIONS_TOTAL
+                    .with_label_values(&["sled", "batch_deduct", "error"])
+                    .inc();
+                let e = match e {
+                    TransactionError::Abort(e) => e,
+                    TransactionError::Storage(e) => {
+                        anyhow!("Sled transaction I/O error in batch_deduct: {}", e)
+                    }
+                };
+                error!(error = %e, "Failed to apply mana batch deduction");
+                Err(e)
+            }
+        }
+    }
 }
 
 // Optional: Add basic unit tests for SledManaLedger here using a temporary sled DB.
@@ -284,6 +993,269 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_sled_mana_ledger_reconcile_detects_and_repairs_drift() -> Result<()> {
+        let dir = tempdir()?;
+        let ledger = SledManaLedger::open(dir.path())?;
+        let did = KeyPair::generate().did;
+        let mut state = ManaState {
+            current_mana: 100,
+            max_mana: 200,
+            last_updated_epoch: 0,
+            regen_rate_per_epoch: 5.0,
+        };
+        ledger.update_mana_state(&did, state.clone()).await?;
+        state.current_mana = 60;
+        ledger.update_mana_state(&did, state.clone()).await?;
+
+        let deltas: Vec<i128> = ledger.audit_log(&did)?.iter().map(|e| e.delta).collect();
+        assert_eq!(deltas, vec![100, -40]);
+        assert!(ledger.reconcile(false).await?.is_consistent());
+
+        // Corrupt the stored balance behind the ledger's back.
+        state.current_mana = 999;
+        ledger
+            .get_tree()?
+            .insert(did.to_string().into_bytes(), bincode::serialize(&state)?)?;
+
+        let report = ledger.reconcile(false).await?;
+        assert_eq!(report.checked, 1);
+        assert_eq!(
+            report.discrepancies,
+            vec![BalanceDiscrepancy {
+                did: did.clone(),
+                scope: None,
+                stored_balance: 999,
+                log_balance: 60,
+            }]
+        );
+        assert_eq!(report.repaired, 0);
+
+        let report = ledger.reconcile(true).await?;
+        assert_eq!(report.repaired, 1);
+        assert_eq!(ledger.get_mana_state(&did).await?.unwrap().current_mana, 60);
+        assert!(ledger.reconcile(false).await?.is_consistent());
+
+        // A balance with no audit history is reported but left alone
+        let unaudited = KeyPair::generate().did;
+        state.current_mana = 30;
+        ledger.get_tree()?.insert(
+            unaudited.to_string().into_bytes(),
+            bincode::serialize(&state)?,
+        )?;
+        let report = ledger.reconcile(true).await?;
+        assert_eq!(report.discrepancies.len(), 1);
+        assert_eq!(report.repaired, 0);
+        assert_eq!(
+            ledger
+                .get_mana_state(&unaudited)
+                .await?
+                .unwrap()
+                .current_mana,
+            30
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sled_mana_ledger_batch_deduct() -> Result<()> {
+        let dir = tempdir()?;
+        let ledger = SledManaLedger::open(dir.path())?;
+        let rich = KeyPair::generate().did;
+        let poor = KeyPair::generate().did;
+        let state = |current_mana| ManaState {
+            current_mana,
+            max_mana: 100,
+            last_updated_epoch: 0,
+            regen_rate_per_epoch: 0.0,
+        };
+        ledger.update_mana_state(&rich, state(100)).await?;
+        ledger.update_mana_state(&poor, state(5)).await?;
+
+        let results = ledger
+            .batch_deduct(&[(rich.clone(), 40), (poor.clone(), 10)])
+            .await?;
+
+        assert_eq!(results[0], (rich.clone(), Ok(())));
+        assert_eq!(
+            results[1],
+            (
+                poor.clone(),
+                Err(ManaError::InsufficientMana {
+                    requested: 10,
+                    available: 5
+                })
+            )
+        );
+        assert_eq!(ledger.get_mana_state(&rich).await?, Some(state(60)));
+        assert_eq!(ledger.get_mana_state(&poor).await?, Some(state(5)));
+
+        // Only the applied debit is audited, so balances still reconcile
+        let deltas: Vec<i128> = ledger.audit_log(&rich)?.iter().map(|e| e.delta).collect();
+        assert_eq!(deltas, vec![100, -40]);
+        assert_eq!(ledger.audit_log(&poor)?.len(), 1);
+        assert!(ledger.reconcile(false).await?.is_consistent());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sled_mana_ledger_audits_scoped_budgets() -> Result<()> {
+        let dir = tempdir()?;
+        let ledger = SledManaLedger::open(dir.path())?;
+        let did = KeyPair::generate().did;
+        let state = |current_mana| ManaState {
+            current_mana,
+            max_mana: 100,
+            last_updated_epoch: 0,
+            regen_rate_per_epoch: 0.0,
+        };
+        ledger.update_mana_state(&did, state(10)).await?;
+        ledger
+            .update_scoped_state(&did, "coop-a", state(50))
+            .await?;
+
+        assert_eq!(ledger.debit_scoped(&did, "coop-a", 20).await?, Ok(()));
+        assert_eq!(
+            ledger.debit_scoped(&did, "coop-a", 40).await?,
+            Err(ManaError::InsufficientMana {
+                requested: 40,
+                available: 30
+            })
+        );
+        assert_eq!(
+            ledger.debit_scoped(&did, "coop-b", 1).await?,
+            Err(ManaError::InsufficientMana {
+                requested: 1,
+                available: 0
+            })
+        );
+        assert_eq!(
+            ledger.get_scoped_state(&did, "coop-a").await?,
+            Some(state(30))
+        );
+        assert_eq!(ledger.get_scoped_state(&did, "coop-b").await?, None);
+        assert_eq!(
+            ledger.all_scoped().await?,
+            vec![(did.clone(), "coop-a".to_string())]
+        );
+
+        // Scoped entries stay out of the DID's default audit log
+        let deltas: Vec<i128> = ledger
+            .scoped_audit_log(&did, "coop-a")?
+            .iter()
+            .map(|e| e.delta)
+            .collect();
+        assert_eq!(deltas, vec![50, -20]);
+        assert_eq!(ledger.audit_log(&did)?.len(), 1);
+        let report = ledger.reconcile(false).await?;
+        assert_eq!(report.checked, 2);
+        assert!(report.is_consistent());
+
+        // Drift in a scoped budget is found and repaired like any other balance
+        ledger.get_scoped_tree()?.insert(
+            SledManaLedger::scoped_key(&did, "coop-a"),
+            bincode::serialize(&state(99))?,
+        )?;
+        let report = ledger.reconcile(true).await?;
+        assert_eq!(
+            report.discrepancies,
+            vec![BalanceDiscrepancy {
+                did: did.clone(),
+                scope: Some("coop-a".to_string()),
+                stored_balance: 99,
+                log_balance: 30,
+            }]
+        );
+        assert_eq!(report.repaired, 1);
+        assert_eq!(
+            ledger.get_scoped_state(&did, "coop-a").await?,
+            Some(state(30))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sled_mana_ledger_pools_are_separate_from_default() -> Result<()> {
+        let dir = tempdir()?;
+        let did = KeyPair::generate().did;
+        let state = |current_mana| ManaState {
+            current_mana,
+            max_mana: 100,
+            last_updated_epoch: 0,
+            regen_rate_per_epoch: 0.0,
+        };
+        {
+            let ledger = SledManaLedger::open(dir.path())?;
+            ledger.update_mana_state(&did, state(10)).await?;
+            ledger
+                .update_pool_state(&did, ResourceType::Cpu, state(20))
+                .await?;
+        }
+
+        let ledger = SledManaLedger::open(dir.path())?;
+        assert_eq!(ledger.get_mana_state(&did).await?, Some(state(10)));
+        assert_eq!(
+            ledger.get_pool_state(&did, ResourceType::Cpu).await?,
+            Some(state(20))
+        );
+        assert_eq!(ledger.get_pool_state(&did, ResourceType::Memory).await?, None);
+        assert_eq!(ledger.all_pools().await?, vec![(did.clone(), ResourceType::Cpu)]);
+        assert_eq!(ledger.all_dids().await?, vec![did]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sled_mana_ledger_migrates_single_pool_store() -> Result<()> {
+        let dir = tempdir()?;
+        let did = KeyPair::generate().did;
+        let state = ManaState {
+            current_mana: 42,
+            max_mana: 100,
+            last_updated_epoch: 7,
+            regen_rate_per_epoch: 1.0,
+        };
+        {
+            // A version 1 store: balances only, no recorded schema version
+            let db = sled::open(dir.path())?;
+            db.open_tree(MANA_STATE_TREE_NAME)?
+                .insert(did.to_string().into_bytes(), bincode::serialize(&state)?)?;
+            // and a version 3 scoped budget, stored before scoped budgets were audited
+            db.open_tree(MANA_SCOPED_TREE_NAME)?.insert(
+                SledManaLedger::scoped_key(&did, "coop-a"),
+                bincode::serialize(&state)?,
+            )?;
+            db.flush()?;
+        }
+
+        let ledger = SledManaLedger::open(dir.path())?;
+        assert_eq!(ledger.schema_version()?, MANA_SCHEMA_VERSION);
+        assert_eq!(ledger.get_mana_state(&did).await?, Some(state.clone()));
+        assert!(ledger.all_pools().await?.is_empty());
+
+        // The pre-audit balance gets an opening entry, so repair keeps it
+        let deltas: Vec<i128> = ledger.audit_log(&did)?.iter().map(|e| e.delta).collect();
+        assert_eq!(deltas, vec![42]);
+        let deltas: Vec<i128> = ledger
+            .scoped_audit_log(&did, "coop-a")?
+            .iter()
+            .map(|e| e.delta)
+            .collect();
+        assert_eq!(deltas, vec![42]);
+        assert!(ledger.reconcile(true).await?.is_consistent());
+        assert_eq!(ledger.get_mana_state(&did).await?, Some(state));
+        drop(ledger);
+
+        // Stores from a newer build are refused rather than misread
+        {
+            let db = sled::open(dir.path())?;
+            db.open_tree(MANA_META_TREE_NAME)?
+                .insert(SCHEMA_VERSION_KEY, (MANA_SCHEMA_VERSION + 1).to_be_bytes().to_vec())?;
+            db.flush()?;
+        }
+        assert!(SledManaLedger::open(dir.path()).is_err());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_sled_mana_ledger_update_existing() -> Result<()> {
         let dir = tempdir()?;
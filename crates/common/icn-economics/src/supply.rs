@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use icn_types::resource::ResourceType;
+use sled::Db;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+const SUPPLY_TREE_NAME: &str = "minted_supply";
+
+/// Where the total minted supply of each resource type is kept.
+///
+/// The supply cap is checked against this, so a store that survives restarts
+/// keeps the cap meaningful across them.
+#[async_trait]
+pub trait SupplyStore: Send + Sync {
+    /// Total of `resource` minted so far; 0 if none has been
+    async fn minted(&self, resource: ResourceType) -> Result<u64>;
+
+    /// Record `total` as the minted supply of `resource`
+    async fn set_minted(&self, resource: ResourceType, total: u64) -> Result<()>;
+}
+
+/// Keeps the minted supply in memory; it starts from zero in every process.
+#[derive(Debug, Default)]
+pub struct InMemorySupplyStore {
+    minted: Mutex<HashMap<ResourceType, u64>>,
+}
+
+impl InMemorySupplyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SupplyStore for InMemorySupplyStore {
+    async fn minted(&self, resource: ResourceType) -> Result<u64> {
+        Ok(self
+            .minted
+            .lock()
+            .await
+            .get(&resource)
+            .copied()
+            .unwrap_or(0))
+    }
+
+    async fn set_minted(&self, resource: ResourceType, total: u64) -> Result<()> {
+        self.minted.lock().await.insert(resource, total);
+        Ok(())
+    }
+}
+
+/// Persists the minted supply in a Sled tree keyed by resource type.
+#[derive(Clone)]
+pub struct SledSupplyStore {
+    db: Db,
+}
+
+impl SledSupplyStore {
+    /// Opens or creates a Sled database at the given path for the minted supply.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let db = sled::open(path).context("Failed to open Sled database for minted supply")?;
+        db.open_tree(SUPPLY_TREE_NAME)
+            .context("Failed to open minted_supply tree in Sled database")?;
+        Ok(Self { db })
+    }
+
+    fn tree(&self) -> Result<sled::Tree> {
+        self.db
+            .open_tree(SUPPLY_TREE_NAME)
+            .context("Failed to access minted_supply tree in Sled database")
+    }
+
+    fn key(resource: ResourceType) -> [u8; 4] {
+        (resource as u32).to_be_bytes()
+    }
+}
+
+#[async_trait]
+impl SupplyStore for SledSupplyStore {
+    async fn minted(&self, resource: ResourceType) -> Result<u64> {
+        let Some(bytes) = self
+            .tree()?
+            .get(Self::key(resource))
+            .context("Failed to read minted supply from Sled")?
+        else {
+            return Ok(0);
+        };
+        let bytes: [u8; 8] = bytes
+            .as_ref()
+            .try_into()
+            .context("Stored minted supply is not a u64")?;
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    async fn set_minted(&self, resource: ResourceType, total: u64) -> Result<()> {
+        let tree = self.tree()?;
+        tree.insert(Self::key(resource), &total.to_be_bytes())
+            .context("Failed to store minted supply in Sled")?;
+        tree.flush_async()
+            .await
+            .context("Failed to flush minted supply to disk")?;
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use icn_economics::{Economics, LedgerKey, ResourceAuthorizationPolicy, ResourceType};
+use icn_economics::{
+    Economics, LedgerKey, ResourceAuthorizationPolicy, ResourceType, SledSupplyStore,
+};
 use icn_identity::KeyPair;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
@@ -183,3 +185,381 @@ async fn test_transfer_insufficient_funds() {
         );
     }
 }
+
+#[tokio::test]
+async fn regenerator_stamps_epoch_from_clock() {
+    use icn_economics::mana::{InMemoryManaLedger, ManaLedger, ManaRegenerator, ManaState, RegenerationPolicy};
+    use icn_types::clock::MockClock;
+    use std::sync::Arc;
+
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let did = KeyPair::generate().did;
+    ledger
+        .update_mana_state(
+            &did,
+            ManaState {
+                current_mana: 10,
+                max_mana: 100,
+                regen_rate_per_epoch: 1.0,
+                last_updated_epoch: 0,
+            },
+        )
+        .await
+        .unwrap();
+
+    let clock = MockClock::at_epoch(5_000);
+    let regenerator = ManaRegenerator::with_clock(
+        ledger.clone(),
+        RegenerationPolicy::FixedRatePerTick(5),
+        Arc::new(clock.clone()),
+    );
+
+    regenerator.tick().await.unwrap();
+    let state = ledger.get_mana_state(&did).await.unwrap().unwrap();
+    assert_eq!(state.current_mana, 15);
+    assert_eq!(state.last_updated_epoch, 5_000);
+
+    clock.advance_secs(30);
+    regenerator.tick().await.unwrap();
+    let state = ledger.get_mana_state(&did).await.unwrap().unwrap();
+    assert_eq!(state.last_updated_epoch, 5_030);
+}
+
+/// Tick `policy` once at `epoch` over a ledger holding only `state`, returning
+/// the state afterwards and how many DIDs regenerated
+async fn tick_once(
+    policy: icn_economics::mana::RegenerationPolicy,
+    state: icn_economics::mana::ManaState,
+    epoch: u64,
+) -> (icn_economics::mana::ManaState, usize) {
+    use icn_economics::mana::{InMemoryManaLedger, ManaLedger, ManaRegenerator};
+    use icn_types::clock::MockClock;
+    use std::sync::Arc;
+
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let did = KeyPair::generate().did;
+    ledger.update_mana_state(&did, state).await.unwrap();
+    let regenerator =
+        ManaRegenerator::with_clock(ledger.clone(), policy, Arc::new(MockClock::at_epoch(epoch)));
+
+    let details = regenerator.tick().await.unwrap();
+    let state = ledger.get_mana_state(&did).await.unwrap().unwrap();
+    (state, details.regenerated_dids_count)
+}
+
+fn mana_state(
+    current_mana: u64,
+    max_mana: u64,
+    last_updated_epoch: u64,
+) -> icn_economics::mana::ManaState {
+    icn_economics::mana::ManaState {
+        current_mana,
+        max_mana,
+        regen_rate_per_epoch: 0.0,
+        last_updated_epoch,
+    }
+}
+
+#[tokio::test]
+async fn proportional_policy_regenerates_a_fraction_of_max() {
+    use icn_economics::mana::RegenerationPolicy;
+    let policy = RegenerationPolicy::ProportionalToMax {
+        fraction_per_tick: 0.1,
+    };
+
+    let (state, regenerated) = tick_once(policy.clone(), mana_state(10, 200, 0), 1_000).await;
+    assert_eq!(state.current_mana, 30);
+    assert_eq!(state.last_updated_epoch, 1_000);
+    assert_eq!(regenerated, 1);
+
+    // 20 would be due, but only 5 fit under the cap
+    let (state, _) = tick_once(policy, mana_state(195, 200, 0), 1_000).await;
+    assert_eq!(state.current_mana, 200);
+}
+
+#[tokio::test]
+async fn elapsed_time_policy_regenerates_for_seconds_since_last_update() {
+    use icn_economics::mana::RegenerationPolicy;
+    let policy = RegenerationPolicy::ElapsedTime {
+        rate_per_second: 0.5,
+    };
+
+    let (state, regenerated) = tick_once(policy.clone(), mana_state(10, 100, 1_000), 1_040).await;
+    assert_eq!(state.current_mana, 30);
+    assert_eq!(state.last_updated_epoch, 1_040);
+    assert_eq!(regenerated, 1);
+
+    // Under a whole mana has accrued, which is dropped as the epoch advances
+    let (state, regenerated) = tick_once(policy.clone(), mana_state(10, 100, 1_000), 1_001).await;
+    assert_eq!(state.current_mana, 10);
+    assert_eq!(state.last_updated_epoch, 1_001);
+    assert_eq!(regenerated, 0);
+
+    // An hour's worth is capped at max_mana
+    let (state, _) = tick_once(policy, mana_state(10, 100, 1_000), 4_600).await;
+    assert_eq!(state.current_mana, 100);
+    assert_eq!(state.last_updated_epoch, 4_600);
+}
+
+#[tokio::test]
+async fn did_at_max_is_left_untouched_by_every_policy() {
+    use icn_economics::mana::RegenerationPolicy;
+
+    for policy in [
+        RegenerationPolicy::FixedRatePerTick(5),
+        RegenerationPolicy::ProportionalToMax {
+            fraction_per_tick: 0.5,
+        },
+        RegenerationPolicy::ElapsedTime {
+            rate_per_second: 2.0,
+        },
+    ] {
+        let (state, regenerated) =
+            tick_once(policy.clone(), mana_state(100, 100, 1_000), 2_000).await;
+        assert_eq!(state.current_mana, 100, "{:?}", policy);
+        assert_eq!(regenerated, 0, "{:?}", policy);
+    }
+}
+
+#[tokio::test]
+async fn elapsed_time_spent_at_max_does_not_count_after_a_spend() {
+    use icn_economics::mana::{
+        InMemoryManaLedger, ManaLedger, ManaRegenerator, RegenerationPolicy,
+    };
+    use icn_types::clock::MockClock;
+    use std::sync::Arc;
+
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let did = KeyPair::generate().did;
+    ledger
+        .set_initial_state(did.clone(), mana_state(100, 100, 1_000))
+        .await;
+    let clock = MockClock::at_epoch(2_000);
+    let regenerator = ManaRegenerator::with_clock(
+        ledger.clone(),
+        RegenerationPolicy::ElapsedTime {
+            rate_per_second: 1.0,
+        },
+        Arc::new(clock.clone()),
+    );
+
+    // A full balance is still stamped, so its idle time is used up here
+    regenerator.tick().await.unwrap();
+    assert_eq!(
+        ledger.get_mana_state(&did).await.unwrap().unwrap(),
+        mana_state(100, 100, 2_000)
+    );
+
+    let mut state = ledger.get_mana_state(&did).await.unwrap().unwrap();
+    state.debit(60).unwrap();
+    ledger.update_mana_state(&did, state).await.unwrap();
+
+    clock.advance_secs(10);
+    let details = regenerator.tick().await.unwrap();
+    assert_eq!(details.regenerated_dids_count, 1);
+    assert_eq!(
+        ledger.get_mana_state(&did).await.unwrap().unwrap(),
+        mana_state(50, 100, 2_010)
+    );
+}
+
+#[tokio::test]
+async fn resource_pools_regenerate_independently() {
+    use icn_economics::mana::{InMemoryManaLedger, ManaLedger, ManaRegenerator, RegenerationPolicy};
+    use icn_types::clock::MockClock;
+    use std::sync::Arc;
+
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let did = KeyPair::generate().did;
+    ledger.set_initial_state(did.clone(), mana_state(10, 100, 0)).await;
+    ledger
+        .update_pool_state(&did, ResourceType::Cpu, mana_state(0, 100, 0))
+        .await
+        .unwrap();
+    ledger
+        .update_pool_state(&did, ResourceType::Memory, mana_state(0, 1_000, 0))
+        .await
+        .unwrap();
+
+    let regenerator = ManaRegenerator::with_clock(
+        ledger.clone(),
+        RegenerationPolicy::FixedRatePerTick(1),
+        Arc::new(MockClock::at_epoch(1_000)),
+    );
+    regenerator.set_pool_policy(ResourceType::Cpu, RegenerationPolicy::FixedRatePerTick(30));
+    regenerator.set_pool_policy(
+        ResourceType::Memory,
+        RegenerationPolicy::ProportionalToMax {
+            fraction_per_tick: 0.25,
+        },
+    );
+
+    for _ in 0..2 {
+        let details = regenerator.tick().await.unwrap();
+        assert_eq!(details.processed_pools_count, 2);
+        assert_eq!(details.regenerated_pools_count, 2);
+    }
+
+    let cpu = ledger.get_pool_state(&did, ResourceType::Cpu).await.unwrap().unwrap();
+    assert_eq!(cpu.current_mana, 60);
+    let memory = ledger.get_pool_state(&did, ResourceType::Memory).await.unwrap().unwrap();
+    assert_eq!(memory.current_mana, 500);
+    // The default pool keeps its own policy
+    let default_pool = ledger.get_mana_state(&did).await.unwrap().unwrap();
+    assert_eq!(default_pool.current_mana, 12);
+    // Pools without a policy of their own fall back to the default one
+    assert!(matches!(
+        regenerator.pool_policy(ResourceType::Io),
+        RegenerationPolicy::FixedRatePerTick(1)
+    ));
+}
+
+#[tokio::test]
+async fn scoped_budgets_regenerate_under_the_default_policy() {
+    use icn_economics::mana::{
+        InMemoryManaLedger, ManaLedger, ManaRegenerator, RegenerationPolicy,
+    };
+    use icn_types::clock::MockClock;
+    use std::sync::Arc;
+
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let did = KeyPair::generate().did;
+    ledger
+        .update_scoped_state(&did, "coop-a", mana_state(0, 100, 0))
+        .await
+        .unwrap();
+    ledger
+        .update_scoped_state(&did, "coop-b", mana_state(95, 100, 0))
+        .await
+        .unwrap();
+    let regenerator = ManaRegenerator::with_clock(
+        ledger.clone(),
+        RegenerationPolicy::FixedRatePerTick(10),
+        Arc::new(MockClock::at_epoch(1_000)),
+    );
+
+    let details = regenerator.tick().await.unwrap();
+    assert_eq!(details.processed_scoped_count, 2);
+    assert_eq!(details.regenerated_scoped_count, 2);
+    let budget = |scope: &'static str| {
+        let ledger = ledger.clone();
+        let did = did.clone();
+        async move { ledger.get_scoped_state(&did, scope).await.unwrap().unwrap() }
+    };
+    assert_eq!(budget("coop-a").await.current_mana, 10);
+    assert_eq!(budget("coop-b").await.current_mana, 100);
+
+    let details = regenerator.tick().await.unwrap();
+    assert_eq!(details.regenerated_scoped_count, 1);
+    assert_eq!(budget("coop-a").await.current_mana, 20);
+}
+
+#[tokio::test]
+async fn batch_deduct_reports_each_did_without_rolling_back_others() {
+    use icn_economics::mana::{InMemoryManaLedger, ManaError, ManaLedger};
+
+    let ledger = InMemoryManaLedger::new();
+    let rich = KeyPair::generate().did;
+    let poor = KeyPair::generate().did;
+    let unknown = KeyPair::generate().did;
+    ledger.set_initial_state(rich.clone(), mana_state(100, 100, 0)).await;
+    ledger.set_initial_state(poor.clone(), mana_state(5, 100, 0)).await;
+
+    let results = ledger
+        .batch_deduct(&[
+            (rich.clone(), 30),
+            (poor.clone(), 10),
+            (unknown.clone(), 1),
+            (rich.clone(), 20),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        results,
+        vec![
+            (rich.clone(), Ok(())),
+            (
+                poor.clone(),
+                Err(ManaError::InsufficientMana {
+                    requested: 10,
+                    available: 5
+                })
+            ),
+            (
+                unknown.clone(),
+                Err(ManaError::InsufficientMana {
+                    requested: 1,
+                    available: 0
+                })
+            ),
+            (rich.clone(), Ok(())),
+        ]
+    );
+    let rich_state = ledger.get_mana_state(&rich).await.unwrap().unwrap();
+    assert_eq!(rich_state.current_mana, 50);
+    let poor_state = ledger.get_mana_state(&poor).await.unwrap().unwrap();
+    assert_eq!(poor_state.current_mana, 5);
+    assert_eq!(ledger.get_mana_state(&unknown).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn mint_respects_supply_cap() {
+    let econ = Economics::new(ResourceAuthorizationPolicy::default());
+    econ.set_supply_cap(Some(100));
+    let did = KeyPair::generate().did;
+    let ledger = RwLock::new(HashMap::new());
+
+    assert_eq!(econ.mint(&did, None, None, ResourceType::Token, 60, &ledger).await, 0);
+    assert_eq!(econ.mint(&did, None, None, ResourceType::Token, 41, &ledger).await, -4);
+    assert_eq!(econ.mint(&did, None, None, ResourceType::Token, 40, &ledger).await, 0);
+    assert_eq!(econ.minted_supply(ResourceType::Token).await.unwrap(), 100);
+
+    econ.set_supply_cap(None);
+    assert_eq!(econ.mint(&did, None, None, ResourceType::Token, 1, &ledger).await, 0);
+    assert_eq!(econ.minted_supply(ResourceType::Token).await.unwrap(), 101);
+}
+
+#[tokio::test]
+async fn abandoned_mint_records_no_supply() {
+    let econ = Economics::new(ResourceAuthorizationPolicy::default());
+    econ.set_supply_cap(Some(100));
+    let did = KeyPair::generate().did;
+    let ledger = RwLock::new(HashMap::new());
+
+    let pending = econ.begin_mint(ResourceType::Token, 80).await.unwrap();
+    drop(pending);
+    assert_eq!(econ.minted_supply(ResourceType::Token).await.unwrap(), 0);
+
+    assert!(econ.begin_mint(ResourceType::Cpu, 1).await.is_err());
+    let pending = econ.begin_mint(ResourceType::Token, 100).await.unwrap();
+    pending.commit(&did, None, None, &ledger).await.unwrap();
+    assert_eq!(econ.minted_supply(ResourceType::Token).await.unwrap(), 100);
+}
+
+#[tokio::test]
+async fn minted_supply_survives_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let did = KeyPair::generate().did;
+    let ledger = RwLock::new(HashMap::new());
+    {
+        let store = std::sync::Arc::new(SledSupplyStore::open(dir.path()).unwrap());
+        let econ = Economics::new(ResourceAuthorizationPolicy::default()).with_supply_store(store);
+        econ.set_supply_cap(Some(100));
+        assert_eq!(
+            econ.mint(&did, None, None, ResourceType::Token, 70, &ledger)
+                .await,
+            0
+        );
+    }
+
+    let store = std::sync::Arc::new(SledSupplyStore::open(dir.path()).unwrap());
+    let econ = Economics::new(ResourceAuthorizationPolicy::default()).with_supply_store(store);
+    econ.set_supply_cap(Some(100));
+    assert_eq!(econ.minted_supply(ResourceType::Token).await.unwrap(), 70);
+    assert_eq!(
+        econ.mint(&did, None, None, ResourceType::Token, 31, &ledger)
+            .await,
+        -4
+    );
+}
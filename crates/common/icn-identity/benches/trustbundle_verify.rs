@@ -11,6 +11,7 @@ fn bench_trustbundle_verify(c: &mut Criterion) {
         name: "Benchmark Federation".to_string(),
         description: Some("A federation for benchmarking".to_string()),
         version: "1.0".to_string(),
+        max_token_supply: None,
         additional: HashMap::new(),
     };
 
@@ -1,10 +1,75 @@
 use crate::Did;
 use ed25519_dalek::{Signer, Verifier};
+use multibase::Base;
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
 
 pub type Signature = ed25519_dalek::Signature;
 
+/// JSON Web Key for an Ed25519 key (RFC 8037 `OKP` key type).
+///
+/// `x` and `d` are unpadded base64url; `d` is only present on private keys.
+/// `Debug` output redacts `d`.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub d: Option<String>,
+}
+
+impl fmt::Debug for Jwk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Jwk")
+            .field("kty", &self.kty)
+            .field("crv", &self.crv)
+            .field("x", &self.x)
+            .field("d", &self.d.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// Errors converting a [`Jwk`] into a [`KeyPair`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum JwkError {
+    #[error("Unsupported JWK key type: expected 'OKP', found '{0}'")]
+    UnsupportedKeyType(String),
+
+    #[error("Unsupported JWK curve: expected 'Ed25519', found '{0}'")]
+    UnsupportedCurve(String),
+
+    #[error("JWK has no private key component 'd'")]
+    MissingPrivateKey,
+
+    #[error("JWK field '{field}' is not valid base64url: {reason}")]
+    InvalidEncoding { field: &'static str, reason: String },
+
+    #[error("JWK field '{field}' must decode to 32 bytes, found {found_len}")]
+    InvalidKeyLength { field: &'static str, found_len: usize },
+
+    #[error("JWK public key 'x' does not match the private key 'd'")]
+    KeyMismatch,
+}
+
+const JWK_KTY: &str = "OKP";
+const JWK_CRV: &str = "Ed25519";
+
+fn decode_jwk_field(field: &'static str, value: &str) -> Result<[u8; 32], JwkError> {
+    let bytes = Base::Base64Url
+        .decode(value)
+        .map_err(|e| JwkError::InvalidEncoding {
+            field,
+            reason: e.to_string(),
+        })?;
+    let found_len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| JwkError::InvalidKeyLength { field, found_len })
+}
+
 /// Ed25519 keypair bound to a DID.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct KeyPair {
@@ -37,4 +102,55 @@ impl KeyPair {
     pub fn to_bytes(&self) -> [u8; 32] {
         self.sk.to_bytes()
     }
+
+    /// Rebuild a keypair from the secret key bytes returned by [`KeyPair::to_bytes`]
+    pub fn from_bytes(secret: &[u8; 32]) -> Self {
+        let sk = ed25519_dalek::SigningKey::from_bytes(secret);
+        let pk = sk.verifying_key();
+        let did = Did::new_ed25519(&pk);
+        Self { did, pk, sk }
+    }
+
+    /// X25519 secret matching the Montgomery form of this keypair's public key
+    pub(crate) fn x25519_secret(&self) -> x25519_dalek::StaticSecret {
+        x25519_dalek::StaticSecret::from(self.sk.to_scalar_bytes())
+    }
+
+    /// Export the public key as an OKP Ed25519 JWK.
+    pub fn to_public_jwk(&self) -> Jwk {
+        Jwk {
+            kty: JWK_KTY.to_string(),
+            crv: JWK_CRV.to_string(),
+            x: Base::Base64Url.encode(self.pk.as_bytes()),
+            d: None,
+        }
+    }
+
+    /// Export the full keypair as an OKP Ed25519 JWK, including `d`.
+    pub fn to_private_jwk(&self) -> Jwk {
+        Jwk {
+            d: Some(Base::Base64Url.encode(self.sk.to_bytes())),
+            ..self.to_public_jwk()
+        }
+    }
+
+    /// Rebuild a keypair from a private OKP Ed25519 JWK.
+    ///
+    /// The public key is derived from `d` and must match `x`.
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, JwkError> {
+        if jwk.kty != JWK_KTY {
+            return Err(JwkError::UnsupportedKeyType(jwk.kty.clone()));
+        }
+        if jwk.crv != JWK_CRV {
+            return Err(JwkError::UnsupportedCurve(jwk.crv.clone()));
+        }
+        let d = jwk.d.as_deref().ok_or(JwkError::MissingPrivateKey)?;
+        let sk = ed25519_dalek::SigningKey::from_bytes(&decode_jwk_field("d", d)?);
+        let pk = sk.verifying_key();
+        if pk.to_bytes() != decode_jwk_field("x", &jwk.x)? {
+            return Err(JwkError::KeyMismatch);
+        }
+        let did = Did::new_ed25519(&pk);
+        Ok(Self { did, pk, sk })
+    }
 }
@@ -1,9 +1,10 @@
 //! ICN Identity – DID & key tooling for the InterCooperative Network.
 //!
 //! - Supports `did:key` using Ed25519 (`multicodec: 0xED`, `multibase: Z-base58`).
-//! - Provides `KeyPair` generation, signing, and verification.
+//! - Provides `KeyPair` generation, signing, verification, and JWK import/export.
 //! - Implements Verifiable Credentials with canonical serialization.
 //! - Provides QuorumProof and TrustBundle for federation governance.
+//! - Seals payloads to `did:key` recipients via their X25519 form.
 //! - Zero `unsafe`; Clippy-clean; `#![forbid(unsafe_code)]`.
 
 #![forbid(unsafe_code)]
@@ -13,6 +14,8 @@ mod identity_index;
 mod keypair;
 mod quorum;
 mod scope_key;
+mod sealed;
+mod tagged_signature;
 #[cfg(test)]
 mod tests;
 mod trust_bundle;
@@ -21,9 +24,11 @@ mod vc;
 
 pub use did::{Did, DidError};
 pub use identity_index::IdentityIndex;
-pub use keypair::{KeyPair, Signature};
+pub use keypair::{Jwk, JwkError, KeyPair, Signature};
 pub use quorum::{QuorumError, QuorumProof, QuorumType};
 pub use scope_key::ScopeKey;
-pub use trust_bundle::{FederationMetadata, TrustBundle, TrustBundleError};
+pub use sealed::{RecipientKey, SealError, SealedPayload};
+pub use tagged_signature::{SignatureAlgorithm, TaggedSignature, TaggedSignatureError};
+pub use trust_bundle::{FederationMetadata, TrustBundle, TrustBundleError, TRUST_BUNDLE_VERSION};
 pub use trust_validator::{TrustValidationError, TrustValidator};
 pub use vc::{CredentialError, Proof, SignedCredential, VerifiableCredential};
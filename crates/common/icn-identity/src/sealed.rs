@@ -0,0 +1,166 @@
+//! Payload encryption to `did:key` recipients.
+//!
+//! An Ed25519 `did:key` converts to an X25519 key, so data can be sealed to any
+//! DID without separate key distribution. The payload is encrypted once under a
+//! random content key with ChaCha20-Poly1305, and that content key is wrapped
+//! for each recipient through an ephemeral X25519 exchange.
+
+use crate::{Did, KeyPair};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+const NONCE_LEN: usize = 12;
+const KEY_WRAP_CONTEXT: &[u8] = b"icn-sealed-payload-v1";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SealError {
+    #[error("a sealed payload needs at least one recipient")]
+    NoRecipients,
+
+    #[error("recipient '{0}' is not a usable Ed25519 did:key")]
+    InvalidRecipient(String),
+
+    #[error("payload is not sealed to '{0}'")]
+    NotARecipient(String),
+
+    #[error("payload could not be decrypted")]
+    DecryptionFailed,
+}
+
+/// The content key wrapped for one recipient
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecipientKey {
+    pub recipient: Did,
+    /// Sender's one-time X25519 public key for this recipient
+    pub ephemeral_public: [u8; 32],
+    pub nonce: [u8; NONCE_LEN],
+    pub wrapped_key: Vec<u8>,
+}
+
+/// Ciphertext readable only by the listed recipients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedPayload {
+    pub nonce: [u8; NONCE_LEN],
+    pub ciphertext: Vec<u8>,
+    pub recipients: Vec<RecipientKey>,
+}
+
+impl SealedPayload {
+    /// Encrypt `plaintext` so that each DID in `recipients` can open it.
+    pub fn seal(plaintext: &[u8], recipients: &[Did]) -> Result<Self, SealError> {
+        if recipients.is_empty() {
+            return Err(SealError::NoRecipients);
+        }
+
+        let mut content_key = [0u8; 32];
+        OsRng.fill_bytes(&mut content_key);
+        let nonce = random_nonce();
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&content_key))
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .expect("ChaCha20-Poly1305 encryption of an in-memory buffer cannot fail");
+
+        let recipients = recipients
+            .iter()
+            .map(|did| wrap_content_key(&content_key, did))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            nonce,
+            ciphertext,
+            recipients,
+        })
+    }
+
+    /// Decrypt the payload with the key of one of its recipients.
+    pub fn open(&self, keypair: &KeyPair) -> Result<Vec<u8>, SealError> {
+        let entry = self
+            .recipients
+            .iter()
+            .find(|r| r.recipient == keypair.did)
+            .ok_or_else(|| SealError::NotARecipient(keypair.did.to_string()))?;
+
+        let secret = keypair.x25519_secret();
+        let recipient_public = PublicKey::from(&secret);
+        let shared = secret.diffie_hellman(&PublicKey::from(entry.ephemeral_public));
+        let wrapping_key = derive_wrapping_key(
+            shared.as_bytes(),
+            &entry.ephemeral_public,
+            recipient_public.as_bytes(),
+        );
+
+        let content_key = ChaCha20Poly1305::new(Key::from_slice(&wrapping_key))
+            .decrypt(
+                Nonce::from_slice(&entry.nonce),
+                entry.wrapped_key.as_slice(),
+            )
+            .map_err(|_| SealError::DecryptionFailed)?;
+        if content_key.len() != 32 {
+            return Err(SealError::DecryptionFailed);
+        }
+
+        ChaCha20Poly1305::new(Key::from_slice(&content_key))
+            .decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_slice())
+            .map_err(|_| SealError::DecryptionFailed)
+    }
+}
+
+fn wrap_content_key(content_key: &[u8; 32], did: &Did) -> Result<RecipientKey, SealError> {
+    let invalid = || SealError::InvalidRecipient(did.to_string());
+    let recipient_public = PublicKey::from(
+        did.to_ed25519()
+            .map_err(|_| invalid())?
+            .to_montgomery()
+            .to_bytes(),
+    );
+
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(&recipient_public);
+    // A low-order recipient key would give every sender the same shared secret
+    if !shared.was_contributory() {
+        return Err(invalid());
+    }
+
+    let wrapping_key = derive_wrapping_key(
+        shared.as_bytes(),
+        ephemeral_public.as_bytes(),
+        recipient_public.as_bytes(),
+    );
+    let nonce = random_nonce();
+    let wrapped_key = ChaCha20Poly1305::new(Key::from_slice(&wrapping_key))
+        .encrypt(Nonce::from_slice(&nonce), content_key.as_slice())
+        .expect("ChaCha20-Poly1305 encryption of an in-memory buffer cannot fail");
+
+    Ok(RecipientKey {
+        recipient: did.clone(),
+        ephemeral_public: ephemeral_public.to_bytes(),
+        nonce,
+        wrapped_key,
+    })
+}
+
+/// Bind the wrapping key to both public keys so it is unique per exchange
+fn derive_wrapping_key(
+    shared: &[u8; 32],
+    ephemeral_public: &[u8; 32],
+    recipient_public: &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(KEY_WRAP_CONTEXT);
+    hasher.update(shared);
+    hasher.update(ephemeral_public);
+    hasher.update(recipient_public);
+    hasher.finalize().into()
+}
+
+fn random_nonce() -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    nonce
+}
@@ -0,0 +1,132 @@
+use crate::{Did, DidError, Signature};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Signature algorithms a [`TaggedSignature`] can carry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureAlgorithm {
+    Ed25519,
+}
+
+impl fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureAlgorithm::Ed25519 => write!(f, "ed25519"),
+        }
+    }
+}
+
+/// Errors verifying a [`TaggedSignature`].
+#[derive(Error, Debug)]
+pub enum TaggedSignatureError {
+    #[error("{alg} signature has an invalid format: {reason}")]
+    InvalidFormat {
+        alg: SignatureAlgorithm,
+        reason: String,
+    },
+
+    #[error("Failed to resolve {alg} key from signer DID: {source}")]
+    UnsupportedSigner {
+        alg: SignatureAlgorithm,
+        #[source]
+        source: DidError,
+    },
+
+    #[error("{alg} signature verification failed: {reason}")]
+    VerificationFailed {
+        alg: SignatureAlgorithm,
+        reason: String,
+    },
+}
+
+/// A signature tagged with the algorithm that produced it.
+///
+/// Older records stored signatures as bare byte arrays; those still deserialize
+/// and are interpreted as Ed25519, the only algorithm in use before tagging.
+/// An empty `bytes` value means "not yet signed".
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct TaggedSignature {
+    pub alg: SignatureAlgorithm,
+    pub bytes: Vec<u8>,
+}
+
+impl TaggedSignature {
+    /// Tag an Ed25519 signature.
+    pub fn ed25519(sig: &Signature) -> Self {
+        Self {
+            alg: SignatureAlgorithm::Ed25519,
+            bytes: sig.to_bytes().to_vec(),
+        }
+    }
+
+    /// Interpret untagged legacy signature bytes, which are always Ed25519.
+    pub fn from_legacy_bytes(bytes: Vec<u8>) -> Self {
+        Self {
+            alg: SignatureAlgorithm::Ed25519,
+            bytes,
+        }
+    }
+
+    /// Raw signature bytes, without the algorithm tag.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Whether this is an empty placeholder rather than an actual signature.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Verify `msg` against the key embedded in `signer`, dispatching on `alg`.
+    pub fn verify(&self, signer: &Did, msg: &[u8]) -> Result<(), TaggedSignatureError> {
+        match self.alg {
+            SignatureAlgorithm::Ed25519 => {
+                let alg = self.alg;
+                let sig = Signature::from_slice(&self.bytes).map_err(|e| {
+                    TaggedSignatureError::InvalidFormat {
+                        alg,
+                        reason: e.to_string(),
+                    }
+                })?;
+                let pk = signer
+                    .to_ed25519()
+                    .map_err(|source| TaggedSignatureError::UnsupportedSigner { alg, source })?;
+                pk.verify_strict(msg, &sig)
+                    .map_err(|e| TaggedSignatureError::VerificationFailed {
+                        alg,
+                        reason: e.to_string(),
+                    })
+            }
+        }
+    }
+}
+
+impl Default for TaggedSignature {
+    fn default() -> Self {
+        Self::from_legacy_bytes(Vec::new())
+    }
+}
+
+impl From<Signature> for TaggedSignature {
+    fn from(sig: Signature) -> Self {
+        Self::ed25519(&sig)
+    }
+}
+
+impl<'de> Deserialize<'de> for TaggedSignature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Tagged { alg: SignatureAlgorithm, bytes: Vec<u8> },
+            Legacy(Vec<u8>),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Tagged { alg, bytes } => TaggedSignature { alg, bytes },
+            Repr::Legacy(bytes) => TaggedSignature::from_legacy_bytes(bytes),
+        })
+    }
+}
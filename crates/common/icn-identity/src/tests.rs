@@ -1,7 +1,11 @@
-use crate::{Did, KeyPair, VerifiableCredential};
-use crate::{FederationMetadata, TrustBundle};
+use crate::{Did, Jwk, JwkError, KeyPair, VerifiableCredential};
+use crate::{SignatureAlgorithm, TaggedSignature, TaggedSignatureError};
+use crate::{FederationMetadata, TrustBundle, TRUST_BUNDLE_VERSION};
 use crate::{QuorumError, QuorumProof, QuorumType};
+use crate::{SealError, SealedPayload};
+use crate::{TrustValidationError, TrustValidator};
 use std::collections::HashMap;
+use std::str::FromStr;
 
 #[test]
 fn did_round_trip_ed25519() {
@@ -249,6 +253,7 @@ fn trust_bundle_verify() {
         name: "Test Federation".to_string(),
         description: Some("A test federation for unit tests".to_string()),
         version: "1.0".to_string(),
+        max_token_supply: None,
         additional: HashMap::new(),
     };
 
@@ -291,3 +296,242 @@ fn trust_bundle_verify() {
     // Verification should fail for the tampered bundle
     assert!(tampered_bundle.verify(&signer_keys).is_err());
 }
+
+// Bundle written by a binary predating the `version` field, signed by two of
+// the three fixture signers below.
+const LEGACY_BUNDLE_V0: &str = include_str!("../tests/fixtures/trust_bundle_v0.json");
+const LEGACY_SIGNERS: [&str; 3] = [
+    "did:key:z2Dbrt3VFRC6Qsb3KFcCTceJqFP1qDeTQ9Ly95Fh7TkrBMR",
+    "did:key:z2DWoXa6WeuSB39G54Kw59W5zohriaeoqXxmyC2nynLM79b",
+    "did:key:z2DYGHPSWPi6iijrmxweXeMGHJzyzgaq5PFxRH6Aq4cTgEP",
+];
+
+#[test]
+fn trust_bundle_migrates_v0_layout() {
+    let bundle: TrustBundle = serde_json::from_str(LEGACY_BUNDLE_V0).unwrap();
+    assert_eq!(bundle.version, TRUST_BUNDLE_VERSION);
+    assert_eq!(bundle.federation_metadata.name, "Legacy Federation");
+    assert_eq!(bundle.quorum_proof.as_ref().unwrap().signatures.len(), 2);
+
+    // Signatures made before the upgrade still verify
+    let signer_keys: HashMap<Did, _> = LEGACY_SIGNERS
+        .iter()
+        .map(|s| {
+            let did = Did::from_str(s).unwrap();
+            let pk = did.to_ed25519().unwrap();
+            (did, pk)
+        })
+        .collect();
+    assert!(bundle.verify(&signer_keys).is_ok());
+
+    // Re-serializing writes the current layout, which round-trips unchanged
+    let upgraded = serde_json::to_value(&bundle).unwrap();
+    assert_eq!(upgraded["version"], TRUST_BUNDLE_VERSION);
+    let reloaded: TrustBundle = serde_json::from_value(upgraded).unwrap();
+    assert!(reloaded.verify(&signer_keys).is_ok());
+}
+
+#[test]
+fn trust_bundle_rejects_unknown_future_version() {
+    let mut future: serde_json::Value = serde_json::from_str(LEGACY_BUNDLE_V0).unwrap();
+    future["version"] = serde_json::json!(TRUST_BUNDLE_VERSION + 1);
+    let err = serde_json::from_value::<TrustBundle>(future).unwrap_err();
+    assert!(err.to_string().contains("unsupported trust bundle version"));
+}
+
+#[test]
+fn jwk_round_trip_signs_identically() {
+    let kp = KeyPair::generate();
+    let jwk = kp.to_private_jwk();
+    assert_eq!(jwk.kty, "OKP");
+    assert_eq!(jwk.crv, "Ed25519");
+
+    let json = serde_json::to_string(&jwk).unwrap();
+    let restored = KeyPair::from_jwk(&serde_json::from_str::<Jwk>(&json).unwrap()).unwrap();
+
+    let msg = b"jose interop";
+    assert_eq!(restored.did, kp.did);
+    assert_eq!(restored.sign(msg).to_bytes(), kp.sign(msg).to_bytes());
+    assert_eq!(restored.to_public_jwk(), kp.to_public_jwk());
+}
+
+#[test]
+fn jwk_debug_redacts_private_key() {
+    let jwk = KeyPair::generate().to_private_jwk();
+    let private_key = jwk.d.clone().unwrap();
+    let debug = format!("{:?}", jwk);
+    assert!(!debug.contains(&private_key));
+    assert!(debug.contains("<redacted>"));
+    assert!(debug.contains(&jwk.x));
+}
+
+#[test]
+fn keypair_from_bytes_restores_did() {
+    let kp = KeyPair::generate();
+    let restored = KeyPair::from_bytes(&kp.to_bytes());
+    assert_eq!(restored.did, kp.did);
+    assert_eq!(restored.pk, kp.pk);
+}
+
+#[test]
+fn public_jwk_omits_private_key() {
+    let kp = KeyPair::generate();
+    let jwk = kp.to_public_jwk();
+    let value = serde_json::to_value(&jwk).unwrap();
+    assert!(value.get("d").is_none());
+    assert_eq!(KeyPair::from_jwk(&jwk).unwrap_err(), JwkError::MissingPrivateKey);
+}
+
+#[test]
+fn jwk_rejects_mismatched_or_foreign_keys() {
+    let kp = KeyPair::generate();
+    let other = KeyPair::generate();
+
+    let mismatched = Jwk {
+        x: other.to_public_jwk().x,
+        ..kp.to_private_jwk()
+    };
+    assert_eq!(KeyPair::from_jwk(&mismatched).unwrap_err(), JwkError::KeyMismatch);
+
+    let wrong_curve = Jwk {
+        crv: "X25519".to_string(),
+        ..kp.to_private_jwk()
+    };
+    assert_eq!(
+        KeyPair::from_jwk(&wrong_curve).unwrap_err(),
+        JwkError::UnsupportedCurve("X25519".to_string())
+    );
+}
+
+#[test]
+fn tagged_signature_verifies_and_dispatches_on_alg() {
+    let kp = KeyPair::generate();
+    let msg = b"tagged";
+    let sig = TaggedSignature::from(kp.sign(msg));
+    assert_eq!(sig.alg, SignatureAlgorithm::Ed25519);
+    assert!(sig.verify(&kp.did, msg).is_ok());
+    assert!(matches!(
+        sig.verify(&kp.did, b"other"),
+        Err(TaggedSignatureError::VerificationFailed { .. })
+    ));
+
+    let truncated = TaggedSignature::from_legacy_bytes(sig.bytes[..10].to_vec());
+    assert!(matches!(
+        truncated.verify(&kp.did, msg),
+        Err(TaggedSignatureError::InvalidFormat { .. })
+    ));
+}
+
+#[test]
+fn tagged_signature_reads_legacy_untagged_bytes() {
+    let kp = KeyPair::generate();
+    let msg = b"legacy";
+    let raw = kp.sign(msg).to_bytes().to_vec();
+
+    let legacy_json = serde_json::to_string(&raw).unwrap();
+    let migrated: TaggedSignature = serde_json::from_str(&legacy_json).unwrap();
+    assert_eq!(migrated, TaggedSignature::from_legacy_bytes(raw));
+    assert!(migrated.verify(&kp.did, msg).is_ok());
+
+    let tagged_json = serde_json::to_value(&migrated).unwrap();
+    assert_eq!(tagged_json["alg"], "ed25519");
+    let roundtrip: TaggedSignature = serde_json::from_value(tagged_json).unwrap();
+    assert_eq!(roundtrip, migrated);
+}
+
+#[test]
+fn sealed_payload_opens_only_for_recipients() {
+    let alice = KeyPair::generate();
+    let bob = KeyPair::generate();
+    let mallory = KeyPair::generate();
+    let secret = b"job output";
+
+    let sealed = SealedPayload::seal(secret, &[alice.did.clone(), bob.did.clone()]).unwrap();
+    assert_ne!(sealed.ciphertext, secret.to_vec());
+    assert_eq!(sealed.open(&alice).unwrap(), secret.to_vec());
+    assert_eq!(sealed.open(&bob).unwrap(), secret.to_vec());
+    assert_eq!(
+        sealed.open(&mallory),
+        Err(SealError::NotARecipient(mallory.did.to_string()))
+    );
+
+    let mut tampered = sealed.clone();
+    tampered.ciphertext[0] ^= 0xff;
+    assert_eq!(tampered.open(&alice), Err(SealError::DecryptionFailed));
+
+    assert_eq!(SealedPayload::seal(secret, &[]), Err(SealError::NoRecipients));
+}
+
+#[test]
+fn trust_validator_registers_signers_while_validating() {
+    let founders: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+    let joiners: Vec<KeyPair> = (0..32).map(|_| KeyPair::generate()).collect();
+    let outsider = KeyPair::generate();
+
+    let mut bundle = TrustBundle::new(
+        "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+        FederationMetadata {
+            name: "Rotating Federation".to_string(),
+            description: None,
+            version: "1.0".to_string(),
+            max_token_supply: None,
+            additional: HashMap::new(),
+        },
+    );
+    let hash = bundle.calculate_hash().unwrap();
+    // A fixed threshold, so the founders' proof stays valid however many
+    // joiners are registered; a majority of all signers would not
+    bundle.add_quorum_proof(QuorumProof::new(
+        QuorumType::Threshold(founders.len() as u8),
+        founders
+            .iter()
+            .map(|kp| (kp.did.clone(), kp.sign(&hash)))
+            .collect(),
+    ));
+
+    let validator = TrustValidator::new();
+    assert!(matches!(
+        validator.is_authorized_signer(&founders[0].did),
+        Err(TrustValidationError::NoBundleConfigured)
+    ));
+    for kp in &founders {
+        validator.register_signer(kp.did.clone(), kp.pk);
+    }
+    validator.set_trust_bundle(bundle.clone()).unwrap();
+
+    std::thread::scope(|scope| {
+        for chunk in joiners.chunks(8) {
+            let validator = validator.clone();
+            let bundle = bundle.clone();
+            scope.spawn(move || {
+                for kp in chunk {
+                    validator.register_signer(kp.did.clone(), kp.pk);
+                    validator.set_trust_bundle(bundle.clone()).unwrap();
+                }
+            });
+        }
+        for _ in 0..4 {
+            let validator = validator.clone();
+            let (founders, joiners, outsider) = (&founders, &joiners, &outsider);
+            scope.spawn(move || {
+                let mut seen = vec![false; joiners.len()];
+                for _ in 0..200 {
+                    for kp in founders {
+                        assert!(validator.is_authorized_signer(&kp.did).unwrap());
+                    }
+                    assert!(!validator.is_authorized_signer(&outsider.did).unwrap());
+                    // Once a joiner is visible it must stay visible
+                    for (kp, seen) in joiners.iter().zip(seen.iter_mut()) {
+                        let authorized = validator.is_authorized_signer(&kp.did).unwrap();
+                        assert!(authorized || !*seen);
+                        *seen = authorized;
+                    }
+                }
+            });
+        }
+    });
+
+    for kp in founders.iter().chain(&joiners) {
+        assert!(validator.is_authorized_signer(&kp.did).unwrap());
+    }
+}
@@ -20,8 +20,17 @@ pub enum TrustBundleError {
 
     #[error("missing required field: {0}")]
     MissingField(String),
+
+    #[error("unsupported trust bundle version {found} (this build supports up to {supported})")]
+    UnsupportedVersion { found: u32, supported: u32 },
 }
 
+/// Current layout version of a serialized [`TrustBundle`].
+///
+/// - `0`: original layout, written before bundles carried a `version` field.
+/// - `1`: adds the explicit `version` field.
+pub const TRUST_BUNDLE_VERSION: u32 = 1;
+
 /// Federation metadata containing essential information about a federation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FederationMetadata {
@@ -35,6 +44,11 @@ pub struct FederationMetadata {
     /// Version of the federation metadata schema
     pub version: String,
 
+    /// Ceiling on the total token supply the federation may ever mint.
+    /// Part of the signed content, so changing it requires a new quorum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_token_supply: Option<u64>,
+
     /// Additional custom metadata fields
     #[serde(flatten)]
     pub additional: HashMap<String, Value>,
@@ -45,8 +59,16 @@ pub struct FederationMetadata {
 /// This bundle is signed by a quorum of signers and serves as the
 /// cryptographic root of trust for the federation. The bundle's hash is
 /// anchored in the DAG to provide tamper-proof verification.
+///
+/// Bundles written in an older layout are upgraded to the current one on
+/// deserialization; bundles from a newer, unknown layout are rejected rather
+/// than partially loaded.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SerializedTrustBundle")]
 pub struct TrustBundle {
+    /// Layout version, see [`TRUST_BUNDLE_VERSION`]
+    pub version: u32,
+
     /// CID of the root DAG block
     pub root_dag_cid: String,
 
@@ -58,10 +80,70 @@ pub struct TrustBundle {
     pub quorum_proof: Option<QuorumProof>,
 }
 
+/// Any layout a [`TrustBundle`] has been serialized in.
+///
+/// Fields added after version 0 must be optional here and filled in by
+/// [`SerializedTrustBundle::migrate`].
+#[derive(Deserialize)]
+struct SerializedTrustBundle {
+    /// Absent in version 0 bundles
+    #[serde(default)]
+    version: u32,
+    root_dag_cid: String,
+    federation_metadata: FederationMetadata,
+    #[serde(default)]
+    quorum_proof: Option<QuorumProof>,
+}
+
+impl SerializedTrustBundle {
+    /// Upgrade one layout version at a time until current.
+    fn migrate(mut self) -> Result<TrustBundle, TrustBundleError> {
+        loop {
+            match self.version {
+                // 0 -> 1: only the version marker was added
+                0 => self.version = 1,
+                TRUST_BUNDLE_VERSION => {
+                    return Ok(TrustBundle {
+                        version: self.version,
+                        root_dag_cid: self.root_dag_cid,
+                        federation_metadata: self.federation_metadata,
+                        quorum_proof: self.quorum_proof,
+                    })
+                }
+                found => {
+                    return Err(TrustBundleError::UnsupportedVersion {
+                        found,
+                        supported: TRUST_BUNDLE_VERSION,
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<SerializedTrustBundle> for TrustBundle {
+    type Error = TrustBundleError;
+
+    fn try_from(serialized: SerializedTrustBundle) -> Result<Self, Self::Error> {
+        serialized.migrate()
+    }
+}
+
+/// The portion of a bundle covered by the quorum signatures.
+///
+/// Deliberately excludes `version` so signatures made over a bundle before a
+/// layout upgrade still verify after it.
+#[derive(Serialize)]
+struct SignedBundleContent<'a> {
+    root_dag_cid: &'a str,
+    federation_metadata: &'a FederationMetadata,
+}
+
 impl TrustBundle {
     /// Creates a new TrustBundle with the given DAG CID and federation metadata.
     pub fn new(root_dag_cid: String, federation_metadata: FederationMetadata) -> Self {
         Self {
+            version: TRUST_BUNDLE_VERSION,
             root_dag_cid,
             federation_metadata,
             quorum_proof: None,
@@ -74,17 +156,17 @@ impl TrustBundle {
     }
 
     /// Calculates a deterministic hash of the bundle for signing.
-    /// This hash includes the DAG CID and federation metadata, but NOT the quorum proof.
+    /// This hash includes the DAG CID and federation metadata, but NOT the quorum proof
+    /// or the layout version.
     pub fn calculate_hash(&self) -> Result<Vec<u8>, TrustBundleError> {
-        // Create a temporary bundle without the quorum proof for hashing
-        let hash_bundle = TrustBundle {
-            root_dag_cid: self.root_dag_cid.clone(),
-            federation_metadata: self.federation_metadata.clone(),
-            quorum_proof: None,
+        // Hash only the signed content: no quorum proof, no layout version
+        let content = SignedBundleContent {
+            root_dag_cid: &self.root_dag_cid,
+            federation_metadata: &self.federation_metadata,
         };
 
         // Serialize to JSON in a deterministic order
-        let bytes = serde_json::to_vec(&hash_bundle)?;
+        let bytes = serde_json::to_vec(&content)?;
         Ok(bytes)
     }
 
@@ -113,6 +195,27 @@ impl TrustBundle {
             .map_err(TrustBundleError::QuorumError)
     }
 
+    /// Whether `did` is an allowed signer whose entry in this bundle's quorum
+    /// proof verifies over [`calculate_hash`](Self::calculate_hash).
+    ///
+    /// Entries that are unauthorized or carry a bad signature don't count,
+    /// even when the rest of the proof still meets its quorum. A bundle
+    /// without a proof has no signers.
+    pub fn is_signer(
+        &self,
+        did: &Did,
+        allowed_signers: &HashMap<Did, VerifyingKey>,
+    ) -> Result<bool, TrustBundleError> {
+        let (Some(proof), Some(key)) = (self.quorum_proof.as_ref(), allowed_signers.get(did))
+        else {
+            return Ok(false);
+        };
+        let hash = self.calculate_hash()?;
+        Ok(proof.signatures.iter().any(|(signer, signature)| {
+            signer == did && key.verify_strict(&hash, signature).is_ok()
+        }))
+    }
+
     /// Returns the root DAG CID of this trust bundle.
     /// This is a convenience method to avoid having to access the field directly.
     pub fn cid(&self) -> &str {
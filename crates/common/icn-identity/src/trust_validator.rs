@@ -1,7 +1,7 @@
-use crate::{Did, TrustBundle, TrustBundleError};
+use crate::{Did, QuorumError, QuorumProof, TrustBundle, TrustBundleError};
 use ed25519_dalek::VerifyingKey;
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use thiserror::Error;
 
 /// Errors related to trust validation.
@@ -15,75 +15,105 @@ pub enum TrustValidationError {
 
     #[error("trust bundle access error")]
     BundleAccessError,
+
+    #[error("quorum proof verification failed: {0}")]
+    QuorumError(#[from] QuorumError),
+}
+
+/// The bundle and the signer keys it was verified against, kept under one
+/// lock so a reader never sees a bundle paired with a different key set.
+#[derive(Debug, Default)]
+struct TrustState {
+    // The current trust bundle, if one is set
+    trust_bundle: Option<TrustBundle>,
+
+    // Known signer public keys
+    trusted_keys: HashMap<Did, VerifyingKey>,
 }
 
 /// A service that validates trust bundles and maintains the current
 /// federation's trusted signers.
+///
+/// Clones share the same state, and every method takes the lock once, so
+/// signers can be registered while other tasks validate.
 #[derive(Debug, Clone)]
 pub struct TrustValidator {
-    // The current trust bundle, if one is set
-    trust_bundle: Arc<RwLock<Option<TrustBundle>>>,
-
-    // Known signer public keys
-    trusted_keys: Arc<RwLock<HashMap<Did, VerifyingKey>>>,
+    state: Arc<RwLock<TrustState>>,
 }
 
 impl TrustValidator {
     /// Creates a new TrustValidator with no configured bundle.
     pub fn new() -> Self {
         Self {
-            trust_bundle: Arc::new(RwLock::new(None)),
-            trusted_keys: Arc::new(RwLock::new(HashMap::new())),
+            state: Arc::new(RwLock::new(TrustState::default())),
         }
     }
 
+    fn read(&self) -> Result<RwLockReadGuard<'_, TrustState>, TrustValidationError> {
+        self.state
+            .read()
+            .map_err(|_| TrustValidationError::BundleAccessError)
+    }
+
+    fn write(&self) -> Result<RwLockWriteGuard<'_, TrustState>, TrustValidationError> {
+        self.state
+            .write()
+            .map_err(|_| TrustValidationError::BundleAccessError)
+    }
+
     /// Registers a trusted signer DID and verifying key.
     pub fn register_signer(&self, did: Did, key: VerifyingKey) {
-        let mut keys = self.trusted_keys.write().unwrap();
-        keys.insert(did, key);
+        // Every write is one verify-then-assign or insert, so a lock poisoned
+        // by a panicking writer still holds consistent state
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        state.trusted_keys.insert(did, key);
     }
 
     /// Sets the active trust bundle and validates it against known signer keys.
     pub fn set_trust_bundle(&self, bundle: TrustBundle) -> Result<(), TrustValidationError> {
-        // First verify the bundle
-        let keys = self
-            .trusted_keys
-            .read()
-            .map_err(|_| TrustValidationError::BundleAccessError)?;
-        bundle.verify(&keys)?;
-
-        // If verification succeeds, set the bundle
-        let mut current = self
-            .trust_bundle
-            .write()
-            .map_err(|_| TrustValidationError::BundleAccessError)?;
-        *current = Some(bundle);
+        // Verify and install under the same write lock, so no signer can be
+        // registered between the two
+        let mut state = self.write()?;
+        bundle.verify(&state.trusted_keys)?;
+        state.trust_bundle = Some(bundle);
 
         Ok(())
     }
 
     /// Gets a reference to the current trust bundle, if one exists.
     pub fn get_trust_bundle(&self) -> Result<Option<TrustBundle>, TrustValidationError> {
-        let current = self
-            .trust_bundle
-            .read()
-            .map_err(|_| TrustValidationError::BundleAccessError)?;
-        Ok(current.clone())
+        Ok(self.read()?.trust_bundle.clone())
     }
 
     /// Validates if the given signer is authorized in the current trust bundle.
     pub fn is_authorized_signer(&self, did: &Did) -> Result<bool, TrustValidationError> {
-        let _bundle = self
-            .get_trust_bundle()?
-            .ok_or(TrustValidationError::NoBundleConfigured)?;
+        let state = self.read()?;
+        if state.trust_bundle.is_none() {
+            return Err(TrustValidationError::NoBundleConfigured);
+        }
 
         // Since we no longer track authorized signers in the bundle,
         // we check if the DID is registered as a trusted signer
-        let keys = self
-            .trusted_keys
-            .read()
-            .map_err(|_| TrustValidationError::BundleAccessError)?;
-        Ok(keys.contains_key(did))
+        Ok(state.trusted_keys.contains_key(did))
+    }
+
+    /// Verifies that a quorum of trusted signers signed `payload`.
+    pub fn verify_quorum(
+        &self,
+        payload: &[u8],
+        proof: &QuorumProof,
+    ) -> Result<(), TrustValidationError> {
+        proof.verify(payload, &self.read()?.trusted_keys)?;
+        Ok(())
+    }
+
+    /// The token supply ceiling from the current trust bundle, if one is set.
+    pub fn max_token_supply(&self) -> Result<Option<u64>, TrustValidationError> {
+        Ok(self
+            .read()?
+            .trust_bundle
+            .as_ref()
+            .and_then(|bundle| bundle.federation_metadata.max_token_supply))
     }
 }
 
@@ -11,8 +11,9 @@ pub struct VerifiableCredential<T>
 where
     T: Serialize + for<'a> Deserialize<'a> + Clone,
 {
+    /// Context URLs or inline JSON-LD context objects.
     #[serde(rename = "@context")]
-    pub context: Vec<String>,
+    pub context: Vec<Value>,
 
     #[serde(rename = "type")]
     pub types: Vec<String>,
@@ -2,13 +2,18 @@
 
 mod sign;
 
-pub use sign::{sign_receipt_in_place, verify_embedded_signature};
+pub use sign::{
+    sign_batch_in_place, sign_receipt_in_place, verify_batch, verify_embedded_signature,
+};
 
 use chrono::{DateTime, Utc};
 use cid::multihash::MultihashDigest;
 use cid::{multihash, Cid};
+use ed25519_dalek::VerifyingKey;
 use icn_economics::ResourceType;
-use icn_identity::Did;
+use icn_identity::{
+    Did, SignatureAlgorithm, TaggedSignature, TrustBundle, TrustBundleError, VerifiableCredential,
+};
 use icn_types::error::SignError;
 use icn_types::mesh::JobStatus;
 use icn_types::org::{CommunityId, CooperativeId};
@@ -31,6 +36,12 @@ pub enum ReceiptError {
 
     #[error("Signature error: {0}")]
     Signature(#[from] SignError),
+
+    #[error("Executor {executor} is not a signer of federation bundle {bundle_cid}")]
+    ExecutorNotInFederation { executor: Did, bundle_cid: String },
+
+    #[error("Trust bundle error: {0}")]
+    TrustBundle(#[from] TrustBundleError),
 }
 
 /// A verifiable receipt of WASM execution.
@@ -46,10 +57,17 @@ pub struct ExecutionReceipt {
     pub result_data_cid: Option<String>,
     /// Optional CID pointing to a collection of execution logs.
     pub logs_cid: Option<String>,
-    /// Reported resource usage for the job.
+    /// Reported resource usage for the job. Serialized in `ResourceType`
+    /// discriminant order so the CID and signing payload are deterministic.
+    #[serde(serialize_with = "serialize_resource_usage")]
     pub resource_usage: HashMap<ResourceType, u64>,
     /// Optional mana cost incurred for the job execution.
     pub mana_cost: Option<u64>,
+    /// Whether the job ran on the deterministic CoVm, so a verifier replaying
+    /// it should expect bit-identical results. Omitted when false, so receipts
+    /// from before the field existed keep their encoding.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deterministic: bool,
     /// Unix timestamp (seconds since epoch) when the job execution started.
     pub execution_start_time: u64,
     /// Unix timestamp (seconds since epoch) when the job execution ended.
@@ -57,28 +75,161 @@ pub struct ExecutionReceipt {
     /// DateTime<Utc> when the job execution ended (kept for convenience, renamed from timestamp).
     pub execution_end_time_dt: DateTime<Utc>,
     /// Cryptographic signature of the receipt content by the executor.
-    pub signature: Vec<u8>,
+    /// Legacy untagged byte arrays deserialize as Ed25519.
+    #[serde(serialize_with = "serialize_signature")]
+    pub signature: TaggedSignature,
     /// Optional cooperative ID that this receipt is associated with.
     pub coop_id: Option<CooperativeId>,
     /// Optional community ID that this receipt is associated with.
     pub community_id: Option<CommunityId>,
 }
 
+/// Serializer for `resource_usage` that writes entries sorted by resource
+/// discriminant rather than in `HashMap` iteration order.
+fn serialize_resource_usage<S>(
+    usage: &HashMap<ResourceType, u64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let mut entries: Vec<_> = usage.iter().collect();
+    entries.sort_by_key(|(resource_type, _)| **resource_type as u32);
+    serializer.collect_map(entries)
+}
+
+/// Serializer for `signature` that writes Ed25519 signatures as the bare byte
+/// arrays receipts carried before signatures were tagged.
+///
+/// The receipt CID and signing payload are both computed over this encoding,
+/// so changing it would invalidate every receipt already signed or anchored.
+/// An algorithm added later gets its own, tagged, encoding.
+fn serialize_signature<S>(signature: &TaggedSignature, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match signature.alg {
+        SignatureAlgorithm::Ed25519 => signature.bytes.serialize(serializer),
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Vocabulary IRI the terms of an [`ExecutionReceiptCredential`] expand under.
+///
+/// A URN rather than a URL, so JSON-LD processors never try to dereference it.
+///
+/// [`ExecutionReceiptCredential`]: ExecutionReceipt::to_verifiable_credential
+pub const EXECUTION_RECEIPT_VOCAB: &str = "urn:icn:execution-receipt#";
+
+/// Inline JSON-LD context defining the terms of an [`ExecutionReceiptCredential`],
+/// embedded in the credential so it can be processed without fetching anything.
+///
+/// [`ExecutionReceiptCredential`]: ExecutionReceipt::to_verifiable_credential
+pub fn execution_receipt_credential_context() -> serde_json::Value {
+    serde_json::json!({ "@vocab": EXECUTION_RECEIPT_VOCAB })
+}
+
+/// Multicodec for DAG-CBOR, used for receipts anchored to the DAG.
+pub const DAG_CBOR_CODEC: u64 = 0x71;
+/// Multicodec for opaque raw bytes.
+pub const RAW_CODEC: u64 = 0x55;
+/// Multicodec for plain JSON documents.
+pub const JSON_CODEC: u64 = 0x0200;
+/// Multicodec for DAG-JSON.
+pub const DAG_JSON_CODEC: u64 = 0x0129;
+
 impl ExecutionReceipt {
     /// Generate a CID (Content Identifier) for this receipt
     ///
     /// The CID is a unique identifier based on the content of the receipt.
-    /// It uses SHA-256 for hashing and the DAG-CBOR codec (0x71).
+    /// It uses SHA-256 for hashing and the DAG-CBOR codec (0x71), hashing the
+    /// receipt's own CBOR encoding. This is the CID used when receipts are
+    /// anchored to the DAG by mesh nodes.
     pub fn cid(&self) -> Result<Cid, ReceiptError> {
         // Serialize receipt to CBOR
         let bytes =
             serde_cbor::to_vec(self).map_err(|e| ReceiptError::Serialization(e.to_string()))?;
 
+        self.cid_with_codec(DAG_CBOR_CODEC, &bytes)
+    }
+
+    /// Generate a CID for this receipt as stored under a specific codec.
+    ///
+    /// `bytes` must be the exact encoding that is persisted, so a reader that
+    /// fetches those bytes computes the same CID independently:
+    ///
+    /// - [`DAG_CBOR_CODEC`]: `serde_cbor::to_vec(receipt)`, the DAG anchoring path
+    ///   (equivalent to [`ExecutionReceipt::cid`]).
+    /// - [`JSON_CODEC`] / [`DAG_JSON_CODEC`]: `serde_json::to_vec(receipt)`, for
+    ///   receipts kept as JSON blobs (e.g. HTTP APIs, exported files).
+    /// - [`RAW_CODEC`]: any other opaque byte encoding, e.g. a compressed or
+    ///   signed envelope stored in a blob store.
+    ///
+    /// Unknown codecs are rejected rather than producing a CID no reader would
+    /// reproduce.
+    pub fn cid_with_codec(&self, codec: u64, bytes: &[u8]) -> Result<Cid, ReceiptError> {
+        match codec {
+            DAG_CBOR_CODEC | RAW_CODEC | JSON_CODEC | DAG_JSON_CODEC => {}
+            other => {
+                return Err(ReceiptError::CidGeneration(format!(
+                    "unsupported codec 0x{:x}",
+                    other
+                )))
+            }
+        }
+
         // Generate multihash using SHA-256
-        let hash = multihash::Code::Sha2_256.digest(&bytes);
+        let hash = multihash::Code::Sha2_256.digest(bytes);
+
+        Ok(Cid::new_v1(codec, hash))
+    }
+
+    /// Verify the embedded signature, then check that the executor validly
+    /// signed `bundle` as one of `allowed_signers`, i.e. is a member of that
+    /// federation.
+    ///
+    /// The bundle's quorum is not re-verified here; callers should only pass
+    /// bundles they already trust.
+    pub fn verify_against_bundle(
+        &self,
+        bundle: &TrustBundle,
+        allowed_signers: &HashMap<Did, VerifyingKey>,
+    ) -> Result<(), ReceiptError> {
+        if !verify_embedded_signature(self)? {
+            return Err(SignError::VerificationFailed.into());
+        }
+        if !bundle.is_signer(&self.executor, allowed_signers)? {
+            return Err(ReceiptError::ExecutorNotInFederation {
+                executor: self.executor.clone(),
+                bundle_cid: bundle.cid().to_string(),
+            });
+        }
+        Ok(())
+    }
 
-        // Create CID with DAG-CBOR codec (0x71)
-        Ok(Cid::new_v1(0x71, hash))
+    /// Export this receipt as an unsigned W3C Verifiable Credential of type
+    /// `ExecutionReceiptCredential`, issued by `issuer` at the execution end time.
+    ///
+    /// The receipt, including its executor signature, becomes the credential
+    /// subject; sign the result with [`VerifiableCredential::sign`] to attach a proof.
+    pub fn to_verifiable_credential(&self, issuer: &Did) -> VerifiableCredential<Self> {
+        VerifiableCredential {
+            context: vec![
+                "https://www.w3.org/2018/credentials/v1".into(),
+                execution_receipt_credential_context(),
+            ],
+            types: vec![
+                "VerifiableCredential".to_string(),
+                "ExecutionReceiptCredential".to_string(),
+            ],
+            issuer: issuer.clone(),
+            issuance_date: self.execution_end_time_dt,
+            credential_subject: self.clone(),
+            proof: None,
+        }
     }
 }
 
@@ -95,8 +246,8 @@ impl VerifiableReceipt for ExecutionReceipt {
         })
     }
 
-    fn get_signature_bytes(&self) -> Option<&[u8]> {
-        Some(&self.signature) // signature is not Option<Vec<u8>>, it's Vec<u8>
+    fn get_signature(&self) -> Option<&TaggedSignature> {
+        Some(&self.signature)
     }
 
     fn get_issuer_did_str(&self) -> &str {
@@ -130,10 +281,11 @@ mod tests {
             execution_end_time_dt: DateTime::parse_from_rfc3339("2023-01-01T00:00:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
-            signature: vec![1, 2, 3, 4],
+            signature: TaggedSignature::from_legacy_bytes(vec![1, 2, 3, 4]),
             coop_id: None,
             community_id: None,
             mana_cost: None,
+            deterministic: false,
         };
 
         let json = serde_json::to_string(&receipt).unwrap();
@@ -162,10 +314,11 @@ mod tests {
             execution_end_time_dt: DateTime::parse_from_rfc3339("2023-01-01T00:00:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
-            signature: vec![1, 2, 3, 4],
+            signature: TaggedSignature::from_legacy_bytes(vec![1, 2, 3, 4]),
             coop_id: None,
             community_id: None,
             mana_cost: None,
+            deterministic: false,
         };
 
         let cbor = serde_cbor::to_vec(&receipt).unwrap();
@@ -196,10 +349,11 @@ mod tests {
             execution_start_time: 1672502400,
             execution_end_time: 1672506000,
             execution_end_time_dt: timestamp,
-            signature: vec![9, 8, 7, 6],
+            signature: TaggedSignature::from_legacy_bytes(vec![9, 8, 7, 6]),
             coop_id: None,
             community_id: None,
             mana_cost: None,
+            deterministic: false,
         };
 
         // Generate CID
@@ -220,4 +374,88 @@ mod tests {
         let cid3 = receipt3.cid().unwrap();
         assert_ne!(cid, cid3, "Different receipts should have different CIDs");
     }
+
+    #[test]
+    fn test_cid_ignores_resource_usage_insertion_order() {
+        let kp = KeyPair::generate();
+        let receipt_with = |entries: &[(ResourceType, u64)]| ExecutionReceipt {
+            job_id: "order-job".to_string(),
+            executor: kp.did.clone(),
+            status: JobStatus::Completed,
+            result_data_cid: None,
+            logs_cid: None,
+            resource_usage: entries.iter().copied().collect(),
+            mana_cost: None,
+            execution_start_time: 1672502400,
+            execution_end_time: 1672506000,
+            execution_end_time_dt: DateTime::parse_from_rfc3339("2023-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            signature: TaggedSignature::default(),
+            coop_id: None,
+            community_id: None,
+            deterministic: false,
+        };
+
+        let forward = receipt_with(&[
+            (ResourceType::Cpu, 10),
+            (ResourceType::Memory, 20),
+            (ResourceType::Io, 30),
+            (ResourceType::Token, 40),
+        ]);
+        let reversed = receipt_with(&[
+            (ResourceType::Token, 40),
+            (ResourceType::Io, 30),
+            (ResourceType::Memory, 20),
+            (ResourceType::Cpu, 10),
+        ]);
+
+        assert_eq!(forward.cid().unwrap(), reversed.cid().unwrap());
+        assert_eq!(
+            serde_cbor::to_vec(&forward).unwrap(),
+            serde_cbor::to_vec(&reversed).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cid_with_codec() {
+        let keypair = KeyPair::generate();
+        let receipt = ExecutionReceipt {
+            job_id: "codec-job".to_string(),
+            executor: keypair.did.clone(),
+            status: JobStatus::Completed,
+            result_data_cid: None,
+            logs_cid: None,
+            resource_usage: HashMap::new(),
+            mana_cost: None,
+            execution_start_time: 1672502400,
+            execution_end_time: 1672506000,
+            execution_end_time_dt: Utc::now(),
+            signature: TaggedSignature::default(),
+            coop_id: None,
+            community_id: None,
+            deterministic: false,
+        };
+
+        let cbor = serde_cbor::to_vec(&receipt).unwrap();
+        assert_eq!(
+            receipt.cid_with_codec(DAG_CBOR_CODEC, &cbor).unwrap(),
+            receipt.cid().unwrap()
+        );
+
+        // The same JSON bytes give the same CID whether or not the reader has the receipt
+        let json = serde_json::to_vec(&receipt).unwrap();
+        let json_cid = receipt.cid_with_codec(JSON_CODEC, &json).unwrap();
+        assert_eq!(json_cid.codec(), JSON_CODEC);
+        assert_ne!(json_cid.hash(), receipt.cid().unwrap().hash());
+
+        let raw_cid = receipt.cid_with_codec(RAW_CODEC, &json).unwrap();
+        assert_eq!(raw_cid.codec(), RAW_CODEC);
+        assert_eq!(raw_cid.hash(), json_cid.hash());
+
+        assert!(matches!(
+            receipt.cid_with_codec(0x1234, &json),
+            Err(ReceiptError::CidGeneration(_))
+        ));
+    }
 }
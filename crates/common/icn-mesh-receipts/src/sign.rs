@@ -1,15 +1,18 @@
 use crate::ExecutionReceipt;
 use ed25519_dalek::Signature as DalekSignature;
-use icn_identity::KeyPair;
+use icn_identity::{KeyPair, TaggedSignature, TaggedSignatureError};
 use icn_types::error::SignError;
+use rayon::prelude::*;
 use serde_cbor;
-use signature::Verifier;
 
 /// Creates the canonical byte representation of the receipt for signing or verification.
 /// This involves temporarily emptying the signature field before serialization.
+///
+/// The payload must stay byte-identical for receipts signed by earlier releases;
+/// see the `signature` and `deterministic` serializers on [`ExecutionReceipt`].
 fn get_receipt_signing_payload(receipt: &ExecutionReceipt) -> Result<Vec<u8>, SignError> {
     let mut receipt_clone = receipt.clone();
-    receipt_clone.signature = Vec::new(); // Ensure signature field is empty for payload generation
+    receipt_clone.signature = TaggedSignature::default(); // Ensure signature field is empty for payload generation
     Ok(serde_cbor::to_vec(&receipt_clone)?)
 }
 
@@ -31,7 +34,7 @@ pub fn sign_receipt_in_place(
 
     let payload_bytes = get_receipt_signing_payload(receipt)?;
     let dalek_signature: DalekSignature = kp.sign(&payload_bytes);
-    receipt.signature = dalek_signature.to_bytes().to_vec(); // Store as Vec<u8>
+    receipt.signature = TaggedSignature::ed25519(&dalek_signature);
     Ok(())
 }
 
@@ -46,26 +49,54 @@ pub fn verify_embedded_signature(receipt: &ExecutionReceipt) -> Result<bool, Sig
 
     let payload_bytes = get_receipt_signing_payload(receipt)?;
 
-    let signature_bytes_slice = receipt.signature.as_slice();
-    if signature_bytes_slice.len() != 64 {
-        return Err(SignError::InvalidSignatureFormat {
-            reason: format!(
-                "Signature is not 64 bytes long, found {} bytes",
-                signature_bytes_slice.len()
-            ),
-        });
+    // Dispatches on the algorithm tag; legacy untagged signatures are Ed25519.
+    match receipt.signature.verify(&receipt.executor, &payload_bytes) {
+        Ok(()) => Ok(true),
+        Err(TaggedSignatureError::InvalidFormat { reason, .. }) => {
+            Err(SignError::InvalidSignatureFormat { reason })
+        }
+        Err(TaggedSignatureError::UnsupportedSigner { source, .. }) => {
+            Err(SignError::DidProcessingError(source))
+        }
+        Err(TaggedSignatureError::VerificationFailed { .. }) => Err(SignError::VerificationFailed),
     }
-    let signature_bytes_array: &[u8; 64] = signature_bytes_slice.try_into().unwrap();
+}
 
-    let dalek_signature = DalekSignature::from_bytes(signature_bytes_array);
+/// Sign every receipt in `receipts` with `kp`, in parallel.
+///
+/// All executors are checked against `kp` before anything is signed, so on an
+/// [`SignError::ExecutorMismatch`] no receipt has been modified.
+pub fn sign_batch_in_place(
+    receipts: &mut [ExecutionReceipt],
+    kp: &KeyPair,
+) -> Result<(), SignError> {
+    if let Some(receipt) = receipts
+        .iter()
+        .find(|receipt| receipt.executor.as_str() != kp.did.as_str())
+    {
+        return Err(SignError::ExecutorMismatch {
+            keypair_did: kp.did.to_string(),
+            executor_did: receipt.executor.to_string(),
+        });
+    }
 
-    let verifying_key = receipt.executor.to_ed25519()?;
+    receipts
+        .par_iter_mut()
+        .try_for_each(|receipt| sign_receipt_in_place(receipt, kp))
+}
 
-    if verifying_key.verify(&payload_bytes, &dalek_signature).is_ok() {
-        Ok(true)
-    } else {
-        Err(SignError::VerificationFailed)
-    }
+/// Verify the embedded signatures of `receipts` in parallel.
+///
+/// The result at each index is the outcome for the receipt at that index.
+pub fn verify_batch(receipts: &[ExecutionReceipt]) -> Vec<Result<(), SignError>> {
+    receipts
+        .par_iter()
+        .map(|receipt| match verify_embedded_signature(receipt) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(SignError::VerificationFailed),
+            Err(e) => Err(e),
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -93,10 +124,11 @@ mod tests {
             execution_start_time: now.timestamp() as u64 - 60,
             execution_end_time: now.timestamp() as u64,
             execution_end_time_dt: now,
-            signature: Vec::new(),
+            signature: TaggedSignature::default(),
             coop_id: None,
             community_id: None,
             mana_cost: None,
+            deterministic: false,
         }
     }
 
@@ -119,7 +151,7 @@ mod tests {
         // Test with a different keypair (should fail verification if we could tamper with DID)
         // More practically, tamper with the signature or payload
         let mut tampered_receipt = receipt.clone();
-        tampered_receipt.signature[0] = tampered_receipt.signature[0].wrapping_add(1); // Corrupt signature
+        tampered_receipt.signature.bytes[0] = tampered_receipt.signature.bytes[0].wrapping_add(1); // Corrupt signature
         let is_tampered_valid = verify_embedded_signature(&tampered_receipt);
         assert!(
             is_tampered_valid.is_err() || !is_tampered_valid.unwrap(),
@@ -149,10 +181,69 @@ mod tests {
         );
     }
 
+    /// Receipt layout and signing procedure of releases before signatures were tagged
+    #[derive(serde::Serialize)]
+    struct PreTaggingReceipt {
+        job_id: String,
+        executor: icn_identity::Did,
+        status: JobStatus,
+        result_data_cid: Option<String>,
+        logs_cid: Option<String>,
+        resource_usage: HashMap<ResourceType, u64>,
+        mana_cost: Option<u64>,
+        execution_start_time: u64,
+        execution_end_time: u64,
+        execution_end_time_dt: chrono::DateTime<Utc>,
+        signature: Vec<u8>,
+        coop_id: Option<icn_types::org::CooperativeId>,
+        community_id: Option<icn_types::org::CommunityId>,
+    }
+
+    #[test]
+    fn test_receipt_signed_before_tagging_still_verifies() {
+        let kp = KeyPair::from_bytes(&[7; 32]);
+        let mut legacy = PreTaggingReceipt {
+            job_id: "legacy_job".to_string(),
+            executor: kp.did.clone(),
+            status: JobStatus::Completed,
+            result_data_cid: Some("bafy-result".to_string()),
+            logs_cid: None,
+            resource_usage: HashMap::from([(ResourceType::Cpu, 1000)]),
+            mana_cost: Some(12),
+            execution_start_time: 1_672_502_400,
+            execution_end_time: 1_672_506_000,
+            execution_end_time_dt: chrono::DateTime::parse_from_rfc3339("2023-01-01T01:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            signature: Vec::new(),
+            coop_id: Some(icn_types::org::CooperativeId::new("coop-a")),
+            community_id: None,
+        };
+        let payload = serde_cbor::to_vec(&legacy).unwrap();
+        legacy.signature = kp.sign(&payload).to_bytes().to_vec();
+        let stored = serde_cbor::to_vec(&legacy).unwrap();
+
+        let receipt: ExecutionReceipt = serde_cbor::from_slice(&stored).unwrap();
+        assert!(verify_embedded_signature(&receipt).unwrap());
+        // Re-encoding reproduces the stored bytes, so the CID is unchanged too
+        assert_eq!(serde_cbor::to_vec(&receipt).unwrap(), stored);
+        assert_eq!(
+            receipt.cid().unwrap(),
+            receipt
+                .cid_with_codec(crate::DAG_CBOR_CODEC, &stored)
+                .unwrap()
+        );
+
+        // A receipt from a deterministic run binds that flag into its signature
+        let mut deterministic = receipt.clone();
+        deterministic.deterministic = true;
+        assert!(verify_embedded_signature(&deterministic).is_err());
+    }
+
     #[test]
     fn test_verify_empty_signature() {
         let kp = KeyPair::generate();
-        let receipt_no_sig = create_test_receipt(&kp); // signature is Vec::new()
+        let receipt_no_sig = create_test_receipt(&kp); // signature is empty
         let verification_result = verify_embedded_signature(&receipt_no_sig);
         assert!(verification_result.is_err());
         match verification_result.unwrap_err() {
@@ -162,4 +253,39 @@ mod tests {
             _ => panic!("Expected MissingSignature error for empty signature verification"),
         }
     }
+
+    #[test]
+    fn test_sign_and_verify_batch() {
+        let kp = KeyPair::generate();
+        let mut receipts: Vec<_> = (0..1000)
+            .map(|i| {
+                let mut receipt = create_test_receipt(&kp);
+                receipt.job_id = format!("batch_job_{}", i);
+                receipt
+            })
+            .collect();
+
+        sign_batch_in_place(&mut receipts, &kp).expect("Batch signing failed");
+        let results = verify_batch(&receipts);
+        assert_eq!(results.len(), receipts.len());
+        assert!(results.iter().all(Result::is_ok));
+
+        receipts[417].mana_cost = Some(1);
+        let results = verify_batch(&receipts);
+        assert!(matches!(results[417], Err(SignError::VerificationFailed)));
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn test_sign_batch_rejects_foreign_executor_untouched() {
+        let kp = KeyPair::generate();
+        let mut receipts = vec![
+            create_test_receipt(&kp),
+            create_test_receipt(&KeyPair::generate()),
+        ];
+
+        let result = sign_batch_in_place(&mut receipts, &kp);
+        assert!(matches!(result, Err(SignError::ExecutorMismatch { .. })));
+        assert!(receipts.iter().all(|r| r.signature.is_empty()));
+    }
 }
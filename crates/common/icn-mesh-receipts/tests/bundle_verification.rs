@@ -0,0 +1,135 @@
+use chrono::Utc;
+use ed25519_dalek::VerifyingKey;
+use icn_economics::ResourceType;
+use icn_identity::Did;
+use icn_identity::{
+    FederationMetadata, KeyPair, QuorumProof, QuorumType, TaggedSignature, TrustBundle,
+};
+use icn_mesh_receipts::{sign_receipt_in_place, ExecutionReceipt, ReceiptError};
+use icn_types::mesh::JobStatus;
+use std::collections::HashMap;
+
+fn signed_receipt(executor: &KeyPair) -> ExecutionReceipt {
+    let mut receipt = ExecutionReceipt {
+        job_id: "bundle-job".to_string(),
+        executor: executor.did.clone(),
+        status: JobStatus::Completed,
+        result_data_cid: None,
+        logs_cid: None,
+        resource_usage: HashMap::from([(ResourceType::Cpu, 100)]),
+        mana_cost: None,
+        deterministic: false,
+        execution_start_time: 1_672_502_400,
+        execution_end_time: 1_672_506_000,
+        execution_end_time_dt: Utc::now(),
+        signature: TaggedSignature::default(),
+        coop_id: None,
+        community_id: None,
+    };
+    sign_receipt_in_place(&mut receipt, executor).unwrap();
+    receipt
+}
+
+/// A bundle whose quorum proof is signed by `members`
+fn bundle_signed_by(members: &[&KeyPair]) -> TrustBundle {
+    let mut bundle = TrustBundle::new(
+        "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+        FederationMetadata {
+            name: "Receipt Federation".to_string(),
+            description: None,
+            version: "1.0".to_string(),
+            max_token_supply: None,
+            additional: HashMap::new(),
+        },
+    );
+    let hash = bundle.calculate_hash().unwrap();
+    bundle.add_quorum_proof(QuorumProof::new(
+        QuorumType::Majority,
+        members
+            .iter()
+            .map(|kp| (kp.did.clone(), kp.sign(&hash)))
+            .collect(),
+    ));
+    bundle
+}
+
+fn keys_of(members: &[&KeyPair]) -> HashMap<Did, VerifyingKey> {
+    members.iter().map(|kp| (kp.did.clone(), kp.pk)).collect()
+}
+
+#[test]
+fn receipt_from_federation_member_verifies() {
+    let member = KeyPair::generate();
+    let other = KeyPair::generate();
+    let bundle = bundle_signed_by(&[&member, &other]);
+
+    signed_receipt(&member)
+        .verify_against_bundle(&bundle, &keys_of(&[&member, &other]))
+        .unwrap();
+}
+
+#[test]
+fn receipt_from_outsider_is_rejected() {
+    let member = KeyPair::generate();
+    let outsider = KeyPair::generate();
+    let bundle = bundle_signed_by(&[&member]);
+
+    let err = signed_receipt(&outsider)
+        .verify_against_bundle(&bundle, &keys_of(&[&member]))
+        .unwrap_err();
+
+    match err {
+        ReceiptError::ExecutorNotInFederation { executor, .. } => {
+            assert_eq!(executor, outsider.did)
+        }
+        other => panic!("expected ExecutorNotInFederation, got {other:?}"),
+    }
+}
+
+#[test]
+fn tampered_receipt_is_rejected_before_membership() {
+    let member = KeyPair::generate();
+    let bundle = bundle_signed_by(&[&member]);
+    let mut receipt = signed_receipt(&member);
+    receipt.mana_cost = Some(1_000);
+
+    assert!(matches!(
+        receipt.verify_against_bundle(&bundle, &keys_of(&[&member])),
+        Err(ReceiptError::Signature(_))
+    ));
+}
+
+#[test]
+fn extra_proof_entries_do_not_admit_outsiders() {
+    let members = [
+        KeyPair::generate(),
+        KeyPair::generate(),
+        KeyPair::generate(),
+    ];
+    let allowed = keys_of(&[&members[0], &members[1], &members[2]]);
+    let mut bundle = bundle_signed_by(&[&members[0], &members[1]]);
+    let hash = bundle.calculate_hash().unwrap();
+    let proof = bundle.quorum_proof.as_mut().unwrap();
+
+    // Validly signed, but not an allowed signer
+    let unauthorized = KeyPair::generate();
+    proof
+        .add_signature(unauthorized.did.clone(), unauthorized.sign(&hash))
+        .unwrap();
+    // An allowed signer's DID carrying a signature over something else
+    proof
+        .add_signature(members[2].did.clone(), members[2].sign(b"not the bundle"))
+        .unwrap();
+
+    // The two good entries still meet the quorum
+    bundle.verify(&allowed).unwrap();
+    signed_receipt(&members[0])
+        .verify_against_bundle(&bundle, &allowed)
+        .unwrap();
+    for executor in [&unauthorized, &members[2]] {
+        assert!(matches!(
+            signed_receipt(executor).verify_against_bundle(&bundle, &allowed),
+            Err(ReceiptError::ExecutorNotInFederation { .. })
+        ));
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use icn_economics::ResourceType;
-use icn_identity::KeyPair;
+use icn_identity::{KeyPair, TaggedSignature};
 use icn_mesh_receipts::ExecutionReceipt;
 use icn_types::mesh::JobStatus;
 use icn_types::org::{CommunityId, CooperativeId};
@@ -35,10 +35,11 @@ fn test_receipt_with_org_identifiers() {
         execution_start_time: start_time,
         execution_end_time: end_time,
         execution_end_time_dt: end_dt,
-        signature: vec![1, 2, 3, 4],
+        signature: TaggedSignature::from_legacy_bytes(vec![1, 2, 3, 4]),
         coop_id: Some(coop_id.clone()),
         community_id: Some(community_id.clone()),
         mana_cost: None,
+        deterministic: false,
     };
 
     // Check that the organization IDs are stored correctly
@@ -88,10 +89,11 @@ fn test_cid_changes_with_different_orgs() {
         execution_start_time: start_time,
         execution_end_time: end_time,
         execution_end_time_dt: end_dt,
-        signature: vec![1, 2, 3, 4],
+        signature: TaggedSignature::from_legacy_bytes(vec![1, 2, 3, 4]),
         coop_id: None,
         community_id: None,
         mana_cost: None,
+        deterministic: false,
     };
 
     // Create an identical receipt but with coop ID
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use icn_economics::ResourceType;
+use icn_identity::{KeyPair, TaggedSignature, VerifiableCredential};
+use icn_mesh_receipts::{execution_receipt_credential_context, ExecutionReceipt};
+use icn_types::mesh::JobStatus;
+use icn_types::org::CooperativeId;
+use std::collections::HashMap;
+
+#[test]
+fn receipt_credential_survives_json_roundtrip() {
+    let executor = KeyPair::generate();
+    let issuer = KeyPair::generate();
+    let end_dt: DateTime<Utc> = DateTime::from_timestamp(1_672_506_000, 0).unwrap();
+    let receipt = ExecutionReceipt {
+        job_id: "vc-job".to_string(),
+        executor: executor.did.clone(),
+        status: JobStatus::Completed,
+        result_data_cid: Some("bafyresult".to_string()),
+        logs_cid: None,
+        resource_usage: HashMap::from([(ResourceType::Cpu, 250), (ResourceType::Memory, 64)]),
+        mana_cost: Some(12),
+        deterministic: true,
+        execution_start_time: 1_672_502_400,
+        execution_end_time: 1_672_506_000,
+        execution_end_time_dt: end_dt,
+        signature: TaggedSignature::from_legacy_bytes(vec![1, 2, 3, 4]),
+        coop_id: Some(CooperativeId::new("coop-vc")),
+        community_id: None,
+    };
+
+    let vc = receipt.to_verifiable_credential(&issuer.did);
+    assert!(vc
+        .context
+        .iter()
+        .any(|c| *c == execution_receipt_credential_context()));
+    assert!(vc.types.iter().any(|t| t == "ExecutionReceiptCredential"));
+    assert_eq!(vc.issuance_date, end_dt);
+
+    let json = serde_json::to_string(&vc).unwrap();
+    let parsed: VerifiableCredential<ExecutionReceipt> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed.issuer, issuer.did);
+    assert_eq!(parsed.issuance_date, end_dt);
+    assert_eq!(parsed.context, vc.context);
+    assert_eq!(parsed.types, vc.types);
+    assert_eq!(parsed.credential_subject, receipt);
+    assert!(parsed.proof.is_none());
+}
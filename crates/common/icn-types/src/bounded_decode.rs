@@ -0,0 +1,266 @@
+//! Size- and shape-bounded deserialization for externally supplied data.
+//!
+//! Receipts and manifests arrive from guests, peers and files we do not
+//! control. Decoding them with plain `serde_json`/`serde_cbor` lets a payload
+//! that is huge, deeply nested or declares enormous collections exhaust
+//! memory or stack. The helpers here enforce [`DecodeLimits`] before the
+//! payload reaches the target type.
+
+use serde::de::DeserializeOwned;
+use serde_cbor::Value as CborValue;
+use thiserror::Error;
+
+/// Bounds applied while decoding untrusted input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Maximum encoded size in bytes
+    pub max_bytes: usize,
+    /// Maximum nesting depth of arrays/maps/objects
+    pub max_depth: usize,
+    /// Maximum number of elements in any single array or map
+    pub max_collection_len: usize,
+}
+
+impl DecodeLimits {
+    /// Limits suited to a single execution receipt
+    pub const fn receipt() -> Self {
+        Self {
+            max_bytes: 256 * 1024,
+            max_depth: 16,
+            max_collection_len: 4096,
+        }
+    }
+
+    /// Limits suited to a job manifest
+    pub const fn manifest() -> Self {
+        Self {
+            max_bytes: 1024 * 1024,
+            max_depth: 32,
+            max_collection_len: 16_384,
+        }
+    }
+
+    /// Limits suited to the log lines of a single job, a flat list of strings
+    pub const fn logs() -> Self {
+        Self {
+            max_bytes: 1024 * 1024,
+            max_depth: 1,
+            max_collection_len: 16_384,
+        }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self::receipt()
+    }
+}
+
+/// Errors produced by bounded decoding
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("payload of {size} bytes exceeds limit of {limit} bytes")]
+    TooLarge { size: usize, limit: usize },
+
+    #[error("payload nesting exceeds maximum depth of {limit}")]
+    TooDeep { limit: usize },
+
+    #[error("collection of {len} elements exceeds limit of {limit}")]
+    CollectionTooLarge { len: usize, limit: usize },
+
+    #[error("malformed payload: {0}")]
+    Malformed(String),
+}
+
+/// Decode JSON after checking size, nesting depth and collection sizes.
+pub fn from_json_bounded<T: DeserializeOwned>(
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<T, DecodeError> {
+    check_size(bytes, limits)?;
+    scan_json(bytes, limits)?;
+    serde_json::from_slice(bytes).map_err(|e| DecodeError::Malformed(e.to_string()))
+}
+
+/// Decode CBOR after checking size, nesting depth and collection sizes.
+///
+/// The payload is first decoded into a generic value, whose memory is bounded
+/// by the size limit, and walked before conversion into `T`.
+pub fn from_cbor_bounded<T: DeserializeOwned>(
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<T, DecodeError> {
+    check_size(bytes, limits)?;
+    let value: CborValue =
+        serde_cbor::from_slice(bytes).map_err(|e| DecodeError::Malformed(e.to_string()))?;
+    check_cbor_value(&value, 1, limits)?;
+    serde_cbor::value::from_value(value).map_err(|e| DecodeError::Malformed(e.to_string()))
+}
+
+fn check_size(bytes: &[u8], limits: &DecodeLimits) -> Result<(), DecodeError> {
+    if bytes.len() > limits.max_bytes {
+        return Err(DecodeError::TooLarge {
+            size: bytes.len(),
+            limit: limits.max_bytes,
+        });
+    }
+    Ok(())
+}
+
+/// Single pass over the raw JSON text tracking container depth and element
+/// counts. String contents are skipped; syntax errors are left to serde_json.
+fn scan_json(bytes: &[u8], limits: &DecodeLimits) -> Result<(), DecodeError> {
+    // Number of separators seen in each open container
+    let mut separators: Vec<usize> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &b in bytes {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                separators.push(0);
+                if separators.len() > limits.max_depth {
+                    return Err(DecodeError::TooDeep {
+                        limit: limits.max_depth,
+                    });
+                }
+            }
+            b']' | b'}' => {
+                separators.pop();
+            }
+            b',' => {
+                if let Some(count) = separators.last_mut() {
+                    *count += 1;
+                    let len = *count + 1;
+                    if len > limits.max_collection_len {
+                        return Err(DecodeError::CollectionTooLarge {
+                            len,
+                            limit: limits.max_collection_len,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_cbor_value(
+    value: &CborValue,
+    depth: usize,
+    limits: &DecodeLimits,
+) -> Result<(), DecodeError> {
+    let check_len = |len: usize| {
+        if len > limits.max_collection_len {
+            Err(DecodeError::CollectionTooLarge {
+                len,
+                limit: limits.max_collection_len,
+            })
+        } else {
+            Ok(())
+        }
+    };
+    let check_depth = || {
+        if depth > limits.max_depth {
+            Err(DecodeError::TooDeep {
+                limit: limits.max_depth,
+            })
+        } else {
+            Ok(())
+        }
+    };
+
+    match value {
+        CborValue::Array(items) => {
+            check_depth()?;
+            check_len(items.len())?;
+            items
+                .iter()
+                .try_for_each(|item| check_cbor_value(item, depth + 1, limits))
+        }
+        CborValue::Map(entries) => {
+            check_depth()?;
+            check_len(entries.len())?;
+            entries.iter().try_for_each(|(k, v)| {
+                check_cbor_value(k, depth + 1, limits)?;
+                check_cbor_value(v, depth + 1, limits)
+            })
+        }
+        CborValue::Tag(_, inner) => check_cbor_value(inner, depth, limits),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn limits() -> DecodeLimits {
+        DecodeLimits {
+            max_bytes: 1024,
+            max_depth: 4,
+            max_collection_len: 8,
+        }
+    }
+
+    #[test]
+    fn accepts_payload_within_limits() {
+        let value: Value = from_json_bounded(br#"{"a":[1,2,3],"b":"[,,,]"}"#, &limits()).unwrap();
+        assert_eq!(value["a"][2], 3);
+    }
+
+    #[test]
+    fn rejects_oversized_deep_and_wide_json() {
+        let big = vec![b' '; 2048];
+        assert!(matches!(
+            from_json_bounded::<Value>(&big, &limits()),
+            Err(DecodeError::TooLarge { .. })
+        ));
+        assert_eq!(
+            from_json_bounded::<Value>(b"[[[[[1]]]]]", &limits()),
+            Err(DecodeError::TooDeep { limit: 4 })
+        );
+        assert!(matches!(
+            from_json_bounded::<Value>(b"[1,2,3,4,5,6,7,8,9]", &limits()),
+            Err(DecodeError::CollectionTooLarge { len: 9, limit: 8 })
+        ));
+    }
+
+    #[test]
+    fn rejects_deep_and_wide_cbor() {
+        let deep = serde_cbor::to_vec(&vec![vec![vec![vec![vec![1u8]]]]]).unwrap();
+        assert_eq!(
+            from_cbor_bounded::<CborValue>(&deep, &limits()),
+            Err(DecodeError::TooDeep { limit: 4 })
+        );
+        let wide = serde_cbor::to_vec(&(0u8..9).collect::<Vec<_>>()).unwrap();
+        assert!(matches!(
+            from_cbor_bounded::<CborValue>(&wide, &limits()),
+            Err(DecodeError::CollectionTooLarge { .. })
+        ));
+        let ok = serde_cbor::to_vec(&vec![1u8, 2, 3]).unwrap();
+        assert_eq!(from_cbor_bounded::<Vec<u8>>(&ok, &limits()).unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn log_limits_take_a_flat_list_of_lines() {
+        let lines: Vec<String> = from_json_bounded(br#"["a","b"]"#, &DecodeLimits::logs()).unwrap();
+        assert_eq!(lines, vec!["a", "b"]);
+        assert_eq!(
+            from_json_bounded::<Value>(br#"[["a"]]"#, &DecodeLimits::logs()),
+            Err(DecodeError::TooDeep { limit: 1 })
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Provenance of compiled governance artifacts.
+
+use serde::{Deserialize, Serialize};
+
+/// Links a CCL source and the WASM produced from it to the compiler build
+/// that did the compilation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilationManifest {
+    /// Version of the CCL compiler that produced the WASM
+    pub compiler_version: String,
+
+    /// CID of the CCL source that was compiled
+    pub ccl_cid: String,
+
+    /// CID of the resulting WASM module
+    pub wasm_cid: String,
+}
@@ -36,6 +36,14 @@ pub struct DagNode {
     pub scope_id: String,
 }
 
+impl DagNode {
+    /// Bytes this node is charged against its scope's storage quota: the
+    /// length of its serialized content.
+    pub fn stored_size(&self) -> u64 {
+        self.content.len() as u64
+    }
+}
+
 // Custom serializer for Option<Cid>
 fn serialize_cid_option<S>(cid_opt: &Option<Cid>, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use cid::Cid;
+use icn_identity::{Did, KeyPair, TaggedSignature, TaggedSignatureError};
+use tokio::sync::{broadcast, RwLock};
 
-use crate::dag::DagNode;
+use crate::dag::{DagEventType, DagNode};
 use crate::error::DagError;
 
 /// Trait for DAG store operations.
@@ -22,6 +25,364 @@ pub trait DagStore: Send + Sync {
 
     /// Begin a write batch for atomic multi-node operations.
     async fn begin_batch(&self) -> DagStoreBatch;
+
+    /// Subscribe to nodes newly stored by `insert` or a committed batch.
+    ///
+    /// Each event carries the node's CID and event type; subscribers fetch the
+    /// node with `get` if they need its content. Only inserts made after the
+    /// call are delivered.
+    fn subscribe(&self) -> DagSubscription;
+
+    /// List the CIDs of receipt nodes issued by `issuer` with a timestamp at or
+    /// after `since`, ordered by timestamp.
+    ///
+    /// The default implementation scans every node; stores that maintain an
+    /// issuer index should override it.
+    async fn receipts_by_issuer(&self, issuer: &Did, since: u64) -> Result<Vec<Cid>, DagError> {
+        let issuer = issuer.to_string();
+        let mut matches = Vec::new();
+        for node in self.list().await? {
+            if node.timestamp >= since && receipt_issuer(&node).as_deref() == Some(issuer.as_str()) {
+                matches.push((node.timestamp, node.cid()?));
+            }
+        }
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.to_string().cmp(&b.1.to_string())));
+        Ok(matches.into_iter().map(|(_, cid)| cid).collect())
+    }
+}
+
+/// Returns the issuer DID of a receipt node, or `None` for other event types.
+///
+/// The issuer is read from the receipt's `issuer` field; receipts without one
+/// fall back to the node's `scope_id`, which `anchor_receipt` sets to the issuer.
+pub fn receipt_issuer(node: &DagNode) -> Option<String> {
+    if node.event_type != DagEventType::Receipt {
+        return None;
+    }
+    serde_json::from_str::<serde_json::Value>(&node.content)
+        .ok()
+        .and_then(|value| value.get("issuer").and_then(|v| v.as_str()).map(str::to_string))
+        .or_else(|| Some(node.scope_id.clone()))
+}
+
+/// Kind of access checked against an [`AccessPolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessOperation {
+    Read,
+    Write,
+    /// Changing the access policy, or inspecting and repairing the whole store
+    Administer,
+}
+
+impl fmt::Display for AccessOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessOperation::Read => f.write_str("read"),
+            AccessOperation::Write => f.write_str("write"),
+            AccessOperation::Administer => f.write_str("administer"),
+        }
+    }
+}
+
+/// Who may write to, and for private scopes read from, one scope
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopeAccess {
+    /// DIDs allowed to insert and remove nodes in the scope; they may also read it
+    pub writers: HashSet<Did>,
+    /// DIDs allowed to read the scope; `None` makes the scope public
+    pub readers: Option<HashSet<Did>>,
+}
+
+/// Per-scope access control for a [`SharedDagStore`].
+///
+/// The default policy is open: any handle may read and write any scope. An
+/// enforced policy requires the handle's caller (see
+/// [`SharedDagStore::with_caller`]) to be a writer of a scope to write to it,
+/// including scopes with no entry, and to be a reader or writer of a private
+/// scope to read from it.
+///
+/// Only the policy's admins may replace it. A store still on an open policy
+/// without admins accepts a policy from any handle, so set one before handing
+/// out handles; an enforced policy without admins can never be replaced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessPolicy {
+    enforced: bool,
+    scopes: HashMap<String, ScopeAccess>,
+    admins: HashSet<Did>,
+}
+
+impl AccessPolicy {
+    /// Allow everything, ignoring any scope entries
+    pub fn open() -> Self {
+        Self::default()
+    }
+
+    /// Deny writes to every scope and reads of private scopes unless granted
+    pub fn enforced() -> Self {
+        Self {
+            enforced: true,
+            ..Self::default()
+        }
+    }
+
+    /// Set the access rules for `scope`
+    pub fn with_scope(mut self, scope: impl Into<String>, access: ScopeAccess) -> Self {
+        self.scopes.insert(scope.into(), access);
+        self
+    }
+
+    /// Allow `admin` to replace the policy and to check and repair the store
+    pub fn with_admin(mut self, admin: Did) -> Self {
+        self.admins.insert(admin);
+        self
+    }
+
+    pub fn is_enforced(&self) -> bool {
+        self.enforced
+    }
+
+    /// Whether `caller` may administer the store under this policy
+    pub fn allows_admin(&self, caller: Option<&Did>) -> bool {
+        caller.is_some_and(|did| self.admins.contains(did))
+            || (!self.enforced && self.admins.is_empty())
+    }
+
+    /// Whether `caller` may perform `operation` on `scope`. Administering is
+    /// not tied to a scope; see [`allows_admin`](Self::allows_admin).
+    pub fn allows(&self, caller: Option<&Did>, scope: &str, operation: AccessOperation) -> bool {
+        if operation == AccessOperation::Administer {
+            return self.allows_admin(caller);
+        }
+        if !self.enforced {
+            return true;
+        }
+        let access = self.scopes.get(scope);
+        let is_writer = |did: &Did| access.is_some_and(|a| a.writers.contains(did));
+        match operation {
+            AccessOperation::Write => caller.is_some_and(is_writer),
+            AccessOperation::Read => match access.and_then(|a| a.readers.as_ref()) {
+                None => true,
+                Some(readers) => caller.is_some_and(|did| readers.contains(did) || is_writer(did)),
+            },
+            AccessOperation::Administer => unreachable!("handled above"),
+        }
+    }
+
+    /// Like [`allows`](Self::allows), but logs a denial for audit and returns
+    /// `DagError::AccessDenied`
+    pub fn check(
+        &self,
+        caller: Option<&Did>,
+        scope: &str,
+        operation: AccessOperation,
+    ) -> Result<(), DagError> {
+        if self.allows(caller, scope, operation) {
+            return Ok(());
+        }
+        let caller = caller.map_or_else(|| "anonymous".to_string(), |did| did.to_string());
+        tracing::warn!(
+            target: "dag_store::audit",
+            %caller,
+            scope,
+            %operation,
+            "DAG store access denied"
+        );
+        Err(DagError::AccessDenied {
+            caller,
+            scope: scope.to_string(),
+            operation,
+        })
+    }
+}
+
+/// A DID whose control of its signing key has been checked, used as the
+/// caller of a [`SharedDagStore`] handle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedCaller(Did);
+
+impl AuthenticatedCaller {
+    /// The identity of a keypair held by this process
+    pub fn from_keypair(keypair: &KeyPair) -> Self {
+        Self(keypair.did.clone())
+    }
+
+    /// A remote caller that signed `challenge` with the key behind `did`.
+    ///
+    /// `challenge` must be fresh, e.g. a nonce issued for this request, or a
+    /// captured signature could be replayed.
+    pub fn verify(
+        did: Did,
+        challenge: &[u8],
+        signature: &TaggedSignature,
+    ) -> Result<Self, TaggedSignatureError> {
+        signature.verify(&did, challenge)?;
+        Ok(Self(did))
+    }
+
+    pub fn did(&self) -> &Did {
+        &self.0
+    }
+}
+
+/// A node stored by `insert` or a committed batch
+#[derive(Debug, Clone)]
+struct InsertEvent {
+    cid: Cid,
+    event_type: DagEventType,
+    scope_id: String,
+}
+
+/// Insert notifications from [`DagStore::subscribe`].
+///
+/// Events for nodes in scopes the subscribing handle may not read are
+/// skipped, under the access policy in force when the event is received.
+pub struct DagSubscription {
+    events: broadcast::Receiver<InsertEvent>,
+    policy: Arc<std::sync::RwLock<AccessPolicy>>,
+    caller: Option<Did>,
+}
+
+impl DagSubscription {
+    fn readable(&self, event: &InsertEvent) -> bool {
+        self.policy.read().unwrap().allows(
+            self.caller.as_ref(),
+            &event.scope_id,
+            AccessOperation::Read,
+        )
+    }
+
+    /// Wait for the next readable insert
+    pub async fn recv(&mut self) -> Result<(Cid, DagEventType), broadcast::error::RecvError> {
+        loop {
+            let event = self.events.recv().await?;
+            if self.readable(&event) {
+                return Ok((event.cid, event.event_type));
+            }
+        }
+    }
+
+    /// Take the next readable insert if one is already buffered
+    pub fn try_recv(&mut self) -> Result<(Cid, DagEventType), broadcast::error::TryRecvError> {
+        loop {
+            let event = self.events.try_recv()?;
+            if self.readable(&event) {
+                return Ok((event.cid, event.event_type));
+            }
+        }
+    }
+}
+
+/// Nodes keyed by CID string, plus a secondary index from issuer DID to the
+/// `(timestamp, cid)` pairs of the receipts it issued.
+#[derive(Default)]
+struct DagStoreState {
+    nodes: HashMap<String, DagNode>,
+    issuer_index: HashMap<String, BTreeSet<(u64, String)>>,
+    /// Nodes removed by an integrity repair, keyed by the ID they were stored under
+    quarantine: HashMap<String, DagNode>,
+    /// Bytes stored per `scope_id`, as measured by `DagNode::stored_size`
+    scope_usage: HashMap<String, u64>,
+    /// Per-scope storage limits in bytes, overriding `default_scope_quota`
+    scope_quotas: HashMap<String, u64>,
+    /// Limit applied to scopes without an explicit quota; `None` is unlimited
+    default_scope_quota: Option<u64>,
+}
+
+impl DagStoreState {
+    /// Store `node` under `id`, returning false if it replaced an existing node
+    fn insert(&mut self, id: String, node: DagNode) -> bool {
+        let previous = self.nodes.remove(&id);
+        if let Some(previous) = &previous {
+            self.unindex(&id, previous);
+        }
+        if let Some(issuer) = receipt_issuer(&node) {
+            self.issuer_index
+                .entry(issuer)
+                .or_default()
+                .insert((node.timestamp, id.clone()));
+        }
+        *self.scope_usage.entry(node.scope_id.clone()).or_default() += node.stored_size();
+        self.nodes.insert(id, node);
+        previous.is_none()
+    }
+
+    fn quota_for(&self, scope: &str) -> Option<u64> {
+        self.scope_quotas
+            .get(scope)
+            .copied()
+            .or(self.default_scope_quota)
+    }
+
+    /// Check that applying `ops` (`None` = remove) would keep every scope that
+    /// grows within its quota. Scopes that shrink or stay level are always
+    /// allowed, so removals still work after a quota is lowered.
+    fn check_quotas<'a>(
+        &self,
+        ops: impl IntoIterator<Item = (&'a String, Option<&'a DagNode>)>,
+    ) -> Result<(), DagError> {
+        let mut projected: HashMap<&str, u64> = HashMap::new();
+        let mut largest_added: HashMap<&str, u64> = HashMap::new();
+        for (id, op) in ops {
+            if let Some(existing) = self.nodes.get(id) {
+                let usage = projected
+                    .entry(existing.scope_id.as_str())
+                    .or_insert_with(|| self.scope_usage(&existing.scope_id));
+                *usage = usage.saturating_sub(existing.stored_size());
+            }
+            if let Some(node) = op {
+                let size = node.stored_size();
+                let usage = projected
+                    .entry(node.scope_id.as_str())
+                    .or_insert_with(|| self.scope_usage(&node.scope_id));
+                *usage += size;
+                let largest = largest_added.entry(node.scope_id.as_str()).or_default();
+                *largest = (*largest).max(size);
+            }
+        }
+
+        for (scope, usage) in projected {
+            let current = self.scope_usage(scope);
+            match self.quota_for(scope) {
+                Some(quota) if usage > quota && usage > current => {
+                    return Err(DagError::ScopeQuotaExceeded {
+                        scope: scope.to_string(),
+                        usage: current,
+                        quota,
+                        node_size: largest_added.get(scope).copied().unwrap_or(0),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn scope_usage(&self, scope: &str) -> u64 {
+        self.scope_usage.get(scope).copied().unwrap_or(0)
+    }
+
+    fn remove(&mut self, id: &str) {
+        if let Some(node) = self.nodes.remove(id) {
+            self.unindex(id, &node);
+        }
+    }
+
+    fn unindex(&mut self, id: &str, node: &DagNode) {
+        if let Some(usage) = self.scope_usage.get_mut(&node.scope_id) {
+            *usage = usage.saturating_sub(node.stored_size());
+            if *usage == 0 {
+                self.scope_usage.remove(&node.scope_id);
+            }
+        }
+        if let Some(issuer) = receipt_issuer(node) {
+            if let Some(entries) = self.issuer_index.get_mut(&issuer) {
+                entries.remove(&(node.timestamp, id.to_string()));
+                if entries.is_empty() {
+                    self.issuer_index.remove(&issuer);
+                }
+            }
+        }
+    }
 }
 
 /// In-memory, async, transactional DAG store.
@@ -33,6 +394,7 @@ pub trait DagStore: Send + Sync {
 /// - Thread-safe concurrent access to DAG nodes
 /// - Transactional batch operations via `DagStoreBatch`
 /// - Optimized for read-heavy workloads (multiple readers can access simultaneously)
+/// - Insert notifications via `subscribe`, so consumers need not poll
 ///
 /// # Example
 /// ```
@@ -61,50 +423,317 @@ pub trait DagStore: Send + Sync {
 ///     assert_eq!(retrieved, Some(node));
 /// }
 /// ```
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct SharedDagStore {
-    // HashMap key is the CID of the DAG node as string
-    inner: Arc<RwLock<HashMap<String, DagNode>>>,
+    // Node map is keyed by the CID of the DAG node as string
+    inner: Arc<RwLock<DagStoreState>>,
+    events: broadcast::Sender<InsertEvent>,
+    /// Shared by every handle; a std lock so subscriptions can check it synchronously
+    access_policy: Arc<std::sync::RwLock<AccessPolicy>>,
+    /// DID checked against the access policy for operations through this handle
+    caller: Option<Did>,
+}
+
+/// Insert events buffered per subscriber; a subscriber that falls further
+/// behind receives `RecvError::Lagged` and skips the oldest events.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+impl Default for SharedDagStore {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SharedDagStore {
     /// Create a new empty SharedDagStore
     pub fn new() -> Self {
+        Self::with_state(DagStoreState::default())
+    }
+
+    /// Create a store where every scope without an explicit quota may hold at
+    /// most `bytes` of node content.
+    pub fn with_default_scope_quota(bytes: u64) -> Self {
+        Self::with_state(DagStoreState {
+            default_scope_quota: Some(bytes),
+            ..Default::default()
+        })
+    }
+
+    fn with_state(state: DagStoreState) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            inner: Arc::new(RwLock::new(state)),
+            events,
+            access_policy: Arc::new(std::sync::RwLock::new(AccessPolicy::default())),
+            caller: None,
+        }
+    }
+
+    /// A handle to the same store whose operations are checked against the
+    /// access policy as `caller`. Handles without a caller are anonymous.
+    pub fn with_caller(&self, caller: AuthenticatedCaller) -> Self {
         Self {
-            inner: Arc::new(RwLock::new(HashMap::new())),
+            caller: Some(caller.0),
+            ..self.clone()
+        }
+    }
+
+    pub fn caller(&self) -> Option<&Did> {
+        self.caller.as_ref()
+    }
+
+    /// Replace the access policy shared by every handle to this store.
+    ///
+    /// Fails with `DagError::AccessDenied` unless this handle's caller may
+    /// administer the store under the current policy.
+    pub async fn set_access_policy(&self, policy: AccessPolicy) -> Result<(), DagError> {
+        let mut current = self.access_policy.write().unwrap();
+        current.check(self.caller(), "*", AccessOperation::Administer)?;
+        *current = policy;
+        Ok(())
+    }
+
+    pub async fn access_policy(&self) -> AccessPolicy {
+        self.access_policy.read().unwrap().clone()
+    }
+
+    fn check_access(&self, scope: &str, operation: AccessOperation) -> Result<(), DagError> {
+        self.access_policy
+            .read()
+            .unwrap()
+            .check(self.caller(), scope, operation)
+    }
+
+    fn may_read(&self, scope: &str) -> bool {
+        self.access_policy
+            .read()
+            .unwrap()
+            .allows(self.caller(), scope, AccessOperation::Read)
+    }
+
+    fn notify_inserted(&self, cid: Cid, node: &DagNode) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.events.send(InsertEvent {
+            cid,
+            event_type: node.event_type.clone(),
+            scope_id: node.scope_id.clone(),
+        });
+    }
+
+    /// Limit the bytes of node content stored under `scope`. Inserts that would
+    /// push the scope past the limit fail with `DagError::ScopeQuotaExceeded`;
+    /// nodes already stored are left in place.
+    pub async fn set_scope_quota(&self, scope: impl Into<String>, bytes: u64) {
+        self.inner.write().await.scope_quotas.insert(scope.into(), bytes);
+    }
+
+    /// Remove the explicit quota for `scope`, falling back to the default quota.
+    pub async fn clear_scope_quota(&self, scope: &str) {
+        self.inner.write().await.scope_quotas.remove(scope);
+    }
+
+    /// Bytes of node content currently stored under `scope`.
+    pub async fn scope_storage_usage(&self, scope: &str) -> u64 {
+        self.inner.read().await.scope_usage(scope)
+    }
+}
+
+/// A stored node whose content does not hash to the ID it is stored under.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorruptNode {
+    /// ID (CID string) the node is stored under
+    pub stored_id: String,
+    /// CID recomputed from the node content, if it could be computed
+    pub computed_cid: Option<String>,
+    /// Human-readable description of the problem
+    pub reason: String,
+}
+
+/// A node whose parent link points at a CID that is not in the store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DanglingLink {
+    /// ID of the node holding the link
+    pub node_id: String,
+    /// Parent CID that could not be found
+    pub missing_parent: Cid,
+}
+
+/// Result of a `SharedDagStore::check_integrity` scan.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    /// Number of nodes scanned
+    pub nodes_checked: usize,
+    /// Nodes whose content does not match their CID
+    pub corrupt_nodes: Vec<CorruptNode>,
+    /// Parent links to nodes that are not stored
+    pub dangling_links: Vec<DanglingLink>,
+    /// Number of corrupt nodes moved to quarantine (repair only)
+    pub quarantined: usize,
+}
+
+impl IntegrityReport {
+    /// Returns true if no corruption or dangling links were found
+    pub fn is_clean(&self) -> bool {
+        self.corrupt_nodes.is_empty() && self.dangling_links.is_empty()
+    }
+}
+
+impl SharedDagStore {
+    /// Scan all nodes, verifying that each one's content hashes to the CID it is
+    /// stored under and that every parent link resolves to a stored node.
+    ///
+    /// The report covers every scope, so the caller must be able to administer
+    /// the store.
+    pub async fn check_integrity(&self) -> Result<IntegrityReport, DagError> {
+        self.check_access("*", AccessOperation::Administer)?;
+        let state = self.inner.read().await;
+        Ok(Self::scan(&state))
+    }
+
+    /// Like `check_integrity`, but additionally moves corrupt nodes into
+    /// quarantine so they are no longer served by `get`/`list`.
+    ///
+    /// Dangling links are reported but not repaired, since the missing parent
+    /// cannot be reconstructed locally.
+    pub async fn check_and_repair(&self) -> Result<IntegrityReport, DagError> {
+        self.check_access("*", AccessOperation::Administer)?;
+        let mut state = self.inner.write().await;
+        let mut report = Self::scan(&state);
+        for corrupt in &report.corrupt_nodes {
+            if let Some(node) = state.nodes.get(&corrupt.stored_id).cloned() {
+                state.remove(&corrupt.stored_id);
+                state.quarantine.insert(corrupt.stored_id.clone(), node);
+                report.quarantined += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Nodes moved out of the store by `check_and_repair`, keyed by their stored
+    /// ID, limited to scopes the caller may read
+    pub async fn quarantined(&self) -> HashMap<String, DagNode> {
+        self.inner
+            .read()
+            .await
+            .quarantine
+            .iter()
+            .filter(|(_, node)| self.may_read(&node.scope_id))
+            .map(|(id, node)| (id.clone(), node.clone()))
+            .collect()
+    }
+
+    fn scan(state: &DagStoreState) -> IntegrityReport {
+        let mut report = IntegrityReport {
+            nodes_checked: state.nodes.len(),
+            ..Default::default()
+        };
+
+        for (id, node) in &state.nodes {
+            match node.cid() {
+                Ok(cid) if cid.to_string() == *id => {}
+                Ok(cid) => report.corrupt_nodes.push(CorruptNode {
+                    stored_id: id.clone(),
+                    computed_cid: Some(cid.to_string()),
+                    reason: "content does not hash to stored CID".to_string(),
+                }),
+                Err(e) => report.corrupt_nodes.push(CorruptNode {
+                    stored_id: id.clone(),
+                    computed_cid: None,
+                    reason: format!("failed to compute CID: {}", e),
+                }),
+            }
+
+            if let Some(parent) = node.parent {
+                if !state.nodes.contains_key(&parent.to_string()) {
+                    report.dangling_links.push(DanglingLink {
+                        node_id: id.clone(),
+                        missing_parent: parent,
+                    });
+                }
+            }
         }
+
+        // HashMap iteration order is arbitrary; keep reports stable
+        report.corrupt_nodes.sort_by(|a, b| a.stored_id.cmp(&b.stored_id));
+        report.dangling_links.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        report
     }
 }
 
 #[async_trait::async_trait]
 impl DagStore for SharedDagStore {
     async fn get(&self, id: &str) -> Result<Option<DagNode>, DagError> {
-        let map = self.inner.read().await;
-        Ok(map.get(id).cloned())
+        let state = self.inner.read().await;
+        let Some(node) = state.nodes.get(id) else {
+            return Ok(None);
+        };
+        self.check_access(&node.scope_id, AccessOperation::Read)?;
+        Ok(Some(node.clone()))
     }
 
     async fn insert(&self, node: DagNode) -> Result<(), DagError> {
         let cid = node.cid()?;
         let id = cid.to_string();
-        let mut map = self.inner.write().await;
-        map.insert(id, node);
+        let mut state = self.inner.write().await;
+        self.check_access(&node.scope_id, AccessOperation::Write)?;
+        state.check_quotas([(&id, Some(&node))])?;
+        let is_new = state.insert(id, node.clone());
+        drop(state);
+        if is_new {
+            self.notify_inserted(cid, &node);
+        }
         Ok(())
     }
 
     async fn remove(&self, id: &str) -> Result<(), DagError> {
-        let mut map = self.inner.write().await;
-        map.remove(id);
+        let mut state = self.inner.write().await;
+        if let Some(node) = state.nodes.get(id) {
+            self.check_access(&node.scope_id, AccessOperation::Write)?;
+        }
+        state.remove(id);
         Ok(())
     }
 
+    /// Lists only the nodes in scopes the caller may read
     async fn list(&self) -> Result<Vec<DagNode>, DagError> {
-        let map = self.inner.read().await;
-        Ok(map.values().cloned().collect())
+        let state = self.inner.read().await;
+        Ok(state
+            .nodes
+            .values()
+            .filter(|node| self.may_read(&node.scope_id))
+            .cloned()
+            .collect())
     }
 
     async fn begin_batch(&self) -> DagStoreBatch {
         DagStoreBatch::new(self.clone())
     }
+
+    fn subscribe(&self) -> DagSubscription {
+        DagSubscription {
+            events: self.events.subscribe(),
+            policy: self.access_policy.clone(),
+            caller: self.caller.clone(),
+        }
+    }
+
+    /// Lists only receipts in scopes the caller may read
+    async fn receipts_by_issuer(&self, issuer: &Did, since: u64) -> Result<Vec<Cid>, DagError> {
+        let state = self.inner.read().await;
+        let Some(entries) = state.issuer_index.get(&issuer.to_string()) else {
+            return Ok(Vec::new());
+        };
+        entries
+            .range((since, String::new())..)
+            .filter(|(_, id)| {
+                state
+                    .nodes
+                    .get(id)
+                    .is_some_and(|node| self.may_read(&node.scope_id))
+            })
+            .map(|(_, id)| Cid::try_from(id.as_str()).map_err(DagError::from))
+            .collect()
+    }
 }
 
 /// Write-batch for atomic multi-node operations.
@@ -152,7 +781,7 @@ impl DagStore for SharedDagStore {
 pub struct DagStoreBatch {
     store: SharedDagStore,
     // None = remove, Some = insert
-    staged: HashMap<String, Option<DagNode>>,
+    staged: HashMap<String, Option<(Cid, DagNode)>>,
     committed: bool,
 }
 
@@ -169,7 +798,7 @@ impl DagStoreBatch {
     pub async fn insert(&mut self, node: DagNode) -> Result<(), DagError> {
         let cid = node.cid()?;
         let id = cid.to_string();
-        self.staged.insert(id, Some(node));
+        self.staged.insert(id, Some((cid, node)));
         Ok(())
     }
 
@@ -179,20 +808,44 @@ impl DagStoreBatch {
         Ok(())
     }
 
-    /// Atomically commit all staged changes
+    /// Atomically commit all staged changes.
+    ///
+    /// Fails without applying anything if the batch would push a scope past
+    /// its storage quota or writes a scope the store handle's caller may not.
     pub async fn commit(mut self) -> Result<(), DagError> {
-        let mut map = self.store.inner.write().await;
+        let mut state = self.store.inner.write().await;
+        for (id, op) in &self.staged {
+            let scope = match op {
+                Some((_, node)) => Some(&node.scope_id),
+                None => state.nodes.get(id).map(|node| &node.scope_id),
+            };
+            if let Some(scope) = scope {
+                self.store.check_access(scope, AccessOperation::Write)?;
+            }
+        }
+        state.check_quotas(
+            self.staged
+                .iter()
+                .map(|(id, op)| (id, op.as_ref().map(|(_, node)| node))),
+        )?;
+        let mut inserted = Vec::new();
         for (id, op) in self.staged.drain() {
             match op {
-                Some(node) => {
-                    map.insert(id, node);
+                Some((cid, node)) => {
+                    if state.insert(id, node.clone()) {
+                        inserted.push((cid, node));
+                    }
                 }
                 None => {
-                    map.remove(&id);
+                    state.remove(&id);
                 }
             }
         }
+        drop(state);
         self.committed = true;
+        for (cid, node) in inserted {
+            self.store.notify_inserted(cid, &node);
+        }
         Ok(())
     }
 
@@ -355,4 +1008,396 @@ mod tests {
         assert!(store.get(&node1_id).await.unwrap().is_none());
         assert!(store.get(&node2_id).await.unwrap().is_some()); // node2 should still be there
     }
+
+    #[tokio::test]
+    async fn test_check_integrity_and_repair() {
+        let store = SharedDagStore::new();
+
+        let root = DagNodeBuilder::new()
+            .content("root".into())
+            .event_type(DagEventType::Genesis)
+            .scope_id("s".into())
+            .timestamp(0)
+            .build()
+            .unwrap();
+        let root_cid = root.cid().unwrap();
+        let child = DagNodeBuilder::new()
+            .content("child".into())
+            .event_type(DagEventType::Proposal)
+            .scope_id("s".into())
+            .parent(root_cid)
+            .timestamp(1)
+            .build()
+            .unwrap();
+        let child_id = child.cid().unwrap().to_string();
+
+        store.insert(root.clone()).await.unwrap();
+        store.insert(child.clone()).await.unwrap();
+        assert!(store.check_integrity().await.unwrap().is_clean());
+
+        // Removing the parent leaves a dangling link
+        store.remove(&root_cid.to_string()).await.unwrap();
+        let report = store.check_integrity().await.unwrap();
+        assert_eq!(
+            report.dangling_links,
+            vec![DanglingLink {
+                node_id: child_id.clone(),
+                missing_parent: root_cid,
+            }]
+        );
+        assert!(report.corrupt_nodes.is_empty());
+
+        // Tamper with stored content so it no longer matches its CID
+        store.insert(root.clone()).await.unwrap();
+        store
+            .inner
+            .write()
+            .await
+            .nodes
+            .get_mut(&child_id)
+            .unwrap()
+            .content = "tampered".into();
+
+        let report = store.check_and_repair().await.unwrap();
+        assert_eq!(report.corrupt_nodes.len(), 1);
+        assert_eq!(report.corrupt_nodes[0].stored_id, child_id);
+        assert_eq!(report.quarantined, 1);
+        assert!(store.get(&child_id).await.unwrap().is_none());
+        assert!(store.quarantined().await.contains_key(&child_id));
+        assert!(store.check_integrity().await.unwrap().is_clean());
+    }
+
+    fn receipt_node(issuer: &Did, timestamp: u64) -> DagNode {
+        DagNodeBuilder::new()
+            .content(format!(r#"{{"issuer":"{}","job_id":"job-{}"}}"#, issuer, timestamp))
+            .event_type(DagEventType::Receipt)
+            .scope_id(issuer.to_string())
+            .timestamp(timestamp)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_receipts_by_issuer_index() {
+        let store = SharedDagStore::new();
+        let issuer = icn_identity::KeyPair::generate().did;
+        let other = icn_identity::KeyPair::generate().did;
+
+        let early = receipt_node(&issuer, 10);
+        let late = receipt_node(&issuer, 20);
+        let foreign = receipt_node(&other, 15);
+        let not_a_receipt = DagNodeBuilder::new()
+            .content("proposal".into())
+            .event_type(DagEventType::Proposal)
+            .scope_id(issuer.to_string())
+            .timestamp(30)
+            .build()
+            .unwrap();
+
+        store.insert(late.clone()).await.unwrap();
+        store.insert(foreign.clone()).await.unwrap();
+        store.insert(not_a_receipt).await.unwrap();
+        let mut batch = store.begin_batch().await;
+        batch.insert(early.clone()).await.unwrap();
+        batch.commit().await.unwrap();
+
+        let all = store.receipts_by_issuer(&issuer, 0).await.unwrap();
+        assert_eq!(all, vec![early.cid().unwrap(), late.cid().unwrap()]);
+
+        let since = store.receipts_by_issuer(&issuer, 11).await.unwrap();
+        assert_eq!(since, vec![late.cid().unwrap()]);
+
+        // Removal drops the node from the index
+        store.remove(&late.cid().unwrap().to_string()).await.unwrap();
+        let after_remove = store.receipts_by_issuer(&issuer, 0).await.unwrap();
+        assert_eq!(after_remove, vec![early.cid().unwrap()]);
+
+        let foreign_only = store.receipts_by_issuer(&other, 0).await.unwrap();
+        assert_eq!(foreign_only, vec![foreign.cid().unwrap()]);
+    }
+
+    fn scoped_node(scope: &str, content: &str) -> DagNode {
+        DagNodeBuilder::new()
+            .content(content.to_string())
+            .event_type(DagEventType::Anchor)
+            .scope_id(scope.to_string())
+            .timestamp(0)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_scope_storage_usage_and_quota() {
+        let store = SharedDagStore::new();
+        store.set_scope_quota("coop-a", 10).await;
+
+        let small = scoped_node("coop-a", "123456");
+        store.insert(small.clone()).await.unwrap();
+        // Re-inserting the same node does not double count
+        store.insert(small.clone()).await.unwrap();
+        assert_eq!(store.scope_storage_usage("coop-a").await, 6);
+
+        match store.insert(scoped_node("coop-a", "too big")).await {
+            Err(DagError::ScopeQuotaExceeded {
+                scope,
+                usage,
+                quota,
+                node_size,
+            }) => {
+                assert_eq!(scope, "coop-a");
+                assert_eq!((usage, quota, node_size), (6, 10, 7));
+            }
+            other => panic!("expected ScopeQuotaExceeded, got {:?}", other),
+        }
+        assert_eq!(store.scope_storage_usage("coop-a").await, 6);
+
+        // Other scopes are unaffected, and removal frees space
+        store.insert(scoped_node("coop-b", "too big")).await.unwrap();
+        assert_eq!(store.scope_storage_usage("coop-b").await, 7);
+        store.remove(&small.cid().unwrap().to_string()).await.unwrap();
+        assert_eq!(store.scope_storage_usage("coop-a").await, 0);
+        store.insert(scoped_node("coop-a", "too big")).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_default_scope_quota_applies_to_batches() {
+        let store = SharedDagStore::with_default_scope_quota(8);
+        store.set_scope_quota("roomy", 100).await;
+
+        let mut batch = store.begin_batch().await;
+        batch.insert(scoped_node("tight", "abcde")).await.unwrap();
+        batch.insert(scoped_node("tight", "fghij")).await.unwrap();
+        batch.insert(scoped_node("roomy", "abcdefghij")).await.unwrap();
+        assert!(matches!(
+            batch.commit().await,
+            Err(DagError::ScopeQuotaExceeded { ref scope, .. }) if scope == "tight"
+        ));
+        // Nothing from the rejected batch was applied
+        assert_eq!(store.scope_storage_usage("tight").await, 0);
+        assert_eq!(store.scope_storage_usage("roomy").await, 0);
+
+        store.insert(scoped_node("roomy", "abcdefghij")).await.unwrap();
+        store.clear_scope_quota("roomy").await;
+        assert!(store
+            .insert(scoped_node("roomy", "0123456789"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_notifies_new_inserts() {
+        let store = SharedDagStore::new();
+        let mut events = store.subscribe();
+
+        let proposal = scoped_node("s", "proposal");
+        store.insert(proposal.clone()).await.unwrap();
+        // Re-inserting a stored node is not a new anchor
+        store.insert(proposal.clone()).await.unwrap();
+
+        let issuer = icn_identity::KeyPair::generate().did;
+        let receipt = receipt_node(&issuer, 5);
+        let mut batch = store.begin_batch().await;
+        batch.insert(receipt.clone()).await.unwrap();
+        batch.commit().await.unwrap();
+
+        // Rejected inserts and rolled-back batches produce no events
+        store.set_scope_quota("full", 0).await;
+        assert!(store.insert(scoped_node("full", "x")).await.is_err());
+        let mut discarded = store.begin_batch().await;
+        discarded.insert(scoped_node("s", "discarded")).await.unwrap();
+        discarded.rollback();
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            (proposal.cid().unwrap(), DagEventType::Anchor)
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            (receipt.cid().unwrap(), DagEventType::Receipt)
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_access_policy_guards_scopes() {
+        let store = SharedDagStore::new();
+        let owner = KeyPair::generate();
+        let auditor = KeyPair::generate();
+        let outsider = KeyPair::generate();
+
+        // Open by default
+        let public = scoped_node("public", "notice");
+        store.insert(public.clone()).await.unwrap();
+        let private = scoped_node("coop-a", "ledger");
+        store.insert(private.clone()).await.unwrap();
+
+        store
+            .set_access_policy(
+                AccessPolicy::enforced()
+                    .with_admin(owner.did.clone())
+                    .with_scope(
+                        "public",
+                        ScopeAccess {
+                            writers: HashSet::from([owner.did.clone()]),
+                            readers: None,
+                        },
+                    )
+                    .with_scope(
+                        "coop-a",
+                        ScopeAccess {
+                            writers: HashSet::from([owner.did.clone()]),
+                            readers: Some(HashSet::from([auditor.did.clone()])),
+                        },
+                    ),
+            )
+            .await
+            .unwrap();
+        let as_owner = store.with_caller(AuthenticatedCaller::from_keypair(&owner));
+        let as_auditor = store.with_caller(AuthenticatedCaller::from_keypair(&auditor));
+        let as_outsider = store.with_caller(AuthenticatedCaller::from_keypair(&outsider));
+
+        as_owner.insert(scoped_node("coop-a", "entry")).await.unwrap();
+        match as_outsider.insert(scoped_node("coop-a", "forged")).await {
+            Err(DagError::AccessDenied {
+                caller,
+                scope,
+                operation,
+            }) => {
+                assert_eq!(caller, outsider.did.to_string());
+                assert_eq!(scope, "coop-a");
+                assert_eq!(operation, AccessOperation::Write);
+            }
+            other => panic!("expected AccessDenied, got {:?}", other),
+        }
+        // Scopes without an entry are not writable once enforced
+        assert!(as_owner.insert(scoped_node("other", "x")).await.is_err());
+        let mut batch = as_auditor.begin_batch().await;
+        batch.insert(scoped_node("coop-a", "batched")).await.unwrap();
+        assert!(batch.commit().await.is_err());
+
+        let private_id = private.cid().unwrap().to_string();
+        assert_eq!(as_auditor.get(&private_id).await.unwrap(), Some(private.clone()));
+        assert_eq!(as_owner.get(&private_id).await.unwrap(), Some(private));
+        assert!(as_outsider.get(&private_id).await.is_err());
+        assert!(store.get(&private_id).await.is_err());
+
+        let public_id = public.cid().unwrap().to_string();
+        assert_eq!(as_outsider.get(&public_id).await.unwrap(), Some(public.clone()));
+        assert_eq!(as_outsider.list().await.unwrap(), vec![public]);
+    }
+
+    #[test]
+    fn test_authenticated_caller_requires_a_valid_signature() {
+        let keypair = KeyPair::generate();
+        let challenge = b"nonce-1";
+        let signature: TaggedSignature = keypair.sign(challenge).into();
+
+        let caller =
+            AuthenticatedCaller::verify(keypair.did.clone(), challenge, &signature).unwrap();
+        assert_eq!(caller.did(), &keypair.did);
+        assert!(AuthenticatedCaller::verify(keypair.did.clone(), b"nonce-2", &signature).is_err());
+        let impostor = KeyPair::generate().did;
+        assert!(AuthenticatedCaller::verify(impostor, challenge, &signature).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_only_admins_replace_the_policy() {
+        let store = SharedDagStore::new();
+        let admin = KeyPair::generate();
+        let as_admin = store.with_caller(AuthenticatedCaller::from_keypair(&admin));
+        let as_other = store.with_caller(AuthenticatedCaller::from_keypair(&KeyPair::generate()));
+
+        // An open store without admins takes its first policy from anyone
+        store
+            .set_access_policy(AccessPolicy::enforced().with_admin(admin.did.clone()))
+            .await
+            .unwrap();
+
+        for handle in [&store, &as_other] {
+            match handle.set_access_policy(AccessPolicy::default()).await {
+                Err(DagError::AccessDenied {
+                    scope, operation, ..
+                }) => {
+                    assert_eq!(scope, "*");
+                    assert_eq!(operation, AccessOperation::Administer);
+                }
+                other => panic!("expected AccessDenied, got {:?}", other),
+            }
+            assert!(handle.check_integrity().await.is_err());
+            assert!(handle.check_and_repair().await.is_err());
+        }
+        assert!(store.access_policy().await.is_enforced());
+
+        assert!(as_admin.check_integrity().await.unwrap().is_clean());
+        as_admin
+            .set_access_policy(AccessPolicy::default())
+            .await
+            .unwrap();
+        assert!(!store.access_policy().await.is_enforced());
+    }
+
+    #[tokio::test]
+    async fn test_every_read_path_honors_the_policy() {
+        let store = SharedDagStore::new();
+        let admin = KeyPair::generate();
+        let issuer = KeyPair::generate().did;
+        let as_admin = store.with_caller(AuthenticatedCaller::from_keypair(&admin));
+        let as_other = store.with_caller(AuthenticatedCaller::from_keypair(&KeyPair::generate()));
+        let mut admin_events = as_admin.subscribe();
+        let mut other_events = as_other.subscribe();
+
+        let receipt = receipt_node(&issuer, 5);
+        store.insert(receipt.clone()).await.unwrap();
+        let private = scoped_node("coop-a", "ledger");
+        let private_id = private.cid().unwrap().to_string();
+        store.insert(private.clone()).await.unwrap();
+
+        // The issuer's receipts and the coop's nodes are private to the admin
+        let private_to_admin = ScopeAccess {
+            writers: HashSet::from([admin.did.clone()]),
+            readers: Some(HashSet::new()),
+        };
+        store
+            .set_access_policy(
+                AccessPolicy::enforced()
+                    .with_admin(admin.did.clone())
+                    .with_scope(issuer.to_string(), private_to_admin.clone())
+                    .with_scope("coop-a", private_to_admin),
+            )
+            .await
+            .unwrap();
+
+        // Subscriptions are filtered when events are received
+        assert_eq!(
+            admin_events.try_recv().unwrap(),
+            (receipt.cid().unwrap(), DagEventType::Receipt)
+        );
+        assert_eq!(
+            admin_events.try_recv().unwrap(),
+            (private.cid().unwrap(), DagEventType::Anchor)
+        );
+        assert!(other_events.try_recv().is_err());
+
+        assert_eq!(
+            as_admin.receipts_by_issuer(&issuer, 0).await.unwrap(),
+            vec![receipt.cid().unwrap()]
+        );
+        assert!(as_other
+            .receipts_by_issuer(&issuer, 0)
+            .await
+            .unwrap()
+            .is_empty());
+
+        store
+            .inner
+            .write()
+            .await
+            .nodes
+            .get_mut(&private_id)
+            .unwrap()
+            .content = "tampered".into();
+        as_admin.check_and_repair().await.unwrap();
+        assert!(as_admin.quarantined().await.contains_key(&private_id));
+        assert!(as_other.quarantined().await.is_empty());
+    }
 }
@@ -33,6 +33,18 @@ pub enum EconomicsError {
         scope: String,
     },
 
+    #[error("Periodic quota exceeded for {resource_type} in scope {scope}: quota={quota}/{period_seconds}s, current_usage_in_window={current_usage_in_window}, requested={requested_amount}, window resets at {resets_at}")]
+    PeriodicQuotaExceeded {
+        quota: u64,
+        period_seconds: u64,
+        current_usage_in_window: u64,
+        requested_amount: u64,
+        /// Epoch second at which the current window ends and usage starts over
+        resets_at: u64,
+        resource_type: String,
+        scope: String,
+    },
+
     #[error("Access denied for DID {did} to resource {resource_type} in scope {scope}")]
     AccessDenied {
         did: Did,
@@ -281,6 +293,22 @@ pub enum DagError {
     #[error("DAG traversal failed: {reason}")]
     TraversalFailure { reason: String },
 
+    #[error("Storage quota exceeded for scope '{scope}': {usage} of {quota} bytes used, node needs {node_size}")]
+    ScopeQuotaExceeded {
+        scope: String,
+        usage: u64,
+        quota: u64,
+        node_size: u64,
+    },
+
+    #[error("{operation} access to scope '{scope}' denied for {caller}")]
+    AccessDenied {
+        /// Calling DID, or `anonymous` for a handle without one
+        caller: String,
+        scope: String,
+        operation: crate::dag_store::AccessOperation,
+    },
+
     #[error("DAG operation failed due to unspecified reason: {0}")]
     Unspecified(String),
 }
@@ -352,8 +380,13 @@ pub enum JobFailureReason {
     #[error("Invalid input provided for the job")]
     InvalidInput, // Consider InvalidInput(String)
 
-    #[error("Job timed out")]
-    Timeout,
+    #[error("Job timed out after {elapsed_secs}s (limit {limit_secs}s)")]
+    Timeout {
+        /// Seconds the job ran before it was stopped
+        elapsed_secs: u64,
+        /// Time budget the job was given, in seconds (0 if unknown)
+        limit_secs: u64,
+    },
 
     #[error("Job was manually cancelled")]
     ManuallyCancelled,
@@ -376,3 +409,16 @@ pub enum JobFailureReason {
     #[error("An unknown error occurred: {0}")]
     Unknown(String), // Default / catch-all, now with a message
 }
+
+impl JobFailureReason {
+    /// Whether a job that failed for this reason should be retried by default.
+    ///
+    /// Timeouts and network errors are usually transient or caused by node
+    /// capacity, so another attempt (possibly on another node) may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            JobFailureReason::Timeout { .. } | JobFailureReason::NetworkError
+        )
+    }
+}
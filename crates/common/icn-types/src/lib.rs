@@ -1,3 +1,6 @@
+pub mod bounded_decode;
+pub mod clock;
+pub mod compilation;
 pub mod crypto;
 pub mod dag;
 pub mod dag_store;
@@ -14,8 +17,13 @@ pub mod runtime_receipt;
 pub mod trust;
 pub mod reports;
 
+pub use clock::{Clock, MockClock, SystemClock};
+pub use compilation::CompilationManifest;
 pub use error::{IcnError, CryptoError, DagError, MulticodecError, IdentityError, TrustError, MeshError, VcError, SignError, EconomicsError, JobFailureReason};
-pub use runtime_receipt::{RuntimeExecutionMetrics, RuntimeExecutionReceipt};
+pub use runtime_receipt::{
+    anchored_cids_merkle_root, AnchoredCidsError, AnchoredCidsRoot, ReceiptBuildError,
+    RuntimeExecutionMetrics, RuntimeExecutionReceipt, RuntimeExecutionReceiptBuilder,
+};
 pub use mesh::{JobStatus as MeshJobStatus, MeshJob, MeshJobParams, QoSProfile, WorkflowType};
 pub use org::{CommunityId, CooperativeId};
 pub use receipt_verification::{ExecutionReceiptPayload, VerifiableReceipt};
@@ -23,12 +31,12 @@ pub use receipt_verification::{ExecutionReceiptPayload, VerifiableReceipt};
 // Corrected jobs re-export to only include types actually defined in icn_types::jobs
 pub use jobs::{policy::ExecutionPolicy, TokenAmount};
 
-pub use mana::{ManaState, ScopedMana};
+pub use mana::{ManaError, ManaState, ScopedMana};
 pub use reputation::{
-    compute_score as compute_reputation_score, ReputationProfile, ReputationRecord,
-    ReputationUpdateEvent,
+    compute_score as compute_reputation_score, rebuild_profile as rebuild_reputation_profile,
+    ReputationProfile, ReputationRecord, ReputationScoreConfig, ReputationUpdateEvent,
 };
-pub use resource::ResourceType;
+pub use resource::{ParseResourceTypeError, ResourceType};
 
 // Re-export did and cid types from icn_identity and cid crates for convenience
 pub use icn_identity::{Did, DidError, CredentialError, QuorumError, TrustBundleError, /* TrustAnchor, */ TrustBundle};
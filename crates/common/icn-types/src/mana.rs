@@ -1,6 +1,13 @@
 use icn_identity::Did;
 use serde::{Deserialize, Serialize};
 
+/// Errors arising from mana balance changes.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ManaError {
+    #[error("Insufficient mana: requested {requested}, available {available}")]
+    InsufficientMana { requested: u64, available: u64 },
+}
+
 /// Represents the state of mana for an entity.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ManaState {
@@ -27,6 +34,50 @@ impl Default for ManaState {
     }
 }
 
+impl ManaState {
+    /// Apply a signed change to `current_mana`, keeping it within `[0, max_mana]`.
+    ///
+    /// Saturation policy:
+    /// - Credits (positive deltas) saturate at `max_mana`; any excess is discarded.
+    /// - Debits (negative deltas) never saturate. If the balance cannot cover the
+    ///   debit, the state is left untouched and `ManaError::InsufficientMana` is returned.
+    ///
+    /// A balance found above `max_mana` (e.g. after the cap was lowered) is clamped
+    /// back into range by any successful delta, including zero.
+    pub fn apply_delta(&mut self, delta: i64) -> Result<(), ManaError> {
+        let magnitude = delta.unsigned_abs();
+        let next = if delta >= 0 {
+            self.current_mana.saturating_add(magnitude)
+        } else {
+            self.current_mana
+                .checked_sub(magnitude)
+                .ok_or(ManaError::InsufficientMana {
+                    requested: magnitude,
+                    available: self.current_mana,
+                })?
+        };
+        self.current_mana = next.min(self.max_mana);
+        Ok(())
+    }
+
+    /// Credit `amount` mana, saturating at `max_mana`.
+    pub fn credit(&mut self, amount: u64) {
+        // Credits cannot fail; amounts beyond i64::MAX saturate like any other excess.
+        let _ = self.apply_delta(i64::try_from(amount).unwrap_or(i64::MAX));
+    }
+
+    /// Debit `amount` mana, failing without change if the balance is insufficient.
+    pub fn debit(&mut self, amount: u64) -> Result<(), ManaError> {
+        let delta = i64::try_from(amount)
+            .map(|a| -a)
+            .map_err(|_| ManaError::InsufficientMana {
+                requested: amount,
+                available: self.current_mana,
+            })?;
+        self.apply_delta(delta)
+    }
+}
+
 /// Associates ManaState with a specific executor and optionally a cooperative.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ScopedMana {
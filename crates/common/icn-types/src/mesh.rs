@@ -1,11 +1,14 @@
 use crate::jobs::policy::ExecutionPolicy;
 use crate::org::{CommunityId, CooperativeId}; // Assuming these are in icn_types::org
 use crate::resource::ResourceType;
-use icn_identity::Did; // Correct source for Did
+use cid::multihash::Multihash;
+use cid::Cid;
+use icn_identity::{Did, KeyPair, TaggedSignature, TaggedSignatureError}; // Correct source for Did
 use serde::{Deserialize, Serialize}; // New import
-                                     // use crate::identity::Did; // Removed erroneous/duplicate import
-                                     // use crate::runtime_receipt::RuntimeExecutionReceipt; // Removed, as it does not appear to be used in this file
-                                     // use std::collections::HashMap; // Removed unused import
+use sha2::{Digest, Sha256};
+// use crate::identity::Did; // Removed erroneous/duplicate import
+// use crate::runtime_receipt::RuntimeExecutionReceipt; // Removed, as it does not appear to be used in this file
+// use std::collections::HashMap; // Removed unused import
 
 // Potential unused imports to be checked by compiler, remove if confirmed unused by later build.
 // Based on previous compiler output, these were unused:
@@ -109,6 +112,16 @@ pub struct MeshJobParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     // Add skip_serializing_if for consistency
     pub execution_policy: Option<ExecutionPolicy>, // ✅ New field
+
+    /// Run on the deterministic CoVm so the result replays bit-identically,
+    /// e.g. for consensus-relevant governance. Defaults to `false`, which uses
+    /// the faster default engine.
+    #[serde(default)]
+    pub deterministic: bool,
+
+    /// DIDs the job's output is encrypted to. Empty stores the output in the clear.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub output_recipients: Vec<Did>,
 }
 
 impl Default for MeshJobParams {
@@ -127,6 +140,8 @@ impl Default for MeshJobParams {
             is_interactive: false,
             expected_output_schema_cid: None,
             execution_policy: None, // Add to default
+            deterministic: false,
+            output_recipients: Vec::new(),
         }
     }
 }
@@ -152,6 +167,87 @@ pub struct MeshJob {
     pub originator_org_scope: Option<OrgScopeIdentifier>,
     /// Timestamp of when the job was submitted to the ICN, as a Unix timestamp (seconds since epoch).
     pub submission_timestamp: u64,
+    /// Job whose execution submitted this one, if it was spawned from within another job.
+    #[serde(default)]
+    pub parent_job_id: Option<String>,
+    /// CID of the execution receipt that led to this job's submission, if known.
+    #[serde(default)]
+    pub origin_receipt_cid: Option<String>,
+    /// Ceiling on the mana spent by this job's whole lineage: every attempt of the job,
+    /// its retries, and every job it (transitively) spawns. Only read on the root job.
+    #[serde(default)]
+    pub max_total_mana: Option<u64>,
+    /// Originator's signature over [`MeshJob::signing_payload`], binding the
+    /// parameters to the DID that submitted them.
+    #[serde(default)]
+    pub originator_signature: Option<TaggedSignature>,
+}
+
+impl MeshJob {
+    /// ID of a job submitted directly by `originator_did` at `submission_timestamp`:
+    /// a CIDv1 (raw, sha2-256) over the originator, timestamp and parameters.
+    ///
+    /// The timestamp doubles as a nonce, so resubmitting the same parameters
+    /// yields a new job rather than a replay of the old one.
+    pub fn submission_id(
+        originator_did: &Did,
+        submission_timestamp: u64,
+        params: &MeshJobParams,
+    ) -> Result<String, bincode::Error> {
+        let bytes = bincode::serialize(&(originator_did.as_str(), submission_timestamp, params))?;
+        let digest = Sha256::digest(&bytes);
+        let hash = Multihash::wrap(SHA2_256, &digest).expect("a 32-byte digest fits a multihash");
+        Ok(Cid::new_v1(RAW_CODEC, hash).to_string())
+    }
+
+    /// Canonical bytes the originator signs: everything that decides what runs,
+    /// on whose behalf and against which budget. `origin_receipt_cid` is left
+    /// out, as it is only known once the parent's receipt exists.
+    pub fn signing_payload(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(&(
+            &self.job_id,
+            self.originator_did.as_str(),
+            &self.originator_org_scope,
+            self.submission_timestamp,
+            &self.parent_job_id,
+            self.max_total_mana,
+            &self.params,
+        ))
+    }
+
+    /// Sign the job as its originator, replacing any previous signature.
+    pub fn sign(&mut self, originator: &KeyPair) -> Result<(), bincode::Error> {
+        let payload = self.signing_payload()?;
+        self.originator_signature = Some(originator.sign(&payload).into());
+        Ok(())
+    }
+
+    /// Check the originator's signature over [`MeshJob::signing_payload`].
+    pub fn verify_signature(&self) -> Result<(), JobSignatureError> {
+        let signature = self
+            .originator_signature
+            .as_ref()
+            .filter(|sig| !sig.is_empty())
+            .ok_or(JobSignatureError::Unsigned)?;
+        let payload = self.signing_payload()?;
+        Ok(signature.verify(&self.originator_did, &payload)?)
+    }
+}
+
+/// Multicodec for raw bytes
+const RAW_CODEC: u64 = 0x55;
+/// Multihash code for SHA2-256
+const SHA2_256: u64 = 0x12;
+
+/// Error returned by [`MeshJob::verify_signature`]
+#[derive(Debug, thiserror::Error)]
+pub enum JobSignatureError {
+    #[error("job carries no originator signature")]
+    Unsigned,
+    #[error("failed to encode job for signature check: {0}")]
+    Encoding(#[from] bincode::Error),
+    #[error(transparent)]
+    Invalid(#[from] TaggedSignatureError),
 }
 
 /// Status of a Mesh Job execution
@@ -1,7 +1,6 @@
 use anyhow::Result;
 use bincode;
-use ed25519_dalek::{Signature, VerifyingKey};
-use icn_identity::Did;
+use icn_identity::{Did, TaggedSignature, TaggedSignatureError};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr; // Use the crate directly
 
@@ -27,17 +26,19 @@ pub trait VerifiableReceipt {
     /// Get the specific data payload that was signed to produce the signature.
     fn get_payload_for_signing(&self) -> Result<ExecutionReceiptPayload>;
 
-    /// Get the raw signature bytes associated with this receipt.
-    fn get_signature_bytes(&self) -> Option<&[u8]>;
+    /// Get the algorithm-tagged signature associated with this receipt.
+    fn get_signature(&self) -> Option<&TaggedSignature>;
 
     /// Get the DID string of the entity that allegedly signed this receipt.
     fn get_issuer_did_str(&self) -> &str;
 
     /// Verify the signature against the payload using the issuer's public key.
+    /// Verification dispatches on the signature's algorithm tag.
     /// This provides a default implementation.
     fn verify_signature(&self) -> Result<()> {
-        let sig_bytes = self
-            .get_signature_bytes()
+        let signature = self
+            .get_signature()
+            .filter(|sig| !sig.is_empty())
             .ok_or_else(|| anyhow::anyhow!("Receipt signature is missing"))?;
 
         let issuer_did_str = self.get_issuer_did_str();
@@ -50,16 +51,6 @@ pub trait VerifiableReceipt {
             anyhow::anyhow!("Invalid issuer DID format '{}': {}", issuer_did_str, e)
         })?;
 
-        // Get the verification key from the DID (requires Did::verifying_key() method)
-        // TODO: Ensure icn_identity::Did implements verifying_key() -> Result<VerifyingKey>
-        let verifying_key: VerifyingKey = issuer_did.verifying_key().map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to get verifying key for DID '{}': {}",
-                issuer_did_str,
-                e
-            )
-        })?;
-
         // Get and serialize the payload that should have been signed
         let payload = self.get_payload_for_signing()?;
         let serialized_payload = bincode::serialize(&payload).map_err(|e| {
@@ -69,19 +60,23 @@ pub trait VerifiableReceipt {
             )
         })?;
 
-        // Parse the signature from bytes
-        let signature = Signature::try_from(sig_bytes)
-            .map_err(|e| anyhow::anyhow!("Invalid signature byte format: {}", e))?;
-
-        // Perform cryptographic verification
-        verifying_key
-            .verify_strict(&serialized_payload, &signature)
-            .map_err(|e| {
-                anyhow::anyhow!(
+        // Perform cryptographic verification for the tagged algorithm
+        signature
+            .verify(&issuer_did, &serialized_payload)
+            .map_err(|e| match e {
+                TaggedSignatureError::InvalidFormat { .. } => {
+                    anyhow::anyhow!("Invalid signature byte format: {}", e)
+                }
+                TaggedSignatureError::UnsupportedSigner { .. } => anyhow::anyhow!(
+                    "Failed to get verifying key for DID '{}': {:?}",
+                    issuer_did_str,
+                    e
+                ),
+                TaggedSignatureError::VerificationFailed { .. } => anyhow::anyhow!(
                     "Signature verification failed for issuer '{}': {}",
                     issuer_did_str,
                     e
-                )
+                ),
             })?;
 
         Ok(())
@@ -99,7 +94,7 @@ mod tests {
         id: String,
         issuer_did_str: String,
         timestamp: u64,
-        signature_bytes: Option<Vec<u8>>,
+        signature: Option<TaggedSignature>,
         // Other fields that might be part of its actual payload
         proposal_id_val: Option<String>,
         wasm_cid_val: Option<String>,
@@ -118,8 +113,8 @@ mod tests {
             })
         }
 
-        fn get_signature_bytes(&self) -> Option<&[u8]> {
-            self.signature_bytes.as_deref()
+        fn get_signature(&self) -> Option<&TaggedSignature> {
+            self.signature.as_ref()
         }
 
         fn get_issuer_did_str(&self) -> &str {
@@ -133,7 +128,7 @@ mod tests {
             id: "test-receipt-123".to_string(),
             issuer_did_str: keypair.did.to_string(),
             timestamp: 1678886400,
-            signature_bytes: None,
+            signature: None,
             proposal_id_val: Some("prop-abc".to_string()),
             wasm_cid_val: Some("wasm-xyz".to_string()),
             ccl_cid_val: Some("ccl-123".to_string()),
@@ -141,7 +136,7 @@ mod tests {
         let payload = receipt.get_payload_for_signing().unwrap();
         let bytes_to_sign = bincode::serialize(&payload).unwrap();
         let signature = keypair.sign(&bytes_to_sign);
-        receipt.signature_bytes = Some(signature.to_bytes().to_vec());
+        receipt.signature = Some(signature.into());
         receipt
     }
 
@@ -156,8 +151,8 @@ mod tests {
     fn verify_tampered_signature_fails() {
         let keypair = KeyPair::generate();
         let mut receipt = create_valid_signed_mock_receipt(&keypair);
-        if let Some(sig) = receipt.signature_bytes.as_mut() {
-            sig[0] ^= 0xFF; // Flip some bits in the signature
+        if let Some(sig) = receipt.signature.as_mut() {
+            sig.bytes[0] ^= 0xFF; // Flip some bits in the signature
         }
         let result = receipt.verify_signature();
         assert!(result.is_err());
@@ -171,7 +166,7 @@ mod tests {
     fn verify_missing_signature_fails() {
         let keypair = KeyPair::generate();
         let mut receipt = create_valid_signed_mock_receipt(&keypair);
-        receipt.signature_bytes = None;
+        receipt.signature = None;
         let result = receipt.verify_signature();
         assert!(result.is_err());
         assert!(result
@@ -241,7 +236,7 @@ mod tests {
     fn verify_malformed_signature_bytes_fails() {
         let keypair = KeyPair::generate();
         let mut receipt = create_valid_signed_mock_receipt(&keypair);
-        receipt.signature_bytes = Some(vec![0, 1, 2, 3]); // Too short for an Ed25519 signature
+        receipt.signature = Some(TaggedSignature::from_legacy_bytes(vec![0, 1, 2, 3])); // Too short for an Ed25519 signature
         let result = receipt.verify_signature();
         assert!(result.is_err());
         assert!(result
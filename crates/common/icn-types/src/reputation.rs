@@ -90,11 +90,110 @@ pub struct ReputationRecord {
     pub timestamp: u64, // Unix epoch timestamp (seconds)
 }
 
+/// Outcome of one record in a batched submission
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BatchItemOutcome {
+    /// Position of the record in the submitted batch
+    pub index: usize,
+    /// Why the record was rejected, or `None` if it was stored
+    pub error: Option<String>,
+}
+
+/// Per-record results of a batched reputation submission, in batch order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BatchResult {
+    pub outcomes: Vec<BatchItemOutcome>,
+}
+
+impl BatchResult {
+    /// Record a result for the record at `index`
+    pub fn push(&mut self, index: usize, result: Result<(), String>) {
+        self.outcomes.push(BatchItemOutcome {
+            index,
+            error: result.err(),
+        });
+    }
+
+    /// Returns true if every record in the batch was stored
+    pub fn all_succeeded(&self) -> bool {
+        self.outcomes.iter().all(|o| o.error.is_none())
+    }
+
+    /// Batch positions of the records that were rejected, for retry
+    pub fn failed_indices(&self) -> Vec<usize> {
+        self.outcomes
+            .iter()
+            .filter(|o| o.error.is_some())
+            .map(|o| o.index)
+            .collect()
+    }
+}
+
+/// Weights used by [`compute_score_with_config`].
+///
+/// Keeping these in one place lets operators change the scoring model and
+/// rebuild existing profiles with [`rebuild_profile`] instead of carrying
+/// forward scores computed under the old weights.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReputationScoreConfig {
+    pub base_score: f64,
+    pub success_weight: f64,
+    pub timeliness_weight: f64,
+    pub accuracy_weight: f64,
+    pub stake_weight: f64,
+    pub penalty_weight: f64,
+    /// Upper bound of the score range; the lower bound is always 0.0
+    pub max_score: f64,
+}
+
+impl Default for ReputationScoreConfig {
+    fn default() -> Self {
+        Self {
+            base_score: 0.5,
+            success_weight: 2.0,
+            timeliness_weight: 1.0,
+            accuracy_weight: 1.5,
+            stake_weight: 0.3,
+            penalty_weight: 0.7,
+            max_score: 10.0,
+        }
+    }
+}
+
 impl ReputationProfile {
+    /// An empty profile with no history, as of `at`.
+    pub fn new(node_id: Did, at: DateTime<Utc>) -> Self {
+        let mut profile = Self {
+            node_id,
+            last_updated: at,
+            total_jobs: 0,
+            successful_jobs: 0,
+            failed_jobs: 0,
+            jobs_on_time: 0,
+            jobs_late: 0,
+            average_execution_ms: None,
+            average_bid_accuracy: None,
+            dishonesty_events: 0,
+            endorsements: Vec::new(),
+            current_stake: None,
+            computed_score: 0.0,
+            latest_anchor_cid: None,
+            mana_state: None,
+        };
+        profile.computed_score = compute_score(&profile);
+        profile
+    }
+
     /// Apply a single reputation event to this profile,
     /// updating its metrics. Does *not* recompute `computed_score`—
     /// call `.recompute_score()` afterward if you have one.
     pub fn apply_event(&mut self, event: &ReputationUpdateEvent) {
+        self.apply_event_at(event, chrono::Utc::now());
+    }
+
+    /// Like [`apply_event`](Self::apply_event), but stamps `last_updated`
+    /// with the time the event originally occurred. Used when replaying history.
+    pub fn apply_event_at(&mut self, event: &ReputationUpdateEvent, at: DateTime<Utc>) {
         use ReputationUpdateEvent::*; // Make enum variants directly accessible
 
         // Always bump total_jobs when a job event occurs
@@ -169,10 +268,41 @@ impl ReputationProfile {
         }
 
         // Update the timestamp
-        self.last_updated = chrono::Utc::now();
+        self.last_updated = at;
     }
 }
 
+/// Rebuild a profile from scratch by replaying its event history in order.
+///
+/// Metrics are re-derived from the events and the score is computed once,
+/// under `config`, from the result. A `ProfileScoreManuallyAdjusted` event
+/// only survives the rebuild if it is the last event in the history.
+pub fn rebuild_profile<'a, I>(
+    node_id: Did,
+    events: I,
+    config: &ReputationScoreConfig,
+) -> ReputationProfile
+where
+    I: IntoIterator<Item = (DateTime<Utc>, &'a ReputationUpdateEvent)>,
+{
+    let mut events = events.into_iter().peekable();
+    let start = events.peek().map(|(at, _)| *at).unwrap_or_else(Utc::now);
+    let mut profile = ReputationProfile::new(node_id, start);
+    let mut manual_score = None;
+
+    for (at, event) in events {
+        profile.apply_event_at(event, at);
+        manual_score = match event {
+            ReputationUpdateEvent::ProfileScoreManuallyAdjusted { new_score, .. } => Some(*new_score),
+            _ => None,
+        };
+    }
+
+    profile.computed_score =
+        manual_score.unwrap_or_else(|| compute_score_with_config(&profile, config));
+    profile
+}
+
 /// Utility to incrementally update a u32 average:
 fn average_u32(prev: Option<u32>, new_val: u32, count: u64) -> u32 {
     if count == 0 {
@@ -202,14 +332,11 @@ fn average_f32(prev: Option<f32>, new_val: f32, count: u64) -> f32 {
 }
 
 pub fn compute_score(profile: &ReputationProfile) -> f64 {
-    const BASE_SCORE: f64 = 0.5;
-
-    const SUCCESS_WEIGHT: f64 = 2.0;
-    const TIMELINESS_WEIGHT: f64 = 1.0;
-    const ACCURACY_WEIGHT: f64 = 1.5;
-    const STAKE_WEIGHT: f64 = 0.3;
-    const PENALTY_WEIGHT: f64 = 0.7;
+    compute_score_with_config(profile, &ReputationScoreConfig::default())
+}
 
+/// Score a profile's metrics under the given weights.
+pub fn compute_score_with_config(profile: &ReputationProfile, config: &ReputationScoreConfig) -> f64 {
     // Ensure total is at least 1.0 to avoid division by zero for rates if total_jobs is 0.
     let total = (profile.total_jobs as f64).max(1.0);
 
@@ -230,15 +357,15 @@ pub fn compute_score(profile: &ReputationProfile) -> f64 {
         .map(|s| (s as f64 + 1.0).ln()) // log(1 + s) is ln_1p, or (s+1.0).ln()
         .unwrap_or(0.0);
 
-    let raw_score = BASE_SCORE
-        + SUCCESS_WEIGHT * success_rate
-        + TIMELINESS_WEIGHT * on_time_rate
-        + ACCURACY_WEIGHT * (avg_accuracy as f64)
-        + STAKE_WEIGHT * stake_log
-        - PENALTY_WEIGHT * dishonesty_events_count;
+    let raw_score = config.base_score
+        + config.success_weight * success_rate
+        + config.timeliness_weight * on_time_rate
+        + config.accuracy_weight * (avg_accuracy as f64)
+        + config.stake_weight * stake_log
+        - config.penalty_weight * dishonesty_events_count;
 
-    // Clamp score to a defined range, e.g., 0.0 to 10.0
-    raw_score.clamp(0.0, 10.0)
+    // Clamp score to the configured range, e.g., 0.0 to 10.0
+    raw_score.clamp(0.0, config.max_score)
 }
 
 // TODO:
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use strum_macros::Display;
+use thiserror::Error;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
 #[repr(u32)]
@@ -8,15 +10,79 @@ pub enum ResourceType {
     Memory = 2,
     Io = 3,
     Token = 4,
+    Storage = 5,
+    Bandwidth = 6,
 }
 
+/// Resource type for a numeric code, as stored in ledger keys or passed by guests.
+///
+/// Codes 5 and 6 mean `Storage` and `Bandwidth` since host ABI 12; before that
+/// they fell through to `Token`, like every other unknown code still does.
 impl From<u32> for ResourceType {
     fn from(v: u32) -> Self {
         match v {
             1 => ResourceType::Cpu,
             2 => ResourceType::Memory,
             3 => ResourceType::Io,
+            5 => ResourceType::Storage,
+            6 => ResourceType::Bandwidth,
             _ => ResourceType::Token,
         }
     }
 }
+
+/// A resource name that does not map to any [`ResourceType`]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unrecognized resource type '{0}'")]
+pub struct ParseResourceTypeError(pub String);
+
+impl FromStr for ResourceType {
+    type Err = ParseResourceTypeError;
+
+    /// Parse a resource name as reported in usage records, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cpu" | "compute" => Ok(ResourceType::Cpu),
+            "memory" | "mem" => Ok(ResourceType::Memory),
+            "io" => Ok(ResourceType::Io),
+            "token" => Ok(ResourceType::Token),
+            "storage" => Ok(ResourceType::Storage),
+            "bandwidth" => Ok(ResourceType::Bandwidth),
+            _ => Err(ParseResourceTypeError(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_alias() {
+        for (name, expected) in [
+            ("cpu", ResourceType::Cpu),
+            ("compute", ResourceType::Cpu),
+            ("memory", ResourceType::Memory),
+            ("mem", ResourceType::Memory),
+            ("io", ResourceType::Io),
+            ("token", ResourceType::Token),
+            ("storage", ResourceType::Storage),
+            ("bandwidth", ResourceType::Bandwidth),
+            ("CPU", ResourceType::Cpu),
+            (" Memory ", ResourceType::Memory),
+        ] {
+            assert_eq!(name.parse::<ResourceType>(), Ok(expected), "{name}");
+        }
+    }
+
+    #[test]
+    fn unknown_names_are_rejected_and_codes_round_trip() {
+        assert_eq!(
+            "gpu".parse::<ResourceType>(),
+            Err(ParseResourceTypeError("gpu".to_string()))
+        );
+        for resource in [ResourceType::Storage, ResourceType::Bandwidth] {
+            assert_eq!(ResourceType::from(resource as u32), resource);
+        }
+    }
+}
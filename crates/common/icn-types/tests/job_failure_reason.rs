@@ -0,0 +1,26 @@
+use icn_types::JobFailureReason;
+
+#[test]
+fn timeout_carries_elapsed_and_limit() {
+    let reason = JobFailureReason::Timeout {
+        elapsed_secs: 42,
+        limit_secs: 30,
+    };
+    assert_eq!(reason.to_string(), "Job timed out after 42s (limit 30s)");
+
+    let json = serde_json::to_string(&reason).unwrap();
+    let decoded: JobFailureReason = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, reason);
+}
+
+#[test]
+fn timeouts_are_retryable_by_default() {
+    assert!(JobFailureReason::Timeout {
+        elapsed_secs: 1,
+        limit_secs: 1
+    }
+    .is_retryable());
+    assert!(JobFailureReason::NetworkError.is_retryable());
+    assert!(!JobFailureReason::PermissionDenied.is_retryable());
+    assert!(!JobFailureReason::ExecutionError("trap".into()).is_retryable());
+}
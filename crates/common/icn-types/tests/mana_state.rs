@@ -0,0 +1,44 @@
+use icn_types::mana::{ManaError, ManaState};
+
+fn state(current_mana: u64, max_mana: u64) -> ManaState {
+    ManaState {
+        current_mana,
+        max_mana,
+        regen_rate_per_epoch: 0.0,
+        last_updated_epoch: 0,
+    }
+}
+
+#[test]
+fn credits_saturate_at_max() {
+    let mut s = state(90, 100);
+    s.apply_delta(25).unwrap();
+    assert_eq!(s.current_mana, 100);
+
+    s.credit(u64::MAX);
+    assert_eq!(s.current_mana, 100);
+}
+
+#[test]
+fn debits_fail_without_change_when_insufficient() {
+    let mut s = state(10, 100);
+    assert_eq!(
+        s.apply_delta(-11),
+        Err(ManaError::InsufficientMana { requested: 11, available: 10 })
+    );
+    assert_eq!(s.current_mana, 10);
+
+    s.debit(10).unwrap();
+    assert_eq!(s.current_mana, 0);
+    assert!(s.debit(u64::MAX).is_err());
+}
+
+#[test]
+fn out_of_range_balance_is_clamped() {
+    let mut s = state(150, 100);
+    s.apply_delta(0).unwrap();
+    assert_eq!(s.current_mana, 100);
+
+    s.apply_delta(i64::MIN).unwrap_err();
+    assert_eq!(s.current_mana, 100);
+}
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use icn_identity::KeyPair;
+use icn_types::Cid;
+use icn_types::reputation::{
+    compute_score, compute_score_with_config, rebuild_profile, ReputationProfile,
+    ReputationScoreConfig, ReputationUpdateEvent,
+};
+
+fn completed(on_time: bool) -> ReputationUpdateEvent {
+    ReputationUpdateEvent::JobCompletedSuccessfully {
+        job_id: Cid::default(),
+        execution_duration_ms: 1_000,
+        bid_accuracy: 0.9,
+        on_time,
+        anchor_cid: None,
+        mana_cost: Some(10),
+        verification_passed: true,
+    }
+}
+
+fn failed() -> ReputationUpdateEvent {
+    ReputationUpdateEvent::JobFailed {
+        job_id: Cid::default(),
+        reason: "timeout".to_string(),
+        anchor_cid: None,
+        verification_failed: false,
+    }
+}
+
+fn history() -> Vec<(DateTime<Utc>, ReputationUpdateEvent)> {
+    let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+    vec![completed(true), failed(), completed(false)]
+        .into_iter()
+        .enumerate()
+        .map(|(i, event)| (start + Duration::minutes(i as i64), event))
+        .collect()
+}
+
+#[test]
+fn rebuild_matches_incremental_application() {
+    let did = KeyPair::generate().did;
+    let events = history();
+
+    let mut incremental = ReputationProfile::new(did.clone(), events[0].0);
+    for (at, event) in &events {
+        incremental.apply_event_at(event, *at);
+        incremental.computed_score = compute_score(&incremental);
+    }
+
+    let rebuilt = rebuild_profile(
+        did,
+        events.iter().map(|(at, e)| (*at, e)),
+        &ReputationScoreConfig::default(),
+    );
+
+    assert_eq!(rebuilt, incremental);
+    assert_eq!(rebuilt.total_jobs, 3);
+    assert_eq!(rebuilt.last_updated, events[2].0);
+}
+
+#[test]
+fn rebuild_applies_new_scoring_config() {
+    let did = KeyPair::generate().did;
+    let events = history();
+    let harsher = ReputationScoreConfig {
+        success_weight: 0.5,
+        max_score: 5.0,
+        ..ReputationScoreConfig::default()
+    };
+
+    let rebuilt = rebuild_profile(did, events.iter().map(|(at, e)| (*at, e)), &harsher);
+
+    assert_eq!(rebuilt.computed_score, compute_score_with_config(&rebuilt, &harsher));
+    assert!(rebuilt.computed_score < compute_score(&rebuilt));
+}
@@ -0,0 +1,157 @@
+//! Affinity and anti-affinity constraints used when selecting a bid for a job.
+//!
+//! Required attributes are hard filters: a bid from a node that does not carry
+//! them is never chosen. Preferred attributes only raise a bid's score.
+//! Anti-affinity keeps a job off nodes already hosting the listed jobs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{Bid, JobManifest, MeshError, NodeCapability};
+
+/// Score added to a bid for every preferred attribute its node satisfies
+pub const PREFERRED_AFFINITY_BOOST: f64 = 0.25;
+
+/// A node attribute a job can require or prefer
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum NodeAttribute {
+    /// Node reports this location
+    Location(String),
+    /// Node runs on this CPU architecture
+    CpuArchitecture(String),
+    /// Node advertises this feature (e.g. "gpu")
+    Feature(String),
+    /// Node supports this job type
+    JobType(String),
+    /// Specific node ID
+    NodeId(String),
+}
+
+impl NodeAttribute {
+    /// Whether a node with the given capabilities carries this attribute
+    pub fn matches(&self, capability: &NodeCapability) -> bool {
+        match self {
+            Self::Location(location) => capability.location.as_deref() == Some(location.as_str()),
+            Self::CpuArchitecture(arch) => capability.cpu_architecture == *arch,
+            Self::Feature(feature) => capability.features.iter().any(|f| f == feature),
+            Self::JobType(job_type) => capability.supported_job_types.iter().any(|t| t == job_type),
+            Self::NodeId(node_id) => capability.node_id == *node_id,
+        }
+    }
+}
+
+impl fmt::Display for NodeAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Location(v) => write!(f, "location={}", v),
+            Self::CpuArchitecture(v) => write!(f, "cpu_architecture={}", v),
+            Self::Feature(v) => write!(f, "feature={}", v),
+            Self::JobType(v) => write!(f, "job_type={}", v),
+            Self::NodeId(v) => write!(f, "node_id={}", v),
+        }
+    }
+}
+
+/// Placement constraints attached to a job manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AffinityConstraints {
+    /// Attributes the executing node must have
+    #[serde(default)]
+    pub required: Vec<NodeAttribute>,
+
+    /// Attributes that make a node more attractive but are not mandatory
+    #[serde(default)]
+    pub preferred: Vec<NodeAttribute>,
+
+    /// Jobs that must not share a node with this one
+    #[serde(default)]
+    pub anti_affinity_job_ids: Vec<String>,
+}
+
+impl AffinityConstraints {
+    /// Whether no constraint is set
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.preferred.is_empty() && self.anti_affinity_job_ids.is_empty()
+    }
+
+    /// Required attributes the node lacks
+    pub fn missing_required<'a>(&'a self, capability: &NodeCapability) -> Vec<&'a NodeAttribute> {
+        self.required
+            .iter()
+            .filter(|attr| !attr.matches(capability))
+            .collect()
+    }
+
+    /// Number of preferred attributes the node satisfies
+    pub fn preferred_matches(&self, capability: &NodeCapability) -> usize {
+        self.preferred
+            .iter()
+            .filter(|attr| attr.matches(capability))
+            .count()
+    }
+
+    /// Score added to a bid from this node: [`PREFERRED_AFFINITY_BOOST`] per
+    /// preferred attribute it satisfies
+    pub fn preference_boost(&self, capability: &NodeCapability) -> f64 {
+        PREFERRED_AFFINITY_BOOST * self.preferred_matches(capability) as f64
+    }
+
+    /// Anti-affinity job already placed on `node_id`, if any
+    pub fn conflicting_job<'a>(
+        &'a self,
+        node_id: &str,
+        placements: &HashMap<String, String>,
+    ) -> Option<&'a str> {
+        self.anti_affinity_job_ids
+            .iter()
+            .find(|job_id| placements.get(job_id.as_str()).map(String::as_str) == Some(node_id))
+            .map(String::as_str)
+    }
+}
+
+/// Bids for `manifest` that pass its placement constraints.
+///
+/// `placements` maps job IDs to the node currently assigned to or running
+/// them. Bids failing a required attribute or anti-affinity rule are
+/// discarded; if none remain the error names why each was discarded.
+pub fn eligible_bids<'a>(
+    manifest: &JobManifest,
+    bids: &'a [Bid],
+    placements: &HashMap<String, String>,
+) -> Result<Vec<&'a Bid>, MeshError> {
+    let constraints = &manifest.affinity;
+
+    let mut rejections = Vec::new();
+    let mut eligible = Vec::new();
+
+    for bid in bids {
+        let missing = constraints.missing_required(&bid.node_capacity);
+        if !missing.is_empty() {
+            let missing: Vec<String> = missing.iter().map(|a| a.to_string()).collect();
+            rejections.push(format!("{} lacks {}", bid.node_id, missing.join(", ")));
+            continue;
+        }
+        if let Some(job_id) = constraints.conflicting_job(&bid.node_id, placements) {
+            rejections.push(format!("{} already hosts {}", bid.node_id, job_id));
+            continue;
+        }
+        eligible.push(bid);
+    }
+
+    if !eligible.is_empty() {
+        Ok(eligible)
+    } else if bids.is_empty() {
+        Err(MeshError::UnsatisfiableAffinity(format!(
+            "no bids received for job {}",
+            manifest.id
+        )))
+    } else {
+        Err(MeshError::UnsatisfiableAffinity(format!(
+            "no bid for job {} satisfies its placement constraints: {}",
+            manifest.id,
+            rejections.join("; ")
+        )))
+    }
+}
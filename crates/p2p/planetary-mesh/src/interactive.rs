@@ -0,0 +1,141 @@
+//! Bounded per-job buffers for interactive input and output.
+//!
+//! Chunks may arrive out of order. Draining a buffer releases them by
+//! `sequence_num`, holding back everything after a gap until the missing
+//! chunk arrives. A full buffer refuses new chunks instead of growing, so a
+//! slow consumer pushes back on the sender, and chunks too far ahead of the
+//! next expected one are refused so held-back chunks stay bounded as well.
+
+use icn_mesh_protocol::{JobInteractiveInputV1, JobInteractiveOutputV1};
+use std::collections::BTreeMap;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+/// Chunks each direction of a job's interactive buffer holds before refusing more
+pub const DEFAULT_INTERACTIVE_BUFFER_CAPACITY: usize = 64;
+
+/// A chunk carrying its position in the stream
+pub trait Sequenced {
+    fn sequence_num(&self) -> u64;
+
+    /// Whether this chunk closes the stream
+    fn is_final(&self) -> bool;
+}
+
+impl Sequenced for JobInteractiveInputV1 {
+    fn sequence_num(&self) -> u64 {
+        self.sequence_num
+    }
+
+    fn is_final(&self) -> bool {
+        self.is_final_chunk
+    }
+}
+
+impl Sequenced for JobInteractiveOutputV1 {
+    fn sequence_num(&self) -> u64 {
+        self.sequence_num
+    }
+
+    fn is_final(&self) -> bool {
+        self.is_final_chunk
+    }
+}
+
+/// A bounded channel that is drained in sequence order
+#[derive(Debug)]
+pub struct SequencedChannel<T> {
+    tx: mpsc::Sender<T>,
+    rx: mpsc::Receiver<T>,
+    /// Received chunks waiting for an earlier one
+    held: BTreeMap<u64, T>,
+    next_sequence_num: u64,
+    capacity: u64,
+    /// Set once the final chunk has been released
+    finished: bool,
+}
+
+impl<T: Sequenced> SequencedChannel<T> {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (tx, rx) = mpsc::channel(capacity);
+        Self {
+            tx,
+            rx,
+            held: BTreeMap::new(),
+            next_sequence_num: 0,
+            capacity: capacity as u64,
+            finished: false,
+        }
+    }
+
+    /// Queue `chunk`, handing it back if the channel is full, the stream has
+    /// finished or the chunk is `capacity` or more ahead of the next one
+    /// expected. Only chunks inside that window can be held back, so at most
+    /// `capacity` chunks wait for a gap to fill.
+    pub fn push(&self, chunk: T) -> Result<(), T> {
+        if self.finished || !self.in_window(chunk.sequence_num()) {
+            return Err(chunk);
+        }
+        self.tx.try_send(chunk).map_err(|e| match e {
+            TrySendError::Full(chunk) | TrySendError::Closed(chunk) => chunk,
+        })
+    }
+
+    /// Take every chunk that is next in sequence. Chunks already released
+    /// before are dropped as duplicates.
+    pub fn drain(&mut self) -> Vec<T> {
+        while let Ok(chunk) = self.rx.try_recv() {
+            if !self.finished
+                && chunk.sequence_num() >= self.next_sequence_num
+                && self.in_window(chunk.sequence_num())
+            {
+                self.held.insert(chunk.sequence_num(), chunk);
+            }
+        }
+
+        let mut ready = Vec::new();
+        while let Some(chunk) = self.held.remove(&self.next_sequence_num) {
+            self.next_sequence_num += 1;
+            let is_final = chunk.is_final();
+            ready.push(chunk);
+            if is_final {
+                // Nothing after the final chunk belongs to the stream
+                self.finished = true;
+                self.held.clear();
+                break;
+            }
+        }
+        ready
+    }
+
+    /// Whether the final chunk has been drained
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn in_window(&self, sequence_num: u64) -> bool {
+        sequence_num < self.next_sequence_num.saturating_add(self.capacity)
+    }
+}
+
+/// Interactive input and output buffers of one job
+#[derive(Debug)]
+pub struct InteractiveBuffers {
+    pub input: SequencedChannel<JobInteractiveInputV1>,
+    pub output: SequencedChannel<JobInteractiveOutputV1>,
+}
+
+impl InteractiveBuffers {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            input: SequencedChannel::new(capacity),
+            output: SequencedChannel::new(capacity),
+        }
+    }
+
+    /// Whether both directions have drained their final chunk
+    pub fn is_finished(&self) -> bool {
+        self.input.is_finished() && self.output.is_finished()
+    }
+}
@@ -2,8 +2,8 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
-use icn_core_vm::{CoVm, ExecutionMetrics, HostContext, ResourceLimits};
+use chrono::{DateTime, TimeZone, Utc};
+use icn_core_vm::{CoVm, ExecutionMetrics, HostContext, PartialExecutionError, ResourceLimits};
 use icn_economics::ScopedResourceToken;
 // use icn_identity_core::did::Did;
 type Did = String; // DIDs are strings in the format did:key:...
@@ -11,6 +11,7 @@ type Did = String; // DIDs are strings in the format did:key:...
 
 // Import standardized ExecutionReceipt and JobStatus
 use icn_mesh_receipts::{ExecutionReceipt, ReceiptError};
+use icn_mesh_protocol::{JobInteractiveInputV1, JobInteractiveOutputV1};
 use icn_types::mesh::JobStatus as StandardJobStatus; // Alias to avoid conflict with local JobStatus
 
 use serde::{Deserialize, Serialize};
@@ -30,8 +31,20 @@ pub use behaviour::{MeshBehaviour, MeshBehaviourEvent, CAPABILITY_TOPIC};
 pub mod node;
 pub use node::MeshNode;
 
+pub mod affinity;
+pub use affinity::{AffinityConstraints, NodeAttribute};
+
+pub mod interactive;
+pub use interactive::{InteractiveBuffers, DEFAULT_INTERACTIVE_BUFFER_CAPACITY};
+
+pub mod output;
+pub use output::{decrypt_result, prepare_job_output, result_hash, StoredOutput};
+
 pub mod reputation_integration;
-pub use reputation_integration::{BidEvaluatorConfig, DefaultReputationClient, ReputationClient};
+pub use reputation_integration::{
+    BidEvaluatorConfig, DefaultReputationClient, MinReputation, ReputationClient,
+    ReputationFallback, ReputationFloor,
+};
 
 // Add the new metrics module
 pub mod metrics;
@@ -56,6 +69,30 @@ pub enum MeshError {
 
     #[error("Network error: {0}")]
     NetworkError(String),
+
+    #[error("Unsatisfiable affinity: {0}")]
+    UnsatisfiableAffinity(String),
+
+    #[error("Output encryption failed: {0}")]
+    OutputEncryption(String),
+
+    #[error("Output not found: {0}")]
+    OutputNotFound(String),
+
+    #[error("Requirements not met: {0}")]
+    RequirementsNotMet(String),
+
+    #[error("Invalid job status transition: {0}")]
+    InvalidTransition(String),
+
+    #[error("Interactive buffer full: {0}")]
+    BufferFull(String),
+
+    #[error("Invalid resource usage: {0}")]
+    InvalidResourceUsage(String),
+
+    #[error("No acceptable bid: {0}")]
+    NoAcceptableBid(String),
 }
 
 /// Job priority levels
@@ -135,6 +172,16 @@ pub enum JobStatus {
     },
 }
 
+impl JobStatus {
+    /// Whether the job has finished, successfully or not, and will not change again
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed { .. } | JobStatus::Failed { .. } | JobStatus::Cancelled
+        )
+    }
+}
+
 /// Compute resource requirements
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ComputeRequirements {
@@ -184,6 +231,10 @@ pub struct JobManifest {
     /// Output data location
     pub output_location: Option<String>,
 
+    /// DIDs the output is encrypted to; empty stores the output in the clear
+    #[serde(default)]
+    pub output_recipients: Vec<Did>,
+
     /// Compute requirements
     pub requirements: ComputeRequirements,
 
@@ -196,6 +247,10 @@ pub struct JobManifest {
     /// Trust requirements (e.g., required credentials)
     pub trust_requirements: Vec<String>,
 
+    /// Node affinity and anti-affinity constraints for scheduling
+    #[serde(default)]
+    pub affinity: AffinityConstraints,
+
     /// Current status
     pub status: JobStatus,
 }
@@ -323,6 +378,34 @@ pub struct JobExecutionReceipt {
     pub execution_logs: Vec<String>,
 }
 
+impl JobExecutionReceipt {
+    /// Fill `result_hash` from the bytes stored under `output_data_cid`.
+    ///
+    /// `fetch` resolves a CID from the node's content store. Output that can't
+    /// be fetched leaves `result_hash` as `None` with a warning, since the
+    /// receipt is still valid without it.
+    pub fn fill_result_hash<F>(&mut self, fetch: F)
+    where
+        F: FnOnce(&str) -> Result<Vec<u8>>,
+    {
+        let Some(cid) = &self.output_data_cid else {
+            return;
+        };
+        match fetch(cid) {
+            Ok(output) => self.result_hash = Some(result_hash(&output)),
+            Err(e) => {
+                tracing::warn!(
+                    "Could not fetch output {} of job {} to hash it: {}",
+                    cid,
+                    self.job_id,
+                    e
+                );
+                self.result_hash = None;
+            }
+        }
+    }
+}
+
 /// Network behavior for P2P communication
 pub struct NetworkBehavior {
     /// Libp2p event sender
@@ -350,6 +433,27 @@ pub enum NetworkEvent {
     NewReceipt(ExecutionReceipt),
 }
 
+/// A mesh node tracking the jobs, bids and receipts it has seen
+pub struct PlanetaryMeshNode {
+    node_did: Did,
+    node_id: String,
+    capabilities: NodeCapability,
+    jobs: Arc<Mutex<HashMap<String, JobManifest>>>,
+    bids: Arc<Mutex<HashMap<String, Vec<Bid>>>>,
+    receipts: Arc<Mutex<HashMap<String, ExecutionReceipt>>>,
+    /// This node's detailed record of each job it executed, by job ID
+    job_receipts: Arc<Mutex<HashMap<String, JobExecutionReceipt>>>,
+    /// Capabilities and reputation scores (0-100) of peers, by node ID
+    known_peers: Arc<Mutex<HashMap<String, (NodeCapability, u32)>>>,
+    /// Interactive input and output buffers, by job ID
+    interactive: Arc<Mutex<HashMap<String, InteractiveBuffers>>>,
+    /// Stored job output and logs, by the CID recorded in their receipt
+    content: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    vm: CoVm,
+    #[allow(dead_code)]
+    network: Option<NetworkBehavior>,
+}
+
 impl PlanetaryMeshNode {
     /// Create a new planetary mesh node
     pub fn new(node_did: Did, capabilities: NodeCapability) -> Result<Self> {
@@ -366,6 +470,10 @@ impl PlanetaryMeshNode {
             jobs: Arc::new(Mutex::new(HashMap::new())),
             bids: Arc::new(Mutex::new(HashMap::new())),
             receipts: Arc::new(Mutex::new(HashMap::new())),
+            job_receipts: Arc::new(Mutex::new(HashMap::new())),
+            known_peers: Arc::new(Mutex::new(HashMap::new())),
+            interactive: Arc::new(Mutex::new(HashMap::new())),
+            content: Arc::new(Mutex::new(HashMap::new())),
             vm,
             network: None,
         })
@@ -401,7 +509,53 @@ impl PlanetaryMeshNode {
         Ok(metrics)
     }
 
-    /// Create a job execution receipt
+    /// Execute `job_id`'s module and create its receipt.
+    ///
+    /// A module that traps or runs out of fuel still gets a failed receipt
+    /// carrying what it logged and used before it stopped.
+    pub async fn execute_job(&self, job_id: &str, wasm_bytes: &[u8]) -> Result<ExecutionReceipt> {
+        let execution_start_time = Utc::now().timestamp() as u64;
+        let failed = |error: String| StandardJobStatus::Failed {
+            error,
+            stage_index: None,
+            stage_id: None,
+        };
+        let (status, host_context) = match self
+            .vm
+            .execute_with_partial_results(wasm_bytes, HostContext::default())
+        {
+            Ok(host_context) => (StandardJobStatus::CompletedSuccess, host_context),
+            Err(PartialExecutionError::FuelExhausted { context }) => {
+                (failed("Fuel exhausted".to_string()), context)
+            }
+            Err(PartialExecutionError::Trapped { error, context }) => {
+                (failed(error.to_string()), context)
+            }
+            Err(PartialExecutionError::Failed(e)) => {
+                return Err(MeshError::ExecutionFailed(e.to_string()).into())
+            }
+        };
+
+        let metrics = host_context.metrics.lock().unwrap().clone();
+        let resource_usage = host_context.resource_usage.lock().unwrap().clone();
+        let logs = host_context.logs.lock().unwrap().clone();
+        self.create_execution_receipt(
+            job_id,
+            status,
+            &metrics,
+            resource_usage,
+            None,
+            logs,
+            execution_start_time,
+        )
+        .await
+    }
+
+    /// Create a job execution receipt.
+    ///
+    /// Alongside it the node records a [`JobExecutionReceipt`] whose
+    /// `result_hash` covers the output stored under `result_data_cid`; see
+    /// [`job_execution_receipt`](Self::job_execution_receipt).
     pub async fn create_execution_receipt(
         &self,
         job_id: &str,
@@ -409,33 +563,43 @@ impl PlanetaryMeshNode {
         metrics: &ExecutionMetrics,    // Pass by reference
         resource_usage_vec: Vec<(String, u64)>, // Changed from direct HashMap to allow conversion
         result_data_cid: Option<String>,
-        logs_cid: Option<String>, // Added for logs
+        logs: Vec<String>,
         execution_start_time_unix: u64, // Added start time
-                                  // signature will be generated internally if not provided, or taken as param if pre-signed
+                                        // signature will be generated internally if not provided, or taken as param if pre-signed
     ) -> Result<ExecutionReceipt> {
         let now_dt = Utc::now();
         let execution_end_time_unix = now_dt.timestamp() as u64;
 
-        // Convert resource_usage Vec<(String, u64)> to HashMap<ResourceType, u64>
-        // This requires ResourceType to be parsable from String or for the input to change.
-        // For now, assuming a helper or direct construction if ResourceType is simple.
-        // This part might need further refinement based on how ResourceType is handled.
+        // Every reported resource must map to a ResourceType, so no usage is dropped
         let mut resource_usage_map = HashMap::new();
-        for (rt_str, amount) in resource_usage_vec {
-            // Placeholder: This conversion needs to be robust.
-            // Assuming icn_economics::ResourceType can be created from rt_str
-            // For example, if ResourceType implements FromStr or similar.
-            // If not, this mapping logic needs to be defined.
-            // For simplicity, let's assume a direct mapping for Cpu, Memory, Io for now.
-            use icn_economics::ResourceType;
-            let key = match rt_str.to_lowercase().as_str() {
-                "cpu" | "compute" => ResourceType::Cpu,
-                "memory" | "mem" => ResourceType::Memory,
-                "io" => ResourceType::Io,
-                _ => continue, // Skip unknown resource types for now or handle error
-            };
-            resource_usage_map.insert(key, amount);
+        let mut unrecognized = Vec::new();
+        for (rt_str, amount) in resource_usage_vec.iter().cloned() {
+            match rt_str.parse::<icn_economics::ResourceType>() {
+                Ok(key) => {
+                    let total = resource_usage_map.entry(key).or_insert(0u64);
+                    *total = total.saturating_add(amount);
+                }
+                Err(_) => unrecognized.push(rt_str),
+            }
         }
+        if !unrecognized.is_empty() {
+            return Err(MeshError::InvalidResourceUsage(format!(
+                "unrecognized resource types for job {}: {}",
+                job_id,
+                unrecognized.join(", ")
+            ))
+            .into());
+        }
+
+        // Logs are stored like output, so the receipt references them by CID
+        let logs_cid = if logs.is_empty() {
+            None
+        } else {
+            let bytes = serde_json::to_vec(&logs)?;
+            let cid = output::content_cid(&bytes);
+            self.content.lock().unwrap().insert(cid.clone(), bytes);
+            Some(cid)
+        };
 
         // Placeholder for actual signature generation
         let signature_bytes = Vec::new(); // In a real scenario, sign the relevant fields
@@ -453,6 +617,7 @@ impl PlanetaryMeshNode {
             signature: signature_bytes, // Placeholder
             coop_id: None,              // TODO: Determine how to populate these if needed
             community_id: None,         // TODO: Determine how to populate these if needed
+            deterministic: false,
         };
 
         // Store the receipt locally
@@ -463,10 +628,556 @@ impl PlanetaryMeshNode {
             receipts_store.insert(job_id.to_string(), receipt.clone());
         }
 
+        let mut job_receipt = JobExecutionReceipt {
+            job_id: job_id.to_string(),
+            executor_node_id: self.node_id.clone(),
+            executor_node_did: self.node_did.clone(),
+            metrics: metrics.clone(),
+            output_data_cid: receipt.result_data_cid.clone(),
+            start_time: Utc
+                .timestamp_opt(execution_start_time_unix as i64, 0)
+                .single()
+                .unwrap_or(now_dt),
+            end_time: now_dt,
+            resource_usage: resource_usage_vec,
+            receipt_cid: receipt.cid()?.to_string(),
+            verified_by_federation: false,
+            verifier_did: None,
+            verified_at: None,
+            result_status: if matches!(receipt.status, StandardJobStatus::CompletedSuccess) {
+                0
+            } else {
+                1
+            },
+            result_hash: None,
+            result_metadata: None,
+            execution_logs: logs,
+        };
+        job_receipt.fill_result_hash(|cid| self.fetch_output(cid));
+        self.job_receipts
+            .lock()
+            .unwrap()
+            .insert(job_id.to_string(), job_receipt);
+
         // TODO: Broadcast receipt to the network (using receipt.cid()?)
 
         Ok(receipt)
     }
+
+    /// Store a job's result, sealed to the manifest's `output_recipients` if
+    /// any. Pass the returned CID to
+    /// [`create_execution_receipt`](Self::create_execution_receipt).
+    pub fn store_job_output(&self, job_id: &str, output: &[u8]) -> Result<StoredOutput> {
+        let stored = {
+            let jobs = self.jobs.lock().unwrap();
+            let manifest = jobs
+                .get(job_id)
+                .ok_or_else(|| MeshError::JobNotFound(job_id.to_string()))?;
+            prepare_job_output(manifest, output)?
+        };
+
+        self.content
+            .lock()
+            .unwrap()
+            .insert(stored.cid.clone(), stored.bytes.clone());
+        Ok(stored)
+    }
+
+    /// Resolve `receipt.logs_cid` into the log lines its job wrote, or an empty
+    /// list when the job logged nothing.
+    pub fn fetch_logs(&self, receipt: &ExecutionReceipt) -> Result<Vec<String>> {
+        let Some(logs_cid) = &receipt.logs_cid else {
+            return Ok(Vec::new());
+        };
+        let bytes = self.fetch_output(logs_cid)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// The [`JobExecutionReceipt`] recorded when this node created the
+    /// execution receipt for `job_id`
+    pub fn job_execution_receipt(&self, job_id: &str) -> Option<JobExecutionReceipt> {
+        self.job_receipts.lock().unwrap().get(job_id).cloned()
+    }
+
+    /// The stored output with the given CID, as written by
+    /// [`store_job_output`](Self::store_job_output)
+    pub fn fetch_output(&self, cid: &str) -> Result<Vec<u8>> {
+        let content = self.content.lock().unwrap();
+        Ok(content
+            .get(cid)
+            .cloned()
+            .ok_or_else(|| MeshError::OutputNotFound(cid.to_string()))?)
+    }
+
+    /// Choose a bid for the job with [`select_winning_bid`](Self::select_winning_bid)
+    /// and assign it.
+    ///
+    /// If no bid satisfies the placement constraints or passes reputation
+    /// verification the job is marked failed with the reason and an error is
+    /// returned.
+    pub async fn select_and_accept_bid<C>(
+        &self,
+        job_id: &str,
+        client: &C,
+        config: &BidEvaluatorConfig,
+    ) -> Result<Bid>
+    where
+        C: ReputationClient + Sync,
+    {
+        let failure = match self.select_winning_bid(job_id, client, config).await {
+            Ok(Some(bid)) => {
+                self.accept_bid(job_id, &bid.node_id).await?;
+                return Ok(bid);
+            }
+            Ok(None) => MeshError::NoAcceptableBid(format!(
+                "no unexpired bid for job {} comes from a node with a verified reputation",
+                job_id
+            ))
+            .into(),
+            Err(e)
+                if matches!(
+                    e.downcast_ref::<MeshError>(),
+                    Some(MeshError::UnsatisfiableAffinity(_))
+                ) =>
+            {
+                e
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(job_id) {
+            job.status = JobStatus::Failed {
+                node_id: None,
+                error: failure.to_string(),
+                stage_index: None,
+                stage_id: None,
+            };
+        }
+        Err(failure)
+    }
+
+    /// Buffer a chunk of interactive input for `job_id`.
+    ///
+    /// Fails with `MeshError::BufferFull` once
+    /// [`DEFAULT_INTERACTIVE_BUFFER_CAPACITY`] chunks are waiting to be drained,
+    /// or when the chunk is that many or more ahead of the next one expected.
+    pub async fn push_interactive_input(
+        &self,
+        job_id: &str,
+        input: JobInteractiveInputV1,
+    ) -> Result<()> {
+        let mut buffers = self.interactive.lock().unwrap();
+        buffers
+            .entry(job_id.to_string())
+            .or_insert_with(|| InteractiveBuffers::new(DEFAULT_INTERACTIVE_BUFFER_CAPACITY))
+            .input
+            .push(input)
+            .map_err(|rejected| {
+                MeshError::BufferFull(format!(
+                    "input chunk {} for job {}",
+                    rejected.sequence_num, job_id
+                ))
+            })?;
+        Ok(())
+    }
+
+    /// Take the buffered input of `job_id` that is next in sequence.
+    ///
+    /// The job's buffers are dropped once both directions have drained their
+    /// final chunk.
+    pub async fn drain_interactive_input(&self, job_id: &str) -> Vec<JobInteractiveInputV1> {
+        let mut buffers = self.interactive.lock().unwrap();
+        let Some(job_buffers) = buffers.get_mut(job_id) else {
+            return Vec::new();
+        };
+        let drained = job_buffers.input.drain();
+        if job_buffers.is_finished() {
+            buffers.remove(job_id);
+        }
+        drained
+    }
+
+    /// Buffer a chunk of interactive output produced by `job_id`, failing with
+    /// `MeshError::BufferFull` like [`push_interactive_input`](Self::push_interactive_input)
+    pub async fn push_interactive_output(
+        &self,
+        job_id: &str,
+        output: JobInteractiveOutputV1,
+    ) -> Result<()> {
+        let mut buffers = self.interactive.lock().unwrap();
+        buffers
+            .entry(job_id.to_string())
+            .or_insert_with(|| InteractiveBuffers::new(DEFAULT_INTERACTIVE_BUFFER_CAPACITY))
+            .output
+            .push(output)
+            .map_err(|rejected| {
+                MeshError::BufferFull(format!(
+                    "output chunk {} for job {}",
+                    rejected.sequence_num, job_id
+                ))
+            })?;
+        Ok(())
+    }
+
+    /// Take the buffered output of `job_id` that is next in sequence, dropping
+    /// the job's buffers like [`drain_interactive_input`](Self::drain_interactive_input)
+    pub async fn drain_interactive_output(&self, job_id: &str) -> Vec<JobInteractiveOutputV1> {
+        let mut buffers = self.interactive.lock().unwrap();
+        let Some(job_buffers) = buffers.get_mut(job_id) else {
+            return Vec::new();
+        };
+        let drained = job_buffers.output.drain();
+        if job_buffers.is_finished() {
+            buffers.remove(job_id);
+        }
+        drained
+    }
+
+    /// Drop the interactive buffers of a job that will not run any further
+    fn close_interactive(&self, job_id: &str) {
+        self.interactive.lock().unwrap().remove(job_id);
+    }
+
+    /// Remember `capability` as a known peer with a reputation score of 0-100,
+    /// replacing what was known about that node before
+    pub fn register_peer(&self, capability: NodeCapability, reputation_score: u32) {
+        self.known_peers.lock().unwrap().insert(
+            capability.node_id.clone(),
+            (capability, reputation_score.min(100)),
+        );
+    }
+
+    /// Register a peer scored by the reputation service behind `client`
+    pub async fn discover_peer<C>(&self, capability: NodeCapability, client: &C) -> Result<()>
+    where
+        C: ReputationClient + Sync,
+    {
+        let profile = client.fetch_profile(&capability.node_did).await?;
+        let score = profile.computed_score.clamp(0.0, 100.0).round() as u32;
+        self.register_peer(capability, score);
+        Ok(())
+    }
+
+    /// Known peers that meet `req` with a reputation of at least
+    /// `min_reputation`, most reputable first
+    pub fn find_capable_nodes(
+        &self,
+        req: &ComputeRequirements,
+        min_reputation: u32,
+    ) -> Vec<NodeCapability> {
+        let peers = self.known_peers.lock().unwrap();
+        let mut capable: Vec<&(NodeCapability, u32)> = peers
+            .values()
+            .filter(|(capability, score)| {
+                *score >= min_reputation && meets_requirements(capability, req).is_ok()
+            })
+            .collect();
+        capable.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.cmp(a_score).then_with(|| a.node_id.cmp(&b.node_id))
+        });
+        capable
+            .into_iter()
+            .map(|(capability, _)| capability.clone())
+            .collect()
+    }
+
+    /// Cancel every job whose `expires_at` has passed and that has not
+    /// finished yet, returning the IDs of the jobs cancelled by this call.
+    ///
+    /// Already cancelled or otherwise terminal jobs are left alone, so calling
+    /// this repeatedly only reports each job once.
+    pub async fn expire_stale_jobs(&self) -> Result<Vec<String>> {
+        let now = Utc::now();
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut expired = Vec::new();
+        for (job_id, job) in jobs.iter_mut() {
+            let past_expiry = job.expires_at.is_some_and(|expires_at| expires_at <= now);
+            if past_expiry && !job.status.is_terminal() {
+                job.status = JobStatus::Cancelled;
+                expired.push(job_id.clone());
+            }
+        }
+        drop(jobs);
+        for job_id in &expired {
+            self.close_interactive(job_id);
+        }
+        Ok(expired)
+    }
+
+    /// Record that stage `completed_stage` of a multi-stage job finished.
+    ///
+    /// The job must be `Running` that stage, or `AwaitingNextStage` with it as
+    /// the next stage; a job that is not `Running` with a stage index is taken
+    /// to be on stage 0. With `next_stage` the job moves to `AwaitingNextStage`,
+    /// which must be the stage directly after `completed_stage`; without it the
+    /// job is `Completed`, referencing the CID of its receipt. Completing a job
+    /// for which no receipt was created fails and leaves its status unchanged.
+    pub async fn advance_stage(
+        &self,
+        job_id: &str,
+        completed_stage: u32,
+        next_stage: Option<u32>,
+    ) -> Result<JobStatus> {
+        let receipt_cid = self
+            .receipts
+            .lock()
+            .unwrap()
+            .get(job_id)
+            .map(|receipt| receipt.cid())
+            .transpose()?
+            .map(|cid| cid.to_string());
+
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| MeshError::JobNotFound(job_id.to_string()))?;
+
+        let (node_id, current_stage) = match &job.status {
+            JobStatus::Running {
+                node_id,
+                current_stage_index,
+                ..
+            } => (node_id.clone(), current_stage_index.unwrap_or(0)),
+            JobStatus::AwaitingNextStage {
+                node_id,
+                next_stage_index,
+                ..
+            } => (node_id.clone(), *next_stage_index),
+            other => {
+                return Err(MeshError::InvalidTransition(format!(
+                    "job {} cannot complete stage {} while {:?}",
+                    job_id, completed_stage, other
+                ))
+                .into())
+            }
+        };
+        if completed_stage != current_stage {
+            return Err(MeshError::InvalidTransition(format!(
+                "job {} is on stage {}, not stage {}",
+                job_id, current_stage, completed_stage
+            ))
+            .into());
+        }
+
+        job.status = match next_stage {
+            Some(next) if Some(next) != completed_stage.checked_add(1) => {
+                return Err(MeshError::InvalidTransition(format!(
+                    "job {} cannot move from stage {} to stage {}",
+                    job_id, completed_stage, next
+                ))
+                .into())
+            }
+            Some(next) => JobStatus::AwaitingNextStage {
+                node_id,
+                completed_stage_index: completed_stage,
+                completed_stage_id: None,
+                next_stage_index: next,
+                next_stage_id: None,
+            },
+            None => match receipt_cid {
+                Some(receipt_cid) => JobStatus::Completed {
+                    node_id,
+                    receipt_cid,
+                },
+                None => {
+                    return Err(MeshError::InvalidTransition(format!(
+                        "job {} cannot complete without an execution receipt",
+                        job_id
+                    ))
+                    .into())
+                }
+            },
+        };
+        let status = job.status.clone();
+        drop(jobs);
+        if status.is_terminal() {
+            self.close_interactive(job_id);
+        }
+        Ok(status)
+    }
+
+    /// Pick the best unexpired bid for `job_id` that satisfies its placement
+    /// constraints.
+    ///
+    /// Each bidder's profile is fetched through `client`. A bid is dropped when
+    /// the profile cannot be fetched, when its self-reported
+    /// `reputation_score` does not match the profile, or when the profile is
+    /// below the executor floor of `config`. The rest are scored by
+    /// [`ReputationClient::calculate_bid_score`] from their price relative to
+    /// the cheapest bid, their resource headroom and the verified profile, plus
+    /// the affinity boost of any preferred attributes. Equal scores go to the
+    /// higher verified reputation, then to the earlier bid.
+    ///
+    /// Returns `None` when no unexpired bid passes verification, and fails
+    /// with `MeshError::UnsatisfiableAffinity` when the placement constraints
+    /// rule out every unexpired bid.
+    pub async fn select_winning_bid<C>(
+        &self,
+        job_id: &str,
+        client: &C,
+        config: &BidEvaluatorConfig,
+    ) -> Result<Option<Bid>>
+    where
+        C: ReputationClient + Sync,
+    {
+        let (manifest, placements) = {
+            let jobs = self.jobs.lock().unwrap();
+            let manifest = jobs
+                .get(job_id)
+                .cloned()
+                .ok_or_else(|| MeshError::JobNotFound(job_id.to_string()))?;
+            let placements: HashMap<String, String> = jobs
+                .iter()
+                .filter_map(|(id, job)| {
+                    job_node_id(&job.status).map(|node_id| (id.clone(), node_id.to_string()))
+                })
+                .collect();
+            (manifest, placements)
+        };
+
+        let now = Utc::now();
+        let live: Vec<Bid> = self
+            .get_bids(job_id)
+            .await?
+            .into_iter()
+            .filter(|bid| bid.expires_at > now)
+            .collect();
+        if live.is_empty() {
+            return Ok(None);
+        }
+        let eligible = affinity::eligible_bids(&manifest, &live, &placements)?;
+        let mut verified = Vec::new();
+        for bid in eligible {
+            let profile = match client.fetch_profile(&bid.node_did).await {
+                Ok(profile) => profile,
+                Err(e) => {
+                    tracing::warn!(
+                        "Dropping bid from {} for job {}: reputation unavailable: {}",
+                        bid.node_id,
+                        job_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            if !client.verify_reported_score(&profile, bid.reputation_score) {
+                tracing::warn!(
+                    "Dropping bid from {} for job {}: reported reputation {} does not match {:.2}",
+                    bid.node_id,
+                    job_id,
+                    bid.reputation_score,
+                    profile.computed_score
+                );
+                continue;
+            }
+            if let Err(e) = config.check_executor_reputation(&profile, manifest.priority) {
+                tracing::info!("Dropping bid for job {}: {}", job_id, e);
+                continue;
+            }
+            verified.push((bid, profile));
+        }
+
+        let min_amount = verified
+            .iter()
+            .map(|(bid, _)| bid.bid_amount)
+            .min()
+            .unwrap_or(0);
+        let mut best: Option<(&Bid, f64, f64)> = None;
+        for (bid, profile) in verified {
+            // 0 for the cheapest bid, approaching 1 as a bid costs many times more
+            let normalized_price = if bid.bid_amount == 0 {
+                0.0
+            } else {
+                1.0 - min_amount as f64 / bid.bid_amount as f64
+            };
+            let score = client.calculate_bid_score(
+                config,
+                &profile,
+                normalized_price,
+                resource_headroom(&bid.node_capacity, &manifest.requirements),
+            ) + manifest.affinity.preference_boost(&bid.node_capacity);
+
+            let better = best.map_or(true, |(best_bid, best_score, best_reputation)| {
+                score
+                    .total_cmp(&best_score)
+                    .then(profile.computed_score.total_cmp(&best_reputation))
+                    .then(best_bid.timestamp.cmp(&bid.timestamp))
+                    .is_gt()
+            });
+            if better {
+                best = Some((bid, score, profile.computed_score));
+            }
+        }
+        Ok(best.map(|(bid, ..)| bid.clone()))
+    }
+}
+
+/// Room a node leaves beyond what a job requires, from 0 (no spare memory,
+/// CPU or storage, or the requirements are not met) to 1 (the job takes none
+/// of them), averaged over the three
+fn resource_headroom(cap: &NodeCapability, req: &ComputeRequirements) -> f64 {
+    if meets_requirements(cap, req).is_err() {
+        return 0.0;
+    }
+    let spare = |available: u32, required: u32| {
+        if available == 0 {
+            1.0
+        } else {
+            1.0 - f64::from(required) / f64::from(available)
+        }
+    };
+    (spare(cap.available_memory_mb, req.min_memory_mb)
+        + spare(cap.available_cpu_cores, req.min_cpu_cores)
+        + spare(cap.available_storage_mb, req.min_storage_mb))
+        / 3.0
+}
+
+/// Check `cap` against `req`, returning every constraint the node falls short of.
+pub fn meets_requirements(
+    cap: &NodeCapability,
+    req: &ComputeRequirements,
+) -> Result<(), Vec<String>> {
+    let mut unmet = Vec::new();
+    if cap.available_memory_mb < req.min_memory_mb {
+        unmet.push(format!(
+            "memory {} MB below required {} MB",
+            cap.available_memory_mb, req.min_memory_mb
+        ));
+    }
+    if cap.available_cpu_cores < req.min_cpu_cores {
+        unmet.push(format!(
+            "{} CPU cores below required {}",
+            cap.available_cpu_cores, req.min_cpu_cores
+        ));
+    }
+    if cap.available_storage_mb < req.min_storage_mb {
+        unmet.push(format!(
+            "storage {} MB below required {} MB",
+            cap.available_storage_mb, req.min_storage_mb
+        ));
+    }
+    for feature in &req.required_features {
+        if !cap.features.contains(feature) {
+            unmet.push(format!("missing feature {}", feature));
+        }
+    }
+
+    if unmet.is_empty() {
+        Ok(())
+    } else {
+        Err(unmet)
+    }
+}
+
+/// Node a job currently occupies, if it has been placed and not yet finished
+fn job_node_id(status: &JobStatus) -> Option<&str> {
+    match status {
+        JobStatus::Assigned { node_id }
+        | JobStatus::Running { node_id, .. }
+        | JobStatus::PendingUserInput { node_id, .. }
+        | JobStatus::AwaitingNextStage { node_id, .. } => Some(node_id),
+        _ => None,
+    }
 }
 
 #[async_trait]
@@ -534,6 +1245,18 @@ impl MeshNode for PlanetaryMeshNode {
     }
 
     async fn submit_bid(&self, job_id: &str, bid: Bid) -> Result<()> {
+        // Refuse bids from nodes that could not run a job we know about
+        if let Some(job) = self.jobs.lock().unwrap().get(job_id) {
+            meets_requirements(&bid.node_capacity, &job.requirements).map_err(|unmet| {
+                MeshError::RequirementsNotMet(format!(
+                    "{} cannot run job {}: {}",
+                    bid.node_id,
+                    job_id,
+                    unmet.join(", ")
+                ))
+            })?;
+        }
+
         // Store the bid
         let mut bids = self.bids.lock().unwrap();
         let job_bids = bids.entry(job_id.to_string()).or_default();
@@ -611,6 +1334,7 @@ mod tests {
             ccl_cid: None,
             input_data_cid: None,
             output_location: None,
+            output_recipients: vec![],
             requirements: ComputeRequirements {
                 min_memory_mb: 512,
                 min_cpu_cores: 2,
@@ -621,6 +1345,7 @@ mod tests {
             priority: JobPriority::Medium,
             resource_token: token,
             trust_requirements: vec![],
+            affinity: AffinityConstraints::default(),
             status: JobStatus::Created,
         };
 
@@ -637,4 +1362,842 @@ mod tests {
         assert_eq!(jobs.len(), 1);
         assert_eq!(jobs[0].id, job_id);
     }
+
+    fn job_receipt(output_data_cid: Option<&str>) -> JobExecutionReceipt {
+        JobExecutionReceipt {
+            job_id: "hash-job".to_string(),
+            executor_node_id: "node-1".to_string(),
+            executor_node_did: "did:key:node-1".to_string(),
+            metrics: ExecutionMetrics::default(),
+            output_data_cid: output_data_cid.map(str::to_string),
+            start_time: Utc::now(),
+            end_time: Utc::now(),
+            resource_usage: vec![],
+            receipt_cid: "receipt-cid".to_string(),
+            verified_by_federation: false,
+            verifier_did: None,
+            verified_at: None,
+            result_status: 0,
+            result_hash: None,
+            result_metadata: None,
+            execution_logs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_result_hash_of_fetched_output() {
+        let mut receipt = job_receipt(Some("bafy-output"));
+        receipt.fill_result_hash(|cid| {
+            assert_eq!(cid, "bafy-output");
+            Ok(b"hello world".to_vec())
+        });
+        assert_eq!(
+            receipt.result_hash.as_deref(),
+            Some("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+        );
+
+        let mut unfetchable = job_receipt(Some("bafy-missing"));
+        unfetchable.fill_result_hash(|cid| Err(MeshError::JobNotFound(cid.to_string()).into()));
+        assert_eq!(unfetchable.result_hash, None);
+
+        let mut no_output = job_receipt(None);
+        no_output.fill_result_hash(|_| panic!("nothing to fetch"));
+        assert_eq!(no_output.result_hash, None);
+    }
+
+    #[tokio::test]
+    async fn created_receipt_hashes_the_stored_output() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        node.submit_job(manifest("hashed", AffinityConstraints::default()))
+            .await
+            .unwrap();
+        let stored = node.store_job_output("hashed", b"hello world").unwrap();
+
+        for (output_cid, expected_hash) in [
+            (
+                stored.cid,
+                Some("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"),
+            ),
+            // Output the node never stored leaves the hash unset
+            ("bafy-missing".to_string(), None),
+        ] {
+            node.create_execution_receipt(
+                "hashed",
+                StandardJobStatus::CompletedSuccess,
+                &ExecutionMetrics::default(),
+                vec![],
+                Some(output_cid),
+                vec![],
+                0,
+            )
+            .await
+            .unwrap();
+            let job_receipt = node.job_execution_receipt("hashed").unwrap();
+            assert_eq!(job_receipt.result_hash.as_deref(), expected_hash);
+        }
+    }
+
+    /// A module importing the CoVm host functions that logs "first" and
+    /// "second", then runs `then`
+    fn logging_module(then: &str) -> Vec<u8> {
+        wat::parse_str(format!(
+            r#"
+            (module
+              (import "icn" "log" (func $log (param i32 i32)))
+              (import "icn" "anchor" (func (param i32 i32)))
+              (import "icn" "check_auth" (func (param i32 i32 i64) (result i32)))
+              (import "icn" "record_usage" (func (param i32 i32 i64)))
+              (import "icn" "submit_job"
+                (func (param i32 i32 i32 i32 i32 i32 i64 i32 i32) (result i32)))
+              (memory (export "memory") 1)
+              (data (i32.const 0) "first")
+              (data (i32.const 16) "second")
+              (func (export "_start")
+                (call $log (i32.const 0) (i32.const 5))
+                (call $log (i32.const 16) (i32.const 6))
+                {then}))
+            "#
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn execution_logs_round_trip_through_their_cid() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let logged = vec!["first".to_string(), "second".to_string()];
+
+        let receipt = node.execute_job("logs", &logging_module("")).await.unwrap();
+        assert_eq!(receipt.status, StandardJobStatus::CompletedSuccess);
+        assert!(receipt.logs_cid.is_some());
+        assert_eq!(node.fetch_logs(&receipt).unwrap(), logged);
+        assert_eq!(
+            node.job_execution_receipt("logs").unwrap().execution_logs,
+            logged
+        );
+
+        // A module that traps keeps what it logged before the trap
+        let trapped = node
+            .execute_job("trapped", &logging_module("unreachable"))
+            .await
+            .unwrap();
+        assert!(matches!(trapped.status, StandardJobStatus::Failed { .. }));
+        assert_eq!(node.fetch_logs(&trapped).unwrap(), logged);
+    }
+
+    fn capability(node_id: &str, location: &str, features: &[&str]) -> NodeCapability {
+        NodeCapability {
+            node_id: node_id.to_string(),
+            node_did: format!("did:key:{}", node_id),
+            available_memory_mb: 1024,
+            available_cpu_cores: 4,
+            available_storage_mb: 10240,
+            cpu_architecture: "x86_64".to_string(),
+            features: features.iter().map(|f| f.to_string()).collect(),
+            location: Some(location.to_string()),
+            bandwidth_mbps: 1000,
+            supported_job_types: vec!["compute".to_string()],
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn bid(job_id: &str, capacity: NodeCapability, bid_amount: u64) -> Bid {
+        Bid {
+            job_id: job_id.to_string(),
+            node_id: capacity.node_id.clone(),
+            node_did: capacity.node_did.clone(),
+            bid_amount,
+            estimated_execution_time: 60,
+            timestamp: Utc::now(),
+            expires_at: Utc::now() + chrono::Duration::minutes(5),
+            node_capacity: capacity,
+            reputation_score: 80,
+            capability_proof: None,
+        }
+    }
+
+    fn manifest(job_id: &str, affinity: AffinityConstraints) -> JobManifest {
+        JobManifest {
+            id: job_id.to_string(),
+            submitter_did: "did:key:submitter".to_string(),
+            description: "Affinity test job".to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            wasm_cid: "wasm-cid".to_string(),
+            ccl_cid: None,
+            input_data_cid: None,
+            output_location: None,
+            output_recipients: vec![],
+            requirements: ComputeRequirements {
+                min_memory_mb: 0,
+                min_cpu_cores: 0,
+                min_storage_mb: 0,
+                max_execution_time_secs: 60,
+                required_features: vec![],
+            },
+            priority: JobPriority::Medium,
+            resource_token: ScopedResourceToken {
+                resource_type: "compute".to_string(),
+                amount: 100,
+                scope: "test-scope".to_string(),
+                expires_at: None,
+                issuer: None,
+            },
+            trust_requirements: vec![],
+            affinity,
+            status: JobStatus::Submitted,
+        }
+    }
+
+    /// Reputation service answering from a fixed table of scores by node DID
+    struct FixedReputation(HashMap<String, f64>);
+
+    impl FixedReputation {
+        fn new(scores: &[(&str, f64)]) -> Self {
+            Self(
+                scores
+                    .iter()
+                    .map(|(node_id, score)| (format!("did:key:{}", node_id), *score))
+                    .collect(),
+            )
+        }
+    }
+
+    #[async_trait]
+    impl ReputationClient for FixedReputation {
+        async fn fetch_profile(
+            &self,
+            did: &str,
+        ) -> Result<icn_types::reputation::ReputationProfile> {
+            let score = self
+                .0
+                .get(did)
+                .ok_or_else(|| anyhow::anyhow!("no reputation profile for {}", did))?;
+            Ok(icn_types::reputation::ReputationProfile {
+                computed_score: *score,
+                ..reputation_integration::neutral_profile(did)
+            })
+        }
+
+        fn verify_reported_score(
+            &self,
+            profile: &icn_types::reputation::ReputationProfile,
+            reported: u32,
+        ) -> bool {
+            (profile.computed_score - f64::from(reported)).abs() < 1.0
+        }
+    }
+
+    #[tokio::test]
+    async fn bid_selection_honours_affinity() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+
+        // A job already running on node-a, which the new job must avoid
+        let mut placed = manifest("db-primary", AffinityConstraints::default());
+        placed.status = JobStatus::Assigned {
+            node_id: "node-a".to_string(),
+        };
+        node.submit_job(placed).await.unwrap();
+
+        let affinity = AffinityConstraints {
+            required: vec![NodeAttribute::Location("eu-west".to_string())],
+            preferred: vec![NodeAttribute::Feature("gpu".to_string())],
+            anti_affinity_job_ids: vec!["db-primary".to_string()],
+        };
+        node.submit_job(manifest("db-replica", affinity)).await.unwrap();
+
+        for bid in [
+            bid("db-replica", capability("node-a", "eu-west", &["gpu"]), 10),
+            bid("db-replica", capability("node-b", "us-east", &["gpu"]), 10),
+            bid("db-replica", capability("node-c", "eu-west", &[]), 10),
+            bid("db-replica", capability("node-d", "eu-west", &["gpu"]), 12),
+        ] {
+            node.submit_bid("db-replica", bid).await.unwrap();
+        }
+
+        // node-d costs more but the preferred GPU outweighs the price gap
+        let reputation = FixedReputation::new(&[("node-c", 80.0), ("node-d", 80.0)]);
+        let chosen = node
+            .select_and_accept_bid("db-replica", &reputation, &BidEvaluatorConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(chosen.node_id, "node-d");
+        assert_eq!(
+            node.get_job_status("db-replica").await.unwrap(),
+            JobStatus::Assigned {
+                node_id: "node-d".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn unsatisfiable_required_affinity_fails_job() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+
+        let affinity = AffinityConstraints {
+            required: vec![NodeAttribute::Feature("gpu".to_string())],
+            ..Default::default()
+        };
+        node.submit_job(manifest("render", affinity)).await.unwrap();
+        node.submit_bid("render", bid("render", capability("node-a", "eu-west", &[]), 5))
+            .await
+            .unwrap();
+
+        let reputation = FixedReputation::new(&[("node-a", 80.0)]);
+        let err = node
+            .select_and_accept_bid("render", &reputation, &BidEvaluatorConfig::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<MeshError>(),
+            Some(MeshError::UnsatisfiableAffinity(_))
+        ));
+        match node.get_job_status("render").await.unwrap() {
+            JobStatus::Failed { error, .. } => assert!(error.contains("node-a lacks feature=gpu")),
+            other => panic!("expected failed job, got {:?}", other),
+        }
+    }
+
+    fn running(node_id: &str, stage: u32) -> JobStatus {
+        JobStatus::Running {
+            node_id: node_id.to_string(),
+            current_stage_index: Some(stage),
+            current_stage_id: None,
+            progress_percent: None,
+            status_message: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn two_stage_job_advances_to_completion() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let mut job = manifest("pipeline", AffinityConstraints::default());
+        job.status = running("node-a", 0);
+        node.submit_job(job).await.unwrap();
+
+        let status = node.advance_stage("pipeline", 0, Some(1)).await.unwrap();
+        assert!(matches!(
+            status,
+            JobStatus::AwaitingNextStage {
+                completed_stage_index: 0,
+                next_stage_index: 1,
+                ..
+            }
+        ));
+
+        let receipt = node
+            .create_execution_receipt(
+                "pipeline",
+                StandardJobStatus::CompletedSuccess,
+                &ExecutionMetrics::default(),
+                vec![],
+                None,
+                vec![],
+                0,
+            )
+            .await
+            .unwrap();
+        let status = node.advance_stage("pipeline", 1, None).await.unwrap();
+        match status {
+            JobStatus::Completed {
+                node_id,
+                receipt_cid,
+            } => {
+                assert_eq!(node_id, "node-a");
+                assert_eq!(receipt_cid, receipt.cid().unwrap().to_string());
+            }
+            other => panic!("expected completed job, got {:?}", other),
+        }
+        assert!(node.advance_stage("pipeline", 2, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn completing_without_a_receipt_is_rejected() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let mut job = manifest("pipeline", AffinityConstraints::default());
+        job.status = running("node-a", 0);
+        node.submit_job(job).await.unwrap();
+
+        let err = node.advance_stage("pipeline", 0, None).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<MeshError>(),
+            Some(MeshError::InvalidTransition(_))
+        ));
+        assert_eq!(
+            node.get_job_status("pipeline").await.unwrap(),
+            running("node-a", 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn out_of_order_stage_completion_is_rejected() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let mut job = manifest("pipeline", AffinityConstraints::default());
+        job.status = running("node-a", 0);
+        node.submit_job(job).await.unwrap();
+
+        let err = node.advance_stage("pipeline", 1, Some(2)).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<MeshError>(),
+            Some(MeshError::InvalidTransition(_))
+        ));
+        assert!(node.advance_stage("pipeline", 0, Some(3)).await.is_err());
+        assert_eq!(
+            node.get_job_status("pipeline").await.unwrap(),
+            running("node-a", 0)
+        );
+    }
+
+    fn input_chunk(sequence_num: u64) -> JobInteractiveInputV1 {
+        JobInteractiveInputV1 {
+            sequence_num,
+            data: vec![sequence_num as u8],
+            input_key: "stdin".to_string(),
+            is_final_chunk: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn interactive_input_drains_in_sequence_order() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        for seq in [2, 0, 3] {
+            node.push_interactive_input("chat", input_chunk(seq)).await.unwrap();
+        }
+
+        let drained: Vec<u64> = node
+            .drain_interactive_input("chat")
+            .await
+            .iter()
+            .map(|c| c.sequence_num)
+            .collect();
+        assert_eq!(drained, vec![0]);
+
+        // Chunk 1 fills the gap, releasing the held-back 2 and 3
+        node.push_interactive_input("chat", input_chunk(1)).await.unwrap();
+        let drained: Vec<u64> = node
+            .drain_interactive_input("chat")
+            .await
+            .iter()
+            .map(|c| c.sequence_num)
+            .collect();
+        assert_eq!(drained, vec![1, 2, 3]);
+        assert!(node.drain_interactive_input("other").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn full_interactive_buffer_refuses_input() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        for seq in 0..DEFAULT_INTERACTIVE_BUFFER_CAPACITY as u64 {
+            node.push_interactive_input("flood", input_chunk(seq)).await.unwrap();
+        }
+
+        let err = node
+            .push_interactive_input("flood", input_chunk(DEFAULT_INTERACTIVE_BUFFER_CAPACITY as u64))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<MeshError>(),
+            Some(MeshError::BufferFull(_))
+        ));
+
+        assert_eq!(
+            node.drain_interactive_input("flood").await.len(),
+            DEFAULT_INTERACTIVE_BUFFER_CAPACITY
+        );
+        node.push_interactive_input("flood", input_chunk(DEFAULT_INTERACTIVE_BUFFER_CAPACITY as u64))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn interactive_input_far_ahead_of_the_gap_is_refused() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        for seq in [DEFAULT_INTERACTIVE_BUFFER_CAPACITY as u64, u64::MAX] {
+            let err = node
+                .push_interactive_input("skip", input_chunk(seq))
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<MeshError>(),
+                Some(MeshError::BufferFull(_))
+            ));
+        }
+
+        // Chunks held back behind the missing 0 stay within the window
+        for seq in 1..DEFAULT_INTERACTIVE_BUFFER_CAPACITY as u64 {
+            node.push_interactive_input("skip", input_chunk(seq))
+                .await
+                .unwrap();
+        }
+        assert!(node.drain_interactive_input("skip").await.is_empty());
+        node.push_interactive_input("skip", input_chunk(0))
+            .await
+            .unwrap();
+        assert_eq!(
+            node.drain_interactive_input("skip").await.len(),
+            DEFAULT_INTERACTIVE_BUFFER_CAPACITY
+        );
+    }
+
+    #[tokio::test]
+    async fn finished_interactive_streams_are_dropped() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let mut last_input = input_chunk(1);
+        last_input.is_final_chunk = true;
+        node.push_interactive_input("done", input_chunk(0))
+            .await
+            .unwrap();
+        node.push_interactive_input("done", last_input)
+            .await
+            .unwrap();
+        node.push_interactive_output(
+            "done",
+            JobInteractiveOutputV1 {
+                sequence_num: 0,
+                data: vec![0],
+                output_key: "stdout".to_string(),
+                is_final_chunk: true,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(node.drain_interactive_input("done").await.len(), 2);
+        assert!(node.interactive.lock().unwrap().contains_key("done"));
+        // Input is closed, so nothing more is accepted in that direction
+        assert!(node
+            .push_interactive_input("done", input_chunk(2))
+            .await
+            .is_err());
+
+        assert_eq!(node.drain_interactive_output("done").await.len(), 1);
+        assert!(!node.interactive.lock().unwrap().contains_key("done"));
+    }
+
+    #[test]
+    fn capable_nodes_are_filtered_and_ranked_by_reputation() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let mut small = capability("small", "eu-west", &["gpu"]);
+        small.available_memory_mb = 128;
+        node.register_peer(capability("trusted", "eu-west", &["gpu"]), 95);
+        node.register_peer(capability("newcomer", "eu-west", &["gpu"]), 30);
+        node.register_peer(capability("solid", "us-east", &["gpu"]), 70);
+        node.register_peer(capability("cpu-only", "us-east", &[]), 99);
+        node.register_peer(small, 90);
+
+        let requirements = ComputeRequirements {
+            min_memory_mb: 512,
+            min_cpu_cores: 2,
+            min_storage_mb: 0,
+            max_execution_time_secs: 60,
+            required_features: vec!["gpu".to_string()],
+        };
+        let found: Vec<String> = node
+            .find_capable_nodes(&requirements, 50)
+            .into_iter()
+            .map(|c| c.node_id)
+            .collect();
+        assert_eq!(found, vec!["trusted".to_string(), "solid".to_string()]);
+
+        assert_eq!(node.find_capable_nodes(&requirements, 0).len(), 3);
+        assert!(node.find_capable_nodes(&requirements, 96).is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_expired_jobs_are_cancelled() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let mut stale = manifest("stale", AffinityConstraints::default());
+        stale.status = JobStatus::Submitted;
+        stale.expires_at = Some(Utc::now() - chrono::Duration::minutes(1));
+        let mut live = manifest("live", AffinityConstraints::default());
+        live.expires_at = Some(Utc::now() + chrono::Duration::minutes(10));
+        let mut done = manifest("done", AffinityConstraints::default());
+        done.expires_at = stale.expires_at;
+        done.status = JobStatus::Completed {
+            node_id: "node-a".to_string(),
+            receipt_cid: "bafy-receipt".to_string(),
+        };
+        for job in [stale, live, done] {
+            node.submit_job(job).await.unwrap();
+        }
+
+        assert_eq!(node.expire_stale_jobs().await.unwrap(), vec!["stale".to_string()]);
+        assert_eq!(node.get_job_status("stale").await.unwrap(), JobStatus::Cancelled);
+        assert_eq!(node.get_job_status("live").await.unwrap(), JobStatus::Created);
+        assert!(node.get_job_status("done").await.unwrap().is_terminal());
+
+        assert!(node.expire_stale_jobs().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn requirements_report_every_shortfall() {
+        let mut requirements = manifest("gpu-job", AffinityConstraints::default()).requirements;
+        requirements.min_memory_mb = 512;
+        requirements.required_features = vec!["gpu".to_string()];
+
+        assert!(meets_requirements(&capability("node-a", "eu-west", &["gpu"]), &requirements).is_ok());
+
+        let unmet = meets_requirements(&capability("node-b", "eu-west", &["avx"]), &requirements)
+            .unwrap_err();
+        assert_eq!(unmet, vec!["missing feature gpu".to_string()]);
+
+        let mut small = capability("node-c", "eu-west", &[]);
+        small.available_memory_mb = 256;
+        let unmet = meets_requirements(&small, &requirements).unwrap_err();
+        assert_eq!(unmet.len(), 2);
+        assert!(unmet[0].contains("memory 256 MB"));
+    }
+
+    #[tokio::test]
+    async fn bids_from_unfit_nodes_are_rejected() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let mut job = manifest("train", AffinityConstraints::default());
+        job.requirements.min_memory_mb = 512;
+        job.requirements.required_features = vec!["gpu".to_string()];
+        node.submit_job(job).await.unwrap();
+
+        let err = node
+            .submit_bid("train", bid("train", capability("node-a", "eu-west", &[]), 5))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<MeshError>(),
+            Some(MeshError::RequirementsNotMet(_))
+        ));
+
+        let mut low_memory = capability("node-b", "eu-west", &["gpu"]);
+        low_memory.available_memory_mb = 128;
+        assert!(node
+            .submit_bid("train", bid("train", low_memory, 5))
+            .await
+            .is_err());
+
+        node.submit_bid("train", bid("train", capability("node-c", "eu-west", &["gpu"]), 5))
+            .await
+            .unwrap();
+        let bids = node.get_bids("train").await.unwrap();
+        assert_eq!(bids.len(), 1);
+        assert_eq!(bids[0].node_id, "node-c");
+    }
+
+    #[tokio::test]
+    async fn winning_bid_balances_price_and_verified_reputation() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        node.submit_job(manifest("batch", AffinityConstraints::default()))
+            .await
+            .unwrap();
+        let reputation = FixedReputation::new(&[
+            ("node-a", 40.0),
+            ("node-b", 90.0),
+            ("node-c", 100.0),
+            ("node-d", 30.0),
+        ]);
+        let config = BidEvaluatorConfig::default();
+        assert!(node
+            .select_winning_bid("batch", &reputation, &config)
+            .await
+            .unwrap()
+            .is_none());
+
+        let mut cheap = bid("batch", capability("node-a", "eu-west", &[]), 5);
+        cheap.reputation_score = 40;
+        let mut balanced = bid("batch", capability("node-b", "eu-west", &[]), 6);
+        balanced.reputation_score = 90;
+        let mut expired = bid("batch", capability("node-c", "eu-west", &[]), 1);
+        expired.reputation_score = 100;
+        expired.expires_at = Utc::now() - chrono::Duration::seconds(1);
+        // Claims more reputation than the service reports for it
+        let mut inflated = bid("batch", capability("node-d", "eu-west", &[]), 1);
+        inflated.reputation_score = 95;
+        // Unknown to the reputation service
+        let mut unknown = bid("batch", capability("node-e", "eu-west", &[]), 1);
+        unknown.reputation_score = 100;
+        for b in [cheap, balanced, expired, inflated, unknown] {
+            node.submit_bid("batch", b).await.unwrap();
+        }
+
+        let winner = node
+            .select_winning_bid("batch", &reputation, &config)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.node_id, "node-b");
+
+        // Price alone picks the cheapest bid that has not expired and whose
+        // reputation checks out
+        let price_only = BidEvaluatorConfig {
+            weight_price: 1.0,
+            weight_resources: 0.0,
+            weight_reputation: 0.0,
+            weight_timeliness: 0.0,
+            ..BidEvaluatorConfig::default()
+        };
+        let winner = node
+            .select_winning_bid("batch", &reputation, &price_only)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.node_id, "node-a");
+
+        assert!(node
+            .select_winning_bid("unknown", &reputation, &config)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn winning_bid_prefers_resource_headroom() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let mut job = manifest("index", AffinityConstraints::default());
+        job.requirements.min_memory_mb = 512;
+        node.submit_job(job).await.unwrap();
+
+        let mut large = capability("node-b", "eu-west", &[]);
+        large.available_memory_mb = 4096;
+        node.submit_bid("index", bid("index", capability("node-a", "eu-west", &[]), 5))
+            .await
+            .unwrap();
+        node.submit_bid("index", bid("index", large, 5)).await.unwrap();
+
+        let reputation = FixedReputation::new(&[("node-a", 80.0), ("node-b", 80.0)]);
+        let resources_only = BidEvaluatorConfig {
+            weight_price: 0.0,
+            weight_resources: 1.0,
+            weight_reputation: 0.0,
+            weight_timeliness: 0.0,
+            ..BidEvaluatorConfig::default()
+        };
+        let winner = node
+            .select_winning_bid("index", &reputation, &resources_only)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.node_id, "node-b");
+    }
+
+    #[tokio::test]
+    async fn winning_bid_ties_break_by_verified_reputation_then_age() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        node.submit_job(manifest("tie", AffinityConstraints::default()))
+            .await
+            .unwrap();
+        let reputation =
+            FixedReputation::new(&[("node-a", 70.0), ("node-b", 70.0), ("node-c", 95.0)]);
+        // Reputation carries no weight, so only the tie-breaks separate the bids
+        let config = BidEvaluatorConfig {
+            weight_reputation: 0.0,
+            ..BidEvaluatorConfig::default()
+        };
+
+        let mut later = bid("tie", capability("node-a", "eu-west", &[]), 5);
+        later.reputation_score = 70;
+        let mut earlier = bid("tie", capability("node-b", "eu-west", &[]), 5);
+        earlier.reputation_score = 70;
+        earlier.timestamp = later.timestamp - chrono::Duration::seconds(10);
+        node.submit_bid("tie", later).await.unwrap();
+        node.submit_bid("tie", earlier).await.unwrap();
+        let winner = node
+            .select_winning_bid("tie", &reputation, &config)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.node_id, "node-b");
+
+        let mut reputable = bid("tie", capability("node-c", "eu-west", &[]), 5);
+        reputable.reputation_score = 95;
+        node.submit_bid("tie", reputable).await.unwrap();
+        let winner = node
+            .select_winning_bid("tie", &reputation, &config)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.node_id, "node-c");
+    }
+
+    #[tokio::test]
+    async fn unverifiable_bids_fail_the_job() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        node.submit_job(manifest("audit", AffinityConstraints::default()))
+            .await
+            .unwrap();
+        node.submit_bid("audit", bid("audit", capability("node-a", "eu-west", &[]), 5))
+            .await
+            .unwrap();
+
+        // The service knows node-a with far less reputation than its bid reports
+        let reputation = FixedReputation::new(&[("node-a", 20.0)]);
+        let err = node
+            .select_and_accept_bid("audit", &reputation, &BidEvaluatorConfig::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<MeshError>(),
+            Some(MeshError::NoAcceptableBid(_))
+        ));
+        assert!(matches!(
+            node.get_job_status("audit").await.unwrap(),
+            JobStatus::Failed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn sealed_output_round_trips_for_recipients_only() {
+        let local = capability("local", "us-west", &[]);
+        let node = PlanetaryMeshNode::new(local.node_did.clone(), local).unwrap();
+        let submitter = icn_identity::KeyPair::generate();
+        let outsider = icn_identity::KeyPair::generate();
+
+        let mut job = manifest("private", AffinityConstraints::default());
+        job.output_recipients = vec![submitter.did.to_string()];
+        node.submit_job(job).await.unwrap();
+        node.submit_job(manifest("public", AffinityConstraints::default()))
+            .await
+            .unwrap();
+
+        let result = b"confidential result";
+        let stored = node.store_job_output("private", result).unwrap();
+        assert!(stored.encrypted);
+        assert!(!stored.bytes.windows(result.len()).any(|w| w == result));
+        assert_eq!(decrypt_result(&stored.bytes, &submitter).unwrap(), result);
+        assert!(matches!(
+            decrypt_result(&stored.bytes, &outsider),
+            Err(MeshError::OutputEncryption(_))
+        ));
+        assert_eq!(node.fetch_output(&stored.cid).unwrap(), stored.bytes);
+
+        let receipt = node
+            .create_execution_receipt(
+                "private",
+                StandardJobStatus::CompletedSuccess,
+                &ExecutionMetrics::default(),
+                vec![],
+                Some(stored.cid.clone()),
+                vec![],
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(receipt.result_data_cid, Some(stored.cid));
+
+        let plain = node.store_job_output("public", result).unwrap();
+        assert!(!plain.encrypted);
+        assert_eq!(plain.bytes, result);
+    }
 }
@@ -3,11 +3,12 @@ use crate::behaviour::{
     RECEIPT_AVAILABILITY_TOPIC_HASH,
 };
 use crate::protocol::{MeshProtocolMessage, NodeCapability};
+use crate::reputation_integration::{BidEvaluatorConfig, DefaultReputationClient, ReputationClient};
 use chrono::{TimeZone, Utc}; // For timestamp conversion
 use cid::Cid; // For storing receipt CIDs
 use futures::StreamExt;
 use icn_economics::ResourceType;
-use icn_identity::{Did, KeyPair as IcnKeyPair};
+use icn_identity::{Did, KeyPair as IcnKeyPair, TaggedSignature};
 use icn_mesh_receipts::{
     sign_receipt_in_place, DagNode, ExecutionReceipt, ReceiptError, SignError as ReceiptSignError,
 };
@@ -15,7 +16,8 @@ use icn_types::mesh::{
     JobId as IcnJobId, JobStatus as StandardJobStatus, MeshJob, MeshJobParams,
     OrganizationScopeIdentifier, QoSProfile,
 };
-use icn_types::reputation::{ReputationProfile, ReputationRecord, ReputationUpdateEvent}; // Added Reputation types
+use icn_types::bounded_decode::{from_cbor_bounded, DecodeLimits};
+use icn_types::reputation::{ReputationRecord, ReputationUpdateEvent}; // Added Reputation types
 use libp2p::identity::{ed25519::SecretKey as Libp2pSecretKey, Keypair as Libp2pKeypair};
 use libp2p::Transport;
 use std::collections::{HashMap, VecDeque};
@@ -58,6 +60,57 @@ fn job_interest_topic_string(job_id: &IcnJobId) -> String {
     format!("/icn/mesh/jobs/{}/interest/v1", job_id)
 }
 
+/// The JobManifest announced for, and executed from, a MeshJob
+fn job_manifest(job: &MeshJob) -> super::JobManifest {
+    // Create a JobManifest from the MeshJob
+    // This is a simplified conversion; a real one would need more robust parsing and default handling.
+    let compute_requirements = serde_json::from_str::<super::ComputeRequirements>(
+        &job.params.required_resources_json,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!(
+            "Failed to parse required_resources_json for job {}: {}. Using default requirements.",
+            job.job_id, e
+        );
+        // Provide some default ComputeRequirements
+        super::ComputeRequirements {
+            min_memory_mb: 0,
+            min_cpu_cores: 0,
+            min_storage_mb: 0,
+            max_execution_time_secs: job.params.max_execution_time_secs.unwrap_or(300), // Default from MeshJob or a const
+            required_features: Vec::new(),
+        }
+    });
+
+    super::JobManifest {
+        id: job.job_id.clone(),
+        submitter_did: job.originator_did.clone(),
+        description: job
+            .params
+            .description
+            .clone()
+            .unwrap_or_else(|| "N/A".to_string()),
+        created_at: chrono::Utc::now(), // Or convert from job.submitted_at if it exists and types match
+        expires_at: None,               // MeshJob doesn't have this directly
+        wasm_cid: job.params.wasm_cid.clone(),
+        ccl_cid: job.params.ccl_cid.clone(),
+        input_data_cid: job.params.input_data_cid.clone(),
+        output_location: job.params.output_location.clone(),
+        output_recipients: job
+            .params
+            .output_recipients
+            .iter()
+            .map(|did| did.to_string())
+            .collect(),
+        requirements: compute_requirements,
+        priority: super::JobPriority::Medium, // Default priority
+        resource_token: icn_economics::ScopedResourceToken::default(), // Placeholder default
+        trust_requirements: job.params.trust_requirements.clone(),
+        affinity: super::AffinityConstraints::default(),
+        status: super::JobStatus::Submitted, // Initial status for a newly announced job
+    }
+}
+
 // 1. Define the internal action enum
 #[derive(Debug)]
 enum NodeInternalAction {
@@ -100,6 +153,9 @@ pub struct MeshNode {
     pub assigned_jobs: Arc<RwLock<HashMap<IcnJobId, MeshJob>>>,
     pub executing_jobs: Arc<RwLock<HashMap<IcnJobId, super::JobManifest>>>,
     pub completed_job_receipt_cids: Arc<RwLock<HashMap<IcnJobId, Cid>>>,
+    /// Output of jobs this node executed, as stored (sealed if the job has
+    /// output recipients), keyed by the CID recorded in the receipt
+    pub job_outputs: Arc<RwLock<HashMap<String, Vec<u8>>>>,
     pub local_runtime_context: Option<Arc<RuntimeContext>>,
     pub discovered_receipt_announcements: Arc<RwLock<HashMap<IcnJobId, (Cid, Did)>>>,
     // ADDITION: Test hook for listening to JobStatusUpdateV1 messages received by this node
@@ -113,6 +169,7 @@ pub struct MeshNode {
     reputation_service_url: Option<String>, // Added for reputation service URL
     http_client: reqwest::Client,           // Added http_client
     pub bids: Arc<RwLock<HashMap<IcnJobId, Vec<crate::protocol::Bid>>>>, // Added for storing bids
+    pub bid_config: BidEvaluatorConfig,
 }
 
 impl MeshNode {
@@ -190,6 +247,7 @@ impl MeshNode {
                 assigned_jobs: Arc::new(RwLock::new(HashMap::new())),
                 executing_jobs: Arc::new(RwLock::new(HashMap::new())),
                 completed_job_receipt_cids: Arc::new(RwLock::new(HashMap::new())),
+                job_outputs: Arc::new(RwLock::new(HashMap::new())),
                 local_runtime_context,
                 discovered_receipt_announcements: Arc::new(RwLock::new(HashMap::new())),
                 // ADDITION: Store the test listener sender
@@ -202,11 +260,18 @@ impl MeshNode {
                 reputation_service_url,                      // Store the URL
                 http_client: reqwest::Client::new(),         // Initialize the client
                 bids: Arc::new(RwLock::new(HashMap::new())), // Initialize bids
+                bid_config: BidEvaluatorConfig::default(),
             },
             internal_action_rx_for_event_loop,
         ))
     }
 
+    /// Use `config` for bid scoring and the reputation floors
+    pub fn with_bid_config(mut self, config: BidEvaluatorConfig) -> Self {
+        self.bid_config = config;
+        self
+    }
+
     fn construct_capability(&self) -> NodeCapability {
         // For now, use mock/static data. In a real node, this would be dynamic.
         let mut available_resources = HashMap::new();
@@ -247,45 +312,7 @@ impl MeshNode {
     }
 
     pub async fn announce_job(&mut self, job: MeshJob) -> Result<(), Box<dyn Error>> {
-        // Create a JobManifest from the MeshJob
-        // This is a simplified conversion; a real one would need more robust parsing and default handling.
-        let compute_requirements = serde_json::from_str::<super::ComputeRequirements>(&job.params.required_resources_json)
-            .unwrap_or_else(|e| {
-                eprintln!(
-                    "Failed to parse required_resources_json for job {}: {}. Using default requirements.",
-                    job.job_id,
-                    e
-                );
-                // Provide some default ComputeRequirements
-                super::ComputeRequirements {
-                    min_memory_mb: 0,
-                    min_cpu_cores: 0,
-                    min_storage_mb: 0,
-                    max_execution_time_secs: job.params.max_execution_time_secs.unwrap_or(300), // Default from MeshJob or a const
-                    required_features: Vec::new(),
-                }
-            });
-
-        let manifest = super::JobManifest {
-            id: job.job_id.clone(),
-            submitter_did: job.originator_did.clone(),
-            description: job
-                .params
-                .description
-                .clone()
-                .unwrap_or_else(|| "N/A".to_string()),
-            created_at: chrono::Utc::now(), // Or convert from job.submitted_at if it exists and types match
-            expires_at: None,               // MeshJob doesn't have this directly
-            wasm_cid: job.params.wasm_cid.clone(),
-            ccl_cid: job.params.ccl_cid.clone(),
-            input_data_cid: job.params.input_data_cid.clone(),
-            output_location: job.params.output_location.clone(),
-            requirements: compute_requirements,
-            priority: super::JobPriority::Medium, // Default priority
-            resource_token: icn_economics::ScopedResourceToken::default(), // Placeholder default
-            trust_requirements: job.params.trust_requirements.clone(),
-            status: super::JobStatus::Submitted, // Initial status for a newly announced job
-        };
+        let manifest = job_manifest(&job);
 
         let message = MeshProtocolMessage::JobAnnouncementV1(job.clone()); // Network message still uses MeshJob
         match serde_cbor::to_vec(&message) {
@@ -336,6 +363,33 @@ impl MeshNode {
 
     // Method to evaluate a job and express interest if suitable
     async fn evaluate_and_express_interest(&mut self, job: &MeshJob) -> Result<(), Box<dyn Error>> {
+        // 0. Submitter reputation floor. MeshJob carries no priority class, so
+        // announced jobs are treated as Medium like in `announce_job`.
+        if self.bid_config.min_reputation.submitter.is_some() {
+            let reputation_client = DefaultReputationClient::new(self.bid_config.clone());
+            let submitter = job.originator_did.to_string();
+            let profile = match reputation_client.fetch_profile(&submitter).await {
+                Ok(profile) => profile,
+                Err(e) => match self.bid_config.fallback_profile(&submitter) {
+                    Some(profile) => {
+                        tracing::warn!("[JobInterest] Could not fetch reputation profile for submitter {}: {}. Using fallback score {:.2}.", submitter, e, profile.computed_score);
+                        profile
+                    }
+                    None => {
+                        tracing::info!("[JobInterest] Not bidding on job {}: reputation of submitter {} is unavailable: {}", job.job_id, submitter, e);
+                        return Ok(());
+                    }
+                },
+            };
+            if let Err(e) = self
+                .bid_config
+                .check_submitter_reputation(&profile, super::JobPriority::Medium)
+            {
+                tracing::info!("[JobInterest] Not bidding on job {}: {}", job.job_id, e);
+                return Ok(());
+            }
+        }
+
         // 1. Suitability Check (Simplified)
         // For now, let's assume we need to parse job.params.required_resources_json
         // and compare with local capabilities. This is a placeholder for more complex logic.
@@ -444,8 +498,26 @@ impl MeshNode {
 
         // Simulate success for this path, error handling would set this to false
         let mut job_execution_successful = true;
+        let output = format!("simulated result of job {}", job_id).into_bytes();
         // --- End of simulated job execution ---
 
+        // Store the output as the manifest asks, sealed to its output recipients
+        // if it names any; the receipt only records the CID of the stored bytes
+        let result_data_cid = match super::prepare_job_output(&job_manifest(&job), &output) {
+            Ok(stored) => {
+                self.job_outputs
+                    .write()
+                    .unwrap()
+                    .insert(stored.cid.clone(), stored.bytes);
+                Some(stored.cid)
+            }
+            Err(e) => {
+                tracing::error!("Failed to store output of job {}: {}", job_id, e);
+                job_execution_successful = false;
+                None
+            }
+        };
+
         metrics::receipts_created_inc(); // Receipt object is about to be populated
 
         let mut receipt = ExecutionReceipt {
@@ -460,10 +532,7 @@ impl MeshNode {
                     stage_id: Some("execution".to_string()),
                 }
             },
-            result_data_cid: Some(
-                "bafybeigdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef"
-                    .to_string(),
-            ), // mock
+            result_data_cid,
             logs_cid: Some(
                 "bafybeigcafecafebeeffeedbeeffeedbeeffeedbeeffeedbeeffeedbeeffeed".to_string(),
             ), // mock
@@ -471,7 +540,7 @@ impl MeshNode {
             execution_start_time,  // u64, ms precision if possible, or seconds
             execution_end_time,    // u64, ms precision if possible, or seconds
             execution_end_time_dt, // DateTime<Utc>
-            signature: Vec::new(), // Will be filled by sign_receipt_in_place
+            signature: TaggedSignature::default(), // Will be filled by sign_receipt_in_place
             coop_id: job
                 .originator_org_scope
                 .as_ref()
@@ -488,6 +557,7 @@ impl MeshNode {
                     .sum::<u64>()
                     .max(10),
             ), // Example mana cost
+            deterministic: false,
         };
 
         let signing_start_time = std::time::Instant::now();
@@ -1173,11 +1243,11 @@ impl MeshNode {
                     });
 
                     // Initialize reputation client
-                    let reputation_client = crate::reputation_integration::DefaultReputationClient::with_default_config();
-                    // Load bid evaluator config (in the future this will come from a CCL policy)
-                    let bid_config = crate::reputation_integration::BidEvaluatorConfig::default();
+                    let reputation_client = DefaultReputationClient::new(self.bid_config.clone());
+                    // In the future this will come from a CCL policy
+                    let bid_config = &self.bid_config;
 
-                    for (job_id, (_manifest, mesh_job_details)) in originated_jobs_guard.iter() {
+                    for (job_id, (manifest, mesh_job_details)) in originated_jobs_guard.iter() {
                         if assigned_by_originator_guard.contains(job_id) {
                             tracing::trace!("[BidSelection] Job {} already assigned. Skipping selection.", job_id);
                             continue;
@@ -1205,28 +1275,23 @@ impl MeshNode {
                                 let runtime_handle = runtime::Handle::current();
                                 let reputation_profile = match runtime_handle.block_on(reputation_client.fetch_profile(&bid.bidder)) {
                                     Ok(profile) => profile,
-                                    Err(e) => {
-                                        tracing::warn!("[BidSelection] Could not fetch reputation profile for bidder {}: {}. Using default score.", bid.bidder, e);
-                                        // Create a default profile with neutral values
-                                        icn_types::reputation::ReputationProfile {
-                                            node_id: bid.bidder.clone(),
-                                            last_updated: chrono::Utc::now(),
-                                            total_jobs: 0,
-                                            successful_jobs: 0,
-                                            failed_jobs: 0,
-                                            jobs_on_time: 0,
-                                            jobs_late: 0,
-                                            average_execution_ms: None,
-                                            average_bid_accuracy: None,
-                                            dishonesty_events: 0,
-                                            endorsements: vec![],
-                                            current_stake: None,
-                                            computed_score: 50.0, // Neutral score
-                                            latest_anchor_cid: None,
+                                    Err(e) => match bid_config.fallback_profile(&bid.bidder) {
+                                        Some(profile) => {
+                                            tracing::warn!("[BidSelection] Could not fetch reputation profile for bidder {}: {}. Using fallback score {:.2}.", bid.bidder, e, profile.computed_score);
+                                            profile
                                         }
-                                    }
+                                        None => {
+                                            tracing::info!("[BidSelection] Rejecting bid for job {}: reputation of bidder {} is unavailable: {}", job_id, bid.bidder, e);
+                                            continue;
+                                        }
+                                    },
                                 };
 
+                                if let Err(e) = bid_config.check_executor_reputation(&reputation_profile, manifest.priority) {
+                                    tracing::info!("[BidSelection] Rejecting bid for job {}: {}", job_id, e);
+                                    continue;
+                                }
+
                                 // Calculate normalized price (0.0 to 1.0, where 0.0 is the best/lowest price)
                                 let normalized_price = if price_range > 0.0 {
                                     (bid.price as f64 - min_price as f64) / price_range
@@ -1240,7 +1305,7 @@ impl MeshNode {
 
                                 // Calculate the combined bid score
                                 let bid_score = reputation_client.calculate_bid_score(
-                                    &bid_config,
+                                    bid_config,
                                     &reputation_profile,
                                     normalized_price,
                                     resource_match
@@ -1335,7 +1400,7 @@ impl MeshNode {
                                     message,
                                 } = gossip_event
                                 {
-                                    match serde_cbor::from_slice::<MeshProtocolMessage>(&message.data) {
+                                    match from_cbor_bounded::<MeshProtocolMessage>(&message.data, &DecodeLimits::manifest()) {
                                         Ok(protocol_message) => {
                                             match protocol_message {
                                                 MeshProtocolMessage::CapabilityAdvertisementV1(capability) => {
@@ -1472,7 +1537,7 @@ impl MeshNode {
                                                         match cbor_data_result {
                                                             Ok(cbor_data) => {
                                                                 println!("[MeshNode] Successfully fetched CBOR data for receipt CID: {}", parsed_receipt_cid);
-                                                                match serde_cbor::from_slice::<ExecutionReceipt>(&cbor_data) {
+                                                                match from_cbor_bounded::<ExecutionReceipt>(&cbor_data, &DecodeLimits::receipt()) {
                                                                     Ok(receipt) => {
                                                                         // Calculate CID from the received and deserialized receipt data
                                                                         let actual_receipt_cid = match receipt.cid() {
@@ -1783,3 +1848,37 @@ fn get_reputation_record_signing_payload(
     };
     serde_cbor::to_vec(&record_for_signing)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn executed_output_is_sealed_to_the_jobs_recipients() {
+        let submitter = IcnKeyPair::generate();
+        let job = MeshJob {
+            job_id: "sealed".to_string(),
+            params: MeshJobParams {
+                output_recipients: vec![submitter.did.clone()],
+                ..MeshJobParams::default()
+            },
+            originator_did: submitter.did.clone(),
+            originator_org_scope: None,
+            submission_timestamp: 0,
+            parent_job_id: None,
+            origin_receipt_cid: None,
+            max_total_mana: None,
+            originator_signature: None,
+        };
+
+        let manifest = job_manifest(&job);
+        assert_eq!(manifest.output_recipients, vec![submitter.did.to_string()]);
+
+        let stored = crate::prepare_job_output(&manifest, b"result").unwrap();
+        assert!(stored.encrypted);
+        assert_eq!(
+            crate::decrypt_result(&stored.bytes, &submitter).unwrap(),
+            b"result"
+        );
+    }
+}
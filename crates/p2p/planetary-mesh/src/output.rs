@@ -0,0 +1,85 @@
+//! Job output handling, including encryption to the manifest's recipients.
+//!
+//! When a [`JobManifest`] lists `output_recipients`, the executor seals the
+//! result with [`SealedPayload`] before it leaves the node. The receipt only
+//! ever carries the CID of the stored bytes, so it stays public while the
+//! payload can be read by the recipients alone via [`decrypt_result`].
+
+use crate::{JobManifest, MeshError};
+use cid::multihash::{Code, MultihashDigest};
+use cid::Cid;
+use icn_identity::{Did, KeyPair, SealedPayload};
+use icn_mesh_receipts::RAW_CODEC;
+use std::str::FromStr;
+
+/// Output bytes ready for the blob store, with the CID to record in the receipt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredOutput {
+    pub cid: String,
+    pub bytes: Vec<u8>,
+    /// Whether `bytes` is a CBOR-encoded [`SealedPayload`] rather than the raw result
+    pub encrypted: bool,
+}
+
+/// Prepare `output` for storage according to `manifest`.
+///
+/// Without `output_recipients` the result is stored as-is. Otherwise it is
+/// sealed to every recipient and the CID covers the ciphertext only.
+pub fn prepare_job_output(
+    manifest: &JobManifest,
+    output: &[u8],
+) -> Result<StoredOutput, MeshError> {
+    if manifest.output_recipients.is_empty() {
+        return Ok(StoredOutput {
+            cid: content_cid(output),
+            bytes: output.to_vec(),
+            encrypted: false,
+        });
+    }
+
+    let recipients = manifest
+        .output_recipients
+        .iter()
+        .map(|did| {
+            Did::from_str(did).map_err(|e| {
+                MeshError::InvalidManifest(format!("output recipient '{}': {}", did, e))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let sealed = SealedPayload::seal(output, &recipients)
+        .map_err(|e| MeshError::OutputEncryption(e.to_string()))?;
+    let bytes =
+        serde_cbor::to_vec(&sealed).map_err(|e| MeshError::OutputEncryption(e.to_string()))?;
+
+    Ok(StoredOutput {
+        cid: content_cid(&bytes),
+        bytes,
+        encrypted: true,
+    })
+}
+
+/// Decrypt stored output that was sealed to `keypair`'s DID.
+pub fn decrypt_result(stored: &[u8], keypair: &KeyPair) -> Result<Vec<u8>, MeshError> {
+    let sealed: SealedPayload =
+        serde_cbor::from_slice(stored).map_err(|e| MeshError::OutputEncryption(e.to_string()))?;
+    sealed
+        .open(keypair)
+        .map_err(|e| MeshError::OutputEncryption(e.to_string()))
+}
+
+/// Hex-encoded SHA-256 of a job's output, as recorded in
+/// [`JobExecutionReceipt::result_hash`](crate::JobExecutionReceipt::result_hash).
+pub fn result_hash(output: &[u8]) -> String {
+    Code::Sha2_256
+        .digest(output)
+        .digest()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// CID under which the node stores `bytes`
+pub(crate) fn content_cid(bytes: &[u8]) -> String {
+    Cid::new_v1(RAW_CODEC, Code::Sha2_256.digest(bytes)).to_string()
+}
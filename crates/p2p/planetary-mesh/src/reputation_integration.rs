@@ -7,6 +7,8 @@ use std::time::Duration;
 // Import types needed for reputation integration
 use icn_types::reputation::{compute_score, ReputationProfile};
 
+use crate::{JobPriority, MeshError};
+
 // Constants for configuration
 const DEFAULT_REPUTATION_API_TIMEOUT_SECS: u64 = 5;
 const DEFAULT_REPUTATION_SCORE_TOLERANCE: f64 = 0.05; // 5% tolerance for score verification
@@ -20,6 +22,147 @@ pub struct BidEvaluatorConfig {
     pub reputation_api_endpoint: String,
     pub reputation_api_timeout_secs: u64,
     pub score_verification_tolerance: f64,
+    /// Reputation floors below which bids and jobs are refused
+    #[serde(default)]
+    pub min_reputation: MinReputation,
+    /// How nodes whose reputation cannot be fetched are treated
+    #[serde(default)]
+    pub reputation_fallback: ReputationFallback,
+}
+
+/// Treatment of a node whose reputation profile could not be fetched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ReputationFallback {
+    /// Refuse the node's bids and jobs
+    #[default]
+    Reject,
+    /// Treat the node as having this score (0-100)
+    AssumeScore(f64),
+}
+
+/// Minimum reputation score (0-100) required for each job priority class.
+///
+/// Keeping the low-priority floor below the others lets nodes under the
+/// floor for important work build reputation through low-stakes jobs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReputationFloor {
+    pub low: f64,
+    pub medium: f64,
+    pub high: f64,
+    pub critical: f64,
+}
+
+impl ReputationFloor {
+    /// The same floor for every priority class
+    pub fn uniform(score: f64) -> Self {
+        Self {
+            low: score,
+            medium: score,
+            high: score,
+            critical: score,
+        }
+    }
+
+    pub fn for_priority(&self, priority: JobPriority) -> f64 {
+        match priority {
+            JobPriority::Low => self.low,
+            JobPriority::Medium => self.medium,
+            JobPriority::High => self.high,
+            JobPriority::Critical => self.critical,
+        }
+    }
+}
+
+impl Default for ReputationFloor {
+    fn default() -> Self {
+        Self::uniform(0.0)
+    }
+}
+
+/// Reputation floors for the two sides of a job
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MinReputation {
+    /// Floor a node must meet for its bid to be accepted
+    #[serde(default)]
+    pub executor: ReputationFloor,
+    /// Floor a submitter must meet for its jobs to be taken on; `None`
+    /// accepts jobs from any submitter
+    #[serde(default)]
+    pub submitter: Option<ReputationFloor>,
+}
+
+impl BidEvaluatorConfig {
+    /// Fail with `MeshError::Unauthorized` if `profile` is below the executor
+    /// floor for `priority`
+    pub fn check_executor_reputation(
+        &self,
+        profile: &ReputationProfile,
+        priority: JobPriority,
+    ) -> Result<(), MeshError> {
+        check_floor("executor", &self.min_reputation.executor, profile, priority)
+    }
+
+    /// Fail with `MeshError::Unauthorized` if a submitter floor is configured
+    /// and `profile` is below it for `priority`
+    pub fn check_submitter_reputation(
+        &self,
+        profile: &ReputationProfile,
+        priority: JobPriority,
+    ) -> Result<(), MeshError> {
+        match &self.min_reputation.submitter {
+            Some(floor) => check_floor("submitter", floor, profile, priority),
+            None => Ok(()),
+        }
+    }
+
+    /// Profile to use for `node_id` when its reputation could not be fetched,
+    /// or `None` if such nodes are refused
+    pub fn fallback_profile(&self, node_id: &str) -> Option<ReputationProfile> {
+        match self.reputation_fallback {
+            ReputationFallback::Reject => None,
+            ReputationFallback::AssumeScore(score) => Some(ReputationProfile {
+                computed_score: score,
+                ..neutral_profile(node_id)
+            }),
+        }
+    }
+}
+
+fn check_floor(
+    role: &str,
+    floor: &ReputationFloor,
+    profile: &ReputationProfile,
+    priority: JobPriority,
+) -> Result<(), MeshError> {
+    let required = floor.for_priority(priority);
+    if profile.computed_score >= required {
+        Ok(())
+    } else {
+        Err(MeshError::Unauthorized(format!(
+            "{} {} has reputation {:.2}, below the {:.2} required for {:?} priority jobs",
+            role, profile.node_id, profile.computed_score, required, priority
+        )))
+    }
+}
+
+/// Profile of a node with no history and the neutral score of 50
+pub fn neutral_profile(node_id: &str) -> ReputationProfile {
+    ReputationProfile {
+        node_id: node_id.to_string(),
+        last_updated: chrono::Utc::now(),
+        total_jobs: 0,
+        successful_jobs: 0,
+        failed_jobs: 0,
+        jobs_on_time: 0,
+        jobs_late: 0,
+        average_execution_ms: None,
+        average_bid_accuracy: None,
+        dishonesty_events: 0,
+        endorsements: vec![],
+        current_stake: None,
+        computed_score: 50.0, // Neutral score
+        latest_anchor_cid: None,
+    }
 }
 
 impl Default for BidEvaluatorConfig {
@@ -32,6 +175,8 @@ impl Default for BidEvaluatorConfig {
             reputation_api_endpoint: "http://localhost:8080/reputation/profiles".to_string(),
             reputation_api_timeout_secs: DEFAULT_REPUTATION_API_TIMEOUT_SECS,
             score_verification_tolerance: DEFAULT_REPUTATION_SCORE_TOLERANCE,
+            min_reputation: MinReputation::default(),
+            reputation_fallback: ReputationFallback::default(),
         }
     }
 }
@@ -40,13 +185,37 @@ impl Default for BidEvaluatorConfig {
 pub trait ReputationClient {
     async fn fetch_profile(&self, did: &str) -> Result<ReputationProfile>;
     fn verify_reported_score(&self, profile: &ReputationProfile, reported: u32) -> bool;
+
+    /// Weighted score of a bid from the node behind `profile`.
+    ///
+    /// `normalized_price` runs from 0 (cheapest) to 1 and `resource_match`
+    /// from 0 to 1 (best); reputation and timeliness come from `profile`.
     fn calculate_bid_score(
         &self,
         config: &BidEvaluatorConfig,
         profile: &ReputationProfile,
         normalized_price: f64,
         resource_match: f64,
-    ) -> f64;
+    ) -> f64 {
+        // Extract parameters from the profile
+        let reputation_score = profile.computed_score / 100.0;
+
+        // Calculate timeliness score - avoid division by zero
+        let timeliness_score = if profile.successful_jobs > 0 {
+            profile.jobs_on_time as f64 / profile.successful_jobs as f64
+        } else {
+            0.5 // Default value if no successful jobs
+        };
+
+        // Calculate the weighted score
+        let price_component = config.weight_price * (1.0 - normalized_price);
+        let resource_component = config.weight_resources * resource_match;
+        let reputation_component = config.weight_reputation * reputation_score;
+        let timeliness_component = config.weight_timeliness * timeliness_score;
+
+        // Sum all components for total score
+        price_component + resource_component + reputation_component + timeliness_component
+    }
 }
 
 pub struct DefaultReputationClient {
@@ -109,33 +278,6 @@ impl ReputationClient for DefaultReputationClient {
 
         difference <= tolerance
     }
-
-    fn calculate_bid_score(
-        &self,
-        config: &BidEvaluatorConfig,
-        profile: &ReputationProfile,
-        normalized_price: f64,
-        resource_match: f64,
-    ) -> f64 {
-        // Extract parameters from the profile
-        let reputation_score = profile.computed_score / 100.0;
-
-        // Calculate timeliness score - avoid division by zero
-        let timeliness_score = if profile.successful_jobs > 0 {
-            profile.jobs_on_time as f64 / profile.successful_jobs as f64
-        } else {
-            0.5 // Default value if no successful jobs
-        };
-
-        // Calculate the weighted score
-        let price_component = config.weight_price * (1.0 - normalized_price);
-        let resource_component = config.weight_resources * resource_match;
-        let reputation_component = config.weight_reputation * reputation_score;
-        let timeliness_component = config.weight_timeliness * timeliness_score;
-
-        // Sum all components for total score
-        price_component + resource_component + reputation_component + timeliness_component
-    }
 }
 
 // Helper function to load bid evaluator config from CCL policy
@@ -282,4 +424,58 @@ mod tests {
             "High reputation should score better despite price disadvantage"
         );
     }
+
+    #[test]
+    fn test_min_reputation_per_priority() {
+        let config = BidEvaluatorConfig {
+            min_reputation: MinReputation {
+                executor: ReputationFloor {
+                    low: 0.0,
+                    ..ReputationFloor::uniform(60.0)
+                },
+                submitter: Some(ReputationFloor::uniform(40.0)),
+            },
+            ..BidEvaluatorConfig::default()
+        };
+        let newcomer = ReputationProfile {
+            computed_score: 30.0,
+            ..neutral_profile("did:key:newcomer")
+        };
+
+        // Below-floor executors can still take low-stakes work
+        assert!(config
+            .check_executor_reputation(&newcomer, JobPriority::Low)
+            .is_ok());
+        match config.check_executor_reputation(&newcomer, JobPriority::High) {
+            Err(MeshError::Unauthorized(msg)) => {
+                assert!(msg.contains("30.00"), "missing actual score: {}", msg);
+                assert!(msg.contains("60.00"), "missing required score: {}", msg);
+            }
+            other => panic!("expected Unauthorized, got {:?}", other),
+        }
+        assert!(matches!(
+            config.check_submitter_reputation(&newcomer, JobPriority::Low),
+            Err(MeshError::Unauthorized(_))
+        ));
+
+        // No submitter floor by default
+        assert!(BidEvaluatorConfig::default()
+            .check_submitter_reputation(&newcomer, JobPriority::Critical)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_unreachable_reputation_is_rejected_by_default() {
+        assert!(BidEvaluatorConfig::default()
+            .fallback_profile("did:key:unknown")
+            .is_none());
+
+        let config = BidEvaluatorConfig {
+            reputation_fallback: ReputationFallback::AssumeScore(20.0),
+            ..BidEvaluatorConfig::default()
+        };
+        let profile = config.fallback_profile("did:key:unknown").unwrap();
+        assert_eq!(profile.node_id, "did:key:unknown");
+        assert_eq!(profile.computed_score, 20.0);
+    }
 }
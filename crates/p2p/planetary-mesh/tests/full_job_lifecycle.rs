@@ -185,6 +185,8 @@ async fn test_full_job_lifecycle() {
         stages: None,
         workflow_type: icn_types::mesh::WorkflowType::SingleWasmModule,
         trust_requirements: None,
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
     let job_to_announce = MeshJob {
         job_id: job_id.clone(),
@@ -194,6 +196,10 @@ async fn test_full_job_lifecycle() {
             originator_did.clone(),
         )),
         submission_timestamp: Utc::now().timestamp(),
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
 
     // Clone Arcs for state checking
@@ -270,6 +276,8 @@ async fn test_full_job_lifecycle() {
         stages: None,
         workflow_type: icn_types::mesh::WorkflowType::SingleWasmModule,
         trust_requirements: None,
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
     let job_s1_to_announce = MeshJob {
         job_id: job_s1_id.clone(),
@@ -279,6 +287,10 @@ async fn test_full_job_lifecycle() {
             originator_did.clone(),
         )),
         submission_timestamp: Utc::now().timestamp(),
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
     let job_s1_price = 50;
 
@@ -326,6 +338,8 @@ async fn test_full_job_lifecycle() {
         stages: None,
         workflow_type: icn_types::mesh::WorkflowType::SingleWasmModule,
         trust_requirements: None,
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
     let job_s2_to_announce = MeshJob {
         job_id: job_s2_id.clone(),
@@ -335,6 +349,10 @@ async fn test_full_job_lifecycle() {
             originator_did.clone(),
         )),
         submission_timestamp: Utc::now().timestamp(),
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
     let job_s2_price = 60;
 
@@ -407,6 +425,7 @@ async fn test_full_job_lifecycle() {
         stages: None,
         workflow_type: icn_types::mesh::WorkflowType::SingleWasmModule,
         trust_requirements: None,
+        deterministic: false,
     };
     let job_p1_to_announce = MeshJob {
         job_id: job_p1_id.clone(),
@@ -416,6 +435,10 @@ async fn test_full_job_lifecycle() {
             originator_did.clone(),
         )),
         submission_timestamp: Utc::now().timestamp(),
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
 
     test_utils::command_originator_to_announce_job(
@@ -629,6 +652,8 @@ async fn test_policy_rejects_all_bidders_due_to_low_reputation() {
         workflow_type: icn_types::mesh::WorkflowType::SingleWasmModule,
         ccl_cid: None,
         trust_requirements: None,
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
     let job_to_announce = MeshJob {
         job_id: job_id.clone(),
@@ -638,6 +663,10 @@ async fn test_policy_rejects_all_bidders_due_to_low_reputation() {
             originator_did.clone(),
         )),
         submission_timestamp: Utc::now().timestamp(),
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
 
     // Clone Arcs for state checking
@@ -877,6 +906,8 @@ async fn test_policy_rejects_all_bidders_due_to_high_price() {
         workflow_type: icn_types::mesh::WorkflowType::SingleWasmModule,
         ccl_cid: None,
         trust_requirements: None,
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
     let job_to_announce = MeshJob {
         job_id: job_id.clone(),
@@ -886,6 +917,10 @@ async fn test_policy_rejects_all_bidders_due_to_high_price() {
             originator_did.clone(),
         )),
         submission_timestamp: Utc::now().timestamp(),
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
 
     // Clone Arcs for state checking
@@ -1510,6 +1545,8 @@ async fn test_policy_filters_region_filter_enforced() {
         workflow_type: WorkflowType::SingleWasmModule,
         is_interactive: false,
         expected_output_schema_cid: None,
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
     let mesh_job = MeshJob {
         job_id: job_id.clone(),
@@ -1519,6 +1556,10 @@ async fn test_policy_filters_region_filter_enforced() {
             originator_did.clone(),
         )),
         submission_timestamp: Utc::now().timestamp(),
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
 
     // Set mock reputations high for everyone so it's not a factor
@@ -151,6 +151,10 @@ async fn test_execution_receipt_announcement_roundtrip() {
         },
         originator_did: executor_did.clone(),
         submission_timestamp: chrono::Utc::now().timestamp() as u64,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
         originator_org_scope: None,
     };
 
@@ -287,6 +291,10 @@ async fn test_receipt_announcement_anchoring_failure() {
         },
         originator_did: executor_did.clone(),
         submission_timestamp: chrono::Utc::now().timestamp() as u64,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
         originator_org_scope: None,
     };
 
@@ -0,0 +1,224 @@
+//! Host ABI versioning.
+//!
+//! Code generators embed the ABI version they targeted in a custom section named
+//! [`ICN_ABI_VERSION_SECTION`] whose payload is the version as a little-endian `u32`.
+//! The runtime reads it back with [`read_abi_version`] and refuses modules built
+//! against a different ABI.
+
+use crate::HostAbiError;
+
+/// Version of the host ABI exposed by this crate.
+///
+/// Bump it in the same change as anything a compiled guest could observe:
+/// - adding, removing or renaming an entry of [`HOST_IMPORTS`];
+/// - changing a host function's parameters or results;
+/// - changing what a host function's arguments, return values or status codes mean.
+///
+/// Fixes that make a host function behave as already documented do not bump it.
+/// Each bump adds a line to the history below.
+///
+/// History: 8: mesh job submission, 9: P2P receive status codes, 10: string
+/// out-params return the negative required size, 11: `host_get_mana_balance`,
+/// 12: resource codes 5 and 6 mean `Storage` and `Bandwidth` instead of `Token`.
+pub const ICN_HOST_ABI_VERSION: u32 = 12;
+
+/// Host functions, as `(module, name)`, that the runtime links for modules
+/// targeting [`ICN_HOST_ABI_VERSION`].
+///
+/// The runtime's linker is tested against this list and the list is pinned to
+/// the version, so an import cannot change without a bump.
+pub const HOST_IMPORTS: &[(&str, &str)] = &[
+    ("icn_host", "account_get_mana"),
+    ("icn_host", "account_spend_mana"),
+    ("icn_host", "anchor_data"),
+    ("icn_host", "anchor_receipt"),
+    ("icn_host", "get_job_id"),
+    ("icn_host", "host_interactive_peek_input_len"),
+    ("icn_host", "host_interactive_prompt_for_input"),
+    ("icn_host", "host_job_get_initial_input_cid"),
+    ("icn_host", "host_job_is_interactive"),
+    ("icn_host", "host_job_report_progress"),
+    ("icn_host", "host_submit_mesh_job_old"),
+    ("icn_host", "host_workflow_complete_current_stage"),
+    ("icn_host", "host_workflow_get_current_stage_id"),
+    ("icn_host", "host_workflow_get_current_stage_index"),
+    ("icn_host", "host_workflow_get_current_stage_input_cid"),
+    ("icn_host", "host_workflow_get_type"),
+    ("icn_host", "interactive_recv"),
+    ("icn_host", "interactive_send"),
+    ("icn_host", "log_message"),
+    ("icn_host", "read_data"),
+    ("icn_host_new", "host_anchor_data"),
+    ("icn_host_new", "host_begin_section"),
+    ("icn_host_new", "host_create_proposal"),
+    ("icn_host_new", "host_else_handler"),
+    ("icn_host_new", "host_end_section"),
+    ("icn_host_new", "host_endif_handler"),
+    ("icn_host_new", "host_generic_call"),
+    ("icn_host_new", "host_get_mana_balance"),
+    ("icn_host_new", "host_if_condition_eval"),
+    ("icn_host_new", "host_log_debug_deprecated"),
+    ("icn_host_new", "host_log_todo"),
+    ("icn_host_new", "host_mint_token"),
+    ("icn_host_new", "host_on_event"),
+    ("icn_host_new", "host_p2p_receive_message"),
+    ("icn_host_new", "host_range_check"),
+    ("icn_host_new", "host_set_property"),
+    ("icn_host_new", "host_submit_mesh_job"),
+    ("icn_host_new", "host_transfer_token"),
+    ("icn_host_new", "host_use_resource"),
+];
+
+/// Name of the custom section that carries a module's targeted ABI version.
+pub const ICN_ABI_VERSION_SECTION: &str = "icn_abi_version";
+
+const WASM_HEADER: &[u8] = b"\0asm\x01\0\0\0";
+const CUSTOM_SECTION_ID: u8 = 0;
+
+/// Payload of the [`ICN_ABI_VERSION_SECTION`] custom section for `version`.
+pub fn abi_version_section_data(version: u32) -> [u8; 4] {
+    version.to_le_bytes()
+}
+
+/// Whether a module targeting ABI `version` can run against this host.
+pub fn is_abi_version_compatible(version: u32) -> bool {
+    version == ICN_HOST_ABI_VERSION
+}
+
+/// Read the ABI version embedded in `wasm`.
+///
+/// Returns `Ok(None)` for modules without an [`ICN_ABI_VERSION_SECTION`] section, e.g.
+/// hand-written modules that predate versioning.
+pub fn read_abi_version(wasm: &[u8]) -> Result<Option<u32>, HostAbiError> {
+    let mut pos = WASM_HEADER.len();
+    if wasm.len() < pos || &wasm[..pos] != WASM_HEADER {
+        return Err(HostAbiError::DataEncodingError(
+            "not a WASM module (bad header)".to_string(),
+        ));
+    }
+
+    while pos < wasm.len() {
+        let id = wasm[pos];
+        pos += 1;
+        let size = read_leb_u32(wasm, &mut pos)? as usize;
+        let end = pos
+            .checked_add(size)
+            .filter(|end| *end <= wasm.len())
+            .ok_or_else(|| {
+                HostAbiError::DataEncodingError("WASM section runs past end of module".to_string())
+            })?;
+
+        if id == CUSTOM_SECTION_ID {
+            let mut name_pos = pos;
+            let name_len = read_leb_u32(&wasm[..end], &mut name_pos)? as usize;
+            let name_end = name_pos.checked_add(name_len).filter(|e| *e <= end).ok_or_else(|| {
+                HostAbiError::DataEncodingError("custom section name runs past section".to_string())
+            })?;
+            if &wasm[name_pos..name_end] == ICN_ABI_VERSION_SECTION.as_bytes() {
+                let payload: [u8; 4] = wasm[name_end..end].try_into().map_err(|_| {
+                    HostAbiError::DataEncodingError(format!(
+                        "{} section must hold exactly 4 bytes, found {}",
+                        ICN_ABI_VERSION_SECTION,
+                        end - name_end
+                    ))
+                })?;
+                return Ok(Some(u32::from_le_bytes(payload)));
+            }
+        }
+        pos = end;
+    }
+
+    Ok(None)
+}
+
+fn read_leb_u32(bytes: &[u8], pos: &mut usize) -> Result<u32, HostAbiError> {
+    let mut result: u32 = 0;
+    for shift in (0..35).step_by(7) {
+        let byte = *bytes.get(*pos).ok_or_else(|| {
+            HostAbiError::DataEncodingError("truncated LEB128 integer".to_string())
+        })?;
+        *pos += 1;
+        result |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err(HostAbiError::DataEncodingError(
+        "LEB128 integer too long".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module_with_custom(name: &str, payload: &[u8]) -> Vec<u8> {
+        let mut body = vec![name.len() as u8];
+        body.extend_from_slice(name.as_bytes());
+        body.extend_from_slice(payload);
+
+        let mut wasm = WASM_HEADER.to_vec();
+        wasm.push(CUSTOM_SECTION_ID);
+        wasm.push(body.len() as u8);
+        wasm.extend(body);
+        wasm
+    }
+
+    #[test]
+    fn reads_embedded_version() {
+        let wasm = module_with_custom(
+            ICN_ABI_VERSION_SECTION,
+            &abi_version_section_data(ICN_HOST_ABI_VERSION),
+        );
+        assert_eq!(read_abi_version(&wasm).unwrap(), Some(ICN_HOST_ABI_VERSION));
+        assert!(is_abi_version_compatible(ICN_HOST_ABI_VERSION));
+        assert!(!is_abi_version_compatible(ICN_HOST_ABI_VERSION - 1));
+    }
+
+    /// FNV-1a over the sorted imports, so the pin below does not depend on their order.
+    fn imports_fingerprint() -> u64 {
+        let mut imports = HOST_IMPORTS.to_vec();
+        imports.sort();
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for (module, name) in imports {
+            let parts: [&[u8]; 4] = [module.as_bytes(), b"\0", name.as_bytes(), b"\n"];
+            for byte in parts.concat() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        hash
+    }
+
+    /// Changing `HOST_IMPORTS` fails this until the version is bumped and the
+    /// pin updated alongside it.
+    #[test]
+    fn host_imports_are_pinned_to_the_abi_version() {
+        assert_eq!(
+            (
+                ICN_HOST_ABI_VERSION,
+                HOST_IMPORTS.len(),
+                imports_fingerprint()
+            ),
+            (12, 39, 0x6a31_1ece_180a_bc0d),
+            "HOST_IMPORTS changed: bump ICN_HOST_ABI_VERSION and update this pin"
+        );
+    }
+
+    #[test]
+    fn unversioned_module_has_no_version() {
+        assert_eq!(read_abi_version(WASM_HEADER).unwrap(), None);
+        let wasm = module_with_custom("name", b"x");
+        assert_eq!(read_abi_version(&wasm).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(read_abi_version(b"not wasm").is_err());
+        let wasm = module_with_custom(ICN_ABI_VERSION_SECTION, &[1, 2]);
+        assert!(read_abi_version(&wasm).is_err());
+        let mut truncated = WASM_HEADER.to_vec();
+        truncated.extend_from_slice(&[CUSTOM_SECTION_ID, 10, 1]);
+        assert!(read_abi_version(&truncated).is_err());
+    }
+}
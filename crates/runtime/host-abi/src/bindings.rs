@@ -32,5 +32,3 @@ extern "C" {
         job_id_buffer_len: i32,
     ) -> i32;
 }
-
-pub const ICN_HOST_ABI_VERSION: u32 = 8;
@@ -47,6 +47,10 @@ pub enum HostAbiError {
     InvalidParameter(String),
     #[error("Resource management error: {0}")]
     ResourceManagementError(String),
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Job submission depth exceeded: {0}")]
+    SubmissionDepthExceeded(String),
     // Consider adding other specific errors if needed, e.g.:
     // #[error("WASM guest module did not export a 'memory'")]
     // MissingMemory,
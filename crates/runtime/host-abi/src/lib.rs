@@ -6,7 +6,11 @@ pub use bindings::*;
 pub mod error;
 pub use error::HostAbiError;
 
-// pub const ICN_HOST_ABI_VERSION: u32 = 8; // bump from 7 → 8 for mesh job submission ABI change
+pub mod abi_version;
+pub use abi_version::{
+    abi_version_section_data, is_abi_version_compatible, read_abi_version, HOST_IMPORTS,
+    ICN_ABI_VERSION_SECTION, ICN_HOST_ABI_VERSION,
+};
 
 // InterCooperative Network (ICN) - Host ABI Definitions
 // This crate defines the Application Binary Interface (ABI) that WASM modules (e.g., CCL contracts)
@@ -139,6 +143,10 @@ pub const INLINE_PAYLOAD_MAX_SIZE: usize = 1024; // 1KB, example value
 /// Max number of bytes that can be peeked from interactive input buffer
 pub const MAX_INTERACTIVE_INPUT_BUFFER_PEEK: usize = 256;
 
+/// Returned by `host_p2p_receive_message` when no message arrived within the timeout.
+/// Other negative returns are the size a buffer needs to hold the next message.
+pub const P2P_RECEIVE_TIMEOUT: i32 = i32::MIN;
+
 /// Trait defining the Host ABI functions callable from WASM modules.
 ///
 /// # Error Handling
@@ -149,7 +157,13 @@ pub const MAX_INTERACTIVE_INPUT_BUFFER_PEEK: usize = 256;
 /// CIDs and other string-like data are passed as `*const c_char` (null-terminated C strings).
 /// Buffers provided by WASM for host functions to write into should be of adequate size.
 /// The host will write a null terminator if the buffer is large enough.
-/// Functions returning string data will indicate the required buffer size if the provided one is too small.
+/// Host functions returning string data follow the [`copy_string_to_c_buf`] convention:
+/// on success they return the number of bytes written, and if the buffer is too small
+/// they return the negative required size (including the null terminator) without
+/// writing anything. The runtime applies it to `host_workflow_get_current_stage_id`,
+/// `host_workflow_get_current_stage_input_cid` and the job ID out-param of
+/// `host_submit_mesh_job` (which then submits nothing). Implementations of this trait
+/// should return strings through [`copy_string_to_c_buf`].
 ///
 /// # Binary Data Handling
 /// Binary data is passed using `AbiBytes` (pointer and length).
@@ -231,6 +245,9 @@ pub trait HostEnvironment: Send + Sync + Clone + 'static {
         data_ptr: *const u8,
         data_len: u32,
     ) -> i32;
+    /// Waits up to `timeout_ms` for a message (`0` polls without waiting) and returns
+    /// the number of bytes written, [`P2P_RECEIVE_TIMEOUT`] if none arrived, or the
+    /// negative size of a message that does not fit `buffer_len`, which stays queued.
     async fn p2p_receive_message(
         &self,
         buffer_ptr: *mut u8,
@@ -249,26 +266,30 @@ pub trait HostEnvironment: Send + Sync + Clone + 'static {
     ) -> i32;
 }
 
+/// Size of the buffer, in bytes, needed to receive `rust_str` as a null-terminated C string.
+pub fn required_buffer_size(rust_str: &str) -> u32 {
+    rust_str.len() as u32 + 1
+}
+
 /// Helper to safely copy a Rust string into a C buffer provided by WASM.
-/// Returns the number of bytes written (excluding null terminator) or a HostAbiError code.
+///
+/// Returns the number of bytes written (excluding the null terminator). If the buffer
+/// is null or too small nothing is written and the negative of
+/// [`required_buffer_size`] is returned instead, so a guest can pass a zero-length
+/// buffer to query the size, allocate, and call again.
 pub fn copy_string_to_c_buf(rust_str: &str, c_buf: *mut c_char, c_buf_len: u32) -> i32 {
-    if c_buf.is_null() || c_buf_len == 0 {
-        return HostAbiError::InvalidArguments as i32;
+    let required = required_buffer_size(rust_str);
+    if c_buf.is_null() || required > c_buf_len {
+        return -(required as i32);
     }
     let bytes = rust_str.as_bytes();
-    let len_to_write = bytes.len();
-
-    if (len_to_write + 1) > c_buf_len as usize {
-        // +1 for null terminator
-        return HostAbiError::BufferTooSmall as i32;
-    }
 
     unsafe {
-        ptr::copy_nonoverlapping(bytes.as_ptr(), c_buf as *mut u8, len_to_write);
+        ptr::copy_nonoverlapping(bytes.as_ptr(), c_buf as *mut u8, bytes.len());
         // Write null terminator
-        *(c_buf as *mut u8).add(len_to_write) = 0;
+        *(c_buf as *mut u8).add(bytes.len()) = 0;
     }
-    len_to_write as i32
+    bytes.len() as i32
 }
 
 /// Helper for converting a C string (UTF-8 assumed) from WASM memory to a Rust String.
@@ -432,6 +453,22 @@ pub trait MeshHostAbi<S: Send + Sync + 'static>: Send + Sync {
         amount: u64,
     ) -> Result<i32, HostAbiError>;
 
+    async fn host_get_mana_balance( // Read a DID's current mana
+        &self,
+        mut caller: Caller<'_, S>,
+        did_ptr: u32, // String: DID whose balance is read
+        did_len: u32,
+        out_balance_ptr: u32, // Receives the balance as a little-endian u64
+    ) -> Result<i32, HostAbiError>; // 0 on success
+
+    async fn host_p2p_receive_message( // Take the next message sent to this execution
+        &self,
+        mut caller: Caller<'_, S>,
+        buffer_ptr: u32, // Receives the message bytes
+        buffer_len: u32,
+        timeout_ms: u32, // How long to wait for a message; 0 polls without waiting
+    ) -> Result<i32, HostAbiError>; // Bytes written; P2P_RECEIVE_TIMEOUT if none arrived, -(size needed) leaves it queued
+
 
     async fn host_transfer_token(
         &self,
@@ -452,5 +489,34 @@ pub trait MeshHostAbi<S: Send + Sync + 'static>: Send + Sync {
         cbor_payload_len: u32,
         job_id_buffer_ptr: u32,   // Pointer to a buffer to write the job ID string
         job_id_buffer_len: u32, // Length of the job ID buffer
-    ) -> Result<i32, HostAbiError>; // Returns length of written job ID or error
-}
\ No newline at end of file
+    ) -> Result<i32, HostAbiError>; // Length of the written job ID, or -(size needed) if it does not fit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_string_with_null_terminator() {
+        let mut buf = [0x7f as c_char; 8];
+        let written = copy_string_to_c_buf("job-1", buf.as_mut_ptr(), buf.len() as u32);
+        assert_eq!(written, 5);
+        let copied = unsafe { string_from_c_str(buf.as_ptr()) }.unwrap();
+        assert_eq!(copied, "job-1");
+    }
+
+    #[test]
+    fn too_small_buffer_returns_negative_required_size() {
+        let mut buf = [0x7f as c_char; 5];
+        assert_eq!(required_buffer_size("job-1"), 6);
+        let result = copy_string_to_c_buf("job-1", buf.as_mut_ptr(), buf.len() as u32);
+        assert_eq!(result, -6);
+        assert!(
+            buf.iter().all(|&b| b == 0x7f),
+            "buffer must be left untouched"
+        );
+
+        // A zero-length query reports the size without needing a buffer
+        assert_eq!(copy_string_to_c_buf("job-1", ptr::null_mut(), 0), -6);
+    }
+}
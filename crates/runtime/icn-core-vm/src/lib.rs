@@ -1,11 +1,19 @@
 // Core-VM: WebAssembly Virtual Machine for ICN runtime
-use anyhow::{anyhow, bail, Result};
+pub mod opcode_policy;
+pub mod preflight;
+pub mod trace;
+
+pub use opcode_policy::{OpcodeClass, OpcodePolicy};
+pub use trace::{ExecutionTrace, HostCallRecord, TraceValue};
+
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
 use wasmtime::{
-    AsContextMut, Caller, Config, Engine, Extern, Func, FuncType, Instance, Linker, Module,
-    OptLevel, Store, Val, ValType,
+    AsContextMut, Caller, Config, Engine, Extern, Func, FuncType, Instance, Linker, Memory,
+    Module, OptLevel, Store, Val, ValType,
 };
 
 /// Error types specific to the Cooperative VM
@@ -27,12 +35,37 @@ pub enum CoVmError {
     FuelExhausted,
 }
 
+/// Error from [`CoVm::execute_with_partial_results`]
+#[derive(Error, Debug)]
+pub enum PartialExecutionError {
+    /// The module ran out of fuel; `context` holds the logs, anchored CIDs and
+    /// metrics accumulated up to that point.
+    #[error("Fuel exhausted")]
+    FuelExhausted { context: HostContext },
+
+    /// The module trapped; `context` holds what it logged, anchored and used
+    /// before the trap.
+    #[error("{error}")]
+    Trapped {
+        error: anyhow::Error,
+        context: HostContext,
+    },
+
+    /// The module could not be run at all, so there are no partial results
+    #[error(transparent)]
+    Failed(#[from] anyhow::Error),
+}
+
 /// Metrics collected during execution
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExecutionMetrics {
     /// Number of host calls made
     pub host_calls: u64,
 
+    /// Number of host calls made, by host function name
+    #[serde(default)]
+    pub host_calls_by_function: HashMap<String, u64>,
+
     /// Total bytes read/written through host functions
     pub io_bytes: u64,
 
@@ -42,6 +75,10 @@ pub struct ExecutionMetrics {
     /// Number of job submissions
     pub job_submissions_count: usize,
 
+    /// Fuel consumed by the WASM instance
+    #[serde(default)]
+    pub fuel_used: u64,
+
     /// Optional mana cost computed post-execution
     pub mana_cost: Option<u64>,
 }
@@ -55,6 +92,11 @@ pub struct ResourceLimits {
     /// Maximum number of host calls
     pub max_host_calls: u32,
 
+    /// Optional per-function caps (e.g. `"anchor" => 10`), applied under
+    /// `max_host_calls`. Functions not listed are only bound by the aggregate.
+    #[serde(default)]
+    pub max_host_calls_per_function: HashMap<String, u32>,
+
     /// Maximum total bytes read/written through host functions
     pub max_io_bytes: u64,
 
@@ -63,6 +105,15 @@ pub struct ResourceLimits {
 
     /// Maximum number of job submissions
     pub max_job_submissions: usize,
+
+    /// Maximum length of a chain of jobs submitting jobs; a job this many
+    /// submissions below its root may not submit any more
+    #[serde(default = "default_max_submission_depth")]
+    pub max_submission_depth: u32,
+}
+
+fn default_max_submission_depth() -> u32 {
+    8
 }
 
 impl Default for ResourceLimits {
@@ -70,13 +121,80 @@ impl Default for ResourceLimits {
         Self {
             max_fuel: 10_000_000, // Default reasonable limit
             max_host_calls: 1000,
+            max_host_calls_per_function: HashMap::new(),
             max_io_bytes: 10_000_000,  // Default reasonable limit
             max_anchored_cids: 1000,   // Default reasonable limit
             max_job_submissions: 1000, // Default reasonable limit
+            max_submission_depth: default_max_submission_depth(),
         }
     }
 }
 
+impl ResourceLimits {
+    /// Cap calls to the host function `name`
+    pub fn with_host_call_limit(mut self, name: impl Into<String>, max_calls: u32) -> Self {
+        self.max_host_calls_per_function.insert(name.into(), max_calls);
+        self
+    }
+
+    /// Count one call to host function `name`, failing if it exceeds either
+    /// that function's cap or the aggregate `max_host_calls`.
+    fn record_host_call(&self, metrics: &mut ExecutionMetrics, name: &str) -> Result<(), CoVmError> {
+        metrics.host_calls += 1;
+        let calls = metrics
+            .host_calls_by_function
+            .entry(name.to_string())
+            .or_insert(0);
+        *calls += 1;
+
+        if let Some(max) = self.max_host_calls_per_function.get(name) {
+            if *calls > u64::from(*max) {
+                return Err(CoVmError::ResourceLimitExceeded(format!(
+                    "host function '{}' called more than {} times",
+                    name, max
+                )));
+            }
+        }
+        if metrics.host_calls > u64::from(self.max_host_calls) {
+            return Err(CoVmError::ResourceLimitExceeded(format!(
+                "more than {} host calls (last call: '{}')",
+                self.max_host_calls, name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Count `bytes` read from guest memory by host function `name`, failing
+    /// if the total exceeds `max_io_bytes`. Host functions charge a read before
+    /// copying it, so an oversized length never reaches guest memory.
+    fn record_io_bytes(
+        &self,
+        metrics: &mut ExecutionMetrics,
+        name: &str,
+        bytes: u64,
+    ) -> Result<(), CoVmError> {
+        metrics.io_bytes = metrics.io_bytes.saturating_add(bytes);
+        if metrics.io_bytes > self.max_io_bytes {
+            return Err(CoVmError::ResourceLimitExceeded(format!(
+                "max_io_bytes: more than {} bytes of host I/O (last call: '{}')",
+                self.max_io_bytes, name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fail if anchoring one more CID on top of `anchored` would exceed `max_anchored_cids`
+    fn check_anchor(&self, anchored: usize) -> Result<(), CoVmError> {
+        if anchored >= self.max_anchored_cids {
+            return Err(CoVmError::ResourceLimitExceeded(format!(
+                "max_anchored_cids: more than {} anchored CIDs",
+                self.max_anchored_cids
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Host context for WASM execution
 #[derive(Debug, Clone)]
 pub struct HostContext {
@@ -100,6 +218,13 @@ pub struct HostContext {
 
     /// Optional organization context: community ID
     pub community_id: Option<icn_types::org::CommunityId>,
+
+    /// Host-call trace, present only when tracing is enabled
+    pub trace: Option<Arc<Mutex<ExecutionTrace>>>,
+
+    /// How many submissions below its root job the executing job is; it may
+    /// not submit jobs once this reaches `max_submission_depth`
+    pub submission_depth: u32,
 }
 
 /// A job submission from a WASM module
@@ -131,6 +256,8 @@ impl Default for HostContext {
             job_submissions: Arc::new(Mutex::new(Vec::new())),
             coop_id: None,
             community_id: None,
+            trace: None,
+            submission_depth: 0,
         }
     }
 }
@@ -146,13 +273,48 @@ impl HostContext {
         self.community_id = community_id;
         self
     }
+
+    /// Run as a job `depth` submissions below its root job
+    pub fn with_submission_depth(mut self, depth: u32) -> Self {
+        self.submission_depth = depth;
+        self
+    }
+
+    /// Snapshot of the host calls recorded so far, if tracing is enabled
+    pub fn trace(&self) -> Option<ExecutionTrace> {
+        self.trace.as_ref().map(|trace| trace.lock().unwrap().clone())
+    }
+
+    /// Append a host call to the trace; `args` is only evaluated when tracing
+    fn trace_call(
+        &self,
+        function: &str,
+        args: impl FnOnce() -> Vec<TraceValue>,
+        result: Option<TraceValue>,
+    ) {
+        if let Some(trace) = &self.trace {
+            trace.lock().unwrap().calls.push(HostCallRecord {
+                function: function.to_string(),
+                args: args(),
+                result,
+            });
+        }
+    }
 }
 
+/// Memory export names tried after the configured one, covering the common
+/// toolchains (`memory` from rustc, clang and AssemblyScript)
+pub const MEMORY_EXPORT_FALLBACKS: [&str; 3] = ["memory", "mem", "__linear_memory"];
+
 /// The Cooperative Virtual Machine for executing governance WASM code
 #[derive(Clone)]
 pub struct CoVm {
     engine: Engine,
     limits: ResourceLimits,
+    opcode_policy: OpcodePolicy,
+    trace: bool,
+    memory_export: String,
+    deterministic: bool,
 }
 
 impl Default for CoVm {
@@ -164,15 +326,108 @@ impl Default for CoVm {
 impl CoVm {
     /// Create a new CoVM with specified resource limits
     pub fn new(limits: ResourceLimits) -> Self {
+        Self::with_engine(limits, false)
+    }
+
+    /// Create a CoVM whose executions replay bit-identically on any host.
+    ///
+    /// Compared to [`CoVm::new`], the engine config sets
+    /// `cranelift_nan_canonicalization(true)` and `relaxed_simd_deterministic(true)`,
+    /// and turns off `wasm_simd`, `wasm_relaxed_simd` and `wasm_threads`, so a
+    /// module using them fails to compile. [`OpcodePolicy::deterministic`]
+    /// additionally rejects floating point, SIMD and threads before a module is
+    /// compiled. Every execution starts with exactly `limits.max_fuel`, so
+    /// `fuel_used` is the same on every replay. Slower than [`CoVm::new`].
+    pub fn new_deterministic(limits: ResourceLimits) -> Self {
+        Self::with_engine(limits, true).with_opcode_policy(OpcodePolicy::deterministic())
+    }
+
+    fn with_engine(limits: ResourceLimits, deterministic: bool) -> Self {
         let mut config = Config::new();
         config.consume_fuel(true);
         config.wasm_multi_memory(true);
         config.wasm_reference_types(true);
         config.cranelift_opt_level(OptLevel::Speed);
+        if deterministic {
+            config.cranelift_nan_canonicalization(true);
+            config.relaxed_simd_deterministic(true);
+            config.wasm_relaxed_simd(false);
+            config.wasm_simd(false);
+            config.wasm_threads(false);
+        }
         let engine = Engine::new(&config).unwrap_or_else(|e| {
             panic!("Failed to create Wasmtime engine: {}", e);
         });
-        Self { engine, limits }
+        Self {
+            engine,
+            limits,
+            opcode_policy: OpcodePolicy::default(),
+            trace: false,
+            memory_export: MEMORY_EXPORT_FALLBACKS[0].to_string(),
+            deterministic,
+        }
+    }
+
+    /// Whether executions replay bit-identically: built by
+    /// [`CoVm::new_deterministic`] and still rejecting every nondeterministic
+    /// instruction class.
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+            && [
+                OpcodeClass::FloatingPoint,
+                OpcodeClass::Simd,
+                OpcodeClass::Threads,
+            ]
+            .into_iter()
+            .all(|class| self.opcode_policy.is_disallowed(class))
+    }
+
+    /// Name of the export host functions read guest memory through. Defaults
+    /// to `memory`; the [`MEMORY_EXPORT_FALLBACKS`] are tried if it is missing.
+    pub fn with_memory_export(mut self, name: impl Into<String>) -> Self {
+        self.memory_export = name.into();
+        self
+    }
+
+    /// The configured memory export name
+    pub fn memory_export(&self) -> &str {
+        &self.memory_export
+    }
+
+    /// The configured name followed by the fallbacks, without duplicates
+    fn memory_export_names(&self) -> Arc<[String]> {
+        std::iter::once(self.memory_export.as_str())
+            .chain(
+                MEMORY_EXPORT_FALLBACKS
+                    .into_iter()
+                    .filter(|name| *name != self.memory_export),
+            )
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Record every host call into the context's trace. Off by default.
+    pub fn with_trace(mut self, enabled: bool) -> Self {
+        self.trace = enabled;
+        self
+    }
+
+    /// Reject modules using instruction classes disallowed by `policy`
+    pub fn with_opcode_policy(mut self, policy: OpcodePolicy) -> Self {
+        self.opcode_policy = policy;
+        self
+    }
+
+    /// The instruction-set policy applied before execution
+    pub fn opcode_policy(&self) -> &OpcodePolicy {
+        &self.opcode_policy
+    }
+
+    /// Check the module against the configured instruction-set policy.
+    ///
+    /// Fails with `CoVmError::ExecutionError` naming the first disallowed instruction.
+    pub fn validate_opcodes(&self, wasm_bytes: &[u8]) -> Result<(), CoVmError> {
+        self.opcode_policy.validate(wasm_bytes)
     }
 
     /// Get a reference to the Wasmtime engine
@@ -180,11 +435,61 @@ impl CoVm {
         &self.engine
     }
 
+    /// Statically estimate the worst-case fuel the module's `_start` can consume.
+    ///
+    /// Returns `Ok(None)` if the module contains loops, recursion or indirect
+    /// calls on a reachable path. Callers can compare a `Some` bound against
+    /// their fuel limit to reject modules without executing them.
+    pub fn estimate_max_fuel(&self, wasm_bytes: &[u8]) -> Result<Option<u64>> {
+        preflight::estimate_max_fuel(wasm_bytes)
+    }
+
     /// Execute a WASM module with the provided context
     pub fn execute(&self, wasm_bytes: &[u8], context: HostContext) -> Result<HostContext> {
+        let (execution_result, final_host_context) = self.run(wasm_bytes, context)?;
+        execution_result.map(|_| final_host_context)
+    }
+
+    /// Execute a WASM module, keeping the host context if it fails part-way.
+    ///
+    /// Behaves like [`CoVm::execute`] except that fuel exhaustion returns
+    /// [`PartialExecutionError::FuelExhausted`] and any other trap
+    /// [`PartialExecutionError::Trapped`], both carrying everything the module
+    /// logged, anchored and submitted before it stopped.
+    pub fn execute_with_partial_results(
+        &self,
+        wasm_bytes: &[u8],
+        context: HostContext,
+    ) -> Result<HostContext, PartialExecutionError> {
+        let (execution_result, final_host_context) = self.run(wasm_bytes, context)?;
+        match execution_result {
+            Ok(()) => Ok(final_host_context),
+            Err(e) if matches!(e.downcast_ref::<CoVmError>(), Some(CoVmError::FuelExhausted)) => {
+                Err(PartialExecutionError::FuelExhausted {
+                    context: final_host_context,
+                })
+            }
+            Err(error) => Err(PartialExecutionError::Trapped {
+                error,
+                context: final_host_context,
+            }),
+        }
+    }
+
+    /// Compile, instantiate and run `_start`, returning the entrypoint result
+    /// together with the host context and its final metrics.
+    ///
+    /// The outer error covers failures before the module starts running.
+    fn run(&self, wasm_bytes: &[u8], context: HostContext) -> Result<(Result<()>, HostContext)> {
+        self.validate_opcodes(wasm_bytes)?;
+
         let module = Module::new(&self.engine, wasm_bytes)
             .map_err(|e| anyhow!("Failed to compile WASM module: {}", e))?;
 
+        let mut context = context;
+        if self.trace && context.trace.is_none() {
+            context.trace = Some(Arc::new(Mutex::new(ExecutionTrace::default())));
+        }
         let mut store = Store::new(&self.engine, context);
 
         let initial_fuel = self.limits.max_fuel;
@@ -212,22 +517,19 @@ impl CoVm {
         let execution_result = self.call_entrypoint(&mut store, &instance);
 
         let fuel_remaining = store.get_fuel().unwrap_or(0);
-        let _fuel_consumed = initial_fuel.saturating_sub(fuel_remaining);
+        let fuel_consumed = initial_fuel.saturating_sub(fuel_remaining);
 
         let anchored_cids_len = store.data().anchored_cids.lock().unwrap().len();
         let job_submissions_len = store.data().job_submissions.lock().unwrap().len();
 
-        store.data_mut().metrics.lock().unwrap().anchored_cids_count = anchored_cids_len;
-        store
-            .data_mut()
-            .metrics
-            .lock()
-            .unwrap()
-            .job_submissions_count = job_submissions_len;
-
-        let final_host_context = store.into_data();
+        {
+            let mut metrics = store.data_mut().metrics.lock().unwrap();
+            metrics.anchored_cids_count = anchored_cids_len;
+            metrics.job_submissions_count = job_submissions_len;
+            metrics.fuel_used = fuel_consumed;
+        }
 
-        execution_result.map(|_| final_host_context)
+        Ok((execution_result, store.into_data()))
     }
 
     /// Execute a WASM module with a provided linker and store
@@ -241,6 +543,8 @@ impl CoVm {
     where
         T: wasmtime::AsContextMut,
     {
+        self.validate_opcodes(wasm_bytes)?;
+
         // Compile the WASM module
         let module = Module::new(&self.engine, wasm_bytes)
             .map_err(|e| anyhow!("Failed to compile WASM module: {}", e))?;
@@ -259,10 +563,11 @@ impl CoVm {
 
         // Calculate resource usage
         let fuel_remaining = store.get_fuel().unwrap_or(0);
-        let _fuel_consumed = initial_fuel.saturating_sub(fuel_remaining);
+        let fuel_consumed = initial_fuel.saturating_sub(fuel_remaining);
 
         // Track metrics in the host context
         let host_context = context;
+        host_context.metrics.lock().unwrap().fuel_used = fuel_consumed;
 
         Ok(host_context)
     }
@@ -272,13 +577,7 @@ impl CoVm {
         let entrypoint = instance
             .get_typed_func::<(), ()>(&mut *store, "_start")
             .map_err(|e| anyhow!("Failed to get _start function: {}", e))?;
-        entrypoint.call(store.as_context_mut(), ()).map_err(|e| {
-            if e.to_string().contains("all fuel consumed") {
-                CoVmError::FuelExhausted.into()
-            } else {
-                anyhow!("WASM execution trapped: {}", e)
-            }
-        })
+        entrypoint.call(store.as_context_mut(), ()).map_err(map_trap)
     }
 
     /// Call entrypoint function with a generic store
@@ -289,24 +588,20 @@ impl CoVm {
         let entrypoint = instance
             .get_typed_func::<(), ()>(store.as_context_mut(), "_start")
             .map_err(|e| anyhow!("Failed to get _start function: {}", e))?;
-        entrypoint.call(store.as_context_mut(), ()).map_err(|e| {
-            if e.to_string().contains("all fuel consumed") {
-                CoVmError::FuelExhausted.into()
-            } else {
-                anyhow!("WASM execution trapped: {}", e)
-            }
-        })
+        entrypoint.call(store.as_context_mut(), ()).map_err(map_trap)
     }
 
     /// Create host function for logging messages
     fn create_log_function(&self, store: &mut Store<HostContext>) -> Func {
+        let limits = self.limits.clone();
+        let memory_exports = self.memory_export_names();
         Func::new(
             store,
             FuncType::new(
                 [ValType::I32, ValType::I32].iter().cloned(),
                 [].iter().cloned(),
             ),
-            |mut caller: Caller<'_, HostContext>,
+            move |mut caller: Caller<'_, HostContext>,
              args: &[Val],
              _results: &mut [Val]|
              -> Result<()> {
@@ -314,24 +609,17 @@ impl CoVm {
                 let len = args[1].unwrap_i32();
                 {
                     let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.host_calls += 1;
+                    limits.record_host_call(&mut metrics, "log")?;
+                    limits.record_io_bytes(&mut metrics, "log", guest_len(len))?;
                 }
-                let memory = match caller.get_export("memory") {
-                    Some(Extern::Memory(mem)) => mem,
-                    _ => bail!("Failed to find memory export"),
-                };
-                let data = memory
-                    .data(&caller)
-                    .get(ptr as u32 as usize..(ptr as u32 + len as u32) as usize)
-                    .ok_or_else(|| anyhow!("Invalid memory access"))?;
-                let message = std::str::from_utf8(data)
-                    .map_err(|_| anyhow!("Invalid UTF-8 in log message"))?
-                    .to_string();
+                let memory = caller_memory(&mut caller, &memory_exports)?;
+                let data = read_guest_slice(&caller, &memory, ptr, len)?;
+                let message = String::from_utf8(data)
+                    .map_err(|_| anyhow!("Invalid UTF-8 in log message"))?;
+                caller
+                    .data()
+                    .trace_call("log", || vec![TraceValue::Str(message.clone())], None);
                 caller.data_mut().logs.lock().unwrap().push(message);
-                {
-                    let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.io_bytes += len as u64;
-                }
                 Ok(())
             },
         )
@@ -339,13 +627,15 @@ impl CoVm {
 
     /// Create host function for anchoring CIDs to DAG
     fn create_anchor_function(&self, store: &mut Store<HostContext>) -> Func {
+        let limits = self.limits.clone();
+        let memory_exports = self.memory_export_names();
         Func::new(
             store,
             FuncType::new(
                 [ValType::I32, ValType::I32].iter().cloned(),
                 [].iter().cloned(),
             ),
-            |mut caller: Caller<'_, HostContext>,
+            move |mut caller: Caller<'_, HostContext>,
              args: &[Val],
              _results: &mut [Val]|
              -> Result<()> {
@@ -353,29 +643,23 @@ impl CoVm {
                 let len = args[1].unwrap_i32();
                 {
                     let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.host_calls += 1;
+                    limits.record_host_call(&mut metrics, "anchor")?;
+                    limits.record_io_bytes(&mut metrics, "anchor", guest_len(len))?;
                 }
-                let memory = match caller.get_export("memory") {
-                    Some(Extern::Memory(mem)) => mem,
-                    _ => bail!("Failed to find memory export"),
-                };
-                let data = memory
-                    .data(&caller)
-                    .get(ptr as u32 as usize..(ptr as u32 + len as u32) as usize)
-                    .ok_or_else(|| anyhow!("Invalid memory access"))?;
-                let cid_str = std::str::from_utf8(data)
-                    .map_err(|_| anyhow!("Invalid UTF-8 in CID"))?
-                    .to_string();
+                let memory = caller_memory(&mut caller, &memory_exports)?;
+                let data = read_guest_slice(&caller, &memory, ptr, len)?;
+                let cid_str = String::from_utf8(data)
+                    .map_err(|_| anyhow!("Invalid UTF-8 in CID"))?;
+                limits.check_anchor(caller.data().anchored_cids.lock().unwrap().len())?;
+                caller
+                    .data()
+                    .trace_call("anchor", || vec![TraceValue::Str(cid_str.clone())], None);
                 caller
                     .data_mut()
                     .anchored_cids
                     .lock()
                     .unwrap()
                     .push(cid_str);
-                {
-                    let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.io_bytes += len as u64;
-                }
                 Ok(())
             },
         )
@@ -383,35 +667,35 @@ impl CoVm {
 
     /// Create host function for checking resource authorization
     fn create_check_auth_function(&self, store: &mut Store<HostContext>) -> Func {
+        let limits = self.limits.clone();
+        let memory_exports = self.memory_export_names();
         Func::new(
             store,
             FuncType::new(
                 [ValType::I32, ValType::I32, ValType::I64].iter().cloned(),
                 [ValType::I32].iter().cloned(),
             ),
-            |mut caller: Caller<'_, HostContext>,
+            move |mut caller: Caller<'_, HostContext>,
              args: &[Val],
              results: &mut [Val]|
              -> Result<()> {
                 let type_ptr = args[0].unwrap_i32();
                 let type_len = args[1].unwrap_i32();
-                let _amount = args[2].unwrap_i64();
+                let amount = args[2].unwrap_i64();
                 {
                     let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.host_calls += 1;
+                    limits.record_host_call(&mut metrics, "check_auth")?;
                 }
-                let memory = match caller.get_export("memory") {
-                    Some(Extern::Memory(mem)) => mem,
-                    _ => bail!("Failed to find memory export"),
-                };
-                let type_data = memory
-                    .data(&caller)
-                    .get(type_ptr as u32 as usize..(type_ptr as u32 + type_len as u32) as usize)
-                    .ok_or_else(|| anyhow!("Invalid memory access"))?;
-                let _resource_type = std::str::from_utf8(type_data)
-                    .map_err(|_| anyhow!("Invalid UTF-8 in resource type"))?
-                    .to_string();
+                let memory = caller_memory(&mut caller, &memory_exports)?;
+                let type_data = read_guest_slice(&caller, &memory, type_ptr, type_len)?;
+                let resource_type = String::from_utf8(type_data)
+                    .map_err(|_| anyhow!("Invalid UTF-8 in resource type"))?;
                 results[0] = Val::I32(1);
+                caller.data().trace_call(
+                    "check_auth",
+                    || vec![TraceValue::Str(resource_type), TraceValue::I64(amount)],
+                    Some(TraceValue::I32(1)),
+                );
                 Ok(())
             },
         )
@@ -419,13 +703,15 @@ impl CoVm {
 
     /// Create host function for recording resource usage
     fn create_record_usage_function(&self, store: &mut Store<HostContext>) -> Func {
+        let limits = self.limits.clone();
+        let memory_exports = self.memory_export_names();
         Func::new(
             store,
             FuncType::new(
                 [ValType::I32, ValType::I32, ValType::I64].iter().cloned(),
                 [].iter().cloned(),
             ),
-            |mut caller: Caller<'_, HostContext>,
+            move |mut caller: Caller<'_, HostContext>,
              args: &[Val],
              _results: &mut [Val]|
              -> Result<()> {
@@ -434,29 +720,24 @@ impl CoVm {
                 let amount = args[2].unwrap_i64();
                 {
                     let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.host_calls += 1;
+                    limits.record_host_call(&mut metrics, "record_usage")?;
+                    limits.record_io_bytes(&mut metrics, "record_usage", guest_len(type_len))?;
                 }
-                let memory = match caller.get_export("memory") {
-                    Some(Extern::Memory(mem)) => mem,
-                    _ => bail!("Failed to find memory export"),
-                };
-                let type_data = memory
-                    .data(&caller)
-                    .get(type_ptr as u32 as usize..(type_ptr as u32 + type_len as u32) as usize)
-                    .ok_or_else(|| anyhow!("Invalid memory access"))?;
-                let resource_type = std::str::from_utf8(type_data)
-                    .map_err(|_| anyhow!("Invalid UTF-8 in resource type"))?
-                    .to_string();
+                let memory = caller_memory(&mut caller, &memory_exports)?;
+                let type_data = read_guest_slice(&caller, &memory, type_ptr, type_len)?;
+                let resource_type = String::from_utf8(type_data)
+                    .map_err(|_| anyhow!("Invalid UTF-8 in resource type"))?;
+                caller.data().trace_call(
+                    "record_usage",
+                    || vec![TraceValue::Str(resource_type.clone()), TraceValue::I64(amount)],
+                    None,
+                );
                 caller
                     .data_mut()
                     .resource_usage
                     .lock()
                     .unwrap()
                     .push((resource_type, amount as u64));
-                {
-                    let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.io_bytes += type_len as u64;
-                }
                 Ok(())
             },
         )
@@ -464,6 +745,8 @@ impl CoVm {
 
     /// Create host function for submitting a job
     fn create_submit_job_function(&self, store: &mut Store<HostContext>) -> Func {
+        let limits = self.limits.clone();
+        let memory_exports = self.memory_export_names();
         Func::new(
             store,
             FuncType::new(
@@ -482,7 +765,7 @@ impl CoVm {
                 .cloned(),
                 [ValType::I32].iter().cloned(),
             ),
-            |mut caller: Caller<'_, HostContext>,
+            move |mut caller: Caller<'_, HostContext>,
              args: &[Val],
              results: &mut [Val]|
              -> Result<()> {
@@ -507,53 +790,41 @@ impl CoVm {
 
                 {
                     let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.host_calls += 1;
+                    limits.record_host_call(&mut metrics, "submit_job")?;
                 }
-                let memory = match caller.get_export("memory") {
-                    Some(Extern::Memory(mem)) => mem,
-                    _ => bail!("Failed to find memory export"),
-                };
+                let depth = caller.data().submission_depth;
+                if depth >= limits.max_submission_depth {
+                    return Err(CoVmError::ResourceLimitExceeded(format!(
+                        "submission depth exceeded: job is {} submissions deep; the limit is {}",
+                        depth, limits.max_submission_depth
+                    ))
+                    .into());
+                }
+                {
+                    let mut metrics = caller.data_mut().metrics.lock().unwrap();
+                    let bytes = [wasm_cid_len, desc_len, rsrc_type_len, priority_len]
+                        .into_iter()
+                        .map(guest_len)
+                        .fold(0u64, u64::saturating_add);
+                    limits.record_io_bytes(&mut metrics, "submit_job", bytes)?;
+                }
+                let memory = caller_memory(&mut caller, &memory_exports)?;
+
+                let wasm_cid_data = read_guest_slice(&caller, &memory, wasm_cid_ptr, wasm_cid_len)?;
+                let wasm_cid = String::from_utf8(wasm_cid_data)
+                    .map_err(|_| anyhow!("Invalid UTF-8 in WASM CID"))?;
+
+                let desc_data = read_guest_slice(&caller, &memory, desc_ptr, desc_len)?;
+                let description = String::from_utf8(desc_data)
+                    .map_err(|_| anyhow!("Invalid UTF-8 in job description"))?;
+
+                let rsrc_type_data = read_guest_slice(&caller, &memory, rsrc_type_ptr, rsrc_type_len)?;
+                let resource_type = String::from_utf8(rsrc_type_data)
+                    .map_err(|_| anyhow!("Invalid UTF-8 in resource type"))?;
 
-                let wasm_cid_data = memory
-                    .data(&caller)
-                    .get(
-                        wasm_cid_ptr as u32 as usize
-                            ..(wasm_cid_ptr as u32 + wasm_cid_len as u32) as usize,
-                    )
-                    .ok_or_else(|| anyhow!("Invalid memory access for WASM CID"))?;
-                let wasm_cid = std::str::from_utf8(wasm_cid_data)
-                    .map_err(|_| anyhow!("Invalid UTF-8 in WASM CID"))?
-                    .to_string();
-
-                let desc_data = memory
-                    .data(&caller)
-                    .get(desc_ptr as u32 as usize..(desc_ptr as u32 + desc_len as u32) as usize)
-                    .ok_or_else(|| anyhow!("Invalid memory access for description"))?;
-                let description = std::str::from_utf8(desc_data)
-                    .map_err(|_| anyhow!("Invalid UTF-8 in job description"))?
-                    .to_string();
-
-                let rsrc_type_data = memory
-                    .data(&caller)
-                    .get(
-                        rsrc_type_ptr as u32 as usize
-                            ..(rsrc_type_ptr as u32 + rsrc_type_len as u32) as usize,
-                    )
-                    .ok_or_else(|| anyhow!("Invalid memory access for resource type"))?;
-                let resource_type = std::str::from_utf8(rsrc_type_data)
-                    .map_err(|_| anyhow!("Invalid UTF-8 in resource type"))?
-                    .to_string();
-
-                let priority_data = memory
-                    .data(&caller)
-                    .get(
-                        priority_ptr as u32 as usize
-                            ..(priority_ptr as u32 + priority_len as u32) as usize,
-                    )
-                    .ok_or_else(|| anyhow!("Invalid memory access for priority"))?;
-                let priority = std::str::from_utf8(priority_data)
-                    .map_err(|_| anyhow!("Invalid UTF-8 in job priority"))?
-                    .to_string();
+                let priority_data = read_guest_slice(&caller, &memory, priority_ptr, priority_len)?;
+                let priority = String::from_utf8(priority_data)
+                    .map_err(|_| anyhow!("Invalid UTF-8 in job priority"))?;
 
                 let job = JobSubmission {
                     wasm_cid,
@@ -562,12 +833,20 @@ impl CoVm {
                     resource_amount: rsrc_amount as u64,
                     priority,
                 };
+                caller.data().trace_call(
+                    "submit_job",
+                    || {
+                        vec![
+                            TraceValue::Str(job.wasm_cid.clone()),
+                            TraceValue::Str(job.description.clone()),
+                            TraceValue::Str(job.resource_type.clone()),
+                            TraceValue::I64(rsrc_amount),
+                            TraceValue::Str(job.priority.clone()),
+                        ]
+                    },
+                    Some(TraceValue::I32(1)),
+                );
                 caller.data_mut().job_submissions.lock().unwrap().push(job);
-                {
-                    let mut metrics = caller.data_mut().metrics.lock().unwrap();
-                    metrics.io_bytes +=
-                        (wasm_cid_len + desc_len + rsrc_type_len + priority_len) as u64;
-                }
                 results[0] = Val::I32(1);
                 Ok(())
             },
@@ -575,10 +854,364 @@ impl CoVm {
     }
 }
 
+/// Copy `len` bytes at `ptr` out of the guest's `memory`, failing cleanly if
+/// `ptr + len` overflows `u32` or runs past the end of linear memory
+fn read_guest_slice(
+    caller: &Caller<'_, HostContext>,
+    memory: &Memory,
+    ptr: i32,
+    len: i32,
+) -> Result<Vec<u8>> {
+    let (start, len) = (ptr as u32, len as u32);
+    let end = start.checked_add(len).ok_or_else(|| {
+        CoVmError::HostFunctionError(format!("guest range {}+{} overflows u32", start, len))
+    })?;
+    memory
+        .data(caller)
+        .get(start as usize..end as usize)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| {
+            CoVmError::HostFunctionError(format!(
+                "guest range {}..{} is outside linear memory",
+                start, end
+            ))
+            .into()
+        })
+}
+
+/// Byte count of a guest-supplied length, read as unsigned like [`read_guest_slice`] does
+fn guest_len(len: i32) -> u64 {
+    u64::from(len as u32)
+}
+
+/// The calling module's linear memory, found under the first of `names` it exports
+fn caller_memory(caller: &mut Caller<'_, HostContext>, names: &[String]) -> Result<Memory> {
+    names
+        .iter()
+        .find_map(|name| match caller.get_export(name) {
+            Some(Extern::Memory(memory)) => Some(memory),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            CoVmError::HostFunctionError("module must export linear memory".to_string()).into()
+        })
+}
+
+/// Classify a trap from the entrypoint, keeping host-raised limit and host
+/// function errors typed
+fn map_trap(e: anyhow::Error) -> anyhow::Error {
+    if e.to_string().contains("all fuel consumed") {
+        return CoVmError::FuelExhausted.into();
+    }
+    match e.downcast::<CoVmError>() {
+        Ok(typed @ (CoVmError::ResourceLimitExceeded(_) | CoVmError::HostFunctionError(_))) => {
+            typed.into()
+        }
+        Ok(other) => anyhow!("WASM execution trapped: {}", other),
+        Err(e) => anyhow!("WASM execution trapped: {}", e),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    // use crate::job_utils::create_test_job; // To be removed
-    // use crate::{CoVm, ExecutionMetrics, HostContext, ResourceLimits}; // To be removed
+    use super::*;
+
+    // A module importing the host functions in the order `execute` supplies them,
+    // with one page of memory exported as `memory_export` and `fields` (data
+    // segments and the `_start` function) appended.
+    fn guest_module(memory_export: &str, fields: &str) -> Vec<u8> {
+        wat::parse_str(format!(
+            r#"
+            (module
+              (import "icn" "log" (func $log (param i32 i32)))
+              (import "icn" "anchor" (func $anchor (param i32 i32)))
+              (import "icn" "check_auth" (func (param i32 i32 i64) (result i32)))
+              (import "icn" "record_usage" (func (param i32 i32 i64)))
+              (import "icn" "submit_job"
+                (func (param i32 i32 i32 i32 i32 i32 i64 i32 i32) (result i32)))
+              (memory (export "{memory_export}") 1)
+              {fields})
+            "#
+        ))
+        .unwrap()
+    }
+
+    // Logs "started", then runs `then`.
+    fn log_then(then: &str) -> Vec<u8> {
+        guest_module(
+            "memory",
+            &format!(
+                r#"(data (i32.const 0) "started")
+                (func (export "_start")
+                  (call $log (i32.const 0) (i32.const 7))
+                  {then})"#
+            ),
+        )
+    }
+
+    fn limited_vm() -> CoVm {
+        CoVm::new(ResourceLimits {
+            max_fuel: 10_000,
+            ..ResourceLimits::default()
+        })
+    }
+
+    #[test]
+    fn partial_results_survive_fuel_exhaustion() {
+        let wasm = log_then("(loop $spin (br $spin))");
+
+        match limited_vm().execute_with_partial_results(&wasm, HostContext::default()) {
+            Err(PartialExecutionError::FuelExhausted { context }) => {
+                assert_eq!(*context.logs.lock().unwrap(), vec!["started".to_string()]);
+                assert_eq!(context.metrics.lock().unwrap().fuel_used, 10_000);
+            }
+            other => panic!("expected fuel exhaustion, got {:?}", other),
+        }
+
+        // The default entrypoint still discards the context
+        let err = limited_vm().execute(&wasm, HostContext::default()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<CoVmError>(), Some(CoVmError::FuelExhausted)));
+    }
+
+    #[test]
+    fn partial_results_survive_a_trap() {
+        let wasm = log_then("unreachable");
+
+        match limited_vm().execute_with_partial_results(&wasm, HostContext::default()) {
+            Err(PartialExecutionError::Trapped { context, .. }) => {
+                assert_eq!(*context.logs.lock().unwrap(), vec!["started".to_string()]);
+            }
+            other => panic!("expected a trap, got {:?}", other),
+        }
+    }
+
+    // Anchors the same CID `n` times, then logs once.
+    fn anchor_then_log(n: u32) -> Vec<u8> {
+        guest_module(
+            "memory",
+            &format!(
+                r#"(data (i32.const 0) "bafy")
+                (func (export "_start") (local $i i32)
+                  (block $done
+                    (loop $next
+                      (br_if $done (i32.ge_u (local.get $i) (i32.const {n})))
+                      (call $anchor (i32.const 0) (i32.const 4))
+                      (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                      (br $next)))
+                  (call $log (i32.const 0) (i32.const 4)))"#
+            ),
+        )
+    }
+
+    #[test]
+    fn per_function_host_call_limit() {
+        let vm = CoVm::new(ResourceLimits::default().with_host_call_limit("anchor", 3));
+
+        let context = vm.execute(&anchor_then_log(3), HostContext::default()).unwrap();
+        let metrics = context.metrics.lock().unwrap();
+        assert_eq!(metrics.host_calls_by_function["anchor"], 3);
+        assert_eq!(metrics.host_calls_by_function["log"], 1);
+
+        let err = vm.execute(&anchor_then_log(4), HostContext::default()).unwrap_err();
+        match err.downcast_ref::<CoVmError>() {
+            Some(CoVmError::ResourceLimitExceeded(msg)) => assert!(msg.contains("anchor")),
+            other => panic!("expected per-function limit error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn aggregate_host_call_limit_still_applies() {
+        let vm = CoVm::new(ResourceLimits {
+            max_host_calls: 3,
+            ..ResourceLimits::default().with_host_call_limit("anchor", 10)
+        });
+
+        let err = vm.execute(&anchor_then_log(3), HostContext::default()).unwrap_err();
+        match err.downcast_ref::<CoVmError>() {
+            Some(CoVmError::ResourceLimitExceeded(msg)) => {
+                assert!(msg.contains("more than 3 host calls"), "{}", msg);
+                assert!(msg.contains("'log'"), "{}", msg);
+            }
+            other => panic!("expected aggregate limit error, got {:?}", other),
+        }
+    }
 
-    // Test will be added once we have test WASM modules
+    /// Anchors the `len` bytes at `ptr`
+    fn anchor_range(ptr: i32, len: i32) -> Vec<u8> {
+        guest_module(
+            "memory",
+            &format!(
+                r#"(func (export "_start")
+                  (call $anchor (i32.const {ptr}) (i32.const {len})))"#
+            ),
+        )
+    }
+
+    #[test]
+    fn out_of_range_guest_reads_fail_cleanly() {
+        for (ptr, len, expected) in [(-1, 2, "overflows u32"), (65_530, 16, "outside linear memory")] {
+            let err = CoVm::default()
+                .execute(&anchor_range(ptr, len), HostContext::default())
+                .unwrap_err();
+            match err.downcast_ref::<CoVmError>() {
+                Some(CoVmError::HostFunctionError(msg)) => assert!(msg.contains(expected), "{}", msg),
+                other => panic!("expected a host function error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_reads_are_charged_before_copying() {
+        let vm = CoVm::new(ResourceLimits {
+            max_io_bytes: 1024,
+            ..ResourceLimits::default()
+        });
+
+        // The range runs far past linear memory; the budget refuses it first
+        let err = vm
+            .execute(&anchor_range(0, 1_000_000), HostContext::default())
+            .unwrap_err();
+        match err.downcast_ref::<CoVmError>() {
+            Some(CoVmError::ResourceLimitExceeded(msg)) => {
+                assert!(msg.contains("max_io_bytes"), "{}", msg)
+            }
+            other => panic!("expected I/O limit error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn io_past_max_io_bytes_traps_with_limit_error() {
+        // Each anchor reads 4 bytes, so the third takes the total to 12
+        let vm = CoVm::new(ResourceLimits {
+            max_io_bytes: 10,
+            ..ResourceLimits::default()
+        });
+
+        let err = vm.execute(&anchor_then_log(3), HostContext::default()).unwrap_err();
+        match err.downcast_ref::<CoVmError>() {
+            Some(CoVmError::ResourceLimitExceeded(msg)) => {
+                assert!(msg.contains("max_io_bytes"), "{}", msg)
+            }
+            other => panic!("expected I/O limit error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn anchoring_past_max_anchored_cids_traps_with_limit_error() {
+        let vm = CoVm::new(ResourceLimits {
+            max_anchored_cids: 2,
+            ..ResourceLimits::default()
+        });
+        assert!(vm.execute(&anchor_then_log(2), HostContext::default()).is_ok());
+
+        let err = vm.execute(&anchor_then_log(3), HostContext::default()).unwrap_err();
+        match err.downcast_ref::<CoVmError>() {
+            Some(CoVmError::ResourceLimitExceeded(msg)) => {
+                assert!(msg.contains("max_anchored_cids"), "{}", msg)
+            }
+            other => panic!("expected anchored CID limit error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn trace_records_host_calls_without_changing_metrics() {
+        let wasm = anchor_then_log(2);
+        let plain = CoVm::default().execute(&wasm, HostContext::default()).unwrap();
+        let traced = CoVm::default()
+            .with_trace(true)
+            .execute(&wasm, HostContext::default())
+            .unwrap();
+
+        assert!(plain.trace().is_none());
+        let trace = traced.trace().unwrap();
+        let calls: Vec<_> = trace.calls.iter().map(|c| c.function.as_str()).collect();
+        assert_eq!(calls, ["anchor", "anchor", "log"]);
+        assert_eq!(trace.calls[0].args, vec![TraceValue::Str("bafy".to_string())]);
+
+        let (plain, traced) = (plain.metrics.lock().unwrap(), traced.metrics.lock().unwrap());
+        assert_eq!(plain.host_calls, traced.host_calls);
+        assert_eq!(plain.io_bytes, traced.io_bytes);
+        assert_eq!(plain.fuel_used, traced.fuel_used);
+
+        let restored: ExecutionTrace =
+            serde_json::from_str(&serde_json::to_string(&trace).unwrap()).unwrap();
+        assert_eq!(restored.first_divergence(&trace), None);
+        let mut shorter = trace.clone();
+        shorter.calls.pop();
+        assert_eq!(trace.first_divergence(&shorter), Some(2));
+    }
+
+    // Logs "renamed" through memory exported as `$name`.
+    fn log_with_memory_export(name: &str) -> Vec<u8> {
+        guest_module(
+            name,
+            r#"(data (i32.const 0) "renamed")
+            (func (export "_start")
+              (call $log (i32.const 0) (i32.const 7)))"#,
+        )
+    }
+
+    #[test]
+    fn memory_export_name_is_configurable_with_fallbacks() {
+        let logs = |vm: &CoVm, wasm: &[u8]| {
+            vm.execute(wasm, HostContext::default())
+                .map(|context| context.logs.lock().unwrap().clone())
+        };
+
+        let custom = log_with_memory_export("linear");
+        let vm = CoVm::default().with_memory_export("linear");
+        assert_eq!(logs(&vm, &custom).unwrap(), ["renamed"]);
+        // The default name is still probed when a custom one is configured
+        assert_eq!(logs(&vm, &log_with_memory_export("memory")).unwrap(), ["renamed"]);
+        assert_eq!(
+            logs(&CoVm::default(), &log_with_memory_export("mem")).unwrap(),
+            ["renamed"]
+        );
+
+        let err = logs(&CoVm::default(), &custom).unwrap_err();
+        match err.downcast_ref::<CoVmError>() {
+            Some(CoVmError::HostFunctionError(msg)) => {
+                assert_eq!(msg, "module must export linear memory")
+            }
+            other => panic!("expected missing memory error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deterministic_vm_rejects_floats_that_the_default_vm_runs() {
+        let wasm = guest_module(
+            "memory",
+            r#"(func (export "_start")
+              (drop (f64.sqrt (f64.const 2))))"#,
+        );
+
+        let fast = CoVm::default();
+        assert!(!fast.is_deterministic());
+        fast.execute(&wasm, HostContext::default()).unwrap();
+
+        let deterministic = CoVm::new_deterministic(ResourceLimits::default());
+        assert!(deterministic.is_deterministic());
+        assert!(deterministic.execute(&wasm, HostContext::default()).is_err());
+        assert!(!deterministic
+            .with_opcode_policy(OpcodePolicy::allow_all())
+            .is_deterministic());
+    }
+
+    #[test]
+    fn deterministic_replays_report_identical_metrics() {
+        let vm = CoVm::new_deterministic(ResourceLimits::default());
+        let wasm = anchor_then_log(5);
+        let run = || {
+            vm.execute(&wasm, HostContext::default())
+                .unwrap()
+                .metrics
+                .lock()
+                .unwrap()
+                .clone()
+        };
+
+        let first = run();
+        assert!(first.fuel_used > 0);
+        assert_eq!(first, run());
+    }
 }
@@ -0,0 +1,300 @@
+//! Instruction-set policy for governance modules.
+//!
+//! Lets operators reject modules that use instruction classes they consider
+//! unsafe for governance code (e.g. floating point when execution must be
+//! bit-for-bit deterministic across nodes) before anything is compiled or run.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use wasmparser::{Operator, Parser, Payload};
+
+use crate::CoVmError;
+
+/// A group of related WASM instructions that can be disallowed as a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpcodeClass {
+    /// Scalar `f32`/`f64` arithmetic, constants and conversions
+    FloatingPoint,
+    /// 128-bit SIMD (`v128`) instructions
+    Simd,
+    /// Shared-memory atomics and fences
+    Threads,
+    /// `memory.copy`/`memory.fill`/`memory.init` and table/segment bulk operations
+    BulkMemory,
+    /// `ref.*` and `table.*` instructions from the reference-types proposal
+    ReferenceTypes,
+    /// `return_call*` from the tail-call proposal
+    TailCall,
+    /// `try`/`catch`/`throw` from the exception-handling proposal
+    Exceptions,
+}
+
+impl OpcodeClass {
+    /// Classify an instruction, returning `None` for core MVP integer/control instructions.
+    pub fn of(op: &Operator) -> Option<Self> {
+        if is_mvp_float(op) {
+            return Some(Self::FloatingPoint);
+        }
+        let (proposal, _) = operator_info(op);
+        match proposal {
+            "simd" | "relaxed_simd" => Some(Self::Simd),
+            "threads" => Some(Self::Threads),
+            "saturating_float_to_int" => Some(Self::FloatingPoint),
+            "bulk_memory" => Some(Self::BulkMemory),
+            "reference_types" => Some(Self::ReferenceTypes),
+            "tail_call" => Some(Self::TailCall),
+            "exceptions" | "legacy_exceptions" => Some(Self::Exceptions),
+            _ => matches!(op, Operator::ReturnCallRef { .. }).then_some(Self::TailCall),
+        }
+    }
+}
+
+/// Whether `op` is one of the MVP's scalar float instructions. The MVP set is
+/// frozen; float instructions from later proposals are classified by proposal.
+fn is_mvp_float(op: &Operator) -> bool {
+    use Operator::*;
+    matches!(
+        op,
+        F32Load { .. }
+            | F64Load { .. }
+            | F32Store { .. }
+            | F64Store { .. }
+            | F32Const { .. }
+            | F64Const { .. }
+            | F32Eq
+            | F32Ne
+            | F32Lt
+            | F32Gt
+            | F32Le
+            | F32Ge
+            | F64Eq
+            | F64Ne
+            | F64Lt
+            | F64Gt
+            | F64Le
+            | F64Ge
+            | F32Abs
+            | F32Neg
+            | F32Ceil
+            | F32Floor
+            | F32Trunc
+            | F32Nearest
+            | F32Sqrt
+            | F32Add
+            | F32Sub
+            | F32Mul
+            | F32Div
+            | F32Min
+            | F32Max
+            | F32Copysign
+            | F64Abs
+            | F64Neg
+            | F64Ceil
+            | F64Floor
+            | F64Trunc
+            | F64Nearest
+            | F64Sqrt
+            | F64Add
+            | F64Sub
+            | F64Mul
+            | F64Div
+            | F64Min
+            | F64Max
+            | F64Copysign
+            | I32TruncF32S
+            | I32TruncF32U
+            | I32TruncF64S
+            | I32TruncF64U
+            | I64TruncF32S
+            | I64TruncF32U
+            | I64TruncF64S
+            | I64TruncF64U
+            | F32ConvertI32S
+            | F32ConvertI32U
+            | F32ConvertI64S
+            | F32ConvertI64U
+            | F32DemoteF64
+            | F64ConvertI32S
+            | F64ConvertI32U
+            | F64ConvertI64S
+            | F64ConvertI64U
+            | F64PromoteF32
+            | I32ReinterpretF32
+            | I64ReinterpretF64
+            | F32ReinterpretI32
+            | F64ReinterpretI64
+    )
+}
+
+/// Defines `operator_info`, which maps an operator to the proposal that
+/// introduced it and its variant name, e.g. `("mvp", "F32Add")`. Generated from
+/// wasmparser's own operator table, so new operators are covered on upgrade.
+macro_rules! define_operator_info {
+    ($( @$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident)*) => {
+        fn operator_info(op: &Operator) -> (&'static str, &'static str) {
+            match op {
+                $( Operator::$op { .. } => (stringify!($proposal), stringify!($op)), )*
+                #[allow(unreachable_patterns)]
+                _ => ("unknown", "unknown"),
+            }
+        }
+    };
+}
+wasmparser::for_each_operator!(define_operator_info);
+
+impl fmt::Display for OpcodeClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::FloatingPoint => "floating-point",
+            Self::Simd => "SIMD",
+            Self::Threads => "threads",
+            Self::BulkMemory => "bulk-memory",
+            Self::ReferenceTypes => "reference-types",
+            Self::TailCall => "tail-call",
+            Self::Exceptions => "exception-handling",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Set of instruction classes a module may not use
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpcodePolicy {
+    #[serde(default)]
+    pub disallowed: BTreeSet<OpcodeClass>,
+}
+
+impl OpcodePolicy {
+    /// Policy that accepts every instruction the engine supports
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Policy restricting modules to instructions with deterministic results
+    /// across hosts: no floating point, SIMD or threads.
+    pub fn deterministic() -> Self {
+        Self::allow_all()
+            .disallow(OpcodeClass::FloatingPoint)
+            .disallow(OpcodeClass::Simd)
+            .disallow(OpcodeClass::Threads)
+    }
+
+    /// Add a class to the disallowed set
+    pub fn disallow(mut self, class: OpcodeClass) -> Self {
+        self.disallowed.insert(class);
+        self
+    }
+
+    /// Whether `class` is disallowed by this policy
+    pub fn is_disallowed(&self, class: OpcodeClass) -> bool {
+        self.disallowed.contains(&class)
+    }
+
+    /// Scan every function body and reject the module at the first disallowed instruction.
+    pub fn validate(&self, wasm_bytes: &[u8]) -> Result<(), CoVmError> {
+        if self.disallowed.is_empty() {
+            return Ok(());
+        }
+
+        let mut function_index = 0usize;
+        for payload in Parser::new(0).parse_all(wasm_bytes) {
+            let payload = payload.map_err(|e| {
+                CoVmError::ExecutionError(format!("Failed to parse WASM module: {}", e))
+            })?;
+            if let Payload::CodeSectionEntry(body) = payload {
+                let mut reader = body.get_operators_reader().map_err(|e| {
+                    CoVmError::ExecutionError(format!("Invalid function body: {}", e))
+                })?;
+                while !reader.eof() {
+                    let op = reader.read().map_err(|e| {
+                        CoVmError::ExecutionError(format!("Invalid instruction: {}", e))
+                    })?;
+                    if let Some(class) = OpcodeClass::of(&op).filter(|c| self.is_disallowed(*c)) {
+                        return Err(CoVmError::ExecutionError(format!(
+                            "Module uses disallowed {} instruction `{}` in function body {}",
+                            class,
+                            operator_info(&op).1,
+                            function_index
+                        )));
+                    }
+                }
+                function_index += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wasm(text: &str) -> Vec<u8> {
+        wat::parse_str(text).expect("valid WAT")
+    }
+
+    #[test]
+    fn classifies_operators() {
+        assert_eq!(
+            OpcodeClass::of(&Operator::F64Add),
+            Some(OpcodeClass::FloatingPoint)
+        );
+        assert_eq!(
+            OpcodeClass::of(&Operator::I32TruncF32S),
+            Some(OpcodeClass::FloatingPoint)
+        );
+        assert_eq!(
+            OpcodeClass::of(&Operator::I32TruncSatF64U),
+            Some(OpcodeClass::FloatingPoint)
+        );
+        assert_eq!(
+            OpcodeClass::of(&Operator::F32x4Add),
+            Some(OpcodeClass::Simd)
+        );
+        assert_eq!(OpcodeClass::of(&Operator::I32Add), None);
+        assert_eq!(OpcodeClass::of(&Operator::I32Extend8S), None);
+    }
+
+    #[test]
+    fn classifies_operators_by_proposal() {
+        let atomics = wasm(
+            r#"(module (memory 1 1 shared)
+                 (func (export "_start") i32.const 0 i32.const 1 i32.atomic.rmw.add drop))"#,
+        );
+        let fill = wasm(
+            r#"(module (memory 1)
+                 (func (export "_start") i32.const 0 i32.const 0 i32.const 1 memory.fill))"#,
+        );
+
+        let threads = OpcodePolicy::allow_all().disallow(OpcodeClass::Threads);
+        let err = threads.validate(&atomics).unwrap_err();
+        assert!(matches!(err, CoVmError::ExecutionError(msg) if msg.contains("I32AtomicRmwAdd")));
+        assert!(threads.validate(&fill).is_ok());
+
+        let bulk = OpcodePolicy::allow_all().disallow(OpcodeClass::BulkMemory);
+        assert!(bulk.validate(&fill).is_err());
+        assert!(bulk.validate(&atomics).is_ok());
+    }
+
+    #[test]
+    fn deterministic_policy_rejects_floats() {
+        let bytes = wasm(
+            r#"(module (func (export "_start") f32.const 1.5 f32.const 2 f32.add drop))"#,
+        );
+        let err = OpcodePolicy::deterministic().validate(&bytes).unwrap_err();
+        assert!(matches!(err, CoVmError::ExecutionError(msg) if msg.contains("floating-point")));
+        assert!(OpcodePolicy::allow_all().validate(&bytes).is_ok());
+    }
+
+    #[test]
+    fn integer_only_module_passes() {
+        let bytes = wasm(
+            r#"(module (func (export "_start") i32.const 1 i32.const 2 i32.add drop))"#,
+        );
+        assert!(OpcodePolicy::deterministic().validate(&bytes).is_ok());
+    }
+}
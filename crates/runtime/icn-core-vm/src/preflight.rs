@@ -0,0 +1,247 @@
+//! Static preflight analysis of WASM modules.
+//!
+//! Gives a worst-case fuel bound for a module's `_start` entrypoint without
+//! executing it, so that obviously-expensive modules can be rejected up front.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use wasmparser::{ExternalKind, Operator, Parser, Payload, TypeRef};
+
+/// Name of the entrypoint invoked by `CoVm::execute`
+const ENTRYPOINT: &str = "_start";
+
+/// Estimate the maximum fuel that running the module's `_start` export can consume.
+///
+/// The bound assumes every instruction in a function executes at most once per
+/// call, which holds when there are no loops: branches then only jump forward.
+/// Calls add the bound of the callee; imported (host) functions contribute only
+/// the cost of the call instruction itself. The module's start function, if any,
+/// is included.
+///
+/// Returns `Ok(None)` when no finite bound can be derived: a reachable function
+/// contains a `loop`, recursion is possible, or an indirect call is reachable.
+pub fn estimate_max_fuel(wasm_bytes: &[u8]) -> Result<Option<u64>> {
+    let module = ModuleSummary::parse(wasm_bytes)?;
+
+    let entry = module
+        .exports
+        .get(ENTRYPOINT)
+        .copied()
+        .ok_or_else(|| anyhow!("Module does not export a `{}` function", ENTRYPOINT))?;
+
+    let mut estimator = Estimator {
+        module: &module,
+        memo: HashMap::new(),
+        visiting: Vec::new(),
+    };
+
+    let mut total = match estimator.function_cost(entry) {
+        Some(cost) => cost,
+        None => return Ok(None),
+    };
+    if let Some(start) = module.start {
+        match estimator.function_cost(start) {
+            Some(cost) => total = total.saturating_add(cost),
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(total))
+}
+
+/// Per-function facts gathered in a single pass over the module
+#[derive(Default)]
+struct FunctionSummary {
+    /// Fuel consumed by the function body's own instructions
+    own_cost: u64,
+    /// Indices of functions called directly
+    callees: Vec<u32>,
+    /// Whether the body contains a `loop`
+    has_loop: bool,
+    /// Whether the body contains an indirect call
+    has_indirect_call: bool,
+}
+
+#[derive(Default)]
+struct ModuleSummary {
+    imported_functions: u32,
+    /// Defined functions, indexed by `function_index - imported_functions`
+    functions: Vec<FunctionSummary>,
+    exports: HashMap<String, u32>,
+    start: Option<u32>,
+}
+
+impl ModuleSummary {
+    fn parse(wasm_bytes: &[u8]) -> Result<Self> {
+        let mut summary = ModuleSummary::default();
+
+        for payload in Parser::new(0).parse_all(wasm_bytes) {
+            match payload.map_err(|e| anyhow!("Failed to parse WASM module: {}", e))? {
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.map_err(|e| anyhow!("Invalid import: {}", e))?;
+                        if let TypeRef::Func(_) = import.ty {
+                            summary.imported_functions += 1;
+                        }
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.map_err(|e| anyhow!("Invalid export: {}", e))?;
+                        if export.kind == ExternalKind::Func {
+                            summary.exports.insert(export.name.to_string(), export.index);
+                        }
+                    }
+                }
+                Payload::StartSection { func, .. } => {
+                    summary.start = Some(func);
+                }
+                Payload::CodeSectionEntry(body) => {
+                    let mut function = FunctionSummary::default();
+                    let mut reader = body
+                        .get_operators_reader()
+                        .map_err(|e| anyhow!("Invalid function body: {}", e))?;
+                    while !reader.eof() {
+                        let op = reader
+                            .read()
+                            .map_err(|e| anyhow!("Invalid instruction: {}", e))?;
+                        function.own_cost = function.own_cost.saturating_add(fuel_cost(&op));
+                        match op {
+                            Operator::Loop { .. } => function.has_loop = true,
+                            Operator::Call { function_index }
+                            | Operator::ReturnCall { function_index } => {
+                                function.callees.push(function_index)
+                            }
+                            Operator::CallIndirect { .. }
+                            | Operator::ReturnCallIndirect { .. }
+                            | Operator::CallRef { .. }
+                            | Operator::ReturnCallRef { .. } => function.has_indirect_call = true,
+                            _ => {}
+                        }
+                    }
+                    summary.functions.push(function);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+struct Estimator<'a> {
+    module: &'a ModuleSummary,
+    memo: HashMap<u32, Option<u64>>,
+    /// Call stack of the current traversal, used to detect recursion
+    visiting: Vec<u32>,
+}
+
+impl Estimator<'_> {
+    fn function_cost(&mut self, index: u32) -> Option<u64> {
+        if index < self.module.imported_functions {
+            // Host functions do not consume WASM fuel beyond the call instruction
+            return Some(0);
+        }
+        if let Some(cached) = self.memo.get(&index) {
+            return *cached;
+        }
+        if self.visiting.contains(&index) {
+            return None;
+        }
+
+        let function = self
+            .module
+            .functions
+            .get((index - self.module.imported_functions) as usize)?;
+        if function.has_loop || function.has_indirect_call {
+            self.memo.insert(index, None);
+            return None;
+        }
+
+        self.visiting.push(index);
+        let mut cost = Some(function.own_cost);
+        for &callee in &function.callees {
+            cost = match (cost, self.function_cost(callee)) {
+                (Some(total), Some(callee_cost)) => Some(total.saturating_add(callee_cost)),
+                _ => None,
+            };
+            if cost.is_none() {
+                break;
+            }
+        }
+        self.visiting.pop();
+
+        self.memo.insert(index, cost);
+        cost
+    }
+}
+
+/// Fuel charged by wasmtime for a single instruction: structural
+/// instructions are free, everything else costs one unit.
+fn fuel_cost(op: &Operator) -> u64 {
+    match op {
+        Operator::Nop
+        | Operator::Drop
+        | Operator::Block { .. }
+        | Operator::Loop { .. }
+        | Operator::Unreachable
+        | Operator::Return
+        | Operator::Else
+        | Operator::End => 0,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wasm(text: &str) -> Vec<u8> {
+        wat::parse_str(text).expect("valid WAT")
+    }
+
+    #[test]
+    fn straight_line_module_is_bounded() {
+        let bytes = wasm(
+            r#"(module
+                (func $helper (result i32) i32.const 1 i32.const 2 i32.add)
+                (func (export "_start") call $helper call $helper drop drop))"#,
+        );
+        // helper: 3 instructions; _start: 2 calls (+3 each)
+        assert_eq!(estimate_max_fuel(&bytes).unwrap(), Some(2 + 3 + 3));
+    }
+
+    #[test]
+    fn host_calls_cost_only_the_call() {
+        let bytes = wasm(
+            r#"(module
+                (import "icn" "host_log" (func $log (param i32 i32)))
+                (func (export "_start") i32.const 0 i32.const 0 call $log))"#,
+        );
+        assert_eq!(estimate_max_fuel(&bytes).unwrap(), Some(3));
+    }
+
+    #[test]
+    fn loops_are_unbounded() {
+        let bytes = wasm(r#"(module (func (export "_start") (loop br 0)))"#);
+        assert_eq!(estimate_max_fuel(&bytes).unwrap(), None);
+    }
+
+    #[test]
+    fn recursion_is_unbounded() {
+        let bytes = wasm(
+            r#"(module
+                (func $a call $b)
+                (func $b call $a)
+                (func (export "_start") call $a))"#,
+        );
+        assert_eq!(estimate_max_fuel(&bytes).unwrap(), None);
+    }
+
+    #[test]
+    fn missing_entrypoint_is_an_error() {
+        let bytes = wasm(r#"(module (func (export "main")))"#);
+        assert!(estimate_max_fuel(&bytes).is_err());
+    }
+}
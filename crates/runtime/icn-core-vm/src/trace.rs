@@ -0,0 +1,46 @@
+//! Host-call tracing for deterministic debugging.
+//!
+//! When enabled with [`CoVm::with_trace`](crate::CoVm::with_trace), every host
+//! function that completes is appended to the [`HostContext`](crate::HostContext)
+//! trace with its decoded arguments and return value. The trace serializes to
+//! JSON so it can be attached to a support ticket, and a trace from a later
+//! re-run can be compared against it with [`ExecutionTrace::first_divergence`].
+//! Tracing never touches metrics or limits, so it does not change execution.
+
+use serde::{Deserialize, Serialize};
+
+/// A decoded host-function argument or return value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceValue {
+    I32(i32),
+    I64(i64),
+    Str(String),
+}
+
+/// One completed host-function invocation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostCallRecord {
+    /// Host function name, e.g. `"anchor"`
+    pub function: String,
+    /// Arguments after reading strings out of guest memory
+    pub args: Vec<TraceValue>,
+    /// Value returned to the guest, if the function returns one
+    pub result: Option<TraceValue>,
+}
+
+/// Ordered record of the host calls made during one execution
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    pub calls: Vec<HostCallRecord>,
+}
+
+impl ExecutionTrace {
+    /// Index of the first call where `self` and `other` differ, or `None` if they match
+    pub fn first_divergence(&self, other: &ExecutionTrace) -> Option<usize> {
+        let common = self.calls.len().min(other.calls.len());
+        (0..common)
+            .find(|&i| self.calls[i] != other.calls[i])
+            .or_else(|| (self.calls.len() != other.calls.len()).then_some(common))
+    }
+}
@@ -1,6 +1,10 @@
+use icn_core_vm::{ExecutionMetrics, ResourceLimits};
 use icn_economics::mana::RegenerationPolicy;
 use serde::Deserialize;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::RuntimeError;
 
 /// Configuration for the ICN Runtime
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -23,10 +27,20 @@ pub struct RuntimeConfig {
     /// If not provided, default scoring parameters will be used.
     pub reputation_scoring_config_path: Option<PathBuf>,
 
+    /// Reputation records sent per request. Above 1, records are queued and
+    /// sent in batches. Defaults to 1 if not specified.
+    #[serde(default)]
+    pub reputation_batch_size: Option<usize>,
+
+    /// Optional interval in seconds at which a partial batch of reputation
+    /// records is sent. Defaults to 10 seconds if not specified.
+    #[serde(default)]
+    pub reputation_flush_interval_seconds: Option<u64>,
+
     /// Optional URL for the mesh job service to poll for new jobs.
     pub mesh_job_service_url: Option<String>,
 
-    /// Optional port for Prometheus metrics http endpoint.
+    /// Optional port for the Prometheus metrics and `/jobs` status http endpoint.
     pub metrics_port: Option<u16>,
 
     /// Optional log level string (e.g., "info", "debug", "icn_runtime=trace").
@@ -44,8 +58,353 @@ pub struct RuntimeConfig {
 
     /// Optional URL for the ICN Mesh Jobs API, used for reporting job failures.
     pub mesh_jobs_api_url: Option<String>,
+
+    /// Optional delay in seconds between polls when no job is available.
+    /// Defaults to 5 seconds if not specified.
+    #[serde(default)]
+    pub job_poll_interval_seconds: Option<u64>,
+
+    /// Optional timeout in seconds for one request to the mesh job service.
+    /// Defaults to 10 seconds if not specified.
+    #[serde(default)]
+    pub job_poll_timeout_seconds: Option<u64>,
+
+    /// Optional cap in seconds on the delay between polls after failed polls.
+    /// The delay doubles from the poll interval with each consecutive failure.
+    /// Defaults to 60 seconds if not specified.
+    #[serde(default)]
+    pub job_poll_max_backoff_seconds: Option<u64>,
+
+    /// Maximum number of jobs `run_forever` executes at once.
+    /// Defaults to 1 (jobs run one after another) if not specified.
+    #[serde(default)]
+    pub max_concurrent_jobs: Option<usize>,
+
+    /// Pricing used to derive a receipt's mana cost from its execution metrics
+    /// when the execution did not set an explicit cost.
+    #[serde(default)]
+    pub fuel_pricing: FuelPricing,
+
+    /// Backend for the mana ledger. The sled ledger lives under
+    /// `storage_path/mana_ledger` and survives restarts.
+    #[serde(default)]
+    pub mana_ledger: ManaLedgerBackend,
+
+    /// Receipts anchoring more CIDs than this keep only a Merkle root of the
+    /// list, which is stored separately. Unset keeps every list inline.
+    #[serde(default)]
+    pub anchored_cids_inline_limit: Option<usize>,
+
+    /// Limits that the metrics of anchored receipts must be consistent with;
+    /// receipts reporting more than any execution under them could are refused.
+    /// Defaults to `ResourceLimits::default()`.
+    #[serde(default)]
+    pub receipt_limits: ResourceLimits,
+}
+
+/// Where a node keeps its mana balances
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ManaLedgerBackend {
+    /// Balances are lost when the node stops
+    #[default]
+    Memory,
+    /// Balances persist in a sled database
+    Sled,
+}
+
+/// Federation-configurable conversion from execution metrics to mana.
+///
+/// The charged cost is
+/// `ceil(fuel_used / fuel_units_per_mana) + host_calls * mana_per_host_call + io_bytes * mana_per_io_byte`,
+/// saturating at `u64::MAX`. Setting `fuel_units_per_mana` to 0 disables the fuel component.
+///
+/// ```toml
+/// [fuel_pricing]
+/// fuel_units_per_mana = 10000
+/// mana_per_host_call = 1
+/// mana_per_io_byte = 0
+/// ```
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct FuelPricing {
+    /// Number of fuel units charged as one unit of mana
+    pub fuel_units_per_mana: u64,
+    /// Mana charged per host function call
+    pub mana_per_host_call: u64,
+    /// Mana charged per byte moved through host functions
+    pub mana_per_io_byte: u64,
+}
+
+impl Default for FuelPricing {
+    fn default() -> Self {
+        Self {
+            fuel_units_per_mana: 10_000,
+            mana_per_host_call: 1,
+            mana_per_io_byte: 0,
+        }
+    }
+}
+
+impl FuelPricing {
+    /// Compute the mana cost for the given execution metrics
+    pub fn mana_cost(&self, metrics: &ExecutionMetrics) -> u64 {
+        let fuel_cost = if self.fuel_units_per_mana == 0 {
+            0
+        } else {
+            metrics.fuel_used.div_ceil(self.fuel_units_per_mana)
+        };
+        fuel_cost
+            .saturating_add(metrics.host_calls.saturating_mul(self.mana_per_host_call))
+            .saturating_add(metrics.io_bytes.saturating_mul(self.mana_per_io_byte))
+    }
 }
 
 fn default_mana_tick_interval() -> Option<u64> {
     Some(30)
 }
+
+/// Delay between job polls when `job_poll_interval_seconds` is unset
+pub const DEFAULT_JOB_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Job service request timeout when `job_poll_timeout_seconds` is unset
+pub const DEFAULT_JOB_POLL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Backoff cap when `job_poll_max_backoff_seconds` is unset
+pub const DEFAULT_JOB_POLL_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Concurrent job limit when `max_concurrent_jobs` is unset
+pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 1;
+
+/// Partial reputation batch flush interval when `reputation_flush_interval_seconds` is unset
+pub const DEFAULT_REPUTATION_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+impl RuntimeConfig {
+    /// Delay between job polls when no job is available
+    pub fn job_poll_interval(&self) -> Duration {
+        self.job_poll_interval_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_JOB_POLL_INTERVAL)
+    }
+
+    /// Timeout for one request to the mesh job service
+    pub fn job_poll_timeout(&self) -> Duration {
+        self.job_poll_timeout_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_JOB_POLL_TIMEOUT)
+    }
+
+    /// Delay before the next poll after `failures` consecutive failed polls:
+    /// the poll interval doubled per failure, capped at the max backoff
+    pub fn job_poll_backoff(&self, failures: u32) -> Duration {
+        let interval = self.job_poll_interval();
+        if failures == 0 {
+            return interval;
+        }
+        let max_backoff = self
+            .job_poll_max_backoff_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_JOB_POLL_MAX_BACKOFF);
+        interval
+            .saturating_mul(2u32.saturating_pow(failures))
+            .min(max_backoff)
+            .max(interval)
+    }
+
+    /// Number of jobs that may execute at once; never less than 1
+    pub fn max_concurrent_jobs(&self) -> usize {
+        self.max_concurrent_jobs
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_JOBS)
+            .max(1)
+    }
+
+    /// Interval at which a partial batch of reputation records is sent
+    pub fn reputation_flush_interval(&self) -> Duration {
+        self.reputation_flush_interval_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_REPUTATION_FLUSH_INTERVAL)
+    }
+}
+
+/// Changes accepted by `Runtime::reconfigure` on a running node.
+///
+/// Hot-reloadable: `job_poll_interval_seconds`, `mana_tick_interval_seconds`,
+/// `fuel_pricing` and `mana_regeneration_policy`. They take effect from the
+/// next poll, tick or receipt.
+///
+/// Immutable: `node_did`, `storage_path` (the ledger/storage backend) and
+/// `key_path`. These may be given only if they equal the running value, so a
+/// full config file can be re-applied; any difference is rejected.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PartialRuntimeConfig {
+    pub job_poll_interval_seconds: Option<u64>,
+    pub mana_tick_interval_seconds: Option<u64>,
+    pub fuel_pricing: Option<FuelPricing>,
+    pub mana_regeneration_policy: Option<RegenerationPolicy>,
+
+    pub node_did: Option<String>,
+    pub storage_path: Option<PathBuf>,
+    pub key_path: Option<PathBuf>,
+}
+
+impl PartialRuntimeConfig {
+    /// Reject changes to fields that cannot be altered while running
+    pub fn check_immutable(&self, current: &RuntimeConfig) -> Result<(), RuntimeError> {
+        if self.node_did.as_ref().is_some_and(|did| *did != current.node_did) {
+            return Err(RuntimeError::ImmutableConfigField("node_did"));
+        }
+        if self.storage_path.as_ref().is_some_and(|path| *path != current.storage_path) {
+            return Err(RuntimeError::ImmutableConfigField("storage_path"));
+        }
+        if self.key_path.is_some() && self.key_path != current.key_path {
+            return Err(RuntimeError::ImmutableConfigField("key_path"));
+        }
+        Ok(())
+    }
+
+    /// Copy the hot-reloadable fields that are set into `config`
+    pub fn apply_to(&self, config: &mut RuntimeConfig) {
+        if let Some(secs) = self.job_poll_interval_seconds {
+            config.job_poll_interval_seconds = Some(secs);
+        }
+        if let Some(secs) = self.mana_tick_interval_seconds {
+            config.mana_tick_interval_seconds = Some(secs);
+        }
+        if let Some(pricing) = &self.fuel_pricing {
+            config.fuel_pricing = pricing.clone();
+        }
+        if let Some(policy) = &self.mana_regeneration_policy {
+            config.mana_regeneration_policy = Some(policy.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuel_pricing_rounds_fuel_up_and_adds_rates() {
+        let pricing = FuelPricing {
+            fuel_units_per_mana: 1_000,
+            mana_per_host_call: 2,
+            mana_per_io_byte: 1,
+        };
+        let metrics = ExecutionMetrics {
+            fuel_used: 2_500,
+            host_calls: 3,
+            io_bytes: 10,
+            ..Default::default()
+        };
+        assert_eq!(pricing.mana_cost(&metrics), 3 + 6 + 10);
+    }
+
+    #[test]
+    fn fuel_pricing_zero_divisor_ignores_fuel() {
+        let pricing = FuelPricing {
+            fuel_units_per_mana: 0,
+            mana_per_host_call: 0,
+            mana_per_io_byte: 0,
+        };
+        let metrics = ExecutionMetrics {
+            fuel_used: u64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(pricing.mana_cost(&metrics), 0);
+    }
+
+    #[test]
+    fn partial_config_rejects_immutable_changes() {
+        let current = RuntimeConfig {
+            node_did: "did:key:node".to_string(),
+            storage_path: PathBuf::from("/var/icn"),
+            ..Default::default()
+        };
+
+        let same = PartialRuntimeConfig {
+            node_did: Some(current.node_did.clone()),
+            storage_path: Some(current.storage_path.clone()),
+            ..Default::default()
+        };
+        assert!(same.check_immutable(&current).is_ok());
+
+        let moved = PartialRuntimeConfig {
+            storage_path: Some(PathBuf::from("/tmp/other")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            moved.check_immutable(&current),
+            Err(RuntimeError::ImmutableConfigField("storage_path"))
+        ));
+    }
+
+    #[test]
+    fn partial_config_applies_only_set_fields() {
+        let mut config = RuntimeConfig::default();
+        assert_eq!(config.job_poll_interval(), DEFAULT_JOB_POLL_INTERVAL);
+
+        PartialRuntimeConfig {
+            job_poll_interval_seconds: Some(1),
+            ..Default::default()
+        }
+        .apply_to(&mut config);
+
+        assert_eq!(config.job_poll_interval(), Duration::from_secs(1));
+        assert_eq!(config.fuel_pricing, FuelPricing::default());
+    }
+
+    #[test]
+    fn mana_ledger_backend_parses_and_defaults_to_memory() {
+        let config: RuntimeConfig =
+            toml::from_str("node_did = \"\"\nstorage_path = \"/tmp/node\"").unwrap();
+        assert_eq!(config.mana_ledger, ManaLedgerBackend::Memory);
+
+        let config: RuntimeConfig = toml::from_str(
+            "node_did = \"\"\nstorage_path = \"/tmp/node\"\nmana_ledger = \"sled\"",
+        )
+        .unwrap();
+        assert_eq!(config.mana_ledger, ManaLedgerBackend::Sled);
+    }
+
+    #[test]
+    fn receipt_limits_parse_and_default() {
+        let config: RuntimeConfig =
+            toml::from_str("node_did = \"\"\nstorage_path = \"/tmp/node\"").unwrap();
+        assert_eq!(
+            config.receipt_limits.max_host_calls,
+            ResourceLimits::default().max_host_calls
+        );
+
+        let config: RuntimeConfig = toml::from_str(
+            r#"
+node_did = ""
+storage_path = "/tmp/node"
+
+[receipt_limits]
+max_fuel = 1000
+max_host_calls = 5
+max_io_bytes = 64
+max_anchored_cids = 2
+max_job_submissions = 1
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.receipt_limits.max_host_calls, 5);
+        assert_eq!(config.receipt_limits.max_io_bytes, 64);
+        assert_eq!(config.receipt_limits.max_anchored_cids, 2);
+    }
+
+    #[test]
+    fn job_poll_backoff_doubles_up_to_the_cap() {
+        let config = RuntimeConfig {
+            job_poll_interval_seconds: Some(2),
+            job_poll_max_backoff_seconds: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(config.job_poll_backoff(0), Duration::from_secs(2));
+        assert_eq!(config.job_poll_backoff(1), Duration::from_secs(4));
+        assert_eq!(config.job_poll_backoff(2), Duration::from_secs(8));
+        assert_eq!(config.job_poll_backoff(3), Duration::from_secs(10));
+        assert_eq!(config.job_poll_backoff(u32::MAX), Duration::from_secs(10));
+    }
+}
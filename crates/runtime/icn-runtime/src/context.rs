@@ -10,16 +10,21 @@ use icn_identity::IdentityIndex;
 use icn_types::dag_store::{SharedDagStore, DagStore}; // Removed DagError, DagStoreBatch
 use icn_types::dag::DagNode; // Changed from: use icn_types::dag::{DagNode, DagNodeIdentifier};
 use icn_types::mesh::MeshJob;
+use icn_types::JobFailureReason;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use std::sync::Mutex;
 use tokio::sync::RwLock;
+use crate::job_registry::JobId;
 use crate::reputation_integration::ReputationScoringConfig;
 use crate::config::RuntimeConfig; // Added import for RuntimeConfig
 // use crate::RuntimeStorage; // Removed unused import
 use std::time::Duration;
 
+/// Messages that may wait in a job's P2P inbox before deliveries are refused
+pub const P2P_INBOX_CAPACITY: usize = 64;
+
 /// High-level execution state of the currently running job / stage.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ExecutionStatus {
@@ -28,6 +33,58 @@ pub enum ExecutionStatus {
     Failed,
 }
 
+/// Mana ceiling of a job lineage and how much of it has been spent so far.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineageManaBudget {
+    /// `MeshJob::max_total_mana` of the lineage's root job
+    pub max_total_mana: u64,
+    /// Mana charged for every attempt, retry and spawned child in the lineage
+    pub spent: u64,
+}
+
+/// Mana set aside on a DID's balance for a job that is still running.
+///
+/// Concurrent jobs of the same originator reserve against one balance, so
+/// together they cannot commit more mana than it holds. The reservation is
+/// released when this guard is dropped.
+#[derive(Debug)]
+pub struct ManaReservation {
+    reservations: Arc<Mutex<HashMap<Did, u64>>>,
+    did: Did,
+    amount: u64,
+}
+
+impl ManaReservation {
+    /// Mana held by this reservation
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+}
+
+impl Drop for ManaReservation {
+    fn drop(&mut self) {
+        let mut reservations = self.reservations.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(reserved) = reservations.get_mut(&self.did) {
+            *reserved = reserved.saturating_sub(self.amount);
+            if *reserved == 0 {
+                reservations.remove(&self.did);
+            }
+        }
+    }
+}
+
+/// `job_id` followed by its ancestors in `parents`, up to the root job
+fn lineage_in(parents: &HashMap<JobId, JobId>, job_id: &str) -> Vec<JobId> {
+    let mut lineage = vec![job_id.to_string()];
+    while let Some(parent) = parents.get(lineage.last().unwrap()) {
+        if lineage.contains(parent) {
+            break;
+        }
+        lineage.push(parent.clone());
+    }
+    lineage
+}
+
 /// Runtime context for execution environments
 ///
 /// Provides shared infrastructure and state needed across the runtime,
@@ -36,8 +93,8 @@ pub enum ExecutionStatus {
 /// trust bundles.
 #[derive(Clone)]
 pub struct RuntimeContext<L: ManaLedger + Send + Sync + 'static = InMemoryManaLedger> {
-    /// Shared DAG store for transaction and anchor operations
-    pub dag_store: Arc<SharedDagStore>,
+    /// DAG store for transaction and anchor operations
+    pub dag_store: Arc<dyn DagStore>,
 
     /// Shared DAG store for mesh receipts
     pub receipt_store: Arc<SharedDagStore>,
@@ -60,6 +117,18 @@ pub struct RuntimeContext<L: ManaLedger + Send + Sync + 'static = InMemoryManaLe
     /// Queue for mesh jobs submitted via host_submit_mesh_job awaiting P2P dispatch
     pub pending_mesh_jobs: Arc<Mutex<VecDeque<MeshJob>>>,
 
+    /// Parent job of every job spawned from within another job's execution
+    pub job_parents: Arc<Mutex<HashMap<JobId, JobId>>>,
+
+    /// Queued and running jobs of each lineage, keyed by the root job of each lineage
+    pub lineage_jobs: Arc<Mutex<HashMap<JobId, usize>>>,
+
+    /// Mana budgets of job lineages, keyed by the root job of each lineage
+    pub job_mana_budgets: Arc<Mutex<HashMap<JobId, LineageManaBudget>>>,
+
+    /// Mana reserved by running jobs, keyed by the DID whose balance backs them
+    pub mana_reservations: Arc<Mutex<HashMap<Did, u64>>>,
+
     /// Regenerating execution resource pools ("mana") by DID/org
     pub mana_manager: Arc<Mutex<ManaManager>>,
 
@@ -75,6 +144,9 @@ pub struct RuntimeContext<L: ManaLedger + Send + Sync + 'static = InMemoryManaLe
     /// Simple FIFO queue of raw interactive input messages pushed by the host.
     pub interactive_input_queue: Arc<Mutex<VecDeque<Vec<u8>>>>,
 
+    /// Senders into the P2P inbox of every running job that may receive messages
+    pub p2p_mailboxes: Arc<Mutex<HashMap<JobId, tokio::sync::mpsc::Sender<Vec<u8>>>>>,
+
     /// Current high-level execution status.
     pub execution_status: ExecutionStatus,
 
@@ -106,10 +178,257 @@ impl<L: ManaLedger + Send + Sync + 'static> RuntimeContext<L> {
         self.execution_status = status;
     }
 
-    pub fn dag_store(&self) -> Arc<SharedDagStore> {
+    pub fn dag_store(&self) -> Arc<dyn DagStore> {
         self.dag_store.clone()
     }
 
+    /// Queue a job spawned by another job, remembering its parent for lineage queries.
+    ///
+    /// The child is held until its parent's receipt is recorded with
+    /// [`Self::record_job_receipt`]. A child more than `max_submission_depth`
+    /// submissions below its root job is refused. The child's mana cost is
+    /// charged against its lineage budget; if that would exceed the root job's
+    /// `max_total_mana`, the job is not queued.
+    pub fn enqueue_spawned_job(
+        &self,
+        job: MeshJob,
+        max_submission_depth: u32,
+    ) -> Result<(), JobFailureReason> {
+        if let Some(parent) = &job.parent_job_id {
+            self.job_parents
+                .lock()
+                .unwrap()
+                .insert(job.job_id.clone(), parent.clone());
+        }
+        if self.submission_depth(&job.job_id) > max_submission_depth {
+            self.job_parents.lock().unwrap().remove(&job.job_id);
+            return Err(JobFailureReason::ResourceLimitExceeded);
+        }
+        if let Err(reason) = self.charge_lineage_mana(&job.job_id, crate::estimated_mana_cost(&job.params)) {
+            self.job_parents.lock().unwrap().remove(&job.job_id);
+            return Err(reason);
+        }
+        self.track_lineage_job(&job.job_id);
+        self.pending_mesh_jobs.lock().unwrap().push_back(job);
+        Ok(())
+    }
+
+    /// Queue another attempt of a failed job, charging it against its lineage budget
+    pub fn retry_job(&self, job: MeshJob) -> Result<(), JobFailureReason> {
+        self.charge_lineage_mana(&job.job_id, crate::estimated_mana_cost(&job.params))?;
+        self.track_lineage_job(&job.job_id);
+        self.pending_mesh_jobs.lock().unwrap().push_back(job);
+        Ok(())
+    }
+
+    /// Take the oldest job this runtime queued itself that is ready to run: another
+    /// attempt of a job that failed, or a child spawned by a job it executed once
+    /// that job's receipt has been recorded
+    pub fn next_queued_job(&self) -> Option<MeshJob> {
+        let parents = self.job_parents.lock().unwrap();
+        let mut queue = self.pending_mesh_jobs.lock().unwrap();
+        let ready = queue
+            .iter()
+            .position(|job| job.origin_receipt_cid.is_some() || !parents.contains_key(&job.job_id))?;
+        queue.remove(ready)
+    }
+
+    /// Count a job polled from the mesh job service as running in its lineage.
+    /// Jobs this runtime queued itself were counted when they were queued.
+    pub fn begin_polled_job(&self, job_id: &str) {
+        self.track_lineage_job(job_id);
+    }
+
+    /// Stamp the CID of `parent_job_id`'s anchored receipt on the children it
+    /// queued, releasing them to run
+    pub fn record_job_receipt(&self, parent_job_id: &str, receipt_cid: &str) {
+        for job in self.pending_mesh_jobs.lock().unwrap().iter_mut() {
+            if job.parent_job_id.as_deref() == Some(parent_job_id) && job.origin_receipt_cid.is_none() {
+                job.origin_receipt_cid = Some(receipt_cid.to_string());
+            }
+        }
+    }
+
+    /// Mark `job_id` as no longer running.
+    ///
+    /// Children it queued are dropped unless its receipt was recorded; another
+    /// attempt of the job spawns them again. Once no job of the lineage is
+    /// queued or running, the lineage's parent links are forgotten.
+    pub fn finish_job(&self, job_id: &str) {
+        let root = self.job_lineage(job_id).pop().unwrap_or_else(|| job_id.to_string());
+        let mut dropped = 0;
+        self.pending_mesh_jobs.lock().unwrap().retain(|job| {
+            let orphaned = job.parent_job_id.as_deref() == Some(job_id) && job.origin_receipt_cid.is_none();
+            dropped += usize::from(orphaned);
+            !orphaned
+        });
+
+        let mut lineage_jobs = self.lineage_jobs.lock().unwrap();
+        let outstanding = lineage_jobs.entry(root.clone()).or_insert(0);
+        *outstanding = outstanding.saturating_sub(dropped + 1);
+        if *outstanding > 0 {
+            return;
+        }
+        lineage_jobs.remove(&root);
+        drop(lineage_jobs);
+
+        let mut parents = self.job_parents.lock().unwrap();
+        let finished: Vec<JobId> = parents
+            .keys()
+            .filter(|id| lineage_in(&parents, id).last() == Some(&root))
+            .cloned()
+            .collect();
+        for id in finished {
+            parents.remove(&id);
+        }
+    }
+
+    fn track_lineage_job(&self, job_id: &str) {
+        let root = self.job_lineage(job_id).pop().unwrap_or_else(|| job_id.to_string());
+        *self.lineage_jobs.lock().unwrap().entry(root).or_insert(0) += 1;
+    }
+
+    /// Start tracking the budget of a directly submitted job that sets `max_total_mana`.
+    ///
+    /// The first attempt is always admitted and charged; the ceiling gates the
+    /// retries and child jobs that follow. Jobs already tracked are left untouched.
+    pub fn begin_job_budget(&self, job: &MeshJob) {
+        let (None, Some(max_total_mana)) = (&job.parent_job_id, job.max_total_mana) else {
+            return;
+        };
+        self.job_mana_budgets
+            .lock()
+            .unwrap()
+            .entry(job.job_id.clone())
+            .or_insert(LineageManaBudget {
+                max_total_mana,
+                spent: crate::estimated_mana_cost(&job.params),
+            });
+    }
+
+    /// Charge `amount` mana to the budget of `job_id`'s lineage.
+    ///
+    /// Lineages whose root set no `max_total_mana` are unbounded. Fails with
+    /// `JobFailureReason::ResourceLimitExceeded`, charging nothing, if the
+    /// budget cannot cover `amount`.
+    pub fn charge_lineage_mana(&self, job_id: &str, amount: u64) -> Result<(), JobFailureReason> {
+        let root = self.job_lineage(job_id).pop().unwrap_or_else(|| job_id.to_string());
+        let mut budgets = self.job_mana_budgets.lock().unwrap();
+        let Some(budget) = budgets.get_mut(&root) else {
+            return Ok(());
+        };
+        match budget.spent.checked_add(amount) {
+            Some(spent) if spent <= budget.max_total_mana => {
+                budget.spent = spent;
+                Ok(())
+            }
+            _ => Err(JobFailureReason::ResourceLimitExceeded),
+        }
+    }
+
+    /// Mana left in the budget of `job_id`'s lineage, or `None` if it is unbounded
+    pub fn remaining_lineage_mana(&self, job_id: &str) -> Option<u64> {
+        let root = self.job_lineage(job_id).pop()?;
+        self.job_mana_budgets
+            .lock()
+            .unwrap()
+            .get(&root)
+            .map(|budget| budget.max_total_mana.saturating_sub(budget.spent))
+    }
+
+    /// Reserve `job`'s estimated mana cost on its originator's balance.
+    ///
+    /// Fails with `JobFailureReason::ResourceLimitExceeded` if the balance,
+    /// less what running jobs have already reserved, cannot cover the cost.
+    /// Originators without a mana state on this node's ledger are not metered
+    /// here; their reservation is tracked but never refused.
+    pub async fn reserve_job_mana(&self, job: &MeshJob) -> Result<ManaReservation, JobFailureReason> {
+        let did = job.originator_did.clone();
+        let amount = crate::estimated_mana_cost(&job.params);
+        let balance = self
+            .mana_repository
+            .ledger()
+            .get_mana_state(&did)
+            .await
+            .map_err(|e| JobFailureReason::ExecutionError(format!("Failed to read mana balance of {}: {}", did, e)))?
+            .map(|state| state.current_mana);
+
+        let mut reservations = self.mana_reservations.lock().unwrap_or_else(|e| e.into_inner());
+        let reserved = reservations.entry(did.clone()).or_insert(0);
+        if let Some(balance) = balance {
+            if reserved.saturating_add(amount) > balance {
+                if *reserved == 0 {
+                    reservations.remove(&did);
+                }
+                return Err(JobFailureReason::ResourceLimitExceeded);
+            }
+        }
+        *reserved = reserved.saturating_add(amount);
+        Ok(ManaReservation {
+            reservations: self.mana_reservations.clone(),
+            did,
+            amount,
+        })
+    }
+
+    /// Mana currently reserved by running jobs against `did`'s balance
+    pub fn reserved_mana(&self, did: &Did) -> u64 {
+        self.mana_reservations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(did)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The chain of jobs leading to `job_id`: the job itself, then its parent,
+    /// and so on up to the job that was submitted directly.
+    pub fn job_lineage(&self, job_id: &str) -> Vec<JobId> {
+        lineage_in(&self.job_parents.lock().unwrap(), job_id)
+    }
+
+    /// How many submissions below the job that was submitted directly
+    /// `job_id` is; 0 for a job no other job spawned
+    pub fn submission_depth(&self, job_id: &str) -> u32 {
+        (self.job_lineage(job_id).len() - 1) as u32
+    }
+
+    /// Open `job_id`'s P2P inbox, replacing any earlier one. Messages passed to
+    /// [`Self::deliver_p2p_message`] arrive on the returned receiver until
+    /// [`Self::close_p2p_inbox`] is called.
+    pub fn open_p2p_inbox(&self, job_id: &str) -> tokio::sync::mpsc::Receiver<Vec<u8>> {
+        let (tx, rx) = tokio::sync::mpsc::channel(P2P_INBOX_CAPACITY);
+        self.p2p_mailboxes
+            .lock()
+            .unwrap()
+            .insert(job_id.to_string(), tx);
+        rx
+    }
+
+    /// Stop delivering messages to `job_id`
+    pub fn close_p2p_inbox(&self, job_id: &str) {
+        self.p2p_mailboxes.lock().unwrap().remove(job_id);
+    }
+
+    /// Queue `message` for `job_id`'s next `host_p2p_receive_message`.
+    ///
+    /// Fails with `JobFailureReason::NotFound` if the job has no open inbox and
+    /// with `JobFailureReason::ResourceLimitExceeded` if its inbox is full.
+    pub fn deliver_p2p_message(
+        &self,
+        job_id: &str,
+        message: Vec<u8>,
+    ) -> Result<(), JobFailureReason> {
+        let mailboxes = self.p2p_mailboxes.lock().unwrap();
+        let tx = mailboxes.get(job_id).ok_or(JobFailureReason::NotFound)?;
+        tx.try_send(message).map_err(|e| match e {
+            tokio::sync::mpsc::error::TrySendError::Full(_) => {
+                JobFailureReason::ResourceLimitExceeded
+            }
+            tokio::sync::mpsc::error::TrySendError::Closed(_) => JobFailureReason::NotFound,
+        })
+    }
+
     pub fn identity(&self) -> Option<&KeyPair> {
         self.identity.as_ref()
     }
@@ -184,11 +503,16 @@ impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContext<L> {
             economics: Arc::new(Economics::new(ResourceAuthorizationPolicy::default())),
             resource_ledger: Arc::new(RwLock::new(HashMap::new())),
             pending_mesh_jobs: Arc::new(Mutex::new(VecDeque::new())),
+            job_parents: Arc::new(Mutex::new(HashMap::new())),
+            lineage_jobs: Arc::new(Mutex::new(HashMap::new())),
+            job_mana_budgets: Arc::new(Mutex::new(HashMap::new())),
+            mana_reservations: Arc::new(Mutex::new(HashMap::new())),
             mana_manager: Arc::new(Mutex::new(ManaManager::new())),
             mana_regenerator: None,
             policy_enforcer: Arc::new(ResourcePolicyEnforcer::new(boxed_mana_repo_adapter_for_enforcer)),
             mana_repository: mana_repo_adapter,
             interactive_input_queue: Arc::new(Mutex::new(VecDeque::new())),
+            p2p_mailboxes: Arc::new(Mutex::new(HashMap::new())),
             execution_status: ExecutionStatus::Running,
             identity_index: None,
             identity: None,
@@ -200,7 +524,7 @@ impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContext<L> {
     }
 
     /// Create a new context with a specific DAG store
-    pub fn with_dag_store(dag_store: Arc<SharedDagStore>) -> Self {
+    pub fn with_dag_store(dag_store: Arc<dyn DagStore>) -> Self {
         let default_ledger = Arc::new(L::default());
         let mana_repo_adapter = Arc::new(ManaRepositoryAdapter::new(default_ledger.clone()));
         let boxed_mana_repo_adapter_for_enforcer = Box::new(ManaRepositoryAdapter::new(default_ledger));
@@ -214,11 +538,16 @@ impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContext<L> {
             economics: Arc::new(Economics::new(ResourceAuthorizationPolicy::default())),
             resource_ledger: Arc::new(RwLock::new(HashMap::new())),
             pending_mesh_jobs: Arc::new(Mutex::new(VecDeque::new())),
+            job_parents: Arc::new(Mutex::new(HashMap::new())),
+            lineage_jobs: Arc::new(Mutex::new(HashMap::new())),
+            job_mana_budgets: Arc::new(Mutex::new(HashMap::new())),
+            mana_reservations: Arc::new(Mutex::new(HashMap::new())),
             mana_manager: Arc::new(Mutex::new(ManaManager::new())),
             mana_regenerator: None,
             policy_enforcer: Arc::new(ResourcePolicyEnforcer::new(boxed_mana_repo_adapter_for_enforcer)),
             mana_repository: mana_repo_adapter,
             interactive_input_queue: Arc::new(Mutex::new(VecDeque::new())),
+            p2p_mailboxes: Arc::new(Mutex::new(HashMap::new())),
             execution_status: ExecutionStatus::Running,
             identity_index: None,
             identity: None,
@@ -243,7 +572,7 @@ impl<L: ManaLedger + Send + Sync + 'static + Default> Default for RuntimeContext
 
 /// Builder pattern for RuntimeContext
 pub struct RuntimeContextBuilder<L: ManaLedger + Send + Sync + 'static = InMemoryManaLedger> {
-    dag_store: Option<Arc<SharedDagStore>>,
+    dag_store: Option<Arc<dyn DagStore>>,
     receipt_store: Option<Arc<SharedDagStore>>,
     federation_id: Option<String>,
     executor_id: Option<String>,
@@ -260,7 +589,7 @@ pub struct RuntimeContextBuilder<L: ManaLedger + Send + Sync + 'static = InMemor
     mana_repository: Option<Arc<ManaRepositoryAdapter<L>>>,
 }
 
-impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContextBuilder<L> {
+impl<L: ManaLedger + Send + Sync + 'static> RuntimeContextBuilder<L> {
     /// Create a new builder
     pub fn new() -> Self {
         Self {
@@ -283,7 +612,7 @@ impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContextBuilder<L> {
     }
 
     /// Set the DAG store
-    pub fn with_dag_store(mut self, dag_store: Arc<SharedDagStore>) -> Self {
+    pub fn with_dag_store(mut self, dag_store: Arc<dyn DagStore>) -> Self {
         self.dag_store = Some(dag_store);
         self
     }
@@ -370,11 +699,12 @@ impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContextBuilder<L> {
         self
     }
 
-    /// Build the RuntimeContext
-    pub fn build(self) -> RuntimeContext<L> {
-        let default_ledger_for_builder = Arc::new(L::default());
-        let default_mana_repo_adapter_for_builder = Arc::new(ManaRepositoryAdapter::new(default_ledger_for_builder.clone()));
-        let default_boxed_repo_for_enforcer = Box::new(ManaRepositoryAdapter::new(default_ledger_for_builder));
+    /// Build the RuntimeContext, backing the default mana repository and
+    /// policy enforcer with `ledger`. Use this for ledgers without a `Default`,
+    /// such as a persistent ledger opened from disk.
+    pub fn build_with_ledger(self, ledger: Arc<L>) -> RuntimeContext<L> {
+        let default_mana_repo_adapter_for_builder = Arc::new(ManaRepositoryAdapter::new(ledger.clone()));
+        let default_boxed_repo_for_enforcer = Box::new(ManaRepositoryAdapter::new(ledger));
         let default_policy_enforcer_for_builder = Arc::new(ResourcePolicyEnforcer::new(default_boxed_repo_for_enforcer));
 
         RuntimeContext {
@@ -386,11 +716,16 @@ impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContextBuilder<L> {
             economics: self.economics.unwrap_or_else(|| Arc::new(Economics::new(ResourceAuthorizationPolicy::default()))),
             resource_ledger: Arc::new(RwLock::new(HashMap::new())),
             pending_mesh_jobs: Arc::new(Mutex::new(VecDeque::new())),
+            job_parents: Arc::new(Mutex::new(HashMap::new())),
+            lineage_jobs: Arc::new(Mutex::new(HashMap::new())),
+            job_mana_budgets: Arc::new(Mutex::new(HashMap::new())),
+            mana_reservations: Arc::new(Mutex::new(HashMap::new())),
             mana_manager: Arc::new(Mutex::new(ManaManager::new())),
             mana_regenerator: self.mana_regenerator,
             policy_enforcer: self.policy_enforcer.unwrap_or(default_policy_enforcer_for_builder),
             mana_repository: self.mana_repository.unwrap_or(default_mana_repo_adapter_for_builder),
             interactive_input_queue: Arc::new(Mutex::new(VecDeque::new())),
+            p2p_mailboxes: Arc::new(Mutex::new(HashMap::new())),
             execution_status: ExecutionStatus::Running,
             identity_index: self.identity_index,
             identity: self.identity,
@@ -402,6 +737,13 @@ impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContextBuilder<L> {
     }
 }
 
+impl<L: ManaLedger + Send + Sync + 'static + Default> RuntimeContextBuilder<L> {
+    /// Build the RuntimeContext
+    pub fn build(self) -> RuntimeContext<L> {
+        self.build_with_ledger(Arc::new(L::default()))
+    }
+}
+
 impl<L: ManaLedger + Send + Sync + Default + 'static> Default for RuntimeContextBuilder<L> {
     fn default() -> Self {
         Self::new()
@@ -473,8 +815,13 @@ impl RuntimeContext<InMemoryManaLedger> {
             economics: Arc::new(Economics::new(ResourceAuthorizationPolicy::default())),
             resource_ledger: Arc::new(RwLock::new(HashMap::new())),
             pending_mesh_jobs: Arc::new(Mutex::new(VecDeque::new())),
+            job_parents: Arc::new(Mutex::new(HashMap::new())),
+            lineage_jobs: Arc::new(Mutex::new(HashMap::new())),
+            job_mana_budgets: Arc::new(Mutex::new(HashMap::new())),
+            mana_reservations: Arc::new(Mutex::new(HashMap::new())),
             mana_manager: Arc::new(Mutex::new(ManaManager::new())),
             interactive_input_queue: Arc::new(Mutex::new(VecDeque::new())),
+            p2p_mailboxes: Arc::new(Mutex::new(HashMap::new())),
             execution_status: ExecutionStatus::Running,
             reputation_service_url: None,
             mesh_job_service_url: None,
@@ -0,0 +1,121 @@
+//! Host ABI capabilities, used to give each class of job only the host
+//! functions it needs.
+
+use host_abi::HostAbiError;
+use std::collections::BTreeSet;
+
+/// A group of host functions that a host environment can expose to a guest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HostCapability {
+    /// `log_message` and the debug/TODO logging calls
+    Logging,
+    /// Job metadata, workflow stage state and progress reporting
+    JobContext,
+    /// Exchanging messages with the submitter while the job runs
+    Interactive,
+    /// Recording resource and mana usage against the caller
+    ResourceRecording,
+    /// Reading data and anchoring data and receipts to the DAG
+    DataAnchoring,
+    /// Sections, properties, proposals, conditionals and events
+    Governance,
+    /// Minting tokens; a mint still needs a quorum authorization
+    Minting,
+    /// Transferring tokens between accounts
+    TokenTransfer,
+    /// Submitting child mesh jobs
+    MeshJobSubmission,
+}
+
+impl HostCapability {
+    /// Every capability, in declaration order
+    pub const ALL: [HostCapability; 9] = [
+        HostCapability::Logging,
+        HostCapability::JobContext,
+        HostCapability::Interactive,
+        HostCapability::ResourceRecording,
+        HostCapability::DataAnchoring,
+        HostCapability::Governance,
+        HostCapability::Minting,
+        HostCapability::TokenTransfer,
+        HostCapability::MeshJobSubmission,
+    ];
+}
+
+/// The set of capabilities enabled for one host environment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostCapabilities(BTreeSet<HostCapability>);
+
+impl HostCapabilities {
+    /// No host functions at all
+    pub fn none() -> Self {
+        Self(BTreeSet::new())
+    }
+
+    /// The full host ABI
+    pub fn all() -> Self {
+        Self(HostCapability::ALL.into_iter().collect())
+    }
+
+    /// What a compute job needs: logging, job and workflow state, interaction,
+    /// resource recording and spawning child jobs. No governance, minting,
+    /// token transfers or DAG anchoring.
+    pub fn compute() -> Self {
+        Self::none()
+            .with(HostCapability::Logging)
+            .with(HostCapability::JobContext)
+            .with(HostCapability::Interactive)
+            .with(HostCapability::ResourceRecording)
+            .with(HostCapability::MeshJobSubmission)
+    }
+
+    /// What a governance proposal needs: the full host ABI
+    pub fn governance() -> Self {
+        Self::all()
+    }
+
+    /// Enable `capability`
+    pub fn with(mut self, capability: HostCapability) -> Self {
+        self.0.insert(capability);
+        self
+    }
+
+    /// Disable `capability`
+    pub fn without(mut self, capability: HostCapability) -> Self {
+        self.0.remove(&capability);
+        self
+    }
+
+    pub fn contains(&self, capability: HostCapability) -> bool {
+        self.0.contains(&capability)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = HostCapability> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Fail with `HostAbiError::Unauthorized` unless `capability` is enabled
+    pub fn require(&self, capability: HostCapability) -> Result<(), HostAbiError> {
+        if self.contains(capability) {
+            Ok(())
+        } else {
+            Err(HostAbiError::Unauthorized(format!(
+                "host capability {:?} is not enabled for this job",
+                capability
+            )))
+        }
+    }
+}
+
+/// The full host ABI, matching environments built without explicit capabilities
+impl Default for HostCapabilities {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FromIterator<HostCapability> for HostCapabilities {
+    fn from_iter<I: IntoIterator<Item = HostCapability>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
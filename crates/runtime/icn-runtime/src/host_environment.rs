@@ -1,14 +1,17 @@
 use crate::context::RuntimeContext;
+use crate::host_capabilities::{HostCapabilities, HostCapability};
 use crate::job_execution_context::JobExecutionContext;
 use anyhow::{anyhow, Result};
-use icn_economics::{ResourceType, ResourceRepository, ScopedResourceToken};
-use icn_identity::{Did, ScopeKey};
+use icn_economics::{mana::ManaLedger, mint_authorization_payload, ResourceType, ResourceRepository, ScopedResourceToken};
+use icn_economics::economics::EconomicsError;
+use icn_identity::{Did, QuorumProof, ScopeKey};
 use host_abi::{
-    HostAbiError, MeshHostAbi,
+    HostAbiError, MeshHostAbi, P2P_RECEIVE_TIMEOUT,
 };
 use icn_types::org::{CommunityId, CooperativeId};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
 use wasmtime::{Caller, Extern, Memory as WasmtimeMemory, AsContextMut, StoreContextMut};
 use std::marker::PhantomData;
 use std::str::FromStr;
@@ -21,7 +24,7 @@ use std::str::FromStr;
 
 #[cfg(test)]
 use icn_economics::{
-    mana::{ManaError, ManaLedger}, // ManaLedger might not be used directly but good for context
+    mana::ManaError,
     PolicyEnforcer, // Corrected path as per linter suggestion
     // ScopedResourceToken,    // Struct for tokens // Removed duplicate
     // ResourceRepository,   // Trait for get_usage, record_usage // Removed duplicate
@@ -36,9 +39,41 @@ pub struct ConcreteHostEnvironment<T_param: Send + Sync + 'static> {
     pub is_governance: bool,
     pub coop_id: Option<CooperativeId>,
     pub community_id: Option<CommunityId>,
+    /// Federation quorum's approval for this execution to mint tokens
+    pub mint_authorization: Option<QuorumProof>,
+    /// Host functions this execution may call; others fail with `HostAbiError::Unauthorized`
+    pub capabilities: HostCapabilities,
+    /// Messages delivered to this execution over the mesh, if it may receive any
+    pub p2p_inbox: Option<Arc<Mutex<P2pInbox>>>,
     _phantom: PhantomData<T_param>,
 }
 
+/// Incoming P2P messages for one execution.
+///
+/// A message that did not fit the guest's buffer is kept here and returned by the
+/// next receive, so the guest can retry with a larger buffer without losing it.
+pub struct P2pInbox {
+    rx: mpsc::Receiver<Vec<u8>>,
+    pending: Option<Vec<u8>>,
+}
+
+impl P2pInbox {
+    pub fn new(rx: mpsc::Receiver<Vec<u8>>) -> Self {
+        Self { rx, pending: None }
+    }
+}
+
+/// Outcome of a P2P receive that did not fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum P2pReceive {
+    /// The next message, removed from the inbox
+    Message(Vec<u8>),
+    /// The next message needs this many bytes; it stays queued
+    TooLarge(usize),
+    /// No message arrived within the timeout
+    TimedOut,
+}
+
 impl<T_param: Send + Sync + 'static> ConcreteHostEnvironment<T_param> {
     pub fn new(
         ctx: Arc<Mutex<JobExecutionContext>>,
@@ -52,6 +87,9 @@ impl<T_param: Send + Sync + 'static> ConcreteHostEnvironment<T_param> {
             is_governance: false,
             coop_id: None,
             community_id: None,
+            mint_authorization: None,
+            capabilities: HostCapabilities::all(),
+            p2p_inbox: None,
             _phantom: PhantomData,
         }
     }
@@ -74,6 +112,9 @@ impl<T_param: Send + Sync + 'static> ConcreteHostEnvironment<T_param> {
             is_governance: false,
             coop_id: None,
             community_id: None,
+            mint_authorization: None,
+            capabilities: HostCapabilities::all(),
+            p2p_inbox: None,
             _phantom: PhantomData::<T_param>,
         }
     }
@@ -90,6 +131,9 @@ impl<T_param: Send + Sync + 'static> ConcreteHostEnvironment<T_param> {
             is_governance: true,
             coop_id: None,
             community_id: None,
+            mint_authorization: None,
+            capabilities: HostCapabilities::all(),
+            p2p_inbox: None,
             _phantom: PhantomData,
         }
     }
@@ -104,6 +148,56 @@ impl<T_param: Send + Sync + 'static> ConcreteHostEnvironment<T_param> {
         self
     }
 
+    /// Attach a quorum proof over `mint_authorization_payload(job_id)` allowing this
+    /// execution to mint tokens
+    pub fn with_mint_authorization(mut self, proof: QuorumProof) -> Self {
+        self.mint_authorization = Some(proof);
+        self
+    }
+
+    /// Deliver messages sent on the other end of `rx` to `host_p2p_receive_message`
+    pub fn with_p2p_inbox(mut self, rx: mpsc::Receiver<Vec<u8>>) -> Self {
+        self.p2p_inbox = Some(Arc::new(Mutex::new(P2pInbox::new(rx))));
+        self
+    }
+
+    /// Start building a host environment that exposes only selected capabilities
+    pub fn builder(
+        ctx: Arc<Mutex<JobExecutionContext>>,
+        caller_did: Did,
+        runtime_ctx: Arc<RuntimeContext>,
+    ) -> HostEnvironmentBuilder<T_param> {
+        HostEnvironmentBuilder {
+            env: Self {
+                capabilities: HostCapabilities::none(),
+                ..Self::new(ctx, caller_did, runtime_ctx)
+            },
+        }
+    }
+
+    /// Fail with `HostAbiError::Unauthorized` unless `capability` is enabled
+    pub fn require_capability(&self, capability: HostCapability) -> Result<(), HostAbiError> {
+        self.capabilities.require(capability)
+    }
+
+    /// Check that this is a governance execution whose mint authorization was
+    /// signed by a quorum of the federation's trusted signers.
+    pub fn authorize_mint(&self, execution_id: &str) -> Result<(), HostAbiError> {
+        if !self.is_governance {
+            return Err(HostAbiError::NotPermitted);
+        }
+        let proof = self
+            .mint_authorization
+            .as_ref()
+            .ok_or(HostAbiError::NotPermitted)?;
+        let validator = self.rt.trust_validator().ok_or_else(|| {
+            HostAbiError::InvalidState("No trust validator to check mint authorization".to_string())
+        })?;
+        validator
+            .verify_quorum(&mint_authorization_payload(execution_id), proof)
+            .map_err(|_| HostAbiError::NotPermitted)
+    }
+
     /// Determine the accounting scope key for mana operations.
     pub fn scope_key(&self) -> ScopeKey {
         // 1) If explicit coop/community overrides exist, honour them first.
@@ -122,6 +216,37 @@ impl<T_param: Send + Sync + 'static> ConcreteHostEnvironment<T_param> {
         }
     }
 
+    /// Take the next incoming P2P message if it is at most `max_len` bytes.
+    ///
+    /// Waits up to `timeout_ms` for one to arrive; `0` polls without waiting. A
+    /// message longer than `max_len` stays queued and its size is returned instead.
+    pub async fn receive_p2p_message(&self, timeout_ms: u32, max_len: u32) -> Result<P2pReceive, HostAbiError> {
+        self.require_capability(HostCapability::Interactive)?;
+        let inbox = self.p2p_inbox.as_ref().ok_or(HostAbiError::NotSupported)?;
+        let mut inbox = inbox.lock().await;
+        let message = match inbox.pending.take() {
+            Some(message) => message,
+            None if timeout_ms == 0 => match inbox.rx.try_recv() {
+                Ok(message) => message,
+                Err(mpsc::error::TryRecvError::Empty) => return Ok(P2pReceive::TimedOut),
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    return Err(HostAbiError::ChannelClosed("P2P inbox closed".to_string()))
+                }
+            },
+            None => match tokio::time::timeout(Duration::from_millis(timeout_ms as u64), inbox.rx.recv()).await {
+                Ok(Some(message)) => message,
+                Ok(None) => return Err(HostAbiError::ChannelClosed("P2P inbox closed".to_string())),
+                Err(_) => return Ok(P2pReceive::TimedOut),
+            },
+        };
+        if message.len() > max_len as usize {
+            let needed = message.len();
+            inbox.pending = Some(message);
+            return Ok(P2pReceive::TooLarge(needed));
+        }
+        Ok(P2pReceive::Message(message))
+    }
+
     pub fn check_resource_authorization(&self, _rt_type: ResourceType, _amt: u64) -> Result<i32, HostAbiError> {
         // TODO: Implement actual resource authorization logic
         Err(HostAbiError::NotSupported)
@@ -145,6 +270,7 @@ impl<T_param: Send + Sync + 'static> ConcreteHostEnvironment<T_param> {
 
     /// Anchor a signed execution receipt to the DAG and broadcast an announcement.
     pub async fn anchor_receipt(&self, _receipt: ()) -> Result<(), HostAbiError> {
+        self.require_capability(HostCapability::DataAnchoring)?;
         // Placeholder implementation. In a real scenario, this would interact with
         // the DAG store, potentially via the RuntimeContext or a dedicated service.
         // For now, assume success or a generic error if it were to fail.
@@ -158,6 +284,57 @@ impl<T_param: Send + Sync + 'static> ConcreteHostEnvironment<T_param> {
     // REMOVED HELPERS FROM HERE
 }
 
+/// Builds a [`ConcreteHostEnvironment`] with only the capabilities it is given.
+///
+/// Starts with no capabilities, so every host function the job class needs
+/// must be enabled explicitly, e.g. `.capabilities(HostCapabilities::compute())`.
+pub struct HostEnvironmentBuilder<T_param: Send + Sync + 'static> {
+    env: ConcreteHostEnvironment<T_param>,
+}
+
+impl<T_param: Send + Sync + 'static> HostEnvironmentBuilder<T_param> {
+    /// Replace the enabled capabilities with `capabilities`
+    pub fn capabilities(mut self, capabilities: HostCapabilities) -> Self {
+        self.env.capabilities = capabilities;
+        self
+    }
+
+    /// Enable one more capability
+    pub fn enable(mut self, capability: HostCapability) -> Self {
+        self.env.capabilities = self.env.capabilities.with(capability);
+        self
+    }
+
+    /// Mark this as a governance execution. Capabilities are left unchanged.
+    pub fn governance(mut self) -> Self {
+        self.env.is_governance = true;
+        self
+    }
+
+    pub fn organization(
+        mut self,
+        coop_id: Option<CooperativeId>,
+        community_id: Option<CommunityId>,
+    ) -> Self {
+        self.env = self.env.with_organization(coop_id, community_id);
+        self
+    }
+
+    pub fn mint_authorization(mut self, proof: QuorumProof) -> Self {
+        self.env = self.env.with_mint_authorization(proof);
+        self
+    }
+
+    pub fn p2p_inbox(mut self, rx: mpsc::Receiver<Vec<u8>>) -> Self {
+        self.env = self.env.with_p2p_inbox(rx);
+        self
+    }
+
+    pub fn build(self) -> ConcreteHostEnvironment<T_param> {
+        self.env
+    }
+}
+
 // --- Standalone Helper memory access functions ---
 
     /// Helper to safely obtain the linear memory exported by the guest module.
@@ -184,7 +361,11 @@ pub fn read_string_from_mem_ctx<T_param: Send + Sync + 'static>(
         .map_err(|e| HostAbiError::DataEncodingError(format!("UTF-8 conversion failed: {}", e)))
 }
 
-/// Write a UTF-8 string `s` into guest memory buffer, using StoreContextMut and pre-fetched Memory
+/// Write a UTF-8 string `s` and a null terminator into guest memory buffer, using StoreContextMut and pre-fetched Memory
+///
+/// Follows the [`host_abi::copy_string_to_c_buf`] convention: returns the string's length,
+/// or, without writing anything, the negative [`host_abi::required_buffer_size`] if the
+/// buffer is too small.
 pub fn write_string_to_mem_ctx<T_param: Send + Sync + 'static>(
     store_ctx: &mut StoreContextMut<'_, ConcreteHostEnvironment<T_param>>,
     memory: &WasmtimeMemory,
@@ -192,13 +373,16 @@ pub fn write_string_to_mem_ctx<T_param: Send + Sync + 'static>(
         ptr: u32,
         len: u32,
 ) -> Result<i32, HostAbiError> {
-        let bytes = s.as_bytes();
-        if bytes.len() > len as usize {
-        return Err(HostAbiError::BufferTooSmall("String too large for buffer".to_string()));
+        let required = host_abi::required_buffer_size(s);
+        if required > len {
+        return Ok(-(required as i32));
         }
-    memory.write(store_ctx, ptr as usize, bytes)
+    let mut bytes = Vec::with_capacity(required as usize);
+    bytes.extend_from_slice(s.as_bytes());
+    bytes.push(0);
+    memory.write(store_ctx, ptr as usize, &bytes)
         .map_err(|e| HostAbiError::MemoryAccessError(format!("Memory write failed: {}", e)))?;
-        Ok(bytes.len() as i32)
+        Ok(s.len() as i32)
     }
 
 /// Read a raw byte slice from guest memory, using StoreContextMut and pre-fetched Memory
@@ -240,6 +424,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         title_ptr: u32,
         title_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let kind = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, kind_ptr, kind_len)?;
@@ -257,6 +442,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         &self,
         _caller: Caller<'_, ConcreteHostEnvironment<T_param>>,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let mut ctx = self.ctx.lock().await;
         ctx.end_section()?;
         Ok(0)
@@ -270,6 +456,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         value_json_ptr: u32,
         value_json_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let key = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, key_ptr, key_len)?;
@@ -287,6 +474,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         data_ref_ptr: u32,
         data_ref_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::DataAnchoring)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let path = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, path_ptr, path_len)?;
@@ -304,6 +492,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         args_payload_ptr: u32,
         args_payload_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let fn_name = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, fn_name_ptr, fn_name_len)?;
@@ -323,6 +512,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         version_ptr: u32,
         version_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let id = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, id_ptr, id_len)?;
@@ -344,6 +534,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         data_json_ptr: u32,
         data_json_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Minting)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let resource_type_str = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, resource_type_ptr, resource_type_len)?;
@@ -355,8 +546,36 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         };
         let recipient_did = Did::from_str(&recipient_did_str)
             .map_err(|e| HostAbiError::InvalidDid(format!("Invalid recipient DID: {}, error: {}", recipient_did_str, e)))?;
+        let resource_type = ResourceType::from_str(&resource_type_str)
+            .map_err(|e| HostAbiError::InvalidParameter(e.to_string()))?;
         let mut ctx = self.ctx.lock().await;
+        self.authorize_mint(&ctx.job_id)?;
+        let pending = self
+            .rt
+            .economics
+            .begin_mint(resource_type, amount)
+            .await
+            .map_err(|e| match e {
+                EconomicsError::SupplyCapExceeded { .. } => {
+                    HostAbiError::ResourceLimitExceeded(format!(
+                        "minting {} {} would exceed the federation supply cap",
+                        amount, resource_type
+                    ))
+                }
+                EconomicsError::NotMintable(_) => HostAbiError::InvalidParameter(e.to_string()),
+                e => HostAbiError::StorageError(e.to_string()),
+            })?;
+        // The supply only grows once the mint went through; dropping `pending` on failure records nothing
         ctx.mint_token(resource_type_str, amount as i64, Some(recipient_did.to_string()), data_json)?;
+        pending
+            .commit(
+                &recipient_did,
+                self.coop_id.as_ref(),
+                self.community_id.as_ref(),
+                &self.rt.resource_ledger,
+            )
+            .await
+            .map_err(|e| HostAbiError::StorageError(e.to_string()))?;
         Ok(0)
     }
 
@@ -366,6 +585,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         condition_str_ptr: u32,
         condition_str_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let condition_str = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, condition_str_ptr, condition_str_len)?;
@@ -378,6 +598,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         &self,
         _caller: Caller<'_, ConcreteHostEnvironment<T_param>>,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let mut ctx = self.ctx.lock().await;
         ctx.else_handler()?;
         Ok(0)
@@ -387,6 +608,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         &self,
         _caller: Caller<'_, ConcreteHostEnvironment<T_param>>,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let mut ctx = self.ctx.lock().await;
         ctx.endif_handler()?;
         Ok(0)
@@ -398,10 +620,12 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         msg_ptr: u32,
         msg_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Logging)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let msg = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, msg_ptr, msg_len)?;
         tracing::warn!("[TODO FROM WASM]: {}", msg);
+        self.ctx.lock().await.logs.push(format!("TODO: {}", msg));
         Ok(0)
     }
 
@@ -411,6 +635,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         event_ptr: u32,
         event_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Governance)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let event_name = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, event_ptr, event_len)?;
@@ -425,6 +650,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         message_ptr: u32,
         message_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::Logging)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let message = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, message_ptr, message_len)?;
@@ -456,6 +682,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         resource_type_len: u32,
         amount: u64,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::ResourceRecording)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let resource_type_str = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, resource_type_ptr, resource_type_len)?;
@@ -485,9 +712,51 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         
         self.rt.mana_repository().record_usage(&self.caller_did, &token).await
             .map_err(|e| HostAbiError::ResourceManagementError(format!("Failed to record usage for resource '{}': {}", resource_type_str, e)))?;
+        self.ctx.lock().await.use_resource(resource_type_str, amount)?;
+        Ok(0)
+    }
+
+    async fn host_get_mana_balance(
+        &self,
+        mut caller: Caller<'_, ConcreteHostEnvironment<T_param>>,
+        did_ptr: u32,
+        did_len: u32,
+        out_balance_ptr: u32,
+    ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::ResourceRecording)?;
+        let memory = get_memory::<T_param>(&mut caller)?;
+        let mut store_context = caller.as_context_mut();
+        let did_bytes = read_bytes_from_mem_ctx::<T_param>(&mut store_context, &memory, did_ptr, did_len)?;
+        let did = String::from_utf8(did_bytes)
+            .ok()
+            .and_then(|did_str| Did::from_str(&did_str).ok())
+            .ok_or_else(|| HostAbiError::InvalidArguments("Malformed DID for host_get_mana_balance".to_string()))?;
+        // A DID the ledger has never seen has no mana
+        let balance = self.rt.mana_repository.ledger().get_mana_state(&did).await
+            .map_err(|e| HostAbiError::StorageError(format!("Failed to read mana balance of {}: {}", did, e)))?
+            .map_or(0, |state| state.current_mana);
+        write_bytes_to_mem_ctx::<T_param>(&mut store_context, &memory, &balance.to_le_bytes(), out_balance_ptr, 8)?;
         Ok(0)
     }
 
+    async fn host_p2p_receive_message(
+        &self,
+        mut caller: Caller<'_, ConcreteHostEnvironment<T_param>>,
+        buffer_ptr: u32,
+        buffer_len: u32,
+        timeout_ms: u32,
+    ) -> Result<i32, HostAbiError> {
+        // Guests poll and retry, so an empty inbox or a short buffer is a status, not a trap
+        let message = match self.receive_p2p_message(timeout_ms, buffer_len).await? {
+            P2pReceive::Message(message) => message,
+            P2pReceive::TooLarge(needed) => return Ok(-i32::try_from(needed).unwrap_or(i32::MAX)),
+            P2pReceive::TimedOut => return Ok(P2P_RECEIVE_TIMEOUT),
+        };
+        let memory = get_memory::<T_param>(&mut caller)?;
+        let mut store_context = caller.as_context_mut();
+        write_bytes_to_mem_ctx::<T_param>(&mut store_context, &memory, &message, buffer_ptr, buffer_len)
+    }
+
     async fn host_transfer_token(
         &self,
         mut caller: Caller<'_, ConcreteHostEnvironment<T_param>>,
@@ -499,6 +768,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         recipient_did_ptr: u32,
         recipient_did_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::TokenTransfer)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let token_type_str = read_string_from_mem_ctx::<T_param>(&mut store_context, &memory, token_type_ptr, token_type_len)?;
@@ -519,6 +789,7 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
         job_id_buffer_ptr: u32,
         job_id_buffer_len: u32,
     ) -> Result<i32, HostAbiError> {
+        self.require_capability(HostCapability::MeshJobSubmission)?;
         let memory = get_memory::<T_param>(&mut caller)?;
         let mut store_context = caller.as_context_mut();
         let cbor_payload = read_bytes_from_mem_ctx::<T_param>(&mut store_context, &memory, cbor_payload_ptr, cbor_payload_len)?;
@@ -527,6 +798,23 @@ impl<T_param: Send + Sync + 'static> MeshHostAbi<ConcreteHostEnvironment<T_param
             cbor_payload, 
             |job_id_str: &str| write_string_to_mem_ctx::<T_param>(&mut store_context, &memory, job_id_str, job_id_buffer_ptr, job_id_buffer_len)
         )?;
+        let max_submission_depth = ctx.resource_limits.max_submission_depth;
+        // Offer every spawned job to the queue before reporting, so one refusal
+        // does not drop the jobs after it
+        let refused: Vec<String> = ctx
+            .take_spawned_jobs()
+            .into_iter()
+            .filter_map(|job| {
+                let job_id = job.job_id.clone();
+                self.rt
+                    .enqueue_spawned_job(job, max_submission_depth)
+                    .err()
+                    .map(|reason| format!("Cannot spawn job {}: {}", job_id, reason))
+            })
+            .collect();
+        if !refused.is_empty() {
+            return Err(HostAbiError::ResourceLimitExceeded(refused.join("; ")));
+        }
         Ok(job_id_len as i32)
     }
 }
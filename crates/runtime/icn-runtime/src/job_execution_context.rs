@@ -3,13 +3,17 @@
 // and manage the execution of a single Mesh Job.
 
 use host_abi::LogLevel;
+use icn_core_vm::ResourceLimits;
 use icn_identity::Did;
 use icn_mesh_protocol::{JobInteractiveInputV1, P2PJobStatus};
-use icn_types::mesh::MeshJobParams;
+use icn_types::bounded_decode::{from_cbor_bounded, DecodeLimits};
+use icn_types::mesh::{MeshJob, MeshJobParams, StageDefinition, StageInputSource, WorkflowType};
 use std::collections::VecDeque;
 use host_abi::HostAbiError;
 use std::collections::HashMap;
-use std::str::FromStr;
+
+/// Returned by the workflow host functions when the job is not a multi-stage workflow.
+pub const NO_WORKFLOW_CONTEXT: i32 = -1;
 
 // Conceptual internal representation of job permissions/capabilities.
 // This would be more complex in a real system, potentially derived from tokens or policies.
@@ -52,6 +56,7 @@ pub struct JobExecutionContext {
     pub current_status: P2PJobStatus, // The rich status from planetary_mesh::protocol::P2PJobStatus
     pub current_stage_index: Option<u32>, // Current stage for workflow jobs.
     pub current_stage_id: Option<String>, // User-defined ID of the current stage.
+    pub stage_outputs: HashMap<String, String>, // Stage ID to output CID of completed stages.
 
     // Interactivity State
     pub interactive_input_queue: VecDeque<JobInteractiveInputV1>,
@@ -70,6 +75,21 @@ pub struct JobExecutionContext {
 
     // For ABI tests
     pub section_stack: Vec<SectionContext>,
+
+    // Child jobs submitted by this job, not yet handed to the runtime queue.
+    pub spawned_jobs: Vec<MeshJob>,
+
+    // How many submissions below its root job this job is; 0 unless another job submitted it.
+    pub submission_depth: u32,
+    // Caps on the child jobs this job may submit (`max_job_submissions`, `max_submission_depth`).
+    pub resource_limits: ResourceLimits,
+    // Child jobs accepted from this job so far.
+    pub job_submissions_count: usize,
+
+    // What the job did, reported back in its `ExecutionResult`.
+    pub anchored_cids: Vec<String>,
+    pub resource_usage: Vec<(String, u64)>,
+    pub logs: Vec<String>,
 }
 
 impl JobExecutionContext {
@@ -87,28 +107,26 @@ impl JobExecutionContext {
         }
         // TODO: Derive more permissions based on job_params, originator, or capability tokens.
 
+        let is_workflow = job_params.workflow_type != WorkflowType::SingleWasmModule;
+        let first_stage_id = job_params
+            .stages
+            .as_ref()
+            .and_then(|s| s.first().map(|sd| sd.stage_id.clone()));
+
         JobExecutionContext {
             job_id,
             originator_did,
             current_status: P2PJobStatus::Running {
                 node_id: host_node_did,
-                current_stage_index: if job_params.workflow_type
-                    != icn_types::mesh::WorkflowType::SingleWasmModule
-                {
-                    Some(0)
-                } else {
-                    None
-                },
-                current_stage_id: job_params
-                    .stages
-                    .as_ref()
-                    .and_then(|s| s.get(0).map(|sd| sd.stage_id.clone())),
+                current_stage_index: is_workflow.then_some(0),
+                current_stage_id: first_stage_id.clone(),
                 progress_percent: Some(0),
                 status_message: Some("Job initializing".to_string()),
             },
             job_params,
-            current_stage_index: None,
-            current_stage_id: None,
+            current_stage_index: is_workflow.then_some(0),
+            current_stage_id: if is_workflow { first_stage_id } else { None },
+            stage_outputs: HashMap::new(),
             interactive_input_queue: VecDeque::new(),
             interactive_output_sequence_num: 0,
             mana_consumed: 0,
@@ -117,6 +135,13 @@ impl JobExecutionContext {
             permissions,
             execution_start_time_ms: current_time_ms,
             section_stack: Vec::new(),
+            spawned_jobs: Vec::new(),
+            submission_depth: 0,
+            resource_limits: ResourceLimits::default(),
+            job_submissions_count: 0,
+            anchored_cids: Vec::new(),
+            resource_usage: Vec::new(),
+            logs: Vec::new(),
         }
     }
 
@@ -129,6 +154,85 @@ impl JobExecutionContext {
         );
     }
 
+    // --- Workflow & Stage Info ---
+    //
+    // All four accessors treat a `SingleWasmModule` job the same way: the
+    // numeric ones return `NO_WORKFLOW_CONTEXT` and the string ones return
+    // `HostAbiError::NotSupported`, which the linker maps to the same code.
+
+    fn is_workflow(&self) -> bool {
+        self.job_params.workflow_type != WorkflowType::SingleWasmModule
+    }
+
+    /// `WorkflowType` discriminant, or `NO_WORKFLOW_CONTEXT` for single-module jobs.
+    pub fn workflow_type_code(&self) -> i32 {
+        match self.job_params.workflow_type {
+            WorkflowType::SingleWasmModule => NO_WORKFLOW_CONTEXT,
+            WorkflowType::SequentialPipeline => 1,
+            WorkflowType::DagWorkflow => 2,
+        }
+    }
+
+    /// Index of the executing stage, or `NO_WORKFLOW_CONTEXT` for single-module jobs.
+    pub fn current_stage_index_code(&self) -> i32 {
+        match self.current_stage_index {
+            Some(index) if self.is_workflow() => index as i32,
+            _ => NO_WORKFLOW_CONTEXT,
+        }
+    }
+
+    /// Definition of the executing stage.
+    pub fn current_stage(&self) -> Result<&StageDefinition, HostAbiError> {
+        if !self.is_workflow() {
+            return Err(HostAbiError::NotSupported);
+        }
+        let index = self
+            .current_stage_index
+            .ok_or_else(|| HostAbiError::InvalidState("Workflow has no current stage".to_string()))?;
+        self.job_params
+            .stages
+            .as_ref()
+            .and_then(|stages| stages.get(index as usize))
+            .ok_or_else(|| HostAbiError::NotFound(format!("Stage {} is not defined", index)))
+    }
+
+    /// User-defined ID of the executing stage.
+    pub fn current_stage_id_for_abi(&self) -> Result<String, HostAbiError> {
+        self.current_stage().map(|stage| stage.stage_id.clone())
+    }
+
+    /// CID the executing stage should read its input from, or `None` for `NoInput` stages.
+    pub fn stage_input_cid(&self) -> Result<Option<String>, HostAbiError> {
+        match &self.current_stage()?.input_source {
+            StageInputSource::NoInput => Ok(None),
+            StageInputSource::JobInput(_) => Ok(self.job_params.input_data_cid.clone()),
+            StageInputSource::PreviousStageOutput(stage_id, _) => self
+                .stage_outputs
+                .get(stage_id)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| {
+                    HostAbiError::NotFound(format!("Stage '{}' has not produced output yet", stage_id))
+                }),
+        }
+    }
+
+    /// Record the executing stage's output and advance to the next stage.
+    pub fn complete_current_stage(&mut self, output_cid: String) -> Result<(), HostAbiError> {
+        let stage_id = self.current_stage_id_for_abi()?;
+        self.stage_outputs.insert(stage_id, output_cid);
+
+        let next_index = self.current_stage_index.unwrap_or(0) + 1;
+        self.current_stage_id = self
+            .job_params
+            .stages
+            .as_ref()
+            .and_then(|stages| stages.get(next_index as usize))
+            .map(|stage| stage.stage_id.clone());
+        self.current_stage_index = Some(next_index);
+        Ok(())
+    }
+
     // --- ABI Method Stubs ---
     pub fn begin_section(&mut self, kind: String, title: Option<String>) -> Result<(), HostAbiError> {
         if self.section_stack.is_empty() {
@@ -160,8 +264,8 @@ impl JobExecutionContext {
     }
 
     pub fn anchor_data(&mut self, path: String, data_ref: String) -> Result<(), HostAbiError> {
-        println!("[JEC STUB] anchor_data: path={}, data_ref={}", path, data_ref);
-        // TODO: Implement actual logic (e.g., record anchor)
+        self.logs.push(format!("anchored {} at {}", data_ref, path));
+        self.anchored_cids.push(data_ref);
         Ok(())
     }
 
@@ -181,6 +285,12 @@ impl JobExecutionContext {
 
     pub fn mint_token(&mut self, res_type: String, amount: i64, recipient: Option<String>, data_json: Option<String>) -> Result<(), HostAbiError> {
         if self.section_stack.is_empty() {
+            self.logs.push(format!(
+                "minted {} {} to {}",
+                amount,
+                res_type,
+                recipient.as_deref().unwrap_or("<none>")
+            ));
             Ok(())
         } else {
             Err(HostAbiError::InvalidState("Mint token operation is not valid in the current context.".to_string()))
@@ -217,9 +327,8 @@ impl JobExecutionContext {
         Ok(())
     }
 
-    pub fn use_resource(&mut self, resource_type: String, amount: i64) -> Result<(), HostAbiError> {
-        println!("[JEC STUB] use_resource: type={}, amount={}", resource_type, amount);
-        // TODO: Implement actual logic (e.g., record resource usage)
+    pub fn use_resource(&mut self, resource_type: String, amount: u64) -> Result<(), HostAbiError> {
+        self.resource_usage.push((resource_type, amount));
         Ok(())
     }
 
@@ -229,22 +338,63 @@ impl JobExecutionContext {
         Ok(())
     }
 
+    /// Decode CBOR `MeshJobParams` into a child job of this one and queue it in
+    /// `spawned_jobs`. The new job ID is passed to `write_back_fn`; if that returns a
+    /// negative size because the guest's buffer is too small, no job is queued.
+    ///
+    /// Fails with `HostAbiError::SubmissionDepthExceeded` if this job is already
+    /// `max_submission_depth` submissions deep, and with `ResourceLimitExceeded`
+    /// once it has submitted `max_job_submissions` jobs.
     pub fn submit_mesh_job(&mut self, cbor_payload: Vec<u8>, write_back_fn: impl FnOnce(&str) -> Result<i32, HostAbiError>) -> Result<i32, HostAbiError> {
-        println!("[JEC STUB] submit_mesh_job: payload_len={}", cbor_payload.len());
-        let dummy_job_id = "dummy_mesh_job_123";
-        write_back_fn(dummy_job_id)
+        if self.submission_depth >= self.resource_limits.max_submission_depth {
+            return Err(HostAbiError::SubmissionDepthExceeded(format!(
+                "Job {} is {} submissions deep; the limit is {}",
+                self.job_id, self.submission_depth, self.resource_limits.max_submission_depth
+            )));
+        }
+        if self.job_submissions_count >= self.resource_limits.max_job_submissions {
+            return Err(HostAbiError::ResourceLimitExceeded(format!(
+                "Job {} already submitted {} jobs",
+                self.job_id, self.job_submissions_count
+            )));
+        }
+        let params: MeshJobParams = from_cbor_bounded(&cbor_payload, &DecodeLimits::manifest())
+            .map_err(|e| {
+                HostAbiError::DataEncodingError(format!("Invalid mesh job params: {}", e))
+            })?;
+        let job = MeshJob {
+            job_id: format!("job-{}", uuid::Uuid::new_v4()),
+            params,
+            originator_did: self.originator_did.clone(),
+            originator_org_scope: None,
+            submission_timestamp: chrono::Utc::now().timestamp() as u64,
+            parent_job_id: Some(self.job_id.clone()),
+            origin_receipt_cid: None,
+            max_total_mana: None,
+            originator_signature: None,
+        };
+        let written = write_back_fn(&job.job_id)?;
+        if written < 0 {
+            // The job ID did not fit; nothing is submitted so the guest can retry
+            return Ok(written);
+        }
+        self.spawned_jobs.push(job);
+        self.job_submissions_count += 1;
+        Ok(written)
+    }
+
+    /// Take the child jobs submitted since the last call
+    pub fn take_spawned_jobs(&mut self) -> Vec<MeshJob> {
+        std::mem::take(&mut self.spawned_jobs)
     }
 }
 
 // Default implementation for JobExecutionContext for testing
 impl Default for JobExecutionContext {
     fn default() -> Self {
-        let dummy_did = Did::from_str("did:icn:test_originator").unwrap_or_else(|_| {
-            panic!("Failed to create dummy DID for JobExecutionContext::default");
-        });
-        let dummy_host_did = Did::from_str("did:icn:test_host").unwrap_or_else(|_| {
-            panic!("Failed to create dummy host DID for JobExecutionContext::default");
-        });
+        // Fixed keys, so defaults are stable and their DIDs are valid did:key identifiers
+        let dummy_did = icn_identity::KeyPair::from_bytes(&[1; 32]).did;
+        let dummy_host_did = icn_identity::KeyPair::from_bytes(&[2; 32]).did;
 
         JobExecutionContext {
             job_id: "test_job_id".to_string(),
@@ -259,6 +409,7 @@ impl Default for JobExecutionContext {
             },
             current_stage_index: None,
             current_stage_id: None,
+            stage_outputs: HashMap::new(),
             interactive_input_queue: VecDeque::new(),
             interactive_output_sequence_num: 0,
             mana_consumed: 0,
@@ -267,6 +418,103 @@ impl Default for JobExecutionContext {
             permissions: JobPermissions::default(),
             execution_start_time_ms: 0,
             section_stack: Vec::new(),
+            spawned_jobs: Vec::new(),
+            submission_depth: 0,
+            resource_limits: ResourceLimits::default(),
+            job_submissions_count: 0,
+            anchored_cids: Vec::new(),
+            resource_usage: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage(stage_id: &str, input_source: StageInputSource) -> StageDefinition {
+        StageDefinition {
+            stage_id: stage_id.to_string(),
+            description: format!("{} stage", stage_id),
+            wasm_cid: format!("{}-wasm", stage_id),
+            input_source,
+            resources_required: None,
+            deadline: None,
         }
     }
+
+    fn context(job_params: MeshJobParams) -> JobExecutionContext {
+        let did = icn_identity::KeyPair::generate().did;
+        JobExecutionContext::new("job-1".to_string(), did.clone(), job_params, did, 0)
+    }
+
+    #[test]
+    fn second_stage_reads_first_stage_output() {
+        let job_params = MeshJobParams {
+            workflow_type: WorkflowType::SequentialPipeline,
+            input_data_cid: Some("job-input-cid".to_string()),
+            stages: Some(vec![
+                stage("extract", StageInputSource::JobInput("raw".to_string())),
+                stage(
+                    "transform",
+                    StageInputSource::PreviousStageOutput("extract".to_string(), "out".to_string()),
+                ),
+            ]),
+            ..MeshJobParams::default()
+        };
+        let mut ctx = context(job_params);
+
+        assert_eq!(ctx.workflow_type_code(), 1);
+        assert_eq!(ctx.current_stage_index_code(), 0);
+        assert_eq!(ctx.stage_input_cid().unwrap().as_deref(), Some("job-input-cid"));
+
+        ctx.complete_current_stage("extract-output-cid".to_string()).unwrap();
+
+        assert_eq!(ctx.current_stage_index_code(), 1);
+        assert_eq!(ctx.current_stage_id_for_abi().unwrap(), "transform");
+        assert_eq!(
+            ctx.stage_input_cid().unwrap().as_deref(),
+            Some("extract-output-cid")
+        );
+    }
+
+    #[test]
+    fn single_module_job_has_no_workflow_context() {
+        let ctx = context(MeshJobParams::default());
+
+        assert_eq!(ctx.workflow_type_code(), NO_WORKFLOW_CONTEXT);
+        assert_eq!(ctx.current_stage_index_code(), NO_WORKFLOW_CONTEXT);
+        assert_eq!(ctx.current_stage_id_for_abi(), Err(HostAbiError::NotSupported));
+        assert_eq!(ctx.stage_input_cid(), Err(HostAbiError::NotSupported));
+    }
+
+    #[test]
+    fn submitted_jobs_record_their_parent() {
+        let mut ctx = context(MeshJobParams::default());
+        let payload = serde_cbor::to_vec(&MeshJobParams::default()).unwrap();
+
+        let mut written = String::new();
+        ctx.submit_mesh_job(payload, |id| {
+            written = id.to_string();
+            Ok(id.len() as i32)
+        })
+        .unwrap();
+
+        let spawned = ctx.take_spawned_jobs();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].job_id, written);
+        assert_eq!(spawned[0].parent_job_id.as_deref(), Some("job-1"));
+        assert!(ctx.take_spawned_jobs().is_empty());
+
+        assert!(matches!(
+            ctx.submit_mesh_job(vec![0xff], |_| Ok(0)),
+            Err(HostAbiError::DataEncodingError(_))
+        ));
+        let oversized = vec![0u8; DecodeLimits::manifest().max_bytes + 1];
+        assert!(matches!(
+            ctx.submit_mesh_job(oversized, |_| Ok(0)),
+            Err(HostAbiError::DataEncodingError(_))
+        ));
+    }
 }
@@ -0,0 +1,174 @@
+// InterCooperative Network (ICN) - Job Registry
+// Tracks the jobs the runtime is currently executing so their status can be
+// inspected (CLI, HTTP) and updated (failure reporting) without parsing logs.
+
+use icn_mesh_protocol::P2PJobStatus;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Identifier of a mesh job as seen by the runtime
+pub type JobId = String;
+
+/// Wire representation of a single in-flight job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveJob {
+    pub job_id: JobId,
+    pub status: P2PJobStatus,
+}
+
+/// Shared registry of in-flight jobs and their current status. Jobs that
+/// failed stay listed with their `Failed` status until they are retried.
+///
+/// Cloning yields another handle to the same registry.
+#[derive(Debug, Clone, Default)]
+pub struct JobRegistry {
+    jobs: Arc<RwLock<HashMap<JobId, P2PJobStatus>>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking a job with its initial status
+    pub fn register(&self, job_id: impl Into<JobId>, status: P2PJobStatus) {
+        self.write().insert(job_id.into(), status);
+    }
+
+    /// Update the status of a tracked job. Returns false if the job is unknown.
+    pub fn update_status(&self, job_id: &str, status: P2PJobStatus) -> bool {
+        match self.write().get_mut(job_id) {
+            Some(current) => {
+                *current = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop tracking a job once it is done with, unless it failed: failed jobs
+    /// keep their `Failed` status until a retry registers them again
+    pub fn finish(&self, job_id: &str) {
+        let mut jobs = self.write();
+        if !matches!(jobs.get(job_id), Some(P2PJobStatus::Failed { .. })) {
+            jobs.remove(job_id);
+        }
+    }
+
+    /// Stop tracking a job, returning its last known status
+    pub fn remove(&self, job_id: &str) -> Option<P2PJobStatus> {
+        self.write().remove(job_id)
+    }
+
+    /// Current status of a tracked job
+    pub fn status(&self, job_id: &str) -> Option<P2PJobStatus> {
+        self.read().get(job_id).cloned()
+    }
+
+    /// Snapshot of all tracked jobs, ordered by job ID
+    pub fn snapshot(&self) -> Vec<(JobId, P2PJobStatus)> {
+        let mut jobs: Vec<_> = self
+            .read()
+            .iter()
+            .map(|(id, status)| (id.clone(), status.clone()))
+            .collect();
+        jobs.sort_by(|a, b| a.0.cmp(&b.0));
+        jobs
+    }
+
+    /// Snapshot in the wire format served over HTTP
+    pub fn active_jobs(&self) -> Vec<ActiveJob> {
+        self.snapshot()
+            .into_iter()
+            .map(|(job_id, status)| ActiveJob { job_id, status })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    // A poisoned lock only means a writer panicked mid-update; the map itself
+    // is still usable, so recover it rather than propagating the panic.
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<JobId, P2PJobStatus>> {
+        self.jobs.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<JobId, P2PJobStatus>> {
+        self.jobs.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use icn_identity::KeyPair;
+    use icn_types::JobFailureReason;
+
+    fn running(node_id: &icn_identity::Did) -> P2PJobStatus {
+        P2PJobStatus::Running {
+            node_id: node_id.clone(),
+            current_stage_index: None,
+            current_stage_id: None,
+            progress_percent: None,
+            status_message: None,
+        }
+    }
+
+    #[test]
+    fn tracks_updates_and_removes_jobs() {
+        let node = KeyPair::generate().did;
+        let registry = JobRegistry::new();
+        let handle = registry.clone();
+
+        registry.register("job-b", running(&node));
+        registry.register("job-a", running(&node));
+        let ids: Vec<_> = handle.snapshot().into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["job-a".to_string(), "job-b".to_string()]);
+
+        assert!(registry.update_status(
+            "job-a",
+            P2PJobStatus::Failed {
+                node_id: node.clone(),
+                reason: JobFailureReason::NotFound,
+            },
+        ));
+        assert!(matches!(handle.status("job-a"), Some(P2PJobStatus::Failed { .. })));
+        assert!(!registry.update_status("missing", running(&node)));
+
+        assert!(registry.remove("job-a").is_some());
+        assert_eq!(handle.len(), 1);
+    }
+
+    #[test]
+    fn finishing_a_failed_job_keeps_its_status() {
+        let node = KeyPair::generate().did;
+        let registry = JobRegistry::new();
+        registry.register("job-ok", running(&node));
+        registry.register("job-failed", running(&node));
+        registry.update_status(
+            "job-failed",
+            P2PJobStatus::Failed {
+                node_id: node.clone(),
+                reason: JobFailureReason::NotFound,
+            },
+        );
+
+        registry.finish("job-ok");
+        registry.finish("job-failed");
+        assert!(registry.status("job-ok").is_none());
+        assert!(matches!(
+            registry.status("job-failed"),
+            Some(P2PJobStatus::Failed { .. })
+        ));
+
+        registry.register("job-failed", running(&node));
+        registry.finish("job-failed");
+        assert!(registry.is_empty());
+    }
+}
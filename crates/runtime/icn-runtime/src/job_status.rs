@@ -0,0 +1,58 @@
+//! Reporting the status of processed jobs back to the mesh job service
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use icn_mesh_protocol::P2PJobStatus;
+use reqwest::Client;
+use tracing::debug;
+
+/// Receives status changes of the jobs a runtime processes, so failures reach
+/// whoever assigned the job instead of only the local job registry
+#[async_trait]
+pub trait JobStatusReporter: Send + Sync {
+    /// Report that `job_id` is now in `status`
+    async fn report_status(&self, job_id: &str, status: P2PJobStatus) -> Result<()>;
+}
+
+/// Reports job statuses with `POST {base_url}/jobs/{id}/status`
+#[derive(Debug, Clone)]
+pub struct HttpJobStatusReporter {
+    client: Client,
+    base_url: String,
+}
+
+impl HttpJobStatusReporter {
+    /// Create a reporter for the mesh job service at `base_url`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_client(Client::new(), base_url)
+    }
+
+    /// Create a reporter that sends its requests through `client`
+    pub fn with_client(client: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl JobStatusReporter for HttpJobStatusReporter {
+    async fn report_status(&self, job_id: &str, status: P2PJobStatus) -> Result<()> {
+        let url = format!(
+            "{}/jobs/{}/status",
+            self.base_url.trim_end_matches('/'),
+            job_id
+        );
+        debug!(%job_id, "Reporting job status to {}", url);
+        let response = self.client.post(&url).json(&status).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Job status report to {} was rejected with HTTP {}",
+                url,
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
@@ -4,26 +4,40 @@ use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
 use ed25519_dalek::VerifyingKey;
-use icn_core_vm::{ExecutionMetrics as CoreVmExecutionMetrics, ResourceLimits};
+use icn_core_vm::{
+    CoVm, CoVmError, ExecutionMetrics as CoreVmExecutionMetrics, PartialExecutionError,
+    ResourceLimits,
+};
 pub use icn_economics::mana::{InMemoryManaLedger, ManaLedger, ManaRegenerator, RegenerationPolicy};
 use icn_economics::ResourceType;
-use icn_identity::{Did, DidError, KeyPair as IcnKeyPair, TrustBundle, TrustValidationError};
+use icn_identity::{
+    Did, DidError, KeyPair as IcnKeyPair, TaggedSignature, TrustBundle, TrustValidationError,
+};
 use icn_mesh_receipts::ExecutionReceipt as MeshExecutionReceipt;
+use icn_types::bounded_decode::{from_json_bounded, DecodeLimits};
+use icn_types::clock::{Clock, SystemClock};
+use icn_types::CompilationManifest;
 use icn_types::dag::{DagEventType, DagNode};
 use icn_types::dag_store::DagStore;
-use icn_types::mesh::{JobStatus as IcnJobStatus, MeshJob, MeshJobParams};
+use icn_types::mesh::{JobSignatureError, JobStatus as IcnJobStatus, MeshJob, MeshJobParams};
+use icn_types::org::{CommunityId, CooperativeId};
 use icn_types::runtime_receipt::{RuntimeExecutionMetrics, RuntimeExecutionReceipt};
 use icn_types::VerifiableReceipt;
 use icn_types::JobFailureReason;
 use icn_mesh_protocol::P2PJobStatus;
+use multihash::{Code, MultihashDigest};
 use icn_types::error::IcnError;
 use icn_types::error::EconomicsError;
 use icn_types::RuntimeJobFailureReport;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
 use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tokio::time::{sleep, Duration};
 use tracing::{info, warn, debug, error};
 use uuid::Uuid;
@@ -36,20 +50,44 @@ use std::str::FromStr;
 use std::fs::{self, File};
 use std::io::{Read, Write};
 
-use crate::config::RuntimeConfig;
+use crate::config::{FuelPricing, PartialRuntimeConfig, RuntimeConfig};
+#[cfg(feature = "full_host_abi")]
+use crate::job_execution_context::JobExecutionContext;
 
 // Import the context module
 pub mod context;
-pub use context::RuntimeContext;
+pub use context::{LineageManaBudget, ManaReservation, RuntimeContext};
 pub use context::RuntimeContextBuilder;
 
 // Import the host environment module
 pub mod host_environment;
-pub use host_environment::ConcreteHostEnvironment;
+pub use host_environment::{ConcreteHostEnvironment, HostEnvironmentBuilder};
+
+// Host ABI capabilities for least-privilege host environments
+pub mod host_capabilities;
+pub use host_capabilities::{HostCapabilities, HostCapability};
 
 // Import the job execution context module
 pub mod job_execution_context;
 
+// Import the job registry module
+pub mod job_registry;
+pub use job_registry::{ActiveJob, JobId, JobRegistry};
+
+// Reporting job status changes to the mesh job service
+pub mod job_status;
+pub use job_status::{HttpJobStatusReporter, JobStatusReporter};
+
+// Exponential backoff for transient failures
+pub mod retry;
+pub use retry::RetryPolicy;
+
+// Import the metrics/status HTTP server
+pub mod metrics_server;
+
+// Node assembly and serving from a config file
+pub mod node;
+
 // Import the wasm module
 pub mod wasm;
 pub use wasm::register_host_functions;
@@ -66,6 +104,10 @@ use reputation_integration::{
 /// Distribution worker for periodic mana payouts
 pub mod distribution_worker;
 
+// Import receipt metrics validation
+pub mod receipt_validation;
+pub use receipt_validation::{MetricsViolation, UsagePlausibility};
+
 // Import sled_storage module and type
 pub mod sled_storage;
 // use sled_storage::SledStorage;
@@ -78,6 +120,23 @@ pub mod sled_storage;
 // Add at the top with other constants
 const DEFAULT_MANA_COST: u64 = 100;
 
+/// Mana charged for one attempt of a job: the explicit cost if given, otherwise the
+/// sum of required resources, falling back to `DEFAULT_MANA_COST`.
+pub fn estimated_mana_cost(params: &MeshJobParams) -> u64 {
+    let cost = params.explicit_mana_cost.unwrap_or_else(|| {
+        if !params.resources_required.is_empty() {
+            params.resources_required.iter().map(|(_, amount)| *amount).sum()
+        } else {
+            DEFAULT_MANA_COST
+        }
+    });
+    if cost == 0 && !params.resources_required.is_empty() {
+        DEFAULT_MANA_COST
+    } else {
+        cost
+    }
+}
+
 /// Module cache trait for caching compiled WASM modules
 #[async_trait]
 pub trait ModuleCache: Send + Sync {
@@ -88,6 +147,101 @@ pub trait ModuleCache: Send + Sync {
     async fn store_module(&self, cid: &str, module: Module) -> Result<()>;
 }
 
+/// Process-local [`ModuleCache`] keyed by WASM content CID.
+///
+/// `Module` is reference counted internally, so cached modules are cheap to
+/// hand out to concurrent executions.
+#[derive(Default)]
+pub struct InMemoryModuleCache {
+    modules: Mutex<HashMap<String, Module>>,
+}
+
+impl InMemoryModuleCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached modules
+    pub fn len(&self) -> usize {
+        self.modules.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl ModuleCache for InMemoryModuleCache {
+    async fn get_module(&self, cid: &str) -> Option<Module> {
+        self.modules.lock().unwrap().get(cid).cloned()
+    }
+
+    async fn store_module(&self, cid: &str, module: Module) -> Result<()> {
+        self.modules.lock().unwrap().insert(cid.to_string(), module);
+        Ok(())
+    }
+}
+
+/// Bounded [`ModuleCache`] that evicts the least recently used modules once
+/// `capacity` modules are cached.
+pub struct LruModuleCache {
+    modules: moka::future::Cache<String, Module>,
+}
+
+impl LruModuleCache {
+    pub fn new(capacity: u64) -> Self {
+        Self {
+            modules: moka::future::Cache::new(capacity),
+        }
+    }
+
+    /// Number of cached modules; evictions are applied lazily, so this may
+    /// briefly exceed the capacity
+    pub fn len(&self) -> u64 {
+        self.modules.entry_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl ModuleCache for LruModuleCache {
+    async fn get_module(&self, cid: &str) -> Option<Module> {
+        self.modules.get(cid).await
+    }
+
+    async fn store_module(&self, cid: &str, module: Module) -> Result<()> {
+        self.modules.insert(cid.to_string(), module).await;
+        Ok(())
+    }
+}
+
+/// CIDv1 (raw codec, sha2-256) of WASM bytes; used as the module cache key so
+/// lookups agree regardless of the key a module was stored under.
+fn wasm_content_cid(wasm_bytes: &[u8]) -> String {
+    cid::Cid::new_v1(0x55, Code::Sha2_256.digest(wasm_bytes)).to_string()
+}
+
+/// Reject modules generated against a different host ABI version.
+///
+/// Modules without an embedded version (hand-written WAT, older toolchains) are
+/// accepted unchanged.
+fn check_abi_version(wasm_bytes: &[u8]) -> Result<(), RuntimeError> {
+    let version = host_abi::read_abi_version(wasm_bytes)
+        .map_err(|e| RuntimeError::LoadError(format!("Failed to read host ABI version: {}", e)))?;
+    match version {
+        Some(v) if !host_abi::is_abi_version_compatible(v) => Err(RuntimeError::LoadError(format!(
+            "Module targets host ABI version {} but this runtime provides version {}",
+            v,
+            host_abi::ICN_HOST_ABI_VERSION
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Error types specific to the runtime
 #[derive(Error, Debug)]
 pub enum RuntimeError {
@@ -129,6 +283,15 @@ pub enum RuntimeError {
 
     #[error("WASM error: {0}")]
     WasmError(anyhow::Error),
+
+    #[error("Config field '{0}' cannot be changed while the runtime is running")]
+    ImmutableConfigField(&'static str),
+
+    #[error("Compilation provenance mismatch: {0}")]
+    ProvenanceMismatch(String),
+
+    #[error("External receipt issuer {issuer} is not a signer of the trust bundle for federation {federation}")]
+    UntrustedExternalIssuer { issuer: String, federation: String },
 }
 
 /// Context for WASM virtual machine execution
@@ -189,6 +352,10 @@ pub struct Proposal {
 
     /// Quorum status
     pub quorum_status: QuorumStatus,
+
+    /// Provenance of `wasm_cid`, checked against the loaded module before execution
+    #[serde(default)]
+    pub compilation_manifest: Option<CompilationManifest>,
 }
 
 /// State of a governance proposal
@@ -238,6 +405,16 @@ pub trait RuntimeStorage: Send + Sync {
     /// Update a proposal
     async fn update_proposal(&self, proposal: &Proposal) -> Result<()>;
 
+    /// Load every proposal currently in `state`, ordered by proposal ID.
+    ///
+    /// Backends that cannot enumerate proposals return an error.
+    async fn load_proposals_by_state(&self, state: ProposalState) -> Result<Vec<Proposal>> {
+        Err(anyhow!(
+            "Listing proposals by state ({:?}) is not supported by this storage backend",
+            state
+        ))
+    }
+
     /// Load a WASM module by CID
     async fn load_wasm(&self, cid: &str) -> Result<Vec<u8>>;
 
@@ -250,8 +427,73 @@ pub trait RuntimeStorage: Send + Sync {
     /// Load an execution receipt by its ID (Added for tests/sled impl)
     async fn load_receipt(&self, receipt_id: &str) -> Result<RuntimeExecutionReceipt>;
 
+    /// List stored receipts ordered by timestamp, then ID, skipping `offset`
+    /// and returning at most `limit`.
+    ///
+    /// Backends that cannot enumerate receipts return an error.
+    async fn list_receipts(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<RuntimeExecutionReceipt>> {
+        Err(anyhow!(
+            "Listing receipts ({}..{}) is not supported by this storage backend",
+            offset,
+            offset.saturating_add(limit)
+        ))
+    }
+
     /// Anchor a CID to the DAG (Conceptually doesn't belong here, but needed by trait)
     async fn anchor_to_dag(&self, cid: &str) -> Result<String>;
+
+    /// Store a compacted receipt's anchored-CID list under its Merkle root.
+    ///
+    /// Backends that cannot hold the lists return an error, which keeps
+    /// receipts inline.
+    async fn store_anchored_cids(&self, root: &str, cids: &[String]) -> Result<()> {
+        let _ = cids;
+        Err(anyhow!(
+            "Storing anchored CID lists ({}) is not supported by this storage backend",
+            root
+        ))
+    }
+
+    /// Load the anchored-CID list stored under `root`
+    async fn load_anchored_cids(&self, root: &str) -> Result<Vec<String>> {
+        Err(anyhow!(
+            "Loading anchored CID lists ({}) is not supported by this storage backend",
+            root
+        ))
+    }
+}
+
+/// Check that `manifest` describes the proposal's sources and the WASM bytes
+/// actually loaded for it.
+fn verify_compilation_manifest(
+    proposal: &Proposal,
+    manifest: &CompilationManifest,
+    wasm_bytes: &[u8],
+) -> Result<(), RuntimeError> {
+    if manifest.ccl_cid != proposal.ccl_cid {
+        return Err(RuntimeError::ProvenanceMismatch(format!(
+            "manifest CCL CID {} does not match proposal CCL CID {}",
+            manifest.ccl_cid, proposal.ccl_cid
+        )));
+    }
+    if manifest.wasm_cid != proposal.wasm_cid {
+        return Err(RuntimeError::ProvenanceMismatch(format!(
+            "manifest WASM CID {} does not match proposal WASM CID {}",
+            manifest.wasm_cid, proposal.wasm_cid
+        )));
+    }
+    let loaded_cid = wasm_content_cid(wasm_bytes);
+    if loaded_cid != manifest.wasm_cid {
+        return Err(RuntimeError::ProvenanceMismatch(format!(
+            "loaded WASM hashes to {}, manifest (compiler {}) records {}",
+            loaded_cid, manifest.compiler_version, manifest.wasm_cid
+        )));
+    }
+    Ok(())
 }
 
 /// Minimal MemStorage for tests (moved out for placeholder use in from_config)
@@ -260,6 +502,7 @@ pub struct MemStorage {
     wasm_modules: std::sync::Mutex<HashMap<String, Vec<u8>>>,
     receipts: std::sync::Mutex<HashMap<String, RuntimeExecutionReceipt>>,
     anchored_cids: std::sync::Mutex<Vec<String>>,
+    anchored_cid_lists: std::sync::Mutex<HashMap<String, Vec<String>>>,
 }
 
 impl Default for MemStorage {
@@ -269,6 +512,7 @@ impl Default for MemStorage {
             wasm_modules: std::sync::Mutex::new(HashMap::new()),
             receipts: std::sync::Mutex::new(HashMap::new()),
             anchored_cids: std::sync::Mutex::new(Vec::new()),
+            anchored_cid_lists: std::sync::Mutex::new(HashMap::new()),
         }
     }
 }
@@ -303,6 +547,19 @@ impl RuntimeStorage for MemStorage {
         Ok(())
     }
 
+    async fn load_proposals_by_state(&self, state: ProposalState) -> Result<Vec<Proposal>> {
+        let mut matching: Vec<Proposal> = self
+            .proposals
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|p| p.state == state)
+            .cloned()
+            .collect();
+        matching.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(matching)
+    }
+
     async fn load_wasm(&self, cid: &str) -> Result<Vec<u8>> {
         self.wasm_modules
             .lock()
@@ -340,18 +597,46 @@ impl RuntimeStorage for MemStorage {
             .ok_or_else(|| anyhow!("Receipt {} not found", receipt_id))
     }
 
+    async fn list_receipts(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<RuntimeExecutionReceipt>> {
+        let mut receipts: Vec<RuntimeExecutionReceipt> =
+            self.receipts.lock().unwrap().values().cloned().collect();
+        receipts.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+        Ok(receipts.into_iter().skip(offset).take(limit).collect())
+    }
+
     async fn anchor_to_dag(&self, cid: &str) -> Result<String> {
         let anchor_cid = format!("mock-anchor-{}", cid);
         self.anchored_cids.lock().unwrap().push(anchor_cid.clone());
         Ok(anchor_cid)
     }
+
+    async fn store_anchored_cids(&self, root: &str, cids: &[String]) -> Result<()> {
+        self.anchored_cid_lists
+            .lock()
+            .unwrap()
+            .insert(root.to_string(), cids.to_vec());
+        Ok(())
+    }
+
+    async fn load_anchored_cids(&self, root: &str) -> Result<Vec<String>> {
+        self.anchored_cid_lists
+            .lock()
+            .unwrap()
+            .get(root)
+            .cloned()
+            .ok_or_else(|| anyhow!("Anchored CID list {} not found", root))
+    }
 }
 
 /// The ICN Runtime for executing governance proposals
 #[derive(Clone)]
 pub struct Runtime<L: ManaLedger + Send + Sync + 'static> {
-    /// Runtime configuration
-    config: RuntimeConfig,
+    /// Runtime configuration; hot-reloadable fields change via `reconfigure`
+    config: Arc<std::sync::RwLock<RuntimeConfig>>,
 
     /// Storage backend
     storage: Arc<dyn RuntimeStorage>,
@@ -370,6 +655,33 @@ pub struct Runtime<L: ManaLedger + Send + Sync + 'static> {
 
     /// Optional reputation updater
     reputation_updater: Option<Arc<dyn ReputationUpdater>>,
+
+    /// Optional destination for the status of failed jobs
+    status_reporter: Option<Arc<dyn JobStatusReporter>>,
+
+    /// Time source for receipt timestamps and deadlines
+    clock: Arc<dyn Clock>,
+
+    /// Jobs currently being processed by `run_forever`
+    job_registry: JobRegistry,
+
+    /// Optional cache of compiled modules, filled on first use or by `warm_up`
+    module_cache: Option<Arc<dyn ModuleCache>>,
+
+    /// Bounds that anchored mesh receipts' usage must fit in
+    usage_plausibility: UsagePlausibility,
+
+    /// Serializes `anchor_receipt`'s already-anchored check with its DAG insert
+    receipt_anchor_lock: Arc<tokio::sync::Mutex<()>>,
+
+    /// Retries of `anchor_receipt`'s DAG insert
+    anchor_retry: RetryPolicy,
+
+    /// HTTP client for polling the mesh job service and reporting failures
+    http_client: reqwest::Client,
+
+    /// Consecutive failed job polls, used to back off the poll loop
+    poll_failures: Arc<AtomicU32>,
 }
 
 impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
@@ -384,7 +696,7 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
         let mut runtime_config = RuntimeConfig::default();
         runtime_config.node_did = default_did.to_string();
 
-        let engine = Engine::default();
+        let engine = runtime_engine();
         let mut linker = Linker::new(&engine);
         crate::wasm::register_host_functions(&mut linker)?;
 
@@ -401,32 +713,239 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
         );
 
         Ok(Self {
-            config: runtime_config,
+            config: Arc::new(std::sync::RwLock::new(runtime_config)),
             storage,
             context,
             engine,
             linker,
             host_env: None,
             reputation_updater: None,
+            status_reporter: None,
+            clock: Arc::new(SystemClock),
+            job_registry: JobRegistry::new(),
+            module_cache: None,
+            usage_plausibility: UsagePlausibility::default(),
+            receipt_anchor_lock: Arc::new(tokio::sync::Mutex::new(())),
+            anchor_retry: RetryPolicy::default(),
+            http_client: reqwest::Client::new(),
+            poll_failures: Arc::new(AtomicU32::new(0)),
         })
     }
 
+    /// Set the limits `anchor_receipt` checks receipt metrics against
+    pub fn with_receipt_limits(self, limits: ResourceLimits) -> Self {
+        self.config_mut().receipt_limits = limits;
+        self
+    }
+
+    /// Set the bounds `anchor_mesh_receipt` checks reported usage against
+    pub fn with_usage_plausibility(mut self, bounds: UsagePlausibility) -> Self {
+        self.usage_plausibility = bounds;
+        self
+    }
+
+    /// Set how `anchor_receipt` retries a failed DAG insert
+    pub fn with_anchor_retry(mut self, policy: RetryPolicy) -> Self {
+        self.anchor_retry = policy;
+        self
+    }
+
+    /// Set the host environment `execute_wasm` runs modules against. Governance
+    /// executions take their capabilities and mint authorization from it.
+    pub fn with_host_environment(mut self, env: ConcreteHostEnvironment<()>) -> Self {
+        self.host_env = Some(Arc::new(Mutex::new(env)));
+        self
+    }
+
     /// Set a reputation updater for this runtime
     pub fn with_reputation_updater(mut self, updater: Arc<dyn ReputationUpdater>) -> Self {
         self.reputation_updater = Some(updater);
         self
     }
 
+    /// Set where the status of failed jobs is reported
+    pub fn with_status_reporter(mut self, reporter: Arc<dyn JobStatusReporter>) -> Self {
+        self.status_reporter = Some(reporter);
+        self
+    }
+
+    /// Snapshot of the current configuration
+    pub fn config(&self) -> RuntimeConfig {
+        self.config.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn config_mut(&self) -> std::sync::RwLockWriteGuard<'_, RuntimeConfig> {
+        self.config.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Apply hot-reloadable config changes to the running instance.
+    ///
+    /// See [`PartialRuntimeConfig`] for which fields may change. The update is
+    /// all-or-nothing: if any immutable field differs, nothing is applied and
+    /// `RuntimeError::ImmutableConfigField` names the offending field.
+    ///
+    /// Clones of a `Runtime` share one configuration, so the change is seen by
+    /// every clone and by the task from [`Runtime::spawn_mana_regeneration`].
+    pub fn reconfigure(&self, partial: PartialRuntimeConfig) -> Result<()> {
+        let mut config = self.config_mut();
+        partial.check_immutable(&config)?;
+        partial.apply_to(&mut config);
+        if let (Some(policy), Some(regenerator)) =
+            (&partial.mana_regeneration_policy, &self.context.mana_regenerator)
+        {
+            regenerator.set_policy(policy.clone());
+        }
+        info!(?partial, "Runtime reconfigured");
+        Ok(())
+    }
+
+    /// Interval between mana regeneration ticks, as currently configured
+    pub fn mana_tick_interval(&self) -> Option<Duration> {
+        self.config().mana_tick_interval_seconds.map(Duration::from_secs)
+    }
+
+    /// Replace the runtime configuration, e.g. with one loaded from a node config file.
+    ///
+    /// Like [`Runtime::reconfigure`], this writes the configuration shared by all
+    /// clones of this runtime.
+    pub fn with_config(self, config: RuntimeConfig) -> Self {
+        *self.config_mut() = config;
+        self
+    }
+
+    /// Spawn a task that ticks mana regeneration at the configured interval.
+    ///
+    /// The interval is re-read before every tick, so `reconfigure` changes
+    /// apply from the next one. Returns `None` when no regenerator or tick
+    /// interval is configured.
+    pub fn spawn_mana_regeneration(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let regenerator = self.context.mana_regenerator.clone()?;
+        self.mana_tick_interval()?;
+        let config = self.config.clone();
+        let tick_interval = move || {
+            config
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .mana_tick_interval_seconds
+                .map(Duration::from_secs)
+        };
+        Some(tokio::spawn(async move {
+            while let Some(interval) = tick_interval() {
+                sleep(interval).await;
+                match regenerator.tick().await {
+                    Ok(details) => {
+                        for error in &details.errors {
+                            error!("Mana regeneration error: {:?}", error);
+                        }
+                    }
+                    Err(e) => error!("Mana regeneration tick failed: {}", e),
+                }
+            }
+        }))
+    }
+
+    /// Set the pricing used to derive mana costs from execution metrics.
+    ///
+    /// The pricing lives in the shared configuration, so existing clones of
+    /// this runtime pick it up as well.
+    pub fn with_fuel_pricing(self, pricing: FuelPricing) -> Self {
+        self.config_mut().fuel_pricing = pricing;
+        self
+    }
+
+    /// Cache compiled modules so repeat executions skip WASM compilation
+    pub fn with_module_cache(mut self, cache: Arc<dyn ModuleCache>) -> Self {
+        self.module_cache = Some(cache);
+        self
+    }
+
+    /// Load and compile the WASM modules stored under `cids` ahead of time so
+    /// their first execution hits the module cache.
+    ///
+    /// Fails on the first module that cannot be loaded or compiled, or if no
+    /// module cache is configured. Modules already cached are not recompiled.
+    pub async fn warm_up(&self, cids: &[String]) -> Result<()> {
+        let cache = self
+            .module_cache
+            .as_ref()
+            .ok_or_else(|| anyhow!("Cannot warm up modules: no module cache configured"))?;
+
+        let started = std::time::Instant::now();
+        let mut compiled = 0usize;
+        for cid in cids {
+            let wasm_bytes = self
+                .storage
+                .load_wasm(cid)
+                .await
+                .with_context(|| format!("Failed to load WASM {} for warm-up", cid))?;
+            let content_cid = wasm_content_cid(&wasm_bytes);
+            if cache.get_module(&content_cid).await.is_some() {
+                continue;
+            }
+            check_abi_version(&wasm_bytes)?;
+            let module = Module::new(&self.engine, &wasm_bytes)
+                .map_err(|e| RuntimeError::LoadError(format!("Failed to compile WASM {}: {}", cid, e)))?;
+            cache.store_module(&content_cid, module).await?;
+            compiled += 1;
+        }
+
+        info!(
+            requested = cids.len(),
+            modules_cached = compiled,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Module warm-up complete"
+        );
+        Ok(())
+    }
+
+    /// Set the clock used for receipt timestamps and job deadlines
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Shared handle to the registry of in-flight jobs
+    pub fn job_registry(&self) -> JobRegistry {
+        self.job_registry.clone()
+    }
+
+    /// IDs of `job_id` and the jobs that spawned it, nearest first
+    pub fn job_lineage(&self, job_id: &str) -> Vec<JobId> {
+        self.context.job_lineage(job_id)
+    }
+
+    /// Jobs currently being processed and their latest status, plus jobs whose
+    /// last attempt failed
+    pub fn active_jobs(&self) -> Vec<(JobId, P2PJobStatus)> {
+        self.job_registry.snapshot()
+    }
+
     /// Get a reference to the runtime context
     pub fn context(&self) -> &RuntimeContext<L> {
         &self.context
     }
 
     /// Get the shared DAG store
-    pub fn dag_store(&self) -> Arc<icn_types::dag_store::SharedDagStore> {
+    pub fn dag_store(&self) -> Arc<dyn DagStore> {
         self.context.dag_store.clone()
     }
 
+    /// Resolve `receipt.logs_cid` into the log lines its job wrote, or an empty
+    /// list when the job logged nothing.
+    pub async fn fetch_logs(&self, receipt: &MeshExecutionReceipt) -> Result<Vec<String>> {
+        let Some(logs_cid) = &receipt.logs_cid else {
+            return Ok(Vec::new());
+        };
+        let node = self
+            .dag_store()
+            .get(logs_cid)
+            .await?
+            .ok_or_else(|| anyhow!("Logs {} of job {} not found in DAG store", logs_cid, receipt.job_id))?;
+        from_json_bounded(node.content.as_bytes(), &DecodeLimits::logs()).with_context(|| {
+            format!("Failed to decode logs {} of job {}", logs_cid, receipt.job_id)
+        })
+    }
+
     /// Execute a proposal by ID
     pub async fn execute_proposal(&mut self, proposal_id: &str) -> Result<MeshExecutionReceipt> {
         let mut proposal = self.storage.load_proposal(proposal_id).await?;
@@ -454,7 +973,10 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             }
         }
 
-        let _wasm_bytes = self.storage.load_wasm(&proposal.wasm_cid).await?;
+        let wasm_bytes = self.storage.load_wasm(&proposal.wasm_cid).await?;
+        if let Some(manifest) = &proposal.compilation_manifest {
+            verify_compilation_manifest(&proposal, manifest, &wasm_bytes)?;
+        }
 
         let executor_did_str = self
             .context
@@ -465,8 +987,8 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
 
         let job_id = format!("proposal-{}", proposal_id);
 
-        let execution_start_time = Utc::now().timestamp() - 2;
-        let execution_end_time_dt = Utc::now();
+        let execution_start_time = self.clock.now().timestamp() - 2;
+        let execution_end_time_dt = self.clock.now();
         let execution_end_time = execution_end_time_dt.timestamp();
 
         let fake_resource_map: HashMap<ResourceType, u64> =
@@ -485,10 +1007,11 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             execution_start_time: execution_start_time as u64,
             execution_end_time: execution_end_time as u64,
             execution_end_time_dt,
-            signature: Vec::new(),
+            signature: TaggedSignature::default(),
             coop_id: None,
             community_id: None,
             mana_cost: None,
+            deterministic: false,
         };
 
         // Store the receipt - Temporarily commented out due to type mismatch
@@ -524,8 +1047,8 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             .did
             .clone();
 
-        let execution_start_time = Utc::now().timestamp() - 1;
-        let execution_end_time_dt = Utc::now();
+        let execution_start_time = self.clock.now().timestamp() - 1;
+        let execution_end_time_dt = self.clock.now();
         let execution_end_time = execution_end_time_dt.timestamp();
 
         let receipt = MeshExecutionReceipt {
@@ -538,10 +1061,11 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             execution_start_time: execution_start_time as u64,
             execution_end_time: execution_end_time as u64,
             execution_end_time_dt,
-            signature: Vec::new(),
+            signature: TaggedSignature::default(),
             coop_id: None,
             community_id: None,
             mana_cost: None,
+            deterministic: false,
         };
 
         Ok(receipt)
@@ -578,8 +1102,12 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
         };
 
         let mut store = store_creator(&self.engine, &self.host_env)?;
+        #[cfg(feature = "full_host_abi")]
+        store
+            .set_fuel(ResourceLimits::default().max_fuel)
+            .map_err(|e| RuntimeError::Execution(e.to_string()))?;
 
-        let module = self.load_module(wasm_bytes, &mut store).await?;
+        let module = self.load_module(wasm_bytes).await?;
 
         let instance = self
             .linker
@@ -600,37 +1128,151 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
         Ok(results.into_boxed_slice())
     }
 
-    /// Helper to load (or get from cache) and compile module (made async)
-    async fn load_module(
-        &self,
-        wasm_bytes: &[u8],
-        _store: &mut Store<wasm::StoreData>,
-    ) -> Result<Module, RuntimeError> {
+    /// Compile `wasm_bytes`, or fetch the compiled module from the module cache.
+    ///
+    /// Modules are cached by the CID of their bytes rather than a caller-given
+    /// code CID, so a mislabelled CID can never return another module. A cache
+    /// miss compiles and stores the module.
+    pub async fn load_module(&self, wasm_bytes: &[u8]) -> Result<Module, RuntimeError> {
+        check_abi_version(wasm_bytes)?;
+        let Some(cache) = &self.module_cache else {
+            return Module::new(&self.engine, wasm_bytes)
+                .map_err(|e| RuntimeError::LoadError(format!("Failed to compile WASM: {}", e)));
+        };
+
+        let content_cid = wasm_content_cid(wasm_bytes);
+        if let Some(module) = cache.get_module(&content_cid).await {
+            debug!(cid = %content_cid, "Module cache hit");
+            return Ok(module);
+        }
         let module = Module::new(&self.engine, wasm_bytes)
             .map_err(|e| RuntimeError::LoadError(format!("Failed to compile WASM: {}", e)))?;
+        if let Err(e) = cache.store_module(&content_cid, module.clone()).await {
+            warn!(cid = %content_cid, error = %e, "Failed to cache compiled module");
+        }
         Ok(module)
     }
 
-    /// Execute a WASM binary with the given context in governance mode
+    /// Execute a WASM binary with the given context in governance mode.
+    ///
+    /// `_start` runs against the mesh host ABI in a governance host environment
+    /// for `context.executor_did`, whose execution ID (the one a mint
+    /// authorization must cover) is `context.code_cid`. Capabilities and mint
+    /// authorization come from the environment set with
+    /// [`Runtime::with_host_environment`]. Without one, a runtime over the
+    /// in-memory ledger enables every capability and refuses minting; other
+    /// runtimes fail with `RuntimeError::HostEnvironmentNotSet`, since host
+    /// environments are built over the in-memory ledger's context.
     #[cfg(feature = "full_host_abi")]
     pub async fn governance_execute_wasm(
         &mut self,
         wasm_bytes: &[u8],
         context: VmContext,
     ) -> Result<ExecutionResult, RuntimeError> {
-        // Full implementation lives behind the feature flag.
-        unimplemented!()
+        let caller_did = Did::from_str(&context.executor_did).map_err(|e| {
+            RuntimeError::Execution(format!("Invalid executor DID {}: {}", context.executor_did, e))
+        })?;
+        let job_id = context.code_cid.clone().unwrap_or_else(|| "governance".to_string());
+        let host_did = self
+            .context
+            .identity()
+            .map(|keypair| keypair.did.clone())
+            .unwrap_or_else(|| caller_did.clone());
+        let mut job = JobExecutionContext::new(
+            job_id.clone(),
+            caller_did.clone(),
+            MeshJobParams::default(),
+            host_did,
+            self.clock.now().timestamp_millis() as u64,
+        );
+        // A job spawned by another job starts as deep in the submission chain as its lineage
+        job.submission_depth = self.context.submission_depth(&job_id);
+        job.resource_limits = context.resource_limits.clone().unwrap_or_default();
+        let max_fuel = job.resource_limits.max_fuel;
+        let job_ctx = Arc::new(tokio::sync::Mutex::new(job));
+
+        let mut host_env = match &self.host_env {
+            Some(env) => env
+                .lock()
+                .map_err(|_| RuntimeError::ExecutionError("Host env mutex poisoned".to_string()))?
+                .clone(),
+            None => {
+                let rt = (self.context.clone() as Arc<dyn std::any::Any + Send + Sync>)
+                    .downcast::<RuntimeContext>()
+                    .map_err(|_| RuntimeError::HostEnvironmentNotSet)?;
+                ConcreteHostEnvironment::new_governance(job_ctx.clone(), caller_did.clone(), rt)
+            }
+        };
+        host_env.ctx = job_ctx.clone();
+        host_env.caller_did = caller_did;
+        host_env.is_governance = true;
+        let host_env = host_env.with_organization(
+            context.coop_id.clone().map(CooperativeId::new),
+            context.community_id.clone().map(CommunityId::new),
+        );
+
+        let module = self.load_module(wasm_bytes).await?;
+        // Messages sent to the job while it runs arrive through `deliver_p2p_message`
+        let host_env = host_env.with_p2p_inbox(self.context.open_p2p_inbox(&job_id));
+        let engine = self.engine.clone();
+        let linker = self.linker.clone();
+        // Run on its own task so a panic while executing the module surfaces
+        // as a failed execution instead of taking the caller down with it
+        let execution = tokio::spawn(async move {
+            let mut store = Store::new(&engine, host_env);
+            store
+                .set_fuel(max_fuel)
+                .map_err(|e| RuntimeError::Execution(e.to_string()))?;
+            let instance = linker
+                .instantiate_async(&mut store, &module)
+                .await
+                .map_err(|e| RuntimeError::Instantiation(e.to_string()))?;
+            let entrypoint = instance
+                .get_typed_func::<(), ()>(&mut store, "_start")
+                .map_err(|_| RuntimeError::FunctionNotFound("_start".to_string()))?;
+            entrypoint
+                .call_async(&mut store, ())
+                .await
+                .map_err(|e| match e.downcast_ref::<wasmtime::Trap>() {
+                    Some(wasmtime::Trap::OutOfFuel) => RuntimeError::Execution(format!(
+                        "fuel exhausted: execution exceeded its limit of {} fuel",
+                        max_fuel
+                    )),
+                    _ => RuntimeError::Execution(e.to_string()),
+                })?;
+            Ok::<_, RuntimeError>(max_fuel.saturating_sub(store.get_fuel().unwrap_or(0)))
+        });
+        let execution = execution.await;
+        self.context.close_p2p_inbox(&job_id);
+        let fuel_used = execution.map_err(|e| {
+            RuntimeError::Execution(format!("Governance execution aborted: {}", e))
+        })??;
+
+        let mut job = job_ctx.lock().await;
+        let anchored_cids = std::mem::take(&mut job.anchored_cids);
+        let metrics = CoreVmExecutionMetrics {
+            anchored_cids_count: anchored_cids.len(),
+            job_submissions_count: job.job_submissions_count,
+            fuel_used,
+            ..CoreVmExecutionMetrics::default()
+        };
+        Ok(ExecutionResult {
+            metrics,
+            anchored_cids,
+            resource_usage: std::mem::take(&mut job.resource_usage),
+            logs: std::mem::take(&mut job.logs),
+        })
     }
 
+    /// Without the full host ABI, governance modules run on the core VM with
+    /// only its host functions available, under the context's resource limits.
     #[cfg(not(feature = "full_host_abi"))]
     pub async fn governance_execute_wasm(
         &mut self,
-        _wasm_bytes: &[u8],
-        _context: VmContext,
+        wasm_bytes: &[u8],
+        context: VmContext,
     ) -> Result<ExecutionResult, RuntimeError> {
-        Err(RuntimeError::ExecutionError(
-            "governance WASM disabled in minimal build".into(),
-        ))
+        execute_on_core_vm(wasm_bytes, &context)
     }
 
     /// Issue an execution receipt after successful execution
@@ -641,33 +1283,34 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
         result: &ExecutionResult,
         context: &VmContext,
     ) -> Result<RuntimeExecutionReceipt> {
-        // Map fields from CoreVmExecutionMetrics (result.metrics) to RuntimeExecutionMetrics (vc_metrics)
+        // Map fields from CoreVmExecutionMetrics (result.metrics) to RuntimeExecutionMetrics (vc_metrics).
+        // An explicit mana cost wins; otherwise it is priced from the metrics.
+        let mana_cost = result
+            .metrics
+            .mana_cost
+            .unwrap_or_else(|| self.config().fuel_pricing.mana_cost(&result.metrics));
         let vc_metrics = RuntimeExecutionMetrics {
             host_calls: result.metrics.host_calls,
             io_bytes: result.metrics.io_bytes,
-            mana_cost: result.metrics.mana_cost,
+            mana_cost: Some(mana_cost),
         };
 
-        let receipt_id = Uuid::new_v4().to_string();
-
-        // Create receipt first with signature: None
-        let mut receipt = RuntimeExecutionReceipt {
-            id: receipt_id,
-            issuer: context.executor_did.clone(), // This should be the DID of the runtime itself
-            proposal_id: context.code_cid.clone().unwrap_or_default(),
-            wasm_cid: wasm_cid.to_string(),
-            ccl_cid: ccl_cid.to_string(),
-            metrics: vc_metrics,
-            anchored_cids: result.anchored_cids.clone(),
-            resource_usage: result.resource_usage.clone(),
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map_err(|e| RuntimeError::ReceiptError(e.to_string()))?
-                .as_secs(),
-            dag_epoch: context.epoch.as_ref().and_then(|s| s.parse().ok()),
-            receipt_cid: None, // Will be set by anchor_receipt
-            signature: None,   // Initialized to None, will be set by signing
-        };
+        // Build the unsigned receipt; signing fills in the signature below
+        let mut receipt = RuntimeExecutionReceipt::builder()
+            .id(Uuid::new_v4().to_string())
+            .issuer(context.executor_did.clone()) // This should be the DID of the runtime itself
+            .proposal_id(context.code_cid.clone().unwrap_or_default())
+            .wasm_cid(wasm_cid)
+            .ccl_cid(ccl_cid)
+            .metrics(vc_metrics)
+            .anchored_cids(result.anchored_cids.clone())
+            .resource_usage(result.resource_usage.clone())
+            .timestamp(self.clock.epoch())
+            .dag_epoch(context.epoch.as_ref().and_then(|s| s.parse().ok()))
+            .coop_id(context.coop_id.clone().map(CooperativeId::new))
+            .community_id(context.community_id.clone().map(CommunityId::new))
+            .build()
+            .map_err(|e| RuntimeError::ReceiptError(e.to_string()))?;
 
         // Sign the receipt using the runtime's identity
         let keypair = self.context.identity()
@@ -682,7 +1325,66 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
         Ok(receipt)
     }
 
-    /// Anchor a receipt to the DAG and return the CID
+    /// Check that a receipt's metrics could have been produced under `limits`.
+    ///
+    /// See [`receipt_validation`] for the individual checks.
+    pub fn validate_receipt_metrics(
+        &self,
+        receipt: &RuntimeExecutionReceipt,
+        limits: &ResourceLimits,
+    ) -> Result<(), MetricsViolation> {
+        receipt_validation::check_receipt_metrics(receipt, limits)
+    }
+
+    /// The CIDs `receipt` anchors, loading a compacted list from storage and
+    /// checking it against the receipt's Merkle root.
+    pub async fn resolve_anchored_cids(&self, receipt: &RuntimeExecutionReceipt) -> Result<Vec<String>> {
+        let Some(root) = &receipt.anchored_cids_root else {
+            return Ok(receipt.anchored_cids.clone());
+        };
+        let cids = self.storage.load_anchored_cids(&root.root).await?;
+        receipt
+            .verify_anchored_cids(&cids)
+            .context("Stored anchored CID list does not match the receipt")?;
+        Ok(cids)
+    }
+
+    /// The receipt as it will be anchored, with its own CID filled in, and the
+    /// DagNode holding it. The node CID is derived from the receipt alone, so it
+    /// identifies an earlier anchoring of the same receipt.
+    fn receipt_anchor_node(
+        receipt: &RuntimeExecutionReceipt,
+        scope_id: &str,
+    ) -> Result<(RuntimeExecutionReceipt, DagNode, String)> {
+        let receipt_cid = receipt
+            .cid()
+            .map_err(|e| anyhow!("Failed to generate CID for receipt: {}", e))?;
+        let mut receipt_to_anchor = receipt.clone();
+        receipt_to_anchor.receipt_cid = Some(receipt_cid.to_string());
+
+        let receipt_json_string = serde_json::to_string(&receipt_to_anchor)
+            .context("Failed to serialize receipt to JSON string for DagNode content")?;
+        let node = DagNode {
+            content: receipt_json_string,
+            parent: None, // TODO: Determine parent if applicable. For now, assuming root or standalone.
+            event_type: DagEventType::Receipt,
+            timestamp: receipt_to_anchor.timestamp,
+            scope_id: scope_id.to_string(), // Using issuer's DID as scope for this example
+        };
+        let node_cid = node
+            .cid()
+            .context("Failed to compute CID of receipt DagNode")?
+            .to_string();
+        Ok((receipt_to_anchor, node, node_cid))
+    }
+
+    /// Anchor a receipt to the DAG and return the CID.
+    ///
+    /// Safe to retry: a receipt that is already in the DAG is returned before
+    /// any verification, storage, scoring or charging is repeated. The DAG node
+    /// only stays in place once the issuer's mana has been deducted, so a
+    /// failed deduction leaves the receipt unanchored and a retry charges it
+    /// again rather than not at all.
     pub async fn anchor_receipt(
         &self,
         receipt: &RuntimeExecutionReceipt, // Kept specific to RuntimeExecutionReceipt
@@ -695,10 +1397,41 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             .federation_id
             .as_deref()
             .unwrap_or("unknown_federation");
-        let coop_id_label = federation_id;
-        let community_id_label = federation_id;
+        let coop_id_label = receipt
+            .coop_id
+            .as_ref()
+            .map(|id| id.0.as_str())
+            .unwrap_or(federation_id);
+        let community_id_label = receipt
+            .community_id
+            .as_ref()
+            .map(|id| id.0.as_str())
+            .unwrap_or(federation_id);
         let issuer_did_label = receipt.issuer.as_str();
 
+        // A long anchored-CID list moves out of the receipt, keeping its Merkle
+        // root. Both the compacted and the inline form are candidates, since the
+        // list store may have been unavailable on an earlier attempt.
+        let inline = Self::receipt_anchor_node(receipt, issuer_did_label)?;
+        let mut compacted = None;
+        if let Some(limit) = self.config().anchored_cids_inline_limit {
+            let mut receipt = receipt.clone();
+            if let Some(cids) = receipt.compact_anchored_cids(limit) {
+                compacted = Some((Self::receipt_anchor_node(&receipt, issuer_did_label)?, cids));
+            }
+        }
+
+        // Held for the whole anchoring so concurrent retries anchor once
+        let _anchoring = self.receipt_anchor_lock.lock().await;
+        let dag_store = self.dag_store();
+        for (anchored, _, node_cid) in compacted.iter().map(|(node, _)| node).chain([&inline]) {
+            if dag_store.get(node_cid).await?.is_some() {
+                let receipt_cid = anchored.receipt_cid.clone().unwrap_or_default();
+                tracing::info!(original_receipt_cid = %receipt_cid, dag_node_cid = %node_cid, "Receipt already anchored, skipping side effects");
+                return Ok(receipt_cid);
+            }
+        }
+
         // 1. Verify signature
         match receipt.verify_signature() {
             Ok(_) => {
@@ -722,54 +1455,100 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             }
         };
 
-        // 2. Generate the content-addressed CID for the receipt
-        // This now assumes RuntimeExecutionReceipt has a working .cid() method.
-        let actual_receipt_cid = receipt
-            .cid()
-            .map_err(|e| anyhow!("Failed to generate CID for receipt: {}", e))?;
-
-        // 3. Create a version of the receipt that includes its own CID
-        let mut receipt_to_anchor = receipt.clone();
-        receipt_to_anchor.receipt_cid = Some(actual_receipt_cid.to_string());
-
-        // 4. Serialize the receipt_to_anchor for DAG storage
-        let _receipt_bytes = serde_cbor::to_vec(&receipt_to_anchor)
-            .context("Failed to serialize receipt for DAG storage")?;
-
-        // 5. Store the serialized receipt in the DAG Store using its actual CID
-        // OLD: self.dag_store().put_raw_block(&actual_receipt_cid, receipt_bytes, 0x71u64).await
-        //    .with_context(|| format!("Failed to anchor receipt CID {} to DAG store", actual_receipt_cid))?;
+        // Refuse receipts whose metrics no execution under our limits could produce,
+        // and count them against the issuer's reputation
+        let receipt_limits = self.config().receipt_limits;
+        if let Err(violation) = self.validate_receipt_metrics(receipt, &receipt_limits) {
+            tracing::warn!(receipt_id = %receipt.id, issuer = %receipt.issuer, "Refusing receipt with impossible metrics: {}", violation);
+            if let Some(updater) = &self.reputation_updater {
+                if let Err(e) = updater
+                    .submit_receipt_based_reputation(receipt, false, coop_id_label, community_id_label)
+                    .await
+                {
+                    tracing::warn!(receipt_id = %receipt.id, "Failed to submit negative reputation update: {}", e);
+                }
+            }
+            return Err(violation).context("Receipt metrics violate resource limits");
+        }
 
-        // NEW: Construct DagNode and insert
-        let receipt_json_string = serde_json::to_string(&receipt_to_anchor)
-            .context("Failed to serialize receipt to JSON string for DagNode content")?;
+        // The issuer pays for the receipt, so an unparsable issuer is refused
+        // before anything is anchored
+        let mana_charge = match receipt.metrics.mana_cost {
+            Some(cost) if cost > 0 && self.reputation_updater.is_some() => {
+                let executor_did = Did::from_str(&receipt.issuer).with_context(|| {
+                    format!(
+                        "Failed to parse issuer DID {} for mana deduction",
+                        receipt.issuer
+                    )
+                })?;
+                Some((executor_did, cost))
+            }
+            _ => None,
+        };
 
-        let dag_node_for_receipt = DagNode {
-            content: receipt_json_string,
-            parent: None, // TODO: Determine parent if applicable. For now, assuming root or standalone.
-            event_type: DagEventType::Receipt,
-            timestamp: receipt_to_anchor.timestamp,
-            scope_id: issuer_did_label.to_string(), // Using issuer's DID as scope for this example
+        // 2. Store a compacted receipt's anchored-CID list, falling back to inline
+        let (receipt_to_anchor, dag_node_for_receipt, node_cid) = match compacted {
+            Some(((compacted, node, node_cid), cids)) => {
+                let root = compacted
+                    .anchored_cids_root
+                    .as_ref()
+                    .map(|root| root.root.clone())
+                    .unwrap_or_default();
+                match self.storage.store_anchored_cids(&root, &cids).await {
+                    Ok(()) => (compacted, node, node_cid),
+                    Err(e) => {
+                        tracing::warn!(receipt_id = %receipt.id, "Keeping anchored CIDs inline: {}", e);
+                        inline
+                    }
+                }
+            }
+            None => inline,
         };
+        let actual_receipt_cid = receipt_to_anchor.receipt_cid.clone().unwrap_or_default();
 
-        // The CID of this dag_node_for_receipt will be different from actual_receipt_cid if DagNode adds metadata.
-        // The insert method will calculate it internally.
-        self.dag_store().insert(dag_node_for_receipt).await
+        // 3. Insert the receipt DagNode. Only the insert is retried: the checks
+        // above are deterministic
+        self.anchor_retry
+            .retry(|| dag_store.insert(dag_node_for_receipt.clone()))
+            .await
             .with_context(|| format!("Failed to insert receipt DagNode (derived from original CID {}) into DAG store", actual_receipt_cid))?;
-        tracing::info!(original_receipt_cid = %actual_receipt_cid, "Receipt (as DagNode) submitted to DAG store");
+        tracing::info!(original_receipt_cid = %actual_receipt_cid, dag_node_cid = %node_cid, "Receipt (as DagNode) submitted to DAG store");
+
+        // 4. Deduct the issuer's mana. The node is what marks the receipt as
+        // anchored, so it is withdrawn again if the deduction fails
+        if let (Some((executor_did, cost)), Some(updater)) =
+            (&mana_charge, &self.reputation_updater)
+        {
+            if let Err(e) = updater
+                .submit_mana_deduction(executor_did, *cost, coop_id_label, community_id_label)
+                .await
+            {
+                if let Err(remove_err) = dag_store.remove(&node_cid).await {
+                    tracing::error!(receipt_id = %receipt.id, dag_node_cid = %node_cid, "Failed to withdraw receipt DagNode after failed mana deduction: {}", remove_err);
+                }
+                return Err(e).with_context(|| {
+                    format!("Failed to deduct {} mana for receipt {}", cost, receipt.id)
+                });
+            }
+            tracing::info!(
+                receipt_id = %receipt.id,
+                executor = %receipt.issuer,
+                mana_deducted = cost,
+                "Mana deduction submitted successfully."
+            );
+        }
 
-        // 6. Store in local Sled storage (optional, for quick lookups by ID if still needed)
+        // 5. Store in local Sled storage (optional, for quick lookups by ID if still needed)
         self.storage
             .store_receipt(&receipt_to_anchor)
             .await
             .context("Failed to store receipt in local Sled storage after DAG anchoring")?;
 
-        // 7. Anchoring receipt.anchored_cids:
-        // The loop `for cid_str in &receipt.anchored_cids` and its call to `self.storage.anchor_to_dag(cid_str).await`
-        // is removed. Storing `receipt_to_anchor` (which contains `anchored_cids`) in the DAG
-        // effectively anchors these references as part of the receipt's immutable record.
+        // 6. Anchoring receipt.anchored_cids:
+        // Storing `receipt_to_anchor` (which contains `anchored_cids` or their root)
+        // in the DAG anchors these references as part of the receipt's immutable record.
 
-        // 8. Submit reputation update
+        // 7. Submit reputation update
         if let Some(updater) = &self.reputation_updater {
             match updater
                 .submit_receipt_based_reputation(
@@ -791,58 +1570,7 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             tracing::info!(receipt_id = %receipt_to_anchor.id, "No reputation updater configured, skipping submission");
         }
 
-        // Perform Mana Deduction if applicable
-        if let Some(cost) = receipt_to_anchor.metrics.mana_cost {
-            if cost > 0 {
-                if let Some(updater) = &self.reputation_updater {
-                    match Did::from_str(&receipt_to_anchor.issuer) {
-                        Ok(executor_did_val) => {
-                            match updater
-                                .submit_mana_deduction(
-                                    &executor_did_val,
-                                    cost,
-                                    coop_id_label,
-                                    community_id_label,
-                                )
-                                .await
-                            {
-                                Ok(_) => tracing::info!(
-                                    receipt_id = %receipt_to_anchor.id,
-                                    executor = %receipt_to_anchor.issuer,
-                                    mana_deducted = cost,
-                                    "Mana deduction submitted successfully."
-                                ),
-                                Err(e) => tracing::warn!(
-                                    receipt_id = %receipt_to_anchor.id,
-                                    executor = %receipt_to_anchor.issuer,
-                                    "Failed to submit mana deduction: {}", e
-                                ),
-                            }
-                        }
-                        Err(e) => {
-                            tracing::error!(
-                                receipt_id = %receipt_to_anchor.id,
-                                issuer_did = %receipt_to_anchor.issuer,
-                                "Failed to parse issuer DID for mana deduction: {}. Skipping deduction.", e
-                            );
-                        }
-                    }
-                }
-            } else {
-                tracing::debug!(
-                    receipt_id = %receipt_to_anchor.id,
-                    mana_cost = cost,
-                    "Mana cost is zero or not set for receipt, skipping deduction."
-                );
-            }
-        } else {
-            tracing::debug!(
-                receipt_id = %receipt_to_anchor.id,
-                "No mana cost found in receipt metrics, skipping deduction."
-            );
-        }
-
-        // 9. Record metrics
+        // 8. Record metrics
         let duration = start_time.elapsed();
         metrics::observe_anchor_receipt_duration(
             duration.as_secs_f64(),
@@ -862,7 +1590,94 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
                 .observe(mana_cost as f64);
         }
 
-        Ok(actual_receipt_cid.to_string())
+        Ok(actual_receipt_cid)
+    }
+
+    /// Verify a receipt issued by another federation and import it into the local DAG.
+    ///
+    /// The receipt is checked against `issuing_bundle` rather than this runtime's own
+    /// trust validator: the bundle's quorum proof must verify, and the receipt issuer
+    /// must be one of its signers. The imported DagNode carries an `external_origin`
+    /// marker naming the issuing federation. Returns the CID of the local DagNode.
+    pub async fn verify_and_import_external_receipt(
+        &self,
+        receipt: &RuntimeExecutionReceipt,
+        issuing_bundle: &TrustBundle,
+    ) -> Result<String> {
+        let federation = issuing_bundle.federation_metadata.name.clone();
+
+        // 1. Collect the bundle's signers and verify its quorum proof
+        let proof = issuing_bundle
+            .quorum_proof
+            .as_ref()
+            .ok_or_else(|| anyhow!("Trust bundle for federation {} has no quorum proof", federation))?;
+        let signers = proof
+            .signatures
+            .iter()
+            .map(|(did, _)| did.verifying_key().map(|key| (did.clone(), key)))
+            .collect::<Result<HashMap<Did, VerifyingKey>>>()
+            .context("Failed to resolve trust bundle signer keys")?;
+        issuing_bundle
+            .verify(&signers)
+            .with_context(|| format!("Trust bundle for federation {} failed verification", federation))?;
+
+        // 2. The issuer must be one of the bundle's signers
+        let issuer = Did::from_str(&receipt.issuer).map_err(RuntimeError::DidError)?;
+        if !signers.contains_key(&issuer) {
+            return Err(RuntimeError::UntrustedExternalIssuer {
+                issuer: receipt.issuer.clone(),
+                federation,
+            }
+            .into());
+        }
+
+        // 3. Verify the receipt signature against the issuer's key
+        receipt
+            .verify_signature()
+            .context("External receipt signature verification failed")?;
+
+        let receipt_cid = receipt
+            .cid()
+            .map_err(|e| anyhow!("Failed to generate CID for external receipt: {}", e))?;
+        let mut receipt_to_import = receipt.clone();
+        receipt_to_import.receipt_cid = Some(receipt_cid.to_string());
+
+        // 4. Tag the content with its origin, keeping the receipt fields top-level
+        let mut content = serde_json::to_value(&receipt_to_import)
+            .context("Failed to serialize external receipt for DagNode content")?;
+        if let Some(fields) = content.as_object_mut() {
+            fields.insert(
+                "external_origin".to_string(),
+                serde_json::json!({
+                    "federation": federation,
+                    "trust_bundle_cid": issuing_bundle.root_dag_cid,
+                }),
+            );
+        }
+
+        let node = DagNode {
+            content: content.to_string(),
+            parent: None,
+            event_type: DagEventType::Receipt,
+            timestamp: receipt_to_import.timestamp,
+            scope_id: receipt_to_import.issuer.clone(),
+        };
+        let local_cid = node
+            .cid()
+            .map_err(|e| anyhow!("Failed to compute CID for external receipt DagNode: {}", e))?;
+
+        self.dag_store()
+            .insert(node)
+            .await
+            .with_context(|| format!("Failed to import external receipt {} into DAG store", receipt_cid))?;
+        tracing::info!(
+            original_receipt_cid = %receipt_cid,
+            local_cid = %local_cid,
+            federation = %federation,
+            "External receipt imported"
+        );
+
+        Ok(local_cid.to_string())
     }
 
     /// Verify a trust bundle using the configured trust validator
@@ -874,7 +1689,12 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
 
         validator
             .set_trust_bundle(bundle.clone())
-            .map_err(RuntimeError::TrustBundleVerificationError)
+            .map_err(RuntimeError::TrustBundleVerificationError)?;
+        // The supply ceiling comes from the quorum-signed federation metadata
+        self.context
+            .economics
+            .set_supply_cap(bundle.federation_metadata.max_token_supply);
+        Ok(())
     }
 
     /// Register a trusted signer with DID and verifying key
@@ -931,8 +1751,8 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
         .cloned()
         .collect();
 
-        let execution_start_time = Utc::now().timestamp() - 1;
-        let execution_end_time_dt = Utc::now();
+        let execution_start_time = self.clock.now().timestamp() - 1;
+        let execution_end_time_dt = self.clock.now();
         let execution_end_time = execution_end_time_dt.timestamp();
 
         Ok(MeshExecutionReceipt {
@@ -945,10 +1765,11 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             execution_start_time: execution_start_time as u64,
             execution_end_time: execution_end_time as u64,
             execution_end_time_dt,
-            signature: Vec::new(),
+            signature: TaggedSignature::default(),
             coop_id: None,
             community_id: None,
             mana_cost: _params.explicit_mana_cost, // Or calculated cost
+            deterministic: _params.deterministic,
         })
     }
 
@@ -972,285 +1793,458 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
             ..Default::default()
         };
 
-        let engine = Engine::default();
+        let engine = runtime_engine();
         let mut linker = Linker::new(&engine);
         crate::wasm::register_host_functions(&mut linker)
             .expect("Failed to register host functions for Runtime::with_context");
 
         Self {
-            config,
+            config: Arc::new(std::sync::RwLock::new(config)),
             storage,
             context,
             engine,
             linker,
             host_env: None,
             reputation_updater: None,
+            status_reporter: None,
+            clock: Arc::new(SystemClock),
+            job_registry: JobRegistry::new(),
+            module_cache: None,
+            usage_plausibility: UsagePlausibility::default(),
+            receipt_anchor_lock: Arc::new(tokio::sync::Mutex::new(())),
+            anchor_retry: RetryPolicy::default(),
+            http_client: reqwest::Client::new(),
+            poll_failures: Arc::new(AtomicU32::new(0)),
         }
     }
 
     /// Main loop for the runtime node service
     pub async fn run_forever(self) -> Result<()> {
+        self.run_until(std::future::pending()).await
+    }
+
+    /// Poll for and execute jobs until `shutdown` completes.
+    ///
+    /// Up to `max_concurrent_jobs` jobs run at once, each on its own task.
+    /// Once `shutdown` completes no further jobs are polled, and the jobs
+    /// already running are allowed to finish so their receipts are anchored.
+    /// The first error from a job stops the loop the same way and is returned.
+    pub async fn run_until(self, shutdown: impl Future<Output = ()>) -> Result<()> {
+        let max_concurrent_jobs = self.config().max_concurrent_jobs();
         info!(
+            max_concurrent_jobs,
             "ICN Runtime node started with DID: {}",
-            self.config.node_did
+            self.config().node_did
         );
 
-        let http_client = reqwest::Client::new();
-
-        loop {
-            let maybe_job = self.poll_for_job().await;
+        let runtime = Arc::new(self);
+        let http_client = runtime.http_client.clone();
+        // Anchoring is serialized so concurrent jobs commit receipts one at a time
+        let anchor_lock = Arc::new(tokio::sync::Mutex::new(()));
+        let permits = Arc::new(Semaphore::new(max_concurrent_jobs));
+        let mut in_flight = JoinSet::new();
+        tokio::pin!(shutdown);
+
+        let mut result = loop {
+            let permit = tokio::select! {
+                _ = &mut shutdown => break Ok(()),
+                permit = permits.clone().acquire_owned() => {
+                    permit.expect("job semaphore is never closed")
+                }
+            };
+            if let Some(err) = take_finished_job_error(&mut in_flight) {
+                break Err(err);
+            }
 
-            if let Some(job) = maybe_job {
-                info!(job_id = %job.job_id, "Received job");
-                let current_job_id_cid_for_reporting = job.job_id.clone();
+            // Jobs queued by this runtime (spawned children, retries) go before new work
+            let queued = runtime.context.next_queued_job();
+            let queued_locally = queued.is_some();
+            let job = match queued {
+                Some(job) => Some(job),
+                None => runtime.poll_for_job().await,
+            };
+            let Some(job) = job else {
+                drop(permit);
+                tracing::debug!("No jobs available. Sleeping...");
+                tokio::select! {
+                    _ = &mut shutdown => break Ok(()),
+                    _ = sleep(runtime.job_poll_delay()) => continue,
+                }
+            };
+
+            let runtime = runtime.clone();
+            let http_client = http_client.clone();
+            let anchor_lock = anchor_lock.clone();
+            in_flight.spawn(async move {
+                let _permit = permit;
+                runtime
+                    .handle_polled_job(job, queued_locally, &http_client, &anchor_lock)
+                    .await
+            });
+        };
 
-                match self.process_polled_job(job.clone()).await {
-                    Ok(receipt) => {
-                        if receipt.status == IcnJobStatus::Failed {
-                            warn!(
-                                job_id = %receipt.job_id,
-                                "Job processing returned Ok(receipt), but receipt status is Failed."
-                            );
+        if !in_flight.is_empty() {
+            info!(jobs = in_flight.len(), "Waiting for in-flight jobs to finish");
+        }
+        while let Some(joined) = in_flight.join_next().await {
+            let job_result = joined.map_err(|e| anyhow!("Job task failed: {}", e)).and_then(|r| r);
+            if let Err(e) = job_result {
+                if result.is_ok() {
+                    result = Err(e);
+                } else {
+                    error!("Job failed during shutdown: {:?}", e);
+                }
+            }
+        }
+        result
+    }
 
-                            let failure_reason = JobFailureReason::ExecutionError(
-                                "Job completed with a 'Failed' status in its execution receipt"
-                                    .to_string(),
-                            );
+    /// Execute one polled job, anchor its receipt and report failures.
+    ///
+    /// `queued_locally` marks jobs taken from this runtime's own queue rather than
+    /// the mesh job service.
+    async fn handle_polled_job(
+        &self,
+        job: icn_types::mesh::MeshJob,
+        queued_locally: bool,
+        http_client: &reqwest::Client,
+        anchor_lock: &tokio::sync::Mutex<()>,
+    ) -> Result<()> {
+        info!(job_id = %job.job_id, "Received job");
+        self.context.begin_job_budget(&job);
+        if !queued_locally {
+            self.context.begin_polled_job(&job.job_id);
+        }
+        let current_job_id_cid_for_reporting = job.job_id.clone();
+        let job_started_at = std::time::Instant::now();
+        // Time budget derived from the job deadline, if any (0 = unknown)
+        let job_limit_secs = job
+            .params
+            .deadline
+            .map(|deadline| deadline.saturating_sub(self.clock.epoch()))
+            .unwrap_or(0);
+        let timeout_reason = || JobFailureReason::Timeout {
+            elapsed_secs: job_started_at.elapsed().as_secs(),
+            limit_secs: job_limit_secs,
+        };
+        if let Ok(node_id) = Did::from_str(&self.config().node_did) {
+            self.job_registry.register(
+                current_job_id_cid_for_reporting.to_string(),
+                P2PJobStatus::Running {
+                    node_id,
+                    current_stage_index: None,
+                    current_stage_id: None,
+                    progress_percent: None,
+                    status_message: None,
+                },
+            );
+        }
 
-                            let executor_node_did_str = self.config.node_did.clone();
-                            let parsed_node_did = match Did::from_str(&executor_node_did_str) {
-                                Ok(did) => did,
-                                Err(did_parse_err) => {
-                                    error!(
-                                        "CRITICAL: Runtime's configured node_did '{}' is invalid: {}. Cannot report job failure accurately.",
-                                        executor_node_did_str, did_parse_err
-                                    );
-                                    return Err(anyhow!(
-                                        "Runtime configuration error: node_did '{}' is invalid: {}",
-                                        executor_node_did_str,
-                                        did_parse_err
-                                    ));
-                                }
-                            };
-                            
-                            if let Some(base_url) = &self.config.mesh_jobs_api_url {
-                                let report_payload = RuntimeJobFailureReport {
-                                    reporting_node_did: parsed_node_did.clone(),
-                                    reason: failure_reason.clone(),
-                                };
-                                let report_url = format!(
-                                    "{}/jobs/{}/runtime-failure",
-                                    base_url,
-                                    receipt.job_id.to_string()
-                                );
-                                info!("Reporting job failure for {} to: {}", receipt.job_id.to_string(), report_url);
-                                match http_client.post(&report_url).json(&report_payload).send().await {
-                                    Ok(response) if response.status().is_success() => {
-                                        info!(
-                                            "Successfully reported job failure for {} to mesh-jobs. Status: {}",
-                                            receipt.job_id.to_string(), response.status()
-                                        );
-                                    }
-                                    Ok(response) => {
-                                        let status = response.status();
-                                        error!(
-                                            job_id = %receipt.job_id.to_string(),
-                                            status = %status,
-                                            "Failed to report job failure to mesh-jobs. HTTP status: {}. Response: {:?}",
-                                            status,
-                                            response.text().await.unwrap_or_else(|_| "Failed to read response body".to_string())
-                                        );
-                                    }
-                                    Err(err) => {
-                                        error!(
-                                            job_id = %receipt.job_id.to_string(),
-                                            "HTTP client error reporting job failure to mesh-jobs: {:?}",
-                                            err
-                                        );
-                                    }
-                                }
-                            } else {
-                                warn!(
-                                    job_id = %receipt.job_id.to_string(),
-                                    "mesh_jobs_api_url not configured. Skipping job failure reporting."
-                                );
-                            }
+        // Held until the receipt is anchored so concurrent jobs of the same
+        // originator cannot commit more mana than its balance holds
+        let _reservation = match self.context.reserve_job_mana(&job).await {
+            Ok(reservation) => reservation,
+            Err(reason) => {
+                warn!(job_id = %current_job_id_cid_for_reporting, %reason, "Could not reserve mana for job");
+                match Did::from_str(&self.config().node_did) {
+                    Ok(node_did) => {
+                        self.report_job_failure(http_client, &current_job_id_cid_for_reporting, node_did, reason)
+                            .await
+                    }
+                    Err(e) => error!(
+                        job_id = %current_job_id_cid_for_reporting,
+                        "Runtime node_did is invalid ({}). Cannot report P2PJobStatus::Failed.", e
+                    ),
+                }
+                self.job_registry.finish(&current_job_id_cid_for_reporting);
+                self.context.finish_job(&current_job_id_cid_for_reporting);
+                return Ok(());
+            }
+        };
 
-                            let _failed_status_update = P2PJobStatus::Failed {
-                                node_id: parsed_node_did,
-                                reason: failure_reason,
-                            };
-                            continue; 
+        match self.process_polled_job(job.clone(), queued_locally).await {
+            Ok(receipt) => {
+                if receipt.status == IcnJobStatus::Failed {
+                    warn!(
+                        job_id = %receipt.job_id,
+                        "Job processing returned Ok(receipt), but receipt status is Failed."
+                    );
+
+                    let failure_reason = JobFailureReason::ExecutionError(
+                        "Job completed with a 'Failed' status in its execution receipt"
+                            .to_string(),
+                    );
+
+                    let executor_node_did_str = self.config().node_did.clone();
+                    let parsed_node_did = match Did::from_str(&executor_node_did_str) {
+                        Ok(did) => did,
+                        Err(did_parse_err) => {
+                            error!(
+                                "CRITICAL: Runtime's configured node_did '{}' is invalid: {}. Cannot report job failure accurately.",
+                                executor_node_did_str, did_parse_err
+                            );
+                            return Err(anyhow!(
+                                "Runtime configuration error: node_did '{}' is invalid: {}",
+                                executor_node_did_str,
+                                did_parse_err
+                            ));
                         }
+                    };
+                    
+                    self.report_job_failure(
+                        http_client,
+                        &current_job_id_cid_for_reporting,
+                        parsed_node_did,
+                        failure_reason,
+                    )
+                    .await;
+                    self.job_registry.finish(&current_job_id_cid_for_reporting);
+                    self.context.finish_job(&current_job_id_cid_for_reporting);
+                    return Ok(());
+                }
 
-                        info!(job_id = %receipt.job_id, "Execution succeeded. Anchoring receipt...");
-                        self.anchor_mesh_receipt(&receipt).await?;
-                    }
-                    Err(e) => {
-                        warn!(job_id = %current_job_id_cid_for_reporting, "Job processing failed: {:?}", e);
-                        
-                        let failure_reason = if let Some(icn_err) = e.downcast_ref::<IcnError>() {
-                            match icn_err {
-                                IcnError::Io(_) => JobFailureReason::NetworkError,
-                                IcnError::Serialization(_) => JobFailureReason::OutputError,
-                                IcnError::InvalidUri(_) => JobFailureReason::InvalidInput,
-                                IcnError::NotFound(_) => JobFailureReason::NotFound,
-                                IcnError::PermissionDenied(s) => {
-                                    JobFailureReason::ExecutionError(format!("Permission denied: {}", s))
-                                }
-                                IcnError::Identity(_) => JobFailureReason::PermissionDenied,
-                                IcnError::Economics(econ_err) => match econ_err {
-                                    EconomicsError::QuotaExceeded { .. } | EconomicsError::RateLimitExceeded { .. } => {
-                                        JobFailureReason::ResourceLimitExceeded
-                                    }
-                                    EconomicsError::AccessDenied { .. } => JobFailureReason::PermissionDenied,
-                                    _ => JobFailureReason::ExecutionError(format!("Economics error: {}", econ_err)),
-                                },
-                                IcnError::Crypto(err) => JobFailureReason::ExecutionError(format!("Crypto error: {}", err)),
-                                IcnError::Dag(err) => JobFailureReason::ExecutionError(format!("DAG error: {}", err)),
-                                IcnError::Multicodec(err) => JobFailureReason::ExecutionError(format!("Multicodec error: {}", err)),
-                                IcnError::Trust(err) => JobFailureReason::ExecutionError(format!("Trust error: {}", err)),
-                                IcnError::Mesh(err) => JobFailureReason::ExecutionError(format!("Mesh error: {}", err)),
-                                IcnError::Timeout(s) => JobFailureReason::ExecutionError(format!("Timeout: {}", s)),
-                                IcnError::Config(s) => JobFailureReason::ExecutionError(format!("Config error: {}", s)),
-                                IcnError::Storage(s) => JobFailureReason::ExecutionError(format!("Storage error: {}", s)),
-                                IcnError::Database(s) => JobFailureReason::ExecutionError(format!("Database error: {}", s)),
-                                IcnError::Plugin(s) => JobFailureReason::ExecutionError(format!("Plugin error: {}", s)),
-                                IcnError::Consensus(s) => JobFailureReason::ExecutionError(format!("Consensus error: {}", s)),
-                                IcnError::InvalidOperation(s) => JobFailureReason::ExecutionError(format!("Invalid operation: {}", s)),
-                                IcnError::General(s) => JobFailureReason::Unknown(s.clone()),
-                            }
-                        } else {
-                            JobFailureReason::ExecutionError(e.to_string())
-                        };
-                        
-                        let executor_node_did_str = self.config.node_did.clone();
-                        match Did::from_str(&executor_node_did_str) {
-                            Ok(parsed_node_did) => {
-                                if let Some(base_url) = &self.config.mesh_jobs_api_url {
-                                    let report_payload = RuntimeJobFailureReport {
-                                        reporting_node_did: parsed_node_did.clone(),
-                                        reason: failure_reason.clone(),
-                                    };
-                                    let report_url = format!(
-                                        "{}/jobs/{}/runtime-failure",
-                                        base_url,
-                                        current_job_id_cid_for_reporting.to_string()
-                                    );
-                                    info!("Reporting job failure for {} to: {}", current_job_id_cid_for_reporting.to_string(), report_url);
-                                    match http_client.post(&report_url).json(&report_payload).send().await {
-                                        Ok(response) if response.status().is_success() => {
-                                            info!(
-                                                "Successfully reported job failure for {} to mesh-jobs. Status: {}",
-                                                current_job_id_cid_for_reporting.to_string(), response.status()
-                                            );
-                                        }
-                                        Ok(response) => {
-                                            let status = response.status();
-                                            error!(
-                                                job_id = %current_job_id_cid_for_reporting.to_string(),
-                                                status = %status,
-                                                "Failed to report job failure to mesh-jobs. HTTP status: {}. Response: {:?}",
-                                                status,
-                                                response.text().await.unwrap_or_else(|_| "Failed to read response body".to_string())
-                                            );
-                                        }
-                                        Err(err) => {
-                                            error!(
-                                                job_id = %current_job_id_cid_for_reporting.to_string(),
-                                                "HTTP client error reporting job failure to mesh-jobs: {:?}",
-                                                err
-                                            );
-                                        }
-                                    }
-                                } else {
-                                    warn!(
-                                        job_id = %current_job_id_cid_for_reporting.to_string(),
-                                        "mesh_jobs_api_url not configured. Skipping job failure reporting."
-                                    );
-                                }
-
-                                let _failed_status_update = P2PJobStatus::Failed {
-                                    node_id: parsed_node_did,
-                                    reason: failure_reason,
-                                };
-                            }
-                            Err(did_parse_err) => {
-                                error!(
-                                    job_id = %current_job_id_cid_for_reporting,
-                                    original_job_error = %e,
-                                    node_did_parse_error = ?did_parse_err,
-                                    invalid_configured_node_did = %executor_node_did_str,
-                                    "Original job failed. Additionally, runtime node_did is invalid. Cannot report P2PJobStatus::Failed."
-                                );
+                info!(job_id = %receipt.job_id, "Execution succeeded. Anchoring receipt...");
+                let _anchoring = anchor_lock.lock().await;
+                self.anchor_mesh_receipt(&receipt).await?;
+                // Children spawned by the job run once they can point at its receipt
+                match receipt.cid() {
+                    Ok(receipt_cid) => self
+                        .context
+                        .record_job_receipt(&current_job_id_cid_for_reporting, &receipt_cid.to_string()),
+                    Err(e) => warn!(job_id = %current_job_id_cid_for_reporting, "Could not compute receipt CID: {}", e),
+                }
+            }
+            Err(e) => {
+                warn!(job_id = %current_job_id_cid_for_reporting, "Job processing failed: {:?}", e);
+                
+                let failure_reason = if let Some(icn_err) = e.downcast_ref::<IcnError>() {
+                    match icn_err {
+                        IcnError::Io(_) => JobFailureReason::NetworkError,
+                        IcnError::Serialization(_) => JobFailureReason::OutputError,
+                        IcnError::InvalidUri(_) => JobFailureReason::InvalidInput,
+                        IcnError::NotFound(_) => JobFailureReason::NotFound,
+                        IcnError::PermissionDenied(s) => {
+                            JobFailureReason::ExecutionError(format!("Permission denied: {}", s))
+                        }
+                        IcnError::Identity(_) => JobFailureReason::PermissionDenied,
+                        IcnError::Economics(econ_err) => match econ_err {
+                            EconomicsError::QuotaExceeded { .. }
+                            | EconomicsError::PeriodicQuotaExceeded { .. }
+                            | EconomicsError::RateLimitExceeded { .. } => {
+                                JobFailureReason::ResourceLimitExceeded
                             }
+                            EconomicsError::AccessDenied { .. } => JobFailureReason::PermissionDenied,
+                            _ => JobFailureReason::ExecutionError(format!("Economics error: {}", econ_err)),
+                        },
+                        IcnError::Crypto(err) => JobFailureReason::ExecutionError(format!("Crypto error: {}", err)),
+                        IcnError::Dag(err) => JobFailureReason::ExecutionError(format!("DAG error: {}", err)),
+                        IcnError::Multicodec(err) => JobFailureReason::ExecutionError(format!("Multicodec error: {}", err)),
+                        IcnError::Trust(err) => JobFailureReason::ExecutionError(format!("Trust error: {}", err)),
+                        IcnError::Mesh(err) => JobFailureReason::ExecutionError(format!("Mesh error: {}", err)),
+                        IcnError::Timeout(_) => timeout_reason(),
+                        IcnError::Config(s) => JobFailureReason::ExecutionError(format!("Config error: {}", s)),
+                        IcnError::Storage(s) => JobFailureReason::ExecutionError(format!("Storage error: {}", s)),
+                        IcnError::Database(s) => JobFailureReason::ExecutionError(format!("Database error: {}", s)),
+                        IcnError::Plugin(s) => JobFailureReason::ExecutionError(format!("Plugin error: {}", s)),
+                        IcnError::Consensus(s) => JobFailureReason::ExecutionError(format!("Consensus error: {}", s)),
+                        IcnError::InvalidOperation(s) => JobFailureReason::ExecutionError(format!("Invalid operation: {}", s)),
+                        IcnError::General(s) => JobFailureReason::Unknown(s.clone()),
+                    }
+                } else if let Some(reason) = e.downcast_ref::<JobFailureReason>() {
+                    reason.clone()
+                } else if matches!(e.downcast_ref::<CoVmError>(), Some(CoVmError::FuelExhausted))
+                    || e.downcast_ref::<tokio::time::error::Elapsed>().is_some()
+                {
+                    // Fuel exhaustion and wall-clock expiry are both time-outs
+                    timeout_reason()
+                } else {
+                    JobFailureReason::ExecutionError(e.to_string())
+                };
+
+                // Retries count against the lineage's mana budget; once it is spent the
+                // job fails for good with ResourceLimitExceeded
+                let failure_reason = if failure_reason.is_retryable() {
+                    match self.context.retry_job(job.clone()) {
+                        Ok(()) => {
+                            info!(job_id = %current_job_id_cid_for_reporting, reason = %failure_reason, "Job failure is retryable; queued another attempt");
+                            failure_reason
+                        }
+                        Err(budget_reason) => {
+                            warn!(job_id = %current_job_id_cid_for_reporting, reason = %failure_reason, "Job failure is retryable but its lineage mana budget is exhausted");
+                            budget_reason
                         }
                     }
+                } else {
+                    failure_reason
+                };
+                
+                let executor_node_did_str = self.config().node_did.clone();
+                match Did::from_str(&executor_node_did_str) {
+                    Ok(parsed_node_did) => {
+                        self.report_job_failure(
+                            http_client,
+                            &current_job_id_cid_for_reporting,
+                            parsed_node_did,
+                            failure_reason,
+                        )
+                        .await;
+                    }
+                    Err(did_parse_err) => {
+                        error!(
+                            job_id = %current_job_id_cid_for_reporting,
+                            original_job_error = %e,
+                            node_did_parse_error = ?did_parse_err,
+                            invalid_configured_node_did = %executor_node_did_str,
+                            "Original job failed. Additionally, runtime node_did is invalid. Cannot report P2PJobStatus::Failed."
+                        );
+                    }
                 }
-            } else {
-                tracing::debug!("No jobs available. Sleeping...");
-                sleep(Duration::from_secs(5)).await;
             }
         }
+        self.job_registry.finish(&current_job_id_cid_for_reporting);
+        self.context.finish_job(&current_job_id_cid_for_reporting);
+        Ok(())
     }
 
-    async fn poll_for_job(&self) -> Option<icn_types::mesh::MeshJob> {
-        // Implementation for polling jobs from mesh service
-        // This would use self.context.mesh_job_service_url() and an HTTP client
-        // For now, returning None
-        if let Some(url) = self.context.mesh_job_service_url() {
-            debug!("Polling for jobs at: {}", url);
-            // Replace with actual HTTP client logic, e.g., reqwest
-            // This is a placeholder. A real implementation would make an HTTP GET request.
-            // For example:
-            // match reqwest::get(format!(\"{}/next-job\", url)).await {
-            //     Ok(response) => match response.json::<icn_types::mesh::MeshJob>().await {
-            //         Ok(job) => Some(job),
-            //         Err(e) => { error!(\"Failed to parse job: {}\", e); None }
-            //     },
-            //     Err(e) => { error!(\"Failed to poll for job: {}\", e); None }
-            // }
-            None // Placeholder
+    /// Report a failed job to mesh-jobs and the status reporter, if configured,
+    /// and mark it failed in the registry
+    async fn report_job_failure(
+        &self,
+        http_client: &reqwest::Client,
+        job_id: &str,
+        node_did: Did,
+        failure_reason: JobFailureReason,
+    ) {
+        if let Some(base_url) = &self.config().mesh_jobs_api_url {
+            let report_payload = RuntimeJobFailureReport {
+                reporting_node_did: node_did.clone(),
+                reason: failure_reason.clone(),
+            };
+            let report_url = format!("{}/jobs/{}/runtime-failure", base_url, job_id);
+            info!("Reporting job failure for {} to: {}", job_id, report_url);
+            match http_client.post(&report_url).json(&report_payload).send().await {
+                Ok(response) if response.status().is_success() => {
+                    info!(
+                        "Successfully reported job failure for {} to mesh-jobs. Status: {}",
+                        job_id, response.status()
+                    );
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    error!(
+                        job_id = %job_id,
+                        status = %status,
+                        "Failed to report job failure to mesh-jobs. HTTP status: {}. Response: {:?}",
+                        status,
+                        response.text().await.unwrap_or_else(|_| "Failed to read response body".to_string())
+                    );
+                }
+                Err(err) => {
+                    error!(
+                        job_id = %job_id,
+                        "HTTP client error reporting job failure to mesh-jobs: {:?}",
+                        err
+                    );
+                }
+            }
         } else {
-            None
+            warn!(
+                job_id = %job_id,
+                "mesh_jobs_api_url not configured. Skipping job failure reporting."
+            );
+        }
+
+        let status = P2PJobStatus::Failed {
+            node_id: node_did,
+            reason: failure_reason,
+        };
+        if let Some(reporter) = &self.status_reporter {
+            if let Err(e) = reporter.report_status(job_id, status.clone()).await {
+                error!(job_id = %job_id, "Failed to report job status: {:#}", e);
+            }
         }
+        self.job_registry.update_status(job_id, status);
+    }
+
+    /// Ask the mesh job service for the next job with `GET {url}/next-job`.
+    ///
+    /// Returns the job on 200 and `None` on 204. Connection errors, other
+    /// statuses and undecodable bodies are logged and also return `None`, and
+    /// count towards the backoff returned by [`job_poll_delay`](Self::job_poll_delay).
+    pub async fn poll_for_job(&self) -> Option<icn_types::mesh::MeshJob> {
+        let url = self.context.mesh_job_service_url()?;
+        match self.fetch_next_job(url).await {
+            Ok(job) => {
+                self.poll_failures.store(0, Ordering::Relaxed);
+                job
+            }
+            Err(e) => {
+                let failures = self.poll_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                error!(failures, "Failed to poll for job at {}: {:#}", url, e);
+                None
+            }
+        }
+    }
+
+    async fn fetch_next_job(&self, base_url: &str) -> Result<Option<icn_types::mesh::MeshJob>> {
+        let url = format!("{}/next-job", base_url.trim_end_matches('/'));
+        debug!("Polling for jobs at: {}", url);
+        let response = self
+            .http_client
+            .get(&url)
+            .timeout(self.config().job_poll_timeout())
+            .send()
+            .await
+            .context("request failed")?;
+        match response.status() {
+            reqwest::StatusCode::OK => {
+                let job = response
+                    .json::<icn_types::mesh::MeshJob>()
+                    .await
+                    .context("invalid job in response")?;
+                Ok(Some(job))
+            }
+            reqwest::StatusCode::NO_CONTENT => Ok(None),
+            status => Err(anyhow!("unexpected status {}", status)),
+        }
+    }
+
+    /// Delay before the next poll when none returned a job: the configured
+    /// poll interval, backed off while polls keep failing
+    pub fn job_poll_delay(&self) -> Duration {
+        self.config()
+            .job_poll_backoff(self.poll_failures.load(Ordering::Relaxed))
     }
 
+    /// Verify and execute a polled job. Its receipt is anchored by the caller,
+    /// under the anchor lock.
     async fn process_polled_job(
         &self,
         job: icn_types::mesh::MeshJob,
+        queued_locally: bool,
     ) -> Result<MeshExecutionReceipt> {
         info!("Processing polled job ID: {:?}", job.job_id);
 
-        let cid_string = &job.params.wasm_cid;
-        let _wasm_bytes = self.storage.load_wasm(cid_string.as_str()).await.map_err(|e| {
-            anyhow!(
-                "Failed to load WASM for job {} (CID: {}): {}",
-                job.job_id.as_str(),
-                cid_string,
-                e
-            )
-        })?;
-
         let local_keypair = self
             .context
             .identity()
             .ok_or_else(|| anyhow!("Runtime identity not set for job processing"))?;
 
-        let originator_did_str = job.originator_did.as_str();
-        let _originator_did = Did::from_str(originator_did_str)?;
-
-        let receipt = execute_mesh_job(job, local_keypair, self.context.clone()).await?;
-
-        if receipt.status == IcnJobStatus::Completed {
-            self.anchor_mesh_receipt(&receipt).await?;
+        // Only the originator may ask for work to be done on its behalf. Jobs this
+        // runtime queued itself are children of jobs it ran, which are unsigned, or
+        // retries of jobs whose signature was checked on their first attempt.
+        if !queued_locally {
+            verify_job_signature(&job)?;
         }
-        Ok(receipt)
+
+        execute_mesh_job(job, local_keypair, self.context.clone(), self.storage.as_ref()).await
     }
 
     pub async fn anchor_mesh_receipt(&self, receipt: &MeshExecutionReceipt) -> Result<()> {
         // Placeholder for anchoring logic (e.g., to DAG, blockchain)
         info!("Anchoring mesh receipt for job ID: {}", receipt.job_id);
+        receipt_validation::check_usage_plausibility(receipt, &self.usage_plausibility)
+            .context("Mesh receipt reports implausible resource usage")?;
         // Example: Storing receipt CID or hash somewhere
         // self.storage.anchor_to_dag(&receipt.job_id).await?; // Assuming job_id is CID-like or used as key
 
@@ -1281,6 +2275,31 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
         Ok(())
     }
 
+    /// Current mana balance of `did`, or of its pool for `resource`.
+    ///
+    /// Reads the same ledger that job mana is reserved against and projects
+    /// what the configured regenerator's policy has accrued since the last
+    /// tick, without storing it. A DID or pool with no ledger record has a
+    /// balance of zero.
+    pub async fn mana_balance(&self, did: &Did, resource: Option<ResourceType>) -> Result<u64> {
+        let ledger = self.context.mana_repository.ledger();
+        let state = match resource {
+            Some(resource) => ledger.get_pool_state(did, resource).await?,
+            None => ledger.get_mana_state(did).await?,
+        };
+        let Some(state) = state else {
+            return Ok(0);
+        };
+        let Some(regenerator) = &self.context.mana_regenerator else {
+            return Ok(state.current_mana);
+        };
+        let policy = match resource {
+            Some(resource) => regenerator.pool_policy(resource),
+            None => regenerator.policy(),
+        };
+        Ok(policy.projected_balance(&state, regenerator.clock.epoch()))
+    }
+
     pub async fn tick_mana(&self) -> Result<()> {
         if let Some(regenerator) = &self.context.mana_regenerator {
             debug!("Ticking mana regeneration...");
@@ -1313,6 +2332,15 @@ impl<L: ManaLedger + Send + Sync + 'static> Runtime<L> {
     }
 }
 
+/// Engine for the runtime's linker; the full host ABI's host functions are async
+/// and its executions are metered with fuel.
+fn runtime_engine() -> Engine {
+    let mut config = wasmtime::Config::new();
+    config.async_support(cfg!(feature = "full_host_abi"));
+    config.consume_fuel(cfg!(feature = "full_host_abi"));
+    Engine::new(&config).expect("wasmtime config with only async support and fuel is valid")
+}
+
 /// Module providing executable trait for CCL DSL files
 pub mod dsl {
     use super::*;
@@ -1324,6 +2352,18 @@ pub mod dsl {
     }
 }
 
+/// First error among the jobs in `in_flight` that have already finished
+fn take_finished_job_error(in_flight: &mut JoinSet<Result<()>>) -> Option<anyhow::Error> {
+    while let Some(joined) = in_flight.try_join_next() {
+        match joined {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Some(e),
+            Err(e) => return Some(anyhow!("Job task failed: {}", e)),
+        }
+    }
+    None
+}
+
 pub fn load_or_generate_keypair(key_path: Option<&Path>) -> Result<IcnKeyPair> {
     match key_path {
         Some(path) => {
@@ -1403,73 +2443,218 @@ fn sign_runtime_receipt_in_place(
     // fn sign(&self, message: &[u8]) -> ed25519_dalek::Signature;
     let signature = keypair.sign(&bytes); // Use the assumed sign method
 
-    receipt.signature = Some(signature.to_bytes().to_vec());
+    receipt.signature = Some(signature.into());
     Ok(())
 }
 
+/// Run `wasm_bytes` on a [`CoVm`] built from `context.resource_limits`, or the
+/// default limits when the context sets none.
+///
+/// Running out of fuel is reported as a `RuntimeError::Execution` whose
+/// message starts with "fuel exhausted", so callers can tell it apart from
+/// traps in the module itself.
+pub fn execute_on_core_vm(
+    wasm_bytes: &[u8],
+    context: &VmContext,
+) -> Result<ExecutionResult, RuntimeError> {
+    let limits = context.resource_limits.clone().unwrap_or_default();
+    let max_fuel = limits.max_fuel;
+    let host_context = icn_core_vm::HostContext::default().with_organization(
+        context.coop_id.clone().map(CooperativeId::new),
+        context.community_id.clone().map(CommunityId::new),
+    );
+
+    let host_context = CoVm::new(limits)
+        .execute(wasm_bytes, host_context)
+        .map_err(|e| match e.downcast_ref::<CoVmError>() {
+            Some(CoVmError::FuelExhausted) => RuntimeError::Execution(format!(
+                "fuel exhausted: execution exceeded its limit of {} fuel",
+                max_fuel
+            )),
+            _ => RuntimeError::Execution(e.to_string()),
+        })?;
+
+    let metrics = host_context.metrics.lock().unwrap().clone();
+    let anchored_cids = host_context.anchored_cids.lock().unwrap().clone();
+    let resource_usage = host_context.resource_usage.lock().unwrap().clone();
+    let logs = host_context.logs.lock().unwrap().clone();
+    Ok(ExecutionResult {
+        metrics,
+        anchored_cids,
+        resource_usage,
+        logs,
+    })
+}
+
+/// Check that `job` carries its originator's signature over
+/// [`MeshJob::signing_payload`].
+///
+/// Unsigned jobs, jobs whose parameters changed after signing and jobs signed
+/// by anyone else are all rejected with `JobFailureReason::PermissionDenied`.
+pub fn verify_job_signature(job: &MeshJob) -> Result<(), JobFailureReason> {
+    job.verify_signature().map_err(|e| match e {
+        JobSignatureError::Encoding(e) => JobFailureReason::ExecutionError(format!(
+            "Failed to encode job {} for signature check: {}",
+            job.job_id, e
+        )),
+        e => {
+            warn!(job_id = %job.job_id, originator = %job.originator_did, "Job signature rejected: {}", e);
+            JobFailureReason::PermissionDenied
+        }
+    })
+}
+
+/// The CoVm a mesh job runs on: [`CoVm::new_deterministic`] when the job asks
+/// for reproducible execution, the faster default engine otherwise.
+pub fn covm_for_job(params: &MeshJobParams, limits: ResourceLimits) -> CoVm {
+    if params.deterministic {
+        CoVm::new_deterministic(limits)
+    } else {
+        CoVm::new(limits)
+    }
+}
+
+/// Store the lines a job logged in `dag_store`, returning the CID of the node
+/// holding them as a JSON array.
+async fn anchor_job_logs(
+    dag_store: &dyn DagStore,
+    job_id: &str,
+    logs: &[String],
+    scope_id: &str,
+) -> Result<String> {
+    let node = DagNode {
+        content: serde_json::to_string(logs)?,
+        parent: None,
+        event_type: DagEventType::Execution,
+        timestamp: Utc::now().timestamp_millis() as u64,
+        scope_id: scope_id.to_string(),
+    };
+    let cid = node
+        .cid()
+        .with_context(|| format!("Failed to compute CID of logs for job {}", job_id))?
+        .to_string();
+    dag_store
+        .insert(node)
+        .await
+        .with_context(|| format!("Failed to anchor logs for job {}", job_id))?;
+    Ok(cid)
+}
+
 /// Executes a MeshJob within the ICN runtime.
+///
+/// The job's module is loaded from `storage` by its WASM CID and run on the
+/// CoVm on a blocking thread. A module that traps or runs out of fuel produces
+/// a receipt with `JobStatus::Failed` carrying what it logged and used before
+/// it stopped. Anything the module logged is anchored
+/// in the context's DAG store and referenced by the receipt's `logs_cid`.
 pub async fn execute_mesh_job<L: ManaLedger + Send + Sync + 'static>(
     mesh_job: MeshJob,
     local_keypair: &IcnKeyPair,
-    _runtime_context: Arc<RuntimeContext<L>>, // Prefix unused variable
+    runtime_context: Arc<RuntimeContext<L>>,
+    storage: &dyn RuntimeStorage,
 ) -> Result<MeshExecutionReceipt, anyhow::Error> {
     info!(
         "Executing mesh job: {:?} with executor {}",
         mesh_job.job_id, local_keypair.did
     );
-    // ... (rest of the logic from the original execute_mesh_job)
-    // ... using runtime_context.storage(), runtime_context.mana_regenerator if needed for cost calculation, etc.
+    let wasm_bytes = storage
+        .load_wasm(&mesh_job.params.wasm_cid)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to load WASM for job {} (CID: {})",
+                mesh_job.job_id, mesh_job.params.wasm_cid
+            )
+        })?;
 
     // Determine mana_cost (priority: explicit, then resource sum, then default)
-    let calculated_mana_cost = mesh_job.params.explicit_mana_cost.unwrap_or_else(|| {
-        if !mesh_job.params.resources_required.is_empty() {
-            mesh_job.params.resources_required.iter().map(|(_, amount)| *amount).sum()
-        } else {
-            DEFAULT_MANA_COST
+    let final_mana_cost = estimated_mana_cost(&mesh_job.params);
+    // The receipt records the engine the job is routed to, so verifiers know
+    // whether to expect a bit-identical replay
+    let vm = covm_for_job(&mesh_job.params, ResourceLimits::default());
+    let deterministic = vm.is_deterministic();
+
+    // A job spawned by another one may only submit as deep as its lineage allows
+    let host_context = icn_core_vm::HostContext::default()
+        .with_submission_depth(runtime_context.submission_depth(&mesh_job.job_id));
+
+    let execution_start_time = Utc::now().timestamp() as u64;
+    let started = std::time::Instant::now();
+    // Execution is synchronous, so it must not hold up the async workers
+    let execution = tokio::task::spawn_blocking(move || {
+        vm.execute_with_partial_results(&wasm_bytes, host_context)
+    })
+    .await
+    .with_context(|| format!("Execution of job {} did not finish", mesh_job.job_id))?;
+    let (status, host_context) = match execution {
+        Ok(host_context) => (IcnJobStatus::Completed, host_context),
+        // What the module logged and used before it stopped still goes on the receipt
+        Err(PartialExecutionError::FuelExhausted { context }) => {
+            warn!(job_id = %mesh_job.job_id, "Job module ran out of fuel");
+            (IcnJobStatus::Failed, context)
+        }
+        Err(PartialExecutionError::Trapped { error, context }) => {
+            warn!(job_id = %mesh_job.job_id, "Job module trapped: {:#}", error);
+            (IcnJobStatus::Failed, context)
+        }
+        Err(PartialExecutionError::Failed(e)) => {
+            warn!(job_id = %mesh_job.job_id, "Job module failed: {:#}", e);
+            (IcnJobStatus::Failed, icn_core_vm::HostContext::default())
         }
-    });
-    let final_mana_cost = if calculated_mana_cost == 0 && !mesh_job.params.resources_required.is_empty() {
-        DEFAULT_MANA_COST
-    } else {
-        calculated_mana_cost
     };
-
-    // Simulate execution
-    let execution_start_time = Utc::now().timestamp_millis() as u64;
-    // Simulate some work
-    tokio::time::sleep(std::time::Duration::from_millis(
-        100 + final_mana_cost as u64,
-    ))
-    .await; // Sleep proportional to cost
+    let elapsed = started.elapsed();
     let execution_end_time_dt = Utc::now();
-    let execution_end_time = execution_end_time_dt.timestamp_millis() as u64;
+    let execution_end_time = execution_end_time_dt.timestamp() as u64;
 
-    // Dummy result CID and resource usage
-    let result_cid = Some(format!(
-        "bafyresimulatedresult{}",
-        mesh_job.job_id.as_str()
-    ));
-    let resource_usage = mesh_job.params.resources_required.iter().map(|(rt, amount)| (rt.clone(), *amount)).collect();
+    // The last CID a completed module anchored is its result; usage is what
+    // was measured, not what the job asked for
+    let result_cid = if status == IcnJobStatus::Completed {
+        host_context.anchored_cids.lock().unwrap().last().cloned()
+    } else {
+        None
+    };
+    let resource_usage = receipt_validation::measured_resource_usage(
+        &host_context.metrics.lock().unwrap(),
+        &host_context.resource_usage.lock().unwrap(),
+        elapsed,
+    )
+    .with_context(|| format!("Job {} recorded usage of an unknown resource", mesh_job.job_id))?;
+    let logs = host_context.logs.lock().unwrap().clone();
+    let logs_cid = if logs.is_empty() {
+        None
+    } else {
+        let dag_store = runtime_context.dag_store();
+        Some(
+            anchor_job_logs(
+                dag_store.as_ref(),
+                &mesh_job.job_id,
+                &logs,
+                local_keypair.did.as_str(),
+            )
+            .await?,
+        )
+    };
 
     let mut receipt = MeshExecutionReceipt {
         job_id: mesh_job.job_id.clone(),
         executor: mesh_job.originator_did.clone(),
-        status: IcnJobStatus::Completed,
+        status,
         result_data_cid: result_cid,
-        logs_cid: None,
+        logs_cid,
         resource_usage,
         execution_start_time,
         execution_end_time,
         execution_end_time_dt,
-        signature: Vec::new(),
+        signature: TaggedSignature::default(),
         coop_id: None,
         community_id: None,
         mana_cost: Some(final_mana_cost),
+        deterministic,
     };
 
     // Sign the receipt
     let receipt_bytes_for_signing = serde_cbor::to_vec(&receipt).unwrap_or_default();
-    receipt.signature = local_keypair.sign(&receipt_bytes_for_signing).to_vec();
+    receipt.signature = local_keypair.sign(&receipt_bytes_for_signing).into();
 
     info!(
         "Finished executing mesh job: {:?}, Mana cost: {}",
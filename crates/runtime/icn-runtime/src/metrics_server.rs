@@ -1,12 +1,17 @@
 // src/metrics_server.rs
 
 use axum::{
+    extract::State,
     routing::get,
     response::Html, // Use Html for plain text response
+    Json,
     Router,
 };
 use prometheus::{Encoder, TextEncoder, gather};
 use std::net::SocketAddr;
+use tracing::info;
+
+use crate::job_registry::{ActiveJob, JobRegistry};
 
 /// Handler for the /metrics endpoint
 async fn metrics_handler() -> Html<String> {
@@ -30,10 +35,24 @@ async fn metrics_handler() -> Html<String> {
     }
 }
 
+/// Handler for the /jobs endpoint: the runtime's in-flight jobs
+async fn active_jobs_handler(State(registry): State<JobRegistry>) -> Json<Vec<ActiveJob>> {
+    Json(registry.active_jobs())
+}
+
+/// Router serving `/metrics` and `/jobs`
+pub fn router(registry: JobRegistry) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_handler))
+        .route("/jobs", get(active_jobs_handler))
+        .with_state(registry)
+}
+
 /// Starts the Prometheus metrics server on the given address.
+/// Also serves the in-flight jobs tracked by `registry` at `/jobs`.
 /// This function runs indefinitely.
-pub async fn run_metrics_server(addr: SocketAddr) {
-    let app = Router::new().route("/metrics", get(metrics_handler));
+pub async fn run_metrics_server(addr: SocketAddr, registry: JobRegistry) {
+    let app = router(registry);
 
     info!("Metrics server listening on {}", addr);
 
@@ -0,0 +1,193 @@
+//! Assembling and serving a runtime node from a [`RuntimeConfig`].
+//!
+//! Shared by the `icn-runtime` binary and `icn-cli runtime serve`, so both
+//! start a node the same way: storage and mana ledger from the config, the
+//! mana regeneration worker, the optional metrics server, and the job loop
+//! until a shutdown signal arrives.
+
+use anyhow::{anyhow, Context, Result};
+use icn_economics::mana::{InMemoryManaLedger, ManaLedger, ManaRegenerator, RegenerationPolicy};
+use icn_economics::{Economics, ResourceAuthorizationPolicy, SledManaLedger, SledSupplyStore};
+use icn_identity::KeyPair;
+use std::future::Future;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::config::{ManaLedgerBackend, RuntimeConfig};
+use crate::context::RuntimeContextBuilder;
+use crate::job_status::HttpJobStatusReporter;
+use crate::metrics_server;
+use crate::reputation_integration::{HttpReputationUpdater, ReputationScoringConfig};
+use crate::sled_storage::SledStorage;
+use crate::Runtime;
+
+/// Directory under `storage_path` holding the sled mana ledger
+pub const MANA_LEDGER_DIR: &str = "mana_ledger";
+
+/// Directory under `storage_path` holding the minted token supply
+pub const TOKEN_SUPPLY_DIR: &str = "token_supply";
+
+/// Run a node with `config` and `keypair` until `shutdown` completes and the
+/// jobs in flight have finished, or until a job fails the run loop.
+///
+/// An empty `node_did` in the config is filled from `keypair`; a different
+/// DID is rejected.
+pub async fn serve(
+    mut config: RuntimeConfig,
+    keypair: KeyPair,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let key_did = keypair.did.to_string();
+    if config.node_did.is_empty() {
+        config.node_did = key_did;
+    } else if config.node_did != key_did {
+        return Err(anyhow!(
+            "Configured node_did {} does not match the keypair DID {}",
+            config.node_did,
+            key_did
+        ));
+    }
+
+    match config.mana_ledger {
+        ManaLedgerBackend::Memory => {
+            serve_with_ledger(
+                config,
+                keypair,
+                Arc::new(InMemoryManaLedger::default()),
+                shutdown,
+            )
+            .await
+        }
+        ManaLedgerBackend::Sled => {
+            let path = config.storage_path.join(MANA_LEDGER_DIR);
+            let ledger = SledManaLedger::open(&path)
+                .with_context(|| format!("Failed to open mana ledger at {:?}", path))?;
+            serve_with_ledger(config, keypair, Arc::new(ledger), shutdown).await
+        }
+    }
+}
+
+async fn serve_with_ledger<L: ManaLedger + Send + Sync + 'static>(
+    config: RuntimeConfig,
+    keypair: KeyPair,
+    ledger: Arc<L>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    info!("Storage Path: {:?}", config.storage_path);
+    let storage = Arc::new(
+        SledStorage::open(&config.storage_path).context("Failed to initialize SledStorage")?,
+    );
+
+    let regeneration_policy = config
+        .mana_regeneration_policy
+        .clone()
+        .unwrap_or(RegenerationPolicy::FixedRatePerTick(10));
+    let mana_regenerator = Arc::new(ManaRegenerator::new(ledger.clone(), regeneration_policy));
+
+    let scoring_config = match &config.reputation_scoring_config_path {
+        Some(path) => ReputationScoringConfig::from_file(path)?,
+        None => ReputationScoringConfig::default(),
+    };
+
+    // The supply cap is checked against the supply minted over the node's whole life
+    let supply_path = config.storage_path.join(TOKEN_SUPPLY_DIR);
+    let supply_store = SledSupplyStore::open(&supply_path)
+        .with_context(|| format!("Failed to open token supply at {:?}", supply_path))?;
+    let economics = Economics::new(ResourceAuthorizationPolicy::default())
+        .with_supply_store(Arc::new(supply_store));
+
+    let mut builder = RuntimeContextBuilder::<L>::new()
+        .with_identity(keypair.clone())
+        .with_economics(Arc::new(economics))
+        .with_executor_id(config.node_did.clone())
+        .with_mana_regenerator(mana_regenerator)
+        .with_reputation_scoring_config(scoring_config.clone());
+    if let Some(url) = &config.mesh_job_service_url {
+        builder = builder.with_mesh_job_service_url(url.clone());
+    }
+    let context = Arc::new(builder.build_with_ledger(ledger));
+
+    let mut runtime = Runtime::with_context(storage, context).with_config(config.clone());
+    if let Some(url) = &config.mesh_job_service_url {
+        runtime = runtime.with_status_reporter(Arc::new(HttpJobStatusReporter::new(url.clone())));
+    }
+
+    // Batched reputation records are flushed on a timer and once more on shutdown
+    let mut batched_reputation = None;
+    if let Some(reputation_url) = config.reputation_service_url.as_deref() {
+        if !reputation_url.is_empty() {
+            info!("Using HTTP reputation updater: {}", reputation_url);
+            let batch_size = config.reputation_batch_size.unwrap_or(1);
+            let updater = Arc::new(
+                HttpReputationUpdater::new_with_config(
+                    reputation_url.to_string(),
+                    keypair.did.clone(),
+                    scoring_config,
+                )
+                .with_batch_size(batch_size),
+            );
+            if batch_size > 1 {
+                let flush_task = updater
+                    .clone()
+                    .spawn_flush_task(config.reputation_flush_interval());
+                batched_reputation = Some((updater.clone(), flush_task));
+            }
+            runtime = runtime.with_reputation_updater(updater);
+        }
+    }
+
+    if let Some(port) = config.metrics_port {
+        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
+        tokio::spawn(metrics_server::run_metrics_server(
+            addr,
+            runtime.job_registry(),
+        ));
+    }
+
+    let regeneration = runtime.spawn_mana_regeneration();
+    info!("Runtime initialized successfully.");
+
+    let res = runtime.run_until(shutdown).await;
+    if let Err(e) = &res {
+        error!("Runtime exited with error: {:?}", e);
+    }
+    info!("Shutting down ICN Runtime Node...");
+    if let Some(task) = regeneration {
+        task.abort();
+    }
+    if let Some((updater, flush_task)) = batched_reputation {
+        flush_task.abort();
+        updater.flush_and_log().await;
+    }
+    res
+}
+
+/// Resolve on SIGINT (Ctrl+C) or, on Unix, SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received shutdown signal (Ctrl+C)."),
+        _ = terminate => info!("Received shutdown signal (SIGTERM)."),
+    }
+}
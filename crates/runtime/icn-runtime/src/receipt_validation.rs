@@ -0,0 +1,178 @@
+//! Retroactive checks of the metrics an executor reports in a receipt.
+//!
+//! A receipt is signed by its executor, so a valid signature says nothing about
+//! whether the metrics are plausible. These checks flag values that no
+//! execution under the job's `ResourceLimits` could have produced, which points
+//! to a tampered receipt or a broken meter. Mesh receipts carry no metrics, so
+//! their `resource_usage` is checked against the wall-clock window instead.
+
+use icn_core_vm::{ExecutionMetrics, ResourceLimits};
+use icn_economics::ResourceType;
+use icn_mesh_receipts::ExecutionReceipt as MeshExecutionReceipt;
+use icn_types::runtime_receipt::RuntimeExecutionReceipt;
+use icn_types::ParseResourceTypeError;
+use std::collections::HashMap;
+use std::time::Duration;
+use thiserror::Error;
+
+/// A receipt metric that is impossible under the limits the job ran with
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MetricsViolation {
+    #[error("receipt reports {reported} host calls but the limit is {limit}")]
+    HostCallsExceeded { reported: u64, limit: u64 },
+
+    #[error("receipt reports {reported} I/O bytes but the limit is {limit}")]
+    IoBytesExceeded { reported: u64, limit: u64 },
+
+    #[error("receipt anchors {reported} CIDs but the limit is {limit}")]
+    AnchoredCidsExceeded { reported: usize, limit: usize },
+
+    /// Bytes only move through host functions, so I/O needs at least one call
+    #[error("receipt reports {io_bytes} I/O bytes without any host calls")]
+    IoWithoutHostCalls { io_bytes: u64 },
+
+    /// Every anchored CID is one call to the `anchor` host function
+    #[error("receipt anchors {anchored} CIDs but reports only {host_calls} host calls")]
+    MoreAnchorsThanHostCalls { anchored: usize, host_calls: u64 },
+
+    #[error("receipt ends at {end} before it starts at {start}")]
+    EndsBeforeStart { start: u64, end: u64 },
+
+    /// More usage than the executor could have measured in the elapsed wall-clock time
+    #[error(
+        "receipt reports {reported} {resource} but at most {max_plausible} fits in {elapsed_ms} ms"
+    )]
+    ImplausibleUsage {
+        resource: ResourceType,
+        reported: u64,
+        max_plausible: u64,
+        elapsed_ms: u64,
+    },
+}
+
+/// How much a mesh executor can consume per millisecond of wall-clock time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsagePlausibility {
+    /// CPU milliseconds per wall-clock millisecond, i.e. the most cores a job can use
+    pub max_cpu_parallelism: u64,
+    /// I/O bytes per wall-clock millisecond
+    pub max_io_bytes_per_ms: u64,
+    /// Added to the elapsed time, since receipt timestamps are whole seconds
+    pub clock_slack_ms: u64,
+}
+
+impl Default for UsagePlausibility {
+    fn default() -> Self {
+        Self {
+            max_cpu_parallelism: 64,
+            max_io_bytes_per_ms: 1_000_000,
+            clock_slack_ms: 1_000,
+        }
+    }
+}
+
+/// Check `receipt`'s metrics against `limits`, returning the first impossible value.
+///
+/// Receipts do not record fuel, so fuel use cannot be checked here; the
+/// execution's own fuel limit already bounds it at run time.
+pub fn check_receipt_metrics(
+    receipt: &RuntimeExecutionReceipt,
+    limits: &ResourceLimits,
+) -> Result<(), MetricsViolation> {
+    let metrics = &receipt.metrics;
+    let anchored = receipt.anchored_cids_count();
+
+    if metrics.host_calls > u64::from(limits.max_host_calls) {
+        return Err(MetricsViolation::HostCallsExceeded {
+            reported: metrics.host_calls,
+            limit: u64::from(limits.max_host_calls),
+        });
+    }
+    if metrics.io_bytes > limits.max_io_bytes {
+        return Err(MetricsViolation::IoBytesExceeded {
+            reported: metrics.io_bytes,
+            limit: limits.max_io_bytes,
+        });
+    }
+    if anchored > limits.max_anchored_cids {
+        return Err(MetricsViolation::AnchoredCidsExceeded {
+            reported: anchored,
+            limit: limits.max_anchored_cids,
+        });
+    }
+    if metrics.io_bytes > 0 && metrics.host_calls == 0 {
+        return Err(MetricsViolation::IoWithoutHostCalls {
+            io_bytes: metrics.io_bytes,
+        });
+    }
+    if anchored as u64 > metrics.host_calls {
+        return Err(MetricsViolation::MoreAnchorsThanHostCalls {
+            anchored,
+            host_calls: metrics.host_calls,
+        });
+    }
+    Ok(())
+}
+
+/// Check that a mesh receipt's `resource_usage` fits in its execution window.
+///
+/// CPU and I/O grow with time, so both are bounded by the elapsed wall-clock
+/// time; memory and tokens are not rates and are left to the job's limits.
+pub fn check_usage_plausibility(
+    receipt: &MeshExecutionReceipt,
+    bounds: &UsagePlausibility,
+) -> Result<(), MetricsViolation> {
+    let (start, end) = (receipt.execution_start_time, receipt.execution_end_time);
+    if end < start {
+        return Err(MetricsViolation::EndsBeforeStart { start, end });
+    }
+    let elapsed_ms = (end - start)
+        .saturating_mul(1_000)
+        .saturating_add(bounds.clock_slack_ms);
+
+    for (resource, per_ms) in [
+        (ResourceType::Cpu, bounds.max_cpu_parallelism),
+        (ResourceType::Io, bounds.max_io_bytes_per_ms),
+    ] {
+        let reported = receipt.resource_usage.get(&resource).copied().unwrap_or(0);
+        let max_plausible = elapsed_ms.saturating_mul(per_ms);
+        if reported > max_plausible {
+            return Err(MetricsViolation::ImplausibleUsage {
+                resource,
+                reported,
+                max_plausible,
+                elapsed_ms,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The resource usage an executor measured for one execution, for a mesh receipt.
+///
+/// CPU is the measured wall-clock time in milliseconds and I/O the bytes that
+/// went through host functions; what the guest reports for either is ignored.
+/// Every other resource comes from the guest's `record_usage` calls, keyed by
+/// resource name. A name that is not a [`ResourceType`] fails the whole
+/// measurement rather than being dropped.
+pub fn measured_resource_usage(
+    metrics: &ExecutionMetrics,
+    recorded: &[(String, u64)],
+    elapsed: Duration,
+) -> Result<HashMap<ResourceType, u64>, ParseResourceTypeError> {
+    let mut usage = HashMap::new();
+    usage.insert(
+        ResourceType::Cpu,
+        u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+    );
+    usage.insert(ResourceType::Io, metrics.io_bytes);
+    for (name, amount) in recorded {
+        let resource = name.parse::<ResourceType>()?;
+        if matches!(resource, ResourceType::Cpu | ResourceType::Io) {
+            continue;
+        }
+        let total = usage.entry(resource).or_insert(0);
+        *total = total.saturating_add(*amount);
+    }
+    Ok(usage)
+}
@@ -3,12 +3,15 @@ use async_trait::async_trait;
 use chrono::Utc;
 use icn_identity::Did;
 pub use icn_types::reputation::ReputationRecord;
+use icn_types::reputation::BatchResult;
 use icn_types::runtime_receipt::RuntimeExecutionReceipt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
 use crate::metrics;
@@ -126,12 +129,25 @@ pub trait ReputationUpdater: Send + Sync {
     ) -> Result<()>;
 }
 
+/// Submissions of a batched record the reputation service may reject before
+/// the record is dropped
+pub const MAX_RECORD_SUBMISSIONS: u32 = 3;
+
+/// A queued record and how many times the service has rejected it
+struct PendingRecord {
+    record: ReputationRecord,
+    rejections: u32,
+}
+
 /// The real implementation that sends HTTP requests to the reputation service
 pub struct HttpReputationUpdater {
     client: Client,
     reputation_service_url: String,
     // local_did: Did, // COMMENTED OUT
     config: ReputationScoringConfig, // Add config field
+    /// Records per request; above 1, records are queued and sent in batches
+    batch_size: usize,
+    pending: Mutex<Vec<PendingRecord>>,
 }
 
 impl HttpReputationUpdater {
@@ -160,7 +176,146 @@ impl HttpReputationUpdater {
             reputation_service_url,
             // local_did, // Field assignment commented out
             config,
+            batch_size: 1,
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue receipt-based records and send them `batch_size` at a time instead
+    /// of one request per anchored receipt. Partial batches are sent by
+    /// [`spawn_flush_task`](Self::spawn_flush_task) and [`flush`](Self::flush),
+    /// e.g. on shutdown.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Number of records waiting to be sent
+    pub async fn pending_records(&self) -> usize {
+        self.pending.lock().await.len()
+    }
+
+    /// Send all queued records in a single request.
+    ///
+    /// Returns the records the service rejected, with its reason for each.
+    /// Rejected records are queued again for the next flush until they have
+    /// been submitted [`MAX_RECORD_SUBMISSIONS`] times, and then dropped. If the
+    /// request itself fails, nothing was stored and the whole batch is queued
+    /// again.
+    pub async fn flush(&self) -> Result<Vec<(ReputationRecord, String)>> {
+        let batch = std::mem::take(&mut *self.pending.lock().await);
+        if batch.is_empty() {
+            return Ok(Vec::new());
+        }
+        let total = batch.len();
+
+        let url = format!(
+            "{}/reputation/records/batch",
+            self.reputation_service_url.trim_end_matches('/')
+        );
+        debug!("Flushing {} reputation records to {}", total, url);
+
+        let records: Vec<&ReputationRecord> = batch.iter().map(|p| &p.record).collect();
+        let posted = self.post_batch(&url, &records).await;
+        let result = match posted {
+            Ok(result) => result,
+            Err(e) => {
+                self.requeue(batch).await;
+                return Err(e);
+            }
+        };
+
+        let mut rejections: Vec<Option<String>> = vec![None; total];
+        for outcome in result.outcomes {
+            if let Some(slot) = rejections.get_mut(outcome.index) {
+                *slot = outcome.error;
+            }
+        }
+        let mut rejected = Vec::new();
+        let mut retry = Vec::new();
+        for (mut pending, rejection) in batch.into_iter().zip(rejections) {
+            let Some(reason) = rejection else {
+                continue;
+            };
+            metrics::REPUTATION_SUBMISSION_HTTP_ERRORS
+                .with_label_values(&[pending.record.subject.as_str(), "batch_item_rejected"])
+                .inc();
+            rejected.push((pending.record.clone(), reason));
+            pending.rejections += 1;
+            if pending.rejections < MAX_RECORD_SUBMISSIONS {
+                retry.push(pending);
+            } else {
+                warn!(
+                    "Dropping reputation record for subject {} (anchor: {}) after {} rejections",
+                    pending.record.subject, pending.record.anchor, pending.rejections
+                );
+            }
+        }
+        info!(
+            "Flushed {} reputation records ({} rejected, {} queued for retry)",
+            total,
+            rejected.len(),
+            retry.len()
+        );
+        self.requeue(retry).await;
+        Ok(rejected)
+    }
+
+    /// Put records back at the front of the queue, ahead of newer ones
+    async fn requeue(&self, records: Vec<PendingRecord>) {
+        let mut pending = self.pending.lock().await;
+        let newer = std::mem::replace(&mut *pending, records);
+        pending.extend(newer);
+    }
+
+    /// Flush, logging rejected records and failures instead of returning them
+    pub async fn flush_and_log(&self) {
+        match self.flush().await {
+            Ok(rejected) => {
+                for (record, reason) in rejected {
+                    warn!(
+                        "Reputation service rejected record for subject {} (anchor: {}): {}",
+                        record.subject, record.anchor, reason
+                    );
+                }
+            }
+            Err(e) => warn!("Failed to flush reputation records: {}", e),
+        }
+    }
+
+    /// Flush queued records every `interval`, so a partial batch is not held
+    /// back until the batch fills
+    pub fn spawn_flush_task(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                self.flush_and_log().await;
+            }
+        })
+    }
+
+    async fn post_batch(&self, url: &str, records: &[&ReputationRecord]) -> Result<BatchResult> {
+        let response = self
+            .client
+            .post(url)
+            .json(records)
+            .send()
+            .await
+            .map_err(|err| anyhow!("HTTP client error during reputation batch submission: {}", err))?;
+        if !response.status().is_success() {
+            let status_code = response.status();
+            let body = response.text().await.unwrap_or_default();
+            error!(
+                "Failed to submit reputation batch: Status {}, Body: {}",
+                status_code,
+                body
+            );
+            anyhow::bail!("Failed to submit reputation batch: HTTP Status {}", status_code);
         }
+        response
+            .json::<BatchResult>()
+            .await
+            .map_err(|e| anyhow!("Failed to parse reputation batch result: {}", e))
     }
 
     // Placeholder method to fetch current reputation score
@@ -316,6 +471,28 @@ impl ReputationUpdater for HttpReputationUpdater {
         // Observe score delta metric with federation labels
         metrics::observe_reputation_score_delta(score_delta, coop_id, community_id, executor_did);
 
+        if self.batch_size > 1 {
+            let queued = {
+                let mut pending = self.pending.lock().await;
+                pending.push(PendingRecord {
+                    record,
+                    rejections: 0,
+                });
+                pending.len()
+            };
+            if queued >= self.batch_size {
+                for (rejected, reason) in self.flush().await? {
+                    warn!(
+                        "Reputation service rejected record for subject {} (anchor: {}): {}",
+                        rejected.subject,
+                        rejected.anchor,
+                        reason
+                    );
+                }
+            }
+            return Ok(());
+        }
+
         // Send the record via HTTP
         let response = self
             .client
@@ -682,11 +859,14 @@ mod tests {
                 mana_cost: receipt_mana_cost,
             },
             anchored_cids: vec![],
+            anchored_cids_root: None,
             resource_usage: vec![],
             timestamp: 1234567890,
             dag_epoch: Some(1),
             receipt_cid: Some("bafy...mockcid".to_string()),
             signature: None,
+            coop_id: None,
+            community_id: None,
         };
 
         // Calculate expected score delta BEFORE mock setup
@@ -763,11 +943,14 @@ mod tests {
                 mana_cost: receipt_mana_cost,
             },
             anchored_cids: vec![],
+            anchored_cids_root: None,
             resource_usage: vec![],
             timestamp: 1234567891,
             dag_epoch: Some(1),
             receipt_cid: Some("bafy...mockcidMOD".to_string()),
             signature: None,
+            coop_id: None,
+            community_id: None,
         };
 
         // Mock for GET /reputation/profiles/{did}
@@ -861,11 +1044,14 @@ mod tests {
                 mana_cost: receipt_mana_cost,
             },
             anchored_cids: vec![],
+            anchored_cids_root: None,
             resource_usage: vec![],
             timestamp: 1234567892,
             dag_epoch: Some(1),
             receipt_cid: Some("bafy...mockcidMODFAIL".to_string()),
             signature: None,
+            coop_id: None,
+            community_id: None,
         };
 
         // Mock for GET /reputation/profiles/{did} - simulate failure (503 Service Unavailable)
@@ -963,11 +1149,14 @@ mod tests {
                 mana_cost: receipt_mana_cost,
             },
             anchored_cids: vec![],
+            anchored_cids_root: None,
             resource_usage: vec![],
             timestamp: 1234567893,
             dag_epoch: Some(1),
             receipt_cid: Some("bafy...mockcidFAILPATH".to_string()),
             signature: None,
+            coop_id: None,
+            community_id: None,
         };
 
         // Calculate expected score delta BEFORE mock setup
@@ -1031,11 +1220,14 @@ mod tests {
                 mana_cost: Some(10u64),
             },
             anchored_cids: vec![],
+            anchored_cids_root: None,
             resource_usage: vec![],
             timestamp: 1234567894,
             dag_epoch: Some(1),
             receipt_cid: Some("bafy...mockcidHTTP500".to_string()),
             signature: None,
+            coop_id: None,
+            community_id: None,
         };
 
         // Mock for POST / - respond with 500 Internal Server Error
@@ -1115,11 +1307,14 @@ mod tests {
                 mana_cost: Some(10u64),
             },
             anchored_cids: vec![],
+            anchored_cids_root: None,
             resource_usage: vec![],
             timestamp: 1234567895,
             dag_epoch: Some(1),
             receipt_cid: Some("bafy...mockcidBADURL".to_string()),
             signature: None,
+            coop_id: None,
+            community_id: None,
         };
 
         // We need to capture the expected error string for the metric label
@@ -1191,11 +1386,14 @@ mod tests {
                 mana_cost: Some(1000),
             },
             anchored_cids: vec![],
+            anchored_cids_root: None,
             resource_usage: vec![],
             timestamp: 0,
             dag_epoch: None,
             receipt_cid: None,
             signature: None,
+            coop_id: None,
+            community_id: None,
         };
 
         // Call on the struct instance directly
@@ -1228,11 +1426,14 @@ mod tests {
                 mana_cost,
             },
             anchored_cids: vec![],
+            anchored_cids_root: None,
             resource_usage: vec![],
             timestamp: Utc::now().timestamp() as u64,
             dag_epoch: None,
             receipt_cid: Some("cid-dynamic-receipt".into()),
             signature: None,
+            coop_id: None,
+            community_id: None,
         }
     }
 
@@ -6,8 +6,133 @@ use sled::Db;
 use std::path::Path;
 use crate::{
     Proposal,
+    ProposalState,
+    QuorumStatus,
     RuntimeExecutionReceipt,
 };
+use icn_identity::TaggedSignature;
+use icn_types::runtime_receipt::RuntimeExecutionMetrics;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of receipt records written as CBOR. Receipts gained optional fields
+/// and an untagged signature encoding that bincode cannot represent, so records
+/// without the prefix are bincode in the layout of [`LegacyReceiptRecord`].
+const RECEIPT_RECORD_V1: &[u8] = b"icn-receipt/v1:";
+
+/// Receipt record layout written before [`RECEIPT_RECORD_V1`]
+#[derive(Deserialize)]
+struct LegacyReceiptRecord {
+    id: String,
+    issuer: String,
+    proposal_id: String,
+    wasm_cid: String,
+    ccl_cid: String,
+    metrics: RuntimeExecutionMetrics,
+    anchored_cids: Vec<String>,
+    resource_usage: Vec<(String, u64)>,
+    timestamp: u64,
+    dag_epoch: Option<u64>,
+    receipt_cid: Option<String>,
+    signature: Option<Vec<u8>>,
+}
+
+impl From<LegacyReceiptRecord> for RuntimeExecutionReceipt {
+    fn from(record: LegacyReceiptRecord) -> Self {
+        RuntimeExecutionReceipt {
+            id: record.id,
+            issuer: record.issuer,
+            proposal_id: record.proposal_id,
+            wasm_cid: record.wasm_cid,
+            ccl_cid: record.ccl_cid,
+            metrics: record.metrics,
+            anchored_cids: record.anchored_cids,
+            anchored_cids_root: None,
+            resource_usage: record.resource_usage,
+            timestamp: record.timestamp,
+            dag_epoch: record.dag_epoch,
+            receipt_cid: record.receipt_cid,
+            signature: record.signature.map(TaggedSignature::from_legacy_bytes),
+            coop_id: None,
+            community_id: None,
+        }
+    }
+}
+
+/// Prefix of proposal records written as CBOR. Records without it are bincode
+/// in the layout of [`LegacyProposalRecord`], from before proposals carried a
+/// compilation manifest.
+const PROPOSAL_RECORD_V1: &[u8] = b"icn-proposal/v1:";
+
+/// Proposal record layout written before [`PROPOSAL_RECORD_V1`]
+#[derive(Deserialize)]
+struct LegacyProposalRecord {
+    id: String,
+    wasm_cid: String,
+    ccl_cid: String,
+    state: ProposalState,
+    quorum_status: QuorumStatus,
+}
+
+impl From<LegacyProposalRecord> for Proposal {
+    fn from(record: LegacyProposalRecord) -> Self {
+        Proposal {
+            id: record.id,
+            wasm_cid: record.wasm_cid,
+            ccl_cid: record.ccl_cid,
+            state: record.state,
+            quorum_status: record.quorum_status,
+            compilation_manifest: None,
+        }
+    }
+}
+
+/// Encode `value` as CBOR behind the record version `prefix`
+fn encode_record<T: Serialize>(prefix: &[u8], value: &T) -> Result<Vec<u8>> {
+    let mut data = prefix.to_vec();
+    serde_cbor::to_writer(&mut data, value)?;
+    Ok(data)
+}
+
+/// Decode a record written by [`encode_record`] with `prefix`, or a bincode
+/// `Legacy` record written before the record was versioned.
+fn decode_record<T, Legacy>(prefix: &[u8], data: &[u8]) -> Result<T>
+where
+    T: DeserializeOwned,
+    Legacy: DeserializeOwned + Into<T>,
+{
+    match data.strip_prefix(prefix) {
+        Some(cbor) => Ok(serde_cbor::from_slice(cbor)?),
+        None => Ok(bincode::deserialize::<Legacy>(data)?.into()),
+    }
+}
+
+fn encode_receipt(receipt: &RuntimeExecutionReceipt) -> Result<Vec<u8>> {
+    encode_record(RECEIPT_RECORD_V1, receipt).context("Failed to serialize receipt")
+}
+
+fn decode_receipt(data: &[u8]) -> Result<RuntimeExecutionReceipt> {
+    decode_record::<_, LegacyReceiptRecord>(RECEIPT_RECORD_V1, data)
+        .context("Failed to deserialize receipt")
+}
+
+fn encode_proposal(proposal: &Proposal) -> Result<Vec<u8>> {
+    encode_record(PROPOSAL_RECORD_V1, proposal).context("Failed to serialize proposal")
+}
+
+fn decode_proposal(data: &[u8]) -> Result<Proposal> {
+    decode_record::<_, LegacyProposalRecord>(PROPOSAL_RECORD_V1, data)
+        .context("Failed to deserialize proposal")
+}
+
+/// Storage layout version written by this build.
+///
+/// Version 2 adds the `receipt_time:` index that receipts are listed from,
+/// and version 3 the `proposal_state:` index that proposals are listed by
+/// state from. Stores without a recorded version are version 1. Records
+/// stored before an index existed are indexed when the store is opened.
+pub const STORAGE_SCHEMA_VERSION: u32 = 3;
+const SCHEMA_VERSION_KEY: &str = "meta:schema_version";
 
 /// A persistent storage backend using Sled embedded database.
 pub struct SledStorage {
@@ -19,7 +144,86 @@ impl SledStorage {
     pub fn open(path: &Path) -> Result<Self> {
         tracing::info!("Opening Sled database at: {:?}", path);
         let db = sled::open(path).context(format!("Failed to open sled database at {:?}", path))?;
-        Ok(Self { db })
+        let storage = Self { db };
+        storage.migrate()?;
+        Ok(storage)
+    }
+
+    /// Storage layout version of the open store
+    pub fn schema_version(&self) -> Result<u32> {
+        match self.db.get(SCHEMA_VERSION_KEY)? {
+            None => Ok(1),
+            Some(ivec) => {
+                let bytes: [u8; 4] = ivec[..]
+                    .try_into()
+                    .map_err(|_| anyhow!("Corrupt storage schema version: {:?}", ivec))?;
+                Ok(u32::from_be_bytes(bytes))
+            }
+        }
+    }
+
+    // Bring a store written by an older build up to STORAGE_SCHEMA_VERSION,
+    // applying the new index entries and the version in one atomic batch
+    fn migrate(&self) -> Result<()> {
+        let version = self.schema_version()?;
+        if version > STORAGE_SCHEMA_VERSION {
+            return Err(anyhow!(
+                "Storage schema version {} is newer than the supported version {}",
+                version,
+                STORAGE_SCHEMA_VERSION
+            ));
+        }
+        if version == STORAGE_SCHEMA_VERSION {
+            return Ok(());
+        }
+        let mut batch = sled::Batch::default();
+        if version < 2 {
+            self.backfill_receipt_time_index(&mut batch)?;
+        }
+        if version < 3 {
+            self.backfill_proposal_state_index(&mut batch)?;
+        }
+        batch.insert(
+            SCHEMA_VERSION_KEY,
+            STORAGE_SCHEMA_VERSION.to_be_bytes().to_vec(),
+        );
+        self.db.apply_batch(batch)?;
+        self.db
+            .flush()
+            .context("Failed to flush storage schema version")?;
+        tracing::info!(
+            from = version,
+            to = STORAGE_SCHEMA_VERSION,
+            "Migrated storage schema"
+        );
+        Ok(())
+    }
+
+    // Index receipts stored before the time index existed, so they are listed
+    fn backfill_receipt_time_index(&self, batch: &mut sled::Batch) -> Result<()> {
+        for entry in self.db.scan_prefix(Self::receipt_key("").as_bytes()) {
+            let (key, value) = entry?;
+            let receipt = decode_receipt(&value).with_context(|| {
+                format!("Failed to index receipt {}", String::from_utf8_lossy(&key))
+            })?;
+            batch.insert(Self::receipt_time_key(&receipt).as_bytes(), &[] as &[u8]);
+        }
+        Ok(())
+    }
+
+    // Index proposals stored before the state index existed, so they are listed
+    fn backfill_proposal_state_index(&self, batch: &mut sled::Batch) -> Result<()> {
+        for entry in self.db.scan_prefix(Self::proposal_key("").as_bytes()) {
+            let (key, value) = entry?;
+            let proposal = decode_proposal(&value).with_context(|| {
+                format!("Failed to index proposal {}", String::from_utf8_lossy(&key))
+            })?;
+            batch.insert(
+                Self::proposal_state_key(&proposal.state, &proposal.id).as_bytes(),
+                &[] as &[u8],
+            );
+        }
+        Ok(())
     }
 
     // Helper to generate keys with prefixes
@@ -31,9 +235,38 @@ impl SledStorage {
         format!("receipt:{}", cid)
     }
 
+    // Time index entries are `receipt_time:<zero-padded timestamp>:<id>` with an
+    // empty value, so a prefix scan yields receipts in timestamp order.
+    const RECEIPT_TIME_PREFIX: &'static str = "receipt_time:";
+
+    fn receipt_time_key(receipt: &RuntimeExecutionReceipt) -> String {
+        format!("{}{:020}:{}", Self::RECEIPT_TIME_PREFIX, receipt.timestamp, receipt.id)
+    }
+
+    fn anchored_cids_key(root: &str) -> String {
+        format!("anchored_cids:{}", root)
+    }
+
     fn proposal_key(id: &str) -> String {
         format!("proposal:{}", id)
     }
+
+    // State index entries are `proposal_state:<state>:<id>` with an empty value,
+    // so all proposals in a state can be found with a prefix scan.
+    fn proposal_state_prefix(state: &ProposalState) -> String {
+        let tag = match state {
+            ProposalState::Created => "created",
+            ProposalState::Voting => "voting",
+            ProposalState::Approved => "approved",
+            ProposalState::Rejected => "rejected",
+            ProposalState::Executed => "executed",
+        };
+        format!("proposal_state:{}:", tag)
+    }
+
+    fn proposal_state_key(state: &ProposalState, id: &str) -> String {
+        format!("{}{}", Self::proposal_state_prefix(state), id)
+    }
 }
 
 #[async_trait]
@@ -67,8 +300,17 @@ impl RuntimeStorage for SledStorage {
         let receipt_id = &receipt.id;
         let key = Self::receipt_key(receipt_id);
         tracing::debug!(key = %key, "Storing Receipt");
-        let data = bincode::serialize(receipt).context("Failed to serialize receipt")?;
-        self.db.insert(&key, data)?;
+        let data = encode_receipt(receipt)?;
+
+        // Write the receipt and move its time index entry in one atomic batch
+        let mut batch = sled::Batch::default();
+        if let Some(previous) = self.db.get(&key)? {
+            let previous = decode_receipt(&previous).context("Failed to decode existing receipt")?;
+            batch.remove(Self::receipt_time_key(&previous).as_bytes());
+        }
+        batch.insert(Self::receipt_time_key(receipt).as_bytes(), &[] as &[u8]);
+        batch.insert(key.as_bytes(), data);
+        self.db.apply_batch(batch)?;
         // self.db.flush_async().await?;
         Ok(receipt_id.clone()) // Return the ID used as the key
     }
@@ -81,9 +323,30 @@ impl RuntimeStorage for SledStorage {
             .db
             .get(&key)?
             .ok_or_else(|| anyhow!("Receipt not found for ID {} (key: {})", receipt_id, key))?;
-        let receipt = bincode::deserialize::<RuntimeExecutionReceipt>(&ivec)
-            .context("Failed to deserialize receipt")?;
-        Ok(receipt)
+        decode_receipt(&ivec)
+    }
+
+    async fn list_receipts(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<RuntimeExecutionReceipt>> {
+        tracing::debug!(offset, limit, "Listing receipts");
+        let mut receipts = Vec::new();
+        for entry in self
+            .db
+            .scan_prefix(Self::RECEIPT_TIME_PREFIX.as_bytes())
+            .skip(offset)
+            .take(limit)
+        {
+            let (index_key, _) = entry?;
+            let index_key = std::str::from_utf8(&index_key)
+                .context("Receipt time index key is not valid UTF-8")?;
+            // Skip the prefix and the fixed-width timestamp and its separator
+            let id = &index_key[Self::RECEIPT_TIME_PREFIX.len() + 21..];
+            receipts.push(self.load_receipt(id).await?);
+        }
+        Ok(receipts)
     }
 
     // --- Proposal Storage (Stubs - Requires Implementation) ---
@@ -94,21 +357,46 @@ impl RuntimeStorage for SledStorage {
             .db
             .get(&key)?
             .ok_or_else(|| anyhow::anyhow!("Proposal {} not found (key: {})", id, key))?;
-        let proposal =
-            bincode::deserialize::<Proposal>(&val).context("Failed to deserialize proposal")?;
-        Ok(proposal)
+        decode_proposal(&val)
     }
 
     async fn update_proposal(&self, proposal: &Proposal) -> Result<()> {
         let key = Self::proposal_key(&proposal.id);
         tracing::debug!(key = %key, "Updating proposal");
-        let data = bincode::serialize(proposal).context("Failed to serialize proposal")?;
-        self.db.insert(key, data)?;
+        let data = encode_proposal(proposal)?;
+
+        // Write the proposal and move its state index entry in one atomic batch
+        let mut batch = sled::Batch::default();
+        if let Some(previous) = self.db.get(&key)? {
+            let previous = decode_proposal(&previous).context("Failed to decode existing proposal")?;
+            if previous.state != proposal.state {
+                batch.remove(Self::proposal_state_key(&previous.state, &proposal.id).as_bytes());
+            }
+        }
+        batch.insert(
+            Self::proposal_state_key(&proposal.state, &proposal.id).as_bytes(),
+            &[] as &[u8],
+        );
+        batch.insert(key.as_bytes(), data);
+        self.db.apply_batch(batch)?;
         // Consider flushing explicitly if immediate durability is critical
         // self.db.flush_async().await?;
         Ok(())
     }
 
+    async fn load_proposals_by_state(&self, state: ProposalState) -> Result<Vec<Proposal>> {
+        let prefix = Self::proposal_state_prefix(&state);
+        tracing::debug!(prefix = %prefix, "Loading proposals by state");
+        let mut proposals = Vec::new();
+        for entry in self.db.scan_prefix(prefix.as_bytes()) {
+            let (index_key, _) = entry?;
+            let id = std::str::from_utf8(&index_key[prefix.len()..])
+                .context("Proposal state index key is not valid UTF-8")?;
+            proposals.push(self.load_proposal(id).await?);
+        }
+        Ok(proposals)
+    }
+
     // --- DAG Anchoring (Stub - Belongs elsewhere) ---
     async fn anchor_to_dag(&self, _cid: &str) -> Result<String> {
         tracing::error!("anchor_to_dag called on SledStorage - this is not a DAG store!");
@@ -116,4 +404,23 @@ impl RuntimeStorage for SledStorage {
         // with a separate DAG component (which might *use* Sled internally).
         Err(anyhow!("SledStorage does not support direct DAG anchoring"))
     }
+
+    // --- Compacted Receipt Anchors ---
+    async fn store_anchored_cids(&self, root: &str, cids: &[String]) -> Result<()> {
+        let key = Self::anchored_cids_key(root);
+        tracing::debug!(key = %key, count = cids.len(), "Storing anchored CID list");
+        let data = bincode::serialize(cids).context("Failed to serialize anchored CID list")?;
+        self.db.insert(key, data)?;
+        Ok(())
+    }
+
+    async fn load_anchored_cids(&self, root: &str) -> Result<Vec<String>> {
+        let key = Self::anchored_cids_key(root);
+        tracing::debug!(key = %key, "Loading anchored CID list");
+        let ivec = self
+            .db
+            .get(&key)?
+            .ok_or_else(|| anyhow!("Anchored CID list not found for root {} (key: {})", root, key))?;
+        bincode::deserialize(&ivec).context("Failed to deserialize anchored CID list")
+    }
 }
@@ -137,6 +137,7 @@ mod tests {
                 name: "Test Federation".to_string(),
                 description: Some("Test Description".to_string()),
                 version: "1.0".to_string(),
+                max_token_supply: None,
                 additional: std::collections::HashMap::new(),
             }
         );
@@ -9,7 +9,9 @@ use crate::host_environment::ConcreteHostEnvironment;
 
 // For full_host_abi, we use types and functions from linker_legacy_impl.rs
 #[cfg(feature = "full_host_abi")]
-pub use crate::wasm::linker_legacy_impl::register_host_functions;
+pub use crate::wasm::linker_legacy_impl::{
+    register_host_functions, register_host_functions_with_capabilities,
+};
 
 #[cfg(feature = "full_host_abi")]
 pub type StoreData = ConcreteHostEnvironment<()>;
@@ -25,5 +27,13 @@ pub fn register_host_functions<T: Send + Sync + 'static>(
     Ok(())
 }
 
+#[cfg(not(feature = "full_host_abi"))]
+pub fn register_host_functions_with_capabilities<T: Send + Sync + 'static>(
+    _linker: &mut Linker<T>,
+    _capabilities: &crate::host_capabilities::HostCapabilities,
+) -> Result<()> {
+    Ok(())
+}
+
 #[cfg(not(feature = "full_host_abi"))]
 pub type StoreData = (); // Minimal store data for non-full ABI builds
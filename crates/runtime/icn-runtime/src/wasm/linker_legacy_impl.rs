@@ -3,10 +3,11 @@
 // Enable with: `--features full_host_abi` in icn-runtime.
 
 use crate::host_environment::{
-    ConcreteHostEnvironment, get_memory, read_string_from_mem_ctx, // Import new helpers
-    // read_bytes_from_mem_ctx, write_string_to_mem_ctx, write_bytes_to_mem_ctx // Import others if needed
+    ConcreteHostEnvironment, get_memory, read_string_from_mem_ctx, write_string_to_mem_ctx, // Import new helpers
+    // read_bytes_from_mem_ctx, write_bytes_to_mem_ctx // Import others if needed
 };
-use crate::job_execution_context::JobExecutionContext; // If needed for type context, unlikely here
+use crate::host_capabilities::{HostCapabilities, HostCapability};
+use crate::job_execution_context::{JobExecutionContext, NO_WORKFLOW_CONTEXT};
 use anyhow::{anyhow, Result};
 use icn_identity::Did;
 use std::str::FromStr;
@@ -22,40 +23,64 @@ async fn host_anchor_receipt(
     receipt_ptr: u32,
     receipt_len: u32,
 ) -> Result<(), Trap> {
+    require_capability(&caller, HostCapability::DataAnchoring)?;
     let memory = get_memory(&mut caller).map_err(|e| Trap::new(format!("get_memory failed for anchor_receipt: {}", e)))?;
     let mut store_context = caller.as_context_mut();
 
+    // Reject oversized receipts before allocating a buffer for them
+    let limits = icn_types::bounded_decode::DecodeLimits::receipt();
+    if receipt_len as usize > limits.max_bytes {
+        return Err(Trap::new(format!(
+            "receipt of {} bytes exceeds limit of {} bytes",
+            receipt_len, limits.max_bytes
+        )));
+    }
+
     let mut buf = vec![0u8; receipt_len as usize];
     memory
         .read(&mut store_context, receipt_ptr as usize, &mut buf)
         .map_err(|e| Trap::new(format!("memory read failed: {}", e)))?;
     
-    let receipt: () = serde_cbor::from_slice(&buf).map_err(|e| Trap::new(format!("CBOR decode failed: {}", e)))?;
+    let receipt: () = icn_types::bounded_decode::from_cbor_bounded(&buf, &limits)
+        .map_err(|e| Trap::new(format!("CBOR decode failed: {}", e)))?;
     caller.data().anchor_receipt(receipt).await.map_err(host_abi_error_to_trap)?;
     Ok(())
 }
 
 /// Get mana balance for a DID (0-length str = caller DID).
 async fn host_account_get_mana(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>, // Mark caller as unused
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _did_ptr: u32, // Mark as unused
     _did_len: u32, // Mark as unused
 ) -> Result<i64, Trap> {
+    require_capability(&caller, HostCapability::ResourceRecording)?;
     // Stub implementation as per user summary
     Err(Trap::new("Deprecated: host_account_get_mana is no longer supported."))
 }
 
 /// Spend mana for a DID (0-length str = caller DID).
 async fn host_account_spend_mana(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>, // Mark caller as unused
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _did_ptr: u32, // Mark as unused
     _did_len: u32, // Mark as unused
     _amount: u64, // Mark as unused
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::ResourceRecording)?;
     // Stub implementation as per user summary
     Err(Trap::new("Deprecated: host_account_spend_mana is no longer supported."))
 }
 
+/// Trap with `HostAbiError::Unauthorized` unless the caller's environment enables `capability`
+fn require_capability(
+    caller: &Caller<'_, ConcreteHostEnvironment<()>>,
+    capability: HostCapability,
+) -> Result<(), Trap> {
+    caller
+        .data()
+        .require_capability(capability)
+        .map_err(host_abi_error_to_trap)
+}
+
 // Helper to convert HostAbiError to Trap
 fn host_abi_error_to_trap(err: HostAbiError) -> Trap {
     Trap::new(err.to_string()) // Already using Trap::new if Trap is in scope
@@ -63,146 +88,212 @@ fn host_abi_error_to_trap(err: HostAbiError) -> Trap {
 
 // Skeleton for host_job_get_id (WASM: "get_job_id")
 async fn local_get_job_id(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _job_id_buf_ptr: u32,
     _job_id_buf_len: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::JobContext)?;
     Err(Trap::new("Host function 'get_job_id' not yet implemented"))
 }
 
 // Skeleton for host_job_get_initial_input_cid (WASM: "host_job_get_initial_input_cid")
 async fn local_host_job_get_initial_input_cid(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _cid_buf_ptr: u32,
     _cid_buf_len: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::JobContext)?;
     Err(Trap::new("Host function 'host_job_get_initial_input_cid' not yet implemented"))
 }
 
 // Skeleton for host_job_is_interactive (WASM: "host_job_is_interactive")
-async fn local_host_job_is_interactive(_caller: Caller<'_, ConcreteHostEnvironment<()>>) -> Result<i32, Trap> {
+async fn local_host_job_is_interactive(caller: Caller<'_, ConcreteHostEnvironment<()>>) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::JobContext)?;
     Err(Trap::new("Host function 'host_job_is_interactive' not yet implemented"))
 }
 
-// Skeleton for host_workflow_get_current_stage_index (WASM: "host_workflow_get_current_stage_index")
+/// Write an optional workflow string into guest memory.
+///
+/// Returns the number of bytes written, `0` when there is no value, or
+/// `NO_WORKFLOW_CONTEXT` when the job is not a multi-stage workflow.
+async fn write_workflow_string(
+    caller: &mut Caller<'_, ConcreteHostEnvironment<()>>,
+    value: Result<Option<String>, HostAbiError>,
+    buf_ptr: u32,
+    buf_len: u32,
+) -> Result<i32, Trap> {
+    let value = match value {
+        Ok(Some(value)) => value,
+        Ok(None) => return Ok(0),
+        Err(HostAbiError::NotSupported) => return Ok(NO_WORKFLOW_CONTEXT),
+        Err(e) => return Err(host_abi_error_to_trap(e)),
+    };
+    let memory = get_memory(caller).map_err(|e| Trap::new(format!("get_memory failed: {}", e)))?;
+    let mut store_context = caller.as_context_mut();
+    write_string_to_mem_ctx(&mut store_context, &memory, &value, buf_ptr, buf_len)
+        .map_err(host_abi_error_to_trap)
+}
+
+/// WASM: "host_workflow_get_type"
+async fn local_host_workflow_get_type(
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
+) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::JobContext)?;
+    let ctx = caller.data().ctx.clone();
+    let code = ctx.lock().await.workflow_type_code();
+    Ok(code)
+}
+
+/// WASM: "host_workflow_get_current_stage_index"
 async fn local_host_workflow_get_current_stage_index(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
 ) -> Result<i32, Trap> {
-    Err(Trap::new("Host function 'host_workflow_get_current_stage_index' not yet implemented"))
+    require_capability(&caller, HostCapability::JobContext)?;
+    let ctx = caller.data().ctx.clone();
+    let code = ctx.lock().await.current_stage_index_code();
+    Ok(code)
 }
 
-// Skeleton for host_workflow_get_current_stage_id (WASM: "host_workflow_get_current_stage_id")
+/// WASM: "host_workflow_get_current_stage_id"
 async fn local_host_workflow_get_current_stage_id(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
-    _stage_id_buf_ptr: u32,
-    _stage_id_buf_len: u32,
+    mut caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    stage_id_buf_ptr: u32,
+    stage_id_buf_len: u32,
 ) -> Result<i32, Trap> {
-    Err(Trap::new("Host function 'host_workflow_get_current_stage_id' not yet implemented"))
+    require_capability(&caller, HostCapability::JobContext)?;
+    let ctx = caller.data().ctx.clone();
+    let stage_id = ctx.lock().await.current_stage_id_for_abi().map(Some);
+    write_workflow_string(&mut caller, stage_id, stage_id_buf_ptr, stage_id_buf_len).await
 }
 
-// Skeleton for host_workflow_get_current_stage_input_cid (WASM: "host_workflow_get_current_stage_input_cid")
+/// WASM: "host_workflow_get_current_stage_input_cid"
 async fn local_host_workflow_get_current_stage_input_cid(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
-    _cid_buf_ptr: u32,
-    _cid_buf_len: u32,
+    mut caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    cid_buf_ptr: u32,
+    cid_buf_len: u32,
 ) -> Result<i32, Trap> {
-    Err(Trap::new("Host function 'host_workflow_get_current_stage_input_cid' not yet implemented"))
+    require_capability(&caller, HostCapability::JobContext)?;
+    let ctx = caller.data().ctx.clone();
+    let input_cid = ctx.lock().await.stage_input_cid();
+    write_workflow_string(&mut caller, input_cid, cid_buf_ptr, cid_buf_len).await
 }
 
 // Skeleton for host_job_report_progress (WASM: "host_job_report_progress")
 async fn local_host_job_report_progress(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _progress_percentage: u32,
     _status_msg_ptr: u32,
     _status_msg_len: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::JobContext)?;
     Err(Trap::new("Host function 'host_job_report_progress' not yet implemented"))
 }
 
-// Skeleton for host_workflow_complete_current_stage (WASM: "host_workflow_complete_current_stage")
+/// WASM: "host_workflow_complete_current_stage"
 async fn local_host_workflow_complete_current_stage(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
-    _output_cid_ptr: u32,
-    _output_cid_len: u32,
-) -> Result<i32, Trap> {
-    Err(Trap::new("Host function 'host_workflow_complete_current_stage' not yet implemented"))
+    mut caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    output_cid_ptr: u32,
+    output_cid_len: u32,
+) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::JobContext)?;
+    let memory = get_memory(&mut caller).map_err(|e| Trap::new(format!("get_memory failed: {}", e)))?;
+    let output_cid = {
+        let mut store_context = caller.as_context_mut();
+        read_string_from_mem_ctx(&mut store_context, &memory, output_cid_ptr, output_cid_len)
+            .map_err(host_abi_error_to_trap)?
+    };
+    let ctx = caller.data().ctx.clone();
+    let result = ctx.lock().await.complete_current_stage(output_cid);
+    match result {
+        Ok(()) => Ok(0),
+        Err(HostAbiError::NotSupported) => Ok(NO_WORKFLOW_CONTEXT),
+        Err(e) => Err(host_abi_error_to_trap(e)),
+    }
 }
 
 // Skeleton for host_interactive_send_output (WASM: "interactive_send")
 async fn local_interactive_send(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _msg_ptr: u32,
     _msg_len: u32,
     _sequence_num: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::Interactive)?;
     Err(Trap::new("Host function 'interactive_send' not yet implemented"))
 }
 
 // Skeleton for host_interactive_receive_input (WASM: "interactive_recv")
 async fn local_interactive_recv(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _buffer_ptr: u32,
     _buffer_len: u32,
     _timeout_ms: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::Interactive)?;
     Err(Trap::new("Host function 'interactive_recv' not yet implemented"))
 }
 
 // Skeleton for host_interactive_peek_input_len (WASM: "host_interactive_peek_input_len")
-async fn local_host_interactive_peek_input_len(_caller: Caller<'_, ConcreteHostEnvironment<()>>) -> Result<i32, Trap> {
+async fn local_host_interactive_peek_input_len(caller: Caller<'_, ConcreteHostEnvironment<()>>) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::Interactive)?;
     Err(Trap::new("Host function 'host_interactive_peek_input_len' not yet implemented"))
 }
 
 // Skeleton for host_interactive_prompt_for_input (WASM: "host_interactive_prompt_for_input")
 async fn local_host_interactive_prompt_for_input(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _prompt_msg_ptr: u32,
     _prompt_msg_len: u32,
     _timeout_ms: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::Interactive)?;
     Err(Trap::new("Host function 'host_interactive_prompt_for_input' not yet implemented"))
 }
 
 // Skeleton for host_data_read_cid (WASM: "read_data")
 async fn local_read_data(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _data_id_ptr: u32,
     _data_id_len: u32,
     _buffer_ptr: u32,
     _buffer_len: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::DataAnchoring)?;
     Err(Trap::new("Host function 'read_data' not yet implemented"))
 }
 
 // Skeleton for host_data_write_buffer (WASM: "anchor_data")
 async fn local_anchor_data(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _data_id_ptr: u32,
     _data_id_len: u32,
     _metadata_ptr: u32,
     _metadata_len: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::DataAnchoring)?;
     Err(Trap::new("Host function 'anchor_data' not yet implemented"))
 }
 
 // Skeleton for host_log_message (WASM: "log_message")
 async fn local_log_message(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _level_val: i32, 
     _message_ptr: u32,
     _message_len: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::Logging)?;
     Err(Trap::new("Host function 'log_message' not yet implemented"))
 }
 
 // Skeleton for host_submit_mesh_job (WASM: "host_submit_mesh_job")
 async fn local_host_submit_mesh_job_old(
-    _caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    caller: Caller<'_, ConcreteHostEnvironment<()>>,
     _job_params_cbor_ptr: u32,
     _job_params_cbor_len: u32,
     _job_id_buf_ptr: u32,
     _job_id_buf_len: u32,
 ) -> Result<i32, Trap> {
+    require_capability(&caller, HostCapability::MeshJobSubmission)?;
     Err(Trap::new("Host function 'host_submit_mesh_job' (old) not yet implemented"))
 }
 
@@ -358,6 +449,24 @@ async fn local_host_use_resource_new(
     MeshHostAbi::host_use_resource(caller.data(), caller, resource_type_ptr, resource_type_len, amount).await.map_err(host_abi_error_to_trap)
 }
 
+async fn local_host_get_mana_balance_new(
+    mut caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    did_ptr: u32,
+    did_len: u32,
+    out_balance_ptr: u32,
+) -> Result<i32, Trap> {
+    MeshHostAbi::host_get_mana_balance(caller.data(), caller, did_ptr, did_len, out_balance_ptr).await.map_err(host_abi_error_to_trap)
+}
+
+async fn local_host_p2p_receive_message_new(
+    mut caller: Caller<'_, ConcreteHostEnvironment<()>>,
+    buffer_ptr: u32,
+    buffer_len: u32,
+    timeout_ms: u32,
+) -> Result<i32, Trap> {
+    MeshHostAbi::host_p2p_receive_message(caller.data(), caller, buffer_ptr, buffer_len, timeout_ms).await.map_err(host_abi_error_to_trap)
+}
+
 async fn local_host_transfer_token_new(
     mut caller: Caller<'_, ConcreteHostEnvironment<()>>,
     token_type_ptr: u32,
@@ -384,46 +493,77 @@ async fn local_host_submit_mesh_job_new(
     MeshHostAbi::host_submit_mesh_job(caller.data(), caller, cbor_payload_ptr, cbor_payload_len, job_id_buffer_ptr, job_id_buffer_len).await.map_err(host_abi_error_to_trap)
 }
 
-/// Register ICN host functions (legacy/full build).
+/// Register the full host ABI (legacy/full build).
 pub fn register_host_functions(linker: &mut Linker<ConcreteHostEnvironment<()>>) -> Result<()> {
-    linker.func_wrap2_async("icn_host", "anchor_receipt", host_anchor_receipt)?;
-    linker.func_wrap2_async("icn_host", "account_get_mana", host_account_get_mana)?;
-    linker.func_wrap3_async("icn_host", "account_spend_mana", host_account_spend_mana)?;
-
-    linker.func_wrap2_async("icn_host", "get_job_id", local_get_job_id)?;
-    linker.func_wrap2_async("icn_host", "host_job_get_initial_input_cid", local_host_job_get_initial_input_cid)?;
-    linker.func_wrap0_async("icn_host", "host_job_is_interactive", local_host_job_is_interactive)?;
-    linker.func_wrap0_async("icn_host", "host_workflow_get_current_stage_index", local_host_workflow_get_current_stage_index)?;
-    linker.func_wrap2_async("icn_host", "host_workflow_get_current_stage_id", local_host_workflow_get_current_stage_id)?;
-    linker.func_wrap2_async("icn_host", "host_workflow_get_current_stage_input_cid", local_host_workflow_get_current_stage_input_cid)?;
-    linker.func_wrap3_async("icn_host", "host_job_report_progress", local_host_job_report_progress)?;
-    linker.func_wrap2_async("icn_host", "host_workflow_complete_current_stage", local_host_workflow_complete_current_stage)?;
-    linker.func_wrap3_async("icn_host", "interactive_send", local_interactive_send)?;
-    linker.func_wrap3_async("icn_host", "interactive_recv", local_interactive_recv)?;
-    linker.func_wrap0_async("icn_host", "host_interactive_peek_input_len", local_host_interactive_peek_input_len)?;
-    linker.func_wrap3_async("icn_host", "host_interactive_prompt_for_input", local_host_interactive_prompt_for_input)?;
-    linker.func_wrap4_async("icn_host", "read_data", local_read_data)?;
-    linker.func_wrap4_async("icn_host", "anchor_data", local_anchor_data)?;
-    linker.func_wrap3_async("icn_host", "log_message", local_log_message)?;
-    linker.func_wrap4_async("icn_host", "host_submit_mesh_job_old", local_host_submit_mesh_job_old)?;
-
-    linker.func_wrap4_async("icn_host_new", "host_begin_section", |mut caller, k_ptr, k_len, t_ptr, t_len| Box::pin(local_host_begin_section_new(caller, k_ptr, k_len, t_ptr, t_len)))?;
-    linker.func_wrap0_async("icn_host_new", "host_end_section", |mut caller| Box::pin(local_host_end_section_new(caller)))?;
-    linker.func_wrap4_async("icn_host_new", "host_set_property", |mut caller, k_ptr, k_len, v_ptr, v_len| Box::pin(local_host_set_property_new(caller, k_ptr, k_len, v_ptr, v_len)))?;
-    linker.func_wrap4_async("icn_host_new", "host_anchor_data", |mut caller, p_ptr, p_len, dr_ptr, dr_len| Box::pin(local_host_anchor_data_new(caller, p_ptr, p_len, dr_ptr, dr_len)))?;
-    linker.func_wrap4_async("icn_host_new", "host_generic_call", |mut caller, fn_ptr, fn_len, ap_ptr, ap_len| Box::pin(local_host_generic_call_new(caller, fn_ptr, fn_len, ap_ptr, ap_len)))?;
-    linker.func_wrap6_async("icn_host_new", "host_create_proposal", |mut caller, id_ptr, id_len, t_ptr, t_len, v_ptr, v_len| Box::pin(local_host_create_proposal_new(caller, id_ptr, id_len, t_ptr, t_len, v_ptr, v_len)))?;
-    linker.func_wrap7_async("icn_host_new", "host_mint_token", |mut caller, rt_ptr, rt_len, amt, recip_ptr, recip_len, dj_ptr, dj_len| Box::pin(local_host_mint_token_new(caller, rt_ptr, rt_len, amt, recip_ptr, recip_len, dj_ptr, dj_len)))?;
-    linker.func_wrap2_async("icn_host_new", "host_if_condition_eval", |mut caller, cond_ptr, cond_len| Box::pin(local_host_if_condition_eval_new(caller, cond_ptr, cond_len)))?;
-    linker.func_wrap0_async("icn_host_new", "host_else_handler", |mut caller| Box::pin(local_host_else_handler_new(caller)))?;
-    linker.func_wrap0_async("icn_host_new", "host_endif_handler", |mut caller| Box::pin(local_host_endif_handler_new(caller)))?;
-    linker.func_wrap2_async("icn_host_new", "host_log_todo", |mut caller, msg_ptr, msg_len| Box::pin(local_host_log_todo_new(caller, msg_ptr, msg_len)))?;
-    linker.func_wrap2_async("icn_host_new", "host_on_event", |mut caller, ev_ptr, ev_len| Box::pin(local_host_on_event_new(caller, ev_ptr, ev_len)))?;
-    linker.func_wrap2_async("icn_host_new", "host_log_debug_deprecated", |mut caller, msg_ptr, msg_len| Box::pin(local_host_log_debug_deprecated_new(caller, msg_ptr, msg_len)))?;
+    register_host_functions_with_capabilities(linker, &HostCapabilities::all())
+}
+
+/// Register only the host functions belonging to `capabilities`.
+///
+/// A module importing a function outside `capabilities` fails to link. Host
+/// environments also check their own capabilities on every call, so a linker
+/// shared across job classes still traps with `HostAbiError::Unauthorized`.
+pub fn register_host_functions_with_capabilities(
+    linker: &mut Linker<ConcreteHostEnvironment<()>>,
+    capabilities: &HostCapabilities,
+) -> Result<()> {
+    if capabilities.contains(HostCapability::DataAnchoring) {
+        linker.func_wrap2_async("icn_host", "anchor_receipt", host_anchor_receipt)?;
+        linker.func_wrap4_async("icn_host", "read_data", local_read_data)?;
+        linker.func_wrap4_async("icn_host", "anchor_data", local_anchor_data)?;
+        linker.func_wrap4_async("icn_host_new", "host_anchor_data", |mut caller, p_ptr, p_len, dr_ptr, dr_len| Box::pin(local_host_anchor_data_new(caller, p_ptr, p_len, dr_ptr, dr_len)))?;
+    }
+    if capabilities.contains(HostCapability::ResourceRecording) {
+        linker.func_wrap2_async("icn_host", "account_get_mana", host_account_get_mana)?;
+        linker.func_wrap3_async("icn_host", "account_spend_mana", host_account_spend_mana)?;
+        linker.func_wrap3_async("icn_host_new", "host_use_resource", |mut caller, rt_ptr, rt_len, amt| Box::pin(local_host_use_resource_new(caller, rt_ptr, rt_len, amt)))?;
+        linker.func_wrap3_async("icn_host_new", "host_get_mana_balance", |mut caller, did_ptr, did_len, out_ptr| Box::pin(local_host_get_mana_balance_new(caller, did_ptr, did_len, out_ptr)))?;
+    }
+    if capabilities.contains(HostCapability::JobContext) {
+        linker.func_wrap2_async("icn_host", "get_job_id", local_get_job_id)?;
+        linker.func_wrap2_async("icn_host", "host_job_get_initial_input_cid", local_host_job_get_initial_input_cid)?;
+        linker.func_wrap0_async("icn_host", "host_job_is_interactive", local_host_job_is_interactive)?;
+        linker.func_wrap0_async("icn_host", "host_workflow_get_type", local_host_workflow_get_type)?;
+        linker.func_wrap0_async("icn_host", "host_workflow_get_current_stage_index", local_host_workflow_get_current_stage_index)?;
+        linker.func_wrap2_async("icn_host", "host_workflow_get_current_stage_id", local_host_workflow_get_current_stage_id)?;
+        linker.func_wrap2_async("icn_host", "host_workflow_get_current_stage_input_cid", local_host_workflow_get_current_stage_input_cid)?;
+        linker.func_wrap3_async("icn_host", "host_job_report_progress", local_host_job_report_progress)?;
+        linker.func_wrap2_async("icn_host", "host_workflow_complete_current_stage", local_host_workflow_complete_current_stage)?;
+    }
+    if capabilities.contains(HostCapability::Interactive) {
+        linker.func_wrap3_async("icn_host", "interactive_send", local_interactive_send)?;
+        linker.func_wrap3_async("icn_host", "interactive_recv", local_interactive_recv)?;
+        linker.func_wrap0_async("icn_host", "host_interactive_peek_input_len", local_host_interactive_peek_input_len)?;
+        linker.func_wrap3_async("icn_host", "host_interactive_prompt_for_input", local_host_interactive_prompt_for_input)?;
+        linker.func_wrap3_async("icn_host_new", "host_p2p_receive_message", |mut caller, buf_ptr, buf_len, timeout_ms| Box::pin(local_host_p2p_receive_message_new(caller, buf_ptr, buf_len, timeout_ms)))?;
+    }
+    if capabilities.contains(HostCapability::Logging) {
+        linker.func_wrap3_async("icn_host", "log_message", local_log_message)?;
+        linker.func_wrap2_async("icn_host_new", "host_log_todo", |mut caller, msg_ptr, msg_len| Box::pin(local_host_log_todo_new(caller, msg_ptr, msg_len)))?;
+        linker.func_wrap2_async("icn_host_new", "host_log_debug_deprecated", |mut caller, msg_ptr, msg_len| Box::pin(local_host_log_debug_deprecated_new(caller, msg_ptr, msg_len)))?;
+    }
+    if capabilities.contains(HostCapability::MeshJobSubmission) {
+        linker.func_wrap4_async("icn_host", "host_submit_mesh_job_old", local_host_submit_mesh_job_old)?;
+        linker.func_wrap4_async("icn_host_new", "host_submit_mesh_job", |mut caller, payload_ptr, payload_len, jid_buf_ptr, jid_buf_len| Box::pin(local_host_submit_mesh_job_new(caller, payload_ptr, payload_len, jid_buf_ptr, jid_buf_len)))?;
+    }
+    if capabilities.contains(HostCapability::Governance) {
+        linker.func_wrap4_async("icn_host_new", "host_begin_section", |mut caller, k_ptr, k_len, t_ptr, t_len| Box::pin(local_host_begin_section_new(caller, k_ptr, k_len, t_ptr, t_len)))?;
+        linker.func_wrap0_async("icn_host_new", "host_end_section", |mut caller| Box::pin(local_host_end_section_new(caller)))?;
+        linker.func_wrap4_async("icn_host_new", "host_set_property", |mut caller, k_ptr, k_len, v_ptr, v_len| Box::pin(local_host_set_property_new(caller, k_ptr, k_len, v_ptr, v_len)))?;
+        linker.func_wrap4_async("icn_host_new", "host_generic_call", |mut caller, fn_ptr, fn_len, ap_ptr, ap_len| Box::pin(local_host_generic_call_new(caller, fn_ptr, fn_len, ap_ptr, ap_len)))?;
+        linker.func_wrap6_async("icn_host_new", "host_create_proposal", |mut caller, id_ptr, id_len, t_ptr, t_len, v_ptr, v_len| Box::pin(local_host_create_proposal_new(caller, id_ptr, id_len, t_ptr, t_len, v_ptr, v_len)))?;
+        linker.func_wrap2_async("icn_host_new", "host_if_condition_eval", |mut caller, cond_ptr, cond_len| Box::pin(local_host_if_condition_eval_new(caller, cond_ptr, cond_len)))?;
+        linker.func_wrap0_async("icn_host_new", "host_else_handler", |mut caller| Box::pin(local_host_else_handler_new(caller)))?;
+        linker.func_wrap0_async("icn_host_new", "host_endif_handler", |mut caller| Box::pin(local_host_endif_handler_new(caller)))?;
+        linker.func_wrap2_async("icn_host_new", "host_on_event", |mut caller, ev_ptr, ev_len| Box::pin(local_host_on_event_new(caller, ev_ptr, ev_len)))?;
+    }
+    if capabilities.contains(HostCapability::Minting) {
+        linker.func_wrap7_async("icn_host_new", "host_mint_token", |mut caller, rt_ptr, rt_len, amt, recip_ptr, recip_len, dj_ptr, dj_len| Box::pin(local_host_mint_token_new(caller, rt_ptr, rt_len, amt, recip_ptr, recip_len, dj_ptr, dj_len)))?;
+    }
+    if capabilities.contains(HostCapability::TokenTransfer) {
+        linker.func_wrap7_async("icn_host_new", "host_transfer_token", |mut caller, tt_ptr, tt_len, amt, s_ptr, s_len, r_ptr, r_len| Box::pin(local_host_transfer_token_new(caller, tt_ptr, tt_len, amt, s_ptr, s_len, r_ptr, r_len)))?;
+    }
     linker.func_wrap3_async("icn_host_new", "host_range_check", |mut caller, val, min, max| Box::pin(local_host_range_check_new(caller, val, min, max)))?;
-    linker.func_wrap3_async("icn_host_new", "host_use_resource", |mut caller, rt_ptr, rt_len, amt| Box::pin(local_host_use_resource_new(caller, rt_ptr, rt_len, amt)))?;
-    linker.func_wrap7_async("icn_host_new", "host_transfer_token", |mut caller, tt_ptr, tt_len, amt, s_ptr, s_len, r_ptr, r_len| Box::pin(local_host_transfer_token_new(caller, tt_ptr, tt_len, amt, s_ptr, s_len, r_ptr, r_len)))?;
-    linker.func_wrap4_async("icn_host_new", "host_submit_mesh_job", |mut caller, payload_ptr, payload_len, jid_buf_ptr, jid_buf_len| Box::pin(local_host_submit_mesh_job_new(caller, payload_ptr, payload_len, jid_buf_ptr, jid_buf_len)))?;
-    
+
     Ok(())
 }
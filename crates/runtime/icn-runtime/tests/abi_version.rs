@@ -0,0 +1,97 @@
+use anyhow::Result;
+use host_abi::{abi_version_section_data, ICN_ABI_VERSION_SECTION, ICN_HOST_ABI_VERSION};
+use icn_economics::mana::InMemoryManaLedger;
+use icn_runtime::{InMemoryModuleCache, MemStorage, Runtime, RuntimeError, RuntimeStorage};
+use std::sync::Arc;
+
+const EMPTY_MODULE: &[u8] = b"\0asm\x01\0\0\0";
+
+/// An empty module carrying an `icn_abi_version` custom section.
+fn module_targeting(version: u32) -> Vec<u8> {
+    let mut body = vec![ICN_ABI_VERSION_SECTION.len() as u8];
+    body.extend_from_slice(ICN_ABI_VERSION_SECTION.as_bytes());
+    body.extend_from_slice(&abi_version_section_data(version));
+
+    let mut wasm = EMPTY_MODULE.to_vec();
+    wasm.push(0); // custom section id
+    wasm.push(body.len() as u8);
+    wasm.extend(body);
+    wasm
+}
+
+async fn runtime_with(cid: &str, wasm: &[u8]) -> Result<Runtime<InMemoryManaLedger>> {
+    let storage = Arc::new(MemStorage::new());
+    storage.store_wasm(cid, wasm).await?;
+    Ok(Runtime::<InMemoryManaLedger>::new(storage)?
+        .with_module_cache(Arc::new(InMemoryModuleCache::new())))
+}
+
+#[tokio::test]
+async fn accepts_current_and_unversioned_modules() -> Result<()> {
+    let runtime = runtime_with("current", &module_targeting(ICN_HOST_ABI_VERSION)).await?;
+    runtime.warm_up(&["current".to_string()]).await?;
+
+    let runtime = runtime_with("legacy", EMPTY_MODULE).await?;
+    runtime.warm_up(&["legacy".to_string()]).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_incompatible_abi_version() -> Result<()> {
+    let runtime = runtime_with("stale", &module_targeting(ICN_HOST_ABI_VERSION - 1)).await?;
+
+    let err = runtime
+        .warm_up(&["stale".to_string()])
+        .await
+        .expect_err("stale ABI must be rejected");
+    match err.downcast_ref::<RuntimeError>() {
+        Some(RuntimeError::LoadError(msg)) => {
+            assert!(msg.contains("host ABI version"), "{}", msg)
+        }
+        other => panic!("expected LoadError, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "full_host_abi")]
+#[tokio::test]
+async fn linker_registers_exactly_the_host_imports() -> Result<()> {
+    use host_abi::HOST_IMPORTS;
+    use icn_identity::KeyPair;
+    use icn_runtime::host_environment::ConcreteHostEnvironment;
+    use icn_runtime::job_execution_context::JobExecutionContext;
+    use icn_runtime::wasm::register_host_functions;
+    use icn_runtime::RuntimeContextBuilder;
+    use icn_types::mesh::MeshJobParams;
+    use std::collections::BTreeSet;
+    use tokio::sync::Mutex;
+    use wasmtime::{Config, Engine, Linker, Store};
+
+    let did = KeyPair::generate().did;
+    let ctx = Arc::new(Mutex::new(JobExecutionContext::new(
+        "job:abi".to_string(),
+        did.clone(),
+        MeshJobParams::default(),
+        KeyPair::generate().did,
+        0,
+    )));
+    let rt = Arc::new(RuntimeContextBuilder::new().build());
+    let env = ConcreteHostEnvironment::<()>::new(ctx, did, rt);
+
+    let engine = Engine::new(Config::new().async_support(true))?;
+    let mut linker = Linker::new(&engine);
+    register_host_functions(&mut linker)?;
+    let mut store = Store::new(&engine, env);
+
+    let linked: Vec<(String, String)> = linker
+        .iter(&mut store)
+        .map(|(module, name, _)| (module.to_string(), name.to_string()))
+        .collect();
+    let expected: BTreeSet<(String, String)> = HOST_IMPORTS
+        .iter()
+        .map(|(module, name)| (module.to_string(), name.to_string()))
+        .collect();
+    assert_eq!(linked.len(), HOST_IMPORTS.len());
+    assert_eq!(linked.into_iter().collect::<BTreeSet<_>>(), expected);
+    Ok(())
+}
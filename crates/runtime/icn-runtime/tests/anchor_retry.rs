@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use cid::Cid;
+use icn_identity::KeyPair;
+use icn_runtime::{InMemoryManaLedger, MemStorage, RetryPolicy, Runtime, RuntimeContextBuilder};
+use icn_types::dag::{DagEventType, DagNode};
+use icn_types::dag_store::{DagStore, DagStoreBatch, DagSubscription, SharedDagStore};
+use icn_types::error::DagError;
+use icn_types::runtime_receipt::{RuntimeExecutionMetrics, RuntimeExecutionReceipt};
+use icn_types::VerifiableReceipt;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// In-memory store whose first `failures` inserts fail
+struct FlakyDagStore {
+    inner: SharedDagStore,
+    failures: AtomicU32,
+    insert_attempts: AtomicU32,
+}
+
+impl FlakyDagStore {
+    fn new(failures: u32) -> Self {
+        Self {
+            inner: SharedDagStore::new(),
+            failures: AtomicU32::new(failures),
+            insert_attempts: AtomicU32::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl DagStore for FlakyDagStore {
+    async fn get(&self, id: &str) -> Result<Option<DagNode>, DagError> {
+        self.inner.get(id).await
+    }
+
+    async fn insert(&self, node: DagNode) -> Result<(), DagError> {
+        self.insert_attempts.fetch_add(1, Ordering::SeqCst);
+        let failing = self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if failing {
+            return Err(DagError::Unspecified("backend unavailable".into()));
+        }
+        self.inner.insert(node).await
+    }
+
+    async fn remove(&self, id: &str) -> Result<(), DagError> {
+        self.inner.remove(id).await
+    }
+
+    async fn list(&self) -> Result<Vec<DagNode>, DagError> {
+        self.inner.list().await
+    }
+
+    async fn begin_batch(&self) -> DagStoreBatch {
+        self.inner.begin_batch().await
+    }
+
+    fn subscribe(&self) -> DagSubscription {
+        self.inner.subscribe()
+    }
+}
+
+fn signed_receipt() -> RuntimeExecutionReceipt {
+    let keypair = KeyPair::generate();
+    let mut receipt = RuntimeExecutionReceipt::builder()
+        .id("receipt-1")
+        .issuer(keypair.did.to_string())
+        .wasm_cid("wasm")
+        .ccl_cid("ccl")
+        .metrics(RuntimeExecutionMetrics {
+            host_calls: 1,
+            io_bytes: 0,
+            mana_cost: None,
+        })
+        .timestamp(1)
+        .build()
+        .unwrap();
+    let payload = bincode::serialize(&receipt.get_payload_for_signing().unwrap()).unwrap();
+    receipt.signature = Some(keypair.sign(&payload).into());
+    receipt
+}
+
+fn runtime_over(dag_store: Arc<FlakyDagStore>, max_attempts: u32) -> Runtime<InMemoryManaLedger> {
+    let context = RuntimeContextBuilder::<InMemoryManaLedger>::new()
+        .with_dag_store(dag_store)
+        .build();
+    Runtime::with_context(Arc::new(MemStorage::new()), Arc::new(context)).with_anchor_retry(
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        },
+    )
+}
+
+#[tokio::test]
+async fn insert_is_retried_until_the_receipt_is_anchored() {
+    let dag_store = Arc::new(FlakyDagStore::new(2));
+    let runtime = runtime_over(dag_store.clone(), 3);
+
+    let receipt = signed_receipt();
+    let receipt_cid = runtime.anchor_receipt(&receipt).await.unwrap();
+
+    assert_eq!(receipt_cid, receipt.cid().unwrap().to_string());
+    assert_eq!(dag_store.insert_attempts.load(Ordering::SeqCst), 3);
+    let nodes = dag_store.list().await.unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].event_type, DagEventType::Receipt);
+    let anchored: RuntimeExecutionReceipt = serde_json::from_str(&nodes[0].content).unwrap();
+    assert_eq!(anchored.receipt_cid, Some(receipt_cid.clone()));
+
+    // Once anchored, a retry finds the node without inserting again
+    assert_eq!(runtime.anchor_receipt(&receipt).await.unwrap(), receipt_cid);
+    assert_eq!(dag_store.insert_attempts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn insert_error_is_returned_once_attempts_run_out() {
+    let dag_store = Arc::new(FlakyDagStore::new(5));
+    let runtime = runtime_over(dag_store.clone(), 3);
+
+    let receipt = signed_receipt();
+    let err = runtime.anchor_receipt(&receipt).await.unwrap_err();
+
+    assert!(format!("{:#}", err).contains("backend unavailable"));
+    assert!(format!("{:#}", err).contains(&receipt.cid().unwrap().to_string()));
+    assert_eq!(dag_store.insert_attempts.load(Ordering::SeqCst), 3);
+    assert!(dag_store.list().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn verification_failures_are_not_retried() {
+    let dag_store = Arc::new(FlakyDagStore::new(0));
+    let runtime = runtime_over(dag_store.clone(), 3);
+    let mut receipt = signed_receipt();
+    receipt.signature = None;
+
+    assert!(runtime.anchor_receipt(&receipt).await.is_err());
+    assert_eq!(dag_store.insert_attempts.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,63 @@
+use icn_economics::mana::InMemoryManaLedger;
+use icn_identity::KeyPair;
+use icn_runtime::{execute_mesh_job, MemStorage, RuntimeContextBuilder, RuntimeStorage};
+use icn_types::mesh::{MeshJob, MeshJobParams};
+use std::sync::Arc;
+
+fn noop_module() -> Vec<u8> {
+    wat::parse_str(
+        r#"
+        (module
+          (import "icn" "log" (func (param i32 i32)))
+          (import "icn" "anchor" (func (param i32 i32)))
+          (import "icn" "check_auth" (func (param i32 i32 i64) (result i32)))
+          (import "icn" "record_usage" (func (param i32 i32 i64)))
+          (import "icn" "submit_job"
+            (func (param i32 i32 i32 i32 i32 i32 i64 i32 i32) (result i32)))
+          (func (export "_start")))
+        "#,
+    )
+    .unwrap()
+}
+
+async fn run(deterministic: bool) -> bool {
+    let executor = KeyPair::generate();
+    let job = MeshJob {
+        job_id: "job".to_string(),
+        params: MeshJobParams {
+            wasm_cid: "noop".to_string(),
+            explicit_mana_cost: Some(0),
+            deterministic,
+            ..Default::default()
+        },
+        originator_did: executor.did.clone(),
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    };
+    let ctx = Arc::new(RuntimeContextBuilder::<InMemoryManaLedger>::new().build());
+    let storage = MemStorage::new();
+    storage.store_wasm("noop", &noop_module()).await.unwrap();
+
+    execute_mesh_job(job, &executor, ctx, &storage)
+        .await
+        .unwrap()
+        .deterministic
+}
+
+#[tokio::test]
+async fn receipt_records_the_execution_mode() {
+    assert!(run(true).await);
+    assert!(!run(false).await);
+}
+
+#[test]
+fn params_without_the_flag_default_to_fast_execution() {
+    let mut json = serde_json::to_value(MeshJobParams::default()).unwrap();
+    json.as_object_mut().unwrap().remove("deterministic");
+    let params: MeshJobParams = serde_json::from_value(json).unwrap();
+    assert!(!params.deterministic);
+}
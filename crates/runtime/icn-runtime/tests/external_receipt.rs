@@ -0,0 +1,83 @@
+mod helpers;
+
+use anyhow::Result;
+use icn_economics::mana::InMemoryManaLedger;
+use icn_identity::KeyPair;
+use icn_runtime::{MemStorage, Runtime, RuntimeContextBuilder, RuntimeError};
+use icn_types::dag_store::{DagStore, SharedDagStore};
+use icn_types::runtime_receipt::{RuntimeExecutionMetrics, RuntimeExecutionReceipt};
+use icn_types::VerifiableReceipt;
+use std::sync::Arc;
+
+use helpers::{create_trust_bundle, generate_signers};
+
+fn local_runtime() -> (Runtime<InMemoryManaLedger>, Arc<SharedDagStore>) {
+    let keypair = KeyPair::generate();
+    let dag_store = Arc::new(SharedDagStore::new());
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new()
+        .with_executor_id(keypair.did.to_string())
+        .with_identity(keypair)
+        .with_dag_store(dag_store.clone())
+        .build();
+    (Runtime::with_context(Arc::new(MemStorage::new()), Arc::new(ctx)), dag_store)
+}
+
+fn signed_receipt(issuer: &KeyPair) -> Result<RuntimeExecutionReceipt> {
+    let mut receipt = RuntimeExecutionReceipt::builder()
+        .id("external-receipt")
+        .issuer(issuer.did.to_string())
+        .proposal_id("remote-proposal")
+        .wasm_cid("remote-wasm")
+        .ccl_cid("remote-ccl")
+        .metrics(RuntimeExecutionMetrics {
+            host_calls: 1,
+            io_bytes: 0,
+            mana_cost: Some(5),
+        })
+        .timestamp(1_700_000_000)
+        .build()?;
+    let payload = bincode::serialize(&receipt.get_payload_for_signing()?)?;
+    receipt.signature = Some(issuer.sign(&payload).into());
+    Ok(receipt)
+}
+
+#[tokio::test]
+async fn imports_receipt_signed_by_bundle_member() -> Result<()> {
+    let (runtime, dag_store) = local_runtime();
+    let signers = generate_signers(3);
+    let bundle = create_trust_bundle(&signers, "federation-b", None)?;
+    let receipt = signed_receipt(&signers[0])?;
+
+    let local_cid = runtime
+        .verify_and_import_external_receipt(&receipt, &bundle)
+        .await?;
+
+    let nodes = dag_store.list().await?;
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].cid()?.to_string(), local_cid);
+
+    let content: serde_json::Value = serde_json::from_str(&nodes[0].content)?;
+    assert_eq!(content["issuer"], receipt.issuer);
+    assert_eq!(content["external_origin"]["federation"], "federation-b");
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_issuer_outside_bundle() -> Result<()> {
+    let (runtime, dag_store) = local_runtime();
+    let bundle = create_trust_bundle(&generate_signers(3), "federation-b", None)?;
+    let outsider = KeyPair::generate();
+    let receipt = signed_receipt(&outsider)?;
+
+    let err = runtime
+        .verify_and_import_external_receipt(&receipt, &bundle)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<RuntimeError>(),
+        Some(RuntimeError::UntrustedExternalIssuer { .. })
+    ));
+    assert!(dag_store.list().await?.is_empty());
+    Ok(())
+}
@@ -271,6 +271,7 @@ async fn bootstrap_genesis_replay() -> Result<()> {
         name: "Genesis Federation".to_string(),
         description: Some("A test federation for genesis and replay".to_string()),
         version: "1.0".to_string(),
+        max_token_supply: None,
         additional: HashMap::new(),
     };
 
@@ -376,6 +377,7 @@ async fn test_bootstrap_federation_and_execute() -> Result<()> {
         ccl_cid: "test-ccl-cid-for-proposal".to_string(),
         state: icn_runtime::ProposalState::Approved,
         quorum_status: icn_runtime::QuorumStatus::MajorityReached,
+        compilation_manifest: None,
     };
 
     // ... existing code ...
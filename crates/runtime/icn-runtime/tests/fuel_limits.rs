@@ -0,0 +1,86 @@
+use icn_core_vm::ResourceLimits;
+use icn_runtime::{
+    InMemoryManaLedger, MemStorage, Runtime, RuntimeContextBuilder, RuntimeError, VmContext,
+};
+use std::sync::Arc;
+
+/// A module with the host imports the CoVm supplies, in its order, and `start`
+/// as the body of `_start`
+fn module(start: &str) -> Vec<u8> {
+    wat::parse_str(format!(
+        r#"
+        (module
+          (import "icn" "log" (func (param i32 i32)))
+          (import "icn" "anchor" (func (param i32 i32)))
+          (import "icn" "check_auth" (func (param i32 i32 i64) (result i32)))
+          (import "icn" "record_usage" (func (param i32 i32 i64)))
+          (import "icn" "submit_job"
+            (func (param i32 i32 i32 i32 i32 i32 i64 i32 i32) (result i32)))
+          (func (export "_start") {start}))
+        "#
+    ))
+    .unwrap()
+}
+
+fn vm_context(resource_limits: Option<ResourceLimits>) -> VmContext {
+    VmContext {
+        executor_did: "did:icn:test-executor".to_string(),
+        scope: None,
+        epoch: None,
+        code_cid: None,
+        resource_limits,
+        coop_id: None,
+        community_id: None,
+    }
+}
+
+fn runtime() -> Runtime<InMemoryManaLedger> {
+    let context = RuntimeContextBuilder::<InMemoryManaLedger>::new().build();
+    Runtime::with_context(Arc::new(MemStorage::new()), Arc::new(context))
+}
+
+#[tokio::test]
+async fn tight_loop_exhausts_per_execution_fuel() {
+    let wasm = module("(loop $spin (br $spin))");
+    let limits = ResourceLimits {
+        max_fuel: 1_000,
+        ..Default::default()
+    };
+
+    let err = runtime()
+        .governance_execute_wasm(&wasm, vm_context(Some(limits)))
+        .await
+        .unwrap_err();
+    match err {
+        RuntimeError::Execution(msg) => assert!(msg.contains("fuel exhausted"), "{}", msg),
+        other => panic!("expected a fuel error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn context_limits_set_the_fuel_budget() {
+    let wasm = module(
+        "(local i32)
+         (loop $count
+           (local.set 0 (i32.add (local.get 0) (i32.const 1)))
+           (br_if $count (i32.lt_u (local.get 0) (i32.const 1000))))",
+    );
+    let mut runtime = runtime();
+
+    // The default budget covers a thousand iterations; a tiny one does not
+    let result = runtime
+        .governance_execute_wasm(&wasm, vm_context(None))
+        .await
+        .unwrap();
+    assert!(result.metrics.fuel_used > 100);
+
+    let limits = ResourceLimits {
+        max_fuel: 100,
+        ..Default::default()
+    };
+    let err = runtime
+        .governance_execute_wasm(&wasm, vm_context(Some(limits)))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("fuel exhausted"), "{}", err);
+}
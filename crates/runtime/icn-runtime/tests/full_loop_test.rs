@@ -179,7 +179,7 @@ async fn full_runtime_loop_executes_and_anchors_job() -> anyhow::Result<()> {
 
     // 4. Create job and inject into queue
     let job_originator_keypair = IcnKeyPair::generate();
-    let job_originator_did = job_originator_keypair.did;
+    let job_originator_did = job_originator_keypair.did.clone();
 
     let job_params = MeshJobParams {
         wasm_cid: wasm_cid.clone(),
@@ -195,9 +195,11 @@ async fn full_runtime_loop_executes_and_anchors_job() -> anyhow::Result<()> {
         expected_output_schema_cid: None,
         execution_policy: None,
         explicit_mana_cost: None, // Added missing field
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
 
-    let job = MeshJob {
+    let mut job = MeshJob {
         job_id: Uuid::new_v4().to_string(),
         originator_did: job_originator_did.clone(), // Use clone
         params: job_params,
@@ -206,7 +208,12 @@ async fn full_runtime_loop_executes_and_anchors_job() -> anyhow::Result<()> {
             community_id: None,
         }),
         submission_timestamp: chrono::Utc::now().timestamp_millis() as u64, // Cast to u64
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
+    job.sign(&job_originator_keypair).unwrap();
 
     {
         let mut queue = runtime.context().pending_mesh_jobs.lock().unwrap();
@@ -283,7 +290,7 @@ async fn test_full_runtime_loop_with_mem_storage() -> anyhow::Result<()> {
     // --- Use public RuntimeContextBuilder ---
     let mut context_builder = RuntimeContextBuilder::<InMemoryManaLedger>::new(); // Added generic type
     context_builder = context_builder
-        .with_identity(keypair)
+        .with_identity(keypair.clone())
         .with_executor_id(executor_did.to_string());
     // Add a default ManaRegenerator
     let mana_ledger_for_loop = Arc::new(InMemoryManaLedger::new());
@@ -316,10 +323,12 @@ async fn test_full_runtime_loop_with_mem_storage() -> anyhow::Result<()> {
         expected_output_schema_cid: None,
         execution_policy: None,
         explicit_mana_cost: None, // Added missing field
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
 
     // --- Corrected MeshJob initialization ---
-    let job = MeshJob {
+    let mut job = MeshJob {
         job_id: job_id.clone(),
         params,
         originator_did: job_originator_did.clone(),
@@ -328,7 +337,12 @@ async fn test_full_runtime_loop_with_mem_storage() -> anyhow::Result<()> {
             community_id: None,
         }),
         submission_timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
+    job.sign(&keypair).unwrap();
     // --------------------------------------
 
     runtime_context
@@ -417,7 +431,7 @@ async fn test_reputation_mana_pipeline() -> anyhow::Result<()> {
 
     // 4. Create job with mana_cost
     let job_originator_keypair = IcnKeyPair::generate();
-    let job_originator_did = job_originator_keypair.did;
+    let job_originator_did = job_originator_keypair.did.clone();
 
     let mana_to_cost = 75u64;
 
@@ -435,9 +449,11 @@ async fn test_reputation_mana_pipeline() -> anyhow::Result<()> {
         expected_output_schema_cid: None,
         execution_policy: None,
         explicit_mana_cost: Some(mana_to_cost), // Set explicit mana cost
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
 
-    let job = MeshJob {
+    let mut job = MeshJob {
         job_id: Uuid::new_v4().to_string(),
         originator_did: job_originator_did.clone(),
         params: job_params,
@@ -446,7 +462,12 @@ async fn test_reputation_mana_pipeline() -> anyhow::Result<()> {
             community_id: Some(CommunityId::new("test-community".to_string())), // Corrected type
         }),
         submission_timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
     };
+    job.sign(&job_originator_keypair).unwrap();
 
     // Push job to queue
     runtime
@@ -0,0 +1,141 @@
+#![cfg(feature = "full_host_abi")]
+use icn_core_vm::ResourceLimits;
+use icn_economics::mint_authorization_payload;
+use icn_identity::{KeyPair, QuorumProof, QuorumType, TrustValidator};
+use icn_runtime::host_environment::ConcreteHostEnvironment;
+use icn_runtime::job_execution_context::JobExecutionContext;
+use icn_runtime::{
+    InMemoryManaLedger, MemStorage, Runtime, RuntimeContextBuilder, RuntimeError, VmContext,
+};
+use icn_types::mesh::MeshJobParams;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const CODE_CID: &str = "bafy-governance-minutes";
+
+fn vm_context() -> VmContext {
+    limited_vm_context(None)
+}
+
+fn limited_vm_context(resource_limits: Option<ResourceLimits>) -> VmContext {
+    VmContext {
+        executor_did: KeyPair::generate().did.to_string(),
+        scope: None,
+        epoch: None,
+        code_cid: Some(CODE_CID.to_string()),
+        resource_limits,
+        coop_id: None,
+        community_id: None,
+    }
+}
+
+/// A runtime whose governance executions may mint, approved by a federation quorum
+fn authorized_runtime() -> Runtime<InMemoryManaLedger> {
+    let signers: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+    let validator = Arc::new(TrustValidator::new());
+    for kp in &signers {
+        validator.register_signer(kp.did.clone(), kp.pk);
+    }
+    let rt = Arc::new(
+        RuntimeContextBuilder::new()
+            .with_trust_validator(validator)
+            .build(),
+    );
+
+    let payload = mint_authorization_payload(CODE_CID);
+    let approval = QuorumProof::new(
+        QuorumType::Majority,
+        signers[..2]
+            .iter()
+            .map(|kp| (kp.did.clone(), kp.sign(&payload)))
+            .collect(),
+    );
+    // Each execution swaps in its own job context; this one is only a placeholder
+    let template_did = KeyPair::generate().did;
+    let template_job = JobExecutionContext::new(
+        "template".to_string(),
+        template_did.clone(),
+        MeshJobParams::default(),
+        template_did.clone(),
+        0,
+    );
+    let env = ConcreteHostEnvironment::<()>::new_governance(
+        Arc::new(Mutex::new(template_job)),
+        template_did,
+        rt.clone(),
+    )
+    .with_mint_authorization(approval);
+
+    Runtime::with_context(Arc::new(MemStorage::new()), rt).with_host_environment(env)
+}
+
+#[tokio::test]
+async fn governance_module_anchors_and_mints() {
+    let recipient = KeyPair::generate().did.to_string();
+    let wasm = wat::parse_str(format!(
+        r#"
+        (module
+          (import "icn_host_new" "host_anchor_data"
+            (func $anchor (param i32 i32 i32 i32) (result i32)))
+          (import "icn_host_new" "host_mint_token"
+            (func $mint (param i32 i32 i64 i32 i32 i32 i32) (result i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "governance/minutes")
+          (data (i32.const 32) "bafy-minutes")
+          (data (i32.const 64) "receipt")
+          (data (i32.const 128) "{recipient}")
+          (func (export "_start")
+            (drop (call $anchor (i32.const 0) (i32.const 18) (i32.const 32) (i32.const 12)))
+            (drop (call $mint
+              (i32.const 64) (i32.const 7) (i64.const 5)
+              (i32.const 128) (i32.const {recipient_len})
+              (i32.const 0) (i32.const 0)))))
+        "#,
+        recipient = recipient,
+        recipient_len = recipient.len(),
+    ))
+    .unwrap();
+
+    let result = authorized_runtime()
+        .governance_execute_wasm(&wasm, vm_context())
+        .await
+        .unwrap();
+
+    assert_eq!(result.anchored_cids, ["bafy-minutes"]);
+    assert_eq!(result.metrics.anchored_cids_count, 1);
+    assert!(result.metrics.fuel_used > 0);
+    assert!(result.resource_usage.is_empty());
+    assert!(result
+        .logs
+        .contains(&format!("minted 5 receipt to {}", recipient)));
+}
+
+#[tokio::test]
+async fn trapping_module_fails_with_execution_error() {
+    let wasm = wat::parse_str(r#"(module (func (export "_start") unreachable))"#).unwrap();
+
+    let err = authorized_runtime()
+        .governance_execute_wasm(&wasm, vm_context())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RuntimeError::Execution(_)));
+}
+
+#[tokio::test]
+async fn looping_module_runs_out_of_fuel() {
+    let wasm =
+        wat::parse_str(r#"(module (func (export "_start") (loop $spin (br $spin))))"#).unwrap();
+    let limits = ResourceLimits {
+        max_fuel: 1_000,
+        ..ResourceLimits::default()
+    };
+
+    let err = authorized_runtime()
+        .governance_execute_wasm(&wasm, limited_vm_context(Some(limits)))
+        .await
+        .unwrap_err();
+    match err {
+        RuntimeError::Execution(msg) => assert!(msg.contains("fuel exhausted"), "{}", msg),
+        other => panic!("expected a fuel error, got {:?}", other),
+    }
+}
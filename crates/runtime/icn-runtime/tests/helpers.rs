@@ -30,6 +30,7 @@ pub fn create_trust_bundle(
         name: name.to_string(),
         description: description.map(String::from),
         version: "1.0".to_string(),
+        max_token_supply: None,
         additional: HashMap::new(),
     };
 
@@ -0,0 +1,67 @@
+use host_abi::HostAbiError;
+use icn_identity::KeyPair;
+use icn_runtime::host_environment::ConcreteHostEnvironment;
+use icn_runtime::job_execution_context::JobExecutionContext;
+use icn_runtime::{HostCapabilities, HostCapability, RuntimeContextBuilder};
+use icn_types::mesh::MeshJobParams;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+fn job() -> Arc<Mutex<JobExecutionContext>> {
+    Arc::new(Mutex::new(JobExecutionContext::new(
+        "job:capabilities".to_string(),
+        KeyPair::generate().did,
+        MeshJobParams::default(),
+        KeyPair::generate().did,
+        0,
+    )))
+}
+
+#[test]
+fn compute_environment_is_least_privilege() {
+    let env = ConcreteHostEnvironment::<()>::builder(
+        job(),
+        KeyPair::generate().did,
+        Arc::new(RuntimeContextBuilder::new().build()),
+    )
+    .capabilities(HostCapabilities::compute())
+    .build();
+
+    assert_eq!(env.require_capability(HostCapability::Logging), Ok(()));
+    assert_eq!(
+        env.require_capability(HostCapability::ResourceRecording),
+        Ok(())
+    );
+    for denied in [
+        HostCapability::Minting,
+        HostCapability::TokenTransfer,
+        HostCapability::Governance,
+        HostCapability::DataAnchoring,
+    ] {
+        assert!(matches!(
+            env.require_capability(denied),
+            Err(HostAbiError::Unauthorized(_))
+        ));
+    }
+}
+
+#[test]
+fn builder_starts_without_capabilities() {
+    let env = ConcreteHostEnvironment::<()>::builder(
+        job(),
+        KeyPair::generate().did,
+        Arc::new(RuntimeContextBuilder::new().build()),
+    )
+    .enable(HostCapability::Logging)
+    .governance()
+    .build();
+
+    assert!(env.is_governance);
+    assert_eq!(
+        env.capabilities,
+        HostCapabilities::none().with(HostCapability::Logging)
+    );
+    assert!(HostCapability::ALL
+        .into_iter()
+        .all(|cap| HostCapabilities::default().contains(cap)));
+}
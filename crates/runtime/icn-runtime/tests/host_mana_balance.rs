@@ -0,0 +1,99 @@
+#![cfg(feature = "full_host_abi")]
+use icn_economics::mana::InMemoryManaLedger;
+use icn_identity::KeyPair;
+use icn_runtime::host_environment::ConcreteHostEnvironment;
+use icn_runtime::job_execution_context::JobExecutionContext;
+use icn_runtime::wasm::register_host_functions;
+use icn_runtime::{HostCapability, RuntimeContextBuilder};
+use icn_types::mana::ManaState;
+use icn_types::mesh::MeshJobParams;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use wasmtime::{Config, Engine, Linker, Module, Store};
+
+/// Returns 1 if the DID at `ptr`/`len` holds at least 100 mana, 0 otherwise.
+const BRANCH_ON_BALANCE_WAT: &str = r#"
+(module
+  (import "icn_host_new" "host_get_mana_balance"
+    (func $get_mana_balance (param i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "can_afford") (param $ptr i32) (param $len i32) (result i32)
+    local.get $ptr
+    local.get $len
+    i32.const 512
+    call $get_mana_balance
+    drop
+    i32.const 512
+    i64.load
+    i64.const 100
+    i64.ge_u
+    if (result i32)
+      i32.const 1
+    else
+      i32.const 0
+    end))
+"#;
+
+/// Run `can_afford` for `did` against a ledger where `rich` holds 250 mana
+async fn can_afford(rich: &icn_identity::Did, did: &str) -> anyhow::Result<i32> {
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    ledger
+        .set_initial_state(
+            rich.clone(),
+            ManaState {
+                current_mana: 250,
+                max_mana: 1000,
+                regen_rate_per_epoch: 0.0,
+                last_updated_epoch: 0,
+            },
+        )
+        .await;
+    let context = RuntimeContextBuilder::<InMemoryManaLedger>::new().build_with_ledger(ledger);
+    let job = JobExecutionContext::new(
+        "job:mana-balance".to_string(),
+        rich.clone(),
+        MeshJobParams::default(),
+        KeyPair::generate().did,
+        0,
+    );
+    let env = ConcreteHostEnvironment::<()>::builder(
+        Arc::new(Mutex::new(job)),
+        rich.clone(),
+        Arc::new(context),
+    )
+    .enable(HostCapability::ResourceRecording)
+    .build();
+
+    let engine = Engine::new(Config::new().async_support(true))?;
+    let module = Module::new(&engine, BRANCH_ON_BALANCE_WAT)?;
+    let mut linker = Linker::new(&engine);
+    register_host_functions(&mut linker)?;
+    let mut store = Store::new(&engine, env);
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .expect("memory export");
+    memory.write(&mut store, 0, did.as_bytes())?;
+    let can_afford = instance.get_typed_func::<(i32, i32), i32>(&mut store, "can_afford")?;
+    can_afford
+        .call_async(&mut store, (0, did.len() as i32))
+        .await
+}
+
+#[tokio::test]
+async fn contract_branches_on_seeded_mana_balance() {
+    let rich = KeyPair::generate().did;
+    let unknown = KeyPair::generate().did;
+
+    assert_eq!(can_afford(&rich, &rich.to_string()).await.unwrap(), 1);
+    assert_eq!(can_afford(&rich, &unknown.to_string()).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn malformed_did_traps() {
+    let rich = KeyPair::generate().did;
+
+    let err = can_afford(&rich, "not-a-did").await.unwrap_err();
+    assert!(format!("{:?}", err).contains("Malformed DID"));
+}
@@ -0,0 +1,134 @@
+use icn_core_vm::ResourceLimits;
+use icn_economics::mana::InMemoryManaLedger;
+use icn_identity::KeyPair;
+use icn_runtime::RuntimeContextBuilder;
+use icn_types::mesh::{MeshJob, MeshJobParams};
+use icn_types::JobFailureReason;
+
+fn max_depth() -> u32 {
+    ResourceLimits::default().max_submission_depth
+}
+
+fn job(job_id: &str, parent: Option<&str>) -> MeshJob {
+    MeshJob {
+        job_id: job_id.to_string(),
+        params: MeshJobParams::default(),
+        originator_did: KeyPair::generate().did,
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: parent.map(str::to_string),
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    }
+}
+
+#[test]
+fn lineage_walks_up_to_the_root_job() {
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new().build();
+
+    ctx.enqueue_spawned_job(job("child", Some("root")), max_depth()).unwrap();
+    ctx.enqueue_spawned_job(job("grandchild", Some("child")), max_depth()).unwrap();
+    ctx.enqueue_spawned_job(job("unrelated", None), max_depth()).unwrap();
+
+    assert_eq!(ctx.job_lineage("grandchild"), vec!["grandchild", "child", "root"]);
+    assert_eq!(ctx.job_lineage("root"), vec!["root"]);
+    assert_eq!(ctx.job_lineage("unrelated"), vec!["unrelated"]);
+    assert_eq!(ctx.pending_mesh_jobs.lock().unwrap().len(), 3);
+    assert_eq!(ctx.submission_depth("grandchild"), 2);
+    assert_eq!(ctx.submission_depth("root"), 0);
+}
+
+#[test]
+fn spawns_past_max_submission_depth_are_refused() {
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new().build();
+
+    ctx.enqueue_spawned_job(job("child", Some("root")), 2).unwrap();
+    ctx.enqueue_spawned_job(job("grandchild", Some("child")), 2).unwrap();
+    assert_eq!(
+        ctx.enqueue_spawned_job(job("great-grandchild", Some("grandchild")), 2),
+        Err(JobFailureReason::ResourceLimitExceeded)
+    );
+
+    assert_eq!(ctx.job_lineage("great-grandchild"), vec!["great-grandchild"]);
+    assert_eq!(ctx.pending_mesh_jobs.lock().unwrap().len(), 2);
+}
+
+fn costing(mut job: MeshJob, mana: u64) -> MeshJob {
+    job.params.explicit_mana_cost = Some(mana);
+    job
+}
+
+#[test]
+fn lineage_budget_caps_spawns_and_retries() {
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new().build();
+    let mut root = costing(job("root", None), 40);
+    root.max_total_mana = Some(100);
+
+    ctx.begin_job_budget(&root);
+    assert_eq!(ctx.remaining_lineage_mana("root"), Some(60));
+
+    ctx.enqueue_spawned_job(costing(job("child", Some("root")), 30), max_depth()).unwrap();
+    ctx.retry_job(costing(job("child", Some("root")), 30)).unwrap();
+    assert_eq!(ctx.remaining_lineage_mana("child"), Some(0));
+
+    assert_eq!(
+        ctx.enqueue_spawned_job(costing(job("grandchild", Some("child")), 1), max_depth()),
+        Err(JobFailureReason::ResourceLimitExceeded)
+    );
+    assert_eq!(
+        ctx.retry_job(root.clone()),
+        Err(JobFailureReason::ResourceLimitExceeded)
+    );
+    assert_eq!(ctx.job_lineage("grandchild"), vec!["grandchild"]);
+    assert_eq!(ctx.pending_mesh_jobs.lock().unwrap().len(), 2);
+
+    // Re-admitting the root (e.g. after a re-poll) does not reset its spend
+    ctx.begin_job_budget(&root);
+    assert_eq!(ctx.remaining_lineage_mana("root"), Some(0));
+}
+
+#[test]
+fn lineage_without_budget_is_unbounded() {
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new().build();
+    let root = costing(job("root", None), u64::MAX);
+
+    ctx.begin_job_budget(&root);
+    ctx.enqueue_spawned_job(costing(job("child", Some("root")), u64::MAX), max_depth()).unwrap();
+    ctx.retry_job(root).unwrap();
+    assert_eq!(ctx.remaining_lineage_mana("child"), None);
+}
+
+#[test]
+fn children_wait_for_their_parent_receipt() {
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new().build();
+
+    ctx.begin_polled_job("root");
+    ctx.enqueue_spawned_job(job("child", Some("root")), max_depth()).unwrap();
+    assert!(ctx.next_queued_job().is_none());
+
+    ctx.record_job_receipt("root", "bafy-root-receipt");
+    ctx.finish_job("root");
+    let child = ctx.next_queued_job().expect("child is released by the root's receipt");
+    assert_eq!(child.origin_receipt_cid.as_deref(), Some("bafy-root-receipt"));
+    assert_eq!(ctx.job_lineage("child"), vec!["child", "root"]);
+
+    // Nothing of the lineage is queued or running any more
+    ctx.finish_job("child");
+    assert!(ctx.job_parents.lock().unwrap().is_empty());
+    assert!(ctx.lineage_jobs.lock().unwrap().is_empty());
+}
+
+#[test]
+fn children_of_a_failed_job_are_dropped() {
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new().build();
+
+    ctx.begin_polled_job("root");
+    ctx.enqueue_spawned_job(job("child", Some("root")), max_depth()).unwrap();
+    ctx.enqueue_spawned_job(job("other-child", Some("root")), max_depth()).unwrap();
+    ctx.finish_job("root");
+
+    assert!(ctx.pending_mesh_jobs.lock().unwrap().is_empty());
+    assert!(ctx.job_parents.lock().unwrap().is_empty());
+    assert!(ctx.lineage_jobs.lock().unwrap().is_empty());
+}
@@ -0,0 +1,100 @@
+use httpmock::prelude::*;
+use icn_identity::KeyPair;
+use icn_runtime::config::RuntimeConfig;
+use icn_runtime::{InMemoryManaLedger, MemStorage, Runtime, RuntimeContextBuilder};
+use icn_types::mesh::{MeshJob, MeshJobParams};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn runtime_polling(base_url: String) -> Runtime<InMemoryManaLedger> {
+    let context = RuntimeContextBuilder::<InMemoryManaLedger>::new()
+        .with_mesh_job_service_url(base_url)
+        .build();
+    Runtime::with_context(Arc::new(MemStorage::default()), Arc::new(context)).with_config(
+        RuntimeConfig {
+            job_poll_interval_seconds: Some(1),
+            job_poll_timeout_seconds: Some(2),
+            job_poll_max_backoff_seconds: Some(3),
+            ..Default::default()
+        },
+    )
+}
+
+fn job() -> MeshJob {
+    MeshJob {
+        job_id: "job-1".to_string(),
+        params: MeshJobParams::default(),
+        originator_did: KeyPair::generate().did,
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    }
+}
+
+#[tokio::test]
+async fn poll_returns_served_job_then_none_on_no_content() {
+    let server = MockServer::start_async().await;
+    let runtime = runtime_polling(server.base_url());
+    let expected = job();
+
+    let served = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/next-job");
+            then.status(200).json_body_obj(&expected);
+        })
+        .await;
+    assert_eq!(runtime.poll_for_job().await, Some(expected.clone()));
+    served.assert_async().await;
+    served.delete_async().await;
+
+    let empty = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/next-job");
+            then.status(204);
+        })
+        .await;
+    assert_eq!(runtime.poll_for_job().await, None);
+    assert_eq!(runtime.poll_for_job().await, None);
+    empty.assert_hits_async(2).await;
+    assert_eq!(runtime.job_poll_delay(), Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn failed_polls_back_off_until_a_poll_succeeds() {
+    let server = MockServer::start_async().await;
+    let runtime = runtime_polling(server.base_url());
+
+    let failing = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/next-job");
+            then.status(500);
+        })
+        .await;
+    assert_eq!(runtime.poll_for_job().await, None);
+    assert_eq!(runtime.job_poll_delay(), Duration::from_secs(2));
+    assert_eq!(runtime.poll_for_job().await, None);
+    assert_eq!(runtime.job_poll_delay(), Duration::from_secs(3));
+    failing.delete_async().await;
+
+    let garbled = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/next-job");
+            then.status(200).body("not a job");
+        })
+        .await;
+    assert_eq!(runtime.poll_for_job().await, None);
+    assert_eq!(runtime.job_poll_delay(), Duration::from_secs(3));
+    garbled.delete_async().await;
+
+    server
+        .mock_async(|when, then| {
+            when.method(GET).path("/next-job");
+            then.status(204);
+        })
+        .await;
+    assert_eq!(runtime.poll_for_job().await, None);
+    assert_eq!(runtime.job_poll_delay(), Duration::from_secs(1));
+}
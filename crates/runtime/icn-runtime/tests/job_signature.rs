@@ -0,0 +1,100 @@
+use icn_identity::KeyPair;
+use icn_runtime::verify_job_signature;
+use icn_types::mesh::{MeshJob, MeshJobParams, OrgScopeIdentifier};
+use icn_types::JobFailureReason;
+
+fn signed_job(originator: &KeyPair) -> MeshJob {
+    let mut job = MeshJob {
+        job_id: "job-1".to_string(),
+        params: MeshJobParams {
+            wasm_cid: "bafy-job".to_string(),
+            explicit_mana_cost: Some(10),
+            ..Default::default()
+        },
+        originator_did: originator.did.clone(),
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    };
+    job.sign(originator).unwrap();
+    job
+}
+
+#[test]
+fn job_signed_by_its_originator_is_accepted() {
+    let job = signed_job(&KeyPair::generate());
+    assert_eq!(verify_job_signature(&job), Ok(()));
+}
+
+#[test]
+fn tampered_params_are_rejected() {
+    let mut job = signed_job(&KeyPair::generate());
+    job.params.explicit_mana_cost = Some(1);
+    assert_eq!(
+        verify_job_signature(&job),
+        Err(JobFailureReason::PermissionDenied)
+    );
+
+    let mut job = signed_job(&KeyPair::generate());
+    job.params.wasm_cid = "bafy-other".to_string();
+    assert_eq!(
+        verify_job_signature(&job),
+        Err(JobFailureReason::PermissionDenied)
+    );
+}
+
+#[test]
+fn job_claiming_another_originator_is_rejected() {
+    let mut job = signed_job(&KeyPair::generate());
+    job.originator_did = KeyPair::generate().did;
+    assert_eq!(
+        verify_job_signature(&job),
+        Err(JobFailureReason::PermissionDenied)
+    );
+}
+
+#[test]
+fn unsigned_job_is_rejected() {
+    let mut job = signed_job(&KeyPair::generate());
+    job.originator_signature = None;
+    assert_eq!(
+        verify_job_signature(&job),
+        Err(JobFailureReason::PermissionDenied)
+    );
+}
+
+#[test]
+fn tampered_budget_lineage_time_or_scope_are_rejected() {
+    let tamperings: [fn(&mut MeshJob); 4] = [
+        |job| job.max_total_mana = Some(1_000_000),
+        |job| job.parent_job_id = Some("job-0".to_string()),
+        |job| job.submission_timestamp += 1,
+        |job| job.originator_org_scope = Some(OrgScopeIdentifier::default()),
+    ];
+    for tamper in tamperings {
+        let mut job = signed_job(&KeyPair::generate());
+        tamper(&mut job);
+        assert_eq!(
+            verify_job_signature(&job),
+            Err(JobFailureReason::PermissionDenied)
+        );
+    }
+}
+
+#[test]
+fn resubmitting_the_same_params_later_gets_a_new_id() {
+    let originator = KeyPair::generate().did;
+    let params = MeshJobParams::default();
+    let first = MeshJob::submission_id(&originator, 1, &params).unwrap();
+    assert_eq!(
+        first,
+        MeshJob::submission_id(&originator, 1, &params).unwrap()
+    );
+    assert_ne!(
+        first,
+        MeshJob::submission_id(&originator, 2, &params).unwrap()
+    );
+}
@@ -0,0 +1,159 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use httpmock::prelude::*;
+use icn_identity::KeyPair;
+use icn_mesh_protocol::P2PJobStatus;
+use icn_runtime::config::RuntimeConfig;
+use icn_runtime::{
+    InMemoryManaLedger, JobStatusReporter, MemStorage, Runtime, RuntimeContextBuilder,
+};
+use icn_types::mesh::{MeshJob, MeshJobParams};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, Notify};
+
+/// Captures reports, holding each one until the test releases it
+#[derive(Default)]
+struct CapturingReporter {
+    statuses: Mutex<Vec<(String, P2PJobStatus)>>,
+    reported: Notify,
+    release: Notify,
+}
+
+#[async_trait]
+impl JobStatusReporter for CapturingReporter {
+    async fn report_status(&self, job_id: &str, status: P2PJobStatus) -> Result<()> {
+        self.statuses
+            .lock()
+            .unwrap()
+            .push((job_id.to_string(), status));
+        self.reported.notify_one();
+        self.release.notified().await;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn failed_job_is_reported_once() {
+    let server = MockServer::start_async().await;
+    let reporter = Arc::new(CapturingReporter::default());
+    let context = RuntimeContextBuilder::<InMemoryManaLedger>::new()
+        .with_mesh_job_service_url(server.base_url())
+        .build();
+    // No WASM is stored for the job, so processing it fails
+    let runtime = Runtime::with_context(Arc::new(MemStorage::new()), Arc::new(context))
+        .with_config(RuntimeConfig {
+            node_did: KeyPair::generate().did.to_string(),
+            max_concurrent_jobs: Some(1),
+            job_poll_interval_seconds: Some(1),
+            ..Default::default()
+        })
+        .with_status_reporter(reporter.clone());
+
+    let originator = KeyPair::generate();
+    let mut job = MeshJob {
+        job_id: "job-1".to_string(),
+        params: MeshJobParams {
+            wasm_cid: "bafy-missing".to_string(),
+            ..Default::default()
+        },
+        originator_did: originator.did.clone(),
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    };
+    job.sign(&originator).unwrap();
+    let served = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/next-job");
+            then.status(200).json_body_obj(&job);
+        })
+        .await;
+
+    let (stop, stopped) = oneshot::channel::<()>();
+    let node = tokio::spawn(runtime.run_until(async {
+        let _ = stopped.await;
+    }));
+
+    // The only job slot is held until the report is released, so the job
+    // cannot be polled a second time before it stops being served
+    reporter.reported.notified().await;
+    served.delete_async().await;
+    server
+        .mock_async(|when, then| {
+            when.method(GET).path("/next-job");
+            then.status(204);
+        })
+        .await;
+    reporter.release.notify_one();
+
+    stop.send(()).unwrap();
+    node.await.unwrap().unwrap();
+
+    let statuses = reporter.statuses.lock().unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].0, "job-1");
+    assert!(matches!(statuses[0].1, P2PJobStatus::Failed { .. }));
+}
+
+#[tokio::test]
+async fn locally_queued_child_job_runs_without_a_signature() {
+    let reporter = Arc::new(CapturingReporter::default());
+    // No mesh job service: the only job is the one queued below
+    let context = Arc::new(
+        RuntimeContextBuilder::<InMemoryManaLedger>::new()
+            .with_identity(KeyPair::generate())
+            .build(),
+    );
+    let child = MeshJob {
+        job_id: "job-child".to_string(),
+        params: MeshJobParams {
+            wasm_cid: "bafy-missing".to_string(),
+            ..Default::default()
+        },
+        originator_did: KeyPair::generate().did,
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: Some("job-parent".to_string()),
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    };
+    context.enqueue_spawned_job(child, 8).unwrap();
+    context.record_job_receipt("job-parent", "bafy-parent-receipt");
+    let runtime = Runtime::with_context(Arc::new(MemStorage::new()), context.clone())
+        .with_config(RuntimeConfig {
+            node_did: KeyPair::generate().did.to_string(),
+            max_concurrent_jobs: Some(1),
+            job_poll_interval_seconds: Some(1),
+            ..Default::default()
+        })
+        .with_status_reporter(reporter.clone());
+
+    let (stop, stopped) = oneshot::channel::<()>();
+    let node = tokio::spawn(runtime.run_until(async {
+        let _ = stopped.await;
+    }));
+    reporter.reported.notified().await;
+    reporter.release.notify_one();
+    stop.send(()).unwrap();
+    node.await.unwrap().unwrap();
+
+    // The job got as far as loading its WASM instead of failing the signature check
+    assert!(context.next_queued_job().is_none());
+    let statuses = reporter.statuses.lock().unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].0, "job-child");
+    match &statuses[0].1 {
+        P2PJobStatus::Failed { reason, .. } => {
+            assert!(
+                reason.to_string().contains("Failed to load WASM"),
+                "{}",
+                reason
+            )
+        }
+        status => panic!("unexpected status {:?}", status),
+    }
+}
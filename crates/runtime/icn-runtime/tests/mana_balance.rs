@@ -0,0 +1,131 @@
+use icn_economics::mana::InMemoryManaLedger;
+use icn_economics::ResourceType;
+use icn_identity::KeyPair;
+use icn_runtime::{
+    ManaLedger, ManaRegenerator, MemStorage, RegenerationPolicy, Runtime, RuntimeContextBuilder,
+};
+use icn_types::clock::MockClock;
+use icn_types::mana::ManaState;
+use std::sync::Arc;
+
+fn runtime_over(
+    ledger: Arc<InMemoryManaLedger>,
+    regenerator: Option<ManaRegenerator<InMemoryManaLedger>>,
+) -> Runtime<InMemoryManaLedger> {
+    let mut builder = RuntimeContextBuilder::<InMemoryManaLedger>::new();
+    if let Some(regenerator) = regenerator {
+        builder = builder.with_mana_regenerator(Arc::new(regenerator));
+    }
+    let context = builder.build_with_ledger(ledger);
+    Runtime::with_context(Arc::new(MemStorage::new()), Arc::new(context))
+}
+
+fn state(current_mana: u64, last_updated_epoch: u64) -> ManaState {
+    ManaState {
+        current_mana,
+        max_mana: 100,
+        regen_rate_per_epoch: 0.0,
+        last_updated_epoch,
+    }
+}
+
+#[tokio::test]
+async fn reports_seeded_balance_and_zero_for_unknown_dids() {
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let did = KeyPair::generate().did;
+    ledger
+        .set_initial_state(
+            did.clone(),
+            ManaState {
+                current_mana: 420,
+                max_mana: 1000,
+                regen_rate_per_epoch: 0.0,
+                last_updated_epoch: 0,
+            },
+        )
+        .await;
+    let runtime = runtime_over(ledger, None);
+
+    assert_eq!(runtime.mana_balance(&did, None).await.unwrap(), 420);
+    assert_eq!(
+        runtime
+            .mana_balance(&KeyPair::generate().did, None)
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        runtime
+            .mana_balance(&did, Some(ResourceType::Cpu))
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn projects_elapsed_time_regeneration_without_storing_it() {
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let did = KeyPair::generate().did;
+    ledger
+        .set_initial_state(did.clone(), state(50, 1_000))
+        .await;
+    let clock = MockClock::at_epoch(1_010);
+    let regenerator = ManaRegenerator::with_clock(
+        ledger.clone(),
+        RegenerationPolicy::ElapsedTime {
+            rate_per_second: 2.0,
+        },
+        Arc::new(clock.clone()),
+    );
+    let runtime = runtime_over(ledger.clone(), Some(regenerator));
+
+    assert_eq!(runtime.mana_balance(&did, None).await.unwrap(), 70);
+    assert_eq!(runtime.mana_balance(&did, None).await.unwrap(), 70);
+    // Reading the balance leaves the stored state for the regenerator to advance
+    assert_eq!(
+        ledger.get_mana_state(&did).await.unwrap().unwrap(),
+        state(50, 1_000)
+    );
+
+    // Regeneration saturates at the cap
+    clock.advance_secs(100);
+    assert_eq!(runtime.mana_balance(&did, None).await.unwrap(), 100);
+}
+
+#[tokio::test]
+async fn per_tick_policies_report_the_stored_pool_balance() {
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let did = KeyPair::generate().did;
+    ledger
+        .update_pool_state(&did, ResourceType::Cpu, state(30, 1_000))
+        .await
+        .unwrap();
+    let clock = MockClock::at_epoch(1_010);
+    let regenerator = ManaRegenerator::with_clock(
+        ledger.clone(),
+        RegenerationPolicy::ElapsedTime {
+            rate_per_second: 2.0,
+        },
+        Arc::new(clock.clone()),
+    );
+    regenerator.set_pool_policy(ResourceType::Cpu, RegenerationPolicy::FixedRatePerTick(10));
+    let runtime = runtime_over(ledger, Some(regenerator));
+
+    // The CPU pool only grows when a tick runs, however much time has passed
+    assert_eq!(
+        runtime
+            .mana_balance(&did, Some(ResourceType::Cpu))
+            .await
+            .unwrap(),
+        30
+    );
+    runtime.tick_mana().await.unwrap();
+    assert_eq!(
+        runtime
+            .mana_balance(&did, Some(ResourceType::Cpu))
+            .await
+            .unwrap(),
+        40
+    );
+}
@@ -0,0 +1,79 @@
+use icn_economics::mana::InMemoryManaLedger;
+use icn_identity::{Did, KeyPair};
+use icn_runtime::RuntimeContextBuilder;
+use icn_types::mana::ManaState;
+use icn_types::mesh::{MeshJob, MeshJobParams};
+use icn_types::JobFailureReason;
+use std::sync::Arc;
+
+fn job(job_id: &str, originator: &Did, mana: u64) -> MeshJob {
+    MeshJob {
+        job_id: job_id.to_string(),
+        params: MeshJobParams {
+            explicit_mana_cost: Some(mana),
+            ..Default::default()
+        },
+        originator_did: originator.clone(),
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    }
+}
+
+#[tokio::test]
+async fn concurrent_reservations_cannot_overspend_a_balance() {
+    let ledger = Arc::new(InMemoryManaLedger::new());
+    let originator = KeyPair::generate().did;
+    ledger
+        .set_initial_state(
+            originator.clone(),
+            ManaState {
+                current_mana: 100,
+                max_mana: 100,
+                regen_rate_per_epoch: 0.0,
+                last_updated_epoch: 0,
+            },
+        )
+        .await;
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new().build_with_ledger(ledger);
+
+    let first = ctx
+        .reserve_job_mana(&job("a", &originator, 60))
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.reserve_job_mana(&job("b", &originator, 60))
+            .await
+            .unwrap_err(),
+        JobFailureReason::ResourceLimitExceeded
+    );
+    let second = ctx
+        .reserve_job_mana(&job("c", &originator, 40))
+        .await
+        .unwrap();
+    assert_eq!(ctx.reserved_mana(&originator), 100);
+
+    drop(first);
+    assert_eq!(ctx.reserved_mana(&originator), 40);
+    ctx.reserve_job_mana(&job("b", &originator, 60))
+        .await
+        .unwrap();
+    drop(second);
+}
+
+#[tokio::test]
+async fn originators_without_ledger_state_are_not_metered() {
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new().build();
+    let originator = KeyPair::generate().did;
+
+    let reservation = ctx
+        .reserve_job_mana(&job("a", &originator, u64::MAX))
+        .await
+        .unwrap();
+    assert_eq!(reservation.amount(), u64::MAX);
+    drop(reservation);
+    assert_eq!(ctx.reserved_mana(&originator), 0);
+}
@@ -0,0 +1,155 @@
+use icn_economics::mana::InMemoryManaLedger;
+use icn_economics::ResourceType;
+use icn_identity::KeyPair;
+use icn_mesh_receipts::ExecutionReceipt;
+use icn_runtime::{execute_mesh_job, MemStorage, Runtime, RuntimeContextBuilder, RuntimeStorage};
+use icn_types::mesh::{JobStatus, MeshJob, MeshJobParams};
+use std::sync::Arc;
+
+/// A module importing the CoVm host functions, with `start` as the body of `_start`
+fn module(start: &str) -> Vec<u8> {
+    wat::parse_str(format!(
+        r#"
+        (module
+          (import "icn" "log" (func $log (param i32 i32)))
+          (import "icn" "anchor" (func $anchor (param i32 i32)))
+          (import "icn" "check_auth" (func (param i32 i32 i64) (result i32)))
+          (import "icn" "record_usage" (func $record_usage (param i32 i32 i64)))
+          (import "icn" "submit_job"
+            (func (param i32 i32 i32 i32 i32 i32 i64 i32 i32) (result i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "job ran")
+          (data (i32.const 16) "bafyresult")
+          (data (i32.const 32) "memory")
+          (func (export "_start") {start}))
+        "#
+    ))
+    .unwrap()
+}
+
+fn job(executor: &KeyPair) -> MeshJob {
+    MeshJob {
+        job_id: "job".to_string(),
+        params: MeshJobParams {
+            wasm_cid: "job-module".to_string(),
+            explicit_mana_cost: Some(5),
+            ..Default::default()
+        },
+        originator_did: executor.did.clone(),
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    }
+}
+
+async fn run(wasm: Option<Vec<u8>>) -> anyhow::Result<ExecutionReceipt> {
+    let executor = KeyPair::generate();
+    let ctx = Arc::new(RuntimeContextBuilder::<InMemoryManaLedger>::new().build());
+    let storage = MemStorage::new();
+    if let Some(wasm) = wasm {
+        storage.store_wasm("job-module", &wasm).await?;
+    }
+    execute_mesh_job(job(&executor), &executor, ctx, &storage).await
+}
+
+#[tokio::test]
+async fn receipt_reflects_what_the_module_did() {
+    let wasm = module(
+        "(call $log (i32.const 0) (i32.const 7))
+         (call $anchor (i32.const 16) (i32.const 10))
+         (call $record_usage (i32.const 32) (i32.const 6) (i64.const 4096))",
+    );
+
+    let receipt = run(Some(wasm)).await.unwrap();
+
+    assert_eq!(receipt.status, JobStatus::Completed);
+    assert_eq!(receipt.result_data_cid.as_deref(), Some("bafyresult"));
+    // Bytes through the log, anchor and record_usage calls
+    assert_eq!(receipt.resource_usage[&ResourceType::Io], 7 + 10 + 6);
+    assert_eq!(receipt.resource_usage[&ResourceType::Memory], 4096);
+    assert!(receipt.resource_usage.contains_key(&ResourceType::Cpu));
+    assert_eq!(receipt.mana_cost, Some(5));
+}
+
+#[tokio::test]
+async fn logs_round_trip_through_their_cid() {
+    let wasm = module(
+        "(call $log (i32.const 0) (i32.const 7))
+         (call $log (i32.const 16) (i32.const 10))",
+    );
+    let executor = KeyPair::generate();
+    let ctx = Arc::new(RuntimeContextBuilder::<InMemoryManaLedger>::new().build());
+    let storage = MemStorage::new();
+    storage.store_wasm("job-module", &wasm).await.unwrap();
+
+    let receipt = execute_mesh_job(job(&executor), &executor, ctx.clone(), &storage)
+        .await
+        .unwrap();
+    assert!(receipt.logs_cid.is_some());
+
+    let runtime = Runtime::with_context(Arc::new(storage), ctx);
+    assert_eq!(
+        runtime.fetch_logs(&receipt).await.unwrap(),
+        vec!["job ran".to_string(), "bafyresult".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn trap_yields_a_failed_receipt() {
+    let wasm = module("(call $anchor (i32.const 16) (i32.const 10)) unreachable");
+
+    let receipt = run(Some(wasm)).await.unwrap();
+
+    assert_eq!(receipt.status, JobStatus::Failed);
+    assert_eq!(receipt.result_data_cid, None);
+}
+
+#[tokio::test]
+async fn trapped_job_keeps_its_logs() {
+    let wasm = module("(call $log (i32.const 0) (i32.const 7)) unreachable");
+    let executor = KeyPair::generate();
+    let ctx = Arc::new(RuntimeContextBuilder::<InMemoryManaLedger>::new().build());
+    let storage = MemStorage::new();
+    storage.store_wasm("job-module", &wasm).await.unwrap();
+
+    let receipt = execute_mesh_job(job(&executor), &executor, ctx.clone(), &storage)
+        .await
+        .unwrap();
+    assert_eq!(receipt.status, JobStatus::Failed);
+
+    let runtime = Runtime::with_context(Arc::new(storage), ctx);
+    assert_eq!(
+        runtime.fetch_logs(&receipt).await.unwrap(),
+        vec!["job ran".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn running_out_of_fuel_yields_a_failed_receipt() {
+    let wasm = module("(call $log (i32.const 0) (i32.const 7)) (loop $spin (br $spin))");
+    let executor = KeyPair::generate();
+    let ctx = Arc::new(RuntimeContextBuilder::<InMemoryManaLedger>::new().build());
+    let storage = MemStorage::new();
+    storage.store_wasm("job-module", &wasm).await.unwrap();
+
+    let receipt = execute_mesh_job(job(&executor), &executor, ctx.clone(), &storage)
+        .await
+        .unwrap();
+    assert_eq!(receipt.status, JobStatus::Failed);
+    assert!(receipt.resource_usage.contains_key(&ResourceType::Cpu));
+
+    let runtime = Runtime::with_context(Arc::new(storage), ctx);
+    assert_eq!(
+        runtime.fetch_logs(&receipt).await.unwrap(),
+        vec!["job ran".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn missing_module_is_an_error() {
+    let err = run(None).await.unwrap_err();
+    assert!(err.to_string().contains("job-module"), "{}", err);
+}
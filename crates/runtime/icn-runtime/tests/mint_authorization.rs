@@ -0,0 +1,155 @@
+use host_abi::HostAbiError;
+use icn_economics::mint_authorization_payload;
+use icn_identity::{
+    Did, FederationMetadata, KeyPair, QuorumProof, QuorumType, TrustBundle, TrustValidator,
+};
+use icn_runtime::host_environment::ConcreteHostEnvironment;
+use icn_runtime::job_execution_context::JobExecutionContext;
+use icn_runtime::{MemStorage, Runtime, RuntimeContext, RuntimeContextBuilder};
+use icn_types::mesh::MeshJobParams;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+fn federation(signers: &[KeyPair]) -> Arc<RuntimeContext> {
+    let validator = Arc::new(TrustValidator::new());
+    for kp in signers {
+        validator.register_signer(kp.did.clone(), kp.pk);
+    }
+    Arc::new(RuntimeContextBuilder::new().with_trust_validator(validator).build())
+}
+
+fn quorum_over(signers: &[KeyPair], payload: &[u8]) -> QuorumProof {
+    let signatures = signers.iter().map(|kp| (kp.did.clone(), kp.sign(payload))).collect();
+    QuorumProof::new(QuorumType::Majority, signatures)
+}
+
+fn governance_job(execution_id: &str, caller: &Did) -> Arc<Mutex<JobExecutionContext>> {
+    Arc::new(Mutex::new(JobExecutionContext::new(
+        execution_id.to_string(),
+        caller.clone(),
+        MeshJobParams::default(),
+        KeyPair::generate().did,
+        0,
+    )))
+}
+
+#[test]
+fn minting_requires_governance_and_quorum() {
+    let signers: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+    let rt = federation(&signers);
+    let caller = KeyPair::generate().did;
+    let execution_id = "governance:mint".to_string();
+    let ctx = governance_job(&execution_id, &caller);
+    let approval = quorum_over(&signers[..2], &mint_authorization_payload(&execution_id));
+
+    let governance = ConcreteHostEnvironment::<()>::new_governance(ctx.clone(), caller.clone(), rt.clone());
+    assert_eq!(governance.authorize_mint(&execution_id), Err(HostAbiError::NotPermitted));
+
+    let authorized = governance.clone().with_mint_authorization(approval.clone());
+    assert_eq!(authorized.authorize_mint(&execution_id), Ok(()));
+    assert_eq!(authorized.authorize_mint("other-execution"), Err(HostAbiError::NotPermitted));
+
+    let minority = governance
+        .clone()
+        .with_mint_authorization(quorum_over(&signers[..1], &mint_authorization_payload(&execution_id)));
+    assert_eq!(minority.authorize_mint(&execution_id), Err(HostAbiError::NotPermitted));
+
+    let regular = ConcreteHostEnvironment::<()>::new(ctx, caller, rt).with_mint_authorization(approval);
+    assert_eq!(regular.authorize_mint(&execution_id), Err(HostAbiError::NotPermitted));
+}
+
+#[test]
+fn trust_bundle_sets_supply_cap() {
+    let signers: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+    let rt = federation(&signers);
+    let runtime = Runtime::with_context(Arc::new(MemStorage::new()), rt);
+
+    let mut bundle = TrustBundle::new(
+        "bafybeiczsscdsbs7ffqz55asqdf3smv6klcw3gofszvwlyarci47bgf354".to_string(),
+        FederationMetadata {
+            name: "capped".to_string(),
+            description: None,
+            version: "1.0".to_string(),
+            max_token_supply: Some(500),
+            additional: HashMap::new(),
+        },
+    );
+    let hash = bundle.calculate_hash().unwrap();
+    bundle.add_quorum_proof(quorum_over(&signers, &hash));
+
+    runtime.verify_trust_bundle(&bundle).unwrap();
+    assert_eq!(runtime.context().economics.supply_cap(), Some(500));
+}
+
+/// Mints `$amount` of the resource named at offset 0, optionally inside a section,
+/// to the DID written at offset 64.
+#[cfg(feature = "full_host_abi")]
+const MINT_WAT: &str = r#"
+(module
+  (import "icn_host_new" "host_begin_section"
+    (func $begin_section (param i32 i32 i32 i32) (result i32)))
+  (import "icn_host_new" "host_end_section"
+    (func $end_section (result i32)))
+  (import "icn_host_new" "host_mint_token"
+    (func $mint (param i32 i32 i64 i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "token")
+  (data (i32.const 32) "vote")
+  (func (export "mint") (param $amount i64) (param $did_len i32) (param $in_section i32) (result i32)
+    (if (local.get $in_section)
+      (then (drop (call $begin_section (i32.const 32) (i32.const 4) (i32.const 0) (i32.const 0)))))
+    (call $mint (i32.const 0) (i32.const 5) (local.get $amount) (i32.const 64) (local.get $did_len) (i32.const 0) (i32.const 0)))
+  (func (export "end_section") (result i32)
+    (call $end_section)))
+"#;
+
+#[cfg(feature = "full_host_abi")]
+#[tokio::test]
+async fn supply_only_grows_once_a_mint_succeeds() -> anyhow::Result<()> {
+    use icn_economics::ResourceType;
+    use icn_runtime::wasm::register_host_functions;
+    use wasmtime::{Config, Engine, Linker, Module, Store};
+
+    let signers: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+    let rt = federation(&signers);
+    rt.economics.set_supply_cap(Some(100));
+    let caller = KeyPair::generate().did;
+    let execution_id = "governance:mint".to_string();
+    let env = ConcreteHostEnvironment::<()>::new_governance(
+        governance_job(&execution_id, &caller),
+        caller,
+        rt.clone(),
+    )
+    .with_mint_authorization(quorum_over(
+        &signers[..2],
+        &mint_authorization_payload(&execution_id),
+    ));
+
+    let engine = Engine::new(Config::new().async_support(true))?;
+    let module = Module::new(&engine, MINT_WAT)?;
+    let mut linker = Linker::new(&engine);
+    register_host_functions(&mut linker)?;
+    let mut store = Store::new(&engine, env);
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    let recipient = KeyPair::generate().did.to_string();
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .expect("memory export");
+    memory.write(&mut store, 64, recipient.as_bytes())?;
+    let mint = instance.get_typed_func::<(i64, i32, i32), i32>(&mut store, "mint")?;
+    let end_section = instance.get_typed_func::<(), i32>(&mut store, "end_section")?;
+    let did_len = recipient.len() as i32;
+
+    // Minting is not allowed inside a section, so nothing is minted
+    assert!(mint.call_async(&mut store, (10, did_len, 1)).await.is_err());
+    assert_eq!(rt.economics.minted_supply(ResourceType::Token).await?, 0);
+
+    end_section.call_async(&mut store, ()).await?;
+    assert_eq!(mint.call_async(&mut store, (10, did_len, 0)).await?, 0);
+    assert_eq!(rt.economics.minted_supply(ResourceType::Token).await?, 10);
+
+    assert!(mint.call_async(&mut store, (95, did_len, 0)).await.is_err());
+    assert_eq!(rt.economics.minted_supply(ResourceType::Token).await?, 10);
+    Ok(())
+}
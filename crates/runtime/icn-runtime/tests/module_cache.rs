@@ -0,0 +1,62 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use icn_economics::mana::InMemoryManaLedger;
+use icn_runtime::{InMemoryModuleCache, LruModuleCache, MemStorage, ModuleCache, Runtime};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use wasmtime::Module;
+
+/// Counts compilations: `load_module` stores a module only after compiling it
+#[derive(Default)]
+struct CountingCache {
+    inner: InMemoryModuleCache,
+    compilations: AtomicUsize,
+}
+
+#[async_trait]
+impl ModuleCache for CountingCache {
+    async fn get_module(&self, cid: &str) -> Option<Module> {
+        self.inner.get_module(cid).await
+    }
+
+    async fn store_module(&self, cid: &str, module: Module) -> Result<()> {
+        self.compilations.fetch_add(1, Ordering::SeqCst);
+        self.inner.store_module(cid, module).await
+    }
+}
+
+fn runtime_with_cache(cache: Arc<dyn ModuleCache>) -> Result<Runtime<InMemoryManaLedger>> {
+    Ok(Runtime::<InMemoryManaLedger>::new(Arc::new(MemStorage::new()))?.with_module_cache(cache))
+}
+
+#[tokio::test]
+async fn repeated_loads_compile_once() -> Result<()> {
+    let cache = Arc::new(CountingCache::default());
+    let runtime = runtime_with_cache(cache.clone())?;
+    let wasm = wat::parse_str(r#"(module (func (export "_start")))"#)?;
+    let other = wat::parse_str(r#"(module (func (export "run")))"#)?;
+
+    runtime.load_module(&wasm).await?;
+    runtime.load_module(&wasm).await?;
+    assert_eq!(cache.compilations.load(Ordering::SeqCst), 1);
+
+    runtime.load_module(&other).await?;
+    assert_eq!(cache.compilations.load(Ordering::SeqCst), 2);
+
+    // Invalid bytes are never cached
+    assert!(runtime.load_module(b"not wasm").await.is_err());
+    assert_eq!(cache.inner.len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn lru_cache_serves_compiled_modules() -> Result<()> {
+    let cache = Arc::new(LruModuleCache::new(16));
+    let runtime = runtime_with_cache(cache.clone())?;
+    let wasm = wat::parse_str(r#"(module (func (export "_start")))"#)?;
+
+    let first = runtime.load_module(&wasm).await?;
+    let second = runtime.load_module(&wasm).await?;
+    assert!(Module::same(&first, &second));
+    Ok(())
+}
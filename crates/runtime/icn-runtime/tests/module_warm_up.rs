@@ -0,0 +1,56 @@
+use anyhow::Result;
+use icn_economics::mana::InMemoryManaLedger;
+use icn_runtime::{InMemoryModuleCache, MemStorage, Runtime, RuntimeStorage};
+use std::sync::Arc;
+
+const EMPTY_MODULE: &[u8] = b"\0asm\x01\0\0\0";
+
+async fn runtime_with_modules(
+    modules: &[(&str, &[u8])],
+) -> Result<(Runtime<InMemoryManaLedger>, Arc<InMemoryModuleCache>)> {
+    let storage = Arc::new(MemStorage::new());
+    for (cid, bytes) in modules {
+        storage.store_wasm(cid, bytes).await?;
+    }
+    let cache = Arc::new(InMemoryModuleCache::new());
+    let runtime = Runtime::<InMemoryManaLedger>::new(storage)?.with_module_cache(cache.clone());
+    Ok((runtime, cache))
+}
+
+#[tokio::test]
+async fn warm_up_compiles_and_caches_known_modules() -> Result<()> {
+    let (runtime, cache) =
+        runtime_with_modules(&[("contract-a", EMPTY_MODULE), ("contract-b", EMPTY_MODULE)])
+            .await?;
+
+    runtime
+        .warm_up(&["contract-a".to_string(), "contract-b".to_string()])
+        .await?;
+    // Identical bytes share one content-addressed cache entry
+    assert_eq!(cache.len(), 1);
+
+    // Warming up again is a no-op
+    runtime.warm_up(&["contract-a".to_string()]).await?;
+    assert_eq!(cache.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn warm_up_fails_on_missing_or_invalid_modules() -> Result<()> {
+    let (runtime, cache) = runtime_with_modules(&[("broken", b"not wasm")]).await?;
+
+    assert!(runtime.warm_up(&["missing".to_string()]).await.is_err());
+    assert!(runtime.warm_up(&["broken".to_string()]).await.is_err());
+    assert!(cache.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn warm_up_requires_a_module_cache() -> Result<()> {
+    let storage = Arc::new(MemStorage::new());
+    storage.store_wasm("contract", EMPTY_MODULE).await?;
+    let runtime = Runtime::<InMemoryManaLedger>::new(storage)?;
+
+    assert!(runtime.warm_up(&["contract".to_string()]).await.is_err());
+    Ok(())
+}
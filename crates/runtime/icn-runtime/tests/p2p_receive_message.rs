@@ -0,0 +1,125 @@
+#![cfg(feature = "full_host_abi")]
+use host_abi::P2P_RECEIVE_TIMEOUT;
+use icn_identity::KeyPair;
+use icn_runtime::host_environment::ConcreteHostEnvironment;
+use icn_runtime::job_execution_context::JobExecutionContext;
+use icn_runtime::wasm::register_host_functions;
+use icn_runtime::{HostCapability, RuntimeContext, RuntimeContextBuilder};
+use icn_types::mesh::MeshJobParams;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use wasmtime::{Config, Engine, Instance, Linker, Module, Store};
+
+const JOB_ID: &str = "job:p2p:receiver";
+
+/// Receives into a `len`-byte buffer at offset 256 and returns the host's result.
+const RECEIVE_WAT: &str = r#"
+(module
+  (import "icn_host_new" "host_p2p_receive_message"
+    (func $receive (param i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "receive") (param $len i32) (param $timeout_ms i32) (result i32)
+    (call $receive (i32.const 256) (local.get $len) (local.get $timeout_ms))))
+"#;
+
+/// A guest whose P2P inbox is the one `RuntimeContext` opens for `JOB_ID`.
+async fn guest_with_inbox() -> anyhow::Result<(
+    Store<ConcreteHostEnvironment<()>>,
+    Instance,
+    Arc<RuntimeContext>,
+)> {
+    let rt = Arc::new(RuntimeContextBuilder::new().build());
+    let did = KeyPair::generate().did;
+    let job_ctx = JobExecutionContext::new(
+        JOB_ID.to_string(),
+        did.clone(),
+        MeshJobParams::default(),
+        did.clone(),
+        0,
+    );
+    let env =
+        ConcreteHostEnvironment::<()>::builder(Arc::new(Mutex::new(job_ctx)), did, rt.clone())
+            .enable(HostCapability::Interactive)
+            .p2p_inbox(rt.open_p2p_inbox(JOB_ID))
+            .build();
+
+    let engine = Engine::new(Config::new().async_support(true))?;
+    let module = Module::new(&engine, RECEIVE_WAT)?;
+    let mut linker = Linker::new(&engine);
+    register_host_functions(&mut linker)?;
+    let mut store = Store::new(&engine, env);
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    Ok((store, instance, rt))
+}
+
+async fn receive(
+    store: &mut Store<ConcreteHostEnvironment<()>>,
+    instance: &Instance,
+    len: i32,
+    timeout_ms: i32,
+) -> anyhow::Result<i32> {
+    let receive = instance.get_typed_func::<(i32, i32), i32>(&mut *store, "receive")?;
+    Ok(receive.call_async(&mut *store, (len, timeout_ms)).await?)
+}
+
+#[tokio::test]
+async fn delivered_message_is_written_to_guest_buffer() -> anyhow::Result<()> {
+    let (mut store, instance, rt) = guest_with_inbox().await?;
+    rt.deliver_p2p_message(JOB_ID, b"hello mesh".to_vec())
+        .unwrap();
+
+    assert_eq!(receive(&mut store, &instance, 64, 0).await?, 10);
+
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .expect("memory export");
+    let mut received = [0u8; 10];
+    memory.read(&store, 256, &mut received)?;
+    assert_eq!(&received, b"hello mesh");
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_inbox_returns_timeout_status() -> anyhow::Result<()> {
+    let (mut store, instance, _rt) = guest_with_inbox().await?;
+
+    assert_eq!(
+        receive(&mut store, &instance, 64, 0).await?,
+        P2P_RECEIVE_TIMEOUT
+    );
+
+    let started = Instant::now();
+    assert_eq!(
+        receive(&mut store, &instance, 64, 50).await?,
+        P2P_RECEIVE_TIMEOUT
+    );
+    assert!(started.elapsed() >= Duration::from_millis(50));
+    Ok(())
+}
+
+#[tokio::test]
+async fn oversized_message_stays_queued_for_a_larger_buffer() -> anyhow::Result<()> {
+    let (mut store, instance, rt) = guest_with_inbox().await?;
+    rt.deliver_p2p_message(JOB_ID, b"a longer message".to_vec())
+        .unwrap();
+
+    assert_eq!(receive(&mut store, &instance, 4, 0).await?, -16);
+    assert_eq!(receive(&mut store, &instance, 16, 0).await?, 16);
+
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .expect("memory export");
+    let mut received = [0u8; 16];
+    memory.read(&store, 256, &mut received)?;
+    assert_eq!(&received, b"a longer message");
+    Ok(())
+}
+
+#[tokio::test]
+async fn closed_inbox_refuses_deliveries() {
+    let rt = RuntimeContextBuilder::new().build();
+    let _rx = rt.open_p2p_inbox(JOB_ID);
+    rt.close_p2p_inbox(JOB_ID);
+    assert!(rt.deliver_p2p_message(JOB_ID, b"late".to_vec()).is_err());
+}
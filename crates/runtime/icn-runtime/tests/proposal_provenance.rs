@@ -0,0 +1,72 @@
+use anyhow::Result;
+use cid::Cid;
+use icn_economics::mana::InMemoryManaLedger;
+use icn_identity::KeyPair;
+use icn_runtime::{
+    MemStorage, Proposal, ProposalState, QuorumStatus, Runtime, RuntimeContextBuilder,
+    RuntimeError, RuntimeStorage,
+};
+use icn_types::CompilationManifest;
+use multihash::{Code, MultihashDigest};
+use std::sync::Arc;
+
+const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+fn raw_cid(bytes: &[u8]) -> String {
+    Cid::new_v1(0x55, Code::Sha2_256.digest(bytes)).to_string()
+}
+
+async fn runtime_with(proposal: &Proposal, wasm: &[u8]) -> Result<Runtime<InMemoryManaLedger>> {
+    let storage = Arc::new(MemStorage::new());
+    storage.update_proposal(proposal).await?;
+    storage.store_wasm(&proposal.wasm_cid, wasm).await?;
+
+    let keypair = KeyPair::generate();
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new()
+        .with_executor_id(keypair.did.to_string())
+        .with_identity(keypair)
+        .build();
+    Ok(Runtime::with_context(storage, Arc::new(ctx)))
+}
+
+fn approved_proposal(manifest: CompilationManifest) -> Proposal {
+    Proposal {
+        id: "provenance".to_string(),
+        wasm_cid: manifest.wasm_cid.clone(),
+        ccl_cid: manifest.ccl_cid.clone(),
+        state: ProposalState::Approved,
+        quorum_status: QuorumStatus::MajorityReached,
+        compilation_manifest: Some(manifest),
+    }
+}
+
+#[tokio::test]
+async fn executes_when_loaded_wasm_matches_manifest() -> Result<()> {
+    let proposal = approved_proposal(CompilationManifest {
+        compiler_version: "0.1.0".to_string(),
+        ccl_cid: raw_cid(b"proposal source"),
+        wasm_cid: raw_cid(WASM),
+    });
+    let mut runtime = runtime_with(&proposal, WASM).await?;
+
+    runtime.execute_proposal(&proposal.id).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_wasm_that_differs_from_manifest() -> Result<()> {
+    let proposal = approved_proposal(CompilationManifest {
+        compiler_version: "0.1.0".to_string(),
+        ccl_cid: raw_cid(b"proposal source"),
+        wasm_cid: raw_cid(WASM),
+    });
+    // Storage holds different bytes under the recorded CID
+    let mut runtime = runtime_with(&proposal, b"\0asm\x01\0\0\0\0").await?;
+
+    let err = runtime.execute_proposal(&proposal.id).await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<RuntimeError>(),
+        Some(RuntimeError::ProvenanceMismatch(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use icn_runtime::sled_storage::{SledStorage, STORAGE_SCHEMA_VERSION};
+use icn_runtime::{MemStorage, Proposal, ProposalState, QuorumStatus, RuntimeStorage};
+use icn_types::CompilationManifest;
+
+fn proposal(id: &str, state: ProposalState) -> Proposal {
+    Proposal {
+        id: id.to_string(),
+        wasm_cid: format!("wasm-{}", id),
+        ccl_cid: format!("ccl-{}", id),
+        state,
+        quorum_status: QuorumStatus::Pending,
+        compilation_manifest: None,
+    }
+}
+
+fn ids(proposals: &[Proposal]) -> Vec<&str> {
+    proposals.iter().map(|p| p.id.as_str()).collect()
+}
+
+async fn check_state_listing(storage: &dyn RuntimeStorage) -> Result<()> {
+    storage.update_proposal(&proposal("b", ProposalState::Approved)).await?;
+    storage.update_proposal(&proposal("a", ProposalState::Approved)).await?;
+    storage.update_proposal(&proposal("c", ProposalState::Voting)).await?;
+
+    let approved = storage.load_proposals_by_state(ProposalState::Approved).await?;
+    assert_eq!(ids(&approved), vec!["a", "b"]);
+
+    // Moving a proposal to a new state moves it between listings
+    storage.update_proposal(&proposal("a", ProposalState::Executed)).await?;
+    let approved = storage.load_proposals_by_state(ProposalState::Approved).await?;
+    assert_eq!(ids(&approved), vec!["b"]);
+    let executed = storage.load_proposals_by_state(ProposalState::Executed).await?;
+    assert_eq!(ids(&executed), vec!["a"]);
+    assert_eq!(executed[0].state, ProposalState::Executed);
+
+    assert!(storage
+        .load_proposals_by_state(ProposalState::Rejected)
+        .await?
+        .is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn mem_storage_lists_proposals_by_state() -> Result<()> {
+    check_state_listing(&MemStorage::new()).await
+}
+
+#[tokio::test]
+async fn sled_storage_lists_proposals_by_state() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let storage = SledStorage::open(dir.path())?;
+    check_state_listing(&storage).await
+}
+
+#[tokio::test]
+async fn sled_storage_keeps_manifests_and_loads_earlier_proposals() -> Result<()> {
+    #[derive(serde::Serialize)]
+    struct LegacyProposal {
+        id: String,
+        wasm_cid: String,
+        ccl_cid: String,
+        state: ProposalState,
+        quorum_status: QuorumStatus,
+    }
+
+    let dir = tempfile::tempdir()?;
+    {
+        let db = sled::open(dir.path())?;
+        let legacy = LegacyProposal {
+            id: "old".to_string(),
+            wasm_cid: "wasm-old".to_string(),
+            ccl_cid: "ccl-old".to_string(),
+            state: ProposalState::Voting,
+            quorum_status: QuorumStatus::Pending,
+        };
+        db.insert("proposal:old", bincode::serialize(&legacy)?)?;
+        db.flush()?;
+    }
+    let storage = SledStorage::open(dir.path())?;
+    assert_eq!(storage.schema_version()?, STORAGE_SCHEMA_VERSION);
+
+    let old = storage.load_proposal("old").await?;
+    assert_eq!(old.wasm_cid, "wasm-old");
+    assert_eq!(old.state, ProposalState::Voting);
+    assert_eq!(old.compilation_manifest, None);
+
+    // Opening the store indexed it by state, so it is listed
+    let voting = storage
+        .load_proposals_by_state(ProposalState::Voting)
+        .await?;
+    assert_eq!(ids(&voting), vec!["old"]);
+
+    let mut new = proposal("new", ProposalState::Created);
+    new.compilation_manifest = Some(CompilationManifest {
+        compiler_version: "0.1.0".to_string(),
+        ccl_cid: new.ccl_cid.clone(),
+        wasm_cid: new.wasm_cid.clone(),
+    });
+    storage.update_proposal(&new).await?;
+    assert_eq!(
+        storage.load_proposal("new").await?.compilation_manifest,
+        new.compilation_manifest
+    );
+
+    // Updating a legacy record decodes the old bytes and rewrites them
+    let mut old = old;
+    old.state = ProposalState::Approved;
+    storage.update_proposal(&old).await?;
+    assert_eq!(
+        storage.load_proposal("old").await?.state,
+        ProposalState::Approved
+    );
+    Ok(())
+}
@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use chrono::Utc;
 use cid::Cid;
 use icn_economics::mana::InMemoryManaLedger;
-use icn_identity::{Did, KeyPair, ScopeKey};
+use icn_identity::{Did, KeyPair, ScopeKey, TaggedSignature};
 use icn_mesh_receipts::ExecutionReceipt as MeshExecutionReceipt;
 use icn_runtime::{
     Proposal, Runtime, RuntimeContextBuilder, RuntimeStorage, MemStorage,
@@ -129,9 +129,10 @@ async fn test_wasm_anchors_receipt() -> Result<()> {
         execution_start_time: Utc::now().timestamp() as u64,
         execution_end_time: Utc::now().timestamp() as u64,
         execution_end_time_dt: Utc::now(),
-        signature: Vec::new(),
+        signature: TaggedSignature::default(),
         coop_id: None,
         community_id: None,
+        deterministic: false,
     };
     println!("Skipping WASM execution for test_wasm_anchors_receipt for now.");
 
@@ -161,11 +162,14 @@ fn create_test_receipt_with_metrics(
             mana_cost: mana_cost_val,
         },
         anchored_cids: Vec::new(),
+        anchored_cids_root: None,
         resource_usage: Vec::new(),
         timestamp: Utc::now().timestamp_millis() as u64,
         dag_epoch: Some(1),
         receipt_cid: None,
         signature: None,
+        coop_id: None,
+        community_id: None,
     }
 }
 
@@ -187,9 +191,10 @@ fn create_mesh_receipt(
         resource_usage: HashMap::new(),
         mana_cost: mana,
         execution_end_time_dt: Utc::now(),
-        signature: Vec::new(),
+        signature: TaggedSignature::default(),
         coop_id: None,
         community_id: None,
+        deterministic: false,
     }
 }
 
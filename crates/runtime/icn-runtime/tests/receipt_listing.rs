@@ -0,0 +1,65 @@
+use anyhow::Result;
+use icn_runtime::sled_storage::SledStorage;
+use icn_runtime::{MemStorage, RuntimeStorage};
+use icn_types::runtime_receipt::RuntimeExecutionReceipt;
+
+fn receipt(id: &str, timestamp: u64) -> RuntimeExecutionReceipt {
+    RuntimeExecutionReceipt::builder()
+        .id(id)
+        .issuer("did:key:issuer")
+        .wasm_cid("wasm")
+        .ccl_cid("ccl")
+        .timestamp(timestamp)
+        .build()
+        .unwrap()
+}
+
+fn timestamps(receipts: &[RuntimeExecutionReceipt]) -> Vec<u64> {
+    receipts.iter().map(|r| r.timestamp).collect()
+}
+
+fn ids(receipts: &[RuntimeExecutionReceipt]) -> Vec<&str> {
+    receipts.iter().map(|r| r.id.as_str()).collect()
+}
+
+async fn check_receipt_paging(storage: &dyn RuntimeStorage) -> Result<()> {
+    // Store out of timestamp order; 7 is coprime with 25 so every slot is hit
+    for i in 0..25u64 {
+        let timestamp = 1_000 + (i * 7) % 25;
+        storage
+            .store_receipt(&receipt(&format!("r{}", timestamp), timestamp))
+            .await?;
+    }
+
+    let first = storage.list_receipts(0, 10).await?;
+    let second = storage.list_receipts(10, 10).await?;
+    let third = storage.list_receipts(20, 10).await?;
+    assert_eq!(timestamps(&first), (1_000..1_010).collect::<Vec<_>>());
+    assert_eq!(timestamps(&second), (1_010..1_020).collect::<Vec<_>>());
+    assert_eq!(timestamps(&third), (1_020..1_025).collect::<Vec<_>>());
+    assert!(storage.list_receipts(30, 10).await?.is_empty());
+    assert!(storage.list_receipts(0, 0).await?.is_empty());
+
+    // Stable across calls
+    assert_eq!(ids(&storage.list_receipts(10, 10).await?), ids(&second));
+
+    // Re-storing a receipt with a new timestamp moves it, without duplicates
+    storage.store_receipt(&receipt("r1000", 2_000)).await?;
+    let all = storage.list_receipts(0, 100).await?;
+    assert_eq!(all.len(), 25);
+    assert_eq!(all[0].id, "r1001");
+    assert_eq!(all[24].id, "r1000");
+    Ok(())
+}
+
+#[tokio::test]
+async fn mem_storage_pages_receipts_by_timestamp() -> Result<()> {
+    check_receipt_paging(&MemStorage::new()).await
+}
+
+#[tokio::test]
+async fn sled_storage_pages_receipts_by_timestamp() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let storage = SledStorage::open(dir.path())?;
+    check_receipt_paging(&storage).await
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use icn_core_vm::ResourceLimits;
+use icn_identity::{Did, KeyPair};
+use icn_runtime::reputation_integration::ReputationUpdater;
+use icn_runtime::{InMemoryManaLedger, MemStorage, MetricsViolation, Runtime, RuntimeStorage};
+use icn_types::runtime_receipt::{RuntimeExecutionMetrics, RuntimeExecutionReceipt};
+use icn_types::VerifiableReceipt;
+use std::sync::{Arc, Mutex};
+
+/// Records the `is_successful` flag of every receipt-based reputation submission
+#[derive(Default)]
+struct RecordingUpdater {
+    outcomes: Mutex<Vec<bool>>,
+}
+
+#[async_trait]
+impl ReputationUpdater for RecordingUpdater {
+    async fn submit_receipt_based_reputation(
+        &self,
+        _receipt: &RuntimeExecutionReceipt,
+        is_successful: bool,
+        _coop_id: &str,
+        _community_id: &str,
+    ) -> Result<()> {
+        self.outcomes.lock().unwrap().push(is_successful);
+        Ok(())
+    }
+
+    async fn submit_mana_deduction(
+        &self,
+        _executor_did: &Did,
+        _amount: u64,
+        _coop_id: &str,
+        _community_id: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn signed_receipt(host_calls: u64, io_bytes: u64, anchored_cids: Vec<String>) -> RuntimeExecutionReceipt {
+    let keypair = KeyPair::generate();
+    let mut receipt = RuntimeExecutionReceipt::builder()
+        .id(uuid::Uuid::new_v4().to_string())
+        .issuer(keypair.did.to_string())
+        .proposal_id("proposal")
+        .wasm_cid("wasm")
+        .ccl_cid("ccl")
+        .metrics(RuntimeExecutionMetrics {
+            host_calls,
+            io_bytes,
+            mana_cost: None,
+        })
+        .anchored_cids(anchored_cids)
+        .timestamp(1)
+        .build()
+        .unwrap();
+    let payload = bincode::serialize(&receipt.get_payload_for_signing().unwrap()).unwrap();
+    receipt.signature = Some(keypair.sign(&payload).into());
+    receipt
+}
+
+fn runtime(updater: Arc<RecordingUpdater>) -> Runtime<InMemoryManaLedger> {
+    Runtime::new(Arc::new(MemStorage::new()))
+        .unwrap()
+        .with_receipt_limits(ResourceLimits {
+            max_host_calls: 10,
+            max_io_bytes: 100,
+            ..ResourceLimits::default()
+        })
+        .with_reputation_updater(updater)
+}
+
+#[test]
+fn flags_impossible_metrics() {
+    let runtime = runtime(Arc::new(RecordingUpdater::default()));
+    let limits = ResourceLimits {
+        max_host_calls: 10,
+        max_io_bytes: 100,
+        max_anchored_cids: 1,
+        ..ResourceLimits::default()
+    };
+    let check = |receipt| runtime.validate_receipt_metrics(&receipt, &limits);
+
+    assert_eq!(check(signed_receipt(10, 100, vec!["a".into()])), Ok(()));
+    assert_eq!(
+        check(signed_receipt(11, 0, vec![])),
+        Err(MetricsViolation::HostCallsExceeded { reported: 11, limit: 10 })
+    );
+    assert_eq!(
+        check(signed_receipt(1, 101, vec![])),
+        Err(MetricsViolation::IoBytesExceeded { reported: 101, limit: 100 })
+    );
+    assert_eq!(
+        check(signed_receipt(5, 0, vec!["a".into(), "b".into()])),
+        Err(MetricsViolation::AnchoredCidsExceeded { reported: 2, limit: 1 })
+    );
+    assert_eq!(
+        check(signed_receipt(0, 8, vec![])),
+        Err(MetricsViolation::IoWithoutHostCalls { io_bytes: 8 })
+    );
+    assert_eq!(
+        check(signed_receipt(0, 0, vec!["a".into()])),
+        Err(MetricsViolation::MoreAnchorsThanHostCalls { anchored: 1, host_calls: 0 })
+    );
+}
+
+#[tokio::test]
+async fn anchor_refuses_impossible_receipt_and_penalizes_issuer() {
+    let updater = Arc::new(RecordingUpdater::default());
+    let runtime = runtime(updater.clone());
+
+    let err = runtime
+        .anchor_receipt(&signed_receipt(50, 0, vec![]))
+        .await
+        .expect_err("receipt over the host-call limit must not be anchored");
+    assert!(matches!(
+        err.downcast_ref::<MetricsViolation>(),
+        Some(MetricsViolation::HostCallsExceeded { reported: 50, .. })
+    ));
+    assert_eq!(*updater.outcomes.lock().unwrap(), vec![false]);
+
+    runtime.anchor_receipt(&signed_receipt(2, 10, vec![])).await.unwrap();
+    assert_eq!(*updater.outcomes.lock().unwrap(), vec![false, true]);
+}
+
+#[tokio::test]
+async fn compacted_anchors_are_counted_and_resolved_from_storage() {
+    let storage = Arc::new(MemStorage::new());
+    let runtime = Runtime::<InMemoryManaLedger>::new(storage.clone()).unwrap();
+    let cids: Vec<String> = (0..3).map(|i| format!("anchor-{}", i)).collect();
+
+    let mut receipt = signed_receipt(2, 0, cids.clone());
+    let stored = receipt.compact_anchored_cids(1).unwrap();
+    let root = receipt.anchored_cids_root.clone().unwrap().root;
+
+    // The root still commits to three anchors, more than the two host calls
+    assert_eq!(
+        runtime.validate_receipt_metrics(&receipt, &ResourceLimits::default()),
+        Err(MetricsViolation::MoreAnchorsThanHostCalls { anchored: 3, host_calls: 2 })
+    );
+
+    assert!(runtime.resolve_anchored_cids(&receipt).await.is_err());
+    storage.store_anchored_cids(&root, &stored).await.unwrap();
+    assert_eq!(runtime.resolve_anchored_cids(&receipt).await.unwrap(), cids);
+
+    storage.store_anchored_cids(&root, &cids[..2]).await.unwrap();
+    assert!(runtime.resolve_anchored_cids(&receipt).await.is_err());
+}
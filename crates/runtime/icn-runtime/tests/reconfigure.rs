@@ -0,0 +1,66 @@
+use icn_economics::mana::{InMemoryManaLedger, ManaRegenerator, RegenerationPolicy};
+use icn_runtime::config::{FuelPricing, PartialRuntimeConfig};
+use icn_runtime::{MemStorage, Runtime, RuntimeContextBuilder, RuntimeError};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn runtime() -> (Runtime<InMemoryManaLedger>, Arc<ManaRegenerator<InMemoryManaLedger>>) {
+    let regenerator = Arc::new(ManaRegenerator::new(
+        Arc::new(InMemoryManaLedger::default()),
+        RegenerationPolicy::FixedRatePerTick(10),
+    ));
+    let ctx = RuntimeContextBuilder::<InMemoryManaLedger>::new()
+        .with_mana_regenerator(regenerator.clone())
+        .build();
+    (Runtime::with_context(Arc::new(MemStorage::new()), Arc::new(ctx)), regenerator)
+}
+
+#[test]
+fn hot_fields_apply_to_running_instance() {
+    let (runtime, regenerator) = runtime();
+    let handle = runtime.clone();
+    let pricing = FuelPricing {
+        fuel_units_per_mana: 1,
+        mana_per_host_call: 0,
+        mana_per_io_byte: 0,
+    };
+
+    runtime
+        .reconfigure(PartialRuntimeConfig {
+            job_poll_interval_seconds: Some(1),
+            mana_tick_interval_seconds: Some(5),
+            fuel_pricing: Some(pricing.clone()),
+            mana_regeneration_policy: Some(RegenerationPolicy::FixedRatePerTick(25)),
+            ..Default::default()
+        })
+        .unwrap();
+
+    // Clones share the live configuration
+    let config = handle.config();
+    assert_eq!(config.job_poll_interval(), Duration::from_secs(1));
+    assert_eq!(config.fuel_pricing, pricing);
+    assert_eq!(handle.mana_tick_interval(), Some(Duration::from_secs(5)));
+    assert!(matches!(regenerator.policy(), RegenerationPolicy::FixedRatePerTick(25)));
+}
+
+#[test]
+fn immutable_change_rejects_whole_update() {
+    let (runtime, regenerator) = runtime();
+    let before = runtime.config();
+
+    let err = runtime
+        .reconfigure(PartialRuntimeConfig {
+            job_poll_interval_seconds: Some(1),
+            mana_regeneration_policy: Some(RegenerationPolicy::FixedRatePerTick(99)),
+            node_did: Some("did:key:someone-else".to_string()),
+            ..Default::default()
+        })
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<RuntimeError>(),
+        Some(RuntimeError::ImmutableConfigField("node_did"))
+    ));
+    assert_eq!(runtime.config().job_poll_interval(), before.job_poll_interval());
+    assert!(matches!(regenerator.policy(), RegenerationPolicy::FixedRatePerTick(10)));
+}
@@ -2,11 +2,11 @@
 
 use anyhow::{anyhow, Result, Context};
 use icn_runtime::metrics;
-use icn_runtime::reputation_integration::{HttpReputationUpdater, NoopReputationUpdater, ReputationScoringConfig, ReputationUpdater};
+use icn_runtime::reputation_integration::{HttpReputationUpdater, NoopReputationUpdater, ReputationScoringConfig, ReputationUpdater, MAX_RECORD_SUBMISSIONS};
 use chrono::Utc;
 use httpmock::Method::POST;
 use httpmock::MockServer;
-use icn_identity::{Did, KeyPair, KeyPair as IcnKeyPair};
+use icn_identity::{Did, KeyPair, KeyPair as IcnKeyPair, TaggedSignature};
 use icn_runtime::config::RuntimeConfig;
 use icn_runtime::{MemStorage, Runtime, RuntimeContext, RuntimeContextBuilder, RuntimeStorage, InMemoryManaLedger, RegenerationPolicy, ManaRegenerator};
 use icn_mesh_receipts::ExecutionReceipt as MeshExecutionReceipt;
@@ -22,6 +22,7 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use tempfile::{tempdir, NamedTempFile};
+use std::time::Duration;
 use tokio::time::sleep;
 use url::Url;
 use uuid::Uuid;
@@ -113,11 +114,14 @@ async fn test_reputation_submission_on_anchor() -> Result<()> {
             io_bytes: 1024,
         },
         anchored_cids: vec![],
+        anchored_cids_root: None,
         resource_usage: vec![],
         timestamp: 1234567890,
         dag_epoch: Some(1),
         receipt_cid: None,
         signature: None,
+        coop_id: None,
+        community_id: None,
     };
 
     let receipt_cid = runtime.anchor_receipt(&receipt).await?;
@@ -152,11 +156,14 @@ async fn test_reputation_submission_skipped_if_no_updater() -> Result<()> {
             io_bytes: 1024,
         },
         anchored_cids: vec![],
+        anchored_cids_root: None,
         resource_usage: vec![],
         timestamp: 1234567890,
         dag_epoch: Some(1),
         receipt_cid: None,
         signature: None,
+        coop_id: None,
+        community_id: None,
     };
 
     let receipt_cid = runtime.anchor_receipt(&receipt).await?;
@@ -183,7 +190,7 @@ fn sign_receipt(receipt: &mut RuntimeExecutionReceipt, keypair: &IcnKeyPair) ->
     let payload = receipt.get_payload_for_signing()?;
     let bytes = bincode::serialize(&payload).unwrap();
     let signature = keypair.sign(&bytes); // Assumes KeyPair::sign exists
-    receipt.signature = Some(signature.to_bytes().to_vec());
+    receipt.signature = Some(signature.into());
     Ok(())
 }
 
@@ -203,11 +210,14 @@ fn generate_and_sign_dummy_receipt(keypair: &IcnKeyPair) -> Result<RuntimeExecut
             io_bytes: 512,
         },
         anchored_cids: vec!["bafybeidata".to_string()],
+        anchored_cids_root: None,
         resource_usage: vec![("cpu".to_string(), 100)], // Must be Vec<(String, u64)>
         timestamp: Utc::now().timestamp() as u64,
         dag_epoch: Some(42), // Must be Option<u64>
         receipt_cid: None,
         signature: None, // Will be added below
+        coop_id: None,
+        community_id: None,
     };
 
     let payload = receipt.get_payload_for_signing()?;
@@ -216,7 +226,7 @@ fn generate_and_sign_dummy_receipt(keypair: &IcnKeyPair) -> Result<RuntimeExecut
 
     // Assumes KeyPair::sign exists
     let signature = keypair.sign(&bytes);
-    receipt.signature = Some(signature.to_bytes().to_vec());
+    receipt.signature = Some(signature.into());
     Ok(receipt)
 }
 
@@ -240,11 +250,14 @@ async fn test_valid_receipt_sends_to_http_reputation_service() -> Result<()> {
             io_bytes: 1024,
         },
         anchored_cids: vec!["cid1".into()],
+        anchored_cids_root: None,
         resource_usage: vec![("cpu".into(), 100)],
         timestamp: Utc::now().timestamp() as u64,
         dag_epoch: Some(1),
         receipt_cid: Some("receipt-cid-123".into()),
-        signature: Some(vec![1, 2, 3]),
+        signature: Some(TaggedSignature::from_legacy_bytes(vec![1, 2, 3])),
+        coop_id: None,
+        community_id: None,
     };
     let updater = HttpReputationUpdater::new(server.url(""), did.clone());
     updater
@@ -274,11 +287,14 @@ async fn test_reputation_updater_handles_http_500() -> Result<()> {
             io_bytes: 512,
         },
         anchored_cids: vec![],
+        anchored_cids_root: None,
         resource_usage: vec![],
         timestamp: Utc::now().timestamp() as u64,
         dag_epoch: Some(2),
         receipt_cid: Some("receipt-cid-500".into()),
         signature: None,
+        coop_id: None,
+        community_id: None,
     };
     let updater = HttpReputationUpdater::new(server.url(""), did.clone());
     let result = updater
@@ -309,11 +325,14 @@ async fn test_noop_reputation_updater_ignores_submission() -> Result<()> {
             io_bytes: 1,
         },
         anchored_cids: vec![],
+        anchored_cids_root: None,
         resource_usage: vec![],
         timestamp: Utc::now().timestamp() as u64,
         dag_epoch: Some(3),
         receipt_cid: Some("receipt-cid-noop".into()),
         signature: None,
+        coop_id: None,
+        community_id: None,
     };
     let result = updater
         .submit_receipt_based_reputation(&receipt, true, "test_coop_noop", "test_community_noop")
@@ -361,6 +380,7 @@ async fn test_http_reputation_updater_submits_correct_payload() -> Result<()> {
         wasm_cid: "wasm-1".to_string(),
         ccl_cid: "ccl-1".to_string(),
         anchored_cids: vec![],
+        anchored_cids_root: None,
         metrics: RuntimeExecutionMetrics {
             mana_cost: expected_mana_cost,
             host_calls: 5,
@@ -369,9 +389,11 @@ async fn test_http_reputation_updater_submits_correct_payload() -> Result<()> {
         resource_usage: vec![],
         timestamp: expected_timestamp,
         receipt_cid: Some(expected_anchor.clone()),
-        signature: Some(vec![0u8; 64]),
+        signature: Some(TaggedSignature::from_legacy_bytes(vec![0u8; 64])),
         id: "receipt-id-123".to_string(),
         dag_epoch: Some(4),
+        coop_id: None,
+        community_id: None,
     };
 
     let updater = HttpReputationUpdater::new_with_config(
@@ -425,6 +447,7 @@ async fn test_http_reputation_updater_score_capping() -> Result<()> {
         wasm_cid: "wasm-cap".to_string(),
         ccl_cid: "ccl-cap".to_string(),
         anchored_cids: vec![],
+        anchored_cids_root: None,
         metrics: RuntimeExecutionMetrics {
             mana_cost: mana_cost_for_capping,
             host_calls: 1,
@@ -433,9 +456,11 @@ async fn test_http_reputation_updater_score_capping() -> Result<()> {
         resource_usage: vec![],
         timestamp,
         receipt_cid: Some(anchor.clone()),
-        signature: Some(vec![0u8; 64]),
+        signature: Some(TaggedSignature::from_legacy_bytes(vec![0u8; 64])),
         id: "receipt-cap-id".to_string(),
         dag_epoch: Some(6),
+        coop_id: None,
+        community_id: None,
     };
 
     let updater = HttpReputationUpdater::new_with_config(
@@ -483,6 +508,7 @@ async fn test_http_reputation_updater_submits_failure_penalty() -> Result<()> {
         wasm_cid: "wasm-fail".to_string(),
         ccl_cid: "ccl-fail".to_string(),
         anchored_cids: vec![],
+        anchored_cids_root: None,
         metrics: RuntimeExecutionMetrics {
             mana_cost: Some(1000),
             host_calls: 2,
@@ -491,9 +517,11 @@ async fn test_http_reputation_updater_submits_failure_penalty() -> Result<()> {
         resource_usage: vec![],
         timestamp,
         receipt_cid: Some(anchor.clone()),
-        signature: Some(vec![0u8; 64]),
+        signature: Some(TaggedSignature::from_legacy_bytes(vec![0u8; 64])),
         id: "receipt-fail-id".to_string(),
         dag_epoch: Some(5),
+        coop_id: None,
+        community_id: None,
     };
     let updater =
         HttpReputationUpdater::new_with_config(server.url(""), Did::from_str(&subject)?, config);
@@ -567,10 +595,11 @@ async fn test_mesh_receipt_signature_verification_and_submission() -> Result<()>
         execution_start_time: now_ts.saturating_sub(1),
         execution_end_time: now_ts,
         execution_end_time_dt: now_dt,
-        signature: vec![], // Will be filled after signing
+        signature: TaggedSignature::default(), // Will be filled after signing
         coop_id: None,
         community_id: None,
         mana_cost: None, // Added missing field
+        deterministic: false,
     };
 
     let payload = receipt
@@ -579,7 +608,7 @@ async fn test_mesh_receipt_signature_verification_and_submission() -> Result<()>
     let bytes =
         bincode::serialize(&payload).expect("Failed to serialize MeshExecutionReceipt payload");
     let sig = keypair_for_receipt_issuer.sign(&bytes); // Sign with receipt issuer's keypair
-    receipt.signature = sig.to_bytes().to_vec();
+    receipt.signature = sig.into();
 
     // 4. Submit to anchor_mesh_receipt
     runtime.anchor_mesh_receipt(&receipt).await?; // Pass by reference
@@ -603,3 +632,178 @@ mod tests_from_original_reputation_integration_rs_file {
     // ... paste tests from the original reputation_integration.rs here if they were separate
     // For now, assuming they are already in the main body of this test file.
 }
+
+#[tokio::test]
+async fn test_batched_updates_flush_in_one_request() -> Result<()> {
+    let server = MockServer::start();
+    let batch_mock = server.mock(|when, then| {
+        when.method(POST).path("/reputation/records/batch");
+        then.status(200).json_body(json!({
+            "outcomes": [
+                { "index": 0, "error": null },
+                { "index": 1, "error": "duplicate anchor" },
+                { "index": 2, "error": null }
+            ]
+        }));
+    });
+    let single_mock = server.mock(|when, then| {
+        when.method(POST).path("/reputation/records");
+        then.status(200);
+    });
+    let did = Did::from_str("did:key:z6MkpTHR8VNsESGeQGSwQy1VBCLeP2g2rM86Zbf3pt12345")?;
+    let updater = HttpReputationUpdater::new(server.url(""), did.clone()).with_batch_size(3);
+
+    for i in 0..3 {
+        let receipt = RuntimeExecutionReceipt {
+            id: format!("batched-receipt-{}", i),
+            issuer: did.to_string(),
+            proposal_id: "batched-proposal".into(),
+            wasm_cid: "batched-wasm".into(),
+            ccl_cid: "batched-ccl".into(),
+            metrics: RuntimeExecutionMetrics {
+                mana_cost: Some(100),
+                host_calls: 1,
+                io_bytes: 0,
+            },
+            anchored_cids: vec![],
+            anchored_cids_root: None,
+            resource_usage: vec![],
+            timestamp: Utc::now().timestamp() as u64,
+            dag_epoch: None,
+            receipt_cid: Some(format!("batched-cid-{}", i)),
+            signature: None,
+            coop_id: None,
+            community_id: None,
+        };
+        updater
+            .submit_receipt_based_reputation(&receipt, true, "test_coop", "test_community")
+            .await?;
+        if i < 2 {
+            assert_eq!(updater.pending_records().await, i + 1);
+        }
+    }
+
+    // The third record filled the batch and triggered a single request
+    batch_mock.assert_hits(1);
+    single_mock.assert_hits(0);
+    assert_eq!(updater.pending_records().await, 0);
+
+    // A flush with nothing queued sends nothing
+    assert!(updater.flush().await?.is_empty());
+    batch_mock.assert_hits(1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flush_reports_rejected_records_and_requeues_on_failure() -> Result<()> {
+    let server = MockServer::start();
+    let did = Did::from_str("did:key:z6MkpTHR8VNsESGeQGSwQy1VBCLeP2g2rM86Zbf3pt12345")?;
+    let updater = HttpReputationUpdater::new(server.url(""), did.clone()).with_batch_size(10);
+    let receipt = |i: u32| RuntimeExecutionReceipt {
+        id: format!("flush-receipt-{}", i),
+        issuer: did.to_string(),
+        proposal_id: "flush-proposal".into(),
+        wasm_cid: "flush-wasm".into(),
+        ccl_cid: "flush-ccl".into(),
+        metrics: RuntimeExecutionMetrics {
+            mana_cost: Some(10),
+            host_calls: 1,
+            io_bytes: 0,
+        },
+        anchored_cids: vec![],
+        anchored_cids_root: None,
+        resource_usage: vec![],
+        timestamp: Utc::now().timestamp() as u64,
+        dag_epoch: None,
+        receipt_cid: Some(format!("flush-cid-{}", i)),
+        signature: None,
+        coop_id: None,
+        community_id: None,
+    };
+    for i in 0..2 {
+        updater
+            .submit_receipt_based_reputation(&receipt(i), true, "test_coop", "test_community")
+            .await?;
+    }
+
+    let mut unavailable = server.mock(|when, then| {
+        when.method(POST).path("/reputation/records/batch");
+        then.status(503);
+    });
+    assert!(updater.flush().await.is_err());
+    assert_eq!(updater.pending_records().await, 2);
+    unavailable.delete();
+
+    let mut partly_rejected = server.mock(|when, then| {
+        when.method(POST).path("/reputation/records/batch");
+        then.status(200).json_body(json!({
+            "outcomes": [
+                { "index": 0, "error": null },
+                { "index": 1, "error": "subject unknown" }
+            ]
+        }));
+    });
+    let rejected = updater.flush().await?;
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].0.anchor, "flush-cid-1");
+    assert_eq!(rejected[0].1, "subject unknown");
+    // The rejected record is retried with the next flush
+    assert_eq!(updater.pending_records().await, 1);
+    partly_rejected.delete();
+
+    let rejecting = server.mock(|when, then| {
+        when.method(POST).path("/reputation/records/batch");
+        then.status(200).json_body(json!({
+            "outcomes": [{ "index": 0, "error": "subject unknown" }]
+        }));
+    });
+    for _ in 1..MAX_RECORD_SUBMISSIONS {
+        assert_eq!(updater.flush().await?.len(), 1);
+    }
+    // ...until it has been rejected MAX_RECORD_SUBMISSIONS times
+    rejecting.assert_hits(MAX_RECORD_SUBMISSIONS as usize - 1);
+    assert_eq!(updater.pending_records().await, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flush_task_sends_partial_batches() -> Result<()> {
+    let server = MockServer::start();
+    let batch_mock = server.mock(|when, then| {
+        when.method(POST).path("/reputation/records/batch");
+        then.status(200).json_body(json!({ "outcomes": [{ "index": 0, "error": null }] }));
+    });
+    let did = Did::from_str("did:key:z6MkpTHR8VNsESGeQGSwQy1VBCLeP2g2rM86Zbf3pt12345")?;
+    let updater = Arc::new(HttpReputationUpdater::new(server.url(""), did.clone()).with_batch_size(10));
+    let receipt = RuntimeExecutionReceipt {
+        id: "timer-receipt".into(),
+        issuer: did.to_string(),
+        proposal_id: "timer-proposal".into(),
+        wasm_cid: "timer-wasm".into(),
+        ccl_cid: "timer-ccl".into(),
+        metrics: RuntimeExecutionMetrics {
+            mana_cost: Some(10),
+            host_calls: 1,
+            io_bytes: 0,
+        },
+        anchored_cids: vec![],
+        resource_usage: vec![],
+        timestamp: Utc::now().timestamp() as u64,
+        dag_epoch: None,
+        receipt_cid: Some("timer-cid".into()),
+        signature: None,
+        coop_id: None,
+        community_id: None,
+    };
+    updater
+        .submit_receipt_based_reputation(&receipt, true, "test_coop", "test_community")
+        .await?;
+
+    let flush_task = updater.clone().spawn_flush_task(Duration::from_millis(50));
+    sleep(Duration::from_millis(300)).await;
+    flush_task.abort();
+
+    batch_mock.assert_hits(1);
+    assert_eq!(updater.pending_records().await, 0);
+    Ok(())
+}
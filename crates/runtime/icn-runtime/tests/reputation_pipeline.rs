@@ -32,11 +32,14 @@ fn create_basic_receipt(
             mana_cost,
         },
         anchored_cids: vec![],
+        anchored_cids_root: None,
         resource_usage: vec![],
         timestamp: 1234567890,
         dag_epoch: Some(1),
         receipt_cid: None,
         signature: None, // Signature will be added later if needed by the test
+        coop_id: None,
+        community_id: None,
     }
 }
 
@@ -48,7 +51,7 @@ fn sign_receipt(receipt: &mut RuntimeExecutionReceipt, keypair: &KeyPair) {
     let bytes_to_sign = bincode::serialize(&payload_struct)
         .expect("Failed to serialize payload for signing in helper");
     let signature = keypair.sign(&bytes_to_sign);
-    receipt.signature = Some(signature.to_bytes().to_vec());
+    receipt.signature = Some(signature.into());
 }
 
 #[tokio::test]
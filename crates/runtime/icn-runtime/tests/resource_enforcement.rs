@@ -39,6 +39,7 @@ impl icn_runtime::RuntimeStorage for MockStorage {
             ccl_cid: "mock_ccl_cid".into(),
             state: ProposalState::Approved,
             quorum_status: QuorumStatus::MajorityReached,
+            compilation_manifest: None,
         })
     }
 
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use icn_identity::{KeyPair, TaggedSignature};
+use icn_runtime::sled_storage::{SledStorage, STORAGE_SCHEMA_VERSION};
+use icn_runtime::RuntimeStorage;
+use icn_types::org::CooperativeId;
+use icn_types::runtime_receipt::{RuntimeExecutionMetrics, RuntimeExecutionReceipt};
+use serde::Serialize;
+
+fn receipt(id: &str, anchored: usize) -> RuntimeExecutionReceipt {
+    RuntimeExecutionReceipt::builder()
+        .id(id)
+        .issuer("did:key:issuer")
+        .wasm_cid("wasm")
+        .ccl_cid("ccl")
+        .anchored_cids((0..anchored).map(|i| format!("bafy-{}", i)).collect())
+        .timestamp(1_000)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn compacted_signed_receipt_round_trips_through_sled() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let storage = SledStorage::open(dir.path())?;
+
+    let mut stored = receipt("r1", 8);
+    stored.coop_id = Some(CooperativeId::new("coop-a"));
+    stored.signature = Some(KeyPair::generate().sign(b"payload").into());
+    let cids = stored
+        .compact_anchored_cids(4)
+        .expect("list is over the limit");
+    let root = stored.anchored_cids_root.clone().unwrap();
+    storage.store_anchored_cids(&root.root, &cids).await?;
+    storage.store_receipt(&stored).await?;
+
+    let loaded = storage.load_receipt("r1").await?;
+    assert_eq!(loaded.anchored_cids_root, Some(root.clone()));
+    assert!(loaded.anchored_cids.is_empty());
+    assert_eq!(loaded.signature, stored.signature);
+    assert_eq!(loaded.coop_id, stored.coop_id);
+    assert_eq!(loaded.community_id, None);
+    let loaded_cids = storage.load_anchored_cids(&root.root).await?;
+    assert_eq!(loaded_cids, cids);
+    assert!(loaded.verify_anchored_cids(&loaded_cids).is_ok());
+
+    // Re-storing replaces the record rather than failing to decode the old one
+    storage.store_receipt(&loaded).await?;
+    assert_eq!(storage.list_receipts(0, 10).await?.len(), 1);
+    Ok(())
+}
+
+/// Receipt record layout written by earlier releases
+#[derive(Serialize)]
+struct LegacyReceipt {
+    id: String,
+    issuer: String,
+    proposal_id: String,
+    wasm_cid: String,
+    ccl_cid: String,
+    metrics: RuntimeExecutionMetrics,
+    anchored_cids: Vec<String>,
+    resource_usage: Vec<(String, u64)>,
+    timestamp: u64,
+    dag_epoch: Option<u64>,
+    receipt_cid: Option<String>,
+    signature: Option<Vec<u8>>,
+}
+
+#[tokio::test]
+async fn receipts_stored_by_earlier_releases_still_load() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let legacy = LegacyReceipt {
+        id: "old".to_string(),
+        issuer: "did:key:issuer".to_string(),
+        proposal_id: "proposal".to_string(),
+        wasm_cid: "wasm".to_string(),
+        ccl_cid: "ccl".to_string(),
+        metrics: RuntimeExecutionMetrics::default(),
+        anchored_cids: vec!["bafy-0".to_string()],
+        resource_usage: vec![("cpu".to_string(), 5)],
+        timestamp: 42,
+        dag_epoch: Some(3),
+        receipt_cid: None,
+        signature: Some(vec![7; 64]),
+    };
+    {
+        let db = sled::open(dir.path())?;
+        db.insert("receipt:old", bincode::serialize(&legacy)?)?;
+        db.flush()?;
+    }
+
+    let storage = SledStorage::open(dir.path())?;
+    assert_eq!(storage.schema_version()?, STORAGE_SCHEMA_VERSION);
+    let loaded = storage.load_receipt("old").await?;
+    assert_eq!(loaded.anchored_cids, legacy.anchored_cids);
+    assert_eq!(loaded.anchored_cids_root, None);
+    assert_eq!(loaded.resource_usage, legacy.resource_usage);
+    assert_eq!(loaded.dag_epoch, Some(3));
+    assert_eq!(
+        loaded.signature,
+        Some(TaggedSignature::from_legacy_bytes(vec![7; 64]))
+    );
+
+    // Opening the store indexed it by time, so it is listed
+    assert_eq!(storage.list_receipts(0, 10).await?, vec![loaded.clone()]);
+
+    // Storing it again rewrites it in the current format, without a second index entry
+    storage.store_receipt(&loaded).await?;
+    assert_eq!(storage.load_receipt("old").await?.timestamp, 42);
+    assert_eq!(storage.list_receipts(0, 10).await?.len(), 1);
+    Ok(())
+}
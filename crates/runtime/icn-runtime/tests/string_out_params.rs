@@ -0,0 +1,116 @@
+#![cfg(feature = "full_host_abi")]
+use icn_identity::KeyPair;
+use icn_runtime::host_environment::ConcreteHostEnvironment;
+use icn_runtime::job_execution_context::JobExecutionContext;
+use icn_runtime::wasm::register_host_functions;
+use icn_runtime::RuntimeContextBuilder;
+use icn_types::mesh::{MeshJobParams, StageDefinition, StageInputSource, WorkflowType};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use wasmtime::{Config, Engine, Instance, Linker, Module, Store};
+
+/// Exposes the string-returning host functions with a caller-chosen buffer at offset 1024.
+const STRINGS_WAT: &str = r#"
+(module
+  (import "icn_host_new" "host_submit_mesh_job"
+    (func $submit (param i32 i32 i32 i32) (result i32)))
+  (import "icn_host" "host_workflow_get_current_stage_id"
+    (func $stage_id (param i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "submit") (param $ptr i32) (param $len i32) (param $buf_len i32) (result i32)
+    (call $submit (local.get $ptr) (local.get $len) (i32.const 1024) (local.get $buf_len)))
+  (func (export "stage_id") (param $buf_len i32) (result i32)
+    (call $stage_id (i32.const 1024) (local.get $buf_len))))
+"#;
+
+async fn guest(
+    job_params: MeshJobParams,
+) -> anyhow::Result<(
+    Store<ConcreteHostEnvironment<()>>,
+    Instance,
+    Arc<Mutex<JobExecutionContext>>,
+)> {
+    let did = KeyPair::generate().did;
+    let ctx = Arc::new(Mutex::new(JobExecutionContext::new(
+        "job:strings".to_string(),
+        did.clone(),
+        job_params,
+        KeyPair::generate().did,
+        0,
+    )));
+    let rt = Arc::new(RuntimeContextBuilder::new().build());
+    let env = ConcreteHostEnvironment::<()>::new(ctx.clone(), did, rt);
+
+    let engine = Engine::new(Config::new().async_support(true))?;
+    let module = Module::new(&engine, STRINGS_WAT)?;
+    let mut linker = Linker::new(&engine);
+    register_host_functions(&mut linker)?;
+    let mut store = Store::new(&engine, env);
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    Ok((store, instance, ctx))
+}
+
+/// The `len` bytes written at offset 1024 and the byte after them.
+fn read_c_string(
+    store: &mut Store<ConcreteHostEnvironment<()>>,
+    instance: &Instance,
+    len: usize,
+) -> Vec<u8> {
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .expect("memory export");
+    let mut bytes = vec![0u8; len + 1];
+    memory.read(&*store, 1024, &mut bytes).unwrap();
+    bytes
+}
+
+#[tokio::test]
+async fn job_id_that_does_not_fit_reports_its_size_and_submits_nothing() -> anyhow::Result<()> {
+    let (mut store, instance, ctx) = guest(MeshJobParams::default()).await?;
+    let payload = serde_cbor::to_vec(&MeshJobParams::default())?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .expect("memory export");
+    memory.write(&mut store, 0, &payload)?;
+    let submit = instance.get_typed_func::<(i32, i32, i32), i32>(&mut store, "submit")?;
+
+    // "job-" followed by a UUID, plus the null terminator
+    let needed = submit
+        .call_async(&mut store, (0, payload.len() as i32, 0))
+        .await?;
+    assert_eq!(needed, -41);
+    assert_eq!(ctx.lock().await.job_submissions_count, 0);
+
+    let written = submit
+        .call_async(&mut store, (0, payload.len() as i32, -needed))
+        .await?;
+    assert_eq!(written, 40);
+    assert_eq!(ctx.lock().await.job_submissions_count, 1);
+    let job_id = read_c_string(&mut store, &instance, 40);
+    assert!(job_id.starts_with(b"job-"));
+    assert_eq!(job_id[40], 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn stage_id_reports_required_size_then_fits() -> anyhow::Result<()> {
+    let job_params = MeshJobParams {
+        workflow_type: WorkflowType::SequentialPipeline,
+        stages: Some(vec![StageDefinition {
+            stage_id: "extract".to_string(),
+            description: "first stage".to_string(),
+            wasm_cid: "wasm-cid".to_string(),
+            input_source: StageInputSource::NoInput,
+            resources_required: None,
+            deadline: None,
+        }]),
+        ..MeshJobParams::default()
+    };
+    let (mut store, instance, _ctx) = guest(job_params).await?;
+    let stage_id = instance.get_typed_func::<i32, i32>(&mut store, "stage_id")?;
+
+    assert_eq!(stage_id.call_async(&mut store, 7).await?, -8);
+    assert_eq!(stage_id.call_async(&mut store, 8).await?, 7);
+    assert_eq!(read_c_string(&mut store, &instance, 7), b"extract\0");
+    Ok(())
+}
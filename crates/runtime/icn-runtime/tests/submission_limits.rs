@@ -0,0 +1,184 @@
+#![cfg(feature = "full_host_abi")]
+use icn_core_vm::ResourceLimits;
+use icn_identity::KeyPair;
+use icn_runtime::host_environment::ConcreteHostEnvironment;
+use icn_runtime::job_execution_context::JobExecutionContext;
+use icn_runtime::wasm::register_host_functions;
+use icn_runtime::{RuntimeContext, RuntimeContextBuilder};
+use icn_types::mesh::{MeshJob, MeshJobParams};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use wasmtime::{Config, Engine, Linker, Module, Store};
+
+/// Submits the CBOR job params at `ptr`/`len` three times in a row.
+const SUBMIT_THRICE_WAT: &str = r#"
+(module
+  (import "icn_host_new" "host_submit_mesh_job"
+    (func $submit (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "submit_thrice") (param $ptr i32) (param $len i32)
+    (drop (call $submit (local.get $ptr) (local.get $len) (i32.const 1024) (i32.const 128)))
+    (drop (call $submit (local.get $ptr) (local.get $len) (i32.const 1024) (i32.const 128)))
+    (drop (call $submit (local.get $ptr) (local.get $len) (i32.const 1024) (i32.const 128)))))
+"#;
+
+/// Run `submit_thrice` as job `job_id` of `rt`, `submission_depth` deep under
+/// `limits`, returning the guest's error, if any, and the job's context afterwards
+async fn submit_thrice(
+    rt: Arc<RuntimeContext>,
+    job_id: &str,
+    submission_depth: u32,
+    limits: ResourceLimits,
+) -> (anyhow::Result<()>, Arc<Mutex<JobExecutionContext>>) {
+    submit_thrice_after(rt, job_id, submission_depth, limits, Vec::new()).await
+}
+
+/// Like [`submit_thrice`], with `already_spawned` waiting in the job's context
+/// to be handed to the runtime queue
+async fn submit_thrice_after(
+    rt: Arc<RuntimeContext>,
+    job_id: &str,
+    submission_depth: u32,
+    limits: ResourceLimits,
+    already_spawned: Vec<MeshJob>,
+) -> (anyhow::Result<()>, Arc<Mutex<JobExecutionContext>>) {
+    let originator = KeyPair::generate().did;
+    let mut job = JobExecutionContext::new(
+        job_id.to_string(),
+        originator.clone(),
+        MeshJobParams::default(),
+        KeyPair::generate().did,
+        0,
+    );
+    job.submission_depth = submission_depth;
+    job.resource_limits = limits;
+    job.spawned_jobs = already_spawned;
+    let ctx = Arc::new(Mutex::new(job));
+    let env = ConcreteHostEnvironment::<()>::new(ctx.clone(), originator, rt);
+
+    let engine = Engine::new(Config::new().async_support(true)).unwrap();
+    let module = Module::new(&engine, SUBMIT_THRICE_WAT).unwrap();
+    let mut linker = Linker::new(&engine);
+    register_host_functions(&mut linker).unwrap();
+    let mut store = Store::new(&engine, env);
+    let instance = linker.instantiate_async(&mut store, &module).await.unwrap();
+
+    let payload = serde_cbor::to_vec(&MeshJobParams::default()).unwrap();
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .expect("memory export");
+    memory.write(&mut store, 0, &payload).unwrap();
+    let submit = instance
+        .get_typed_func::<(i32, i32), ()>(&mut store, "submit_thrice")
+        .unwrap();
+    let result = submit
+        .call_async(&mut store, (0, payload.len() as i32))
+        .await;
+    (result, ctx)
+}
+
+fn runtime_context() -> Arc<RuntimeContext> {
+    Arc::new(RuntimeContextBuilder::new().build())
+}
+
+fn spawned(job_id: &str, parent: &str) -> MeshJob {
+    MeshJob {
+        job_id: job_id.to_string(),
+        params: MeshJobParams::default(),
+        originator_did: KeyPair::generate().did,
+        originator_org_scope: None,
+        submission_timestamp: 0,
+        parent_job_id: Some(parent.to_string()),
+        origin_receipt_cid: None,
+        max_total_mana: None,
+        originator_signature: None,
+    }
+}
+
+#[tokio::test]
+async fn job_at_max_depth_cannot_submit() {
+    let limits = ResourceLimits {
+        max_submission_depth: 2,
+        ..ResourceLimits::default()
+    };
+
+    let (result, ctx) = submit_thrice(runtime_context(), "job", 1, limits.clone()).await;
+    assert!(result.is_ok());
+    assert_eq!(ctx.lock().await.job_submissions_count, 3);
+
+    let (result, ctx) = submit_thrice(runtime_context(), "job", 2, limits).await;
+    let err = result.unwrap_err();
+    assert!(
+        format!("{:?}", err).contains("submission depth exceeded"),
+        "{:?}",
+        err
+    );
+    assert_eq!(ctx.lock().await.job_submissions_count, 0);
+}
+
+#[tokio::test]
+async fn submissions_stop_at_max_job_submissions() {
+    let limits = ResourceLimits {
+        max_job_submissions: 2,
+        ..ResourceLimits::default()
+    };
+
+    let (result, ctx) = submit_thrice(runtime_context(), "job", 0, limits).await;
+    assert!(result.is_err());
+    assert_eq!(ctx.lock().await.job_submissions_count, 2);
+}
+
+#[tokio::test]
+async fn spawned_job_depth_comes_from_its_lineage() {
+    let limits = ResourceLimits {
+        max_submission_depth: 2,
+        ..ResourceLimits::default()
+    };
+    // root -> parent -> job: the job is two submissions deep, whatever its
+    // own context claims
+    let rt = runtime_context();
+    rt.enqueue_spawned_job(spawned("parent", "root"), 2)
+        .unwrap();
+    rt.enqueue_spawned_job(spawned("job", "parent"), 2).unwrap();
+    assert_eq!(rt.submission_depth("job"), 2);
+
+    let (result, _) = submit_thrice(rt.clone(), "job", 0, limits).await;
+    let err = result.unwrap_err();
+    assert!(
+        format!("{:?}", err).contains("Cannot spawn job"),
+        "{:?}",
+        err
+    );
+    assert_eq!(rt.pending_mesh_jobs.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn refused_spawned_job_does_not_drop_the_rest() {
+    let limits = ResourceLimits {
+        max_submission_depth: 2,
+        ..ResourceLimits::default()
+    };
+    // root -> parent -> deep: a child of `deep` would be three submissions deep
+    let rt = runtime_context();
+    rt.enqueue_spawned_job(spawned("parent", "root"), 2).unwrap();
+    rt.enqueue_spawned_job(spawned("deep", "parent"), 2).unwrap();
+
+    let (result, _) = submit_thrice_after(
+        rt.clone(),
+        "job",
+        0,
+        limits,
+        vec![spawned("too-deep", "deep")],
+    )
+    .await;
+    let err = result.unwrap_err();
+    assert!(
+        format!("{:?}", err).contains("Cannot spawn job too-deep"),
+        "{:?}",
+        err
+    );
+    // The guest's own submission, taken after the refused job, is still queued
+    let pending = rt.pending_mesh_jobs.lock().unwrap();
+    assert_eq!(pending.len(), 3);
+    assert_eq!(pending.back().unwrap().parent_job_id.as_deref(), Some("job"));
+}
@@ -0,0 +1,117 @@
+use chrono::Utc;
+use icn_core_vm::ExecutionMetrics;
+use icn_economics::ResourceType;
+use icn_identity::{KeyPair, TaggedSignature};
+use icn_mesh_receipts::ExecutionReceipt;
+use icn_runtime::receipt_validation::{check_usage_plausibility, measured_resource_usage};
+use icn_runtime::{MetricsViolation, UsagePlausibility};
+use icn_types::mesh::JobStatus;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn receipt(start: u64, end: u64, usage: &[(ResourceType, u64)]) -> ExecutionReceipt {
+    ExecutionReceipt {
+        job_id: "job".to_string(),
+        executor: KeyPair::generate().did,
+        status: JobStatus::Completed,
+        result_data_cid: None,
+        logs_cid: None,
+        resource_usage: usage.iter().copied().collect(),
+        mana_cost: None,
+        execution_start_time: start,
+        execution_end_time: end,
+        execution_end_time_dt: Utc::now(),
+        signature: TaggedSignature::default(),
+        coop_id: None,
+        community_id: None,
+        deterministic: false,
+    }
+}
+
+#[test]
+fn usage_within_the_execution_window_is_accepted() {
+    let bounds = UsagePlausibility::default();
+    // Ten seconds plus one of slack on 64 cores
+    let usage = [
+        (ResourceType::Cpu, 11_000 * 64),
+        (ResourceType::Memory, u64::MAX),
+    ];
+    assert_eq!(
+        check_usage_plausibility(&receipt(100, 110, &usage), &bounds),
+        Ok(())
+    );
+}
+
+#[test]
+fn usage_beyond_the_execution_window_is_flagged() {
+    let bounds = UsagePlausibility {
+        max_cpu_parallelism: 4,
+        max_io_bytes_per_ms: 10,
+        clock_slack_ms: 0,
+    };
+    assert_eq!(
+        check_usage_plausibility(&receipt(100, 101, &[(ResourceType::Io, 10_001)]), &bounds),
+        Err(MetricsViolation::ImplausibleUsage {
+            resource: ResourceType::Io,
+            reported: 10_001,
+            max_plausible: 10_000,
+            elapsed_ms: 1_000,
+        })
+    );
+    assert!(matches!(
+        check_usage_plausibility(&receipt(100, 100, &[(ResourceType::Cpu, 1)]), &bounds),
+        Err(MetricsViolation::ImplausibleUsage {
+            resource: ResourceType::Cpu,
+            ..
+        })
+    ));
+    assert_eq!(
+        check_usage_plausibility(&receipt(101, 100, &[]), &bounds),
+        Err(MetricsViolation::EndsBeforeStart {
+            start: 101,
+            end: 100
+        })
+    );
+}
+
+#[test]
+fn measured_usage_ignores_guest_reported_cpu_and_io() {
+    let metrics = ExecutionMetrics {
+        io_bytes: 512,
+        ..Default::default()
+    };
+    let recorded = vec![
+        ("cpu".to_string(), 1_000_000),
+        ("io".to_string(), 1_000_000),
+        ("memory".to_string(), 64),
+        ("Memory".to_string(), 64),
+        ("token".to_string(), 3),
+        ("bandwidth".to_string(), 7),
+    ];
+
+    let usage = measured_resource_usage(&metrics, &recorded, Duration::from_millis(250)).unwrap();
+
+    let expected: HashMap<_, _> = [
+        (ResourceType::Cpu, 250),
+        (ResourceType::Io, 512),
+        (ResourceType::Memory, 128),
+        (ResourceType::Token, 3),
+        (ResourceType::Bandwidth, 7),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(usage, expected);
+}
+
+#[test]
+fn measured_usage_rejects_unknown_resources() {
+    let recorded = vec![("memory".to_string(), 64), ("gpu".to_string(), 1)];
+
+    let err = measured_resource_usage(
+        &ExecutionMetrics::default(),
+        &recorded,
+        Duration::from_millis(1),
+    )
+    .unwrap_err();
+    assert_eq!(err.0, "gpu");
+}
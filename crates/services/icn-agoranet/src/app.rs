@@ -7,10 +7,12 @@ use axum::{
 };
 use tower::ServiceBuilder;
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, Any, CorsLayer},
+    limit::RequestBodyLimitLayer,
     trace::TraceLayer,
 };
-use axum::http::{Method, header::{AUTHORIZATION, CONTENT_TYPE}};
+use axum::extract::DefaultBodyLimit;
+use axum::http::{HeaderName, HeaderValue, Method, header::{AUTHORIZATION, CONTENT_TYPE}};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
@@ -69,7 +71,7 @@ use crate::models::{
 };
 use crate::websocket::{websocket_routes, WebSocketState};
 use crate::auth::{JwtConfig, revocation::{TokenRevocationStore, InMemoryRevocationStore}};
-use crate::mesh_handlers::{list_announced_receipts_handler, DiscoveredReceiptsState};
+use crate::mesh_handlers::{list_announced_receipts_handler, runtime_jobs_router, DiscoveredReceiptsState, RuntimeJobsState};
 
 /// Type alias for the Axum application state
 pub type AppState = (
@@ -80,6 +82,132 @@ pub type AppState = (
     DiscoveredReceiptsState,
 );
 
+/// Default maximum accepted request body size (1 MiB)
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// HTTP-level configuration applied to the router: CORS policy and body limits
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Origins allowed by CORS. An empty list allows any origin.
+    pub allowed_origins: Vec<String>,
+    /// Methods allowed by CORS
+    pub allowed_methods: Vec<Method>,
+    /// Request headers allowed by CORS
+    pub allowed_headers: Vec<HeaderName>,
+    /// Maximum request body size in bytes; larger bodies are rejected with 413
+    pub max_body_bytes: usize,
+    /// Base URL of a runtime node's status endpoint, proxied at `/api/v1/runtime/jobs[/{job_id}]`
+    pub runtime_status_url: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec![Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS],
+            allowed_headers: vec![CONTENT_TYPE, AUTHORIZATION],
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            runtime_status_url: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Build the configuration from environment variables, falling back to defaults.
+    ///
+    /// - `CORS_ALLOWED_ORIGINS`: comma-separated origins, or `*` for any
+    /// - `CORS_ALLOWED_METHODS`: comma-separated HTTP methods
+    /// - `CORS_ALLOWED_HEADERS`: comma-separated header names
+    /// - `MAX_BODY_BYTES`: maximum request body size in bytes
+    /// - `RUNTIME_STATUS_URL`: base URL of the runtime node whose jobs are proxied
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(origins) = std::env::var("CORS_ALLOWED_ORIGINS") {
+            config.allowed_origins = split_list(&origins)
+                .filter(|o| *o != "*")
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(methods) = std::env::var("CORS_ALLOWED_METHODS") {
+            let parsed: Vec<Method> = split_list(&methods)
+                .filter_map(|m| match m.to_ascii_uppercase().parse::<Method>() {
+                    Ok(method) => Some(method),
+                    Err(_) => {
+                        tracing::warn!("Ignoring invalid CORS method: {}", m);
+                        None
+                    }
+                })
+                .collect();
+            if !parsed.is_empty() {
+                config.allowed_methods = parsed;
+            }
+        }
+
+        if let Ok(headers) = std::env::var("CORS_ALLOWED_HEADERS") {
+            let parsed: Vec<HeaderName> = split_list(&headers)
+                .filter_map(|h| match HeaderName::from_bytes(h.as_bytes()) {
+                    Ok(name) => Some(name),
+                    Err(_) => {
+                        tracing::warn!("Ignoring invalid CORS header: {}", h);
+                        None
+                    }
+                })
+                .collect();
+            if !parsed.is_empty() {
+                config.allowed_headers = parsed;
+            }
+        }
+
+        if let Ok(limit) = std::env::var("MAX_BODY_BYTES") {
+            match limit.parse::<usize>() {
+                Ok(bytes) if bytes > 0 => config.max_body_bytes = bytes,
+                _ => tracing::warn!("Ignoring invalid MAX_BODY_BYTES value: {}", limit),
+            }
+        }
+
+        if let Ok(url) = std::env::var("RUNTIME_STATUS_URL") {
+            let url = url.trim();
+            if !url.is_empty() {
+                config.runtime_status_url = Some(url.to_string());
+            }
+        }
+
+        config
+    }
+
+    /// Build the CORS layer described by this configuration
+    pub fn cors_layer(&self) -> CorsLayer {
+        let origins: Vec<HeaderValue> = self
+            .allowed_origins
+            .iter()
+            .filter_map(|o| match HeaderValue::from_str(o) {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!("Ignoring invalid CORS origin: {}", o);
+                    None
+                }
+            })
+            .collect();
+
+        let allow_origin = if origins.is_empty() {
+            AllowOrigin::from(Any)
+        } else {
+            AllowOrigin::list(origins)
+        };
+
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(self.allowed_methods.clone())
+            .allow_headers(self.allowed_headers.clone())
+    }
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
 /// API documentation
 #[derive(OpenApi)]
 #[openapi(
@@ -120,8 +248,13 @@ pub type AppState = (
 )]
 struct ApiDoc;
 
-/// Create the Axum application with all routes
+/// Create the Axum application with all routes and the default HTTP configuration
 pub fn create_app(app_state: AppState) -> Router {
+    create_app_with_config(app_state, HttpConfig::default())
+}
+
+/// Create the Axum application with all routes and the given HTTP configuration
+pub fn create_app_with_config(app_state: AppState, http_config: HttpConfig) -> Router {
     // Define the API documentation for OpenAPI
     let openapi = ApiDoc::openapi();
     
@@ -132,6 +265,9 @@ pub fn create_app(app_state: AppState) -> Router {
     let ws_router = websocket_routes()
         .with_state((db.clone(), ws_state.clone(), jwt_config.clone()));
     
+    // Runtime job introspection is proxied to the configured runtime node
+    let runtime_router = runtime_jobs_router(RuntimeJobsState::new(http_config.runtime_status_url.clone()));
+
     // Create main API router with the full state
     let api_router = Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", openapi))
@@ -177,21 +313,18 @@ pub fn create_app(app_state: AppState) -> Router {
         // Monitoring routes
         .route("/internal/metrics-ui", get(metrics_dashboard_handler))
         
-        // Common middleware
-        .layer(
-            ServiceBuilder::new()
-                .layer(
-                    CorsLayer::new()
-                        .allow_origin(Any)
-                        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-                        .allow_headers([CONTENT_TYPE, AUTHORIZATION]),
-                )
-                .layer(TraceLayer::new_for_http())
-        )
         .with_state(app_state);
     
-    // Merge the API and WebSocket routers
-    api_router.merge(ws_router)
+    // Merge the API and WebSocket routers, then apply common middleware.
+    // Bodies whose Content-Length exceeds the limit are rejected with 413
+    // before any buffering; streamed bodies are cut off at the same limit.
+    api_router.merge(ws_router).merge(runtime_router).layer(
+        ServiceBuilder::new()
+            .layer(TraceLayer::new_for_http())
+            .layer(http_config.cors_layer())
+            .layer(DefaultBodyLimit::disable())
+            .layer(RequestBodyLimitLayer::new(http_config.max_body_bytes)),
+    )
 }
 
 /// Handler for the metrics dashboard UI
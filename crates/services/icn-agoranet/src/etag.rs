@@ -0,0 +1,112 @@
+//! ETag helpers for conditional GET requests.
+//!
+//! Receipts are content-addressed, so a receipt CID is used directly as a
+//! strong ETag. Collection responses get a weak ETag derived from the CIDs of
+//! their members, which changes whenever the set (or its order) changes.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+/// Build a strong ETag from a content identifier (e.g. a receipt CID)
+pub fn strong_etag(cid: &str) -> String {
+    format!("\"{}\"", cid)
+}
+
+/// Build a weak ETag from an ordered sequence of content identifiers
+pub fn weak_etag_for<'a, I>(cids: I) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut hasher = DefaultHasher::new();
+    let mut count: usize = 0;
+    for cid in cids {
+        cid.hash(&mut hasher);
+        count += 1;
+    }
+    count.hash(&mut hasher);
+    format!("W/\"{:016x}\"", hasher.finish())
+}
+
+/// Returns true if the request's `If-None-Match` header matches `etag`.
+///
+/// Uses weak comparison as required for `If-None-Match` (RFC 9110 §13.1.2).
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let wanted = opaque_tag(etag);
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || opaque_tag(candidate) == wanted)
+}
+
+/// Respond with `304 Not Modified` if the client already holds `etag`,
+/// otherwise with the JSON body. Both responses carry the `ETag` header.
+pub fn conditional_json<T: Serialize>(headers: &HeaderMap, etag: &str, body: T) -> Response {
+    let etag_value = match HeaderValue::from_str(etag) {
+        Ok(value) => value,
+        Err(_) => return Json(body).into_response(),
+    };
+
+    if if_none_match(headers, etag) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_value)]).into_response();
+    }
+
+    ([(header::ETAG, etag_value)], Json(body)).into_response()
+}
+
+fn opaque_tag(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn strong_etag_matches_weakly() {
+        let etag = strong_etag("bafytest");
+        assert!(if_none_match(&headers_with("\"bafytest\""), &etag));
+        assert!(if_none_match(&headers_with("W/\"bafytest\""), &etag));
+        assert!(if_none_match(&headers_with("\"other\", \"bafytest\""), &etag));
+        assert!(if_none_match(&headers_with("*"), &etag));
+        assert!(!if_none_match(&headers_with("\"other\""), &etag));
+        assert!(!if_none_match(&HeaderMap::new(), &etag));
+    }
+
+    #[test]
+    fn weak_etag_changes_with_membership() {
+        let a = weak_etag_for(["cid1", "cid2"]);
+        let b = weak_etag_for(["cid1", "cid2"]);
+        let c = weak_etag_for(["cid1", "cid2", "cid3"]);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.starts_with("W/\""));
+    }
+
+    #[test]
+    fn conditional_json_returns_304_on_match() {
+        let etag = strong_etag("bafytest");
+        let response = conditional_json(&headers_with(&etag), &etag, "body");
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), etag.as_str());
+
+        let response = conditional_json(&HeaderMap::new(), &etag, "body");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), etag.as_str());
+    }
+}
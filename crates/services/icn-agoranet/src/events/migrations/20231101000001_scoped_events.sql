@@ -0,0 +1,12 @@
+-- Scoped websocket events, kept so /ws/events can replay them after a restart
+CREATE TABLE scoped_events (
+    seq BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL UNIQUE,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    federation_id TEXT,
+    coop_id TEXT,
+    community_id TEXT,
+    event JSONB NOT NULL
+);
+
+CREATE INDEX idx_scoped_events_coop ON scoped_events (coop_id, occurred_at);
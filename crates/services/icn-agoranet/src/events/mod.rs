@@ -0,0 +1,20 @@
+pub mod pg_store;
+pub mod store;
+
+pub use pg_store::PostgresEventStore;
+pub use store::{EventStore, EventStoreError};
+
+use crate::ledger::create_pg_pool;
+
+/// Create a PostgreSQL event store
+pub async fn create_pg_event_store(database_url: &str) -> Result<PostgresEventStore, sqlx::Error> {
+    let pool = create_pg_pool(database_url).await?;
+
+    // Run migrations. The ledger's migrations share the database, so theirs
+    // are expected in its migration table.
+    let mut migrator = sqlx::migrate!("./src/events/migrations");
+    migrator.set_ignore_missing(true);
+    migrator.run(&pool).await?;
+
+    Ok(PostgresEventStore::new(pool))
+}
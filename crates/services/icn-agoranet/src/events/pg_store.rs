@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::store::{EventStore, EventStoreError};
+use crate::websocket::{ScopedEvent, WebSocketEvent};
+
+/// PostgreSQL implementation of the EventStore trait
+#[derive(Clone, Debug)]
+pub struct PostgresEventStore {
+    pool: PgPool,
+}
+
+impl PostgresEventStore {
+    /// Create a new PostgreSQL event store with the given connection pool
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+type EventRow = (
+    Uuid,
+    DateTime<Utc>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Json<WebSocketEvent>,
+);
+
+#[async_trait]
+impl EventStore for PostgresEventStore {
+    async fn append(&self, event: &ScopedEvent) -> Result<(), EventStoreError> {
+        sqlx::query(
+            r#"
+            INSERT INTO scoped_events (event_id, occurred_at, federation_id, coop_id, community_id, event)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (event_id) DO NOTHING
+            "#,
+        )
+        .bind(event.id)
+        .bind(event.timestamp)
+        .bind(&event.federation_id)
+        .bind(&event.coop_id)
+        .bind(&event.community_id)
+        .bind(Json(&event.event))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn coop_events_since(
+        &self,
+        coop_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ScopedEvent>, EventStoreError> {
+        let rows: Vec<EventRow> = sqlx::query_as(
+            r#"
+            SELECT event_id, occurred_at, federation_id, coop_id, community_id, event
+            FROM scoped_events
+            WHERE coop_id = $1 AND occurred_at >= $2
+            ORDER BY seq
+            "#,
+        )
+        .bind(coop_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, timestamp, federation_id, coop_id, community_id, Json(event))| ScopedEvent {
+                    id,
+                    timestamp,
+                    federation_id,
+                    coop_id,
+                    community_id,
+                    event,
+                },
+            )
+            .collect())
+    }
+}
@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::websocket::ScopedEvent;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EventStoreError {
+    #[error("database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// Durable history of scoped events, replayed by `/ws/events`.
+///
+/// This records what agoranet itself broadcast; it is not read from or
+/// anchored to the DAG.
+#[async_trait]
+pub trait EventStore: Send + Sync {
+    /// Persist an event
+    async fn append(&self, event: &ScopedEvent) -> Result<(), EventStoreError>;
+
+    /// Stored events of a cooperative broadcast at or after `since`, oldest first
+    async fn coop_events_since(
+        &self,
+        coop_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ScopedEvent>, EventStoreError>;
+}
@@ -1,6 +1,7 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::Response,
     Json,
 };
 use chrono::Utc;
@@ -16,6 +17,7 @@ use std::str::FromStr; // Added
 use cid::Cid; // Added
 
 use crate::error::ApiError;
+use crate::etag::{conditional_json, strong_etag, weak_etag_for};
 use crate::models::*; // Added ApiError import
 use crate::auth::{
     AuthenticatedRequest, AuthError, 
@@ -969,13 +971,15 @@ pub async fn get_proposal_votes_handler(
         GetReceiptsQuery
     ),
     responses(
-        (status = 200, description = "List of execution receipt summaries", body = Vec<ExecutionReceiptSummary>)
+        (status = 200, description = "List of execution receipt summaries", body = Vec<ExecutionReceiptSummary>),
+        (status = 304, description = "Receipt list unchanged since the ETag given in If-None-Match")
     )
 )]
 pub async fn get_receipts_handler(
+    headers: HeaderMap,
     Query(params): Query<GetReceiptsQuery>,
     State(db): State<Db>,
-) -> Result<Json<Vec<ExecutionReceiptSummary>>, ApiError> {
+) -> Result<Response, ApiError> {
     let store = db
         .read()
         .map_err(|_| ApiError::InternalServerError("Failed to acquire read lock".to_string()))?;
@@ -996,7 +1000,8 @@ pub async fn get_receipts_handler(
         (None, None) => receipts,
     };
     
-    Ok(Json(paginated_receipts))
+    let etag = weak_etag_for(paginated_receipts.iter().map(|r| r.cid.as_str()));
+    Ok(conditional_json(&headers, &etag, paginated_receipts))
 }
 
 // GET /receipts/{cid}
@@ -1008,13 +1013,15 @@ pub async fn get_receipts_handler(
     ),
     responses(
         (status = 200, description = "Receipt detail", body = ExecutionReceiptDetail),
+        (status = 304, description = "Receipt unchanged since the ETag given in If-None-Match"),
         (status = 404, description = "Receipt not found")
     )
 )]
 pub async fn get_receipt_detail_handler(
+    headers: HeaderMap,
     Path(cid_str): Path<String>, // Renamed to cid_str for clarity
     State(db): State<Db>,
-) -> Result<Json<ExecutionReceiptDetail>, ApiError> {
+) -> Result<Response, ApiError> {
     let validated_cid = Cid::try_from(cid_str.clone())?; // Parse the CID string
 
     let store = db
@@ -1025,7 +1032,8 @@ pub async fn get_receipt_detail_handler(
         .receipts
         .iter()
         .find(|r| r.summary.cid == validated_cid.to_string()) // Use validated_cid.to_string()
-        .map(|r| Json(r.clone()))
+        // Receipts are content-addressed, so the CID is a strong ETag
+        .map(|r| conditional_json(&headers, &strong_etag(&r.summary.cid), r.clone()))
         .ok_or_else(|| ApiError::NotFound(format!("Receipt with CID {} not found", validated_cid.to_string())))
 }
 
@@ -1220,9 +1228,10 @@ pub struct GetTokenStatsQuery {
 /// Endpoint for accessing execution receipts with authorization
 pub async fn get_receipts_authorized(
     auth: AuthenticatedRequest,
+    headers: HeaderMap,
     Query(params): Query<GetReceiptsQuery>,
     State(db): State<Db>,
-) -> Result<Json<Vec<ExecutionReceiptSummary>>, AuthError> {
+) -> Result<Response, AuthError> {
     // Check if the user has access to the requested organization scope
     if !auth.claims.has_org_scope_access(
         None, // We don't have federation_id in the model yet
@@ -1238,7 +1247,8 @@ pub async fn get_receipts_authorized(
     
     let receipts = store.filter_receipts(&params);
     
-    Ok(Json(receipts))
+    let etag = weak_etag_for(receipts.iter().map(|r| r.cid.as_str()));
+    Ok(conditional_json(&headers, &etag, receipts))
 }
 
 /// Endpoint for accessing token balances with authorization
@@ -20,10 +20,11 @@ pub async fn create_pg_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
 pub async fn create_pg_ledger_store(database_url: &str) -> Result<PostgresLedgerStore, sqlx::Error> {
     let pool = create_pg_pool(database_url).await?;
     
-    // Run migrations
-    sqlx::migrate!("./src/ledger/migrations")
-        .run(&pool)
-        .await?;
+    // Run migrations. The event store's migrations share the database, so
+    // theirs are expected in its migration table.
+    let mut migrator = sqlx::migrate!("./src/ledger/migrations");
+    migrator.set_ignore_missing(true);
+    migrator.run(&pool).await?;
     
     Ok(PostgresLedgerStore::new(pool))
 } 
\ No newline at end of file
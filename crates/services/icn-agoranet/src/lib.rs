@@ -1,5 +1,6 @@
 pub mod app;
 pub mod error;
+pub mod etag;
 pub mod handlers;
 pub mod models;
 pub mod websocket;
@@ -7,7 +8,9 @@ pub mod auth;
 pub mod auth_handlers;
 pub mod org_handlers;
 pub mod ledger;
+pub mod events;
 pub mod transfers;
 pub mod metrics;
+pub mod mesh_handlers;
 
 // Potentially shared functions or constants can go here
@@ -3,7 +3,7 @@ use axum::{
     Router,
 };
 use icn_agoranet::{
-    app::create_app, 
+    app::{create_app_with_config, HttpConfig}, 
     auth, 
     handlers::{InMemoryStore, Db}, 
     websocket::WebSocketState,
@@ -13,7 +13,6 @@ use icn_agoranet::{
 };
 use std::net::SocketAddr;
 use std::sync::{Arc, RwLock};
-use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -28,6 +27,8 @@ use cid::Cid;
 // use icn_agoranet::models::*; // No longer needed directly here
 mod auth_handlers;
 mod error;
+mod etag;
+mod events;
 mod app;
 mod handlers;
 mod models;
@@ -144,7 +145,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let db: Db = Arc::new(RwLock::new(store));
     
     // Initialize WebSocket state
-    let ws_state = WebSocketState::new();
+    let mut ws_state = WebSocketState::new();
+    if env::var("USE_POSTGRES").unwrap_or_else(|_| "true".to_string()) == "true" {
+        tracing::info!("Initializing PostgreSQL event store");
+        match icn_agoranet::events::create_pg_event_store(&database_url).await {
+            Ok(event_store) => {
+                tracing::info!("PostgreSQL event store initialized successfully");
+                ws_state = ws_state.with_event_store(Arc::new(event_store));
+            },
+            Err(e) => {
+                tracing::error!("Failed to initialize PostgreSQL event store: {}", e);
+                tracing::info!("Event replay will only cover events since startup");
+            }
+        }
+    }
     
     // Start event simulation (for development/testing)
     if std::env::var("SIMULATE_EVENTS").unwrap_or_else(|_| "true".into()) == "true" {
@@ -152,11 +166,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ws_state.clone().start_simulation();
     }
 
-    // Configure CORS
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    // Configure CORS and request body limits
+    let http_config = HttpConfig::from_env();
+    tracing::info!(
+        "HTTP config: {} allowed origin(s) (empty = any), max body {} bytes",
+        http_config.allowed_origins.len(),
+        http_config.max_body_bytes
+    );
 
     // Initialize JWT configuration
     let jwt_secret = env::var("JWT_SECRET").unwrap_or_else(|_| "icn-debug-secret-key".to_string());
@@ -199,7 +215,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Starting server on {}", address);
     axum::serve(
         tokio::net::TcpListener::bind(address).await?,
-        create_app(app_state),
+        create_app_with_config(app_state, http_config),
     )
     .await?;
 
@@ -1,4 +1,10 @@
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::Response,
+    routing::get,
+    Router,
+};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -9,6 +15,9 @@ use icn_identity::Did; // For Did
 use icn_types::mesh::JobId as IcnJobId; // For IcnJobId (usually type JobId = String)
 use cid::Cid; // For Cid
 
+use crate::error::ApiError;
+use crate::etag::{conditional_json, weak_etag_for};
+
 /// Represents a single announced execution receipt.
 #[derive(Serialize, Debug, Clone)]
 pub struct AnnouncedReceiptResponseItem {
@@ -23,12 +32,14 @@ pub type DiscoveredReceiptsState = Arc<RwLock<HashMap<IcnJobId, (Cid, Did)>>>;
 
 /// Handles GET /api/v1/mesh/receipts/announced
 /// Returns a list of all execution receipt announcements discovered by the node.
+/// Responses carry a weak ETag over the announced receipt CIDs and honor `If-None-Match`.
 pub async fn list_announced_receipts_handler(
+    headers: HeaderMap,
     State(discovered_receipts): State<DiscoveredReceiptsState>,
-) -> Json<Vec<AnnouncedReceiptResponseItem>> {
+) -> Response {
     let announcements_map_guard = discovered_receipts.read().await;
 
-    let response_list: Vec<AnnouncedReceiptResponseItem> = announcements_map_guard
+    let mut response_list: Vec<AnnouncedReceiptResponseItem> = announcements_map_guard
         .iter()
         .map(|(job_id, (receipt_cid, executor_did))| AnnouncedReceiptResponseItem {
             job_id: job_id.clone(),
@@ -37,5 +48,85 @@ pub async fn list_announced_receipts_handler(
         })
         .collect();
 
-    Json(response_list)
-} 
\ No newline at end of file
+    // Sort so the listing (and therefore its ETag) is stable across requests
+    response_list.sort_by(|a, b| a.job_id.cmp(&b.job_id));
+
+    let etag = weak_etag_for(response_list.iter().map(|r| r.receipt_cid.as_str()));
+    conditional_json(&headers, &etag, response_list)
+}
+
+/// State for proxying a runtime node's in-flight jobs.
+#[derive(Clone)]
+pub struct RuntimeJobsState {
+    runtime_status_url: Option<String>,
+    client: reqwest::Client,
+}
+
+impl RuntimeJobsState {
+    pub fn new(runtime_status_url: Option<String>) -> Self {
+        Self {
+            runtime_status_url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// Fetch the in-flight job listing from the configured runtime node
+async fn fetch_runtime_jobs(state: &RuntimeJobsState) -> Result<Vec<serde_json::Value>, ApiError> {
+    let base_url = state.runtime_status_url.as_deref().ok_or_else(|| {
+        ApiError::NotFound("No runtime status endpoint configured".to_string())
+    })?;
+    let url = format!("{}/jobs", base_url.trim_end_matches('/'));
+
+    let jobs = state
+        .client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Vec<serde_json::Value>>()
+        .await?;
+
+    Ok(jobs)
+}
+
+/// Handles GET /api/v1/runtime/jobs
+/// Forwards the jobs currently being processed by the configured runtime node.
+/// Jobs have no CID, so responses carry a weak ETag over the serialized jobs
+/// and honor `If-None-Match`.
+pub async fn runtime_jobs_handler(
+    headers: HeaderMap,
+    State(state): State<RuntimeJobsState>,
+) -> Result<Response, ApiError> {
+    let jobs = fetch_runtime_jobs(&state).await?;
+
+    let serialized: Vec<String> = jobs.iter().map(|job| job.to_string()).collect();
+    let etag = weak_etag_for(serialized.iter().map(String::as_str));
+    Ok(conditional_json(&headers, &etag, jobs))
+}
+
+/// Handles GET /api/v1/runtime/jobs/{job_id}
+/// Forwards one in-flight job of the configured runtime node, with a weak ETag
+/// over its current state.
+pub async fn runtime_job_detail_handler(
+    headers: HeaderMap,
+    Path(job_id): Path<String>,
+    State(state): State<RuntimeJobsState>,
+) -> Result<Response, ApiError> {
+    let job = fetch_runtime_jobs(&state)
+        .await?
+        .into_iter()
+        .find(|job| job.get("job_id").and_then(|id| id.as_str()) == Some(job_id.as_str()))
+        .ok_or_else(|| ApiError::NotFound(format!("Job {} is not in flight", job_id)))?;
+
+    let etag = weak_etag_for([job.to_string().as_str()]);
+    Ok(conditional_json(&headers, &etag, job))
+}
+
+/// Router exposing the runtime job listing and job detail with their own state
+pub fn runtime_jobs_router(state: RuntimeJobsState) -> Router {
+    Router::new()
+        .route("/api/v1/runtime/jobs", get(runtime_jobs_handler))
+        .route("/api/v1/runtime/jobs/:job_id", get(runtime_job_detail_handler))
+        .with_state(state)
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, RwLock};
 use axum::{
     extract::{ws::{Message, WebSocket, WebSocketUpgrade}, Path, Query, State},
@@ -8,11 +8,12 @@ use axum::{
 };
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio::time::{interval, Duration};
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, TimeZone, Utc};
 
+use crate::events::EventStore;
 use crate::handlers::Db;
 use crate::models::{ExecutionReceiptSummary, TokenTransaction, ResourceType};
 use crate::auth::{validate_token, JwtConfig, Claims, ScopeClaims};
@@ -20,6 +21,9 @@ use crate::auth::{validate_token, JwtConfig, Claims, ScopeClaims};
 // Maximum number of messages to buffer for each channel
 const MAX_CHANNEL_CAPACITY: usize = 100;
 
+// Maximum number of past events kept for replay
+const MAX_EVENT_LOG: usize = 1000;
+
 /// WebSocket event types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
@@ -36,6 +40,30 @@ pub enum WebSocketEvent {
     Custom(serde_json::Value),
 }
 
+/// An event together with the organization scope it was broadcast to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopedEvent {
+    /// Unique ID of the event
+    pub id: Uuid,
+    /// When the event was broadcast
+    pub timestamp: DateTime<Utc>,
+    /// Federation the event belongs to
+    pub federation_id: Option<String>,
+    /// Cooperative the event belongs to
+    pub coop_id: Option<String>,
+    /// Community the event belongs to
+    pub community_id: Option<String>,
+    /// The event itself
+    pub event: WebSocketEvent,
+}
+
+impl ScopedEvent {
+    /// Whether the event belongs to the cooperative or one of its communities
+    pub fn in_coop(&self, coop_id: &str) -> bool {
+        self.coop_id.as_deref() == Some(coop_id)
+    }
+}
+
 /// WebSocket channel name builder
 fn build_channel_name(federation_id: Option<&str>, coop_id: Option<&str>, community_id: Option<&str>) -> String {
     match (federation_id, coop_id, community_id) {
@@ -62,11 +90,44 @@ pub struct WebSocketParams {
     pub token: Option<String>,
 }
 
+/// Work for the task that writes scoped events to the event store
+enum StoreOp {
+    /// Persist an event
+    Append(ScopedEvent),
+    /// Signal once every earlier event has been written
+    Flush(oneshot::Sender<()>),
+}
+
 /// Broadcast channels for different organization scopes
-#[derive(Debug, Default, Clone)]
+#[derive(Clone)]
 pub struct WebSocketState {
     /// Map of channel names to broadcast senders
     channels: Arc<RwLock<HashMap<String, broadcast::Sender<WebSocketEvent>>>>,
+    /// Recent scoped events, oldest first, for replay
+    event_log: Arc<RwLock<VecDeque<ScopedEvent>>>,
+    /// Every scoped event, for feeds that filter on their own
+    events: broadcast::Sender<ScopedEvent>,
+    /// Durable history replayed alongside the recent log, if configured
+    event_store: Option<Arc<dyn EventStore>>,
+    /// Queue of the single task appending to `event_store`, in record order
+    store_writer: Option<mpsc::UnboundedSender<StoreOp>>,
+}
+
+impl std::fmt::Debug for WebSocketState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSocketState")
+            .field("channels", &self.channels)
+            .field("event_log", &self.event_log)
+            .field("events", &self.events)
+            .field("event_store", &self.event_store.is_some())
+            .finish()
+    }
+}
+
+impl Default for WebSocketState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WebSocketState {
@@ -74,9 +135,124 @@ impl WebSocketState {
     pub fn new() -> Self {
         Self {
             channels: Arc::new(RwLock::new(HashMap::new())),
+            event_log: Arc::new(RwLock::new(VecDeque::new())),
+            events: broadcast::channel(MAX_CHANNEL_CAPACITY).0,
+            event_store: None,
+            store_writer: None,
+        }
+    }
+
+    /// Persist every scoped event to `store` and replay from it, so history
+    /// survives a restart.
+    ///
+    /// Events are appended by one background task in the order they were
+    /// recorded, so this must be called within a Tokio runtime.
+    pub fn with_event_store(mut self, store: Arc<dyn EventStore>) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let writer = store.clone();
+        tokio::spawn(async move {
+            while let Some(op) = rx.recv().await {
+                match op {
+                    StoreOp::Append(event) => {
+                        if let Err(e) = writer.append(&event).await {
+                            tracing::warn!("Failed to store event {}: {}", event.id, e);
+                        }
+                    }
+                    StoreOp::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+        self.event_store = Some(store);
+        self.store_writer = Some(tx);
+        self
+    }
+
+    /// Wait until every event recorded so far has been written to the event
+    /// store. Returns immediately if none is configured.
+    pub async fn flush_event_store(&self) {
+        let Some(writer) = &self.store_writer else {
+            return;
+        };
+        let (done, flushed) = oneshot::channel();
+        if writer.send(StoreOp::Flush(done)).is_ok() {
+            let _ = flushed.await;
         }
     }
 
+    /// Append an event to the replay log and the event store, and publish it
+    /// to scoped feeds.
+    ///
+    /// Publishing happens under the log's write lock so that
+    /// [`WebSocketState::replay_and_subscribe`] never misses an event between
+    /// its snapshot and its subscription, and so that the store receives events
+    /// in log order.
+    fn record_event(&self, event: ScopedEvent) {
+        let mut log = self.event_log.write().unwrap();
+        if let Some(writer) = &self.store_writer {
+            if writer.send(StoreOp::Append(event.clone())).is_err() {
+                tracing::warn!("Event store writer stopped; event {} not stored", event.id);
+            }
+        }
+        if log.len() == MAX_EVENT_LOG {
+            log.pop_front();
+        }
+        log.push_back(event.clone());
+        let _ = self.events.send(event); // Ignore errors (no subscribers)
+    }
+
+    /// Recently logged events for a cooperative broadcast at or after `since`
+    pub fn coop_events_since(&self, coop_id: &str, since: DateTime<Utc>) -> Vec<ScopedEvent> {
+        self.event_log
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|e| e.timestamp >= since && e.in_coop(coop_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Snapshot a cooperative's history and subscribe to all later events.
+    ///
+    /// The recent log is snapshotted atomically with the subscription. Stored
+    /// history is merged into it, so events broadcast before a restart are
+    /// replayed too; an event stored after the subscription may then also
+    /// arrive live, under the same ID.
+    pub async fn replay_and_subscribe(
+        &self,
+        coop_id: &str,
+        since: Option<DateTime<Utc>>,
+    ) -> (Vec<ScopedEvent>, broadcast::Receiver<ScopedEvent>) {
+        let (recent, rx) = {
+            let log = self.event_log.read().unwrap();
+            let recent: Vec<ScopedEvent> = match since {
+                Some(since) => log
+                    .iter()
+                    .filter(|e| e.timestamp >= since && e.in_coop(coop_id))
+                    .cloned()
+                    .collect(),
+                None => Vec::new(),
+            };
+            (recent, self.events.subscribe())
+        };
+        let (Some(since), Some(store)) = (since, &self.event_store) else {
+            return (recent, rx);
+        };
+
+        let mut history = match store.coop_events_since(coop_id, since).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                tracing::warn!("Failed to load stored events for coop {}: {}", coop_id, e);
+                Vec::new()
+            }
+        };
+        let stored: HashSet<Uuid> = history.iter().map(|e| e.id).collect();
+        history.extend(recent.into_iter().filter(|e| !stored.contains(&e.id)));
+        history.sort_by_key(|e| e.timestamp);
+        (history, rx)
+    }
+
     /// Get or create a broadcast channel for the given organization scope
     fn get_or_create_channel(&self, channel_name: &str) -> broadcast::Sender<WebSocketEvent> {
         let mut channels = self.channels.write().unwrap();
@@ -100,6 +276,15 @@ impl WebSocketState {
         community_id: Option<&str>,
         event: WebSocketEvent,
     ) {
+        self.record_event(ScopedEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            federation_id: federation_id.map(str::to_string),
+            coop_id: coop_id.map(str::to_string),
+            community_id: community_id.map(str::to_string),
+            event: event.clone(),
+        });
+
         // Broadcast to the most specific channel
         let specific_channel = build_channel_name(federation_id, coop_id, community_id);
         self.broadcast_to_channel(&specific_channel, event.clone());
@@ -475,10 +660,130 @@ async fn websocket_connection(
     tracing::info!("Client disconnected: {} from channel {}", client_id, channel_name);
 }
 
+/// Query parameters for the cooperative event feed
+#[derive(Debug, Deserialize)]
+pub struct EventFeedParams {
+    /// Cooperative whose events are streamed
+    pub scope: String,
+    /// Replay logged events from this Unix timestamp (seconds) before going live.
+    ///
+    /// History comes from agoranet's own event log and store, not the DAG, so
+    /// only events broadcast through this service are replayed.
+    pub since: Option<i64>,
+    /// JWT token for authentication
+    pub token: Option<String>,
+}
+
+/// WebSocket handler streaming one cooperative's events, optionally replaying
+/// recent history first.
+///
+/// Unlike the channel endpoints this one always requires a token whose claims
+/// grant access to the cooperative.
+pub async fn event_feed_handler(
+    ws: WebSocketUpgrade,
+    Query(params): Query<EventFeedParams>,
+    State((_db, ws_state, jwt_config)): State<(Db, WebSocketState, Arc<JwtConfig>)>,
+) -> impl IntoResponse {
+    let Some(token) = params.token.as_deref() else {
+        return (
+            axum::http::StatusCode::UNAUTHORIZED,
+            "Unauthorized: a token is required for event feeds",
+        ).into_response();
+    };
+    let scope_claims: ScopeClaims = match validate_token(token, &jwt_config) {
+        Ok(claims) => claims.into(),
+        Err(err) => {
+            tracing::warn!("JWT validation failed: {}", err);
+            return (
+                axum::http::StatusCode::UNAUTHORIZED,
+                format!("Unauthorized: {}", err),
+            ).into_response();
+        }
+    };
+    if !scope_claims.has_coop_access(&params.scope) {
+        tracing::warn!("{} denied event feed for coop {}", scope_claims.sub, params.scope);
+        return (
+            axum::http::StatusCode::FORBIDDEN,
+            "Unauthorized: You do not have access to this organization scope",
+        ).into_response();
+    }
+
+    let since = match params.since.map(|ts| Utc.timestamp_opt(ts, 0).single()) {
+        Some(None) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("Invalid since timestamp: {}", params.since.unwrap_or_default()),
+            ).into_response();
+        }
+        Some(Some(since)) => Some(since),
+        None => None,
+    };
+
+    ws.on_upgrade(move |socket| event_feed_connection(socket, params.scope, since, ws_state))
+}
+
+/// Send replayed then live events for `coop_id` until the client goes away
+async fn event_feed_connection(
+    socket: WebSocket,
+    coop_id: String,
+    since: Option<DateTime<Utc>>,
+    ws_state: WebSocketState,
+) {
+    let (mut sender, mut receiver) = socket.split();
+    let (history, mut rx) = ws_state.replay_and_subscribe(&coop_id, since).await;
+    tracing::info!("Event feed opened for coop {} ({} replayed)", coop_id, history.len());
+    let replayed: HashSet<Uuid> = history.iter().map(|e| e.id).collect();
+
+    let coop_for_task = coop_id.clone();
+    let mut send_task = tokio::spawn(async move {
+        for event in history {
+            if let Ok(json) = serde_json::to_string(&event) {
+                if sender.send(Message::Text(json)).await.is_err() {
+                    return;
+                }
+            }
+        }
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Event feed for coop {} skipped {} events", coop_for_task, skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if !event.in_coop(&coop_for_task) || replayed.contains(&event.id) {
+                continue;
+            }
+            if let Ok(json) = serde_json::to_string(&event) {
+                if sender.send(Message::Text(json)).await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut recv_task = tokio::spawn(async move {
+        while let Some(Ok(msg)) = receiver.next().await {
+            if let Message::Close(_) = msg {
+                break;
+            }
+        }
+    });
+
+    tokio::select! {
+        _ = &mut send_task => recv_task.abort(),
+        _ = &mut recv_task => send_task.abort(),
+    }
+
+    tracing::info!("Event feed closed for coop {}", coop_id);
+}
+
 /// Helper function to create a WebSocket router
 pub fn websocket_routes() -> Router<(Db, WebSocketState, Arc<JwtConfig>)> {
     Router::new()
         .route("/ws", get(websocket_handler))
+        .route("/ws/events", get(event_feed_handler))
         .route("/ws/:federation_id", get(federation_websocket_handler))
         .route("/ws/:federation_id/:coop_id", get(coop_websocket_handler))
         .route("/ws/:federation_id/:coop_id/:community_id", get(community_websocket_handler))
@@ -0,0 +1,121 @@
+// Tests for the router-level CORS policy and request body size limits
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use icn_agoranet::{
+    app::{create_app_with_config, HttpConfig},
+    auth::{revocation::{InMemoryRevocationStore, TokenRevocationStore}, JwtConfig},
+    handlers::{Db, InMemoryStore},
+    mesh_handlers::DiscoveredReceiptsState,
+    websocket::WebSocketState,
+};
+use std::sync::{Arc, RwLock};
+use tower::ServiceExt;
+
+fn test_app(http_config: HttpConfig) -> Router {
+    let db: Db = Arc::new(RwLock::new(InMemoryStore::new()));
+    let jwt_config = Arc::new(JwtConfig {
+        secret_key: "test_secret_key_for_http_config".to_string(),
+        issuer: Some("icn-test".to_string()),
+        audience: None,
+        validation: jsonwebtoken::Validation::default(),
+    });
+    let revocation_store =
+        Arc::new(InMemoryRevocationStore::new()) as Arc<dyn TokenRevocationStore>;
+    let discovered_receipts: DiscoveredReceiptsState = Default::default();
+
+    create_app_with_config(
+        (
+            db,
+            Arc::new(WebSocketState::new()),
+            jwt_config,
+            revocation_store,
+            discovered_receipts,
+        ),
+        http_config,
+    )
+}
+
+#[tokio::test]
+async fn oversized_body_is_rejected_with_413() {
+    let app = test_app(HttpConfig {
+        max_body_bytes: 64,
+        ..HttpConfig::default()
+    });
+
+    let body = format!(r#"{{"title":"{}"}}"#, "x".repeat(1024));
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/threads")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn small_body_is_not_rejected_by_limit() {
+    let app = test_app(HttpConfig {
+        max_body_bytes: 64,
+        ..HttpConfig::default()
+    });
+
+    let body = format!(r#"{{"title":"{}"}}"#, "x".repeat(48));
+    assert!(body.len() < 64);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/threads")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_ne!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn cors_allows_only_configured_origins() {
+    let config = HttpConfig {
+        allowed_origins: vec!["https://dashboard.example.org".to_string()],
+        ..HttpConfig::default()
+    };
+
+    let preflight = |origin: &'static str| {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/threads")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let allowed = test_app(config.clone())
+        .oneshot(preflight("https://dashboard.example.org"))
+        .await
+        .unwrap();
+    assert_eq!(
+        allowed.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+        "https://dashboard.example.org"
+    );
+
+    let denied = test_app(config)
+        .oneshot(preflight("https://evil.example.com"))
+        .await
+        .unwrap();
+    assert!(denied.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+}
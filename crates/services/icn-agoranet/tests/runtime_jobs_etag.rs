@@ -0,0 +1,100 @@
+// Tests for ETag / If-None-Match handling on the proxied runtime job endpoints
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Request, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use icn_agoranet::mesh_handlers::{runtime_jobs_router, RuntimeJobsState};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+type RuntimeJobs = Arc<Mutex<Value>>;
+
+/// Serve `jobs` at `/jobs` like a runtime node's status endpoint and return its base URL
+async fn spawn_fake_runtime(jobs: RuntimeJobs) -> String {
+    let app = Router::new()
+        .route(
+            "/jobs",
+            get(|State(jobs): State<RuntimeJobs>| async move { Json(jobs.lock().unwrap().clone()) }),
+        )
+        .with_state(jobs);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+async fn get(app: &Router, uri: &str, if_none_match: Option<&str>) -> axum::response::Response {
+    let mut request = Request::builder().uri(uri);
+    if let Some(etag) = if_none_match {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+fn etag_of(response: &axum::response::Response) -> String {
+    response
+        .headers()
+        .get(header::ETAG)
+        .expect("response carries an ETag")
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn job_list_returns_304_until_the_jobs_change() {
+    let jobs: RuntimeJobs = Arc::new(Mutex::new(json!([
+        { "job_id": "job-1", "status": "Running" },
+        { "job_id": "job-2", "status": "Pending" },
+    ])));
+    let app = runtime_jobs_router(RuntimeJobsState::new(Some(spawn_fake_runtime(jobs.clone()).await)));
+
+    let first = get(&app, "/api/v1/runtime/jobs", None).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    let etag = etag_of(&first);
+    assert!(etag.starts_with("W/\""));
+
+    let unchanged = get(&app, "/api/v1/runtime/jobs", Some(&etag)).await;
+    assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(etag_of(&unchanged), etag);
+
+    jobs.lock().unwrap()[1]["status"] = json!("Running");
+    let changed = get(&app, "/api/v1/runtime/jobs", Some(&etag)).await;
+    assert_eq!(changed.status(), StatusCode::OK);
+    assert_ne!(etag_of(&changed), etag);
+}
+
+#[tokio::test]
+async fn job_detail_returns_304_until_the_job_changes() {
+    let jobs: RuntimeJobs = Arc::new(Mutex::new(json!([
+        { "job_id": "job-1", "status": "Running" },
+        { "job_id": "job-2", "status": "Pending" },
+    ])));
+    let app = runtime_jobs_router(RuntimeJobsState::new(Some(spawn_fake_runtime(jobs.clone()).await)));
+
+    let first = get(&app, "/api/v1/runtime/jobs/job-2", None).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    let etag = etag_of(&first);
+
+    // Another job changing leaves this job's ETag alone
+    jobs.lock().unwrap()[0]["status"] = json!("Failed");
+    let unchanged = get(&app, "/api/v1/runtime/jobs/job-2", Some(&etag)).await;
+    assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED);
+
+    jobs.lock().unwrap()[1]["status"] = json!("Running");
+    let changed = get(&app, "/api/v1/runtime/jobs/job-2", Some(&etag)).await;
+    assert_eq!(changed.status(), StatusCode::OK);
+    assert_ne!(etag_of(&changed), etag);
+
+    let missing = get(&app, "/api/v1/runtime/jobs/job-3", None).await;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,125 @@
+// Tests for the cooperative-scoped event log behind /ws/events
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use icn_agoranet::events::{EventStore, EventStoreError};
+use icn_agoranet::websocket::{ScopedEvent, WebSocketEvent, WebSocketState};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Event store standing in for the database a previous process wrote to
+#[derive(Default)]
+struct StoredEvents(Mutex<Vec<ScopedEvent>>);
+
+#[async_trait]
+impl EventStore for StoredEvents {
+    async fn append(&self, event: &ScopedEvent) -> Result<(), EventStoreError> {
+        self.0.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+
+    async fn coop_events_since(
+        &self,
+        coop_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ScopedEvent>, EventStoreError> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.timestamp >= since && e.in_coop(coop_id))
+            .cloned()
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn replay_returns_only_the_requested_coop() {
+    let ws_state = WebSocketState::new();
+    let start = Utc::now() - Duration::seconds(1);
+
+    ws_state.broadcast_event(Some("fed1"), Some("coop1"), None, WebSocketEvent::Custom(json!(1)));
+    ws_state.broadcast_event(Some("fed1"), Some("coop2"), None, WebSocketEvent::Custom(json!(2)));
+    ws_state.broadcast_event(Some("fed1"), Some("coop1"), Some("comm1"), WebSocketEvent::Custom(json!(3)));
+    ws_state.broadcast_event(Some("fed1"), None, None, WebSocketEvent::Custom(json!(4)));
+
+    let events = ws_state.coop_events_since("coop1", start);
+    let payloads: Vec<_> = events
+        .iter()
+        .map(|e| match &e.event {
+            WebSocketEvent::Custom(v) => v.clone(),
+            other => panic!("unexpected event {:?}", other),
+        })
+        .collect();
+    assert_eq!(payloads, vec![json!(1), json!(3)]);
+
+    assert!(ws_state.coop_events_since("coop1", Utc::now() + Duration::seconds(60)).is_empty());
+}
+
+#[tokio::test]
+async fn subscription_sees_events_after_the_snapshot() {
+    let ws_state = WebSocketState::new();
+    ws_state.broadcast_event(Some("fed1"), Some("coop1"), None, WebSocketEvent::Custom(json!("old")));
+
+    let (history, mut rx) = ws_state.replay_and_subscribe("coop1", Some(Utc::now() - Duration::seconds(60))).await;
+    assert_eq!(history.len(), 1);
+
+    ws_state.broadcast_event(Some("fed1"), Some("coop1"), None, WebSocketEvent::Custom(json!("new")));
+    let live = rx.recv().await.unwrap();
+    assert!(live.in_coop("coop1"));
+    assert!(matches!(live.event, WebSocketEvent::Custom(ref v) if v == &json!("new")));
+}
+
+#[tokio::test]
+async fn replay_includes_events_stored_before_a_restart() {
+    let store = Arc::new(StoredEvents::default());
+    store
+        .append(&ScopedEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now() - Duration::seconds(30),
+            federation_id: Some("fed1".to_string()),
+            coop_id: Some("coop1".to_string()),
+            community_id: None,
+            event: WebSocketEvent::Custom(json!("before restart")),
+        })
+        .await
+        .unwrap();
+
+    let ws_state = WebSocketState::new().with_event_store(store.clone());
+    ws_state.broadcast_event(Some("fed1"), Some("coop1"), None, WebSocketEvent::Custom(json!("after restart")));
+    ws_state.flush_event_store().await;
+
+    let (history, _rx) = ws_state.replay_and_subscribe("coop1", Some(Utc::now() - Duration::seconds(60))).await;
+    let payloads: Vec<_> = history
+        .iter()
+        .map(|e| match &e.event {
+            WebSocketEvent::Custom(v) => v.clone(),
+            other => panic!("unexpected event {:?}", other),
+        })
+        .collect();
+    // The new event is in both the recent log and the store, but is replayed once
+    assert_eq!(payloads, vec![json!("before restart"), json!("after restart")]);
+}
+
+#[tokio::test]
+async fn store_receives_every_event_in_broadcast_order() {
+    let store = Arc::new(StoredEvents::default());
+    let ws_state = WebSocketState::new().with_event_store(store.clone());
+    for i in 0..50 {
+        ws_state.broadcast_event(Some("fed1"), Some("coop1"), None, WebSocketEvent::Custom(json!(i)));
+    }
+    ws_state.flush_event_store().await;
+
+    let stored: Vec<_> = store
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| match &e.event {
+            WebSocketEvent::Custom(v) => v.clone(),
+            other => panic!("unexpected event {:?}", other),
+        })
+        .collect();
+    assert_eq!(stored, (0..50).map(|i| json!(i)).collect::<Vec<_>>());
+}
@@ -8,10 +8,10 @@ use axum::{
 };
 use cid::Cid;
 use futures::{stream::StreamExt, SinkExt};
-use icn_identity::Did;
+use icn_identity::{Did, TaggedSignature};
 use icn_types::jobs::{Bid, JobRequest, JobStatus, ResourceEstimate, ResourceRequirements};
 use icn_types::reputation::{ReputationRecord, ReputationUpdateEvent, ReputationProfile};
-use icn_types::mesh::MeshJobParams;
+use icn_types::mesh::{MeshJob, MeshJobParams, OrgScopeIdentifier};
 use icn_types::JobFailureReason;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -21,8 +21,6 @@ use std::sync::Arc;
 use tokio::sync::broadcast::error::RecvError;
 use tracing_subscriber;
 use chrono::Utc;
-use sha2::{Digest, Sha256};
-use multihash::{Code, Multihash};
 use dotenv::dotenv;
 use std::ops::Deref;
 
@@ -51,6 +49,7 @@ mod models; // Add models module
 use crate::job_assignment::{DefaultExecutorSelector, ExecutorSelector, GovernedExecutorSelector, ExecutionPolicy}; // Updated import
 use crate::models::{BidEvaluatorConfig, ScoreComponent, ReputationSummary, BidExplanation, BidsExplainResponse};
 use icn_types::RuntimeJobFailureReport; // <-- ADD THIS IMPORT
+use icn_mesh_protocol::P2PJobStatus;
 
 // ADDITION START
 // Define a type alias for the shared P2P node state
@@ -104,35 +103,42 @@ struct AssignJobResponse {
 }
 
 /// Payload expected for creating a new job.
+///
+/// The originator signs the [`MeshJob`] these fields describe, with `job_id` set
+/// to [`MeshJob::submission_id`], so executors can check who asked for the work.
 #[derive(Deserialize)]
 struct CreateJobApiPayload {
     params: MeshJobParams,
     originator_did: Did,
+    #[serde(default)]
+    originator_org_scope: Option<OrgScopeIdentifier>,
+    submission_timestamp: u64,
+    #[serde(default)]
+    max_total_mana: Option<u64>,
+    originator_signature: TaggedSignature,
 }
 
-/// Internal struct for deterministic CID generation of a job.
-#[derive(Serialize)]
-struct JobCidInput<'a> {
-    params: &'a MeshJobParams,
-    originator_did: &'a Did,
-}
-
-/// Generates a deterministic CID for a job based on its parameters and originator.
-fn generate_job_cid_from_payload(
-    params: &MeshJobParams,
-    originator_did: &Did,
-) -> Result<Cid, AppError> {
-    let cid_input = JobCidInput { params, originator_did };
-    let bytes = serde_json::to_vec(&cid_input)
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize job data for CID generation: {}", e)))?;
-    let mut hasher = Sha256::new();
-    hasher.update(&bytes);
-    let hash_bytes = hasher.finalize();
-    // Using SHA2-256 multihash code (0x12)
-    let multihash = Multihash::new(Code::Sha2_256.into(), &hash_bytes)
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create multihash for CID generation: {}", e)))?;
-    // Using DAG-CBOR codec (0x71) for the CID, common for IPLD structured data.
-    Ok(Cid::new_v1(cid:: известных_кодеков::DAG_CBOR, multihash))
+impl CreateJobApiPayload {
+    /// The signed job this payload describes, rejecting it unless the signature
+    /// verifies against `originator_did`.
+    fn into_signed_job(self) -> Result<MeshJob, AppError> {
+        let job_id = MeshJob::submission_id(&self.originator_did, self.submission_timestamp, &self.params)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to derive job ID: {}", e)))?;
+        let job = MeshJob {
+            job_id,
+            params: self.params,
+            originator_did: self.originator_did,
+            originator_org_scope: self.originator_org_scope,
+            submission_timestamp: self.submission_timestamp,
+            parent_job_id: None,
+            origin_receipt_cid: None,
+            max_total_mana: self.max_total_mana,
+            originator_signature: Some(self.originator_signature),
+        };
+        job.verify_signature()
+            .map_err(|e| AppError::Forbidden(format!("Job {} rejected: {}", job.job_id, e)))?;
+        Ok(job)
+    }
 }
 
 // Add bid extension trait
@@ -253,6 +259,7 @@ pub async fn run_server(
         .route("/jobs/:job_id/bids/explain", get(get_bids_explained_handler))
         .route("/worker/:worker_did/jobs", get(get_jobs_for_worker_handler))
         .route("/jobs/:job_id/runtime-failure", routing::post(report_runtime_failure_handler))
+        .route("/jobs/:job_id/status", post(report_job_status_handler))
         .route("/metrics", metrics_route)
         .layer(Extension(store))
         .layer(Extension(reputation_url))
@@ -352,16 +359,13 @@ async fn create_job(
     Extension(store): Extension<Arc<dyn MeshJobStore>>,
     AxumJson(payload): AxumJson<CreateJobApiPayload>,
 ) -> Result<impl IntoResponse, AppError> {
-    // generate_job_cid_from_payload in main.rs already returns Result<Cid, AppError>
-    // It maps serde_json to AppError::Internal(anyhow!("Failed to serialize job data for CID generation: {}", e))
-    // and multihash to AppError::Internal(anyhow!("Failed to create multihash for CID generation: {}", e))
-    // This could be refined further if needed, e.g. map serde to AppError::Serialization or AppError::BadRequest.
-    let job_id = generate_job_cid_from_payload(&payload.params, &payload.originator_did)?;
+    let job = payload.into_signed_job()?;
+    let job_id = Cid::try_from(job.job_id.as_str()).map_err(|e| AppError::InvalidCid(e.to_string()))?;
 
     let job_request = JobRequest {
         job_id: job_id.clone(),
-        params: payload.params,
-        originator: payload.originator_did,
+        params: job.params,
+        originator: job.originator_did,
         execution_policy: None, // TODO: Allow specifying execution_policy in CreateJobApiPayload
     };
 
@@ -1122,4 +1126,29 @@ async fn report_runtime_failure_handler(
     //    This is out of scope for the initial implementation.
 
     Ok(StatusCode::OK)
-} 
\ No newline at end of file
+} 
+
+/// Status change of a job pushed by a runtime's `HttpJobStatusReporter`
+async fn report_job_status_handler(
+    Path(job_id_str): Path<String>,
+    Extension(store): Extension<Arc<dyn MeshJobStore>>,
+    AxumJson(status): AxumJson<P2PJobStatus>,
+) -> Result<StatusCode, AppError> {
+    let job_id = Cid::try_from(job_id_str.clone())
+        .map_err(|e| AppError::InvalidCid(format!("Invalid Job ID CID format for status report: {}, error: {}", job_id_str, e)))?;
+
+    if store.get_job(&job_id).await?.is_none() {
+        return Err(AppError::NotFound(format!("Job not found: {}", job_id)));
+    }
+
+    let new_status = match status {
+        P2PJobStatus::Running { node_id, .. } | P2PJobStatus::PendingUserInput { node_id, .. } => {
+            IcnJobStatus::Running { runner: node_id }
+        }
+        P2PJobStatus::Completed { .. } => IcnJobStatus::Completed,
+        P2PJobStatus::Failed { reason, .. } => IcnJobStatus::Failed { reason },
+    };
+    tracing::info!(job_id = %job_id, status = ?new_status, "Received job status report.");
+    store.update_job_status(&job_id, new_status).await?;
+    Ok(StatusCode::OK)
+}
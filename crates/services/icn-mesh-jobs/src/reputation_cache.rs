@@ -2,7 +2,8 @@ use anyhow::Result;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
-use crate::reputation_client::{ReputationClient, ReputationProfile, DefaultReputationClient};
+use crate::reputation_client::{ReputationClient, ReputationClientError, ReputationProfile, DefaultReputationClient};
+use icn_types::reputation::{BatchResult, ReputationRecord};
 use crate::models::BidEvaluatorConfig;
 use crate::metrics;
 
@@ -105,6 +106,15 @@ impl ReputationClient for CachingReputationClient {
         metrics::record_bid_score(score);
         score
     }
+
+    async fn submit_record(&self, record: ReputationRecord) -> Result<(), ReputationClientError> {
+        self.inner_client.submit_record(record).await
+    }
+
+    // Records are never cached, so batches go straight to the inner client
+    async fn submit_batch(&self, records: Vec<ReputationRecord>) -> Result<BatchResult, ReputationClientError> {
+        self.inner_client.submit_batch(records).await
+    }
 }
 
 // Implement cleanup task that runs periodically to remove expired entries
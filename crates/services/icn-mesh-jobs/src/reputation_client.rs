@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result as AnyhowResult};
 use icn_identity::Did;
-use icn_types::reputation::{ReputationProfile, ReputationRecord}; // Assuming this path is correct based on icn-types structure
+use icn_types::reputation::{BatchResult, ReputationProfile, ReputationRecord}; // Assuming this path is correct based on icn-types structure
 use serde::{Deserialize, Serialize};
 use reqwest::{Client, Error as ReqwestError, StatusCode};
 use std::time::Duration;
@@ -148,6 +148,18 @@ pub trait ReputationClient: Send + Sync {
 
     /// Submit a reputation record
     async fn submit_record(&self, record: ReputationRecord) -> Result<(), ReputationClientError>;
+
+    /// Submit several reputation records, reporting success or failure per record.
+    ///
+    /// The default implementation submits the records one at a time; clients
+    /// talking to the reputation service should send a single request instead.
+    async fn submit_batch(&self, records: Vec<ReputationRecord>) -> Result<BatchResult, ReputationClientError> {
+        let mut result = BatchResult::default();
+        for (index, record) in records.into_iter().enumerate() {
+            result.push(index, self.submit_record(record).await.map_err(|e| e.to_string()));
+        }
+        Ok(result)
+    }
 }
 
 /// Default implementation of the reputation client
@@ -269,6 +281,47 @@ impl ReputationClient for DefaultReputationClient {
             })
         }
     }
+
+    async fn submit_batch(&self, records: Vec<ReputationRecord>) -> Result<BatchResult, ReputationClientError> {
+        if records.is_empty() {
+            return Ok(BatchResult::default());
+        }
+        let base = self.base_url.trim_end_matches('/');
+        let url = format!("{}/reputation/records/batch", base);
+
+        tracing::debug!("Submitting batch of {} reputation records to URL: {}", records.len(), url);
+
+        let resp = self.client.post(&url).json(&records).send().await?;
+
+        if resp.status().is_success() {
+            let result: BatchResult = resp.json().await.map_err(|e| {
+                ReputationClientError::Deserialization(format!("Failed to parse batch result: {}", e))
+            })?;
+            let failed = result.failed_indices();
+            if !failed.is_empty() {
+                tracing::warn!(
+                    "Reputation service rejected {} of {} batched records (indices {:?})",
+                    failed.len(),
+                    records.len(),
+                    failed
+                );
+            }
+            Ok(result)
+        } else {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_else(|_| "<failed to read response>".to_string());
+            tracing::error!(
+                "Failed to submit batch of {} reputation records: HTTP {} - {}",
+                records.len(),
+                status,
+                error_body
+            );
+            Err(ReputationClientError::Http {
+                status,
+                message: format!("Failed to submit reputation record batch: HTTP {} - {}", status, error_body),
+            })
+        }
+    }
 }
 
 pub struct CachingReputationClient {
@@ -332,4 +385,8 @@ impl ReputationClient for CachingReputationClient {
     async fn submit_record(&self, record: ReputationRecord) -> Result<(), ReputationClientError> {
         self.client.submit_record(record).await
     }
+
+    async fn submit_batch(&self, records: Vec<ReputationRecord>) -> Result<BatchResult, ReputationClientError> {
+        self.client.submit_batch(records).await
+    }
 } 
\ No newline at end of file
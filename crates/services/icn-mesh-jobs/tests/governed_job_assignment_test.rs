@@ -94,6 +94,8 @@ async fn sample_governed_assignment_test() {
         is_interactive: false,
         expected_output_schema_cid: None,
         execution_policy: Some(execution_policy.clone()),
+        deterministic: false,
+        output_recipients: Vec::new(),
     };
 
     // 3. Define Originator DID
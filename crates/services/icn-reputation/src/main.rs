@@ -6,7 +6,9 @@ use axum::{
     Router,
 };
 use icn_identity::Did;
-use icn_types::reputation::{ReputationProfile, ReputationRecord, ReputationUpdateEvent};
+use icn_types::reputation::{
+    BatchResult, ReputationProfile, ReputationRecord, ReputationScoreConfig, ReputationUpdateEvent,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{net::SocketAddr, sync::Arc};
@@ -69,10 +71,13 @@ async fn main() -> anyhow::Result<()> {
 
     let app = Router::new()
         .route("/reputation/records", post(submit_record_handler))
+        .route("/reputation/records/batch", post(submit_batch_handler))
         .route("/reputation/profiles/:did", get(get_profile_handler))
         .route("/reputation/records/:did", get(get_records_handler))
         .route("/reputation/profiles", get(get_all_reputation_profiles_handler))
         .route("/reputation/profiles/:did/history", get(get_reputation_profile_history_handler))
+        .route("/reputation/rebuild", post(rebuild_all_profiles_handler))
+        .route("/reputation/rebuild/:did", post(rebuild_profile_handler))
         .layer(Extension(store));
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 8081)); // Using port 8081 as specified
@@ -93,6 +98,19 @@ async fn submit_record_handler(
     Ok(StatusCode::CREATED)
 }
 
+/// Store several records in one request. Each record is applied on its own, so
+/// one bad record does not reject the rest; the response lists every outcome.
+async fn submit_batch_handler(
+    Extension(store): Extension<Arc<dyn ReputationStore>>,
+    AxumJson(records): AxumJson<Vec<ReputationRecord>>,
+) -> AxumJson<BatchResult> {
+    let mut result = BatchResult::default();
+    for (index, record) in records.into_iter().enumerate() {
+        result.push(index, store.submit_record(record).await.map_err(|e| e.to_string()));
+    }
+    AxumJson(result)
+}
+
 async fn get_profile_handler(
     Extension(store): Extension<Arc<dyn ReputationStore>>,
     Path(did_str): Path<String>,
@@ -107,6 +125,31 @@ async fn get_profile_handler(
     }
 }
 
+/// Recompute one node's profile from its record history. Accepts an optional
+/// scoring config in the body; the default weights are used otherwise.
+async fn rebuild_profile_handler(
+    Extension(store): Extension<Arc<dyn ReputationStore>>,
+    Path(did_str): Path<String>,
+    config: Option<AxumJson<ReputationScoreConfig>>,
+) -> Result<impl IntoResponse, AppError> {
+    let did = Did(did_str);
+    let config = config.map(|AxumJson(c)| c).unwrap_or_default();
+
+    match store.rebuild_profile(&did, &config).await? {
+        Some(profile) => Ok(AxumJson(profile).into_response()),
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+/// Recompute every profile from its record history.
+async fn rebuild_all_profiles_handler(
+    Extension(store): Extension<Arc<dyn ReputationStore>>,
+    config: Option<AxumJson<ReputationScoreConfig>>,
+) -> Result<AxumJson<Vec<ReputationProfile>>, AppError> {
+    let config = config.map(|AxumJson(c)| c).unwrap_or_default();
+    Ok(AxumJson(store.rebuild_all(&config).await?))
+}
+
 async fn get_records_handler(
     Extension(store): Extension<Arc<dyn ReputationStore>>,
     Path(did_str): Path<String>,
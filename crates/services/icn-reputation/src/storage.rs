@@ -3,12 +3,15 @@ use async_trait::async_trait;
 use icn_identity::Did;
 use icn_types::reputation::{
     compute_score,
+    rebuild_profile,
     ReputationProfile, 
     ReputationRecord,
+    ReputationScoreConfig,
     ReputationUpdateEvent // Used indirectly via ReputationRecord but good to have for context
 };
 use std::collections::HashMap;
-use tokio::sync::RwLock;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
 use chrono::Utc; // For setting initial last_updated timestamp
 
 #[async_trait]
@@ -25,6 +28,17 @@ pub trait ReputationStore: Send + Sync + 'static {
 
     /// Lists all reputation records from all subjects in the store.
     async fn list_all_records(&self) -> Result<Vec<ReputationRecord>, anyhow::Error>;
+
+    /// Recomputes a node's profile from its full record history under `config`,
+    /// replacing the stored profile. Returns `None` if the node has no records.
+    async fn rebuild_profile(
+        &self,
+        node_id: &Did,
+        config: &ReputationScoreConfig,
+    ) -> Result<Option<ReputationProfile>>;
+
+    /// Rebuilds the profile of every node with records. Returns the rebuilt profiles.
+    async fn rebuild_all(&self, config: &ReputationScoreConfig) -> Result<Vec<ReputationProfile>>;
 }
 
 pub struct InMemoryReputationStore {
@@ -32,6 +46,9 @@ pub struct InMemoryReputationStore {
     profiles: RwLock<HashMap<Did, ReputationProfile>>,
     // Stores a log of all reputation records for each node.
     records: RwLock<HashMap<Did, Vec<ReputationRecord>>>,
+    // Serializes record submissions and rebuilds per node, so a rebuild cannot
+    // drop or double-count a record applied while it replays.
+    profile_locks: std::sync::Mutex<HashMap<Did, Arc<Mutex<()>>>>,
 }
 
 impl InMemoryReputationStore {
@@ -39,8 +56,18 @@ impl InMemoryReputationStore {
         Self {
             profiles: RwLock::new(HashMap::new()),
             records: RwLock::new(HashMap::new()),
+            profile_locks: std::sync::Mutex::new(HashMap::new()),
         }
     }
+
+    fn profile_lock(&self, node_id: &Did) -> Arc<Mutex<()>> {
+        self.profile_locks
+            .lock()
+            .unwrap()
+            .entry(node_id.clone())
+            .or_default()
+            .clone()
+    }
 }
 
 #[async_trait]
@@ -51,6 +78,9 @@ impl ReputationStore for InMemoryReputationStore {
     }
 
     async fn submit_record(&self, record: ReputationRecord) -> Result<()> {
+        let profile_lock = self.profile_lock(&record.subject);
+        let _profile_guard = profile_lock.lock().await;
+
         // 1. Store the raw record
         let mut records_guard = self.records.write().await;
         records_guard
@@ -64,26 +94,9 @@ impl ReputationStore for InMemoryReputationStore {
         let profile = profiles_guard
             .entry(record.subject.clone())
             .or_insert_with(|| {
-                // Create a new default profile if one doesn't exist for the subject DID
-                // The compute_score on a default profile will give its base score.
-                let mut new_profile = ReputationProfile {
-                    node_id: record.subject.clone(),
-                    last_updated: record.timestamp, // Initialize with the record's timestamp
-                    total_jobs: 0,
-                    successful_jobs: 0,
-                    failed_jobs: 0,
-                    jobs_on_time: 0,
-                    jobs_late: 0,
-                    average_execution_ms: None,
-                    average_bid_accuracy: None,
-                    dishonesty_events: 0,
-                    endorsements: Vec::new(),
-                    current_stake: None,
-                    computed_score: 0.0, // Will be computed properly after creation
-                    latest_anchor_cid: None,
-                };
-                new_profile.computed_score = compute_score(&new_profile); // Set initial score
-                new_profile
+                // Create a new default profile if one doesn't exist for the subject DID,
+                // initialized with the record's timestamp and its base score.
+                ReputationProfile::new(record.subject.clone(), record.timestamp)
             });
 
         // 3. Apply the event from the record to the profile
@@ -112,4 +125,55 @@ impl ReputationStore for InMemoryReputationStore {
         let all_records: Vec<ReputationRecord> = records_map_guard.values().flatten().cloned().collect();
         Ok(all_records)
     }
+
+    async fn rebuild_profile(
+        &self,
+        node_id: &Did,
+        config: &ReputationScoreConfig,
+    ) -> Result<Option<ReputationProfile>> {
+        let profile_lock = self.profile_lock(node_id);
+        let _profile_guard = profile_lock.lock().await;
+
+        let records = self.list_records(node_id).await?;
+        if records.is_empty() {
+            return Ok(None);
+        }
+        let profile = replay_records(node_id, &records, config);
+        self.profiles
+            .write()
+            .await
+            .insert(node_id.clone(), profile.clone());
+        Ok(Some(profile))
+    }
+
+    async fn rebuild_all(&self, config: &ReputationScoreConfig) -> Result<Vec<ReputationProfile>> {
+        // Hold the records lock for the whole rebuild so no record lands between
+        // replay and replacement of the profiles.
+        let records_guard = self.records.read().await;
+        let rebuilt: Vec<ReputationProfile> = records_guard
+            .iter()
+            .map(|(node_id, records)| replay_records(node_id, records, config))
+            .collect();
+
+        let mut profiles_guard = self.profiles.write().await;
+        for profile in &rebuilt {
+            profiles_guard.insert(profile.node_id.clone(), profile.clone());
+        }
+        Ok(rebuilt)
+    }
+}
+
+/// Replay a node's records in submission order into a fresh profile.
+fn replay_records(
+    node_id: &Did,
+    records: &[ReputationRecord],
+    config: &ReputationScoreConfig,
+) -> ReputationProfile {
+    let mut profile = rebuild_profile(
+        node_id.clone(),
+        records.iter().map(|r| (r.timestamp, &r.event)),
+        config,
+    );
+    profile.latest_anchor_cid = records.iter().rev().find_map(|r| r.anchor);
+    profile
 } 
\ No newline at end of file
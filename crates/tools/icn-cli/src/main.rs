@@ -1,9 +1,13 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use icn_ccl_compiler::CclCompiler;
+use icn_economics::sled_mana_ledger::SledManaLedger;
+use icn_economics::ResourceType;
 use icn_identity::{Did, FederationMetadata, KeyPair, QuorumProof, QuorumType, TrustBundle, DidError, ED25519_KEY_LENGTH, ED25519_MULTICODEC_PREFIX};
-use icn_runtime::{ExecutionReceipt, Proposal, ProposalState, QuorumStatus, RuntimeExecutionReceipt, VmContext as RuntimeVmContext};
+use icn_mesh_protocol::P2PJobStatus;
+use icn_runtime::{ActiveJob, ExecutionReceipt, Proposal, ProposalState, QuorumStatus, RuntimeExecutionReceipt, VmContext as RuntimeVmContext};
+use icn_types::mesh::{MeshJob, MeshJobParams};
 use icn_types::error::{IcnError, IdentityError as IcnTypesIdentityError, DagError as IcnTypesDagError, CryptoError as IcnTypesCryptoError, MeshError as IcnTypesMeshError, TrustError as IcnTypesTrustError, MulticodecError as IcnTypesMulticodecError, VcError as IcnTypesVcError};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -410,6 +414,10 @@ enum CclCommands {
         /// Output file for the DSL
         #[clap(long, short)]
         output: PathBuf,
+
+        /// Print lint warnings before compiling
+        #[clap(long)]
+        lint: bool,
     },
 
     /// Compile a CCL file to WASM
@@ -421,6 +429,14 @@ enum CclCommands {
         /// Output file for the WASM
         #[clap(long, short)]
         output: PathBuf,
+
+        /// Print lint warnings before compiling
+        #[clap(long)]
+        lint: bool,
+
+        /// Fold static conditions and collapse repeated properties
+        #[clap(long)]
+        optimize: bool,
     },
 }
 
@@ -463,6 +479,43 @@ enum RuntimeCommands {
         #[clap(long, short)]
         output: Option<PathBuf>,
     },
+
+    /// List the jobs a runtime node is currently processing
+    Jobs {
+        /// Base URL of the runtime's metrics/status endpoint
+        #[clap(long, default_value = "http://127.0.0.1:9090")]
+        url: String,
+    },
+
+    /// Run a runtime node until SIGINT or SIGTERM
+    Serve {
+        /// Path to the node configuration file (TOML)
+        #[clap(long, short)]
+        config: PathBuf,
+
+        /// Path to the node's keypair file
+        #[clap(long, short)]
+        key: PathBuf,
+    },
+
+    /// Sign a mesh job as its originator and submit it to a mesh-jobs service
+    Submit {
+        /// Path to the job parameters (JSON `MeshJobParams`)
+        #[clap(long, short)]
+        params: PathBuf,
+
+        /// Path to the originator's keypair file
+        #[clap(long, short)]
+        key: PathBuf,
+
+        /// Ceiling on the mana spent by the job, its retries and child jobs
+        #[clap(long)]
+        max_total_mana: Option<u64>,
+
+        /// Base URL of the mesh-jobs service
+        #[clap(long, default_value = "http://127.0.0.1:8080")]
+        url: String,
+    },
 }
 
 /// Commands for working with the DAG store
@@ -474,15 +527,19 @@ enum DagCommands {
 /// Commands for working with the economic ledger
 #[derive(Subcommand)]
 enum LedgerCommands {
-    /// Show resource balances for a DID
+    /// Show the mana balance of a DID
     Show {
-        /// The DID to show resources for
+        /// The DID to show the balance of
         #[clap(long, short)]
         did: String,
 
-        /// Resource type to show (CPU, MEMORY, TOKEN, IO)
+        /// Show the DID's pool for this resource type (CPU, MEMORY, TOKEN, IO) instead of its default balance
         #[clap(long, short)]
         resource: Option<String>,
+
+        /// Path to the Sled mana ledger database
+        #[clap(long)]
+        db_path: PathBuf,
     },
 
     /// Mint tokens for a DID (governance operation)
@@ -495,6 +552,17 @@ enum LedgerCommands {
         #[clap(long, short)]
         amount: u64,
     },
+
+    /// Verify stored mana balances against the ledger's audit log
+    Reconcile {
+        /// Path to the Sled mana ledger database
+        #[clap(long)]
+        db_path: PathBuf,
+
+        /// Overwrite discrepant balances with the audit-log-derived value
+        #[clap(long)]
+        repair: bool,
+    },
 }
 
 /// Commands for token operations
@@ -608,21 +676,18 @@ impl icn_runtime::RuntimeStorage for CliRuntimeStorage {
 async fn create_proposal(ccl_file: &Path, _title: &str, output: Option<&Path>) -> Result<()> {
     println!("Creating proposal from CCL file: {}", ccl_file.display());
 
-    // Compile the CCL file to WASM
+    // Compile the CCL file to WASM, recording the compiler and content CIDs
     let compiler = CclCompiler::new()?;
-    let _wasm_bytes = compiler.compile_file(ccl_file)?;
-
-    // Generate CIDs for the CCL and WASM (just UUIDs for simplicity)
-    let ccl_cid = format!("ccl-{}", Uuid::new_v4());
-    let wasm_cid = format!("wasm-{}", Uuid::new_v4());
+    let (_wasm_bytes, manifest) = compiler.compile_file_to_manifest(ccl_file)?;
 
     // Create the proposal
     let proposal = Proposal {
         id: format!("proposal-{}", Uuid::new_v4()),
-        wasm_cid,
-        ccl_cid,
+        wasm_cid: manifest.wasm_cid.clone(),
+        ccl_cid: manifest.ccl_cid.clone(),
         state: ProposalState::Created,
         quorum_status: QuorumStatus::Pending,
+        compilation_manifest: Some(manifest),
     };
 
     // Output the proposal
@@ -730,15 +795,33 @@ async fn compile_to_dsl(input: &Path, output: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Compile a CCL file to WASM
-async fn compile_to_wasm(input: &Path, output: &Path) -> Result<()> {
+/// Print lint warnings for a CCL file; they never stop compilation.
+///
+/// Neither does a file the linter can't lower: the error is printed and
+/// compiling goes ahead to report it in full.
+fn lint_ccl(input: &Path) {
+    let warnings = match CclCompiler::new().and_then(|compiler| compiler.lint_file(input)) {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            eprintln!("{}: lint skipped: {:#}", input.display(), e);
+            return;
+        }
+    };
+    for warning in &warnings {
+        eprintln!("{}: {}", input.display(), warning);
+    }
+    println!("Lint finished with {} warning(s)", warnings.len());
+}
+
+/// Compile a CCL file to WASM, optionally running the optimization passes
+async fn compile_to_wasm(input: &Path, output: &Path, optimize: bool) -> Result<()> {
     println!(
         "Compiling CCL to WASM: {} -> {}",
         input.display(),
         output.display()
     );
 
-    let compiler = CclCompiler::new()?;
+    let compiler = CclCompiler::new()?.with_optimization(optimize);
     compiler.compile_file_to_wasm(input, output)?;
 
     println!("WASM compilation successful!");
@@ -982,6 +1065,100 @@ async fn verify_receipt(receipt_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Fetch and display the in-flight jobs of a running runtime node
+async fn list_runtime_jobs(base_url: &str) -> Result<()> {
+    let url = format!("{}/jobs", base_url.trim_end_matches('/'));
+    let jobs: Vec<ActiveJob> = reqwest::get(&url)
+        .await
+        .with_context(|| format!("Failed to reach runtime at {}", url))?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Failed to parse runtime jobs response")?;
+
+    if jobs.is_empty() {
+        println!("No jobs in flight.");
+        return Ok(());
+    }
+
+    println!("{}", format!("{} job(s) in flight", jobs.len()).blue().bold());
+    for job in jobs {
+        let status = match &job.status {
+            P2PJobStatus::Running { progress_percent, status_message, .. } => format!(
+                "running{}{}",
+                progress_percent.map(|p| format!(" ({}%)", p)).unwrap_or_default(),
+                status_message.as_deref().map(|m| format!(": {}", m)).unwrap_or_default()
+            ),
+            P2PJobStatus::PendingUserInput { .. } => "waiting for input".to_string(),
+            P2PJobStatus::Completed { output_cid, .. } => format!("completed ({})", output_cid),
+            P2PJobStatus::Failed { reason, .. } => format!("failed: {:?}", reason),
+        };
+        println!("  {}  {}", job.job_id, status);
+    }
+
+    Ok(())
+}
+
+/// Sign a job with the originator's key and submit it to a mesh-jobs service
+async fn submit_mesh_job(params_path: &Path, key_path: &Path, max_total_mana: Option<u64>, base_url: &str) -> Result<()> {
+    let params: MeshJobParams = read_and_parse_json(params_path, "job parameters")?;
+    let keypair = load_keypair_file(key_path)?;
+    let submission_timestamp = chrono::Utc::now().timestamp() as u64;
+    let mut job = MeshJob {
+        job_id: MeshJob::submission_id(&keypair.did, submission_timestamp, &params)?,
+        params,
+        originator_did: keypair.did.clone(),
+        originator_org_scope: None,
+        submission_timestamp,
+        parent_job_id: None,
+        origin_receipt_cid: None,
+        max_total_mana,
+        originator_signature: None,
+    };
+    job.sign(&keypair)?;
+
+    let url = format!("{}/jobs", base_url.trim_end_matches('/'));
+    let response: serde_json::Value = reqwest::Client::new()
+        .post(&url)
+        .json(&serde_json::json!({
+            "params": job.params,
+            "originator_did": job.originator_did,
+            "submission_timestamp": job.submission_timestamp,
+            "max_total_mana": job.max_total_mana,
+            "originator_signature": job.originator_signature,
+        }))
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach mesh-jobs service at {}", url))?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Failed to parse job submission response")?;
+
+    println!("{}", "Job submitted".green().bold());
+    println!("Job ID: {}", response["job_id"].as_str().unwrap_or(&job.job_id));
+    Ok(())
+}
+
+/// Run a runtime node from a config file until a shutdown signal arrives
+async fn serve_runtime(config_path: &Path, key_path: &Path) -> Result<()> {
+    let config_contents = std::fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read configuration file '{}'", config_path.display()))?;
+    let config: icn_runtime::config::RuntimeConfig = toml::from_str(&config_contents)
+        .with_context(|| format!("Failed to parse configuration file '{}'", config_path.display()))?;
+    let keypair = load_keypair_file(key_path)?;
+
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .or_else(|_| tracing_subscriber::EnvFilter::try_new(config.log_level.as_deref().unwrap_or("info")))
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+
+    println!("Serving runtime node {} (storage: {})", keypair.did, config.storage_path.display());
+    icn_runtime::node::serve(config, keypair, icn_runtime::node::shutdown_signal()).await?;
+    println!("Runtime node stopped.");
+    Ok(())
+}
+
 /// Execute a CCL file by compiling to DSL, then WASM, and executing
 async fn execute_ccl(ccl_path: &Path, receipt_path: Option<&Path>) -> Result<String> {
     println!("{}", "Executing CCL file".blue().bold());
@@ -998,7 +1175,7 @@ async fn execute_ccl(ccl_path: &Path, receipt_path: Option<&Path>) -> Result<Str
 
     // Step 2: Compile DSL to WASM
     println!("\n{}", "Step 2: Compiling DSL to WASM".yellow());
-    compile_to_wasm(&dsl_path, &wasm_path).await?;
+    compile_to_wasm(&dsl_path, &wasm_path, false).await?;
 
     // Step 3: Execute the WASM
     println!("\n{}", "Step 3: Executing WASM".yellow());
@@ -1042,6 +1219,7 @@ async fn create_federation(
         name: name.to_string(),
         description: description.map(String::from),
         version: "1.0".to_string(),
+        max_token_supply: None,
         additional: HashMap::new(),
     };
 
@@ -1116,6 +1294,25 @@ async fn keypair_info(input: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Load a keypair file written by `keypair generate`
+fn load_keypair_file(path: &Path) -> Result<KeyPair> {
+    let keypair_data: KeypairFileFormat = read_and_parse_json(path, "keypair data")?;
+    let secret: [u8; 32] = hex::decode(&keypair_data.secret_key)
+        .map_err(|e| anyhow!("Invalid secret key in '{}': {}", path.display(), e))?
+        .try_into()
+        .map_err(|_| anyhow!("Secret key in '{}' must be 32 bytes", path.display()))?;
+    let keypair = KeyPair::from_bytes(&secret);
+    if keypair.did.as_str() != keypair_data.did {
+        return Err(anyhow!(
+            "Keypair file '{}' lists DID {} but its secret key belongs to {}",
+            path.display(),
+            keypair_data.did,
+            keypair.did
+        ));
+    }
+    Ok(keypair)
+}
+
 /// Anchor a trust bundle to the DAG
 async fn anchor_trust_bundle(bundle_path: &Path, node_api: &str, output: &Path) -> Result<String> {
     println!("Anchoring trust bundle to DAG via node: {}", node_api);
@@ -1204,37 +1401,70 @@ actions {{
     Ok(())
 }
 
-/// Show the resource balances for a DID
-async fn show_ledger(did: &str, resource_type: Option<&str>) -> Result<()> {
-    println!("Showing ledger for DID: {}", did);
-
-    // In a real implementation, we would query the actual ledger
-    // For this CLI prototype, we'll show mock data
-    let resources = vec![
-        ("TOKEN", 100),
-        ("CPU", 5000),
-        ("MEMORY", 10000),
-        ("IO", 1000),
-    ];
-
-    if let Some(rt) = resource_type {
-        // Show only the specified resource
-        if let Some(&(_, amount)) = resources.iter().find(|(r, _)| r == &rt) {
-            println!("{}: {}", rt, amount);
-        } else {
-            println!("Resource type '{}' not found for DID {}", rt, did);
-        }
-    } else {
-        // Show all resources
-        println!("Resources for DID {}:", did);
-        for (resource, amount) in resources {
-            println!("  {}: {}", resource, amount);
-        }
-    }
+/// Show the mana balance of a DID, or of its pool for one resource type, as
+/// stored in the ledger
+async fn show_ledger(did: &str, resource: Option<&str>, db_path: &Path) -> Result<()> {
+    let did = did.parse::<Did>().map_err(|e| format_did_error(&e, did))?;
+    let resource = resource
+        .map(|r| r.parse::<ResourceType>())
+        .transpose()
+        .map_err(|e| anyhow!("{}", e))?;
+    let ledger = SledManaLedger::open(db_path)
+        .map_err(|e| anyhow!("Failed to open mana ledger at '{}': {}", db_path.display(), e))?;
+
+    let context = icn_runtime::RuntimeContextBuilder::<SledManaLedger>::new()
+        .build_with_ledger(Arc::new(ledger));
+    let runtime = icn_runtime::Runtime::with_context(
+        Arc::new(CliRuntimeStorage::new()),
+        Arc::new(context),
+    );
+    let balance = runtime.mana_balance(&did, resource).await?;
 
+    match resource {
+        Some(resource) => println!("{} mana balance for {}: {}", resource, did, balance),
+        None => println!("Mana balance for {}: {}", did, balance),
+    }
     Ok(())
 }
 
+/// Reconcile stored mana balances against the audit log of a Sled ledger
+async fn reconcile_ledger(db_path: &Path, repair: bool) -> Result<()> {
+    let ledger = SledManaLedger::open(db_path)
+        .map_err(|e| anyhow!("Failed to open mana ledger at '{}': {}", db_path.display(), e))?;
+
+    let report = ledger.reconcile(repair).await?;
+
+    println!("Checked {} balance(s)", report.checked);
+    if report.is_consistent() {
+        println!("{}", "All balances match the audit log".green());
+        return Ok(());
+    }
+
+    for discrepancy in &report.discrepancies {
+        let balance = match &discrepancy.scope {
+            Some(scope) => format!("{} (scope {})", discrepancy.did, scope),
+            None => discrepancy.did.to_string(),
+        };
+        println!(
+            "{} {}: stored {}, audit log {}",
+            "MISMATCH".red(),
+            balance,
+            discrepancy.stored_balance,
+            discrepancy.log_balance
+        );
+    }
+
+    if repair {
+        println!("{}", format!("Repaired {} balance(s)", report.repaired).yellow());
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} balance discrepancies found; re-run with --repair to restore log-derived values",
+            report.discrepancies.len()
+        ))
+    }
+}
+
 /// Entrypoint
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -1261,11 +1491,26 @@ async fn main() -> Result<()> {
             }
         },
         Commands::Ccl(cmd) => match cmd {
-            CclCommands::CompileToDsl { input, output } => {
+            CclCommands::CompileToDsl {
+                input,
+                output,
+                lint,
+            } => {
+                if *lint {
+                    lint_ccl(input);
+                }
                 compile_to_dsl(input, output).await?;
             }
-            CclCommands::CompileToWasm { input, output } => {
-                compile_to_wasm(input, output).await?;
+            CclCommands::CompileToWasm {
+                input,
+                output,
+                lint,
+                optimize,
+            } => {
+                if *lint {
+                    lint_ccl(input);
+                }
+                compile_to_wasm(input, output, *optimize).await?;
             }
         },
         Commands::Runtime(cmd) => match cmd {
@@ -1283,6 +1528,15 @@ async fn main() -> Result<()> {
             RuntimeCommands::ExecuteCcl { input, output } => {
                 execute_ccl(input, output.as_deref()).await?;
             }
+            RuntimeCommands::Jobs { url } => {
+                list_runtime_jobs(url).await?;
+            }
+            RuntimeCommands::Serve { config, key } => {
+                serve_runtime(config, key).await?;
+            }
+            RuntimeCommands::Submit { params, key, max_total_mana, url } => {
+                submit_mesh_job(params, key, *max_total_mana, url).await?;
+            }
         },
         Commands::Federation(cmd) => match cmd {
             FederationCommands::Create {
@@ -1327,8 +1581,12 @@ async fn main() -> Result<()> {
             unimplemented!("DAG commands not yet implemented");
         }
         Commands::Ledger(cmd) => match cmd {
-            LedgerCommands::Show { did, resource } => {
-                show_ledger(did, resource.as_deref()).await?;
+            LedgerCommands::Show {
+                did,
+                resource,
+                db_path,
+            } => {
+                show_ledger(did, resource.as_deref(), db_path).await?;
             }
             LedgerCommands::Mint { did, amount } => {
                 // Implementation for minting tokens
@@ -1337,6 +1595,9 @@ async fn main() -> Result<()> {
                 // in governance mode to mint tokens
                 println!("Note: Token minting requires governance context");
             }
+            LedgerCommands::Reconcile { db_path, repair } => {
+                reconcile_ledger(db_path, *repair).await?;
+            }
         },
         Commands::Token(cmd) => match cmd {
             TokenCommands::Transfer { from, to, amount } => {
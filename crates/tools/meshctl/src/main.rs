@@ -3,6 +3,7 @@ use chrono::Utc;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use icn_economics::ScopedResourceToken;
+use icn_types::bounded_decode::{from_cbor_bounded, from_json_bounded, DecodeLimits};
 // use icn_identity_core::did::Did;
 use planetary_mesh::{
     Bid, ComputeRequirements, JobManifest, JobPriority, JobStatus, MeshNode, NodeCapability,
@@ -249,10 +250,12 @@ async fn submit_job(args: &Commands) -> Result<()> {
             ccl_cid: None,
             input_data_cid: None,
             output_location: None,
+            output_recipients: vec![],
             requirements,
             priority: job_priority,
             resource_token: token,
             trust_requirements: vec![],
+            affinity: Default::default(),
             status: JobStatus::Created,
         };
 
@@ -786,11 +789,11 @@ async fn handle_mesh_subcommand(cmd: Commands) -> Result<()> {
             // Determine file type based on extension
             let receipt_obj = if receipt.ends_with(".cbor") {
                 // Deserialize from CBOR
-                serde_cbor::from_slice::<icn_mesh_receipts::ExecutionReceipt>(&receipt_bytes)
+                from_cbor_bounded::<icn_mesh_receipts::ExecutionReceipt>(&receipt_bytes, &DecodeLimits::receipt())
                     .map_err(|e| anyhow::anyhow!("Failed to parse CBOR receipt: {}", e))?
             } else if receipt.ends_with(".json") {
                 // Deserialize from JSON
-                serde_json::from_slice::<icn_mesh_receipts::ExecutionReceipt>(&receipt_bytes)
+                from_json_bounded::<icn_mesh_receipts::ExecutionReceipt>(&receipt_bytes, &DecodeLimits::receipt())
                     .map_err(|e| anyhow::anyhow!("Failed to parse JSON receipt: {}", e))?
             } else {
                 return Err(anyhow::anyhow!(